@@ -58,6 +58,8 @@ pub fn op_webgpu_create_command_encoder(
 
     let descriptor = wgpu_types::CommandEncoderDescriptor {
         label: label.map(Cow::from),
+        reusable: false,
+        protected: false,
     };
 
     gfx_put!(device => instance.device_create_command_encoder(
@@ -71,6 +73,7 @@ pub fn op_webgpu_create_command_encoder(
 #[serde(rename_all = "camelCase")]
 pub struct GpuRenderPassColorAttachment {
     view: ResourceId,
+    depth_slice: Option<u32>,
     resolve_target: Option<ResourceId>,
     clear_value: Option<wgpu_types::Color>,
     load_op: wgpu_core::command::LoadOp,
@@ -124,6 +127,7 @@ pub fn op_webgpu_command_encoder_begin_render_pass(
 
                 Some(wgpu_core::command::RenderPassColorAttachment {
                     view: texture_view_resource.1,
+                    depth_slice: at.depth_slice,
                     resolve_target,
                     channel: wgpu_core::command::PassChannel {
                         load_op: at.load_op,
@@ -540,6 +544,7 @@ pub fn op_webgpu_command_encoder_finish(
 
     let descriptor = wgpu_types::CommandBufferDescriptor {
         label: label.map(Cow::from),
+        reusable: false,
     };
 
     let (val, maybe_err) = gfx_select!(command_encoder => instance.command_encoder_finish(
@@ -13,6 +13,7 @@ use wgpu_core::command::ClearError;
 use wgpu_core::command::CommandEncoderError;
 use wgpu_core::command::ComputePassError;
 use wgpu_core::command::CopyError;
+use wgpu_core::command::CreateCommandEncoderError;
 use wgpu_core::command::CreateRenderBundleError;
 use wgpu_core::command::QueryError;
 use wgpu_core::command::RenderBundleError;
@@ -171,6 +172,15 @@ impl From<CommandEncoderError> for WebGpuError {
     }
 }
 
+impl From<CreateCommandEncoderError> for WebGpuError {
+    fn from(err: CreateCommandEncoderError) -> Self {
+        match err {
+            CreateCommandEncoderError::Device(err) => err.into(),
+            err => WebGpuError::Validation(fmt_err(&err)),
+        }
+    }
+}
+
 impl From<QueryError> for WebGpuError {
     fn from(err: QueryError) -> Self {
         WebGpuError::Validation(fmt_err(&err))
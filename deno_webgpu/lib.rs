@@ -405,6 +405,7 @@ pub async fn op_webgpu_request_adapter(
             wgpu_core::identity::IdentityManagerFactory,
             wgpu_types::InstanceDescriptor {
                 backends,
+                flags: wgpu_types::InstanceFlags::default(),
                 dx12_shader_compiler: wgpu_types::Dx12Compiler::Fxc,
                 gles_minor_version: wgpu_types::Gles3MinorVersion::default(),
             },
@@ -647,6 +648,7 @@ pub async fn op_webgpu_request_device(
         label: label.map(Cow::from),
         features: required_features.into(),
         limits: required_limits.unwrap_or_default(),
+        ..Default::default()
     };
 
     let (device, maybe_err) = gfx_select!(adapter => instance.adapter_request_device(
@@ -8,6 +8,7 @@ use deno_core::ResourceId;
 use serde::Deserialize;
 use serde::Serialize;
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::rc::Rc;
 
 use super::error::WebGpuError;
@@ -78,7 +79,7 @@ pub enum GPUPipelineLayoutOrGPUAutoLayoutMode {
 pub struct GpuProgrammableStage {
     module: ResourceId,
     entry_point: String,
-    // constants: HashMap<String, GPUPipelineConstantValue>
+    constants: Option<HashMap<String, f64>>,
 }
 
 #[op]
@@ -113,8 +114,9 @@ pub fn op_webgpu_create_compute_pipeline(
         stage: wgpu_core::pipeline::ProgrammableStageDescriptor {
             module: compute_shader_module_resource.1,
             entry_point: Cow::from(compute.entry_point),
-            // TODO(lucacasonato): support args.compute.constants
+            constants: Cow::Owned(compute.constants.unwrap_or_default()),
         },
+        cache: None,
     };
     let implicit_pipelines = match layout {
         GPUPipelineLayoutOrGPUAutoLayoutMode::Layout(_) => None,
@@ -253,6 +255,7 @@ impl From<GpuDepthStencilState> for wgpu_types::DepthStencilState {
                 slope_scale: state.depth_bias_slope_scale,
                 clamp: state.depth_bias_clamp,
             },
+            depth_bounds: None,
         }
     }
 }
@@ -356,6 +359,7 @@ pub fn op_webgpu_create_render_pipeline(
             stage: wgpu_core::pipeline::ProgrammableStageDescriptor {
                 module: fragment_shader_module_resource.1,
                 entry_point: Cow::from(fragment.entry_point),
+                constants: Default::default(),
             },
             targets: Cow::from(fragment.targets),
         })
@@ -378,6 +382,7 @@ pub fn op_webgpu_create_render_pipeline(
             stage: wgpu_core::pipeline::ProgrammableStageDescriptor {
                 module: vertex_shader_module_resource.1,
                 entry_point: Cow::Owned(args.vertex.entry_point),
+                constants: Default::default(),
             },
             buffers: Cow::Owned(vertex_buffers),
         },
@@ -386,6 +391,7 @@ pub fn op_webgpu_create_render_pipeline(
         multisample: args.multisample,
         fragment,
         multiview: None,
+        cache: None,
     };
 
     let implicit_pipelines = match args.layout {
@@ -32,7 +32,7 @@ pub fn op_webgpu_queue_submit(
         })
         .collect::<Result<Vec<_>, AnyError>>()?;
 
-    let maybe_err = gfx_select!(queue => instance.queue_submit(queue, &ids)).err();
+    let maybe_err = gfx_select!(queue => instance.queue_submit(queue, &ids, None)).err();
 
     for rid in command_buffers {
         state.resource_table.close(rid)?;
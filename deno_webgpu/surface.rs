@@ -72,7 +72,9 @@ pub fn op_webgpu_surface_configure(
         width: args.width,
         height: args.height,
         present_mode: args.present_mode.unwrap_or_default(),
+        desired_maximum_frame_latency: 2,
         alpha_mode: args.alpha_mode,
+        color_space: wgpu_types::SurfaceColorSpace::Srgb,
         view_formats: args.view_formats,
     };
 
@@ -159,6 +159,7 @@ async fn setup<E: Example>(title: &str) -> Setup {
 
     let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
         backends,
+        flags: wgpu::InstanceFlags::default(),
         dx12_shader_compiler,
         gles_minor_version,
     });
@@ -226,6 +227,7 @@ async fn setup<E: Example>(title: &str) -> Setup {
                 label: None,
                 features: (optional_features & adapter_features) | required_features,
                 limits: needed_limits,
+                ..Default::default()
             },
             trace_dir.ok().as_ref().map(std::path::Path::new),
         )
@@ -559,7 +561,9 @@ pub fn test<E: Example>(mut params: FrameworkRefTest) {
                     width: params.width,
                     height: params.height,
                     present_mode: wgpu::PresentMode::Fifo,
+                    desired_maximum_frame_latency: 2,
                     alpha_mode: wgpu::CompositeAlphaMode::Auto,
+                    color_space: wgpu::SurfaceColorSpace::Srgb,
                     view_formats: vec![wgpu::TextureFormat::Rgba8UnormSrgb],
                 },
                 &ctx.adapter,
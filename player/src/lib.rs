@@ -155,8 +155,13 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
                 }
             }
         }
-        let (cmd_buf, error) = self
-            .command_encoder_finish::<A>(encoder, &wgt::CommandBufferDescriptor { label: None });
+        let (cmd_buf, error) = self.command_encoder_finish::<A>(
+            encoder,
+            &wgt::CommandBufferDescriptor {
+                label: None,
+                reusable: false,
+            },
+        );
         if let Some(e) = error {
             panic!("{:?}", e);
         }
@@ -195,6 +200,9 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
             Action::DestroyBuffer(id) => {
                 self.buffer_drop::<A>(id, true);
             }
+            Action::ResizeBuffer(id, size) => {
+                self.buffer_resize::<A>(id, size).unwrap();
+            }
             Action::CreateTexture(id, desc) => {
                 self.device_maintain_ids::<A>(device).unwrap();
                 let (_, error) = self.device_create_texture::<A>(device, &desc, id);
@@ -287,6 +295,16 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
             Action::DestroyShaderModule(id) => {
                 self.shader_module_drop::<A>(id);
             }
+            Action::CreatePipelineCache { id, desc } => {
+                let (_, error) =
+                    unsafe { self.device_create_pipeline_cache::<A>(device, &desc, id) };
+                if let Some(e) = error {
+                    panic!("{:?}", e);
+                }
+            }
+            Action::DestroyPipelineCache(id) => {
+                self.pipeline_cache_drop::<A>(id);
+            }
             Action::CreateComputePipeline {
                 id,
                 desc,
@@ -384,19 +402,23 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
                     .unwrap();
             }
             Action::Submit(_index, ref commands) if commands.is_empty() => {
-                self.queue_submit::<A>(device, &[]).unwrap();
+                self.queue_submit::<A>(device, &[], None).unwrap();
             }
             Action::Submit(_index, commands) => {
                 let (encoder, error) = self.device_create_command_encoder::<A>(
                     device,
-                    &wgt::CommandEncoderDescriptor { label: None },
+                    &wgt::CommandEncoderDescriptor {
+                        label: None,
+                        reusable: false,
+                        protected: false,
+                    },
                     comb_manager.alloc(device.backend()),
                 );
                 if let Some(e) = error {
                     panic!("{:?}", e);
                 }
                 let cmdbuf = self.encode_commands::<A>(encoder, commands);
-                self.queue_submit::<A>(device, &[cmdbuf]).unwrap();
+                self.queue_submit::<A>(device, &[cmdbuf], None).unwrap();
             }
         }
     }
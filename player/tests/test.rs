@@ -7,6 +7,11 @@
  *    - all expected buffers have `MAP_READ` usage
  *    - last action is `Submit`
  *    - no swapchain use
+ *
+ *  Expectations may set a per-byte `tolerance` for backends that round
+ *  differently. When an expectation isn't met, the actual contents are
+ *  written next to the test binary's temporary directory, so they can be
+ *  inspected or used as new reference data.
 !*/
 #![cfg(not(target_arch = "wasm32"))]
 
@@ -47,6 +52,9 @@ struct Expectation {
     buffer: RawId,
     offset: wgt::BufferAddress,
     data: ExpectedData,
+    /// Maximum allowed absolute difference of each byte.
+    #[serde(default)]
+    tolerance: u8,
 }
 
 #[derive(serde::Deserialize)]
@@ -91,6 +99,7 @@ impl Test<'_> {
                 label: None,
                 features: self.features,
                 limits: wgt::Limits::default(),
+                ..Default::default()
             },
             None,
             device
@@ -146,11 +155,28 @@ impl Test<'_> {
                     .collect::<Vec<u8>>(),
             };
 
-            #[allow(unknown_lints, clippy::if_then_panic)]
-            if &expected_data[..] != contents {
+            let mismatches = contents
+                .iter()
+                .zip(&expected_data)
+                .filter(|&(&actual, &expected)| actual.abs_diff(expected) > expect.tolerance)
+                .count();
+            if mismatches != 0 {
+                let dump_path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!(
+                    "{}-{:?}.bin",
+                    expect
+                        .name
+                        .replace(|c: char| !c.is_ascii_alphanumeric(), "_"),
+                    backend
+                ));
+                std::fs::write(&dump_path, contents).unwrap();
                 panic!(
-                    "Test expectation is not met!\nBuffer content was:\n{:?}\nbut expected:\n{:?}",
-                    contents, expected_data
+                    "Test expectation is not met!\n{} of {} bytes differ by more than {}, actual content was written to {:?}\nBuffer content was:\n{:?}\nbut expected:\n{:?}",
+                    mismatches,
+                    contents.len(),
+                    expect.tolerance,
+                    dump_path,
+                    contents,
+                    expected_data
                 );
             }
         }
@@ -184,6 +210,7 @@ impl Corpus {
             IdentityPassThroughFactory,
             wgt::InstanceDescriptor {
                 backends: corpus.backends,
+                flags: wgt::InstanceFlags::default(),
                 dx12_shader_compiler: wgt::Dx12Compiler::Fxc,
                 gles_minor_version: wgt::Gles3MinorVersion::default(),
             },
@@ -346,6 +346,8 @@ fn copy_via_compute(
         layout: Some(&pll),
         module: &sm,
         entry_point: "copy_texture_to_buffer",
+        constants: &Default::default(),
+        cache: None,
     });
 
     {
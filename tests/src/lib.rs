@@ -24,6 +24,7 @@ async fn initialize_device(
                 label: None,
                 features,
                 limits,
+                ..Default::default()
             },
             None,
         )
@@ -351,6 +352,7 @@ fn initialize_adapter() -> (Adapter, SurfaceGuard) {
     let gles_minor_version = wgpu::util::gles_minor_version_from_env().unwrap_or_default();
     let instance = Instance::new(wgpu::InstanceDescriptor {
         backends,
+        flags: wgpu::InstanceFlags::default(),
         dx12_shader_compiler,
         gles_minor_version,
     });
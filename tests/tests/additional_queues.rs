@@ -0,0 +1,59 @@
+//! Tests for `DeviceDescriptor::additional_queues`.
+
+use std::sync::{Arc, Mutex};
+
+use wgpu::util::{DeviceExt, DownloadBuffer};
+use wgpu_test::{initialize_test, TestParameters};
+
+#[test]
+fn additional_queue_copy_read_back_on_main_queue() {
+    // Only Vulkan and DX12 open additional queues.
+    let parameters = TestParameters::default().specific_failure(
+        Some(wgpu::Backends::GL | wgpu::Backends::METAL | wgpu::Backends::DX11),
+        None,
+        None,
+        true,
+    );
+    initialize_test(parameters, |ctx| {
+        let (device, queue) = pollster::block_on(ctx.adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                additional_queues: vec![wgpu::QueueKind::Transfer],
+                ..Default::default()
+            },
+            None,
+        ))
+        .unwrap();
+        let transfer_queue = device.additional_queue(0).unwrap();
+        assert!(device.additional_queue(1).is_none());
+
+        let data: Vec<u8> = (0..64).collect();
+        let src = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &data,
+            usage: wgpu::BufferUsages::COPY_SRC,
+        });
+        let dst = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder_for_queue(
+            &transfer_queue,
+            &wgpu::CommandEncoderDescriptor::default(),
+        );
+        encoder.copy_buffer_to_buffer(&src, 0, &dst, 0, 64);
+        transfer_queue.submit(Some(encoder.finish()));
+
+        // The read back on the main queue waits for the copy on the transfer queue.
+        let result = Arc::new(Mutex::new(None));
+        let result_clone = result.clone();
+        DownloadBuffer::read_buffer(&device, &queue, &dst.slice(..), move |download| {
+            *result_clone.lock().unwrap() = Some(download.unwrap().to_vec());
+        });
+        device.poll(wgpu::Maintain::Wait);
+
+        assert_eq!(result.lock().unwrap().take().unwrap(), data);
+    });
+}
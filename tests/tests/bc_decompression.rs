@@ -0,0 +1,79 @@
+use wgpu::util::DeviceExt;
+use wgpu_test::{initialize_test, TestParameters};
+
+/// A BC1 block whose first row uses all four colors interpolated between red and blue.
+const BC1_BLOCK: [u8; 8] = [0x00, 0xF8, 0x1F, 0x00, 0b11_10_01_00, 0, 0, 0];
+const BC1_FIRST_ROW: [u8; 16] = [
+    255, 0, 0, 255, 0, 0, 255, 255, 170, 0, 85, 255, 85, 0, 170, 255,
+];
+
+#[test]
+fn decompress_bc1_block() {
+    let texels =
+        wgpu::util::decompress_bc(wgpu::TextureFormat::Bc1RgbaUnorm, 4, 4, &BC1_BLOCK).unwrap();
+    assert_eq!(texels.len(), 4 * 4 * 4);
+    assert_eq!(texels[..16], BC1_FIRST_ROW);
+    assert!(texels[16..]
+        .chunks(4)
+        .all(|texel| texel == [255, 0, 0, 255]));
+
+    // Images smaller than a block are cropped.
+    let texels =
+        wgpu::util::decompress_bc(wgpu::TextureFormat::Bc1RgbaUnorm, 2, 1, &BC1_BLOCK).unwrap();
+    assert_eq!(texels, BC1_FIRST_ROW[..8]);
+
+    assert!(wgpu::util::decompress_bc(wgpu::TextureFormat::Bc7RgbaUnorm, 4, 4, &[0; 16]).is_none());
+}
+
+#[test]
+fn create_bc_texture_without_feature() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let size = wgpu::Extent3d {
+            width: 4,
+            height: 4,
+            depth_or_array_layers: 1,
+        };
+        let texture = ctx.device.create_texture_with_data(
+            &ctx.queue,
+            &wgpu::TextureDescriptor {
+                label: None,
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Bc1RgbaUnorm,
+                usage: wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            },
+            &BC1_BLOCK,
+        );
+        assert_eq!(texture.format(), wgpu::TextureFormat::Rgba8Unorm);
+
+        let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 256 * 4,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(256),
+                    rows_per_image: None,
+                },
+            },
+            size,
+        );
+        ctx.queue.submit(Some(encoder.finish()));
+
+        buffer.slice(..).map_async(wgpu::MapMode::Read, |_| ());
+        ctx.device.poll(wgpu::Maintain::Wait);
+        assert_eq!(buffer.slice(..16).get_mapped_range()[..], BC1_FIRST_ROW);
+    })
+}
@@ -0,0 +1,184 @@
+//! Tests for `Bgra8Unorm` textures, whose bytes are in BGRA order in buffers.
+
+use std::sync::{Arc, Mutex};
+
+use wgpu::util::{DeviceExt, DownloadTexture};
+use wgpu_test::{initialize_test, TestParameters, TestingContext};
+
+/// Two rows of 3 texels, so copies to buffers need row padding.
+const SIZE: wgpu::Extent3d = wgpu::Extent3d {
+    width: 3,
+    height: 2,
+    depth_or_array_layers: 1,
+};
+
+fn create_texture(ctx: &TestingContext, usage: wgpu::TextureUsages, data: &[u8]) -> wgpu::Texture {
+    ctx.device.create_texture_with_data(
+        &ctx.queue,
+        &wgpu::TextureDescriptor {
+            label: None,
+            size: SIZE,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Bgra8Unorm,
+            usage: wgpu::TextureUsages::COPY_SRC | usage,
+            view_formats: &[],
+        },
+        data,
+    )
+}
+
+fn read_texture(ctx: &TestingContext, texture: &wgpu::Texture) -> Vec<u8> {
+    let result = Arc::new(Mutex::new(None));
+    let result_clone = result.clone();
+    DownloadTexture::read_texture(
+        &ctx.device,
+        &ctx.queue,
+        texture.as_image_copy(),
+        SIZE,
+        move |download| {
+            *result_clone.lock().unwrap() = Some(download.unwrap());
+        },
+    );
+    ctx.device.poll(wgpu::Maintain::Wait);
+
+    let download = result.lock().unwrap().take().unwrap();
+    download.to_vec()
+}
+
+#[test]
+fn bgra8_copy_round_trip() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let data: Vec<u8> = (0..SIZE.width * SIZE.height * 4).map(|i| i as u8).collect();
+        let texture = create_texture(&ctx, wgpu::TextureUsages::TEXTURE_BINDING, &data);
+
+        assert_eq!(read_texture(&ctx, &texture), data);
+    });
+}
+
+#[test]
+fn bgra8_render_target() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let data = vec![0; (SIZE.width * SIZE.height * 4) as usize];
+        let texture = create_texture(&ctx, wgpu::TextureUsages::RENDER_ATTACHMENT, &data);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::RED),
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        ctx.queue.submit(Some(encoder.finish()));
+
+        let texels = read_texture(&ctx, &texture);
+        for texel in texels.chunks_exact(4) {
+            assert_eq!(texel, [0, 0, 255, 255]);
+        }
+    });
+}
+
+#[test]
+fn bgra8_texture_load() {
+    initialize_test(
+        TestParameters::default().downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            let data: Vec<u8> = [51, 102, 153, 204]
+                .into_iter()
+                .cycle()
+                .take((SIZE.width * SIZE.height * 4) as usize)
+                .collect();
+            let texture = create_texture(&ctx, wgpu::TextureUsages::TEXTURE_BINDING, &data);
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+            let module = ctx
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(
+                        "@group(0) @binding(0) var texture: texture_2d<f32>;
+                        @group(0) @binding(1) var<storage, read_write> output: vec4<f32>;
+                        @compute @workgroup_size(1)
+                        fn main() { output = textureLoad(texture, vec2<i32>(0), 0); }"
+                            .into(),
+                    ),
+                });
+            let pipeline = ctx
+                .device
+                .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: None,
+                    layout: None,
+                    module: &module,
+                    entry_point: "main",
+                    constants: &Default::default(),
+                    cache: None,
+                });
+            let output = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: 16,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            });
+            let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: 16,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout: &pipeline.get_bind_group_layout(0),
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: output.as_entire_binding(),
+                    },
+                ],
+            });
+
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            {
+                let mut pass = encoder.begin_compute_pass(&Default::default());
+                pass.set_pipeline(&pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.dispatch_workgroups(1, 1, 1);
+            }
+            encoder.copy_buffer_to_buffer(&output, 0, &readback, 0, 16);
+            ctx.queue.submit(Some(encoder.finish()));
+
+            readback
+                .slice(..)
+                .map_async(wgpu::MapMode::Read, Result::unwrap);
+            ctx.device.poll(wgpu::Maintain::Wait);
+
+            // The shader sees the channels in RGBA order.
+            let mapped = readback.slice(..).get_mapped_range();
+            let values: &[f32] = bytemuck::cast_slice(&mapped);
+            for (value, expected) in values.iter().zip([0.6, 0.4, 0.2, 0.8]) {
+                assert!(
+                    (value - expected).abs() < 0.01,
+                    "read {values:?}, expected {expected}"
+                );
+            }
+        },
+    );
+}
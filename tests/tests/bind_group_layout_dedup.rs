@@ -1,4 +1,4 @@
-use wgpu_test::{initialize_test, TestParameters};
+use wgpu_test::{initialize_test, valid, TestParameters};
 
 #[test]
 fn bind_group_layout_deduplication() {
@@ -76,17 +76,20 @@ fn bind_group_layout_deduplication() {
             vertex: wgpu::VertexState {
                 module: &module,
                 entry_point: "vs_main",
+                constants: &Default::default(),
                 buffers: &[],
             },
             fragment: Some(wgpu::FragmentState {
                 module: &module,
                 entry_point: "fs_main",
+                constants: &Default::default(),
                 targets,
             }),
             primitive: wgpu::PrimitiveState::default(),
             depth_stencil: None,
             multiview: None,
             multisample: wgpu::MultisampleState::default(),
+            cache: None,
         };
 
         let pipeline = ctx.device.create_render_pipeline(&desc);
@@ -115,6 +118,7 @@ fn bind_group_layout_deduplication() {
                 label: None,
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     view: &texture_view,
+                    depth_slice: None,
                     resolve_target: None,
                     ops: Default::default(),
                 })],
@@ -142,3 +146,120 @@ const SHADER_SRC: &str = "
 @vertex fn vs_main() -> @builtin(position) vec4<f32> { return vec4<f32>(1.0); }
 @fragment fn fs_main() -> @location(0) vec4<f32> { return vec4<f32>(1.0); }
 ";
+
+#[test]
+fn bind_group_layout_deduplication_derived() {
+    // Bind groups created from the derived layout of one pipeline must be usable
+    // with another pipeline whose derived layout has the same entries. WebGPU
+    // doesn't allow this, so it fails on the WebGPU backend.
+    let parameters = TestParameters::default().backend_failure(wgpu::Backends::BROWSER_WEBGPU);
+    initialize_test(parameters, |ctx| {
+        let module = ctx
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: None,
+                source: wgpu::ShaderSource::Wgsl(DERIVED_SHADER_SRC.into()),
+            });
+
+        let targets = &[Some(wgpu::ColorTargetState {
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            blend: None,
+            write_mask: Default::default(),
+        })];
+
+        let create_pipeline = || {
+            ctx.device
+                .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: None,
+                    layout: None,
+                    vertex: wgpu::VertexState {
+                        module: &module,
+                        entry_point: "vs_main",
+                        constants: &Default::default(),
+                        buffers: &[],
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &module,
+                        entry_point: "fs_main",
+                        constants: &Default::default(),
+                        targets,
+                    }),
+                    primitive: wgpu::PrimitiveState::default(),
+                    depth_stencil: None,
+                    multiview: None,
+                    multisample: wgpu::MultisampleState::default(),
+                    cache: None,
+                })
+        };
+
+        let pipeline_1 = create_pipeline();
+        let pipeline_2 = create_pipeline();
+
+        let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline_1.get_bind_group_layout(0),
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        });
+
+        let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            dimension: wgpu::TextureDimension::D2,
+            size: wgpu::Extent3d {
+                width: 32,
+                height: 32,
+                depth_or_array_layers: 1,
+            },
+            sample_count: 1,
+            mip_level_count: 1,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+
+        let texture_view = texture.create_view(&Default::default());
+
+        valid(&ctx.device, || {
+            let mut encoder = ctx.device.create_command_encoder(&Default::default());
+            {
+                let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: None,
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &texture_view,
+                        depth_slice: None,
+                        resolve_target: None,
+                        ops: Default::default(),
+                    })],
+                    depth_stencil_attachment: None,
+                    occlusion_query_set: None,
+                    timestamp_writes: None,
+                });
+
+                pass.set_bind_group(0, &bind_group, &[]);
+
+                pass.set_pipeline(&pipeline_1);
+                pass.draw(0..6, 0..1);
+
+                pass.set_pipeline(&pipeline_2);
+                pass.draw(0..6, 0..1);
+            }
+            ctx.queue.submit(Some(encoder.finish()));
+        });
+    })
+}
+
+const DERIVED_SHADER_SRC: &str = "
+@group(0) @binding(0) var<uniform> color: vec4<f32>;
+
+@vertex fn vs_main() -> @builtin(position) vec4<f32> { return vec4<f32>(1.0); }
+@fragment fn fs_main() -> @location(0) vec4<f32> { return color; }
+";
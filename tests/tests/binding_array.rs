@@ -0,0 +1,57 @@
+use wgpu_test::{initialize_test, TestParameters};
+
+#[test]
+fn binding_array_requires_array_layout() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let module = ctx
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: None,
+                source: wgpu::ShaderSource::Wgsl(
+                    "@group(0) @binding(0) var textures: binding_array<texture_2d<f32>, 4>;
+                    @compute @workgroup_size(1)
+                    fn main() { let size = textureDimensions(textures[0]); }"
+                        .into(),
+                ),
+            });
+        let bind_group_layout =
+            ctx.device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    }],
+                });
+        let pipeline_layout = ctx
+            .device
+            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: &[&bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        // The layout binding is not an array, and an implicit layout would need
+        // `TEXTURE_BINDING_ARRAY` for the array derived from the shader.
+        for layout in [Some(&pipeline_layout), None] {
+            ctx.device.push_error_scope(wgpu::ErrorFilter::Validation);
+            let _ = ctx
+                .device
+                .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: None,
+                    layout,
+                    module: &module,
+                    entry_point: "main",
+                    constants: &Default::default(),
+                    cache: None,
+                });
+            assert!(pollster::block_on(ctx.device.pop_error_scope()).is_some());
+        }
+    })
+}
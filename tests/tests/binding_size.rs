@@ -0,0 +1,217 @@
+use wasm_bindgen_test::*;
+use wgpu_test::{fail, initialize_test, valid, TestParameters, TestingContext};
+
+// The struct is 32 bytes with a single element in its runtime-sized array:
+// 16 for `header`, 4 for the element, rounded up to the struct alignment.
+const SHADER_SRC: &str = "
+struct Data {
+    header: vec4<f32>,
+    values: array<u32>,
+}
+
+@group(0) @binding(0)
+var<storage, read> data: Data;
+
+@compute @workgroup_size(1)
+fn cs_main() {
+    _ = data.values[0];
+}
+
+@vertex
+fn vs_main() -> @builtin(position) vec4<f32> {
+    return vec4<f32>(f32(arrayLength(&data.values)));
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(0.0);
+}
+";
+
+fn create_bind_group_layout(
+    ctx: &TestingContext,
+    min_binding_size: Option<wgpu::BufferSize>,
+) -> wgpu::BindGroupLayout {
+    ctx.device
+        .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE | wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size,
+                },
+                count: None,
+            }],
+        })
+}
+
+fn create_bind_group(
+    ctx: &TestingContext,
+    layout: &wgpu::BindGroupLayout,
+    buffer: &wgpu::Buffer,
+    size: u64,
+) -> wgpu::BindGroup {
+    ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                buffer,
+                offset: 0,
+                size: wgpu::BufferSize::new(size),
+            }),
+        }],
+    })
+}
+
+fn create_buffer(ctx: &TestingContext) -> wgpu::Buffer {
+    ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 64,
+        usage: wgpu::BufferUsages::STORAGE,
+        mapped_at_creation: false,
+    })
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn min_binding_size_at_bind_group_creation() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let layout = create_bind_group_layout(&ctx, wgpu::BufferSize::new(32));
+        let buffer = create_buffer(&ctx);
+
+        valid(&ctx.device, || {
+            create_bind_group(&ctx, &layout, &buffer, 32)
+        });
+        fail(&ctx.device, || {
+            create_bind_group(&ctx, &layout, &buffer, 16)
+        });
+    })
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn late_binding_size_at_dispatch() {
+    initialize_test(
+        TestParameters::default().downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            let module = ctx
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(SHADER_SRC.into()),
+                });
+            let bind_group_layout = create_bind_group_layout(&ctx, None);
+            let pipeline_layout =
+                ctx.device
+                    .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                        label: None,
+                        bind_group_layouts: &[&bind_group_layout],
+                        push_constant_ranges: &[],
+                    });
+            let pipeline = ctx
+                .device
+                .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: None,
+                    layout: Some(&pipeline_layout),
+                    module: &module,
+                    entry_point: "cs_main",
+                    constants: &Default::default(),
+                    cache: None,
+                });
+            let buffer = create_buffer(&ctx);
+
+            let dispatch = |size: u64| {
+                // Without a `min_binding_size`, any non-empty binding is accepted here.
+                let bind_group = create_bind_group(&ctx, &bind_group_layout, &buffer, size);
+
+                let mut encoder = ctx
+                    .device
+                    .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+                {
+                    let mut pass = encoder.begin_compute_pass(&Default::default());
+                    pass.set_pipeline(&pipeline);
+                    pass.set_bind_group(0, &bind_group, &[]);
+                    pass.dispatch_workgroups(1, 1, 1);
+                }
+                encoder.finish()
+            };
+
+            valid(&ctx.device, || dispatch(32));
+            fail(&ctx.device, || dispatch(16));
+        },
+    );
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn late_binding_size_in_render_bundle() {
+    initialize_test(
+        TestParameters::default().downlevel_flags(wgpu::DownlevelFlags::VERTEX_STORAGE),
+        |ctx| {
+            let module = ctx
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(SHADER_SRC.into()),
+                });
+            let bind_group_layout = create_bind_group_layout(&ctx, None);
+            let pipeline_layout =
+                ctx.device
+                    .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                        label: None,
+                        bind_group_layouts: &[&bind_group_layout],
+                        push_constant_ranges: &[],
+                    });
+            let pipeline = ctx
+                .device
+                .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: None,
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: &module,
+                        entry_point: "vs_main",
+                        constants: &Default::default(),
+                        buffers: &[],
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &module,
+                        entry_point: "fs_main",
+                        constants: &Default::default(),
+                        targets: &[Some(wgpu::TextureFormat::Rgba8Unorm.into())],
+                    }),
+                    primitive: wgpu::PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: wgpu::MultisampleState::default(),
+                    multiview: None,
+                    cache: None,
+                });
+            let buffer = create_buffer(&ctx);
+
+            let record = |size: u64| {
+                let bind_group = create_bind_group(&ctx, &bind_group_layout, &buffer, size);
+
+                let mut encoder =
+                    ctx.device
+                        .create_render_bundle_encoder(&wgpu::RenderBundleEncoderDescriptor {
+                            label: None,
+                            color_formats: &[Some(wgpu::TextureFormat::Rgba8Unorm)],
+                            depth_stencil: None,
+                            sample_count: 1,
+                            multiview: None,
+                        });
+                encoder.set_pipeline(&pipeline);
+                encoder.set_bind_group(0, &bind_group, &[]);
+                encoder.draw(0..3, 0..1);
+                encoder.finish(&wgpu::RenderBundleDescriptor::default())
+            };
+
+            valid(&ctx.device, || record(32));
+            fail(&ctx.device, || record(16));
+        },
+    );
+}
@@ -1,4 +1,6 @@
-use wgpu_test::{initialize_test, TestParameters, TestingContext};
+use std::sync::{Arc, Mutex};
+
+use wgpu_test::{fail, initialize_test, TestParameters, TestingContext};
 
 fn test_empty_buffer_range(ctx: &TestingContext, buffer_size: u64, label: &str) {
     let r = wgpu::BufferUsages::MAP_READ;
@@ -134,7 +136,11 @@ fn test_map_offset() {
 
         let mut encoder = ctx
             .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: None,
+                reusable: false,
+                protected: false,
+            });
 
         encoder.copy_buffer_to_buffer(&write_buf, 0, &read_buf, 0, 256);
 
@@ -159,3 +165,270 @@ fn test_map_offset() {
         }
     });
 }
+
+#[test]
+fn map_disjoint_ranges() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 512,
+            usage: wgpu::BufferUsages::MAP_WRITE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let read_buf = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 512,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Both ranges are mapped concurrently, the second one while the first is pending.
+        buffer
+            .slice(0..256)
+            .map_async(wgpu::MapMode::Write, Result::unwrap);
+        buffer
+            .slice(256..512)
+            .map_async(wgpu::MapMode::Write, Result::unwrap);
+
+        ctx.device.poll(wgpu::MaintainBase::Wait);
+
+        {
+            let mut low = buffer.slice(0..256).get_mapped_range_mut();
+            let mut high = buffer.slice(256..).get_mapped_range_mut();
+            low.fill(1);
+            high.fill(2);
+        }
+
+        buffer.unmap();
+
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: None,
+                reusable: false,
+                protected: false,
+            });
+        encoder.copy_buffer_to_buffer(&buffer, 0, &read_buf, 0, 512);
+        ctx.queue.submit(Some(encoder.finish()));
+
+        read_buf
+            .slice(256..)
+            .map_async(wgpu::MapMode::Read, Result::unwrap);
+        ctx.device.poll(wgpu::MaintainBase::Wait);
+        // A range can be added while another one is already mapped.
+        read_buf
+            .slice(..256)
+            .map_async(wgpu::MapMode::Read, Result::unwrap);
+        ctx.device.poll(wgpu::MaintainBase::Wait);
+
+        let low = read_buf.slice(..256).get_mapped_range();
+        let high = read_buf.slice(256..).get_mapped_range();
+        assert!(low.iter().all(|&byte| byte == 1));
+        assert!(high.iter().all(|&byte| byte == 2));
+    });
+}
+
+#[test]
+fn map_overlapping_range_error() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 512,
+            usage: wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let results = Arc::new(Mutex::new(Vec::new()));
+
+        buffer
+            .slice(0..256)
+            .map_async(wgpu::MapMode::Read, Result::unwrap);
+        let pending_results = results.clone();
+        fail(&ctx.device, || {
+            buffer
+                .slice(128..384)
+                .map_async(wgpu::MapMode::Read, move |result| {
+                    pending_results.lock().unwrap().push(result)
+                });
+        });
+
+        ctx.device.poll(wgpu::MaintainBase::Wait);
+
+        let mapped_results = results.clone();
+        fail(&ctx.device, || {
+            buffer
+                .slice(0..128)
+                .map_async(wgpu::MapMode::Read, move |result| {
+                    mapped_results.lock().unwrap().push(result)
+                });
+        });
+
+        let aborted_results = results.clone();
+        buffer
+            .slice(256..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                aborted_results.lock().unwrap().push(result)
+            });
+        buffer.unmap();
+
+        assert_eq!(
+            *results.lock().unwrap(),
+            [
+                Err(wgpu::BufferAsyncError::MapAlreadyPending),
+                Err(wgpu::BufferAsyncError::AlreadyMapped),
+                Err(wgpu::BufferAsyncError::Aborted),
+            ]
+        );
+    });
+}
+
+// `Buffer::resize` is not available on WebGPU.
+#[cfg(any(not(target_arch = "wasm32"), feature = "webgl"))]
+fn read_buffer(ctx: &TestingContext, buffer: &wgpu::Buffer) -> Vec<u8> {
+    let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: buffer.size(),
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.copy_buffer_to_buffer(buffer, 0, &readback, 0, buffer.size());
+    ctx.queue.submit(Some(encoder.finish()));
+
+    readback
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, Result::unwrap);
+    ctx.device.poll(wgpu::Maintain::Wait);
+    let data = readback.slice(..).get_mapped_range().to_vec();
+    data
+}
+
+#[test]
+#[cfg(any(not(target_arch = "wasm32"), feature = "webgl"))]
+fn resize_buffer() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let mut buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 8,
+            usage: wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+        ctx.queue.write_buffer(&buffer, 0, &[1, 2, 3, 4]);
+
+        // Growing keeps the contents, and the new bytes are zeroed.
+        buffer.resize(16);
+        assert_eq!(buffer.size(), 16);
+        ctx.queue.write_buffer(&buffer, 12, &[5, 6, 7, 8]);
+        assert_eq!(
+            read_buffer(&ctx, &buffer),
+            [1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 0, 0, 5, 6, 7, 8]
+        );
+
+        buffer.resize(4);
+        assert_eq!(read_buffer(&ctx, &buffer), [1, 2, 3, 4]);
+
+        // A bind group refers to the allocation of the buffer.
+        let bind_group_layout =
+            ctx.device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only: true },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    }],
+                });
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        });
+        fail(&ctx.device, || buffer.resize(8));
+        assert_eq!(buffer.size(), 4);
+
+        drop(bind_group);
+        buffer.resize(8);
+        assert_eq!(read_buffer(&ctx, &buffer), [1, 2, 3, 4, 0, 0, 0, 0]);
+    });
+}
+
+#[test]
+#[cfg(any(not(target_arch = "wasm32"), feature = "webgl"))]
+fn resize_buffer_used_by_render_bundle() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let mut buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 16,
+            usage: wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::VERTEX,
+            mapped_at_creation: false,
+        });
+        ctx.queue.write_buffer(&buffer, 0, &[1, 2, 3, 4]);
+
+        // The bundle validated its vertex buffer range against the current size,
+        // so shrinking the buffer under it must fail.
+        let mut bundle_encoder =
+            ctx.device
+                .create_render_bundle_encoder(&wgpu::RenderBundleEncoderDescriptor {
+                    label: None,
+                    color_formats: &[Some(wgpu::TextureFormat::Rgba8Unorm)],
+                    depth_stencil: None,
+                    sample_count: 1,
+                    multiview: None,
+                });
+        bundle_encoder.set_vertex_buffer(0, buffer.slice(..));
+        let bundle = bundle_encoder.finish(&wgpu::RenderBundleDescriptor::default());
+        fail(&ctx.device, || buffer.resize(4));
+        assert_eq!(buffer.size(), 16);
+
+        drop(bundle);
+        buffer.resize(4);
+        assert_eq!(read_buffer(&ctx, &buffer), [1, 2, 3, 4]);
+    });
+}
+
+#[test]
+#[cfg(any(not(target_arch = "wasm32"), feature = "webgl"))]
+fn resize_buffer_used_by_reusable_command_buffer() {
+    initialize_test(
+        TestParameters::default().features(wgpu::Features::REUSABLE_COMMAND_BUFFERS),
+        |ctx| {
+            let mut buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: 16,
+                usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            ctx.queue.write_buffer(&buffer, 0, &[1, 2, 3, 4]);
+
+            // A submitted reusable command buffer still records the old allocation,
+            // which it would use again when resubmitted.
+            let mut encoder = ctx.device.create_command_encoder(
+                &wgpu::CommandEncoderDescriptor::default().with_reusable(true),
+            );
+            encoder.clear_buffer(&buffer, 8, None);
+            let command_buffer = encoder.finish_reusable();
+            ctx.queue.submit_reusable([&command_buffer]);
+            ctx.device.poll(wgpu::Maintain::Wait);
+            fail(&ctx.device, || buffer.resize(4));
+            assert_eq!(buffer.size(), 16);
+
+            drop(command_buffer);
+            buffer.resize(4);
+            assert_eq!(read_buffer(&ctx, &buffer), [1, 2, 3, 4]);
+        },
+    )
+}
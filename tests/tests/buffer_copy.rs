@@ -1,9 +1,10 @@
 //! Tests for buffer copy validation.
 
 use wasm_bindgen_test::wasm_bindgen_test;
+use wgpu::util::DeviceExt;
 use wgt::BufferAddress;
 
-use wgpu_test::{fail_if, initialize_test, TestParameters};
+use wgpu_test::{fail, fail_if, initialize_test, TestParameters};
 
 #[test]
 #[wasm_bindgen_test]
@@ -31,6 +32,52 @@ fn copy_alignment() {
     try_copy(1024 + 4, 4, false);
 }
 
+#[test]
+#[wasm_bindgen_test]
+fn copy_within_buffer() {
+    // WebGPU doesn't allow copies within a buffer.
+    let parameters = TestParameters::default().backend_failure(wgpu::Backends::BROWSER_WEBGPU);
+    initialize_test(parameters, |ctx| {
+        let buffer = ctx
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: &[1, 2, 3, 4, 5, 6, 7, 8, 0, 0, 0, 0, 0, 0, 0, 0],
+                usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+            });
+        let read_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 16,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        // Overlapping ranges within a buffer are rejected.
+        fail(&ctx.device, || {
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            encoder.copy_buffer_to_buffer(&buffer, 0, &buffer, 4, 8);
+            encoder.finish()
+        });
+
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.copy_buffer_to_buffer(&buffer, 4, &buffer, 12, 4);
+        encoder.copy_buffer_to_buffer(&buffer, 0, &buffer, 8, 4);
+        encoder.copy_buffer_to_buffer(&buffer, 0, &read_buffer, 0, 16);
+        ctx.queue.submit(Some(encoder.finish()));
+
+        read_buffer.slice(..).map_async(wgpu::MapMode::Read, |_| ());
+        ctx.device.poll(wgpu::Maintain::Wait);
+        assert_eq!(
+            *read_buffer.slice(..).get_mapped_range(),
+            [1, 2, 3, 4, 5, 6, 7, 8, 1, 2, 3, 4, 5, 6, 7, 8]
+        );
+    });
+}
+
 const BUFFER_SIZE: BufferAddress = 1234;
 
 const BUFFER_DESCRIPTOR: wgpu::BufferDescriptor = wgpu::BufferDescriptor {
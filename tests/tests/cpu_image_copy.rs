@@ -0,0 +1,152 @@
+//! Tests for copying CPU images with `Queue::copy_external_image_to_texture`.
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::sync::Arc;
+
+use wgpu_test::{fail, initialize_test, TestParameters, TestingContext};
+
+// 2x2 image: opaque red, half transparent green, opaque blue, transparent white.
+const IMAGE: [u8; 16] = [
+    255, 0, 0, 255, 0, 255, 0, 128, //
+    0, 0, 255, 255, 255, 255, 255, 0,
+];
+
+fn cpu_image(flip_y: bool) -> wgpu::ImageCopyExternalImage {
+    wgpu::ImageCopyExternalImage {
+        source: wgpu::ExternalImageSource::CpuImage(wgpu::CpuImage {
+            data: Arc::from(&IMAGE[..]),
+            width: 2,
+            height: 2,
+            premultiplied_alpha: false,
+        }),
+        origin: wgpu::Origin2d::ZERO,
+        flip_y,
+    }
+}
+
+fn create_texture(ctx: &TestingContext, format: wgpu::TextureFormat) -> wgpu::Texture {
+    ctx.device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d {
+            width: 2,
+            height: 2,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    })
+}
+
+fn copy_and_read_back(
+    ctx: &TestingContext,
+    format: wgpu::TextureFormat,
+    flip_y: bool,
+    premultiplied_alpha: bool,
+) -> Vec<u8> {
+    let texture = create_texture(ctx, format);
+    let size = wgpu::Extent3d {
+        width: 2,
+        height: 2,
+        depth_or_array_layers: 1,
+    };
+    ctx.queue.copy_external_image_to_texture(
+        &cpu_image(flip_y),
+        texture
+            .as_image_copy()
+            .to_tagged(wgpu::PredefinedColorSpace::Srgb, premultiplied_alpha),
+        size,
+    );
+
+    let read_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64 * 2,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &read_buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                rows_per_image: None,
+            },
+        },
+        size,
+    );
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let slice = read_buffer.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| ());
+    ctx.device.poll(wgpu::Maintain::Wait);
+    let data = slice.get_mapped_range();
+    let row = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize;
+    [&data[..8], &data[row..row + 8]].concat()
+}
+
+#[test]
+fn cpu_image_copy() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let data = copy_and_read_back(&ctx, wgpu::TextureFormat::Rgba8Unorm, false, false);
+        assert_eq!(data, IMAGE);
+    });
+}
+
+#[test]
+fn cpu_image_copy_flip_premultiply_swizzle() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let data = copy_and_read_back(&ctx, wgpu::TextureFormat::Bgra8Unorm, true, true);
+        assert_eq!(
+            data,
+            [
+                255, 0, 0, 255, 0, 0, 0, 0, //
+                0, 0, 255, 255, 0, 128, 0, 128,
+            ]
+        );
+    });
+}
+
+#[test]
+fn cpu_image_copy_validation() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let size = wgpu::Extent3d {
+            width: 2,
+            height: 2,
+            depth_or_array_layers: 1,
+        };
+
+        // Only 8-bit RGBA destinations can be converted to.
+        let texture = create_texture(&ctx, wgpu::TextureFormat::R8Unorm);
+        fail(&ctx.device, || {
+            ctx.queue.copy_external_image_to_texture(
+                &cpu_image(false),
+                texture
+                    .as_image_copy()
+                    .to_tagged(wgpu::PredefinedColorSpace::Srgb, false),
+                size,
+            );
+        });
+
+        // The source region must lie within the image.
+        let texture = create_texture(&ctx, wgpu::TextureFormat::Rgba8Unorm);
+        let mut source = cpu_image(false);
+        source.origin = wgpu::Origin2d { x: 1, y: 0 };
+        fail(&ctx.device, || {
+            ctx.queue.copy_external_image_to_texture(
+                &source,
+                texture
+                    .as_image_copy()
+                    .to_tagged(wgpu::PredefinedColorSpace::Srgb, false),
+                size,
+            );
+        });
+    });
+}
@@ -0,0 +1,108 @@
+//! Tests for `wgpu::util::copy_buffer_to_device` and `wgpu::util::copy_texture_to_device`.
+
+use std::sync::{Arc, Mutex};
+
+use wgpu::util::{DeviceExt, DownloadBuffer, DownloadTexture};
+use wgpu_test::{initialize_test, TestParameters};
+
+#[test]
+fn copy_buffer_between_devices() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let (dst_device, dst_queue) =
+            pollster::block_on(ctx.adapter.request_device(&Default::default(), None)).unwrap();
+
+        let data: Vec<u8> = (0..64).collect();
+        let src = ctx
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: &data,
+                usage: wgpu::BufferUsages::COPY_SRC,
+            });
+        let dst = dst_device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 128,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        wgpu::util::copy_buffer_to_device(
+            &ctx.device,
+            &ctx.queue,
+            &src.slice(..),
+            &dst_queue,
+            &dst,
+            64,
+        )
+        .unwrap();
+
+        let result = Arc::new(Mutex::new(None));
+        let result_clone = result.clone();
+        DownloadBuffer::read_buffer(&dst_device, &dst_queue, &dst.slice(..), move |download| {
+            *result_clone.lock().unwrap() = Some(download.unwrap().to_vec());
+        });
+        dst_device.poll(wgpu::Maintain::Wait);
+
+        let download = result.lock().unwrap().take().unwrap();
+        assert!(download[..64].iter().all(|&b| b == 0));
+        assert_eq!(&download[64..], &data[..]);
+    });
+}
+
+#[test]
+fn copy_texture_between_devices() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let (dst_device, dst_queue) =
+            pollster::block_on(ctx.adapter.request_device(&Default::default(), None)).unwrap();
+
+        let size = wgpu::Extent3d {
+            width: 3,
+            height: 2,
+            depth_or_array_layers: 2,
+        };
+        let descriptor = wgpu::TextureDescriptor {
+            label: None,
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        };
+
+        let data: Vec<u8> = (0..3 * 2 * 2 * 4).map(|i| i as u8).collect();
+        let src = ctx
+            .device
+            .create_texture_with_data(&ctx.queue, &descriptor, &data);
+        let dst = dst_device.create_texture(&descriptor);
+
+        wgpu::util::copy_texture_to_device(
+            &ctx.device,
+            &ctx.queue,
+            src.as_image_copy(),
+            &dst_queue,
+            dst.as_image_copy(),
+            size,
+        )
+        .unwrap();
+
+        let result = Arc::new(Mutex::new(None));
+        let result_clone = result.clone();
+        DownloadTexture::read_texture(
+            &dst_device,
+            &dst_queue,
+            dst.as_image_copy(),
+            size,
+            move |download| {
+                *result_clone.lock().unwrap() = Some(download.unwrap());
+            },
+        );
+        dst_device.poll(wgpu::Maintain::Wait);
+
+        let download = result.lock().unwrap().take().unwrap();
+        assert_eq!(&*download, &data[..]);
+    });
+}
@@ -36,3 +36,39 @@ fn device_mismatch() {
         ctx.device.poll(wgpu::Maintain::Poll);
     });
 }
+
+#[test]
+fn device_lost_callback_called_on_drop() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let (device, _queue) =
+            pollster::block_on(ctx.adapter.request_device(&Default::default(), None)).unwrap();
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        device.set_device_lost_callback(move |reason, _message| {
+            sender.send(reason).unwrap();
+        });
+        drop(device);
+
+        assert_eq!(receiver.try_recv(), Ok(wgpu::DeviceLostReason::Dropped));
+    })
+}
+
+#[test]
+#[cfg(not(target_arch = "wasm32"))]
+fn device_report() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 256,
+            usage: wgpu::BufferUsages::VERTEX,
+            mapped_at_creation: false,
+        });
+        let report = ctx.device.generate_report();
+        assert_eq!(report.buffers.count, 1);
+        assert_eq!(report.buffers.bytes, 256);
+
+        buffer.destroy();
+        let report = ctx.device.generate_report();
+        assert_eq!(report.buffers.bytes, 0);
+    })
+}
@@ -0,0 +1,49 @@
+//! Tests for `wgpu::util::DownloadTexture`.
+
+use std::sync::{Arc, Mutex};
+
+use wgpu::util::{DeviceExt, DownloadTexture};
+use wgpu_test::{initialize_test, TestParameters};
+
+#[test]
+fn download_texture_removes_padding() {
+    initialize_test(TestParameters::default(), |ctx| {
+        // 3x2 texels with 2 layers: rows of 12 bytes that need padding to 256 bytes.
+        let data: Vec<u8> = (0..3 * 2 * 2 * 4).map(|i| i as u8).collect();
+        let texture = ctx.device.create_texture_with_data(
+            &ctx.queue,
+            &wgpu::TextureDescriptor {
+                label: None,
+                size: wgpu::Extent3d {
+                    width: 3,
+                    height: 2,
+                    depth_or_array_layers: 2,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+            &data,
+        );
+
+        let result = Arc::new(Mutex::new(None));
+        let result_clone = result.clone();
+        DownloadTexture::read_texture(
+            &ctx.device,
+            &ctx.queue,
+            texture.as_image_copy(),
+            texture.size(),
+            move |download| {
+                *result_clone.lock().unwrap() = Some(download.unwrap());
+            },
+        );
+        ctx.device.poll(wgpu::Maintain::Wait);
+
+        let download = result.lock().unwrap().take().unwrap();
+        assert_eq!(download.bytes_per_row(), 12);
+        assert_eq!(&*download, &data[..]);
+    });
+}
@@ -0,0 +1,135 @@
+//! Tests for setting the same bind group again with new dynamic offsets.
+
+use std::sync::{Arc, Mutex};
+
+use wgpu::util::{DeviceExt, DownloadBuffer};
+use wgpu_test::{initialize_test, TestParameters};
+
+const STRIDE: u32 = 256;
+const DISPATCHES: u32 = 4;
+
+#[test]
+fn rebind_same_group_with_new_offsets() {
+    initialize_test(
+        TestParameters::default().downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            let module = ctx
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(
+                        "@group(0) @binding(0) var<storage, read_write> output: u32;
+                        @group(1) @binding(0) var<uniform> input: u32;
+                        @compute @workgroup_size(1) fn main() { output = input + 100u; }"
+                            .into(),
+                    ),
+                });
+
+            let layout_entry = |ty| {
+                ctx.device
+                    .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                        label: None,
+                        entries: &[wgpu::BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: wgpu::ShaderStages::COMPUTE,
+                            ty: wgpu::BindingType::Buffer {
+                                ty,
+                                has_dynamic_offset: true,
+                                min_binding_size: wgpu::BufferSize::new(4),
+                            },
+                            count: None,
+                        }],
+                    })
+            };
+            let output_bgl = layout_entry(wgpu::BufferBindingType::Storage { read_only: false });
+            let input_bgl = layout_entry(wgpu::BufferBindingType::Uniform);
+            let layout = ctx
+                .device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: None,
+                    bind_group_layouts: &[&output_bgl, &input_bgl],
+                    push_constant_ranges: &[],
+                });
+            let pipeline = ctx
+                .device
+                .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: None,
+                    layout: Some(&layout),
+                    module: &module,
+                    entry_point: "main",
+                    constants: &Default::default(),
+                    cache: None,
+                });
+
+            let mut input = vec![0u8; (STRIDE * DISPATCHES) as usize];
+            for i in 0..DISPATCHES {
+                let offset = (i * STRIDE) as usize;
+                input[offset..offset + 4].copy_from_slice(&i.to_ne_bytes());
+            }
+            let input_buffer = ctx
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: None,
+                    contents: &input,
+                    usage: wgpu::BufferUsages::UNIFORM,
+                });
+            let output_buffer = ctx
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: None,
+                    contents: &vec![0; (STRIDE * DISPATCHES) as usize],
+                    usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+                });
+            let bind_group = |layout, buffer: &wgpu::Buffer| {
+                ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: None,
+                    layout,
+                    entries: &[wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                            buffer,
+                            offset: 0,
+                            size: wgpu::BufferSize::new(4),
+                        }),
+                    }],
+                })
+            };
+            let output_group = bind_group(&output_bgl, &output_buffer);
+            let input_group = bind_group(&input_bgl, &input_buffer);
+
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            {
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+                pass.set_pipeline(&pipeline);
+                for i in 0..DISPATCHES {
+                    pass.set_bind_group(0, &output_group, &[i * STRIDE]);
+                    // Reverse the input order, so both groups change independently.
+                    pass.set_bind_group(1, &input_group, &[(DISPATCHES - 1 - i) * STRIDE]);
+                    pass.dispatch_workgroups(1, 1, 1);
+                }
+            }
+            ctx.queue.submit(Some(encoder.finish()));
+
+            let result = Arc::new(Mutex::new(None));
+            let result_clone = result.clone();
+            DownloadBuffer::read_buffer(
+                &ctx.device,
+                &ctx.queue,
+                &output_buffer.slice(..),
+                move |download| {
+                    *result_clone.lock().unwrap() = Some(download.unwrap().to_vec());
+                },
+            );
+            ctx.device.poll(wgpu::Maintain::Wait);
+
+            let output = result.lock().unwrap().take().unwrap();
+            for i in 0..DISPATCHES {
+                let offset = (i * STRIDE) as usize;
+                let value = u32::from_ne_bytes(output[offset..offset + 4].try_into().unwrap());
+                assert_eq!(value, DISPATCHES - 1 - i + 100, "dispatch {i}");
+            }
+        },
+    );
+}
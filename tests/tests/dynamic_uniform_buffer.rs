@@ -0,0 +1,163 @@
+//! Tests for `util::DynamicUniformBuffer`.
+
+use std::sync::{Arc, Mutex};
+
+use wgpu::util::{DeviceExt, DownloadBuffer, DynamicUniformBuffer};
+use wgpu_test::{initialize_test, TestParameters, TestingContext};
+
+const ENTRIES: u32 = 4;
+
+/// Dispatches once per `(index, value)` entry of `uniforms`, bound at each of the `offsets`,
+/// and returns the values the dispatches stored at their indices.
+fn dispatch_entries(
+    ctx: &TestingContext,
+    uniforms: &DynamicUniformBuffer,
+    offsets: &[wgpu::DynamicOffset],
+) -> Vec<u32> {
+    let module = ctx
+        .device
+        .create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(
+                "@group(0) @binding(0) var<storage, read_write> output: array<u32>;
+                @group(0) @binding(1) var<uniform> entry: vec2<u32>;
+                @compute @workgroup_size(1) fn main() { output[entry.x] = entry.y; }"
+                    .into(),
+            ),
+        });
+
+    let output_buffer = ctx
+        .device
+        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &[0; (ENTRIES * 4) as usize],
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        });
+    let layout = ctx
+        .device
+        .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: wgpu::BufferSize::new(8),
+                    },
+                    count: None,
+                },
+            ],
+        });
+    let pipeline_layout = ctx
+        .device
+        .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+    let pipeline = ctx
+        .device
+        .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            module: &module,
+            entry_point: "main",
+            constants: &Default::default(),
+            cache: None,
+        });
+    let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout: &layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: output_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: uniforms.binding(wgpu::BufferSize::new(8).unwrap()),
+            },
+        ],
+    });
+
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    {
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+        pass.set_pipeline(&pipeline);
+        for &offset in offsets {
+            pass.set_bind_group(0, &bind_group, &[offset]);
+            pass.dispatch_workgroups(1, 1, 1);
+        }
+    }
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let result = Arc::new(Mutex::new(None));
+    let result_clone = result.clone();
+    DownloadBuffer::read_buffer(
+        &ctx.device,
+        &ctx.queue,
+        &output_buffer.slice(..),
+        move |download| {
+            *result_clone.lock().unwrap() = Some(download.unwrap().to_vec());
+        },
+    );
+    ctx.device.poll(wgpu::Maintain::Wait);
+
+    let output = result.lock().unwrap().take().unwrap();
+    bytemuck::cast_slice(&output).to_vec()
+}
+
+/// Pushes an `(index, value)` entry for each of `values`, returning their offsets.
+fn push_entries(uniforms: &mut DynamicUniformBuffer, values: &[u32]) -> Vec<u32> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, &value)| uniforms.push(bytemuck::cast_slice(&[index as u32, value])))
+        .collect()
+}
+
+#[test]
+fn dynamic_uniform_buffer_grows_and_is_reused() {
+    initialize_test(
+        TestParameters::default().downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            let alignment = ctx.device.limits().min_uniform_buffer_offset_alignment;
+            let mut uniforms = DynamicUniformBuffer::new(&ctx.device, None, 0);
+            assert_eq!(uniforms.buffer().size(), alignment as u64);
+
+            // The buffer starts with room for a single entry, so four entries make it grow.
+            let offsets = push_entries(&mut uniforms, &[10, 20, 30, 40]);
+            let expected_offsets = (0..ENTRIES).map(|i| i * alignment).collect::<Vec<_>>();
+            assert_eq!(offsets, expected_offsets);
+            assert!(uniforms.write(&ctx.device, &ctx.queue));
+            let grown_size = uniforms.buffer().size();
+            assert!(grown_size >= (ENTRIES * alignment) as u64);
+            assert_eq!(
+                dispatch_entries(&ctx, &uniforms, &offsets),
+                [10, 20, 30, 40]
+            );
+
+            // After clearing, fewer entries fit in the grown buffer, which is kept.
+            uniforms.clear();
+            let offsets = push_entries(&mut uniforms, &[50, 60]);
+            assert_eq!(offsets, expected_offsets[..2]);
+            assert!(!uniforms.write(&ctx.device, &ctx.queue));
+            assert_eq!(uniforms.buffer().size(), grown_size);
+            assert_eq!(dispatch_entries(&ctx, &uniforms, &offsets), [50, 60, 0, 0]);
+        },
+    );
+}
@@ -50,6 +50,7 @@ fn drop_encoder_after_error() {
                 ops: wgpu::Operations::default(),
                 resolve_target: None,
                 view: &target_view,
+                depth_slice: None,
             })],
             depth_stencil_attachment: None,
             timestamp_writes: None,
@@ -68,3 +69,176 @@ fn drop_encoder_after_error() {
         drop(encoder);
     })
 }
+
+#[test]
+fn record_encoders_on_multiple_threads() {
+    initialize_test(TestParameters::default(), |ctx| {
+        const THREADS: u8 = 4;
+        let extent = wgpu::Extent3d {
+            width: 64,
+            height: 1,
+            depth_or_array_layers: 1,
+        };
+
+        let readback_buffers: Vec<_> = (0..THREADS)
+            .map(|_| {
+                ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                    label: None,
+                    size: 256,
+                    usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                    mapped_at_creation: false,
+                })
+            })
+            .collect();
+
+        // Each thread records its own encoder, clearing a texture to a distinct color.
+        let command_buffers: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = readback_buffers
+                .iter()
+                .enumerate()
+                .map(|(i, buffer)| {
+                    let ctx = &ctx;
+                    scope.spawn(move || {
+                        let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+                            label: None,
+                            size: extent,
+                            mip_level_count: 1,
+                            sample_count: 1,
+                            dimension: wgpu::TextureDimension::D2,
+                            format: wgpu::TextureFormat::Rgba8Unorm,
+                            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                                | wgpu::TextureUsages::COPY_SRC,
+                            view_formats: &[],
+                        });
+                        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+                        let mut encoder = ctx
+                            .device
+                            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+                        encoder.begin_render_pass(&RenderPassDescriptor {
+                            label: None,
+                            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                                view: &view,
+                                depth_slice: None,
+                                resolve_target: None,
+                                ops: wgpu::Operations {
+                                    load: wgpu::LoadOp::Clear(wgpu::Color {
+                                        r: (i + 1) as f64 / 255.0,
+                                        g: 0.0,
+                                        b: 0.0,
+                                        a: 1.0,
+                                    }),
+                                    store: true,
+                                },
+                            })],
+                            depth_stencil_attachment: None,
+                            timestamp_writes: None,
+                            occlusion_query_set: None,
+                        });
+                        encoder.copy_texture_to_buffer(
+                            texture.as_image_copy(),
+                            wgpu::ImageCopyBuffer {
+                                buffer,
+                                layout: wgpu::ImageDataLayout {
+                                    offset: 0,
+                                    bytes_per_row: Some(256),
+                                    rows_per_image: None,
+                                },
+                            },
+                            extent,
+                        );
+                        encoder.finish()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        ctx.queue.submit(command_buffers);
+
+        for buffer in &readback_buffers {
+            buffer
+                .slice(..)
+                .map_async(wgpu::MapMode::Read, Result::unwrap);
+        }
+        ctx.device.poll(wgpu::Maintain::Wait);
+
+        for (i, buffer) in readback_buffers.iter().enumerate() {
+            let data = buffer.slice(..).get_mapped_range();
+            for texel in data.chunks_exact(4) {
+                assert_eq!(texel, [i as u8 + 1, 0, 0, 255]);
+            }
+        }
+    })
+}
+
+#[test]
+fn resubmit_reusable_command_buffer() {
+    initialize_test(
+        TestParameters::default().features(wgpu::Features::REUSABLE_COMMAND_BUFFERS),
+        |ctx| {
+            let src = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: 4,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            });
+            let dst = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: 4,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            });
+
+            let mut encoder = ctx.device.create_command_encoder(
+                &wgpu::CommandEncoderDescriptor::default().with_reusable(true),
+            );
+            encoder.copy_buffer_to_buffer(&src, 0, &dst, 0, 4);
+            let command_buffer = encoder.finish_reusable();
+
+            // Each submission copies the current contents of `src`.
+            for value in 1..=3u8 {
+                ctx.queue.write_buffer(&src, 0, &[value; 4]);
+                ctx.queue.submit_reusable([&command_buffer]);
+
+                dst.slice(..).map_async(wgpu::MapMode::Read, Result::unwrap);
+                ctx.device.poll(wgpu::Maintain::Wait);
+                assert_eq!(*dst.slice(..).get_mapped_range(), [value; 4]);
+                dst.unmap();
+            }
+
+            // Dropping the command buffer while it's executing is fine.
+            ctx.queue.submit_reusable([&command_buffer]);
+            drop(command_buffer);
+            ctx.device.poll(wgpu::Maintain::Wait);
+        },
+    )
+}
+
+#[test]
+fn finish_reusable_without_feature() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default().with_reusable(true));
+        let command_buffer = fail(&ctx.device, || encoder.finish_reusable());
+        drop(command_buffer);
+    })
+}
+
+#[test]
+fn finish_reusable_from_one_time_encoder() {
+    initialize_test(
+        TestParameters::default().features(wgpu::Features::REUSABLE_COMMAND_BUFFERS),
+        |ctx| {
+            let encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            let command_buffer = fail(&ctx.device, || encoder.finish_reusable());
+            drop(command_buffer);
+        },
+    )
+}
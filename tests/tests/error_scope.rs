@@ -0,0 +1,62 @@
+use wasm_bindgen_test::*;
+use wgpu_test::{fail, initialize_test, TestParameters};
+
+fn create_invalid_buffer(device: &wgpu::Device) -> wgpu::Buffer {
+    // Mapped for reading and used as a vertex buffer at the same time.
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 4,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::VERTEX,
+        mapped_at_creation: false,
+    })
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn error_scope_captures_innermost_matching_filter() {
+    initialize_test(TestParameters::default(), |ctx| {
+        ctx.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        ctx.device.push_error_scope(wgpu::ErrorFilter::OutOfMemory);
+        ctx.device.push_error_scope(wgpu::ErrorFilter::Internal);
+        let _buffer = create_invalid_buffer(&ctx.device);
+        let internal = pollster::block_on(ctx.device.pop_error_scope());
+        let oom = pollster::block_on(ctx.device.pop_error_scope());
+        let validation = pollster::block_on(ctx.device.pop_error_scope());
+
+        assert!(internal.is_none());
+        assert!(oom.is_none());
+        assert!(matches!(validation, Some(wgpu::Error::Validation { .. })));
+    })
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn error_scope_is_empty_without_errors() {
+    initialize_test(TestParameters::default(), |ctx| {
+        ctx.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let _encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        assert!(pollster::block_on(ctx.device.pop_error_scope()).is_none());
+    })
+}
+
+#[test]
+fn render_bundle_finish_error_is_captured() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let mut encoder =
+            ctx.device
+                .create_render_bundle_encoder(&wgpu::RenderBundleEncoderDescriptor {
+                    label: None,
+                    color_formats: &[Some(wgpu::TextureFormat::Rgba8Unorm)],
+                    depth_stencil: None,
+                    sample_count: 1,
+                    multiview: None,
+                });
+        encoder.push_debug_group("unbalanced");
+
+        fail(&ctx.device, || {
+            encoder.finish(&wgpu::RenderBundleDescriptor { label: None });
+        });
+    })
+}
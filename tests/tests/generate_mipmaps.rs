@@ -0,0 +1,148 @@
+//! Tests for `wgpu::util::generate_mipmaps`.
+
+use wgpu::util::DeviceExt;
+use wgpu_test::{initialize_test, TestParameters, TestingContext};
+
+fn create_texture(
+    ctx: &TestingContext,
+    format: wgpu::TextureFormat,
+    usage: wgpu::TextureUsages,
+    data: &[u8],
+) -> wgpu::Texture {
+    let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d {
+            width: 4,
+            height: 4,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 3,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: usage | wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let texel_size = format.block_size(None).unwrap();
+    ctx.queue.write_texture(
+        texture.as_image_copy(),
+        data,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(4 * texel_size),
+            rows_per_image: None,
+        },
+        wgpu::Extent3d {
+            width: 4,
+            height: 4,
+            depth_or_array_layers: 1,
+        },
+    );
+    texture
+}
+
+/// Generates the mips of `texture` and reads back the top-left texel of mip levels 1 and 2.
+fn generate_and_read_back(ctx: &TestingContext, texture: &wgpu::Texture) -> Vec<u8> {
+    let texel_size = texture.format().block_size(None).unwrap() as usize;
+    let read_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64 * 2,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    wgpu::util::generate_mipmaps(&mut encoder, &ctx.device, texture);
+    for mip_level in 1..3 {
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &read_buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: (mip_level as u64 - 1) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64,
+                    bytes_per_row: None,
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let slice = read_buffer.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| ());
+    ctx.device.poll(wgpu::Maintain::Wait);
+    let data = slice.get_mapped_range();
+    let row = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize;
+    [&data[..texel_size], &data[row..row + texel_size]].concat()
+}
+
+#[test]
+fn generate_mipmaps_render() {
+    // The GL backend can't sample from views with a non-zero base mip level.
+    let parameters = TestParameters::default().backend_failure(wgpu::Backends::GL);
+    initialize_test(parameters, |ctx| {
+        // Each 2x2 quadrant has a single color, so mip level 1 holds the quadrant
+        // colors and mip level 2 their average.
+        let quadrants: [[u8; 4]; 4] = [
+            [255, 0, 0, 255],
+            [0, 255, 0, 255],
+            [0, 0, 255, 255],
+            [255, 255, 255, 255],
+        ];
+        let mut data = Vec::new();
+        for y in 0..4 {
+            for x in 0..4 {
+                data.extend_from_slice(&quadrants[(y / 2) * 2 + x / 2]);
+            }
+        }
+
+        let texture = create_texture(
+            &ctx,
+            wgpu::TextureFormat::Rgba8Unorm,
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+            &data,
+        );
+        let result = generate_and_read_back(&ctx, &texture);
+
+        assert_eq!(result[..4], quadrants[0]);
+        for (texel, expected) in result[4..].iter().zip([128u8, 128, 128, 255]) {
+            assert!(texel.abs_diff(expected) <= 1, "{result:?}");
+        }
+    });
+}
+
+#[test]
+fn generate_mipmaps_compute() {
+    initialize_test(
+        TestParameters::default()
+            .downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+            .backend_failure(wgpu::Backends::GL),
+        |ctx| {
+            // Rgba32Float isn't filterable, so it is downsampled in a compute shader.
+            let data: Vec<f32> = (0..16).flat_map(|i| [i as f32, 0.0, 0.0, 1.0]).collect();
+            let texture = create_texture(
+                &ctx,
+                wgpu::TextureFormat::Rgba32Float,
+                wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::STORAGE_BINDING,
+                bytemuck::cast_slice(&data),
+            );
+            let result = generate_and_read_back(&ctx, &texture);
+            let result: &[f32] = bytemuck::cast_slice(&result);
+
+            // Top-left 2x2 block is 0, 1, 4, 5; the whole image averages to 7.5.
+            assert_eq!(result, [2.5, 0.0, 0.0, 1.0, 7.5, 0.0, 0.0, 1.0]);
+        },
+    );
+}
@@ -0,0 +1,50 @@
+use std::borrow::Cow;
+
+use wgpu_test::{fail, initialize_test, valid, TestParameters};
+
+const SHADER: &str = "
+#version 450
+
+void main() {
+    gl_Position = vec4(OFFSET, 0.0, 0.0, 1.0);
+}
+";
+
+fn glsl_source(defines: &[(&str, &str)]) -> wgpu::ShaderSource<'static> {
+    wgpu::ShaderSource::Glsl {
+        shader: Cow::Borrowed(SHADER),
+        stage: naga::ShaderStage::Vertex,
+        defines: defines
+            .iter()
+            .map(|&(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+    }
+}
+
+#[test]
+fn glsl_shader_with_defines() {
+    initialize_test(TestParameters::default(), |ctx| {
+        valid(&ctx.device, || {
+            ctx.device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some("glsl"),
+                    source: glsl_source(&[("OFFSET", "0.5")]),
+                })
+        });
+    });
+}
+
+#[test]
+fn glsl_parse_error_is_reported() {
+    // Without the define the shader refers to an unknown identifier, which
+    // must be reported as a validation error rather than a panic.
+    initialize_test(TestParameters::default(), |ctx| {
+        fail(&ctx.device, || {
+            ctx.device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some("glsl"),
+                    source: glsl_source(&[]),
+                })
+        });
+    });
+}
@@ -0,0 +1,135 @@
+//! Tests for the shader hot-reload helpers in `wgpu::util`.
+
+use std::{
+    borrow::Cow,
+    sync::{Arc, Mutex},
+};
+
+use wgpu::util::{
+    recreate_compute_pipeline_with_shader, try_create_shader_module, DeviceExt, DownloadBuffer,
+};
+use wgpu_test::{initialize_test, TestParameters};
+
+fn shader_writing(value: u32) -> String {
+    format!(
+        "@group(0) @binding(0) var<storage, read_write> output: u32;
+        @compute @workgroup_size(1) fn main() {{ output = {value}u; }}"
+    )
+}
+
+#[test]
+fn recreate_compute_pipeline_keeps_bind_groups() {
+    initialize_test(
+        TestParameters::default().downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            let bgl = ctx
+                .device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only: false },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    }],
+                });
+            let layout = ctx
+                .device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: None,
+                    bind_group_layouts: &[&bgl],
+                    push_constant_ranges: &[],
+                });
+            let buffer = ctx
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: None,
+                    contents: &[0; 4],
+                    usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+                });
+            let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout: &bgl,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                }],
+            });
+
+            let module = pollster::block_on(try_create_shader_module(
+                &ctx.device,
+                wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(Cow::Owned(shader_writing(1))),
+                },
+            ))
+            .unwrap();
+            let constants = Default::default();
+            let desc = wgpu::ComputePipelineDescriptor {
+                label: None,
+                layout: Some(&layout),
+                module: &module,
+                entry_point: "main",
+                constants: &constants,
+                cache: None,
+            };
+            let mut pipeline = ctx.device.create_compute_pipeline(&desc);
+
+            // A broken shader is reported and leaves the current pipeline in place.
+            let broken = pollster::block_on(try_create_shader_module(
+                &ctx.device,
+                wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(Cow::Borrowed("this is not wgsl")),
+                },
+            ));
+            assert!(broken.is_err());
+
+            let reloaded = pollster::block_on(try_create_shader_module(
+                &ctx.device,
+                wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(Cow::Owned(shader_writing(2))),
+                },
+            ))
+            .unwrap();
+            if let Ok(new_pipeline) = pollster::block_on(recreate_compute_pipeline_with_shader(
+                &ctx.device,
+                &desc,
+                &reloaded,
+            )) {
+                pipeline = new_pipeline;
+            }
+
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            {
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+                pass.set_pipeline(&pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.dispatch_workgroups(1, 1, 1);
+            }
+            ctx.queue.submit(Some(encoder.finish()));
+
+            let result = Arc::new(Mutex::new(None));
+            let result_clone = result.clone();
+            DownloadBuffer::read_buffer(
+                &ctx.device,
+                &ctx.queue,
+                &buffer.slice(..),
+                move |download| {
+                    *result_clone.lock().unwrap() = Some(download.unwrap().to_vec());
+                },
+            );
+            ctx.device.poll(wgpu::Maintain::Wait);
+
+            let output = result.lock().unwrap().take().unwrap();
+            assert_eq!(output, 2u32.to_ne_bytes());
+        },
+    );
+}
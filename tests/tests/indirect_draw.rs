@@ -0,0 +1,234 @@
+use wgpu::util::DeviceExt;
+use wgpu_test::{initialize_test, TestParameters, TestingContext};
+
+/// How the draws of [`draw_twice`] are recorded.
+#[derive(Clone, Copy)]
+enum Draw {
+    NonIndexed,
+    Indexed,
+    NonIndexedInBundle,
+}
+
+/// Draws a triangle covering a 1x1 target with each of the two indirect draws in `args`, and
+/// returns the resulting pixels.
+fn draw_twice(ctx: &TestingContext, draw: Draw, args: &[u32]) -> [[u8; 4]; 2] {
+    let module = ctx
+        .device
+        .create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(
+                "@vertex
+                fn vs_main(@location(0) position: vec2<f32>) -> @builtin(position) vec4<f32> {
+                    return vec4<f32>(position, 0.0, 1.0);
+                }
+
+                @fragment
+                fn fs_main() -> @location(0) vec4<f32> {
+                    return vec4<f32>(1.0);
+                }"
+                .into(),
+            ),
+        });
+    let pipeline = ctx
+        .device
+        .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                constants: &Default::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: 8,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x2],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                constants: &Default::default(),
+                targets: &[Some(wgpu::TextureFormat::Rgba8Unorm.into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+    // The triangle is repeated past the bound range of the buffer, so draws reading beyond it
+    // still cover the target unless they are validated.
+    let triangle = [-1.0f32, -1.0, 3.0, -1.0, -1.0, 3.0];
+    let vertex_buffer = ctx
+        .device
+        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&[triangle, triangle]),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+    let vertices = vertex_buffer.slice(..24);
+    let index_buffer = ctx
+        .device
+        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&[0u16, 1, 2, 0]),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+    let indirect_buffer = ctx
+        .device
+        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(args),
+            usage: wgpu::BufferUsages::INDIRECT,
+        });
+    let indirect_stride = (args.len() / 2 * 4) as u64;
+    let size = wgpu::Extent3d {
+        width: 1,
+        height: 1,
+        depth_or_array_layers: 1,
+    };
+    let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 256 * 2,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    for (index, indirect_offset) in [0, indirect_stride].into_iter().enumerate() {
+        let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&Default::default());
+        let bundle = match draw {
+            Draw::NonIndexedInBundle => {
+                let mut bundle_encoder =
+                    ctx.device
+                        .create_render_bundle_encoder(&wgpu::RenderBundleEncoderDescriptor {
+                            label: None,
+                            color_formats: &[Some(wgpu::TextureFormat::Rgba8Unorm)],
+                            depth_stencil: None,
+                            sample_count: 1,
+                            multiview: None,
+                        });
+                bundle_encoder.set_pipeline(&pipeline);
+                bundle_encoder.set_vertex_buffer(0, vertices);
+                bundle_encoder.draw_indirect(&indirect_buffer, indirect_offset);
+                Some(bundle_encoder.finish(&Default::default()))
+            }
+            _ => None,
+        };
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            match draw {
+                Draw::NonIndexed => {
+                    pass.set_pipeline(&pipeline);
+                    pass.set_vertex_buffer(0, vertices);
+                    pass.draw_indirect(&indirect_buffer, indirect_offset);
+                }
+                Draw::Indexed => {
+                    pass.set_pipeline(&pipeline);
+                    pass.set_vertex_buffer(0, vertices);
+                    pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+                    pass.draw_indexed_indirect(&indirect_buffer, indirect_offset);
+                }
+                Draw::NonIndexedInBundle => {
+                    pass.execute_bundles(bundle.as_ref());
+                }
+            }
+        }
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 256 * index as u64,
+                    bytes_per_row: None,
+                    rows_per_image: None,
+                },
+            },
+            size,
+        );
+    }
+    ctx.queue.submit(Some(encoder.finish()));
+
+    buffer.slice(..).map_async(wgpu::MapMode::Read, |_| ());
+    ctx.device.poll(wgpu::Maintain::Wait);
+    let data = buffer.slice(..).get_mapped_range();
+    [
+        data[..4].try_into().unwrap(),
+        data[256..260].try_into().unwrap(),
+    ]
+}
+
+fn test_parameters() -> TestParameters {
+    TestParameters::default().downlevel_flags(
+        wgpu::DownlevelFlags::COMPUTE_SHADERS | wgpu::DownlevelFlags::INDIRECT_EXECUTION,
+    )
+}
+
+/// Draws with first instance 0, then 1.
+const FIRST_INSTANCES: [u32; 8] = [3, 1, 0, 0, 3, 1, 0, 1];
+
+/// Without `INDIRECT_FIRST_INSTANCE`, indirect draws with a non-zero first instance draw nothing.
+#[test]
+fn indirect_first_instance_without_feature() {
+    initialize_test(test_parameters(), |ctx| {
+        let pixels = draw_twice(&ctx, Draw::NonIndexed, &FIRST_INSTANCES);
+        assert_eq!(pixels, [[255; 4], [0, 0, 0, 255]]);
+    });
+}
+
+#[test]
+fn indirect_first_instance_with_feature() {
+    initialize_test(
+        test_parameters().features(wgpu::Features::INDIRECT_FIRST_INSTANCE),
+        |ctx| {
+            let pixels = draw_twice(&ctx, Draw::NonIndexed, &FIRST_INSTANCES);
+            assert_eq!(pixels, [[255; 4], [255; 4]]);
+        },
+    );
+}
+
+#[test]
+fn indirect_first_instance_in_bundle_without_feature() {
+    initialize_test(test_parameters(), |ctx| {
+        let pixels = draw_twice(&ctx, Draw::NonIndexedInBundle, &FIRST_INSTANCES);
+        assert_eq!(pixels, [[255; 4], [0, 0, 0, 255]]);
+    });
+}
+
+/// Indexed draws whose base vertex leaves every index past the vertex buffer draw nothing.
+#[test]
+fn indirect_base_vertex_out_of_bounds() {
+    initialize_test(test_parameters(), |ctx| {
+        // Draws with base vertex 0, then 3.
+        let args = [3, 1, 0, 0, 0, 3, 1, 0, 3, 0];
+        let pixels = draw_twice(&ctx, Draw::Indexed, &args);
+        assert_eq!(pixels, [[255; 4], [0, 0, 0, 255]]);
+    });
+}
@@ -5,6 +5,7 @@ use wasm_bindgen_test::*;
 fn initialize() {
     let _ = wgpu::Instance::new(wgpu::InstanceDescriptor {
         backends: wgpu::util::backend_bits_from_env().unwrap_or_else(wgpu::Backends::all),
+        flags: wgpu::InstanceFlags::default(),
         dx12_shader_compiler: wgpu::util::dx12_shader_compiler_from_env().unwrap_or_default(),
         gles_minor_version: wgpu::util::gles_minor_version_from_env().unwrap_or_default(),
     });
@@ -13,6 +14,7 @@ fn initialize() {
 fn request_adapter_inner(power: wgt::PowerPreference) {
     let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
         backends: wgpu::util::backend_bits_from_env().unwrap_or_else(wgpu::Backends::all),
+        flags: wgpu::InstanceFlags::default(),
         dx12_shader_compiler: wgpu::util::dx12_shader_compiler_from_env().unwrap_or_default(),
         gles_minor_version: wgpu::util::gles_minor_version_from_env().unwrap_or_default(),
     });
@@ -34,3 +36,23 @@ fn request_adapter_low_power() {
 fn request_adapter_high_power() {
     request_adapter_inner(wgt::PowerPreference::HighPerformance);
 }
+
+#[test]
+fn request_fallback_adapter() {
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends: wgpu::util::backend_bits_from_env().unwrap_or_else(wgpu::Backends::all),
+        flags: wgpu::InstanceFlags::default(),
+        dx12_shader_compiler: wgpu::util::dx12_shader_compiler_from_env().unwrap_or_default(),
+        gles_minor_version: wgpu::util::gles_minor_version_from_env().unwrap_or_default(),
+    });
+
+    // Not every machine has a software implementation, but any adapter returned must be one.
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgt::PowerPreference::None,
+        force_fallback_adapter: true,
+        compatible_surface: None,
+    }));
+    if let Some(adapter) = adapter {
+        assert_eq!(adapter.get_info().device_type, wgpu::DeviceType::Cpu);
+    }
+}
@@ -0,0 +1,71 @@
+//! Tests for the noop backend, which needs no graphics drivers and so runs everywhere.
+
+use wgpu::util::DeviceExt;
+
+fn noop_device() -> (wgpu::Adapter, wgpu::Device, wgpu::Queue) {
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::NOOP,
+        ..Default::default()
+    });
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgt::PowerPreference::None,
+        force_fallback_adapter: false,
+        compatible_surface: None,
+    }))
+    .unwrap();
+    let (device, queue) =
+        pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default(), None))
+            .unwrap();
+    (adapter, device, queue)
+}
+
+#[test]
+fn noop_adapter_info() {
+    let (adapter, _device, _queue) = noop_device();
+    let info = adapter.get_info();
+    assert_eq!(info.backend, wgpu::Backend::Empty);
+    assert_eq!(info.device_type, wgpu::DeviceType::Cpu);
+}
+
+#[test]
+fn noop_buffer_round_trip() {
+    let (_adapter, device, queue) = noop_device();
+
+    let src = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: &[1, 2, 3, 4, 5, 6, 7, 8],
+        usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+    });
+    queue.write_buffer(&src, 4, &[9, 9, 9, 9]);
+    let dst = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 8,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.copy_buffer_to_buffer(&src, 0, &dst, 0, 8);
+    queue.submit(Some(encoder.finish()));
+
+    dst.slice(..).map_async(wgpu::MapMode::Read, Result::unwrap);
+    device.poll(wgpu::Maintain::Wait);
+    assert_eq!(
+        &*dst.slice(..).get_mapped_range(),
+        &[1, 2, 3, 4, 9, 9, 9, 9]
+    );
+}
+
+#[test]
+fn noop_validation_error() {
+    let (_adapter, device, _queue) = noop_device();
+
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let _ = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 8,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::MAP_WRITE,
+        mapped_at_creation: false,
+    });
+    assert!(pollster::block_on(device.pop_error_scope()).is_some());
+}
@@ -0,0 +1,99 @@
+//! Tests for the NV12 multi-planar texture format.
+
+use wgpu_test::{fail, initialize_test, TestParameters, TestingContext};
+
+fn create_nv12_texture(ctx: &TestingContext) -> Option<wgpu::Texture> {
+    let features = ctx
+        .adapter
+        .get_texture_format_features(wgpu::TextureFormat::NV12);
+    if !features
+        .allowed_usages
+        .contains(wgpu::TextureUsages::TEXTURE_BINDING)
+    {
+        return None;
+    }
+
+    Some(ctx.device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d {
+            width: 64,
+            height: 64,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::NV12,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    }))
+}
+
+#[test]
+fn nv12_plane_views() {
+    initialize_test(
+        TestParameters::default()
+            .features(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES),
+        |ctx| {
+            let Some(texture) = create_nv12_texture(&ctx) else {
+                return;
+            };
+
+            let luma = texture.create_view(&wgpu::TextureViewDescriptor {
+                format: Some(wgpu::TextureFormat::R8Unorm),
+                aspect: wgpu::TextureAspect::Plane0,
+                ..Default::default()
+            });
+            let chroma = texture.create_view(&wgpu::TextureViewDescriptor {
+                format: Some(wgpu::TextureFormat::Rg8Unorm),
+                aspect: wgpu::TextureAspect::Plane1,
+                ..Default::default()
+            });
+            drop((luma, chroma));
+
+            // Views of a multi-planar texture must select a single plane.
+            fail(&ctx.device, || {
+                texture.create_view(&wgpu::TextureViewDescriptor::default())
+            });
+
+            // Each plane has its own format.
+            fail(&ctx.device, || {
+                texture.create_view(&wgpu::TextureViewDescriptor {
+                    format: Some(wgpu::TextureFormat::Rg8Unorm),
+                    aspect: wgpu::TextureAspect::Plane0,
+                    ..Default::default()
+                })
+            });
+        },
+    );
+}
+
+#[test]
+fn nv12_odd_size() {
+    initialize_test(
+        TestParameters::default()
+            .features(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES),
+        |ctx| {
+            if create_nv12_texture(&ctx).is_none() {
+                return;
+            }
+
+            fail(&ctx.device, || {
+                ctx.device.create_texture(&wgpu::TextureDescriptor {
+                    label: None,
+                    size: wgpu::Extent3d {
+                        width: 63,
+                        height: 64,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: wgpu::TextureFormat::NV12,
+                    usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                })
+            });
+        },
+    );
+}
@@ -1,5 +1,5 @@
 use std::borrow::Cow;
-use wgpu_test::{initialize_test, TestParameters};
+use wgpu_test::{fail, initialize_test, TestParameters};
 
 #[test]
 fn occlusion_query() {
@@ -36,6 +36,7 @@ fn occlusion_query() {
                 vertex: wgpu::VertexState {
                     module: &shader,
                     entry_point: "vs_main",
+                    constants: &Default::default(),
                     buffers: &[],
                 },
                 fragment: None,
@@ -46,9 +47,11 @@ fn occlusion_query() {
                     depth_compare: wgpu::CompareFunction::Less,
                     stencil: wgpu::StencilState::default(),
                     bias: wgpu::DepthBiasState::default(),
+                    depth_bounds: None,
                 }),
                 multisample: wgpu::MultisampleState::default(),
                 multiview: None,
+                cache: None,
             });
 
         // Create occlusion query set
@@ -126,3 +129,56 @@ fn occlusion_query() {
         assert_eq!(query_data[2], 0);
     })
 }
+
+#[test]
+fn occlusion_query_not_ended() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let depth_texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Depth texture"),
+            size: wgpu::Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let depth_texture_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let query_set = ctx.device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Query set"),
+            ty: wgpu::QueryType::Occlusion,
+            count: 1,
+        });
+
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+
+        // The query is still active when the pass ends, which is invalid.
+        fail(&ctx.device, || {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Render pass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &depth_texture_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: true,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: Some(&query_set),
+            });
+            render_pass.begin_occlusion_query(0);
+            drop(render_pass);
+        });
+
+        drop(encoder);
+    })
+}
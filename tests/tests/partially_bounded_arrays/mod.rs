@@ -72,6 +72,8 @@ fn partially_bounded_array() {
                     layout: Some(&pipeline_layout),
                     module: &cs_module,
                     entry_point: "main",
+                    constants: &Default::default(),
+                    cache: None,
                 });
 
             let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -83,8 +85,11 @@ fn partially_bounded_array() {
                 label: Some("bind group"),
             });
 
-            let mut encoder =
-                device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: None,
+                reusable: false,
+                protected: false,
+            });
             {
                 let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                     label: None,
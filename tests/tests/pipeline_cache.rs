@@ -0,0 +1,54 @@
+use wgpu_test::{initialize_test, TestParameters};
+
+const SHADER_SRC: &str = "
+@compute @workgroup_size(1)
+fn main() {}
+";
+
+#[test]
+fn pipeline_cache_round_trip() {
+    initialize_test(
+        TestParameters::default()
+            .features(wgpu::Features::PIPELINE_CACHE)
+            .downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            let module = ctx
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(SHADER_SRC.into()),
+                });
+
+            let create_pipeline = |cache: &wgpu::PipelineCache| {
+                ctx.device
+                    .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                        label: None,
+                        layout: None,
+                        module: &module,
+                        entry_point: "main",
+                        constants: &Default::default(),
+                        cache: Some(cache),
+                    })
+            };
+
+            let cache = unsafe {
+                ctx.device
+                    .create_pipeline_cache(&wgpu::PipelineCacheDescriptor::default())
+            };
+            let _pipeline = create_pipeline(&cache);
+            let data = cache.get_data();
+            drop(cache);
+
+            // A cache seeded with the data of another one must be usable the same way.
+            let cache = unsafe {
+                ctx.device
+                    .create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                        label: Some("seeded"),
+                        data: data.as_deref(),
+                    })
+            };
+            let _pipeline = create_pipeline(&cache);
+            let _ = cache.get_data();
+        },
+    )
+}
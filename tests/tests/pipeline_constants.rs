@@ -0,0 +1,172 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use wasm_bindgen_test::*;
+use wgpu::util::DeviceExt;
+use wgpu_test::{fail, initialize_test, valid, TestParameters, TestingContext};
+
+const SHADER_SRC: &str = "
+@compute @workgroup_size(1)
+fn main() {}
+";
+
+// The WGSL frontend doesn't parse `override` declarations yet, so this shader is SPIR-V with a
+// specialization constant named `value`, with id 0 and a default of 1. It stores that constant
+// in `output`.
+#[rustfmt::skip]
+const SPEC_CONSTANT_SHADER: &[u32] = &[
+    0x07230203, 0x00010300, 0, 13, 0,
+    0x00020011, 1,                      // OpCapability Shader
+    0x0003000e, 0, 1,                   // OpMemoryModel Logical GLSL450
+    0x0005000f, 5, 1, 0x6e69616d, 0,    // OpEntryPoint GLCompute %1 "main"
+    0x00060010, 1, 17, 1, 1, 1,         // OpExecutionMode %1 LocalSize 1 1 1
+    0x00040005, 5, 0x756c6176, 0x65,    // OpName %5 "value"
+    0x00040047, 5, 1, 0,                // OpDecorate %5 SpecId 0
+    0x00030047, 6, 2,                   // OpDecorate %6 Block
+    0x00050048, 6, 0, 35, 0,            // OpMemberDecorate %6 0 Offset 0
+    0x00040047, 8, 34, 0,               // OpDecorate %8 DescriptorSet 0
+    0x00040047, 8, 33, 0,               // OpDecorate %8 Binding 0
+    0x00020013, 2,                      // %2 = OpTypeVoid
+    0x00030021, 3, 2,                   // %3 = OpTypeFunction %2
+    0x00040015, 4, 32, 0,               // %4 = OpTypeInt 32 0
+    0x00040032, 4, 5, 1,                // %5 = OpSpecConstant %4 1
+    0x0003001e, 6, 4,                   // %6 = OpTypeStruct %4
+    0x00040020, 7, 12, 6,               // %7 = OpTypePointer StorageBuffer %6
+    0x0004003b, 7, 8, 12,               // %8 = OpVariable %7 StorageBuffer
+    0x00040020, 9, 12, 4,               // %9 = OpTypePointer StorageBuffer %4
+    0x0004002b, 4, 10, 0,               // %10 = OpConstant %4 0
+    0x00050036, 2, 1, 0, 3,             // %1 = OpFunction %2 None %3
+    0x000200f8, 11,                     // %11 = OpLabel
+    0x00050041, 9, 12, 8, 10,           // %12 = OpAccessChain %9 %8 %10
+    0x0003003e, 12, 5,                  // OpStore %12 %5
+    0x000100fd,                         // OpReturn
+    0x00010038,                         // OpFunctionEnd
+];
+
+#[test]
+#[wasm_bindgen_test]
+fn unknown_pipeline_constant() {
+    initialize_test(
+        TestParameters::default().downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            let module = ctx
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(SHADER_SRC.into()),
+                });
+
+            let create_pipeline = |constants: &HashMap<String, f64>| {
+                ctx.device
+                    .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                        label: None,
+                        layout: None,
+                        module: &module,
+                        entry_point: "main",
+                        constants,
+                        cache: None,
+                    })
+            };
+
+            valid(&ctx.device, || create_pipeline(&HashMap::new()));
+
+            // The shader has no overridable constants, so any key is unknown.
+            let constants = HashMap::from([("missing".to_string(), 1.0)]);
+            fail(&ctx.device, || create_pipeline(&constants));
+        },
+    );
+}
+
+/// Runs the specialization constant shader with `constants` and returns the value it stored.
+fn run_spec_constant_shader(
+    ctx: &TestingContext,
+    module: &wgpu::ShaderModule,
+    constants: &HashMap<String, f64>,
+) -> u32 {
+    let pipeline = ctx
+        .device
+        .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: None,
+            module,
+            entry_point: "main",
+            constants,
+            cache: None,
+        });
+    let output = ctx
+        .device
+        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &[0; 4],
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        });
+    let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 4,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: output.as_entire_binding(),
+        }],
+    });
+
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    {
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+        pass.set_pipeline(&pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(1, 1, 1);
+    }
+    encoder.copy_buffer_to_buffer(&output, 0, &readback, 0, 4);
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let slice = readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, Result::unwrap);
+    ctx.device.poll(wgpu::Maintain::Wait);
+    let data = slice.get_mapped_range();
+    bytemuck::cast_slice::<u8, u32>(&data)[0]
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn override_spirv_specialization_constant() {
+    initialize_test(
+        TestParameters::default().downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            let module = ctx
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::SpirV(Cow::Borrowed(SPEC_CONSTANT_SHADER)),
+                });
+
+            assert_eq!(run_spec_constant_shader(&ctx, &module, &HashMap::new()), 1);
+
+            // The constant can be overridden by its id or by its name.
+            let by_id = HashMap::from([("0".to_string(), 42.0)]);
+            assert_eq!(run_spec_constant_shader(&ctx, &module, &by_id), 42);
+            let by_name = HashMap::from([("value".to_string(), 7.0)]);
+            assert_eq!(run_spec_constant_shader(&ctx, &module, &by_name), 7);
+
+            // Values are checked against the type of the constant.
+            let negative = HashMap::from([("value".to_string(), -1.0)]);
+            fail(&ctx.device, || {
+                ctx.device
+                    .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                        label: None,
+                        layout: None,
+                        module: &module,
+                        entry_point: "main",
+                        constants: &negative,
+                        cache: None,
+                    })
+            });
+        },
+    );
+}
@@ -113,3 +113,18 @@ fn wait_out_of_order() {
         ctx.device.poll(Maintain::WaitForSubmissionIndex(index1));
     })
 }
+
+#[test]
+#[wasm_bindgen_test]
+fn wait_on_submission_with_timeout() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let cmd_buf = generate_dummy_work(&ctx);
+
+        let index = ctx.queue.submit(Some(cmd_buf));
+        let queue_empty = ctx.device.poll(Maintain::WaitForSubmissionIndexWithTimeout(
+            index,
+            std::time::Duration::from_secs(10),
+        ));
+        assert!(queue_empty);
+    })
+}
@@ -0,0 +1,64 @@
+use wgpu::util::ProfileScopeExt;
+
+use wgpu_test::{initialize_test, TestParameters};
+
+#[test]
+fn profiler_scopes_without_timestamps() {
+    // No `TIMESTAMP_QUERY` is requested, so scopes are recorded but not timed.
+    initialize_test(TestParameters::default(), |ctx| {
+        let mut profiler = wgpu::util::GpuProfiler::new(&ctx.device, &ctx.queue, 16);
+
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        let value = encoder.profile_scope(&mut profiler, "frame", |scope| {
+            scope.scope("shadow pass", |_| ());
+            scope.scope("main pass", |scope| {
+                scope.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+                42
+            })
+        });
+        assert_eq!(value, 42);
+        profiler.resolve_queries(&mut encoder);
+        ctx.queue.submit(Some(encoder.finish()));
+        profiler.end_frame(&ctx.device);
+
+        ctx.device.poll(wgpu::Maintain::Wait);
+        let report = profiler.process_finished_frame().unwrap();
+        assert!(profiler.process_finished_frame().is_none());
+
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].label, "frame");
+        assert_eq!(report[0].time, None);
+        let nested: Vec<_> = report[0]
+            .nested_scopes
+            .iter()
+            .map(|scope| scope.label.as_str())
+            .collect();
+        assert_eq!(nested, ["shadow pass", "main pass"]);
+        assert_eq!(
+            wgpu::util::chrome_trace_json(&report),
+            "{\"traceEvents\":[]}"
+        );
+    })
+}
+
+#[test]
+fn profiler_chrome_trace() {
+    let timed = wgpu::util::GpuTimerScopeResult {
+        label: "\"frame\"".to_string(),
+        time: Some(0.5..0.75),
+        nested_scopes: vec![wgpu::util::GpuTimerScopeResult {
+            label: "pass".to_string(),
+            time: Some(0.5..0.5625),
+            nested_scopes: Vec::new(),
+        }],
+    };
+    assert_eq!(
+        wgpu::util::chrome_trace_json(&[timed]),
+        "{\"traceEvents\":[\
+            {\"name\":\"\\\"frame\\\"\",\"ph\":\"X\",\"ts\":500000,\"dur\":250000,\"pid\":0,\"tid\":0},\
+            {\"name\":\"pass\",\"ph\":\"X\",\"ts\":500000,\"dur\":62500,\"pid\":0,\"tid\":0}\
+        ]}"
+    );
+}
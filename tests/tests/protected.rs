@@ -0,0 +1,199 @@
+//! Tests for protected command encoders.
+
+use wgpu_test::{fail, initialize_test, valid, TestParameters, TestingContext};
+
+fn protected_parameters() -> TestParameters {
+    TestParameters::default().features(wgpu::Features::PROTECTED_CONTENT)
+}
+
+fn create_encoder(ctx: &TestingContext, protected: bool) -> wgpu::CommandEncoder {
+    ctx.device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: None,
+            reusable: false,
+            protected,
+        })
+}
+
+fn create_buffer(ctx: &TestingContext, usage: wgpu::BufferUsages) -> wgpu::Buffer {
+    ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 256,
+        usage,
+        mapped_at_creation: false,
+    })
+}
+
+fn create_target(ctx: &TestingContext, usage: wgpu::TextureUsages) -> wgpu::TextureView {
+    ctx.device
+        .create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | usage,
+            view_formats: &[],
+        })
+        .create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+fn clear_target(encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) {
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: None,
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            depth_slice: None,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: true,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+}
+
+#[test]
+fn protected_encoder_requires_feature() {
+    initialize_test(TestParameters::default(), |ctx| {
+        fail(&ctx.device, || create_encoder(&ctx, true));
+    })
+}
+
+#[test]
+fn protected_copy_to_protected_buffer() {
+    initialize_test(protected_parameters(), |ctx| {
+        let src = create_buffer(&ctx, wgpu::BufferUsages::COPY_SRC);
+        let dst = create_buffer(
+            &ctx,
+            wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::PROTECTED,
+        );
+
+        valid(&ctx.device, || {
+            let mut encoder = create_encoder(&ctx, true);
+            encoder.copy_buffer_to_buffer(&src, 0, &dst, 0, 256);
+            ctx.queue.submit(Some(encoder.finish()));
+        });
+    })
+}
+
+#[test]
+fn protected_copy_to_unprotected_buffer() {
+    initialize_test(protected_parameters(), |ctx| {
+        let src = create_buffer(&ctx, wgpu::BufferUsages::COPY_SRC);
+        let dst = create_buffer(&ctx, wgpu::BufferUsages::COPY_DST);
+
+        fail(&ctx.device, || {
+            let mut encoder = create_encoder(&ctx, true);
+            encoder.copy_buffer_to_buffer(&src, 0, &dst, 0, 256);
+            encoder.finish();
+        });
+    })
+}
+
+#[test]
+fn protected_clear_of_unprotected_buffer() {
+    initialize_test(protected_parameters(), |ctx| {
+        let dst = create_buffer(&ctx, wgpu::BufferUsages::COPY_DST);
+
+        fail(&ctx.device, || {
+            let mut encoder = create_encoder(&ctx, true);
+            encoder.clear_buffer(&dst, 0, None);
+            encoder.finish();
+        });
+    })
+}
+
+#[test]
+fn protected_render_to_unprotected_attachment() {
+    initialize_test(protected_parameters(), |ctx| {
+        let protected_view = create_target(&ctx, wgpu::TextureUsages::PROTECTED);
+        let view = create_target(&ctx, wgpu::TextureUsages::empty());
+
+        valid(&ctx.device, || {
+            let mut encoder = create_encoder(&ctx, true);
+            clear_target(&mut encoder, &protected_view);
+            encoder.finish();
+        });
+        fail(&ctx.device, || {
+            let mut encoder = create_encoder(&ctx, true);
+            clear_target(&mut encoder, &view);
+            encoder.finish();
+        });
+    })
+}
+
+#[test]
+fn protected_storage_write_to_unprotected_buffer() {
+    initialize_test(
+        protected_parameters().downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            let module = ctx
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(
+                        "@group(0) @binding(0) var<storage, read_write> output: array<u32>;
+                        @compute @workgroup_size(1) fn main() { output[0] = 1u; }"
+                            .into(),
+                    ),
+                });
+            let pipeline = ctx
+                .device
+                .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: None,
+                    layout: None,
+                    module: &module,
+                    entry_point: "main",
+                    constants: &Default::default(),
+                    cache: None,
+                });
+            let output = create_buffer(&ctx, wgpu::BufferUsages::STORAGE);
+            let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout: &pipeline.get_bind_group_layout(0),
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: output.as_entire_binding(),
+                }],
+            });
+
+            fail(&ctx.device, || {
+                let mut encoder = create_encoder(&ctx, true);
+                {
+                    let mut pass =
+                        encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+                    pass.set_pipeline(&pipeline);
+                    pass.set_bind_group(0, &bind_group, &[]);
+                    pass.dispatch_workgroups(1, 1, 1);
+                }
+                encoder.finish();
+            });
+        },
+    )
+}
+
+#[test]
+fn unprotected_copy_from_protected_buffer() {
+    initialize_test(protected_parameters(), |ctx| {
+        let src = create_buffer(
+            &ctx,
+            wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::PROTECTED,
+        );
+        let dst = create_buffer(&ctx, wgpu::BufferUsages::COPY_DST);
+
+        fail(&ctx.device, || {
+            let mut encoder = create_encoder(&ctx, false);
+            encoder.copy_buffer_to_buffer(&src, 0, &dst, 0, 256);
+            encoder.finish();
+        });
+    })
+}
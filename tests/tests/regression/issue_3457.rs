@@ -54,6 +54,7 @@ fn pass_reset_vertex_buffer() {
                 vertex: VertexState {
                     module: &module,
                     entry_point: "double_buffer_vert",
+                    constants: &Default::default(),
                     buffers: &[
                         VertexBufferLayout {
                             array_stride: 16,
@@ -73,6 +74,7 @@ fn pass_reset_vertex_buffer() {
                 fragment: Some(FragmentState {
                     module: &module,
                     entry_point: "double_buffer_frag",
+                    constants: &Default::default(),
                     targets: &[Some(ColorTargetState {
                         format: TextureFormat::Rgba8Unorm,
                         blend: None,
@@ -80,6 +82,7 @@ fn pass_reset_vertex_buffer() {
                     })],
                 }),
                 multiview: None,
+                cache: None,
             });
 
         let single_pipeline = ctx
@@ -90,6 +93,7 @@ fn pass_reset_vertex_buffer() {
                 vertex: VertexState {
                     module: &module,
                     entry_point: "single_buffer_vert",
+                    constants: &Default::default(),
                     buffers: &[VertexBufferLayout {
                         array_stride: 16,
                         step_mode: VertexStepMode::Vertex,
@@ -102,6 +106,7 @@ fn pass_reset_vertex_buffer() {
                 fragment: Some(FragmentState {
                     module: &module,
                     entry_point: "single_buffer_frag",
+                    constants: &Default::default(),
                     targets: &[Some(ColorTargetState {
                         format: TextureFormat::Rgba8Unorm,
                         blend: None,
@@ -109,6 +114,7 @@ fn pass_reset_vertex_buffer() {
                     })],
                 }),
                 multiview: None,
+                cache: None,
             });
 
         let view = ctx
@@ -137,6 +143,7 @@ fn pass_reset_vertex_buffer() {
             label: Some("double renderpass"),
             color_attachments: &[Some(RenderPassColorAttachment {
                 view: &view,
+                depth_slice: None,
                 resolve_target: None,
                 ops: Operations {
                     load: LoadOp::Clear(Color::BLACK),
@@ -172,6 +179,7 @@ fn pass_reset_vertex_buffer() {
             label: Some("single renderpass"),
             color_attachments: &[Some(RenderPassColorAttachment {
                 view: &view,
+                depth_slice: None,
                 resolve_target: None,
                 ops: Operations {
                     load: LoadOp::Clear(Color::BLACK),
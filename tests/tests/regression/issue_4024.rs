@@ -31,6 +31,8 @@ fn queue_submitted_callback_ordering() {
             .device
             .create_command_encoder(&CommandEncoderDescriptor {
                 label: Some("encoder"),
+                reusable: false,
+                protected: false,
             });
 
         encoder.clear_buffer(&buffer, 0, None);
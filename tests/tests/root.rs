@@ -5,27 +5,56 @@ mod regression {
     mod issue_4024;
 }
 
+mod additional_queues;
+mod bc_decompression;
+mod bgra8;
 mod bind_group_layout_dedup;
+mod binding_array;
+mod binding_size;
 mod buffer;
 mod buffer_copy;
 mod buffer_usages;
 mod clear_texture;
+mod color_target;
+mod cpu_image_copy;
+mod cross_device;
 mod device;
+mod download_texture;
+mod dual_source_blending;
+mod dynamic_offsets;
+mod dynamic_uniform_buffer;
 mod encoder;
+mod error_scope;
 mod example_wgsl;
 mod external_texture;
+mod generate_mipmaps;
+mod glsl;
+mod hot_reload;
+mod indirect_draw;
 mod instance;
+#[cfg(not(target_arch = "wasm32"))]
+mod nv12_texture;
 mod occlusion_query;
 mod partially_bounded_arrays;
+mod pipeline_cache;
+mod pipeline_constants;
 mod poll;
+mod profiler;
+mod protected;
 mod queue_transfer;
+mod read_only_depth;
 mod resource_descriptor_accessor;
 mod resource_error;
+mod sampler;
 mod scissor_tests;
 mod shader;
 mod shader_primitive_index;
 mod shader_view_format;
+mod shading_rate;
+mod snapshot;
 mod texture_bounds;
+mod texture_swizzle;
+mod tonemap;
 mod transfer;
 mod vertex_indices;
 mod write_texture;
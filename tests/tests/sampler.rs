@@ -0,0 +1,162 @@
+use wasm_bindgen_test::*;
+use wgpu_test::{fail, initialize_test, TestParameters};
+
+const BORDER_COLOR: [f32; 4] = [0.25, 0.5, 0.75, 1.0];
+
+const SHADER_SRC: &str = "
+@group(0) @binding(0)
+var tex: texture_2d<f32>;
+@group(0) @binding(1)
+var samp: sampler;
+@group(0) @binding(2)
+var<storage, read_write> output: vec4<f32>;
+
+@compute @workgroup_size(1)
+fn main() {
+    output = textureSampleLevel(tex, samp, vec2<f32>(2.0, 2.0), 0.0);
+}
+";
+
+fn border_sampler_desc(border_color: wgpu::SamplerBorderColor) -> wgpu::SamplerDescriptor<'static> {
+    wgpu::SamplerDescriptor {
+        address_mode_u: wgpu::AddressMode::ClampToBorder,
+        address_mode_v: wgpu::AddressMode::ClampToBorder,
+        address_mode_w: wgpu::AddressMode::ClampToBorder,
+        border_color: Some(border_color),
+        ..Default::default()
+    }
+}
+
+/// Custom border colors require `ADDRESS_MODE_CLAMP_TO_BORDER_CUSTOM`.
+#[test]
+#[wasm_bindgen_test]
+fn custom_border_color_requires_feature() {
+    initialize_test(
+        TestParameters::default().features(wgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER),
+        |ctx| {
+            fail(&ctx.device, || {
+                ctx.device
+                    .create_sampler(&border_sampler_desc(wgpu::SamplerBorderColor::Custom(
+                        BORDER_COLOR,
+                    )))
+            });
+        },
+    );
+}
+
+/// Sampling outside of the texture returns the custom border color.
+#[test]
+fn custom_border_color_sampled() {
+    initialize_test(
+        TestParameters::default()
+            .features(
+                wgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER
+                    | wgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER_CUSTOM,
+            )
+            .downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            let size = wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            };
+            let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+                label: None,
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            });
+            ctx.queue.write_texture(
+                texture.as_image_copy(),
+                &[0, 0, 0, 0],
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: None,
+                    rows_per_image: None,
+                },
+                size,
+            );
+            let view = texture.create_view(&Default::default());
+            let sampler =
+                ctx.device
+                    .create_sampler(&border_sampler_desc(wgpu::SamplerBorderColor::Custom(
+                        BORDER_COLOR,
+                    )));
+
+            let output = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: 16,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            });
+            let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: 16,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+
+            let module = ctx
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(SHADER_SRC.into()),
+                });
+            let pipeline = ctx
+                .device
+                .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: None,
+                    layout: None,
+                    module: &module,
+                    entry_point: "main",
+                    constants: &Default::default(),
+                    cache: None,
+                });
+            let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout: &pipeline.get_bind_group_layout(0),
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: output.as_entire_binding(),
+                    },
+                ],
+            });
+
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            {
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+                pass.set_pipeline(&pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.dispatch_workgroups(1, 1, 1);
+            }
+            encoder.copy_buffer_to_buffer(&output, 0, &readback, 0, 16);
+            ctx.queue.submit(Some(encoder.finish()));
+
+            readback.slice(..).map_async(wgpu::MapMode::Read, |_| ());
+            ctx.device.poll(wgpu::Maintain::Wait);
+            let data = readback.slice(..).get_mapped_range();
+            let sampled: &[f32] = bytemuck::cast_slice(&data);
+            for (sampled, expected) in sampled.iter().zip(BORDER_COLOR) {
+                assert!(
+                    (sampled - expected).abs() < 0.01,
+                    "expected {BORDER_COLOR:?}, sampled {sampled:?}"
+                );
+            }
+        },
+    );
+}
@@ -0,0 +1,62 @@
+use wasm_bindgen_test::*;
+
+use wgpu_test::{fail, initialize_test, valid, TestParameters};
+
+const VALID_SHADER: &str = "
+@vertex
+fn vs_main() -> @builtin(position) vec4<f32> {
+    return vec4<f32>(0.0);
+}
+";
+
+const INVALID_SHADER: &str = "
+@vertex
+fn vs_main() -> @builtin(position) vec4<f32> {
+    return vec4<f32>(0.0) +;
+}
+";
+
+#[test]
+#[wasm_bindgen_test]
+fn compilation_info_valid_shader() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let module = valid(&ctx.device, || {
+            ctx.device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(VALID_SHADER.into()),
+                })
+        });
+
+        let info = pollster::block_on(module.get_compilation_info());
+        assert!(info
+            .messages
+            .iter()
+            .all(|message| message.message_type != wgpu::CompilationMessageType::Error));
+    });
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn compilation_info_invalid_shader() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let module = fail(&ctx.device, || {
+            ctx.device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(INVALID_SHADER.into()),
+                })
+        });
+
+        let info = pollster::block_on(module.get_compilation_info());
+        let error = info
+            .messages
+            .iter()
+            .find(|message| message.message_type == wgpu::CompilationMessageType::Error)
+            .expect("expected an error message");
+        let location = error
+            .location
+            .expect("expected the error to have a location");
+        assert_eq!(location.line_number, 4);
+    });
+}
@@ -15,8 +15,10 @@ use wgpu::{
 
 use wgpu_test::TestingContext;
 
+mod compilation_info;
 mod numeric_builtins;
 mod struct_layout;
+mod workgroup_storage;
 mod zero_init_workgroup_mem;
 
 #[derive(Clone, Copy, PartialEq)]
@@ -297,6 +299,8 @@ fn shader_input_output_test(
                 layout: Some(&pll),
                 module: &sm,
                 entry_point: "cs_main",
+                constants: &Default::default(),
+                cache: None,
             });
 
         // -- Initializing data --
@@ -322,7 +326,11 @@ fn shader_input_output_test(
 
         let mut encoder = ctx
             .device
-            .create_command_encoder(&CommandEncoderDescriptor { label: None });
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: None,
+                reusable: false,
+                protected: false,
+            });
 
         let mut cpass = encoder.begin_compute_pass(&ComputePassDescriptor {
             label: Some(&format!("cpass {test_name}")),
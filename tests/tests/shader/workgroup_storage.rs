@@ -0,0 +1,72 @@
+//! Tests for validation of the workgroup storage used by compute entry points.
+
+use wgpu::{ComputePipelineDescriptor, DownlevelFlags, ShaderModuleDescriptor, ShaderSource};
+
+use wgpu_test::{fail, initialize_test, valid, TestParameters, TestingContext};
+
+/// Creates a compute pipeline whose entry point uses each of the workgroup `arrays`,
+/// given as `(name, element count)` pairs of `u32` arrays.
+fn create_pipeline(ctx: &TestingContext, arrays: &[(&str, u32)]) {
+    let mut source = String::new();
+    for &(name, count) in arrays {
+        source += &format!("var<workgroup> {name}: array<u32, {count}>;\n");
+    }
+    source += "@compute @workgroup_size(1) fn main() {\n";
+    for &(name, _) in arrays {
+        source += &format!("    {name}[0] = 1u;\n");
+    }
+    source += "}\n";
+
+    let module = ctx.device.create_shader_module(ShaderModuleDescriptor {
+        label: None,
+        source: ShaderSource::Wgsl(source.into()),
+    });
+    ctx.device
+        .create_compute_pipeline(&ComputePipelineDescriptor {
+            label: None,
+            layout: None,
+            module: &module,
+            entry_point: "main",
+            constants: &Default::default(),
+            cache: None,
+        });
+}
+
+#[test]
+fn workgroup_storage_within_limit() {
+    initialize_test(
+        TestParameters::default().downlevel_flags(DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            let limit = ctx.device.limits().max_compute_workgroup_storage_size;
+            valid(&ctx.device, || create_pipeline(&ctx, &[("a", limit / 4)]));
+        },
+    );
+}
+
+#[test]
+fn workgroup_storage_above_limit() {
+    initialize_test(
+        TestParameters::default().downlevel_flags(DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            let limit = ctx.device.limits().max_compute_workgroup_storage_size;
+            // Each variable is rounded up to 16 bytes, so two of them only fit
+            // when the limit leaves room for the padding.
+            fail(&ctx.device, || {
+                create_pipeline(&ctx, &[("a", limit / 4 - 3), ("b", 1)])
+            });
+        },
+    );
+}
+
+#[test]
+fn workgroup_storage_size_overflow() {
+    initialize_test(
+        TestParameters::default().downlevel_flags(DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            // Each array is 2^31 bytes, so together they don't fit in a `u32`.
+            fail(&ctx.device, || {
+                create_pipeline(&ctx, &[("a", 1 << 29), ("b", 1 << 29)])
+            });
+        },
+    );
+}
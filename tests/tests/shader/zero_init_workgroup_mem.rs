@@ -4,8 +4,8 @@ use wgpu::{
     include_wgsl, Backends, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor,
     BindGroupLayoutEntry, BindingResource, BindingType, BufferBinding, BufferBindingType,
     BufferDescriptor, BufferUsages, CommandEncoderDescriptor, ComputePassDescriptor,
-    ComputePipelineDescriptor, DownlevelFlags, Limits, Maintain, MapMode, PipelineLayoutDescriptor,
-    ShaderStages,
+    ComputePipelineDescriptor, DownlevelFlags, Features, Limits, Maintain, MapMode,
+    PipelineLayoutDescriptor, ShaderModuleDescriptor, ShaderSource, ShaderStages,
 };
 
 use wgpu_test::{initialize_test, TestParameters, TestingContext};
@@ -106,6 +106,8 @@ fn zero_init_workgroup_mem_impl(ctx: TestingContext) {
             layout: Some(&pll),
             module: &sm,
             entry_point: "read",
+            constants: &Default::default(),
+            cache: None,
         });
 
     let pipeline_write = ctx
@@ -115,6 +117,8 @@ fn zero_init_workgroup_mem_impl(ctx: TestingContext) {
             layout: None,
             module: &sm,
             entry_point: "write",
+            constants: &Default::default(),
+            cache: None,
         });
 
     // -- Initializing data --
@@ -172,3 +176,87 @@ fn zero_init_workgroup_mem_impl(ctx: TestingContext) {
     drop(mapped);
     mapping_buffer.unmap();
 }
+
+#[test]
+fn uninitialized_workgroup_mem() {
+    initialize_test(
+        TestParameters::default()
+            .features(Features::UNINITIALIZED_WORKGROUP_MEMORY)
+            .downlevel_flags(DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            // Workgroup memory that is written before it's read still works without
+            // zero-initialization.
+            let sm = ctx.device.create_shader_module(ShaderModuleDescriptor {
+                label: None,
+                source: ShaderSource::Wgsl(
+                    "@group(0) @binding(0) var<storage, read_write> output: array<u32>;
+                    var<workgroup> values: array<u32, 64>;
+
+                    @compute @workgroup_size(64)
+                    fn main(
+                        @builtin(local_invocation_index) index: u32,
+                        @builtin(workgroup_id) workgroup: vec3<u32>,
+                    ) {
+                        values[index] = workgroup.x * 64u + index;
+                        workgroupBarrier();
+                        output[workgroup.x * 64u + index] = values[63u - index];
+                    }"
+                    .into(),
+                ),
+            });
+            let pipeline = ctx
+                .device
+                .create_compute_pipeline(&ComputePipelineDescriptor {
+                    label: None,
+                    layout: None,
+                    module: &sm,
+                    entry_point: "main",
+                    constants: &Default::default(),
+                    cache: None,
+                });
+
+            let size = 4 * 64 * 4;
+            let output_buffer = ctx.device.create_buffer(&BufferDescriptor {
+                label: Some("output buffer"),
+                size,
+                usage: BufferUsages::COPY_SRC | BufferUsages::STORAGE,
+                mapped_at_creation: false,
+            });
+            let mapping_buffer = ctx.device.create_buffer(&BufferDescriptor {
+                label: Some("mapping buffer"),
+                size,
+                usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            });
+            let bg = ctx.device.create_bind_group(&BindGroupDescriptor {
+                label: None,
+                layout: &pipeline.get_bind_group_layout(0),
+                entries: &[BindGroupEntry {
+                    binding: 0,
+                    resource: output_buffer.as_entire_binding(),
+                }],
+            });
+
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&CommandEncoderDescriptor::default());
+            let mut cpass = encoder.begin_compute_pass(&ComputePassDescriptor::default());
+            cpass.set_pipeline(&pipeline);
+            cpass.set_bind_group(0, &bg, &[]);
+            cpass.dispatch_workgroups(4, 1, 1);
+            drop(cpass);
+            encoder.copy_buffer_to_buffer(&output_buffer, 0, &mapping_buffer, 0, size);
+            ctx.queue.submit(Some(encoder.finish()));
+
+            mapping_buffer.slice(..).map_async(MapMode::Read, |_| ());
+            ctx.device.poll(Maintain::Wait);
+
+            let mapped = mapping_buffer.slice(..).get_mapped_range();
+            let typed: &[u32] = bytemuck::cast_slice(&mapped);
+            for (i, &value) in typed.iter().enumerate() {
+                let (workgroup, index) = (i as u32 / 64, i as u32 % 64);
+                assert_eq!(value, workgroup * 64 + 63 - index, "invocation {i}");
+            }
+        },
+    );
+}
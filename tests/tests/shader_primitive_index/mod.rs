@@ -133,6 +133,7 @@ fn pulling_common(
                     }],
                 }],
                 entry_point: "vs_main",
+                constants: &Default::default(),
                 module: &shader,
             },
             primitive: wgpu::PrimitiveState::default(),
@@ -140,6 +141,7 @@ fn pulling_common(
             multisample: wgpu::MultisampleState::default(),
             fragment: Some(wgpu::FragmentState {
                 entry_point: "fs_main",
+                constants: &Default::default(),
                 module: &shader,
                 targets: &[Some(wgpu::ColorTargetState {
                     format: wgpu::TextureFormat::Rgba8Unorm,
@@ -148,6 +150,7 @@ fn pulling_common(
                 })],
             }),
             multiview: None,
+            cache: None,
         });
 
     let width = 2;
@@ -184,6 +187,7 @@ fn pulling_common(
                 },
                 resolve_target: None,
                 view: &color_view,
+                depth_slice: None,
             })],
             depth_stencil_attachment: None,
             timestamp_writes: None,
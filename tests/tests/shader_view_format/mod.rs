@@ -90,11 +90,13 @@ fn reinterpret(
             vertex: wgpu::VertexState {
                 module: shader,
                 entry_point: "vs_main",
+                constants: &Default::default(),
                 buffers: &[],
             },
             fragment: Some(wgpu::FragmentState {
                 module: shader,
                 entry_point: "fs_main",
+                constants: &Default::default(),
                 targets: &[Some(src_format.into())],
             }),
             primitive: wgpu::PrimitiveState {
@@ -104,6 +106,7 @@ fn reinterpret(
             depth_stencil: None,
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
+            cache: None,
         });
     let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
         layout: &pipeline.get_bind_group_layout(0),
@@ -135,6 +138,7 @@ fn reinterpret(
             ops: wgpu::Operations::default(),
             resolve_target: None,
             view: &target_view,
+            depth_slice: None,
         })],
         depth_stencil_attachment: None,
         timestamp_writes: None,
@@ -155,7 +159,11 @@ fn reinterpret(
 
     let mut encoder = ctx
         .device
-        .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: None,
+            reusable: false,
+            protected: false,
+        });
     encoder.copy_texture_to_buffer(
         wgpu::ImageCopyTexture {
             texture: &target_tex,
@@ -0,0 +1,79 @@
+//! Tests for `RenderPass::set_shading_rate`.
+
+use wgpu_test::{fail, initialize_test, valid, TestParameters, TestingContext};
+
+/// Records a render pass that sets each of `rates` in turn, and finishes the encoder.
+fn set_shading_rates(ctx: &TestingContext, rates: &[wgpu::ShadingRate]) {
+    let view = ctx
+        .device
+        .create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        })
+        .create_view(&wgpu::TextureViewDescriptor::default());
+
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: wgpu::Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        for &rate in rates {
+            pass.set_shading_rate(rate);
+        }
+    }
+    ctx.queue.submit(Some(encoder.finish()));
+}
+
+#[test]
+fn set_shading_rate_requires_feature() {
+    initialize_test(TestParameters::default(), |ctx| {
+        // Even the default rate needs the feature.
+        fail(&ctx.device, || {
+            set_shading_rates(&ctx, &[wgpu::ShadingRate::Rate1x1])
+        });
+        fail(&ctx.device, || {
+            set_shading_rates(&ctx, &[wgpu::ShadingRate::Rate2x2])
+        });
+    })
+}
+
+#[test]
+fn set_shading_rate_with_feature() {
+    initialize_test(
+        TestParameters::default().features(wgpu::Features::SHADING_RATE),
+        |ctx| {
+            valid(&ctx.device, || {
+                set_shading_rates(
+                    &ctx,
+                    &[
+                        wgpu::ShadingRate::Rate1x2,
+                        wgpu::ShadingRate::Rate2x1,
+                        wgpu::ShadingRate::Rate2x2,
+                        wgpu::ShadingRate::Rate1x1,
+                    ],
+                )
+            });
+        },
+    )
+}
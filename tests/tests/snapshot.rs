@@ -0,0 +1,207 @@
+//! Tests for `Device::snapshot` and `Device::restore`.
+
+use std::sync::{Arc, Mutex};
+
+use wgpu::util::{DeviceExt, DownloadBuffer, DownloadTexture};
+use wgpu_test::{initialize_test, TestParameters};
+
+#[test]
+fn restore_overwritten_resources() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let buffer_data: Vec<u8> = (0..64).collect();
+        let buffer = ctx
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: &buffer_data,
+                usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+            });
+
+        let size = wgpu::Extent3d {
+            width: 3,
+            height: 2,
+            depth_or_array_layers: 1,
+        };
+        let texture_data: Vec<u8> = (0..3 * 2 * 4).map(|i| i as u8 + 100).collect();
+        let texture = ctx.device.create_texture_with_data(
+            &ctx.queue,
+            &wgpu::TextureDescriptor {
+                label: None,
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            },
+            &texture_data,
+        );
+
+        let snapshot = ctx.device.snapshot().unwrap();
+        assert_eq!(snapshot.buffers.len(), 1);
+        assert_eq!(snapshot.buffers[0].data, buffer_data);
+        assert_eq!(snapshot.textures.len(), 1);
+        assert_eq!(snapshot.textures[0].subresources[0].data, texture_data);
+
+        ctx.queue.write_buffer(&buffer, 0, &[0; 64]);
+        ctx.queue.write_texture(
+            texture.as_image_copy(),
+            &[0; 3 * 2 * 4],
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(3 * 4),
+                rows_per_image: None,
+            },
+            size,
+        );
+        ctx.device.restore(&snapshot).unwrap();
+
+        let result = Arc::new(Mutex::new(None));
+        let result_clone = result.clone();
+        DownloadBuffer::read_buffer(
+            &ctx.device,
+            &ctx.queue,
+            &buffer.slice(..),
+            move |download| {
+                *result_clone.lock().unwrap() = Some(download.unwrap().to_vec());
+            },
+        );
+        ctx.device.poll(wgpu::Maintain::Wait);
+        assert_eq!(result.lock().unwrap().take().unwrap(), buffer_data);
+
+        let result = Arc::new(Mutex::new(None));
+        let result_clone = result.clone();
+        DownloadTexture::read_texture(
+            &ctx.device,
+            &ctx.queue,
+            texture.as_image_copy(),
+            size,
+            move |download| {
+                *result_clone.lock().unwrap() = Some(download.unwrap().to_vec());
+            },
+        );
+        ctx.device.poll(wgpu::Maintain::Wait);
+        assert_eq!(result.lock().unwrap().take().unwrap(), texture_data);
+    });
+}
+
+#[test]
+fn snapshot_skips_depth_aspect() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let _texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            usage: wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+
+        // The depth of `Depth32Float` can be read back, but not written with a copy.
+        let snapshot = ctx.device.snapshot().unwrap();
+        assert_eq!(snapshot.textures.len(), 1);
+        assert!(snapshot.textures[0].subresources.is_empty());
+        assert_eq!(
+            snapshot.textures[0].skipped_aspects,
+            [wgpu::TextureAspect::All]
+        );
+        ctx.device.restore(&snapshot).unwrap();
+    });
+}
+
+#[test]
+fn restore_validates_before_writing() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let usage = wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST;
+        let buffer = ctx
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: &[1; 16],
+                usage,
+            });
+        let destroyed = ctx
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: &[2; 16],
+                usage,
+            });
+
+        let snapshot = ctx.device.snapshot().unwrap();
+        ctx.queue.write_buffer(&buffer, 0, &[3; 16]);
+        destroyed.destroy();
+        assert!(matches!(
+            ctx.device.restore(&snapshot),
+            Err(wgpu::core::device::snapshot::DeviceSnapshotError::InvalidBuffer(_))
+        ));
+
+        let result = Arc::new(Mutex::new(None));
+        let result_clone = result.clone();
+        DownloadBuffer::read_buffer(
+            &ctx.device,
+            &ctx.queue,
+            &buffer.slice(..),
+            move |download| {
+                *result_clone.lock().unwrap() = Some(download.unwrap().to_vec());
+            },
+        );
+        ctx.device.poll(wgpu::Maintain::Wait);
+        assert_eq!(result.lock().unwrap().take().unwrap(), [3; 16]);
+    });
+}
+
+#[test]
+fn snapshot_pipeline_descriptors() {
+    use wgpu::core::device::snapshot::ShaderSourceSnapshot;
+
+    initialize_test(TestParameters::default(), |ctx| {
+        let source = "@compute @workgroup_size(1) fn main() {}";
+        let module = ctx
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: None,
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+        let _pipeline = ctx
+            .device
+            .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("pipeline"),
+                layout: None,
+                module: &module,
+                entry_point: "main",
+                constants: &Default::default(),
+                cache: None,
+            });
+
+        let pipelines = ctx.device.snapshot().unwrap().pipelines;
+        assert_eq!(pipelines.shader_modules.len(), 1);
+        assert!(matches!(
+            pipelines.shader_modules[0].source,
+            ShaderSourceSnapshot::Wgsl(ref code) if code == source
+        ));
+        assert_eq!(pipelines.compute_pipelines.len(), 1);
+        assert!(pipelines.render_pipelines.is_empty());
+
+        // The implicit layout is captured as well.
+        let (_, desc) = &pipelines.compute_pipelines[0];
+        assert_eq!(desc.label.as_deref(), Some("pipeline"));
+        assert_eq!(desc.stage.module, pipelines.shader_modules[0].id);
+        assert_eq!(desc.stage.entry_point, "main");
+        let (_, layout) = pipelines
+            .pipeline_layouts
+            .iter()
+            .find(|&&(id, _)| Some(id) == desc.layout)
+            .unwrap();
+        assert_eq!(layout.bind_group_layouts.len(), 0);
+    });
+}
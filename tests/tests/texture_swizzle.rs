@@ -0,0 +1,212 @@
+use wgpu::util::DeviceExt;
+use wgpu_test::{fail, initialize_test, TestParameters, TestingContext};
+
+const SHADER_SRC: &str = "
+@group(0) @binding(0)
+var texture: texture_2d<f32>;
+@group(0) @binding(1)
+var<storage, read_write> output: vec4<f32>;
+
+@compute @workgroup_size(1)
+fn main() {
+    output = textureLoad(texture, vec2<i32>(0), 0);
+}
+";
+
+const SWIZZLE: wgpu::TextureSwizzle = wgpu::TextureSwizzle {
+    r: wgpu::SwizzleComponent::B,
+    g: wgpu::SwizzleComponent::Zero,
+    b: wgpu::SwizzleComponent::One,
+    a: wgpu::SwizzleComponent::R,
+};
+
+fn create_texture(
+    ctx: &TestingContext,
+    format: wgpu::TextureFormat,
+    usage: wgpu::TextureUsages,
+) -> wgpu::Texture {
+    ctx.device.create_texture_with_data(
+        &ctx.queue,
+        &wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage,
+            view_formats: &[],
+        },
+        &[51, 102, 153, 204],
+    )
+}
+
+/// Reads the texel of a 1x1 texture through a view with `swizzle`.
+fn load_texel(
+    ctx: &TestingContext,
+    texture: &wgpu::Texture,
+    swizzle: Option<wgpu::TextureSwizzle>,
+) -> Vec<f32> {
+    let view = texture.create_view(&wgpu::TextureViewDescriptor {
+        swizzle,
+        ..Default::default()
+    });
+
+    let module = ctx
+        .device
+        .create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(SHADER_SRC.into()),
+        });
+    let pipeline = ctx
+        .device
+        .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: None,
+            module: &module,
+            entry_point: "main",
+            constants: &Default::default(),
+            cache: None,
+        });
+
+    let output = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 16,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 16,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: output.as_entire_binding(),
+            },
+        ],
+    });
+
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    {
+        let mut pass = encoder.begin_compute_pass(&Default::default());
+        pass.set_pipeline(&pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(1, 1, 1);
+    }
+    encoder.copy_buffer_to_buffer(&output, 0, &readback, 0, 16);
+    ctx.queue.submit(Some(encoder.finish()));
+
+    readback
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, Result::unwrap);
+    ctx.device.poll(wgpu::Maintain::Wait);
+
+    let data = readback.slice(..).get_mapped_range();
+    bytemuck::cast_slice(&data).to_vec()
+}
+
+fn assert_texel(values: &[f32], expected: [f32; 4]) {
+    for (value, component) in values.iter().zip(expected) {
+        assert!(
+            (value - component).abs() < 0.01,
+            "read {values:?}, expected {expected:?}"
+        );
+    }
+}
+
+#[test]
+fn texture_swizzle_sampled() {
+    initialize_test(
+        TestParameters::default()
+            .features(wgpu::Features::TEXTURE_COMPONENT_SWIZZLE)
+            .downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            let texture = create_texture(
+                &ctx,
+                wgpu::TextureFormat::Rgba8Unorm,
+                wgpu::TextureUsages::TEXTURE_BINDING,
+            );
+            let values = load_texel(&ctx, &texture, Some(SWIZZLE));
+            assert_texel(&values, [0.6, 0.0, 1.0, 0.2]);
+        },
+    );
+}
+
+#[test]
+fn texture_swizzle_bgra8() {
+    initialize_test(
+        TestParameters::default()
+            .features(wgpu::Features::TEXTURE_COMPONENT_SWIZZLE)
+            .downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS),
+        |ctx| {
+            // The view swizzle picks from the channels as the shader sees them, in RGBA order,
+            // even where the texels are stored in BGRA order.
+            let texture = create_texture(
+                &ctx,
+                wgpu::TextureFormat::Bgra8Unorm,
+                wgpu::TextureUsages::TEXTURE_BINDING,
+            );
+            let values = load_texel(&ctx, &texture, None);
+            assert_texel(&values, [0.6, 0.4, 0.2, 0.8]);
+            let values = load_texel(&ctx, &texture, Some(SWIZZLE));
+            assert_texel(&values, [0.2, 0.0, 1.0, 0.6]);
+            // Sampling through an unswizzled view again must undo the swizzle of the last bind.
+            let values = load_texel(&ctx, &texture, None);
+            assert_texel(&values, [0.6, 0.4, 0.2, 0.8]);
+        },
+    );
+}
+
+#[test]
+fn texture_swizzle_not_renderable() {
+    initialize_test(
+        TestParameters::default().features(wgpu::Features::TEXTURE_COMPONENT_SWIZZLE),
+        |ctx| {
+            let texture = create_texture(
+                &ctx,
+                wgpu::TextureFormat::Rgba8Unorm,
+                wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+            );
+            let view = texture.create_view(&wgpu::TextureViewDescriptor {
+                swizzle: Some(SWIZZLE),
+                ..Default::default()
+            });
+
+            fail(&ctx.device, || {
+                let mut encoder = ctx
+                    .device
+                    .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+                encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: None,
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &view,
+                        depth_slice: None,
+                        resolve_target: None,
+                        ops: wgpu::Operations::default(),
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+                encoder.finish()
+            });
+        },
+    );
+}
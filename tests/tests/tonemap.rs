@@ -0,0 +1,143 @@
+//! Tests for `wgpu::util::Tonemapper`.
+
+use std::sync::{Arc, Mutex};
+
+use wgpu::util::{
+    DeviceExt, DownloadBuffer, OutputTransfer, TonemapOperator, TonemapParams, Tonemapper,
+};
+use wgpu_test::{initialize_test, TestParameters};
+
+const SIZE: wgpu::Extent3d = wgpu::Extent3d {
+    width: 4,
+    height: 4,
+    depth_or_array_layers: 1,
+};
+
+/// Tonemaps a texture filled with `(2.0, 0.5, 0.0, 1.0)` to `format` and returns the first texel.
+fn tonemap(
+    ctx: &wgpu_test::TestingContext,
+    format: wgpu::TextureFormat,
+    params: TonemapParams,
+) -> [u8; 4] {
+    // The color as f16 bits.
+    let texel: [u16; 4] = [0x4000, 0x3800, 0x0000, 0x3C00];
+    let data: Vec<u8> = texel
+        .iter()
+        .cycle()
+        .take(4 * (SIZE.width * SIZE.height) as usize)
+        .flat_map(|bits| bits.to_ne_bytes())
+        .collect();
+    let hdr = ctx.device.create_texture_with_data(
+        &ctx.queue,
+        &wgpu::TextureDescriptor {
+            label: None,
+            size: SIZE,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba16Float,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        },
+        &data,
+    );
+    let output = ctx.device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: SIZE,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+
+    let tonemapper = Tonemapper::new(&ctx.device, format, params);
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    tonemapper.render(
+        &ctx.device,
+        &mut encoder,
+        &hdr.create_view(&Default::default()),
+        &output.create_view(&Default::default()),
+    );
+    // Only the first row is read back.
+    let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    encoder.copy_texture_to_buffer(
+        output.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &readback,
+            layout: wgpu::ImageDataLayout::default(),
+        },
+        wgpu::Extent3d {
+            width: SIZE.width,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+    );
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let result = Arc::new(Mutex::new(None));
+    let result_clone = result.clone();
+    DownloadBuffer::read_buffer(
+        &ctx.device,
+        &ctx.queue,
+        &readback.slice(..),
+        move |download| {
+            *result_clone.lock().unwrap() = Some(download.unwrap().to_vec());
+        },
+    );
+    ctx.device.poll(wgpu::Maintain::Wait);
+    let download = result.lock().unwrap().take().unwrap();
+    download[..4].try_into().unwrap()
+}
+
+fn assert_close(actual: [u8; 4], expected: [u8; 4]) {
+    for (a, e) in actual.iter().zip(expected) {
+        assert!(a.abs_diff(e) <= 1, "{actual:?} != {expected:?}");
+    }
+}
+
+#[test]
+fn tonemap_to_8bit_formats() {
+    initialize_test(TestParameters::default(), |ctx| {
+        // Reinhard maps 2.0 to 2/3 and 0.5 to 1/3.
+        let reinhard = TonemapParams {
+            operator: TonemapOperator::Reinhard,
+            transfer: OutputTransfer::Linear,
+            ..Default::default()
+        };
+        assert_close(
+            tonemap(&ctx, wgpu::TextureFormat::Rgba8Unorm, reinhard),
+            [170, 85, 0, 255],
+        );
+
+        // Without tonemapping, 2.0 is clipped.
+        let clipped = TonemapParams {
+            transfer: OutputTransfer::Linear,
+            ..Default::default()
+        };
+        assert_close(
+            tonemap(&ctx, wgpu::TextureFormat::Rgba8Unorm, clipped),
+            [255, 128, 0, 255],
+        );
+
+        // sRGB is encoded by the shader or by the format, with the same result.
+        let srgb = TonemapParams {
+            operator: TonemapOperator::Reinhard,
+            ..Default::default()
+        };
+        let encoded = tonemap(&ctx, wgpu::TextureFormat::Rgba8Unorm, srgb);
+        assert_close(encoded, [213, 156, 0, 255]);
+        assert_close(
+            tonemap(&ctx, wgpu::TextureFormat::Rgba8UnormSrgb, srgb),
+            encoded,
+        );
+    });
+}
@@ -1,4 +1,4 @@
-use wgpu_test::{fail, initialize_test, TestParameters};
+use wgpu_test::{fail, initialize_test, valid, TestParameters};
 
 #[test]
 fn copy_overflow_z() {
@@ -67,3 +67,74 @@ fn copy_overflow_z() {
         });
     })
 }
+
+#[test]
+fn copy_between_srgb_compatible_formats() {
+    // Formats which only differ in srgb-ness are copy-compatible, and the copy
+    // reinterprets the texels without conversion.
+    initialize_test(TestParameters::default(), |ctx| {
+        let size = wgpu::Extent3d {
+            width: 64,
+            height: 1,
+            depth_or_array_layers: 1,
+        };
+        let create_texture = |format| {
+            ctx.device.create_texture(&wgpu::TextureDescriptor {
+                label: None,
+                dimension: wgpu::TextureDimension::D2,
+                size,
+                format,
+                usage: wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::COPY_DST,
+                mip_level_count: 1,
+                sample_count: 1,
+                view_formats: &[],
+            })
+        };
+        let src = create_texture(wgpu::TextureFormat::Rgba8UnormSrgb);
+        let dst = create_texture(wgpu::TextureFormat::Rgba8Unorm);
+        let other = create_texture(wgpu::TextureFormat::Bgra8Unorm);
+
+        let data: Vec<u8> = (0..256).map(|i| (i * 7 % 256) as u8).collect();
+        let layout = wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(256),
+            rows_per_image: None,
+        };
+        ctx.queue
+            .write_texture(src.as_image_copy(), &data, layout, size);
+        let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 256,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        valid(&ctx.device, || {
+            encoder.copy_texture_to_texture(src.as_image_copy(), dst.as_image_copy(), size);
+        });
+        encoder.copy_texture_to_buffer(
+            dst.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout,
+            },
+            size,
+        );
+        ctx.queue.submit(Some(encoder.finish()));
+
+        buffer.slice(..).map_async(wgpu::MapMode::Read, |_| ());
+        ctx.device.poll(wgpu::Maintain::Wait);
+        assert_eq!(*buffer.slice(..).get_mapped_range(), *data);
+
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        fail(&ctx.device, || {
+            encoder.copy_texture_to_texture(src.as_image_copy(), other.as_image_copy(), size);
+            encoder.finish();
+        });
+    })
+}
@@ -64,6 +64,7 @@ fn pulling_common(
             vertex: wgpu::VertexState {
                 buffers: &[],
                 entry_point: "vs_main",
+                constants: &Default::default(),
                 module: &shader,
             },
             primitive: wgpu::PrimitiveState::default(),
@@ -71,6 +72,7 @@ fn pulling_common(
             multisample: wgpu::MultisampleState::default(),
             fragment: Some(wgpu::FragmentState {
                 entry_point: "fs_main",
+                constants: &Default::default(),
                 module: &shader,
                 targets: &[Some(wgpu::ColorTargetState {
                     format: wgpu::TextureFormat::Rgba8Unorm,
@@ -79,6 +81,7 @@ fn pulling_common(
                 })],
             }),
             multiview: None,
+            cache: None,
         });
 
     let dummy = ctx
@@ -113,6 +116,7 @@ fn pulling_common(
             ops: wgpu::Operations::default(),
             resolve_target: None,
             view: &dummy,
+            depth_slice: None,
         })],
         depth_stencil_attachment: None,
         timestamp_writes: None,
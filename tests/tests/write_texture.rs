@@ -1,6 +1,6 @@
 //! Tests for texture copy
 
-use wgpu_test::{initialize_test, TestParameters};
+use wgpu_test::{initialize_test, TestParameters, TestingContext};
 
 use wasm_bindgen_test::*;
 
@@ -59,7 +59,11 @@ fn write_texture_subset_2d() {
 
         let mut encoder = ctx
             .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: None,
+                reusable: false,
+                protected: false,
+            });
 
         encoder.copy_texture_to_buffer(
             wgpu::ImageCopyTexture {
@@ -155,7 +159,11 @@ fn write_texture_subset_3d() {
 
         let mut encoder = ctx
             .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: None,
+                reusable: false,
+                protected: false,
+            });
 
         encoder.copy_texture_to_buffer(
             wgpu::ImageCopyTexture {
@@ -194,3 +202,166 @@ fn write_texture_subset_3d() {
         }
     });
 }
+
+/// Clears `tex` to a depth of 0.5 and, if it has one, a stencil value of 7.
+fn clear_depth_stencil(ctx: &TestingContext, tex: &wgpu::Texture) {
+    let view = tex.create_view(&wgpu::TextureViewDescriptor::default());
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: None,
+        color_attachments: &[],
+        depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+            view: &view,
+            depth_ops: Some(wgpu::Operations {
+                load: wgpu::LoadOp::Clear(0.5),
+                store: true,
+            }),
+            stencil_ops: tex
+                .format()
+                .has_stencil_aspect()
+                .then_some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(7),
+                    store: true,
+                }),
+        }),
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+    ctx.queue.submit(Some(encoder.finish()));
+}
+
+/// Reads back one aspect of a 4x4 texture whose texels have `texel_size` bytes in that aspect.
+fn read_aspect(
+    ctx: &TestingContext,
+    tex: &wgpu::Texture,
+    aspect: wgpu::TextureAspect,
+    texel_size: u32,
+) -> Vec<u8> {
+    let read_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 4 * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.copy_texture_to_buffer(
+        wgpu::ImageCopyTexture {
+            texture: tex,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect,
+        },
+        wgpu::ImageCopyBuffer {
+            buffer: &read_buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                rows_per_image: None,
+            },
+        },
+        tex.size(),
+    );
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let slice = read_buffer.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| ());
+    ctx.device.poll(wgpu::Maintain::Wait);
+    let data = slice.get_mapped_range();
+    data.chunks(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize)
+        .flat_map(|row| &row[..(4 * texel_size) as usize])
+        .copied()
+        .collect()
+}
+
+fn create_depth_stencil_texture(
+    ctx: &TestingContext,
+    format: wgpu::TextureFormat,
+) -> wgpu::Texture {
+    ctx.device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        dimension: wgpu::TextureDimension::D2,
+        size: wgpu::Extent3d {
+            width: 4,
+            height: 4,
+            depth_or_array_layers: 1,
+        },
+        format,
+        usage: wgpu::TextureUsages::COPY_DST
+            | wgpu::TextureUsages::COPY_SRC
+            | wgpu::TextureUsages::RENDER_ATTACHMENT,
+        mip_level_count: 1,
+        sample_count: 1,
+        view_formats: &[],
+    })
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn copy_depth_aspect_to_buffer() {
+    let parameters = TestParameters::default()
+        .downlevel_flags(wgpu::DownlevelFlags::DEPTH_TEXTURE_AND_BUFFER_COPIES);
+    initialize_test(parameters, |ctx| {
+        let tex = create_depth_stencil_texture(&ctx, wgpu::TextureFormat::Depth32Float);
+        clear_depth_stencil(&ctx, &tex);
+
+        let depth = read_aspect(&ctx, &tex, wgpu::TextureAspect::DepthOnly, 4);
+        assert_eq!(bytemuck::cast_slice::<u8, f32>(&depth), [0.5; 16]);
+    });
+}
+
+/// Uploads the stencil aspect of a depth-stencil texture, checking that it reads back
+/// and, where the depth aspect can be copied, that the depth is left alone.
+fn write_stencil_aspect(ctx: &TestingContext, format: wgpu::TextureFormat) {
+    let tex = create_depth_stencil_texture(ctx, format);
+    clear_depth_stencil(ctx, &tex);
+
+    let data = (0..16).collect::<Vec<u8>>();
+    ctx.queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture: &tex,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::StencilOnly,
+        },
+        &data,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(4),
+            rows_per_image: None,
+        },
+        tex.size(),
+    );
+    ctx.queue.submit(None);
+
+    let stencil = read_aspect(ctx, &tex, wgpu::TextureAspect::StencilOnly, 1);
+    assert_eq!(stencil, data);
+    if format == wgpu::TextureFormat::Depth32FloatStencil8 {
+        let depth = read_aspect(ctx, &tex, wgpu::TextureAspect::DepthOnly, 4);
+        assert_eq!(bytemuck::cast_slice::<u8, f32>(&depth), [0.5; 16]);
+    }
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn write_texture_stencil_aspect() {
+    let parameters = TestParameters::default()
+        .downlevel_flags(wgpu::DownlevelFlags::DEPTH_TEXTURE_AND_BUFFER_COPIES);
+    initialize_test(parameters, |ctx| {
+        write_stencil_aspect(&ctx, wgpu::TextureFormat::Depth24PlusStencil8);
+    });
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn write_texture_stencil_aspect_d32_s8() {
+    let parameters = TestParameters::default()
+        .downlevel_flags(wgpu::DownlevelFlags::DEPTH_TEXTURE_AND_BUFFER_COPIES)
+        .features(wgpu::Features::DEPTH32FLOAT_STENCIL8);
+    initialize_test(parameters, |ctx| {
+        write_stencil_aspect(&ctx, wgpu::TextureFormat::Depth32FloatStencil8);
+    });
+}
@@ -2,7 +2,10 @@ use crate::{
     device::{DeviceError, MissingDownlevelFlags, MissingFeatures, SHADER_STAGE_COUNT},
     error::{ErrorFormatter, PrettyError},
     hal_api::HalApi,
-    id::{BindGroupLayoutId, BufferId, DeviceId, SamplerId, TextureId, TextureViewId, Valid},
+    id::{
+        AccelerationStructureId, BindGroupLayoutId, BufferId, DeviceId, SamplerId, TextureId,
+        TextureViewId, Valid,
+    },
     init_tracker::{BufferInitTrackerAction, TextureInitTrackerAction},
     resource::Resource,
     track::{BindGroupStates, UsageConflict},
@@ -76,6 +79,8 @@ pub enum CreateBindGroupError {
     InvalidTexture(TextureId),
     #[error("Sampler {0:?} is invalid")]
     InvalidSampler(SamplerId),
+    #[error("Acceleration structure {0:?} is invalid")]
+    InvalidAccelerationStructure(AccelerationStructureId),
     #[error(
         "Binding count declared with at most {expected} items, but {actual} items were provided"
     )]
@@ -157,6 +162,11 @@ pub enum CreateBindGroupError {
     },
     #[error("Storage texture bindings must have a single mip level, but given a view with mip_level_count = {mip_level_count:?} at binding {binding}")]
     InvalidStorageTextureMipLevelCount { binding: u32, mip_level_count: u32 },
+    #[error("Storage texture bindings can't swizzle their components, but given a view with swizzle {swizzle:?} at binding {binding}")]
+    InvalidStorageTextureSwizzle {
+        binding: u32,
+        swizzle: wgt::TextureSwizzle,
+    },
     #[error("Sampler binding {binding} expects comparison = {layout_cmp}, but given a sampler with comparison = {sampler_cmp}")]
     WrongSamplerComparison {
         binding: u32,
@@ -336,6 +346,7 @@ impl BindingTypeMaxCountValidator {
             wgt::BindingType::StorageTexture { .. } => {
                 self.storage_textures.add(binding.visibility, count);
             }
+            wgt::BindingType::AccelerationStructure => {}
         }
     }
 
@@ -436,6 +447,40 @@ pub struct BindGroupLayoutDescriptor<'a> {
 
 pub(crate) type BindEntryMap = FastHashMap<u32, wgt::BindGroupLayoutEntry>;
 
+/// The canonical bind group layouts of a device, keyed by their entries.
+///
+/// Bind group layouts with identical entries are deduplicated: either the
+/// canonical layout's id is handed out again, or the new layout refers to the
+/// canonical one through `compatible_layout`. Only canonical layouts are
+/// stored here, and they are removed once destroyed.
+#[derive(Debug, Default)]
+pub(crate) struct BindGroupLayoutPool {
+    layouts: FastHashMap<Vec<wgt::BindGroupLayoutEntry>, Valid<BindGroupLayoutId>>,
+}
+
+impl BindGroupLayoutPool {
+    fn key(entries: &BindEntryMap) -> Vec<wgt::BindGroupLayoutEntry> {
+        let mut key = entries.values().copied().collect::<Vec<_>>();
+        key.sort_unstable_by_key(|entry| entry.binding);
+        key
+    }
+
+    pub(crate) fn get(&self, entries: &BindEntryMap) -> Option<Valid<BindGroupLayoutId>> {
+        self.layouts.get(&Self::key(entries)).copied()
+    }
+
+    pub(crate) fn insert(&mut self, entries: &BindEntryMap, id: Valid<BindGroupLayoutId>) {
+        self.layouts.entry(Self::key(entries)).or_insert(id);
+    }
+
+    pub(crate) fn remove(&mut self, entries: &BindEntryMap, id: Valid<BindGroupLayoutId>) {
+        let key = Self::key(entries);
+        if self.layouts.get(&key) == Some(&id) {
+            self.layouts.remove(&key);
+        }
+    }
+}
+
 pub type BindGroupLayouts<A> = crate::storage::Storage<BindGroupLayout<A>, BindGroupLayoutId>;
 
 /// Bind group layout.
@@ -719,6 +764,7 @@ pub enum BindingResource<'a> {
     SamplerArray(Cow<'a, [SamplerId]>),
     TextureView(TextureViewId),
     TextureViewArray(Cow<'a, [TextureViewId]>),
+    AccelerationStructure(AccelerationStructureId),
 }
 
 #[derive(Clone, Debug, Error)]
@@ -808,6 +854,9 @@ pub struct BindGroup<A: HalApi> {
     /// Actual binding sizes for buffers that don't have `min_binding_size`
     /// specified in BGL. Listed in the order of iteration of `BGL.entries`.
     pub(crate) late_buffer_binding_sizes: Vec<wgt::BufferSize>,
+    /// If true, the bind group writes to buffers or textures without the
+    /// `PROTECTED` usage, so protected command buffers can't use it.
+    pub(crate) writes_unprotected: bool,
 }
 
 impl<A: HalApi> BindGroup<A> {
@@ -94,6 +94,11 @@ mod compat {
             self.make_range(index)
         }
 
+        /// Returns `true` if the group at `index` is bound to the backend.
+        pub fn is_bound(&self, index: usize) -> bool {
+            self.make_range(index).contains(&index)
+        }
+
         pub fn list_active(&self) -> impl Iterator<Item = usize> + '_ {
             self.entries
                 .iter()
@@ -285,6 +290,35 @@ impl Binder {
         &self.payloads[bind_range]
     }
 
+    /// Returns `true` if `bind_group_id` is the group assigned at `index`.
+    pub(super) fn is_assigned(&self, index: usize, bind_group_id: Valid<BindGroupId>) -> bool {
+        self.payloads[index]
+            .group_id
+            .as_ref()
+            .map(|stored| stored.value)
+            == Some(bind_group_id)
+    }
+
+    /// Replaces the dynamic offsets of the group assigned at `index`.
+    ///
+    /// Returns the payload to rebind, if the group is bound to the backend.
+    pub(super) fn assign_dynamic_offsets(
+        &mut self,
+        index: usize,
+        offsets: &[wgt::DynamicOffset],
+    ) -> Option<&EntryPayload> {
+        log::trace!("\tBinding [{}] = new offsets {:?}", index, offsets);
+
+        let payload = &mut self.payloads[index];
+        payload.dynamic_offsets.clear();
+        payload.dynamic_offsets.extend_from_slice(offsets);
+        if self.manager.is_bound(index) {
+            Some(&self.payloads[index])
+        } else {
+            None
+        }
+    }
+
     pub(super) fn list_active(&self) -> impl Iterator<Item = Valid<BindGroupId>> + '_ {
         let payloads = &self.payloads;
         self.manager
@@ -79,7 +79,7 @@ index format changes.
 #![allow(clippy::reversed_empty_ranges)]
 
 use crate::{
-    binding_model::{self, buffer_binding_type_alignment},
+    binding_model::{self, buffer_binding_type_alignment, LateMinBufferBindingSizeMismatch},
     command::{
         BasePass, BindGroupStateChange, ColorAttachmentError, DrawError, MapPassErr,
         PassErrorScope, RenderCommand, RenderCommandError, StateChange,
@@ -94,6 +94,7 @@ use crate::{
     hub::{Hub, Token},
     id,
     identity::GlobalIdentityHandlerFactory,
+    indirect_validation::{DrawLimits, Draws},
     init_tracker::{BufferInitTrackerAction, MemoryInitKind, TextureInitTrackerAction},
     pipeline::{self, PipelineFlags},
     resource::{self, Resource},
@@ -103,7 +104,7 @@ use crate::{
     Label, LabelHelpers, LifeGuard, Stored,
 };
 use arrayvec::ArrayVec;
-use std::{borrow::Cow, mem, num::NonZeroU32, ops::Range};
+use std::{borrow::Cow, mem, num::NonZeroU32, ops::Range, str};
 use thiserror::Error;
 
 use hal::CommandEncoder as _;
@@ -282,9 +283,18 @@ impl RenderBundleEncoder {
         let mut commands = Vec::new();
         let mut buffer_memory_init_actions = Vec::new();
         let mut texture_memory_init_actions = Vec::new();
+        let mut indirect_draws = Vec::new();
+        // Validated arguments are copied out of the buffer before the pass.
+        let indirect_use = match device.indirect_validation {
+            Some(_) => hal::BufferUses::COPY_SRC,
+            None => hal::BufferUses::INDIRECT,
+        };
 
         let base = self.base.as_ref();
         let mut next_dynamic_offset = 0;
+        let mut string_offset = 0;
+        let mut debug_scope_depth = 0u32;
+        let mut writes_unprotected = false;
 
         for &command in base.commands {
             match command {
@@ -303,6 +313,7 @@ impl RenderBundleEncoder {
                         .map_pass_err(scope)?;
                     self.check_valid_to_use(bind_group.device_id.value)
                         .map_pass_err(scope)?;
+                    writes_unprotected |= bind_group.writes_unprotected;
 
                     let max_bind_groups = device.limits.max_bind_groups;
                     if index >= max_bind_groups {
@@ -483,6 +494,10 @@ impl RenderBundleEncoder {
                     };
                     let pipeline = state.pipeline(scope)?;
                     let used_bind_groups = pipeline.used_bind_groups;
+                    state
+                        .check_late_buffer_bindings(pipeline, &bind_group_guard)
+                        .map_err(DrawError::from)
+                        .map_pass_err(scope)?;
                     let vertex_limits = state.vertex_limits(pipeline);
                     let last_vertex = first_vertex + vertex_count;
                     if last_vertex > vertex_limits.vertex_limit {
@@ -520,6 +535,10 @@ impl RenderBundleEncoder {
                     };
                     let pipeline = state.pipeline(scope)?;
                     let used_bind_groups = pipeline.used_bind_groups;
+                    state
+                        .check_late_buffer_bindings(pipeline, &bind_group_guard)
+                        .map_err(DrawError::from)
+                        .map_pass_err(scope)?;
                     let index = match state.index {
                         Some(ref index) => index,
                         None => return Err(DrawError::MissingIndexBuffer).map_pass_err(scope),
@@ -566,11 +585,16 @@ impl RenderBundleEncoder {
 
                     let pipeline = state.pipeline(scope)?;
                     let used_bind_groups = pipeline.used_bind_groups;
+                    state
+                        .check_late_buffer_bindings(pipeline, &bind_group_guard)
+                        .map_err(DrawError::from)
+                        .map_pass_err(scope)?;
+                    let vertex_limits = state.vertex_limits(pipeline);
 
                     let buffer: &resource::Buffer<A> = state
                         .trackers
                         .buffers
-                        .merge_single(&*buffer_guard, buffer_id, hal::BufferUses::INDIRECT)
+                        .merge_single(&*buffer_guard, buffer_id, indirect_use)
                         .map_pass_err(scope)?;
                     self.check_valid_to_use(buffer.device_id.value)
                         .map_pass_err(scope)?;
@@ -583,6 +607,16 @@ impl RenderBundleEncoder {
                         MemoryInitKind::NeedsInitializedMemory,
                     ));
 
+                    indirect_draws.push((
+                        false,
+                        DrawLimits {
+                            count: vertex_limits.vertex_limit,
+                            instance: vertex_limits.instance_limit,
+                            vertex: vertex_limits.vertex_limit,
+                            max_index: u32::MAX,
+                        },
+                    ));
+
                     commands.extend(state.flush_vertices());
                     commands.extend(state.flush_binds(used_bind_groups, base.dynamic_offsets));
                     commands.push(command);
@@ -604,11 +638,16 @@ impl RenderBundleEncoder {
 
                     let pipeline = state.pipeline(scope)?;
                     let used_bind_groups = pipeline.used_bind_groups;
+                    state
+                        .check_late_buffer_bindings(pipeline, &bind_group_guard)
+                        .map_err(DrawError::from)
+                        .map_pass_err(scope)?;
+                    let vertex_limits = state.vertex_limits(pipeline);
 
                     let buffer: &resource::Buffer<A> = state
                         .trackers
                         .buffers
-                        .merge_single(&*buffer_guard, buffer_id, hal::BufferUses::INDIRECT)
+                        .merge_single(&*buffer_guard, buffer_id, indirect_use)
                         .map_pass_err(scope)?;
                     self.check_valid_to_use(buffer.device_id.value)
                         .map_pass_err(scope)?;
@@ -617,7 +656,7 @@ impl RenderBundleEncoder {
 
                     buffer_memory_init_actions.extend(buffer.initialization_status.create_action(
                         buffer_id,
-                        offset..(offset + mem::size_of::<wgt::DrawIndirectArgs>() as u64),
+                        offset..(offset + mem::size_of::<wgt::DrawIndexedIndirectArgs>() as u64),
                         MemoryInitKind::NeedsInitializedMemory,
                     ));
 
@@ -625,6 +664,19 @@ impl RenderBundleEncoder {
                         Some(ref mut index) => index,
                         None => return Err(DrawError::MissingIndexBuffer).map_pass_err(scope),
                     };
+                    indirect_draws.push((
+                        true,
+                        DrawLimits {
+                            count: index.limit(),
+                            instance: vertex_limits.instance_limit,
+                            vertex: vertex_limits.vertex_limit,
+                            max_index: match index.format {
+                                _ if vertex_limits.vertex_limit == u32::MAX => u32::MAX,
+                                wgt::IndexFormat::Uint16 => u16::MAX as u32,
+                                wgt::IndexFormat::Uint32 => u32::MAX,
+                            },
+                        },
+                    ));
 
                     commands.extend(index.flush());
                     commands.extend(state.flush_vertices());
@@ -633,9 +685,24 @@ impl RenderBundleEncoder {
                 }
                 RenderCommand::MultiDrawIndirect { .. }
                 | RenderCommand::MultiDrawIndirectCount { .. } => unimplemented!(),
-                RenderCommand::PushDebugGroup { color: _, len: _ } => unimplemented!(),
-                RenderCommand::InsertDebugMarker { color: _, len: _ } => unimplemented!(),
-                RenderCommand::PopDebugGroup => unimplemented!(),
+                RenderCommand::PushDebugGroup { color: _, len } => {
+                    debug_scope_depth += 1;
+                    string_offset += len;
+                    commands.push(command);
+                }
+                RenderCommand::InsertDebugMarker { color: _, len } => {
+                    string_offset += len;
+                    commands.push(command);
+                }
+                RenderCommand::PopDebugGroup => {
+                    let scope = PassErrorScope::PopDebugGroup;
+                    if debug_scope_depth == 0 {
+                        return Err(RenderBundleErrorInner::InvalidPopDebugGroup)
+                            .map_pass_err(scope);
+                    }
+                    debug_scope_depth -= 1;
+                    commands.push(command);
+                }
                 RenderCommand::WriteTimestamp { .. } // Must check the TIMESTAMP_QUERY_INSIDE_PASSES feature
                 | RenderCommand::BeginOcclusionQuery { .. }
                 | RenderCommand::EndOcclusionQuery
@@ -644,18 +711,32 @@ impl RenderBundleEncoder {
                 RenderCommand::ExecuteBundle(_)
                 | RenderCommand::SetBlendConstant(_)
                 | RenderCommand::SetStencilReference(_)
+                | RenderCommand::SetShadingRate(_)
+                | RenderCommand::SetDepthBounds { .. }
                 | RenderCommand::SetViewport { .. }
-                | RenderCommand::SetScissor(_) => unreachable!("not supported by a render bundle"),
+                | RenderCommand::SetScissor(_)
+                | RenderCommand::SetViewportIndexed { .. }
+                | RenderCommand::SetScissorIndexed { .. } => unreachable!("not supported by a render bundle"),
             }
         }
 
+        // Debug groups can't span the bundle boundary: the pass executing it
+        // would otherwise end up with unbalanced markers.
+        if debug_scope_depth != 0 {
+            return Err(RenderBundleErrorInner::UnbalancedDebugGroups {
+                count: debug_scope_depth,
+            })
+            .map_pass_err(PassErrorScope::Bundle);
+        }
+        debug_assert_eq!(string_offset, base.string_data.len());
+
         Ok(RenderBundle {
             base: BasePass {
                 label: desc.label.as_ref().map(|cow| cow.to_string()),
                 commands,
                 dynamic_offsets: state.flat_dynamic_offsets,
-                string_data: Vec::new(),
-                push_constant_data: Vec::new(),
+                string_data: base.string_data.to_vec(),
+                push_constant_data: base.push_constant_data.to_vec(),
             },
             is_depth_read_only: self.is_depth_read_only,
             is_stencil_read_only: self.is_stencil_read_only,
@@ -666,7 +747,9 @@ impl RenderBundleEncoder {
             used: state.trackers,
             buffer_memory_init_actions,
             texture_memory_init_actions,
+            indirect_draws,
             context: self.context,
+            writes_unprotected,
             life_guard: LifeGuard::new(desc.label.borrow_or_default()),
         })
     }
@@ -744,7 +827,13 @@ pub struct RenderBundle<A: HalApi> {
     pub(crate) used: RenderBundleScope<A>,
     pub(super) buffer_memory_init_actions: Vec<BufferInitTrackerAction>,
     pub(super) texture_memory_init_actions: Vec<TextureInitTrackerAction>,
+    /// Whether each indirect draw is indexed, and the limits to validate it
+    /// against, in the order of the commands.
+    pub(crate) indirect_draws: Vec<(bool, DrawLimits)>,
     pub(super) context: RenderPassContext,
+    /// If true, one of the bind groups writes to unprotected resources, so
+    /// protected command buffers can't execute the bundle.
+    pub(super) writes_unprotected: bool,
     pub(crate) life_guard: LifeGuard,
 }
 
@@ -775,6 +864,9 @@ impl<A: HalApi> RenderBundle<A> {
     /// Note that the function isn't expected to fail, generally.
     /// All the validation has already been done by this point.
     /// The only failure condition is if some of the used buffers are destroyed.
+    ///
+    /// If the device validates indirect draws, they are added to
+    /// `indirect_draws` and draw from its buffer instead.
     pub(super) unsafe fn execute(
         &self,
         raw: &mut A::CommandEncoder,
@@ -785,8 +877,11 @@ impl<A: HalApi> RenderBundle<A> {
         bind_group_guard: &Storage<crate::binding_model::BindGroup<A>, id::BindGroupId>,
         pipeline_guard: &Storage<crate::pipeline::RenderPipeline<A>, id::RenderPipelineId>,
         buffer_guard: &Storage<crate::resource::Buffer<A>, id::BufferId>,
+        mut indirect_draws: Option<&mut Draws<A>>,
     ) -> Result<(), ExecutionError> {
         let mut offsets = self.base.dynamic_offsets.as_slice();
+        let mut draw_limits = self.indirect_draws.iter();
+        let mut string_offset = 0;
         let mut pipeline_layout_id = None::<id::Valid<id::PipelineLayoutId>>;
         if let Some(ref label) = self.base.label {
             unsafe { raw.begin_debug_marker(label) };
@@ -813,6 +908,9 @@ impl<A: HalApi> RenderBundle<A> {
                 RenderCommand::SetPipeline(pipeline_id) => {
                     let pipeline = pipeline_guard.get(pipeline_id).unwrap();
                     unsafe { raw.set_render_pipeline(&pipeline.raw) };
+                    if let Some(bounds) = pipeline.depth_bounds {
+                        unsafe { raw.set_depth_bounds(bounds.min..bounds.max) };
+                    }
 
                     pipeline_layout_id = Some(pipeline.layout_id.value);
                 }
@@ -918,21 +1016,7 @@ impl<A: HalApi> RenderBundle<A> {
                     buffer_id,
                     offset,
                     count: None,
-                    indexed: false,
-                } => {
-                    let buffer = buffer_guard
-                        .get(buffer_id)
-                        .unwrap()
-                        .raw
-                        .as_ref()
-                        .ok_or(ExecutionError::DestroyedBuffer(buffer_id))?;
-                    unsafe { raw.draw_indirect(buffer, offset, 1) };
-                }
-                RenderCommand::MultiDrawIndirect {
-                    buffer_id,
-                    offset,
-                    count: None,
-                    indexed: true,
+                    indexed,
                 } => {
                     let buffer = buffer_guard
                         .get(buffer_id)
@@ -940,16 +1024,39 @@ impl<A: HalApi> RenderBundle<A> {
                         .raw
                         .as_ref()
                         .ok_or(ExecutionError::DestroyedBuffer(buffer_id))?;
-                    unsafe { raw.draw_indexed_indirect(buffer, offset, 1) };
+                    let &(_, limits) = draw_limits.next().unwrap();
+                    let (buffer, offset) = match indirect_draws {
+                        Some(ref mut draws) => {
+                            let offset = draws.add(buffer_id, offset, 1, indexed, limits);
+                            (draws.buffer(), offset)
+                        }
+                        None => (buffer, offset),
+                    };
+                    match indexed {
+                        false => unsafe { raw.draw_indirect(buffer, offset, 1) },
+                        true => unsafe { raw.draw_indexed_indirect(buffer, offset, 1) },
+                    }
                 }
                 RenderCommand::MultiDrawIndirect { .. }
                 | RenderCommand::MultiDrawIndirectCount { .. } => {
                     return Err(ExecutionError::Unimplemented("multi-draw-indirect"))
                 }
-                RenderCommand::PushDebugGroup { .. }
-                | RenderCommand::InsertDebugMarker { .. }
-                | RenderCommand::PopDebugGroup => {
-                    return Err(ExecutionError::Unimplemented("debug-markers"))
+                RenderCommand::PushDebugGroup { color: _, len } => {
+                    let label =
+                        str::from_utf8(&self.base.string_data[string_offset..string_offset + len])
+                            .unwrap();
+                    string_offset += len;
+                    unsafe { raw.begin_debug_marker(label) };
+                }
+                RenderCommand::InsertDebugMarker { color: _, len } => {
+                    let label =
+                        str::from_utf8(&self.base.string_data[string_offset..string_offset + len])
+                            .unwrap();
+                    string_offset += len;
+                    unsafe { raw.insert_debug_marker(label) };
+                }
+                RenderCommand::PopDebugGroup => {
+                    unsafe { raw.end_debug_marker() };
                 }
                 RenderCommand::WriteTimestamp { .. }
                 | RenderCommand::BeginOcclusionQuery { .. }
@@ -961,8 +1068,12 @@ impl<A: HalApi> RenderBundle<A> {
                 RenderCommand::ExecuteBundle(_)
                 | RenderCommand::SetBlendConstant(_)
                 | RenderCommand::SetStencilReference(_)
+                | RenderCommand::SetShadingRate(_)
+                | RenderCommand::SetDepthBounds { .. }
                 | RenderCommand::SetViewport { .. }
-                | RenderCommand::SetScissor(_) => unreachable!(),
+                | RenderCommand::SetScissor(_)
+                | RenderCommand::SetViewportIndexed { .. }
+                | RenderCommand::SetScissorIndexed { .. } => unreachable!(),
             }
         }
 
@@ -1115,6 +1226,10 @@ struct PipelineState {
 
     /// The number of bind groups this pipeline uses.
     used_bind_groups: usize,
+
+    /// Buffer binding sizes the pipeline's shaders expect, for bindings whose
+    /// layout doesn't specify a `min_binding_size`.
+    late_sized_buffer_groups: ArrayVec<pipeline::LateSizedBufferGroup, { hal::MAX_BIND_GROUPS }>,
 }
 
 impl PipelineState {
@@ -1129,6 +1244,7 @@ impl PipelineState {
             steps: pipeline.vertex_steps.to_vec(),
             push_constant_ranges: layout.push_constant_ranges.iter().cloned().collect(),
             used_bind_groups: layout.bind_group_layout_ids.len(),
+            late_sized_buffer_groups: pipeline.late_sized_buffer_groups.clone(),
         }
     }
 
@@ -1234,6 +1350,43 @@ impl<A: HalApi> State<A> {
             .map_pass_err(scope)
     }
 
+    /// Check that the buffers bound to layouts without a `min_binding_size`
+    /// are large enough for the shaders of `pipeline`.
+    fn check_late_buffer_bindings(
+        &self,
+        pipeline: &PipelineState,
+        bind_group_guard: &Storage<binding_model::BindGroup<A>, id::BindGroupId>,
+    ) -> Result<(), LateMinBufferBindingSizeMismatch> {
+        for (group_index, (entry, late_group)) in self
+            .bind
+            .iter()
+            .zip(pipeline.late_sized_buffer_groups.iter())
+            .enumerate()
+        {
+            let contents = match *entry {
+                Some(ref contents) => contents,
+                None => continue,
+            };
+            let bind_group = &bind_group_guard[id::Valid(contents.bind_group_id)];
+            for (compact_index, (&shader_size, bound_size)) in late_group
+                .shader_sizes
+                .iter()
+                .zip(bind_group.late_buffer_binding_sizes.iter())
+                .enumerate()
+            {
+                if bound_size.get() < shader_size {
+                    return Err(LateMinBufferBindingSizeMismatch {
+                        group_index: group_index as u32,
+                        compact_index,
+                        shader_size,
+                        bound_size: bound_size.get(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Mark all non-empty bind group table entries from `index` onwards as dirty.
     fn invalidate_bind_group_from(&mut self, index: usize) {
         for contents in self.bind[index..].iter_mut().flatten() {
@@ -1410,6 +1563,10 @@ pub(super) enum RenderBundleErrorInner {
     Draw(#[from] DrawError),
     #[error(transparent)]
     MissingDownlevelFlags(#[from] MissingDownlevelFlags),
+    #[error("Cannot pop debug group, because number of pushed debug groups is zero")]
+    InvalidPopDebugGroup,
+    #[error("Render bundle ends with {count} debug groups that were not popped")]
+    UnbalancedDebugGroups { count: u32 },
 }
 
 impl<T> From<T> for RenderBundleErrorInner
@@ -1460,7 +1617,7 @@ where
 pub mod bundle_ffi {
     use super::{RenderBundleEncoder, RenderCommand};
     use crate::{id, RawString};
-    use std::{convert::TryInto, slice};
+    use std::{convert::TryInto, ffi, slice};
     use wgt::{BufferAddress, BufferSize, DynamicOffset, IndexFormat};
 
     /// # Safety
@@ -1647,15 +1804,21 @@ pub mod bundle_ffi {
     /// is a valid null-terminated string.
     #[no_mangle]
     pub unsafe extern "C" fn wgpu_render_bundle_push_debug_group(
-        _bundle: &mut RenderBundleEncoder,
-        _label: RawString,
+        bundle: &mut RenderBundleEncoder,
+        label: RawString,
     ) {
-        //TODO
+        let bytes = unsafe { ffi::CStr::from_ptr(label) }.to_bytes();
+        bundle.base.string_data.extend_from_slice(bytes);
+
+        bundle.base.commands.push(RenderCommand::PushDebugGroup {
+            color: 0,
+            len: bytes.len(),
+        });
     }
 
     #[no_mangle]
-    pub extern "C" fn wgpu_render_bundle_pop_debug_group(_bundle: &mut RenderBundleEncoder) {
-        //TODO
+    pub extern "C" fn wgpu_render_bundle_pop_debug_group(bundle: &mut RenderBundleEncoder) {
+        bundle.base.commands.push(RenderCommand::PopDebugGroup);
     }
 
     /// # Safety
@@ -1664,9 +1827,15 @@ pub mod bundle_ffi {
     /// is a valid null-terminated string.
     #[no_mangle]
     pub unsafe extern "C" fn wgpu_render_bundle_insert_debug_marker(
-        _bundle: &mut RenderBundleEncoder,
-        _label: RawString,
+        bundle: &mut RenderBundleEncoder,
+        label: RawString,
     ) {
-        //TODO
+        let bytes = unsafe { ffi::CStr::from_ptr(label) }.to_bytes();
+        bundle.base.string_data.extend_from_slice(bytes);
+
+        bundle.base.commands.push(RenderCommand::InsertDebugMarker {
+            color: 0,
+            len: bytes.len(),
+        });
     }
 }
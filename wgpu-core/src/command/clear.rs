@@ -3,7 +3,7 @@ use std::ops::Range;
 #[cfg(feature = "trace")]
 use crate::device::trace::Command as TraceCommand;
 use crate::{
-    command::CommandBuffer,
+    command::{CommandBuffer, IncompatibleQueueError},
     get_lowest_common_denom,
     global::Global,
     hal_api::HalApi,
@@ -38,6 +38,8 @@ pub enum ClearError {
     InvalidTexture(TextureId),
     #[error("Texture {0:?} can not be cleared")]
     NoValidTextureClearMode(TextureId),
+    #[error(transparent)]
+    IncompatibleQueue(#[from] IncompatibleQueueError),
     #[error("Buffer clear size {0:?} is not a multiple of `COPY_BUFFER_ALIGNMENT`")]
     UnalignedFillSize(BufferSize),
     #[error("Buffer offset {0:?} is not a multiple of `COPY_BUFFER_ALIGNMENT`")]
@@ -50,6 +52,8 @@ pub enum ClearError {
     },
     #[error("Destination buffer is missing the `COPY_DST` usage flag")]
     MissingCopyDstUsageFlag(Option<BufferId>, Option<TextureId>),
+    #[error("Destination buffer/texture is missing the `PROTECTED` usage flag, so protected command buffers can't clear it")]
+    UnprotectedDestination(Option<BufferId>, Option<TextureId>),
     #[error("Texture lacks the aspects that were specified in the image subresource range. Texture with format {texture_format:?}, specified was {subresource_range_aspects:?}")]
     MissingTextureAspect {
         texture_format: wgt::TextureFormat,
@@ -105,6 +109,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         if !dst_buffer.usage.contains(BufferUsages::COPY_DST) {
             return Err(ClearError::MissingCopyDstUsageFlag(Some(dst), None));
         }
+        if cmd_buf.protected && !dst_buffer.usage.contains(BufferUsages::PROTECTED) {
+            return Err(ClearError::UnprotectedDestination(Some(dst), None));
+        }
 
         // Check if offset & size are valid.
         if offset % wgt::COPY_BUFFER_ALIGNMENT != 0 {
@@ -217,21 +224,43 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 subresource_array_layer_count: subresource_range.array_layer_count,
             });
         }
+        if cmd_buf.protected
+            && !dst_texture
+                .desc
+                .usage
+                .contains(wgt::TextureUsages::PROTECTED)
+        {
+            return Err(ClearError::UnprotectedDestination(None, Some(dst)));
+        }
+
+        // Render attachments and depth-stencil textures are cleared with render passes.
+        if let TextureClearMode::RenderPass { .. } = dst_texture.clear_mode {
+            cmd_buf.require_queue("Clearing render attachments", &[])?;
+        }
 
         let device = &device_guard[cmd_buf.device_id.value];
 
+        let range = TextureInitRange {
+            mip_range: subresource_mip_range,
+            layer_range: subresource_layer_range,
+        };
         clear_texture(
             &*texture_guard,
             Valid(dst),
-            TextureInitRange {
-                mip_range: subresource_mip_range,
-                layer_range: subresource_layer_range,
-            },
+            range.clone(),
             cmd_buf.encoder.open(),
             &mut cmd_buf.trackers.textures,
             &device.alignments,
             &device.zero_buffer,
-        )
+        )?;
+
+        // The cleared subresources now hold zeros, so they don't need to be
+        // lazily zero-initialized again before their next use.
+        cmd_buf
+            .texture_memory_actions
+            .register_implicit_init(Valid(dst), range, &texture_guard);
+
+        Ok(())
     }
 }
 
@@ -317,80 +346,84 @@ fn clear_texture_via_buffer_copies<A: hal::Api>(
     encoder: &mut A::CommandEncoder,
     dst_raw: &A::Texture,
 ) {
-    assert_eq!(
-        hal::FormatAspects::from(texture_desc.format),
-        hal::FormatAspects::COLOR
-    );
+    assert!(!texture_desc.format.is_depth_stencil_format());
 
     // Gather list of zero_buffer copies and issue a single command then to perform them
     let mut zero_buffer_copy_regions = Vec::new();
     let buffer_copy_pitch = alignments.buffer_copy_pitch.get() as u32;
     let (block_width, block_height) = texture_desc.format.block_dimensions();
-    let block_size = texture_desc.format.block_size(None).unwrap();
-
-    let bytes_per_row_alignment = get_lowest_common_denom(buffer_copy_pitch, block_size);
 
-    for mip_level in range.mip_range {
-        let mut mip_size = texture_desc.mip_level_size(mip_level).unwrap();
-        // Round to multiple of block size
-        mip_size.width = align_to(mip_size.width, block_width);
-        mip_size.height = align_to(mip_size.height, block_height);
-
-        let bytes_per_row = align_to(
-            mip_size.width / block_width * block_size,
-            bytes_per_row_alignment,
-        );
-
-        let max_rows_per_copy = crate::device::ZERO_BUFFER_SIZE as u32 / bytes_per_row;
-        // round down to a multiple of rows needed by the texture format
-        let max_rows_per_copy = max_rows_per_copy / block_height * block_height;
-        assert!(
-            max_rows_per_copy > 0,
-            "Zero buffer size is too small to fill a single row \
+    // Planes of multi-planar formats are cleared one at a time.
+    for aspect in hal::FormatAspects::from(texture_desc.format).iter() {
+        let block_size = texture_desc.format.block_size(Some(aspect.map())).unwrap();
+        let (width_factor, height_factor) = texture_desc.format.subsampling_factors(aspect.map());
+
+        let bytes_per_row_alignment = get_lowest_common_denom(buffer_copy_pitch, block_size);
+
+        for mip_level in range.mip_range.clone() {
+            let mut mip_size = texture_desc.mip_level_size(mip_level).unwrap();
+            mip_size.width /= width_factor;
+            mip_size.height /= height_factor;
+            // Round to multiple of block size
+            mip_size.width = align_to(mip_size.width, block_width);
+            mip_size.height = align_to(mip_size.height, block_height);
+
+            let bytes_per_row = align_to(
+                mip_size.width / block_width * block_size,
+                bytes_per_row_alignment,
+            );
+
+            let max_rows_per_copy = crate::device::ZERO_BUFFER_SIZE as u32 / bytes_per_row;
+            // round down to a multiple of rows needed by the texture format
+            let max_rows_per_copy = max_rows_per_copy / block_height * block_height;
+            assert!(
+                max_rows_per_copy > 0,
+                "Zero buffer size is too small to fill a single row \
                  of a texture with format {:?} and desc {:?}",
-            texture_desc.format,
-            texture_desc.size
-        );
-
-        let z_range = 0..(if texture_desc.dimension == wgt::TextureDimension::D3 {
-            mip_size.depth_or_array_layers
-        } else {
-            1
-        });
-
-        for array_layer in range.layer_range.clone() {
-            // TODO: Only doing one layer at a time for volume textures right now.
-            for z in z_range.clone() {
-                // May need multiple copies for each subresource! However, we
-                // assume that we never need to split a row.
-                let mut num_rows_left = mip_size.height;
-                while num_rows_left > 0 {
-                    let num_rows = num_rows_left.min(max_rows_per_copy);
-
-                    zero_buffer_copy_regions.push(hal::BufferTextureCopy {
-                        buffer_layout: wgt::ImageDataLayout {
-                            offset: 0,
-                            bytes_per_row: Some(bytes_per_row),
-                            rows_per_image: None,
-                        },
-                        texture_base: hal::TextureCopyBase {
-                            mip_level,
-                            array_layer,
-                            origin: wgt::Origin3d {
-                                x: 0, // Always full rows
-                                y: mip_size.height - num_rows_left,
-                                z,
+                texture_desc.format,
+                texture_desc.size
+            );
+
+            let z_range = 0..(if texture_desc.dimension == wgt::TextureDimension::D3 {
+                mip_size.depth_or_array_layers
+            } else {
+                1
+            });
+
+            for array_layer in range.layer_range.clone() {
+                // TODO: Only doing one layer at a time for volume textures right now.
+                for z in z_range.clone() {
+                    // May need multiple copies for each subresource! However, we
+                    // assume that we never need to split a row.
+                    let mut num_rows_left = mip_size.height;
+                    while num_rows_left > 0 {
+                        let num_rows = num_rows_left.min(max_rows_per_copy);
+
+                        zero_buffer_copy_regions.push(hal::BufferTextureCopy {
+                            buffer_layout: wgt::ImageDataLayout {
+                                offset: 0,
+                                bytes_per_row: Some(bytes_per_row),
+                                rows_per_image: None,
                             },
-                            aspect: hal::FormatAspects::COLOR,
-                        },
-                        size: hal::CopyExtent {
-                            width: mip_size.width, // full row
-                            height: num_rows,
-                            depth: 1, // Only single slice of volume texture at a time right now
-                        },
-                    });
+                            texture_base: hal::TextureCopyBase {
+                                mip_level,
+                                array_layer,
+                                origin: wgt::Origin3d {
+                                    x: 0, // Always full rows
+                                    y: mip_size.height - num_rows_left,
+                                    z,
+                                },
+                                aspect,
+                            },
+                            size: hal::CopyExtent {
+                                width: mip_size.width, // full row
+                                height: num_rows,
+                                depth: 1, // Only single slice of volume texture at a time right now
+                            },
+                        });
 
-                    num_rows_left -= num_rows;
+                        num_rows_left -= num_rows;
+                    }
                 }
             }
         }
@@ -425,6 +458,7 @@ fn clear_texture_via_render_passes<A: hal::Api>(
                         view: dst_texture.get_clear_view(mip_level, depth_or_layer),
                         usage: hal::TextureUses::COLOR_TARGET,
                     },
+                    depth_slice: None,
                     resolve_target: None,
                     ops: hal::AttachmentOps::STORE,
                     clear_value: wgt::Color::TRANSPARENT,
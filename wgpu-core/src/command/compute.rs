@@ -8,7 +8,8 @@ use crate::{
         end_pipeline_statistics_query,
         memory_init::{fixup_discarded_surfaces, SurfacesInDiscardState},
         BasePass, BasePassRef, BindGroupStateChange, CommandBuffer, CommandEncoderError,
-        CommandEncoderStatus, MapPassErr, PassErrorScope, QueryUseError, StateChange,
+        CommandEncoderStatus, IncompatibleQueueError, MapPassErr, PassErrorScope, QueryUseError,
+        StateChange,
     },
     device::{MissingDownlevelFlags, MissingFeatures},
     error::{ErrorFormatter, PrettyError},
@@ -191,8 +192,12 @@ pub enum DispatchError {
 pub enum ComputePassErrorInner {
     #[error(transparent)]
     Encoder(#[from] CommandEncoderError),
+    #[error(transparent)]
+    IncompatibleQueue(#[from] IncompatibleQueueError),
     #[error("Bind group {0:?} is invalid")]
     InvalidBindGroup(id::BindGroupId),
+    #[error("Bind group {0:?} writes to resources without the `PROTECTED` usage flag, so protected command buffers can't use it")]
+    UnprotectedBindGroup(id::BindGroupId),
     #[error("Bind group index {index} is greater than the device's requested `max_bind_group` limit {max}")]
     BindGroupIndexOutOfRange { index: u32, max: u32 },
     #[error("Compute pipeline {0:?} is invalid")]
@@ -380,6 +385,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let cmd_buf: &mut CommandBuffer<A> =
             CommandBuffer::get_encoder_mut(&mut *cmd_buf_guard, encoder_id)
                 .map_pass_err(init_scope)?;
+        cmd_buf
+            .require_queue("Compute passes", &[wgt::QueueKind::Compute])
+            .map_pass_err(init_scope)?;
 
         // We automatically keep extending command buffers over time, and because
         // we want to insert a command buffer _before_ what we're about to record,
@@ -468,7 +476,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         );
 
         let hal_desc = hal::ComputePassDescriptor {
-            label: base.label,
+            label: crate::hal_label(base.label, device.instance_flags),
             timestamp_writes,
         };
 
@@ -507,6 +515,36 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     );
                     dynamic_offset_count += num_dynamic_offsets as usize;
 
+                    // Setting the same group again only changes its dynamic offsets:
+                    // its resources were already tracked when it was first set.
+                    if state
+                        .binder
+                        .is_assigned(index as usize, id::Valid(bind_group_id))
+                    {
+                        let bind_group = &bind_group_guard[id::Valid(bind_group_id)];
+                        bind_group
+                            .validate_dynamic_bindings(index, &temp_offsets, &cmd_buf.limits)
+                            .map_pass_err(scope)?;
+
+                        let pipeline_layout_id = state.binder.pipeline_layout_id;
+                        if let Some(e) = state
+                            .binder
+                            .assign_dynamic_offsets(index as usize, &temp_offsets)
+                        {
+                            let pipeline_layout =
+                                &pipeline_layout_guard[pipeline_layout_id.unwrap()].raw;
+                            unsafe {
+                                raw.set_bind_group_dynamic_offsets(
+                                    pipeline_layout,
+                                    index,
+                                    &bind_group.raw,
+                                    &e.dynamic_offsets,
+                                );
+                            }
+                        }
+                        continue;
+                    }
+
                     let bind_group: &BindGroup<A> = cmd_buf
                         .trackers
                         .bind_groups
@@ -516,6 +554,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     bind_group
                         .validate_dynamic_bindings(index, &temp_offsets, &cmd_buf.limits)
                         .map_pass_err(scope)?;
+                    if cmd_buf.protected && bind_group.writes_unprotected {
+                        return Err(ComputePassErrorInner::UnprotectedBindGroup(bind_group_id))
+                            .map_pass_err(scope);
+                    }
 
                     cmd_buf.buffer_memory_init_actions.extend(
                         bind_group.used_buffer_ranges.iter().filter_map(
@@ -842,6 +884,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             }
         }
 
+        if let Some((_, query_index)) = active_query {
+            return Err(QueryUseError::NotStopped { query_index }).map_pass_err(init_scope);
+        }
+
         unsafe {
             raw.end_compute_pass();
         }
@@ -93,8 +93,12 @@ pub enum RenderCommandError {
     InvalidViewportRect(Rect<f32>, wgt::Extent3d),
     #[error("Viewport minDepth {0} and/or maxDepth {1} are not in [0, 1]")]
     InvalidViewportDepth(f32, f32),
+    #[error("Depth bounds min {0} and/or max {1} are not in [0, 1], or min is greater than max")]
+    InvalidDepthBounds(f32, f32),
     #[error("Scissor {0:?} is not contained in the render target {1:?}")]
     InvalidScissorRect(Rect<u32>, wgt::Extent3d),
+    #[error("Viewport index {0} must be less than {}", wgt::MAX_VIEWPORTS)]
+    InvalidViewportIndex(u32),
     #[error("Support for {0} is not implemented yet")]
     Unimplemented(&'static str),
 }
@@ -167,6 +171,11 @@ pub enum RenderCommand {
     },
     SetBlendConstant(Color),
     SetStencilReference(u32),
+    SetShadingRate(wgt::ShadingRate),
+    SetDepthBounds {
+        min: f32,
+        max: f32,
+    },
     SetViewport {
         rect: Rect<f32>,
         //TODO: use half-float to reduce the size?
@@ -174,6 +183,16 @@ pub enum RenderCommand {
         depth_max: f32,
     },
     SetScissor(Rect<u32>),
+    SetViewportIndexed {
+        index: u32,
+        rect: Rect<f32>,
+        depth_min: f32,
+        depth_max: f32,
+    },
+    SetScissorIndexed {
+        index: u32,
+        rect: Rect<u32>,
+    },
 
     /// Set a range of push constants to values stored in [`BasePass::push_constant_data`].
     ///
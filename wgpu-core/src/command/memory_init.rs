@@ -3,7 +3,7 @@ use std::{collections::hash_map::Entry, ops::Range, vec::Drain};
 use hal::CommandEncoder;
 
 use crate::{
-    device::Device,
+    device::{queue::PendingWrites, Device},
     hal_api::HalApi,
     id::{self, TextureId},
     init_tracker::*,
@@ -26,7 +26,7 @@ pub(crate) struct TextureSurfaceDiscard {
 
 pub(crate) type SurfacesInDiscardState = Vec<TextureSurfaceDiscard>;
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub(crate) struct CommandBufferTextureMemoryActions {
     /// The tracker actions that we need to be executed before the command
     /// buffer is executed.
@@ -161,10 +161,14 @@ pub(crate) fn fixup_discarded_surfaces<
 impl<A: HalApi> BakedCommands<A> {
     // inserts all buffer initializations that are going to be needed for
     // executing the commands and updates resource init states accordingly
+    //
+    // Protected command buffers can't clear unprotected buffers, these clears
+    // are recorded into `pending_writes` instead, which execute first.
     pub(crate) fn initialize_buffer_memory(
         &mut self,
         device_tracker: &mut Tracker<A>,
         buffer_guard: &mut Storage<Buffer<A>, id::BufferId>,
+        pending_writes: &mut PendingWrites<A>,
     ) -> Result<(), DestroyedBufferError> {
         // Gather init ranges for each buffer so we can collapse them.
         // It is not possible to do this at an earlier point since previously
@@ -230,9 +234,15 @@ impl<A: HalApi> BakedCommands<A> {
                 .get_mut(buffer_id)
                 .map_err(|_| DestroyedBufferError(buffer_id))?;
             let raw_buf = buffer.raw.as_ref().ok_or(DestroyedBufferError(buffer_id))?;
+            let encoder = if self.protected && !buffer.usage.contains(wgt::BufferUsages::PROTECTED)
+            {
+                pending_writes.activate()
+            } else {
+                &mut self.encoder
+            };
 
             unsafe {
-                self.encoder.transition_buffers(
+                encoder.transition_buffers(
                     transition
                         .map(|pending| pending.into_hal(buffer))
                         .into_iter(),
@@ -256,7 +266,7 @@ impl<A: HalApi> BakedCommands<A> {
                 );
 
                 unsafe {
-                    self.encoder.clear_buffer(raw_buf, range.clone());
+                    encoder.clear_buffer(raw_buf, range.clone());
                 }
             }
         }
@@ -267,11 +277,16 @@ impl<A: HalApi> BakedCommands<A> {
     // executing the commands and updates resource init states accordingly any
     // textures that are left discarded by this command buffer will be marked as
     // uninitialized
+    //
+    // As for buffers, the clears of unprotected textures used by protected
+    // command buffers are recorded into `pending_writes`.
     pub(crate) fn initialize_texture_memory(
         &mut self,
         device_tracker: &mut Tracker<A>,
         texture_guard: &mut Storage<Texture<A>, TextureId>,
-        device: &Device<A>,
+        pending_writes: &mut PendingWrites<A>,
+        alignments: &hal::Alignments,
+        zero_buffer: &A::Buffer,
     ) -> Result<(), DestroyedTextureError> {
         let mut ranges: Vec<TextureInitRange> = Vec::new();
         for texture_use in self.texture_memory_actions.drain_init_actions() {
@@ -306,16 +321,26 @@ impl<A: HalApi> BakedCommands<A> {
                 }
             }
 
+            if ranges.is_empty() {
+                continue;
+            }
+            let encoder =
+                if self.protected && !texture.desc.usage.contains(wgt::TextureUsages::PROTECTED) {
+                    pending_writes.activate()
+                } else {
+                    &mut self.encoder
+                };
+
             // TODO: Could we attempt some range collapsing here?
             for range in ranges.drain(..) {
                 clear_texture(
                     texture_guard,
                     id::Valid(texture_use.id),
                     range,
-                    &mut self.encoder,
+                    encoder,
                     &mut device_tracker.textures,
-                    &device.alignments,
-                    &device.zero_buffer,
+                    alignments,
+                    zero_buffer,
                 )
                 .unwrap();
             }
@@ -21,14 +21,16 @@ use crate::error::{ErrorFormatter, PrettyError};
 use crate::init_tracker::BufferInitTrackerAction;
 use crate::track::{Tracker, UsageScope};
 use crate::{
+    device::{queue::TempResource, DeviceError, MissingFeatures},
     global::Global,
     hal_api::HalApi,
     hub::Token,
     id,
     identity::GlobalIdentityHandlerFactory,
-    resource::{Buffer, Texture},
+    indirect_validation::Draws,
+    resource::{Buffer, Texture, TextureInner},
     storage::Storage,
-    Label, Stored,
+    Label, Stored, SubmissionIndex,
 };
 
 use hal::CommandEncoder as _;
@@ -51,6 +53,10 @@ struct CommandEncoder<A: hal::Api> {
     list: Vec<A::CommandBuffer>,
     is_open: bool,
     label: Option<String>,
+    /// The label passed to the backend, `None` if the instance discards them.
+    hal_label: Option<String>,
+    /// If true, the command buffers are recorded to be submitted more than once.
+    reusable: bool,
 }
 
 //TODO: handle errors better
@@ -82,15 +88,15 @@ impl<A: hal::Api> CommandEncoder<A> {
     fn open(&mut self) -> &mut A::CommandEncoder {
         if !self.is_open {
             self.is_open = true;
-            let label = self.label.as_deref();
-            unsafe { self.raw.begin_encoding(label).unwrap() };
+            let label = self.hal_label.as_deref();
+            unsafe { self.raw.begin_encoding(label, self.reusable).unwrap() };
         }
         &mut self.raw
     }
 
     fn open_pass(&mut self, label: Option<&str>) {
         self.is_open = true;
-        unsafe { self.raw.begin_encoding(label).unwrap() };
+        unsafe { self.raw.begin_encoding(label, self.reusable).unwrap() };
     }
 }
 
@@ -100,6 +106,31 @@ pub struct BakedCommands<A: HalApi> {
     pub(crate) trackers: Tracker<A>,
     buffer_memory_init_actions: Vec<BufferInitTrackerAction>,
     texture_memory_actions: CommandBufferTextureMemoryActions,
+    pub(crate) temp_resources: Vec<TempResource<A>>,
+    /// Whether the commands are recorded into protected command buffers.
+    pub(crate) protected: bool,
+    /// Index of the additional queue `encoder` was created for, if any.
+    pub(crate) additional_queue: Option<usize>,
+}
+
+impl<A: HalApi> BakedCommands<A> {
+    /// Bakes the memory initializations of a reusable command buffer for one
+    /// submission, to be recorded into `transit`.
+    ///
+    /// The command buffer keeps its own commands and resources, so they are
+    /// not part of the result. `transit` is an encoder of the main queue.
+    pub(crate) fn for_reusable(cmd_buf: &CommandBuffer<A>, transit: A::CommandEncoder) -> Self {
+        BakedCommands {
+            encoder: transit,
+            list: Vec::new(),
+            trackers: Tracker::new(),
+            buffer_memory_init_actions: cmd_buf.buffer_memory_init_actions.clone(),
+            texture_memory_actions: cmd_buf.texture_memory_actions.clone(),
+            temp_resources: Vec::new(),
+            protected: cmd_buf.protected,
+            additional_queue: None,
+        }
+    }
 }
 
 pub(crate) struct DestroyedBufferError(pub id::BufferId);
@@ -113,8 +144,26 @@ pub struct CommandBuffer<A: HalApi> {
     buffer_memory_init_actions: Vec<BufferInitTrackerAction>,
     texture_memory_actions: CommandBufferTextureMemoryActions,
     pub(crate) pending_query_resets: QueryResetMap<A>,
+    /// Internal resources used by the recorded commands, to be freed once
+    /// they are done executing.
+    temp_resources: Vec<TempResource<A>>,
+    /// Indirect draws of the render pass being recorded, to be validated
+    /// before it.
+    indirect_draws: Option<Draws<A>>,
     limits: wgt::Limits,
     support_clear_texture: bool,
+    support_reusable: bool,
+    support_protected: bool,
+    /// If true, the command buffer is protected and must not write to
+    /// unprotected resources. Otherwise, it must not use protected resources.
+    pub(crate) protected: bool,
+    /// If true, submitting the command buffer doesn't consume it.
+    pub(crate) reusable: bool,
+    /// Index of the last submission of a reusable command buffer.
+    pub(crate) last_submission: Option<SubmissionIndex>,
+    /// The index and kind of the additional queue the command buffer is
+    /// recorded for, or `None` for the main queue.
+    pub(crate) additional_queue: Option<(usize, wgt::QueueKind)>,
     #[cfg(feature = "trace")]
     pub(crate) commands: Option<Vec<TraceCommand>>,
 }
@@ -128,13 +177,20 @@ impl<A: HalApi> CommandBuffer<A> {
         features: wgt::Features,
         #[cfg(feature = "trace")] enable_tracing: bool,
         label: &Label,
+        reusable: bool,
+        protected: bool,
+        instance_flags: wgt::InstanceFlags,
+        additional_queue: Option<(usize, wgt::QueueKind)>,
     ) -> Self {
+        let label = crate::LabelHelpers::borrow_option(label);
         CommandBuffer {
             encoder: CommandEncoder {
                 raw: encoder,
                 is_open: false,
                 list: Vec::new(),
-                label: crate::LabelHelpers::borrow_option(label).map(|s| s.to_string()),
+                label: label.map(|s| s.to_string()),
+                hal_label: crate::hal_label(label, instance_flags).map(|s| s.to_string()),
+                reusable,
             },
             status: CommandEncoderStatus::Recording,
             device_id,
@@ -142,8 +198,16 @@ impl<A: HalApi> CommandBuffer<A> {
             buffer_memory_init_actions: Default::default(),
             texture_memory_actions: Default::default(),
             pending_query_resets: QueryResetMap::new(),
+            temp_resources: Vec::new(),
+            indirect_draws: None,
             limits,
             support_clear_texture: features.contains(wgt::Features::CLEAR_TEXTURE),
+            support_reusable: features.contains(wgt::Features::REUSABLE_COMMAND_BUFFERS),
+            support_protected: features.contains(wgt::Features::PROTECTED_CONTENT),
+            protected,
+            reusable: false,
+            last_submission: None,
+            additional_queue,
             #[cfg(feature = "trace")]
             commands: if enable_tracing {
                 Some(Vec::new())
@@ -224,6 +288,81 @@ impl<A: HalApi> CommandBuffer<A> {
         }
     }
 
+    /// Checks that the command buffer can be finished, as reusable if asked.
+    ///
+    /// Surface textures are only valid for the frame they were acquired for,
+    /// so they can't be used again by a later submission. They are presented
+    /// from the main queue, so they can't be used on additional queues either.
+    fn validate_finish(
+        &self,
+        reusable: bool,
+        buffer_guard: &Storage<Buffer<A>, id::BufferId>,
+        texture_guard: &Storage<Texture<A>, id::TextureId>,
+    ) -> Result<(), CommandEncoderError> {
+        // Protected resources can only exist with `PROTECTED_CONTENT`.
+        if self.support_protected && !self.protected {
+            for id in self.trackers.buffers.used() {
+                if buffer_guard[id]
+                    .usage
+                    .contains(wgt::BufferUsages::PROTECTED)
+                {
+                    return Err(CommandEncoderError::ProtectedBuffer(id.0));
+                }
+            }
+            for id in self.trackers.textures.used() {
+                if texture_guard[id]
+                    .desc
+                    .usage
+                    .contains(wgt::TextureUsages::PROTECTED)
+                {
+                    return Err(CommandEncoderError::ProtectedTexture(id.0));
+                }
+            }
+        }
+        if reusable {
+            if !self.support_reusable {
+                return Err(MissingFeatures(wgt::Features::REUSABLE_COMMAND_BUFFERS).into());
+            }
+            if !self.encoder.reusable {
+                return Err(CommandEncoderError::NotReusable);
+            }
+        } else if self.additional_queue.is_none() {
+            return Ok(());
+        }
+        for id in self.trackers.textures.used() {
+            if let TextureInner::Surface { .. } = texture_guard[id].inner {
+                return Err(if reusable {
+                    CommandEncoderError::ReusableSurfaceTexture(id.0)
+                } else {
+                    CommandEncoderError::AdditionalQueueSurfaceTexture(id.0)
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the queue the command buffer is recorded for supports
+    /// `operation`.
+    ///
+    /// The main queue supports every operation, additional queues only
+    /// support it if their kind is in `kinds`.
+    pub(crate) fn require_queue(
+        &self,
+        operation: &'static str,
+        kinds: &[wgt::QueueKind],
+    ) -> Result<(), IncompatibleQueueError> {
+        match self.additional_queue {
+            Some((_, kind)) if !kinds.contains(&kind) => {
+                Err(IncompatibleQueueError { operation, kind })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn raw_command_buffers(&self) -> &[A::CommandBuffer] {
+        &self.encoder.list
+    }
+
     pub fn is_finished(&self) -> bool {
         match self.status {
             CommandEncoderStatus::Finished => true,
@@ -231,13 +370,20 @@ impl<A: HalApi> CommandBuffer<A> {
         }
     }
 
-    pub(crate) fn into_baked(self) -> BakedCommands<A> {
+    pub(crate) fn into_baked(mut self) -> BakedCommands<A> {
+        // Left over if recording the render pass failed.
+        if let Some(draws) = self.indirect_draws.take() {
+            self.temp_resources.push(draws.into_temp_resource());
+        }
         BakedCommands {
             encoder: self.encoder.raw,
             list: self.encoder.list,
             trackers: self.trackers,
             buffer_memory_init_actions: self.buffer_memory_init_actions,
             texture_memory_actions: self.texture_memory_actions,
+            temp_resources: self.temp_resources,
+            protected: self.protected,
+            additional_queue: self.additional_queue.map(|(index, _)| index),
         }
     }
 }
@@ -275,7 +421,7 @@ pub struct BasePassRef<'a, C> {
 /// [`SetBindGroup`]: RenderCommand::SetBindGroup
 /// [`InsertDebugMarker`]: RenderCommand::InsertDebugMarker
 #[doc(hidden)]
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(
     any(feature = "serial-pass", feature = "trace"),
     derive(serde::Serialize)
@@ -349,29 +495,73 @@ pub enum CommandEncoderError {
     Invalid,
     #[error("Command encoder must be active")]
     NotRecording,
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+    #[error("Reusable command buffers can't use the surface texture {0:?}")]
+    ReusableSurfaceTexture(id::TextureId),
+    #[error("Command encoder wasn't created as reusable")]
+    NotReusable,
+    #[error("Command buffers of additional queues can't use the surface texture {0:?}")]
+    AdditionalQueueSurfaceTexture(id::TextureId),
+    #[error("Command encoder isn't protected, so it can't use the protected buffer {0:?}")]
+    ProtectedBuffer(id::BufferId),
+    #[error("Command encoder isn't protected, so it can't use the protected texture {0:?}")]
+    ProtectedTexture(id::TextureId),
+}
+
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum CreateCommandEncoderError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+    #[error("Device has no additional queue {0}")]
+    InvalidAdditionalQueue(usize),
+    #[error("Command encoders of additional queues can't be protected")]
+    ProtectedAdditionalQueue,
+}
+
+/// An operation was recorded for a queue that can't execute it.
+#[derive(Clone, Debug, Error)]
+#[error("{operation} is not supported on {kind:?} queues")]
+pub struct IncompatibleQueueError {
+    pub operation: &'static str,
+    pub kind: wgt::QueueKind,
 }
 
 impl<G: GlobalIdentityHandlerFactory> Global<G> {
     pub fn command_encoder_finish<A: HalApi>(
         &self,
         encoder_id: id::CommandEncoderId,
-        _desc: &wgt::CommandBufferDescriptor<Label>,
+        desc: &wgt::CommandBufferDescriptor<Label>,
     ) -> (id::CommandBufferId, Option<CommandEncoderError>) {
         profiling::scope!("CommandEncoder::finish");
 
         let hub = A::hub(self);
         let mut token = Token::root();
-        let (mut cmd_buf_guard, _) = hub.command_buffers.write(&mut token);
+        let (mut cmd_buf_guard, mut token) = hub.command_buffers.write(&mut token);
+        let (buffer_guard, mut token) = hub.buffers.read(&mut token);
+        let (texture_guard, _) = hub.textures.read(&mut token);
 
         let error = match cmd_buf_guard.get_mut(encoder_id) {
             Ok(cmd_buf) => match cmd_buf.status {
                 CommandEncoderStatus::Recording => {
                     cmd_buf.encoder.close();
-                    cmd_buf.status = CommandEncoderStatus::Finished;
-                    //Note: if we want to stop tracking the swapchain texture view,
-                    // this is the place to do it.
-                    log::trace!("Command buffer {:?}", encoder_id);
-                    None
+                    match cmd_buf.validate_finish(desc.reusable, &buffer_guard, &texture_guard) {
+                        Ok(()) => {
+                            cmd_buf.status = CommandEncoderStatus::Finished;
+                            cmd_buf.reusable = desc.reusable;
+                            //Note: if we want to stop tracking the swapchain texture view,
+                            // this is the place to do it.
+                            log::trace!("Command buffer {:?}", encoder_id);
+                            None
+                        }
+                        Err(e) => {
+                            cmd_buf.status = CommandEncoderStatus::Error;
+                            Some(e)
+                        }
+                    }
                 }
                 CommandEncoderStatus::Finished => Some(CommandEncoderError::NotRecording),
                 CommandEncoderStatus::Error => {
@@ -584,6 +774,10 @@ pub enum PassErrorScope {
     SetViewport,
     #[error("In a set_scissor_rect command")]
     SetScissorRect,
+    #[error("In a set_shading_rate command")]
+    SetShadingRate,
+    #[error("In a set_depth_bounds command")]
+    SetDepthBounds,
     #[error("In a draw command, indexed:{indexed} indirect:{indirect}")]
     Draw {
         indexed: bool,
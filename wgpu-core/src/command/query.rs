@@ -3,7 +3,7 @@ use hal::CommandEncoder as _;
 #[cfg(feature = "trace")]
 use crate::device::trace::Command as TraceCommand;
 use crate::{
-    command::{CommandBuffer, CommandEncoderError},
+    command::{CommandBuffer, CommandEncoderError, IncompatibleQueueError},
     global::Global,
     hal_api::HalApi,
     hub::Token,
@@ -107,6 +107,8 @@ impl From<wgt::QueryType> for SimplifiedQueryType {
 pub enum QueryError {
     #[error(transparent)]
     Encoder(#[from] CommandEncoderError),
+    #[error(transparent)]
+    IncompatibleQueue(#[from] IncompatibleQueueError),
     #[error("Error encountered while trying to use queries")]
     Use(#[from] QueryUseError),
     #[error("Error encountered while trying to resolve a query")]
@@ -147,6 +149,8 @@ pub enum QueryUseError {
     },
     #[error("Query was stopped while there was no active query")]
     AlreadyStopped,
+    #[error("Query {query_index} was started but not stopped before the end of the pass")]
+    NotStopped { query_index: u32 },
     #[error("A query of type {query_type:?} was started using a query set of type {set_type:?}")]
     IncompatibleType {
         set_type: SimplifiedQueryType,
@@ -162,6 +166,10 @@ pub enum ResolveError {
     MissingBufferUsage,
     #[error("Resolve buffer offset has to be aligned to `QUERY_RESOLVE_BUFFER_ALIGNMENT")]
     BufferOffsetAlignment,
+    #[error(
+        "Protected command buffers can only resolve queries to buffers with the PROTECTED usage"
+    )]
+    UnprotectedBuffer,
     #[error("Resolving queries {start_query}..{end_query} would overrun the query set of size {query_set_size}")]
     QueryOverrun {
         start_query: u32,
@@ -357,6 +365,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let (query_set_guard, _) = hub.query_sets.read(&mut token);
 
         let cmd_buf = CommandBuffer::get_encoder_mut(&mut cmd_buf_guard, command_encoder_id)?;
+        cmd_buf.require_queue("Writing timestamps", &[wgt::QueueKind::Compute])?;
         let raw_encoder = cmd_buf.encoder.open();
 
         #[cfg(feature = "trace")]
@@ -395,6 +404,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let (buffer_guard, _) = hub.buffers.read(&mut token);
 
         let cmd_buf = CommandBuffer::get_encoder_mut(&mut cmd_buf_guard, command_encoder_id)?;
+        cmd_buf.require_queue("Resolving query sets", &[wgt::QueueKind::Compute])?;
         let raw_encoder = cmd_buf.encoder.open();
 
         #[cfg(feature = "trace")]
@@ -428,6 +438,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         if !dst_buffer.usage.contains(wgt::BufferUsages::QUERY_RESOLVE) {
             return Err(ResolveError::MissingBufferUsage.into());
         }
+        if cmd_buf.protected && !dst_buffer.usage.contains(wgt::BufferUsages::PROTECTED) {
+            return Err(ResolveError::UnprotectedBuffer.into());
+        }
 
         let end_query = start_query + query_count;
         if end_query > query_set.desc.count {
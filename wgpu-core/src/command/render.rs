@@ -6,11 +6,12 @@ use crate::{
         end_occlusion_query, end_pipeline_statistics_query,
         memory_init::{fixup_discarded_surfaces, SurfacesInDiscardState},
         BasePass, BasePassRef, BindGroupStateChange, CommandBuffer, CommandEncoderError,
-        CommandEncoderStatus, DrawError, ExecutionError, MapPassErr, PassErrorScope, QueryUseError,
-        RenderCommand, RenderCommandError, StateChange,
+        CommandEncoderStatus, DrawError, ExecutionError, IncompatibleQueueError, MapPassErr,
+        PassErrorScope, QueryUseError, RenderCommand, RenderCommandError, SimplifiedQueryType,
+        StateChange,
     },
     device::{
-        AttachmentData, Device, MissingDownlevelFlags, MissingFeatures,
+        AttachmentData, Device, DeviceError, MissingDownlevelFlags, MissingFeatures,
         RenderPassCompatibilityCheckType, RenderPassCompatibilityError, RenderPassContext,
     },
     error::{ErrorFormatter, PrettyError},
@@ -19,6 +20,7 @@ use crate::{
     hub::Token,
     id,
     identity::GlobalIdentityHandlerFactory,
+    indirect_validation::DrawLimits,
     init_tracker::{MemoryInitKind, TextureInitRange, TextureInitTrackerAction},
     pipeline::{self, PipelineFlags},
     resource::{Buffer, QuerySet, Texture, TextureView, TextureViewNotRenderableReason},
@@ -121,6 +123,8 @@ impl<V> PassChannel<V> {
 pub struct RenderPassColorAttachment {
     /// The view to use as an attachment.
     pub view: id::TextureViewId,
+    /// The depth slice to render to, required if and only if `view` is a 3D texture view.
+    pub depth_slice: Option<u32>,
     /// The view that will receive the resolved output if multisampling is used.
     pub resolve_target: Option<id::TextureViewId>,
     /// What operations will be performed on this color attachment.
@@ -421,6 +425,24 @@ struct State {
 }
 
 impl State {
+    /// Number of vertices, or indices for indexed draws, that can be drawn.
+    fn draw_limits(&self, indexed: bool) -> DrawLimits {
+        let max_index = match self.index.format {
+            _ if self.vertex.vertex_limit == u32::MAX => u32::MAX,
+            Some(IndexFormat::Uint16) => u16::MAX as u32,
+            Some(IndexFormat::Uint32) | None => u32::MAX,
+        };
+        DrawLimits {
+            count: match indexed {
+                false => self.vertex.vertex_limit,
+                true => self.index.limit,
+            },
+            instance: self.vertex.instance_limit,
+            vertex: self.vertex.vertex_limit,
+            max_index,
+        }
+    }
+
     fn is_ready<A: hal::Api>(
         &self,
         indexed: bool,
@@ -486,6 +508,7 @@ impl State {
 pub enum AttachmentErrorLocation {
     Color { index: usize, resolve: bool },
     Depth,
+    Stencil,
 }
 
 impl fmt::Display for AttachmentErrorLocation {
@@ -503,6 +526,9 @@ impl fmt::Display for AttachmentErrorLocation {
                 "color attachment at index {index}'s resolve texture view"
             ),
             AttachmentErrorLocation::Depth => write!(f, "depth attachment's texture view"),
+            AttachmentErrorLocation::Stencil => {
+                write!(f, "stencil aspect of the depth attachment's texture view")
+            }
         }
     }
 }
@@ -514,6 +540,18 @@ pub enum ColorAttachmentError {
     InvalidFormat(wgt::TextureFormat),
     #[error("The number of color attachments {given} exceeds the limit {limit}")]
     TooMany { given: usize, limit: usize },
+    #[error("A depth slice must be given to render to a 3D texture view")]
+    MissingDepthSlice,
+    #[error("A depth slice can only be given for 3D texture views")]
+    UnsupportedDepthSlice,
+    #[error("Depth slice {given} is out of bounds for a 3D texture view of depth {limit}")]
+    DepthSliceLimit { given: u32, limit: u32 },
+    #[error("Depth slice {depth_slice} of mip level {mip_level} of texture view {view:?} is used by more than one color attachment")]
+    SubresourceOverlap {
+        view: id::TextureViewId,
+        mip_level: u32,
+        depth_slice: u32,
+    },
 }
 
 /// Error encountered when performing a render pass.
@@ -523,6 +561,10 @@ pub enum RenderPassErrorInner {
     ColorAttachment(#[from] ColorAttachmentError),
     #[error(transparent)]
     Encoder(#[from] CommandEncoderError),
+    #[error(transparent)]
+    IncompatibleQueue(#[from] IncompatibleQueueError),
+    #[error(transparent)]
+    Device(#[from] DeviceError),
     #[error("Attachment texture view {0:?} is invalid")]
     InvalidAttachment(id::TextureViewId),
     #[error("The format of the depth-stencil attachment ({0:?}) is not a depth-stencil format")]
@@ -572,6 +614,14 @@ pub enum RenderPassErrorInner {
     SurfaceTextureDropped,
     #[error("Not enough memory left")]
     OutOfMemory,
+    #[error("The {location} is transient, so it must be cleared on load, discarded on store and can't be read-only or a resolve target")]
+    InvalidTransientAttachment { location: AttachmentErrorLocation },
+    #[error("The {location} is missing the `PROTECTED` usage flag, so protected command buffers can't write to it")]
+    UnprotectedAttachment { location: AttachmentErrorLocation },
+    #[error("Bind group {0:?} writes to resources without the `PROTECTED` usage flag, so protected command buffers can't use it")]
+    UnprotectedBindGroup(id::BindGroupId),
+    #[error("Render bundle {0:?} writes to resources without the `PROTECTED` usage flag, so protected command buffers can't execute it")]
+    UnprotectedBundle(id::RenderBundleId),
     #[error("Unable to clear non-present/read-only depth")]
     InvalidDepthOps,
     #[error("Unable to clear non-present/read-only stencil")]
@@ -582,6 +632,8 @@ pub enum RenderPassErrorInner {
     MissingFeatures(#[from] MissingFeatures),
     #[error(transparent)]
     MissingDownlevelFlags(#[from] MissingDownlevelFlags),
+    #[error("Indirect buffer offset {0:?} is not a multiple of 4")]
+    UnalignedIndirectBufferOffset(BufferAddress),
     #[error("Indirect draw uses bytes {offset}..{end_offset} {} which overruns indirect buffer of size {buffer_size}",
         count.map_or_else(String::new, |v| format!("(using count {v})")))]
     IndirectBufferOverrun {
@@ -596,6 +648,10 @@ pub enum RenderPassErrorInner {
         end_count_offset: u64,
         count_buffer_size: u64,
     },
+    #[error("Indirect draws can't be validated on this device without the `wgsl` feature")]
+    IndirectValidationUnavailable,
+    #[error("Render pass has {0} indirect draws, more than can be validated")]
+    TooManyIndirectDraws(u64),
     #[error("Cannot pop debug group, because number of pushed debug groups is zero")]
     InvalidPopDebugGroup,
     #[error(transparent)]
@@ -756,6 +812,37 @@ impl<'a, A: HalApi> RenderPassInfo<'a, A> {
         }
     }
 
+    /// Transient textures don't keep their contents outside of a render pass,
+    /// and protected command buffers can only write to protected textures.
+    fn check_attachment_usage<V>(
+        channel: Option<&PassChannel<V>>,
+        view: &TextureView<A>,
+        texture_guard: &Storage<Texture<A>, id::TextureId>,
+        protected: bool,
+        location: AttachmentErrorLocation,
+    ) -> Result<(), RenderPassErrorInner> {
+        let usage = texture_guard[view.parent_id.value].desc.usage;
+        // Resolve targets are always written
+        let is_written = channel.map_or(true, |channel| !channel.read_only);
+        if protected && is_written && !usage.contains(wgt::TextureUsages::PROTECTED) {
+            return Err(RenderPassErrorInner::UnprotectedAttachment { location });
+        }
+        let is_transient = usage.contains(wgt::TextureUsages::TRANSIENT);
+        let is_valid = match channel {
+            Some(channel) => {
+                channel.load_op == LoadOp::Clear
+                    && channel.store_op == StoreOp::Discard
+                    && !channel.read_only
+            }
+            // Resolve targets are always stored
+            None => false,
+        };
+        if is_transient && !is_valid {
+            return Err(RenderPassErrorInner::InvalidTransientAttachment { location });
+        }
+        Ok(())
+    }
+
     fn start(
         device: &Device<A>,
         label: Option<&str>,
@@ -777,6 +864,7 @@ impl<'a, A: HalApi> RenderPassInfo<'a, A> {
         let mut is_depth_read_only = false;
         let mut is_stencil_read_only = false;
 
+        let protected = cmd_buf.protected;
         let mut render_attachments = AttachmentDataVec::<RenderAttachment>::new();
         let mut discarded_surfaces = AttachmentDataVec::new();
         let mut pending_discard_init_fixups = SurfacesInDiscardState::new();
@@ -871,6 +959,24 @@ impl<'a, A: HalApi> RenderPassInfo<'a, A> {
                     view.desc.format,
                 ));
             }
+            if ds_aspects.contains(hal::FormatAspects::DEPTH) {
+                Self::check_attachment_usage(
+                    Some(&at.depth),
+                    view,
+                    texture_guard,
+                    protected,
+                    AttachmentErrorLocation::Depth,
+                )?;
+            }
+            if ds_aspects.contains(hal::FormatAspects::STENCIL) {
+                Self::check_attachment_usage(
+                    Some(&at.stencil),
+                    view,
+                    texture_guard,
+                    protected,
+                    AttachmentErrorLocation::Stencil,
+                )?;
+            }
 
             if !ds_aspects.contains(hal::FormatAspects::STENCIL)
                 || (at.stencil.load_op == at.depth.load_op
@@ -988,6 +1094,7 @@ impl<'a, A: HalApi> RenderPassInfo<'a, A> {
             });
         }
 
+        let mut depth_slices = ArrayVec::<_, { hal::MAX_COLOR_ATTACHMENTS }>::new();
         for (index, attachment) in color_attachments.iter().enumerate() {
             let at = if let Some(attachment) = attachment.as_ref() {
                 attachment
@@ -1018,14 +1125,69 @@ impl<'a, A: HalApi> RenderPassInfo<'a, A> {
                     ColorAttachmentError::InvalidFormat(color_view.desc.format),
                 ));
             }
-
-            Self::add_pass_texture_init_actions(
-                &at.channel,
-                &mut cmd_buf.texture_memory_actions,
+            Self::check_attachment_usage(
+                Some(&at.channel),
                 color_view,
                 texture_guard,
-                &mut pending_discard_init_fixups,
-            );
+                protected,
+                AttachmentErrorLocation::Color {
+                    index,
+                    resolve: false,
+                },
+            )?;
+
+            let mut hal_ops = at.channel.hal_ops();
+            if color_view.desc.dimension == TextureViewDimension::D3 {
+                let slice = at
+                    .depth_slice
+                    .ok_or(ColorAttachmentError::MissingDepthSlice)?;
+                let texture = &texture_guard[color_view.parent_id.value];
+                let mip_level = color_view.selector.mips.start;
+                let depth = (texture.desc.size.depth_or_array_layers >> mip_level).max(1);
+                if slice >= depth {
+                    return Err(ColorAttachmentError::DepthSliceLimit {
+                        given: slice,
+                        limit: depth,
+                    }
+                    .into());
+                }
+                let subresource = (color_view.parent_id.value, mip_level, slice);
+                if depth_slices.contains(&subresource) {
+                    return Err(ColorAttachmentError::SubresourceOverlap {
+                        view: at.view,
+                        mip_level,
+                        depth_slice: slice,
+                    }
+                    .into());
+                }
+                depth_slices.push(subresource);
+
+                // Init state is tracked for whole mip levels of 3D textures, so
+                // rendering to one slice needs the rest of the volume initialized
+                // and can't discard it.
+                pending_discard_init_fixups.extend(
+                    cmd_buf.texture_memory_actions.register_init_action(
+                        &TextureInitTrackerAction {
+                            id: color_view.parent_id.value.0,
+                            range: TextureInitRange::from(color_view.selector.clone()),
+                            kind: MemoryInitKind::NeedsInitializedMemory,
+                        },
+                        texture_guard,
+                    ),
+                );
+                hal_ops |= hal::AttachmentOps::STORE;
+            } else {
+                if at.depth_slice.is_some() {
+                    return Err(ColorAttachmentError::UnsupportedDepthSlice.into());
+                }
+                Self::add_pass_texture_init_actions(
+                    &at.channel,
+                    &mut cmd_buf.texture_memory_actions,
+                    color_view,
+                    texture_guard,
+                    &mut pending_discard_init_fixups,
+                );
+            }
             render_attachments
                 .push(color_view.to_render_attachment(hal::TextureUses::COLOR_TARGET));
 
@@ -1043,6 +1205,13 @@ impl<'a, A: HalApi> RenderPassInfo<'a, A> {
                     index,
                     resolve: true,
                 };
+                Self::check_attachment_usage::<()>(
+                    None,
+                    resolve_view,
+                    texture_guard,
+                    protected,
+                    resolve_location,
+                )?;
 
                 let render_extent = resolve_view.render_extent.map_err(|reason| {
                     RenderPassErrorInner::TextureViewIsNotRenderable {
@@ -1102,8 +1271,9 @@ impl<'a, A: HalApi> RenderPassInfo<'a, A> {
                     view: &color_view.raw,
                     usage: hal::TextureUses::COLOR_TARGET,
                 },
+                depth_slice: at.depth_slice,
                 resolve_target: hal_resolve_target,
-                ops: at.channel.hal_ops(),
+                ops: hal_ops,
                 clear_value: at.channel.clear_value,
             }));
         }
@@ -1169,6 +1339,14 @@ impl<'a, A: HalApi> RenderPassInfo<'a, A> {
                 .add_single(query_set_guard, occlusion_query_set)
                 .ok_or(RenderPassErrorInner::InvalidQuerySet(occlusion_query_set))?;
 
+            if !matches!(query_set.desc.ty, wgt::QueryType::Occlusion) {
+                return Err(QueryUseError::IncompatibleType {
+                    set_type: query_set.desc.ty.into(),
+                    query_type: SimplifiedQueryType::Occlusion,
+                }
+                .into());
+            }
+
             Some(&query_set.raw)
         } else {
             None
@@ -1283,6 +1461,49 @@ impl<'a, A: HalApi> RenderPassInfo<'a, A> {
     }
 }
 
+fn check_viewport(
+    rect: &command::Rect<f32>,
+    depth_min: f32,
+    depth_max: f32,
+    extent: wgt::Extent3d,
+) -> Result<hal::Rect<f32>, RenderCommandError> {
+    if rect.x < 0.0
+        || rect.y < 0.0
+        || rect.w <= 0.0
+        || rect.h <= 0.0
+        || rect.x + rect.w > extent.width as f32
+        || rect.y + rect.h > extent.height as f32
+    {
+        return Err(RenderCommandError::InvalidViewportRect(*rect, extent));
+    }
+    if !(0.0..=1.0).contains(&depth_min) || !(0.0..=1.0).contains(&depth_max) {
+        return Err(RenderCommandError::InvalidViewportDepth(
+            depth_min, depth_max,
+        ));
+    }
+    Ok(hal::Rect {
+        x: rect.x,
+        y: rect.y,
+        w: rect.w,
+        h: rect.h,
+    })
+}
+
+fn check_scissor(
+    rect: &command::Rect<u32>,
+    extent: wgt::Extent3d,
+) -> Result<hal::Rect<u32>, RenderCommandError> {
+    if rect.x + rect.w > extent.width || rect.y + rect.h > extent.height {
+        return Err(RenderCommandError::InvalidScissorRect(*rect, extent));
+    }
+    Ok(hal::Rect {
+        x: rect.x,
+        y: rect.y,
+        w: rect.w,
+        h: rect.h,
+    })
+}
+
 // Common routines between render/compute
 
 impl<G: GlobalIdentityHandlerFactory> Global<G> {
@@ -1326,6 +1547,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             let cmd_buf: &mut CommandBuffer<A> =
                 CommandBuffer::get_encoder_mut(&mut *cmb_guard, encoder_id)
                     .map_pass_err(init_scope)?;
+            cmd_buf
+                .require_queue("Render passes", &[])
+                .map_pass_err(init_scope)?;
 
             // We automatically keep extending command buffers over time, and because
             // we want to insert a command buffer _before_ what we're about to record,
@@ -1346,7 +1570,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             }
 
             let device = &device_guard[cmd_buf.device_id.value];
-            cmd_buf.encoder.open_pass(base.label);
+            let hal_label = crate::hal_label(base.label, device.instance_flags);
+            cmd_buf.encoder.open_pass(hal_label);
 
             let (bundle_guard, mut token) = hub.render_bundles.read(&mut token);
             let (pipeline_layout_guard, mut token) = hub.pipeline_layouts.read(&mut token);
@@ -1365,7 +1590,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
 
             let mut info = RenderPassInfo::start(
                 device,
-                base.label,
+                hal_label,
                 color_attachments,
                 depth_stencil_attachment,
                 timestamp_writes,
@@ -1390,6 +1615,13 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Some(&*query_set_guard),
             );
 
+            cmd_buf.indirect_draws = match device.indirect_validation {
+                Some(ref indirect_validation) => indirect_validation
+                    .create_draws(&device.raw, base.commands, &bundle_guard, cmd_buf.protected)
+                    .map_pass_err(init_scope)?,
+                None => None,
+            };
+
             let raw = &mut cmd_buf.encoder.raw;
 
             let mut state = State {
@@ -1431,6 +1663,36 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         );
                         dynamic_offset_count += num_dynamic_offsets as usize;
 
+                        // Setting the same group again only changes its dynamic offsets:
+                        // its resources were already tracked when it was first set.
+                        if state
+                            .binder
+                            .is_assigned(index as usize, id::Valid(bind_group_id))
+                        {
+                            let bind_group = &bind_group_guard[id::Valid(bind_group_id)];
+                            bind_group
+                                .validate_dynamic_bindings(index, &temp_offsets, &cmd_buf.limits)
+                                .map_pass_err(scope)?;
+
+                            let pipeline_layout_id = state.binder.pipeline_layout_id;
+                            if let Some(e) = state
+                                .binder
+                                .assign_dynamic_offsets(index as usize, &temp_offsets)
+                            {
+                                let pipeline_layout =
+                                    &pipeline_layout_guard[pipeline_layout_id.unwrap()].raw;
+                                unsafe {
+                                    raw.set_bind_group_dynamic_offsets(
+                                        pipeline_layout,
+                                        index,
+                                        &bind_group.raw,
+                                        &e.dynamic_offsets,
+                                    );
+                                }
+                            }
+                            continue;
+                        }
+
                         let bind_group: &crate::binding_model::BindGroup<A> = cmd_buf
                             .trackers
                             .bind_groups
@@ -1440,6 +1702,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         bind_group
                             .validate_dynamic_bindings(index, &temp_offsets, &cmd_buf.limits)
                             .map_pass_err(scope)?;
+                        if cmd_buf.protected && bind_group.writes_unprotected {
+                            return Err(RenderPassErrorInner::UnprotectedBindGroup(bind_group_id))
+                                .map_pass_err(scope);
+                        }
 
                         // merge the resource tracker in
                         unsafe {
@@ -1534,6 +1800,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                 raw.set_stencil_reference(state.stencil_reference);
                             }
                         }
+                        if let Some(bounds) = pipeline.depth_bounds {
+                            unsafe {
+                                raw.set_depth_bounds(bounds.min..bounds.max);
+                            }
+                        }
 
                         // Rebind resource
                         if state.binder.pipeline_layout_id != Some(pipeline.layout_id.value) {
@@ -1715,6 +1986,28 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             raw.set_blend_constants(&array);
                         }
                     }
+                    RenderCommand::SetShadingRate(rate) => {
+                        let scope = PassErrorScope::SetShadingRate;
+                        device
+                            .require_features(wgt::Features::SHADING_RATE)
+                            .map_pass_err(scope)?;
+                        unsafe {
+                            raw.set_shading_rate(rate);
+                        }
+                    }
+                    RenderCommand::SetDepthBounds { min, max } => {
+                        let scope = PassErrorScope::SetDepthBounds;
+                        device
+                            .require_features(wgt::Features::DEPTH_BOUNDS_TEST)
+                            .map_pass_err(scope)?;
+                        if !(0.0..=1.0).contains(&min) || !(min..=1.0).contains(&max) {
+                            return Err(RenderCommandError::InvalidDepthBounds(min, max))
+                                .map_pass_err(scope);
+                        }
+                        unsafe {
+                            raw.set_depth_bounds(min..max);
+                        }
+                    }
                     RenderCommand::SetStencilReference(value) => {
                         state.stencil_reference = value;
                         if state
@@ -1732,33 +2025,30 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         depth_max,
                     } => {
                         let scope = PassErrorScope::SetViewport;
-                        if rect.x < 0.0
-                            || rect.y < 0.0
-                            || rect.w <= 0.0
-                            || rect.h <= 0.0
-                            || rect.x + rect.w > info.extent.width as f32
-                            || rect.y + rect.h > info.extent.height as f32
-                        {
-                            return Err(RenderCommandError::InvalidViewportRect(
-                                *rect,
-                                info.extent,
-                            ))
-                            .map_pass_err(scope);
+                        let r = check_viewport(rect, depth_min, depth_max, info.extent)
+                            .map_pass_err(scope)?;
+                        unsafe {
+                            raw.set_viewport(&r, depth_min..depth_max);
                         }
-                        if !(0.0..=1.0).contains(&depth_min) || !(0.0..=1.0).contains(&depth_max) {
-                            return Err(RenderCommandError::InvalidViewportDepth(
-                                depth_min, depth_max,
-                            ))
-                            .map_pass_err(scope);
+                    }
+                    RenderCommand::SetViewportIndexed {
+                        index,
+                        ref rect,
+                        depth_min,
+                        depth_max,
+                    } => {
+                        let scope = PassErrorScope::SetViewport;
+                        device
+                            .require_features(wgt::Features::MULTI_VIEWPORT)
+                            .map_pass_err(scope)?;
+                        if index >= wgt::MAX_VIEWPORTS {
+                            return Err(RenderCommandError::InvalidViewportIndex(index))
+                                .map_pass_err(scope);
                         }
-                        let r = hal::Rect {
-                            x: rect.x,
-                            y: rect.y,
-                            w: rect.w,
-                            h: rect.h,
-                        };
+                        let r = check_viewport(rect, depth_min, depth_max, info.extent)
+                            .map_pass_err(scope)?;
                         unsafe {
-                            raw.set_viewport(&r, depth_min..depth_max);
+                            raw.set_viewport_indexed(index, &r, depth_min..depth_max);
                         }
                     }
                     RenderCommand::SetPushConstant {
@@ -1796,20 +2086,23 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     }
                     RenderCommand::SetScissor(ref rect) => {
                         let scope = PassErrorScope::SetScissorRect;
-                        if rect.x + rect.w > info.extent.width
-                            || rect.y + rect.h > info.extent.height
-                        {
-                            return Err(RenderCommandError::InvalidScissorRect(*rect, info.extent))
+                        let r = check_scissor(rect, info.extent).map_pass_err(scope)?;
+                        unsafe {
+                            raw.set_scissor_rect(&r);
+                        }
+                    }
+                    RenderCommand::SetScissorIndexed { index, ref rect } => {
+                        let scope = PassErrorScope::SetScissorRect;
+                        device
+                            .require_features(wgt::Features::MULTI_VIEWPORT)
+                            .map_pass_err(scope)?;
+                        if index >= wgt::MAX_VIEWPORTS {
+                            return Err(RenderCommandError::InvalidViewportIndex(index))
                                 .map_pass_err(scope);
                         }
-                        let r = hal::Rect {
-                            x: rect.x,
-                            y: rect.y,
-                            w: rect.w,
-                            h: rect.h,
-                        };
+                        let r = check_scissor(rect, info.extent).map_pass_err(scope)?;
                         unsafe {
-                            raw.set_scissor_rect(&r);
+                            raw.set_scissor_rect_indexed(index, &r);
                         }
                     }
                     RenderCommand::Draw {
@@ -1931,10 +2224,15 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             .require_downlevel_flags(wgt::DownlevelFlags::INDIRECT_EXECUTION)
                             .map_pass_err(scope)?;
 
+                        // Validated arguments are copied out of the buffer before the pass.
+                        let indirect_use = match cmd_buf.indirect_draws {
+                            Some(_) => hal::BufferUses::COPY_SRC,
+                            None => hal::BufferUses::INDIRECT,
+                        };
                         let indirect_buffer: &Buffer<A> = info
                             .usage_scope
                             .buffers
-                            .merge_single(&*buffer_guard, buffer_id, hal::BufferUses::INDIRECT)
+                            .merge_single(&*buffer_guard, buffer_id, indirect_use)
                             .map_pass_err(scope)?;
                         check_buffer_usage(indirect_buffer.usage, BufferUsages::INDIRECT)
                             .map_pass_err(scope)?;
@@ -1944,6 +2242,13 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             .ok_or(RenderCommandError::DestroyedBuffer(buffer_id))
                             .map_pass_err(scope)?;
 
+                        if offset % 4 != 0 {
+                            return Err(RenderPassErrorInner::UnalignedIndirectBufferOffset(
+                                offset,
+                            ))
+                            .map_pass_err(scope);
+                        }
+
                         let actual_count = count.map_or(1, |c| c.get());
 
                         let end_offset = offset + stride as u64 * actual_count as u64;
@@ -1965,6 +2270,20 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             ),
                         );
 
+                        let (indirect_raw, offset) = match cmd_buf.indirect_draws {
+                            Some(ref mut draws) => {
+                                let offset = draws.add(
+                                    buffer_id,
+                                    offset,
+                                    actual_count,
+                                    indexed,
+                                    state.draw_limits(indexed),
+                                );
+                                (draws.buffer(), offset)
+                            }
+                            None => (indirect_raw, offset),
+                        };
+
                         match indexed {
                             false => unsafe {
                                 raw.draw_indirect(indirect_raw, offset, actual_count);
@@ -2003,10 +2322,15 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             .require_downlevel_flags(wgt::DownlevelFlags::INDIRECT_EXECUTION)
                             .map_pass_err(scope)?;
 
+                        // Validated arguments are copied out of the buffer before the pass.
+                        let indirect_use = match cmd_buf.indirect_draws {
+                            Some(_) => hal::BufferUses::COPY_SRC,
+                            None => hal::BufferUses::INDIRECT,
+                        };
                         let indirect_buffer: &Buffer<A> = info
                             .usage_scope
                             .buffers
-                            .merge_single(&*buffer_guard, buffer_id, hal::BufferUses::INDIRECT)
+                            .merge_single(&*buffer_guard, buffer_id, indirect_use)
                             .map_pass_err(scope)?;
                         check_buffer_usage(indirect_buffer.usage, BufferUsages::INDIRECT)
                             .map_pass_err(scope)?;
@@ -2033,6 +2357,19 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             .ok_or(RenderCommandError::DestroyedBuffer(count_buffer_id))
                             .map_pass_err(scope)?;
 
+                        if offset % 4 != 0 {
+                            return Err(RenderPassErrorInner::UnalignedIndirectBufferOffset(
+                                offset,
+                            ))
+                            .map_pass_err(scope);
+                        }
+                        if count_buffer_offset % 4 != 0 {
+                            return Err(RenderPassErrorInner::UnalignedIndirectBufferOffset(
+                                count_buffer_offset,
+                            ))
+                            .map_pass_err(scope);
+                        }
+
                         let end_offset = offset + stride * max_count as u64;
                         if end_offset > indirect_buffer.size {
                             return Err(RenderPassErrorInner::IndirectBufferOverrun {
@@ -2069,6 +2406,20 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             ),
                         );
 
+                        let (indirect_raw, offset) = match cmd_buf.indirect_draws {
+                            Some(ref mut draws) => {
+                                let offset = draws.add(
+                                    buffer_id,
+                                    offset,
+                                    max_count,
+                                    indexed,
+                                    state.draw_limits(indexed),
+                                );
+                                (draws.buffer(), offset)
+                            }
+                            None => (indirect_raw, offset),
+                        };
+
                         match indexed {
                             false => unsafe {
                                 raw.draw_indirect_count(
@@ -2235,6 +2586,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             )
                             .map_pass_err(scope);
                         }
+                        if cmd_buf.protected && bundle.writes_unprotected {
+                            return Err(RenderPassErrorInner::UnprotectedBundle(bundle_id))
+                                .map_pass_err(scope);
+                        }
 
                         cmd_buf.buffer_memory_init_actions.extend(
                             bundle
@@ -2260,6 +2615,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                 &*bind_group_guard,
                                 &*render_pipeline_guard,
                                 &*buffer_guard,
+                                cmd_buf.indirect_draws.as_mut(),
                             )
                         }
                         .map_err(|e| match e {
@@ -2286,6 +2642,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 }
             }
 
+            if let Some((_, query_index)) = active_query {
+                return Err(QueryUseError::NotStopped { query_index }).map_pass_err(init_scope);
+            }
+
             log::trace!("Merging renderpass into cmd_buf {:?}", encoder_id);
             let (trackers, pending_discard_init_fixups) =
                 info.finish(raw, &*texture_guard).map_pass_err(init_scope)?;
@@ -2328,6 +2688,23 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 &*buffer_guard,
                 &*texture_guard,
             );
+
+            if let Some(draws) = cmd_buf.indirect_draws.take() {
+                let device = &device_guard[cmd_buf.device_id.value];
+                // Only created when the device has an indirect validation.
+                device
+                    .indirect_validation
+                    .as_ref()
+                    .unwrap()
+                    .encode(
+                        &device.raw,
+                        transit,
+                        draws,
+                        &buffer_guard,
+                        &mut cmd_buf.temp_resources,
+                    )
+                    .map_pass_err(PassErrorScope::Pass(encoder_id))?;
+            }
         }
 
         cmd_buf.status = CommandEncoderStatus::Recording;
@@ -2427,6 +2804,21 @@ pub mod render_ffi {
             .push(RenderCommand::SetBlendConstant(*color));
     }
 
+    #[no_mangle]
+    pub extern "C" fn wgpu_render_pass_set_shading_rate(
+        pass: &mut RenderPass,
+        rate: wgt::ShadingRate,
+    ) {
+        pass.base.commands.push(RenderCommand::SetShadingRate(rate));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn wgpu_render_pass_set_depth_bounds(pass: &mut RenderPass, min: f32, max: f32) {
+        pass.base
+            .commands
+            .push(RenderCommand::SetDepthBounds { min, max });
+    }
+
     #[no_mangle]
     pub extern "C" fn wgpu_render_pass_set_stencil_reference(pass: &mut RenderPass, value: u32) {
         pass.base
@@ -2464,6 +2856,40 @@ pub mod render_ffi {
             .push(RenderCommand::SetScissor(Rect { x, y, w, h }));
     }
 
+    #[no_mangle]
+    pub extern "C" fn wgpu_render_pass_set_viewport_indexed(
+        pass: &mut RenderPass,
+        index: u32,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        depth_min: f32,
+        depth_max: f32,
+    ) {
+        pass.base.commands.push(RenderCommand::SetViewportIndexed {
+            index,
+            rect: Rect { x, y, w, h },
+            depth_min,
+            depth_max,
+        });
+    }
+
+    #[no_mangle]
+    pub extern "C" fn wgpu_render_pass_set_scissor_rect_indexed(
+        pass: &mut RenderPass,
+        index: u32,
+        x: u32,
+        y: u32,
+        w: u32,
+        h: u32,
+    ) {
+        pass.base.commands.push(RenderCommand::SetScissorIndexed {
+            index,
+            rect: Rect { x, y, w, h },
+        });
+    }
+
     /// # Safety
     ///
     /// This function is unsafe as there is no guarantee that the given pointer is
@@ -1,7 +1,7 @@
 #[cfg(feature = "trace")]
 use crate::device::trace::Command as TraceCommand;
 use crate::{
-    command::{clear_texture, CommandBuffer, CommandEncoderError},
+    command::{clear_texture, CommandBuffer, CommandEncoderError, IncompatibleQueueError},
     conv,
     device::{Device, MissingDownlevelFlags},
     error::{ErrorFormatter, PrettyError},
@@ -44,12 +44,20 @@ pub enum TransferError {
     InvalidBuffer(BufferId),
     #[error("Texture {0:?} is invalid or destroyed")]
     InvalidTexture(TextureId),
-    #[error("Source and destination cannot be the same buffer")]
-    SameSourceDestinationBuffer,
+    #[error("Copying {size} bytes from offset {source_offset} to offset {destination_offset} within the same buffer overlaps")]
+    OverlappingBufferCopy {
+        source_offset: BufferAddress,
+        destination_offset: BufferAddress,
+        size: BufferAddress,
+    },
     #[error("Source buffer/texture is missing the `COPY_SRC` usage flag")]
     MissingCopySrcUsageFlag,
     #[error("Destination buffer/texture is missing the `COPY_DST` usage flag")]
     MissingCopyDstUsageFlag(Option<BufferId>, Option<TextureId>),
+    #[error("Destination buffer/texture is protected and can't be written by the queue")]
+    ProtectedDestination(Option<BufferId>, Option<TextureId>),
+    #[error("Destination buffer/texture is missing the `PROTECTED` usage flag, so protected command buffers can't write to it")]
+    UnprotectedDestination(Option<BufferId>, Option<TextureId>),
     #[error("Destination texture is missing the `RENDER_ATTACHMENT` usage flag")]
     MissingRenderAttachmentUsageFlag(TextureId),
     #[error("Copy of {start_offset}..{end_offset} would end up overrunning the bounds of the {side:?} buffer of size {buffer_size}")]
@@ -108,6 +116,11 @@ pub enum TransferError {
     CopyDstMissingAspects,
     #[error("Copy aspect must refer to a single aspect of texture format")]
     CopyAspectNotOne,
+    #[error("Source aspect ({src_aspect:?}) and destination aspect ({dst_aspect:?}) must refer to the same plane of the multi-planar format")]
+    CopyPlanesMismatch {
+        src_aspect: wgt::TextureAspect,
+        dst_aspect: wgt::TextureAspect,
+    },
     #[error("Copying from textures with format {format:?} and aspect {aspect:?} is forbidden")]
     CopyFromForbiddenTextureFormat {
         format: wgt::TextureFormat,
@@ -122,6 +135,8 @@ pub enum TransferError {
         "Copying to textures with format {0:?} is forbidden when copying from external texture"
     )]
     ExternalCopyToForbiddenTextureFormat(wgt::TextureFormat),
+    #[error("Converting to color space {0:?} is not supported when copying from a CPU image")]
+    ExternalCopyToUnsupportedColorSpace(wgt::PredefinedColorSpace),
     #[error("The entire texture must be copied when copying from depth texture")]
     InvalidDepthTextureExtent,
     #[error(
@@ -161,7 +176,9 @@ impl PrettyError for TransferError {
             //         ret.push_str(&format_label_line("source", &name));
             //     }
             // }
-            Self::MissingCopyDstUsageFlag(buf_opt, tex_opt) => {
+            Self::MissingCopyDstUsageFlag(buf_opt, tex_opt)
+            | Self::ProtectedDestination(buf_opt, tex_opt)
+            | Self::UnprotectedDestination(buf_opt, tex_opt) => {
                 if let Some(buf) = buf_opt {
                     fmt.buffer_label_with_key(&buf, "destination");
                 }
@@ -181,6 +198,8 @@ pub enum CopyError {
     Encoder(#[from] CommandEncoderError),
     #[error("Copy error")]
     Transfer(#[from] TransferError),
+    #[error(transparent)]
+    IncompatibleQueue(#[from] IncompatibleQueueError),
 }
 
 pub(crate) fn extract_texture_selector<A: hal::Api>(
@@ -356,7 +375,12 @@ pub(crate) fn validate_texture_copy_range(
         },
     )?;
     // physical size can be larger than the virtual
-    let extent = extent_virtual.physical_size(desc.format);
+    let mut extent = extent_virtual.physical_size(desc.format);
+
+    // planes of multi-planar formats may be subsampled
+    let (width_factor, height_factor) = desc.format.subsampling_factors(texture_copy_view.aspect);
+    extent.width /= width_factor;
+    extent.height /= height_factor;
 
     if desc.format.is_depth_stencil_format() && *copy_size != extent {
         return Err(TransferError::InvalidDepthTextureExtent);
@@ -557,9 +581,6 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
     ) -> Result<(), CopyError> {
         profiling::scope!("CommandEncoder::copy_buffer_to_buffer");
 
-        if source == destination {
-            return Err(TransferError::SameSourceDestinationBuffer.into());
-        }
         let hub = A::hub(self);
         let mut token = Token::root();
 
@@ -581,10 +602,16 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             });
         }
 
+        // A buffer copied within itself is used for both sides of the copy at once.
+        let src_usage = if source == destination {
+            hal::BufferUses::COPY_SRC | hal::BufferUses::COPY_DST
+        } else {
+            hal::BufferUses::COPY_SRC
+        };
         let (src_buffer, src_pending) = cmd_buf
             .trackers
             .buffers
-            .set_single(&*buffer_guard, source, hal::BufferUses::COPY_SRC)
+            .set_single(&*buffer_guard, source, src_usage)
             .ok_or(TransferError::InvalidBuffer(source))?;
         let src_raw = src_buffer
             .raw
@@ -596,11 +623,15 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         // expecting only a single barrier
         let src_barrier = src_pending.map(|pending| pending.into_hal(src_buffer));
 
-        let (dst_buffer, dst_pending) = cmd_buf
-            .trackers
-            .buffers
-            .set_single(&*buffer_guard, destination, hal::BufferUses::COPY_DST)
-            .ok_or(TransferError::InvalidBuffer(destination))?;
+        let (dst_buffer, dst_pending) = if source == destination {
+            (src_buffer, None)
+        } else {
+            cmd_buf
+                .trackers
+                .buffers
+                .set_single(&*buffer_guard, destination, hal::BufferUses::COPY_DST)
+                .ok_or(TransferError::InvalidBuffer(destination))?
+        };
         let dst_raw = dst_buffer
             .raw
             .as_ref()
@@ -608,6 +639,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         if !dst_buffer.usage.contains(BufferUsages::COPY_DST) {
             return Err(TransferError::MissingCopyDstUsageFlag(Some(destination), None).into());
         }
+        if cmd_buf.protected && !dst_buffer.usage.contains(BufferUsages::PROTECTED) {
+            return Err(TransferError::UnprotectedDestination(Some(destination), None).into());
+        }
         let dst_barrier = dst_pending.map(|pending| pending.into_hal(dst_buffer));
 
         if size % wgt::COPY_BUFFER_ALIGNMENT != 0 {
@@ -661,6 +695,19 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             .into());
         }
 
+        // Copies within a buffer are fine as long as the ranges are disjoint.
+        if source == destination
+            && source_offset < destination_end_offset
+            && destination_offset < source_end_offset
+        {
+            return Err(TransferError::OverlappingBufferCopy {
+                source_offset,
+                destination_offset,
+                size,
+            }
+            .into());
+        }
+
         if size == 0 {
             log::trace!("Ignoring copy_buffer_to_buffer of size 0");
             return Ok(());
@@ -741,6 +788,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         )?;
 
         let (dst_range, dst_base) = extract_texture_selector(destination, copy_size, dst_texture)?;
+        if dst_texture.desc.format.is_depth_stencil_format() {
+            cmd_buf.require_queue("Copying depth-stencil textures", &[])?;
+        }
 
         // Handle texture init *before* dealing with barrier transitions so we
         // have an easier time inserting "immediate-inits" that may be required
@@ -780,6 +830,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 TransferError::MissingCopyDstUsageFlag(None, Some(destination.texture)).into(),
             );
         }
+        if cmd_buf.protected && !dst_texture.desc.usage.contains(TextureUsages::PROTECTED) {
+            return Err(
+                TransferError::UnprotectedDestination(None, Some(destination.texture)).into(),
+            );
+        }
         let dst_barrier = dst_pending.map(|pending| pending.into_hal(dst_texture));
 
         if !dst_base.aspect.is_one() {
@@ -881,6 +936,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             validate_texture_copy_range(source, &src_texture.desc, CopySide::Source, copy_size)?;
 
         let (src_range, src_base) = extract_texture_selector(source, copy_size, src_texture)?;
+        if src_texture.desc.format.is_depth_stencil_format() {
+            cmd_buf.require_queue("Copying depth-stencil textures", &[])?;
+        }
 
         // Handle texture init *before* dealing with barrier transitions so we
         // have an easier time inserting "immediate-inits" that may be required
@@ -937,6 +995,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 TransferError::MissingCopyDstUsageFlag(Some(destination.buffer), None).into(),
             );
         }
+        if cmd_buf.protected && !dst_buffer.usage.contains(BufferUsages::PROTECTED) {
+            return Err(
+                TransferError::UnprotectedDestination(Some(destination.buffer), None).into(),
+            );
+        }
         let dst_barrier = dst_pending.map(|pending| pending.into_hal(dst_buffer));
 
         if !src_base.aspect.is_one() {
@@ -1053,6 +1116,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             }
             .into());
         }
+        if src_texture.desc.format.is_depth_stencil_format() {
+            cmd_buf.require_queue("Copying depth-stencil textures", &[])?;
+        }
 
         let (src_copy_size, array_layer_count) =
             validate_texture_copy_range(source, &src_texture.desc, CopySide::Source, copy_size)?;
@@ -1066,13 +1132,27 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let (src_range, src_tex_base) = extract_texture_selector(source, copy_size, src_texture)?;
         let (dst_range, dst_tex_base) =
             extract_texture_selector(destination, copy_size, dst_texture)?;
-        let src_texture_aspects = hal::FormatAspects::from(src_texture.desc.format);
-        let dst_texture_aspects = hal::FormatAspects::from(dst_texture.desc.format);
-        if src_tex_base.aspect != src_texture_aspects {
-            return Err(TransferError::CopySrcMissingAspects.into());
-        }
-        if dst_tex_base.aspect != dst_texture_aspects {
-            return Err(TransferError::CopyDstMissingAspects.into());
+        if src_texture.desc.format.is_multi_planar_format() {
+            // planes of multi-planar formats are copied one at a time
+            if !src_tex_base.aspect.is_one() || !dst_tex_base.aspect.is_one() {
+                return Err(TransferError::CopyAspectNotOne.into());
+            }
+            if source.aspect != destination.aspect {
+                return Err(TransferError::CopyPlanesMismatch {
+                    src_aspect: source.aspect,
+                    dst_aspect: destination.aspect,
+                }
+                .into());
+            }
+        } else {
+            let src_texture_aspects = hal::FormatAspects::from(src_texture.desc.format);
+            let dst_texture_aspects = hal::FormatAspects::from(dst_texture.desc.format);
+            if src_tex_base.aspect != src_texture_aspects {
+                return Err(TransferError::CopySrcMissingAspects.into());
+            }
+            if dst_tex_base.aspect != dst_texture_aspects {
+                return Err(TransferError::CopyDstMissingAspects.into());
+            }
         }
 
         // Handle texture init *before* dealing with barrier transitions so we
@@ -1124,6 +1204,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 TransferError::MissingCopyDstUsageFlag(None, Some(destination.texture)).into(),
             );
         }
+        if cmd_buf.protected && !dst_texture.desc.usage.contains(TextureUsages::PROTECTED) {
+            return Err(
+                TransferError::UnprotectedDestination(None, Some(destination.texture)).into(),
+            );
+        }
 
         barriers.extend(dst_pending.map(|pending| pending.into_hal(dst_texture)));
 
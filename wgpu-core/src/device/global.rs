@@ -2,7 +2,9 @@
 use crate::device::trace;
 use crate::{
     binding_model, command, conv,
-    device::{life::WaitIdleError, map_buffer, queue, Device, DeviceError, HostMap},
+    device::{
+        life::WaitIdleError, map_buffer, map_buffer_range, queue, Device, DeviceError, HostMap,
+    },
     global::Global,
     hal_api::HalApi,
     hub::Token,
@@ -13,9 +15,9 @@ use crate::{
     pipeline, present,
     resource::{self, Buffer, BufferAccessResult, BufferMapState},
     resource::{BufferAccessError, BufferMapOperation, TextureClearMode},
-    storage::InvalidId,
+    storage::{InvalidId, Storage},
     validation::check_buffer_usage,
-    FastHashMap, Label, LabelHelpers as _, Stored,
+    FastHashMap, Label, LabelHelpers as _, LifeGuard, Stored,
 };
 
 use hal::{CommandEncoder as _, Device as _};
@@ -25,7 +27,10 @@ use wgt::{BufferAddress, TextureFormat};
 
 use std::{borrow::Cow, iter, mem, ops::Range, ptr};
 
-use super::{BufferMapPendingClosure, ImplicitPipelineIds, InvalidDevice, UserClosures};
+use super::{
+    BufferMapPendingClosure, DeviceLostClosure, DeviceReport, ImplicitPipelineIds, InvalidDevice,
+    UserClosures,
+};
 
 impl<G: GlobalIdentityHandlerFactory> Global<G> {
     pub fn adapter_is_surface_supported<A: HalApi>(
@@ -64,6 +69,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 formats: hal_caps.formats,
                 present_modes: hal_caps.present_modes,
                 alpha_modes: hal_caps.composite_alpha_modes,
+                color_spaces: hal_caps.color_spaces,
                 usages,
             })
         })
@@ -148,6 +154,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(device) => device,
                 Err(_) => break DeviceError::Invalid.into(),
             };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
 
             if desc.usage.is_empty() {
                 // Per spec, `usage` must not be zero.
@@ -177,24 +186,28 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             } else if desc.usage.contains(wgt::BufferUsages::MAP_WRITE) {
                 // buffer is mappable, so we are just doing that at start
                 let map_size = buffer.size;
-                let ptr = if map_size == 0 {
-                    std::ptr::NonNull::dangling()
-                } else {
-                    match map_buffer(&device.raw, &mut buffer, 0, map_size, HostMap::Write) {
-                        Ok(ptr) => ptr,
-                        Err(e) => {
-                            let raw = buffer.raw.unwrap();
-                            device.lock_life(&mut token).schedule_resource_destruction(
-                                queue::TempResource::Buffer(raw),
-                                !0,
-                            );
-                            break e.into();
-                        }
+                let mapping = match map_buffer(&device.raw, &buffer) {
+                    Ok(mapping) => mapping,
+                    Err(e) => {
+                        let raw = buffer.raw.unwrap();
+                        device
+                            .lock_life(&mut token)
+                            .schedule_resource_destruction(queue::TempResource::Buffer(raw), !0);
+                        break e.into();
                     }
                 };
+                map_buffer_range(
+                    &device.raw,
+                    &mut buffer,
+                    &mapping,
+                    0,
+                    map_size,
+                    HostMap::Write,
+                );
                 buffer.map_state = resource::BufferMapState::Active {
-                    ptr,
-                    range: 0..map_size,
+                    mapping,
+                    ranges: iter::once(0..map_size).collect(),
+                    pending: Vec::new(),
                     host: HostMap::Write,
                 };
                 hal::BufferUses::MAP_WRITE
@@ -237,7 +250,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 assert_eq!(buffer.size % wgt::COPY_BUFFER_ALIGNMENT, 0);
                 // Zero initialize memory and then mark both staging and buffer as initialized
                 // (it's guaranteed that this is the case by the time the buffer is usable)
-                unsafe { ptr::write_bytes(mapping.ptr.as_ptr(), 0, buffer.size as usize) };
+                if device.zero_initialize {
+                    unsafe { ptr::write_bytes(mapping.ptr.as_ptr(), 0, buffer.size as usize) };
+                }
                 buffer.initialization_status.drain(0..buffer.size);
                 stage.initialization_status.drain(0..buffer.size);
 
@@ -484,9 +499,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 | &BufferMapState::Active { .. }
                 => {
                     self.buffer_unmap_inner(buffer_id, buffer, device)
-                        .unwrap_or(None)
+                        .unwrap_or_default()
                 }
-                _ => None,
+                _ => Vec::new(),
             };
 
             #[cfg(feature = "trace")]
@@ -512,13 +527,189 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         }
 
         // Note: outside the scope where locks are held when calling the callback
-        if let Some((operation, status)) = map_closure {
+        for (operation, status) in map_closure {
             operation.callback.call(status);
         }
 
         Ok(())
     }
 
+    /// Resizes the buffer, keeping its contents up to the smaller of both sizes.
+    ///
+    /// The buffer is reallocated and its contents are copied over, so the
+    /// bind groups and the unsubmitted command buffers that refer to it must
+    /// be dropped first.
+    pub fn buffer_resize<A: HalApi>(
+        &self,
+        buffer_id: id::BufferId,
+        size: BufferAddress,
+    ) -> Result<(), resource::ResizeBufferError> {
+        profiling::scope!("Buffer::resize");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+
+        let (mut device_guard, mut token) = hub.devices.write(&mut token);
+        let device_id = {
+            let (buffer_guard, _) = hub.buffers.read(&mut token);
+            buffer_guard
+                .get(buffer_id)
+                .map_err(|_| resource::ResizeBufferError::Invalid)?
+                .device_id
+                .value
+        };
+        let device = &mut device_guard[device_id];
+        if !device.is_valid() {
+            return Err(DeviceError::Lost.into());
+        }
+
+        // Release the bind groups and render bundles dropped by the user, so
+        // that only the ones that are still alive count as users of the buffer
+        // below.
+        device.lock_life(&mut token).triage_suspected(
+            hub,
+            &device.trackers,
+            &device.bgl_pool,
+            #[cfg(feature = "trace")]
+            device.trace.as_ref(),
+            &mut token,
+        );
+
+        // Bind groups have the allocation of the buffer in their descriptors,
+        // render bundles have validated their draws against its size, and
+        // command buffers that aren't submitted yet have recorded it. So have
+        // reusable command buffers, which stay registered after they are
+        // submitted and would use the old allocation again when resubmitted.
+        // Submitted work keeps using the old allocation until it is done.
+        let (cmd_buf_guard, mut token) = hub.command_buffers.read(&mut token);
+        let (bundle_guard, mut token) = hub.render_bundles.read(&mut token);
+        let (_, mut token) = hub.pipeline_layouts.read(&mut token); // skip token
+        let (bind_group_guard, mut token) = hub.bind_groups.read(&mut token);
+        let backend = A::VARIANT;
+        let in_use = cmd_buf_guard.iter(backend).any(|(_, cmd_buf)| {
+            cmd_buf.device_id.value == device_id
+                && cmd_buf.trackers.buffers.used().any(|id| id.0 == buffer_id)
+        }) || bundle_guard.iter(backend).any(|(_, bundle)| {
+            bundle.device_id.value == device_id
+                && bundle.used.buffers.used().any(|id| id.0 == buffer_id)
+        }) || bind_group_guard.iter(backend).any(|(_, bind_group)| {
+            bind_group.device_id.value == device_id
+                && bind_group.used.buffers.used().any(|id| id.0 == buffer_id)
+        });
+
+        let (mut buffer_guard, _) = hub.buffers.write(&mut token);
+        let buffer = buffer_guard
+            .get(buffer_id)
+            .map_err(|_| resource::ResizeBufferError::Invalid)?;
+        if buffer.raw.is_none() {
+            return Err(resource::ResizeBufferError::Destroyed);
+        }
+        if !matches!(buffer.map_state, BufferMapState::Idle) {
+            return Err(resource::ResizeBufferError::Mapped);
+        }
+        if !buffer.usage.contains(wgt::BufferUsages::COPY_SRC) {
+            return Err(resource::ResizeBufferError::MissingCopySrcUsage);
+        }
+        if in_use {
+            return Err(resource::ResizeBufferError::InUse);
+        }
+
+        #[cfg(feature = "trace")]
+        if let Some(ref trace) = device.trace {
+            trace
+                .lock()
+                .add(trace::Action::ResizeBuffer(buffer_id, size));
+        }
+
+        let desc = resource::BufferDescriptor {
+            label: Some(Cow::Borrowed(resource::Resource::label(buffer))),
+            size,
+            usage: buffer.usage,
+            mapped_at_creation: false,
+        };
+        let mut resized = device.create_buffer(device_id.0, &desc, false)?;
+        // Both allocations are padded to `COPY_BUFFER_ALIGNMENT`.
+        let copy_size = wgt::math::align_to(buffer.size.min(size), wgt::COPY_BUFFER_ALIGNMENT);
+        // The copied range has the contents of the buffer, initialized or not.
+        resized.initialization_status.drain(0..copy_size);
+
+        let mut trackers = device.trackers.lock();
+        let buffer = buffer_guard.get_mut(buffer_id).unwrap();
+        let uninitialized_ranges = buffer
+            .initialization_status
+            .drain(0..copy_size)
+            .collect::<Vec<_>>();
+        device
+            .pending_writes
+            .previous_uses
+            .insert(buffer.life_guard.life_count());
+        let encoder = device.pending_writes.activate();
+
+        // Zero the uninitialized ranges, so that the copy carries them over.
+        if !uninitialized_ranges.is_empty() {
+            let (buffer, transition) = trackers
+                .buffers
+                .set_single(&buffer_guard, buffer_id, hal::BufferUses::COPY_DST)
+                .unwrap();
+            let raw = buffer.raw.as_ref().unwrap();
+            unsafe {
+                encoder.transition_buffers(
+                    transition
+                        .map(|pending| pending.into_hal(buffer))
+                        .into_iter(),
+                );
+                for range in uninitialized_ranges {
+                    let end = wgt::math::align_to(range.end, wgt::COPY_BUFFER_ALIGNMENT);
+                    encoder.clear_buffer(raw, range.start..end);
+                }
+            }
+        }
+        let (buffer, transition) = trackers
+            .buffers
+            .set_single(&buffer_guard, buffer_id, hal::BufferUses::COPY_SRC)
+            .unwrap();
+        unsafe {
+            encoder.transition_buffers(
+                transition
+                    .map(|pending| pending.into_hal(buffer))
+                    .into_iter(),
+            );
+        }
+
+        let buffer = buffer_guard.get_mut(buffer_id).unwrap();
+        let old_raw = mem::replace(&mut buffer.raw, resized.raw.take()).unwrap();
+        buffer.size = size;
+        buffer.initialization_status = resized.initialization_status;
+        buffer.life_guard.use_at(device.active_submission_index + 1);
+
+        // The new allocation starts out as the destination of the copy.
+        let (buffer, transition) = trackers
+            .buffers
+            .set_single(&buffer_guard, buffer_id, hal::BufferUses::COPY_DST)
+            .unwrap();
+        let raw = buffer.raw.as_ref().unwrap();
+        let region = wgt::BufferSize::new(copy_size).map(|size| hal::BufferCopy {
+            src_offset: 0,
+            dst_offset: 0,
+            size,
+        });
+        unsafe {
+            encoder.transition_buffers(
+                transition
+                    .map(|pending| pending.into_hal(buffer))
+                    .into_iter(),
+            );
+            encoder.copy_buffer_to_buffer(&old_raw, raw, region.into_iter());
+        }
+
+        device.pending_writes.dst_buffers.insert(buffer_id);
+        device
+            .pending_writes
+            .consume_temp(queue::TempResource::Buffer(old_raw));
+
+        Ok(())
+    }
+
     pub fn buffer_drop<A: HalApi>(&self, buffer_id: id::BufferId, wait: bool) {
         profiling::scope!("Buffer::drop");
         log::debug!("buffer {:?} is dropped", buffer_id);
@@ -586,6 +777,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(device) => device,
                 Err(_) => break DeviceError::Invalid.into(),
             };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
             #[cfg(feature = "trace")]
             if let Some(ref trace) = device.trace {
                 trace
@@ -641,6 +835,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(device) => device,
                 Err(_) => break DeviceError::Invalid.into(),
             };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
 
             // NB: Any change done through the raw texture handle will not be
             // recorded in the replay
@@ -717,6 +914,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(device) => device,
                 Err(_) => break DeviceError::Invalid.into(),
             };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
 
             // NB: Any change done through the raw buffer handle will not be
             // recorded in the replay
@@ -978,6 +1178,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(device) => device,
                 Err(_) => break DeviceError::Invalid.into(),
             };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
             #[cfg(feature = "trace")]
             if let Some(ref trace) = device.trace {
                 trace
@@ -1035,6 +1238,114 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             .push(id::Valid(sampler_id));
     }
 
+    /// Creates an acceleration structure from a `wgpu-hal` one, so that it can be bound with
+    /// [`BindingResource::AccelerationStructure`].
+    ///
+    /// # Safety
+    ///
+    /// - `hal_acceleration_structure` must be created from `device_id`'s internal handle.
+    /// - `hal_acceleration_structure` must be built before the bind groups using it are used.
+    ///
+    /// [`BindingResource::AccelerationStructure`]: binding_model::BindingResource::AccelerationStructure
+    pub unsafe fn create_acceleration_structure_from_hal<A: HalApi>(
+        &self,
+        hal_acceleration_structure: A::AccelerationStructure,
+        device_id: DeviceId,
+        desc: &resource::AccelerationStructureDescriptor,
+        id_in: Input<G, id::AccelerationStructureId>,
+    ) -> (
+        id::AccelerationStructureId,
+        Option<resource::CreateAccelerationStructureError>,
+    ) {
+        profiling::scope!("Device::create_acceleration_structure_from_hal");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let fid = hub.acceleration_structures.prepare(id_in);
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let error = loop {
+            let device = match device_guard.get(device_id) {
+                Ok(device) => device,
+                Err(_) => break DeviceError::Invalid.into(),
+            };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
+            if let Err(error) =
+                device.require_features(wgt::Features::RAY_TRACING_ACCELERATION_STRUCTURE)
+            {
+                break error.into();
+            }
+
+            // NB: Acceleration structures are not recorded in traces.
+            let acceleration_structure = resource::AccelerationStructure {
+                raw: hal_acceleration_structure,
+                device_id: Stored {
+                    value: id::Valid(device_id),
+                    ref_count: device.life_guard.add_ref(),
+                },
+                life_guard: LifeGuard::new(desc.label.borrow_or_default()),
+            };
+            let ref_count = acceleration_structure.life_guard.add_ref();
+            let id = fid.assign(acceleration_structure, &mut token);
+
+            device
+                .trackers
+                .lock()
+                .acceleration_structures
+                .insert_single(id, ref_count);
+
+            return (id.0, None);
+        };
+
+        let id = fid.assign_error(desc.label.borrow_or_default(), &mut token);
+        (id, Some(error))
+    }
+
+    pub fn acceleration_structure_label<A: HalApi>(
+        &self,
+        id: id::AccelerationStructureId,
+    ) -> String {
+        A::hub(self).acceleration_structures.label_for_resource(id)
+    }
+
+    pub fn acceleration_structure_drop<A: HalApi>(
+        &self,
+        acceleration_structure_id: id::AccelerationStructureId,
+    ) {
+        profiling::scope!("AccelerationStructure::drop");
+        log::debug!(
+            "acceleration structure {:?} is dropped",
+            acceleration_structure_id
+        );
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+
+        let device_id = {
+            let (mut guard, _) = hub.acceleration_structures.write(&mut token);
+            match guard.get_mut(acceleration_structure_id) {
+                Ok(acceleration_structure) => {
+                    acceleration_structure.life_guard.ref_count.take();
+                    acceleration_structure.device_id.value
+                }
+                Err(InvalidId) => {
+                    hub.acceleration_structures
+                        .unregister_locked(acceleration_structure_id, &mut *guard);
+                    return;
+                }
+            }
+        };
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        device_guard[device_id]
+            .lock_life(&mut token)
+            .suspected_resources
+            .acceleration_structures
+            .push(id::Valid(acceleration_structure_id));
+    }
+
     pub fn device_create_bind_group_layout<A: HalApi>(
         &self,
         device_id: DeviceId,
@@ -1056,6 +1367,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(device) => device,
                 Err(_) => break DeviceError::Invalid.into(),
             };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
             #[cfg(feature = "trace")]
             if let Some(ref trace) = device.trace {
                 trace
@@ -1081,9 +1395,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             let mut compatible_layout = None;
             {
                 let (bgl_guard, _) = hub.bind_group_layouts.read(&mut token);
-                if let Some(id) =
-                    Device::deduplicate_bind_group_layout(device_id, &entry_map, &*bgl_guard)
-                {
+                if let Some(id) = device.deduplicate_bind_group_layout(&entry_map, &*bgl_guard) {
                     // If there is an equivalent BGL, just bump the refcount and return it.
                     // This is only applicable if ids are generated in wgpu. In practice:
                     //  - wgpu users take this branch and return the existing
@@ -1108,6 +1420,12 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Err(e) => break e,
             };
 
+            if compatible_layout.is_none() {
+                device
+                    .bgl_pool
+                    .lock()
+                    .insert(&layout.entries, id::Valid(fid.id()));
+            }
             layout.compatible_layout = compatible_layout;
 
             let id = fid.assign(layout, &mut token);
@@ -1170,6 +1488,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(device) => device,
                 Err(_) => break DeviceError::Invalid.into(),
             };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
             #[cfg(feature = "trace")]
             if let Some(ref trace) = device.trace {
                 trace
@@ -1249,6 +1570,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(device) => device,
                 Err(_) => break DeviceError::Invalid.into(),
             };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
             #[cfg(feature = "trace")]
             if let Some(ref trace) = device.trace {
                 trace
@@ -1351,6 +1675,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(device) => device,
                 Err(_) => break DeviceError::Invalid.into(),
             };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
             #[cfg(feature = "trace")]
             if let Some(ref trace) = device.trace {
                 let mut trace = trace.lock();
@@ -1359,6 +1686,24 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     pipeline::ShaderModuleSource::Wgsl(ref code) => {
                         trace.make_binary("wgsl", code.as_bytes())
                     }
+                    #[cfg(feature = "glsl")]
+                    pipeline::ShaderModuleSource::Glsl(ref code, ref options) => {
+                        // The stage and defines aren't part of the trace, so record
+                        // the parsed module. Shaders that fail to parse can't be
+                        // replayed anyway.
+                        let mut parser = naga::front::glsl::Frontend::default();
+                        match parser.parse(options, code) {
+                            Ok(module) => {
+                                let string = ron::ser::to_string_pretty(
+                                    &module,
+                                    ron::ser::PrettyConfig::default(),
+                                )
+                                .unwrap();
+                                trace.make_binary("ron", string.as_bytes())
+                            }
+                            Err(_) => trace.make_binary("glsl", code.as_bytes()),
+                        }
+                    }
                     pipeline::ShaderModuleSource::Naga(ref module) => {
                         let string =
                             ron::ser::to_string_pretty(module, ron::ser::PrettyConfig::default())
@@ -1416,6 +1761,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(device) => device,
                 Err(_) => break DeviceError::Invalid.into(),
             };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
             #[cfg(feature = "trace")]
             if let Some(ref trace) = device.trace {
                 let mut trace = trace.lock();
@@ -1468,12 +1816,111 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         }
     }
 
+    /// # Safety
+    ///
+    /// `desc.data` is passed to the driver, which may not validate it beyond its header.
+    /// It must have been returned by [`Self::pipeline_cache_get_data`].
+    pub unsafe fn device_create_pipeline_cache<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        desc: &pipeline::PipelineCacheDescriptor,
+        id_in: Input<G, id::PipelineCacheId>,
+    ) -> (
+        id::PipelineCacheId,
+        Option<pipeline::CreatePipelineCacheError>,
+    ) {
+        profiling::scope!("Device::create_pipeline_cache");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let fid = hub.pipeline_caches.prepare(id_in);
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let error = loop {
+            let device = match device_guard.get(device_id) {
+                Ok(device) => device,
+                Err(_) => break DeviceError::Invalid.into(),
+            };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
+            #[cfg(feature = "trace")]
+            if let Some(ref trace) = device.trace {
+                trace.lock().add(trace::Action::CreatePipelineCache {
+                    id: fid.id(),
+                    desc: desc.clone(),
+                });
+            }
+
+            let cache = match unsafe { device.create_pipeline_cache(device_id, desc) } {
+                Ok(cache) => cache,
+                Err(e) => break e,
+            };
+            let id = fid.assign(cache, &mut token);
+            return (id.0, None);
+        };
+
+        let id = fid.assign_error(desc.label.borrow_or_default(), &mut token);
+        (id, Some(error))
+    }
+
+    /// Returns the contents of a pipeline cache, to be passed to
+    /// [`Self::device_create_pipeline_cache`] on a later run.
+    ///
+    /// Returns `None` if the cache is invalid or the backend can't serialize
+    /// pipeline caches.
+    pub fn pipeline_cache_get_data<A: HalApi>(
+        &self,
+        cache_id: id::PipelineCacheId,
+    ) -> Option<Vec<u8>> {
+        profiling::scope!("PipelineCache::get_data");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let (cache_guard, _) = hub.pipeline_caches.read(&mut token);
+        let cache = cache_guard.get(cache_id).ok()?;
+        let device = &device_guard[cache.device_id.value];
+        unsafe { device.raw.pipeline_cache_get_data(&cache.raw) }
+    }
+
+    pub fn pipeline_cache_label<A: HalApi>(&self, id: id::PipelineCacheId) -> String {
+        A::hub(self).pipeline_caches.label_for_resource(id)
+    }
+
+    pub fn pipeline_cache_drop<A: HalApi>(&self, pipeline_cache_id: id::PipelineCacheId) {
+        profiling::scope!("PipelineCache::drop");
+        log::debug!("pipeline cache {:?} is dropped", pipeline_cache_id);
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let (cache, _) = hub
+            .pipeline_caches
+            .unregister(pipeline_cache_id, &mut token);
+        if let Some(cache) = cache {
+            let device = &device_guard[cache.device_id.value];
+            #[cfg(feature = "trace")]
+            if let Some(ref trace) = device.trace {
+                trace
+                    .lock()
+                    .add(trace::Action::DestroyPipelineCache(pipeline_cache_id));
+            }
+            unsafe {
+                device.raw.destroy_pipeline_cache(cache.raw);
+            }
+        }
+    }
+
     pub fn device_create_command_encoder<A: HalApi>(
         &self,
         device_id: DeviceId,
         desc: &wgt::CommandEncoderDescriptor<Label>,
         id_in: Input<G, id::CommandEncoderId>,
-    ) -> (id::CommandEncoderId, Option<DeviceError>) {
+    ) -> (
+        id::CommandEncoderId,
+        Option<command::CreateCommandEncoderError>,
+    ) {
         profiling::scope!("Device::create_command_encoder");
 
         let hub = A::hub(self);
@@ -1484,30 +1931,53 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let error = loop {
             let device = match device_guard.get(device_id) {
                 Ok(device) => device,
-                Err(_) => break DeviceError::Invalid,
+                Err(_) => break DeviceError::Invalid.into(),
             };
-            let dev_stored = Stored {
-                value: id::Valid(device_id),
-                ref_count: device.life_guard.add_ref(),
+            let command_buffer = match device.create_command_buffer(device_id, desc, None) {
+                Ok(command_buffer) => command_buffer,
+                Err(e) => break e,
             };
-            let encoder = match device
-                .command_allocator
-                .lock()
-                .acquire_encoder(&device.raw, &device.queue)
-            {
-                Ok(raw) => raw,
-                Err(_) => break DeviceError::OutOfMemory,
+
+            let id = fid.assign(command_buffer, &mut token);
+            return (id.0, None);
+        };
+
+        let id = fid.assign_error(desc.label.borrow_or_default(), &mut token);
+        (id, Some(error))
+    }
+
+    /// Creates a command encoder whose command buffer is submitted to the
+    /// additional queue `queue_index` of the device.
+    pub fn device_create_command_encoder_for_queue<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        queue_index: usize,
+        desc: &wgt::CommandEncoderDescriptor<Label>,
+        id_in: Input<G, id::CommandEncoderId>,
+    ) -> (
+        id::CommandEncoderId,
+        Option<command::CreateCommandEncoderError>,
+    ) {
+        profiling::scope!("Device::create_command_encoder_for_queue");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let fid = hub.command_buffers.prepare(id_in);
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let error = loop {
+            let device = match device_guard.get(device_id) {
+                Ok(device) => device,
+                Err(_) => break DeviceError::Invalid.into(),
             };
-            let command_buffer = command::CommandBuffer::new(
-                encoder,
-                dev_stored,
-                device.limits.clone(),
-                device.downlevel.clone(),
-                device.features,
-                #[cfg(feature = "trace")]
-                device.trace.is_some(),
-                &desc.label,
-            );
+            if queue_index >= device.additional_queues.len() {
+                break command::CreateCommandEncoderError::InvalidAdditionalQueue(queue_index);
+            }
+            let command_buffer =
+                match device.create_command_buffer(device_id, desc, Some(queue_index)) {
+                    Ok(command_buffer) => command_buffer,
+                    Err(e) => break e,
+                };
 
             let id = fid.assign(command_buffer, &mut token);
             return (id.0, None);
@@ -1535,7 +2005,13 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         if let Some(cmdbuf) = cmdbuf {
             let device = &mut device_guard[cmdbuf.device_id.value];
             device.untrack::<G>(hub, &cmdbuf.trackers, &mut token);
-            device.destroy_command_buffer(cmdbuf);
+            // A reusable command buffer may still be executing.
+            match cmdbuf.last_submission {
+                Some(index) => {
+                    device.schedule_command_buffer_destruction(cmdbuf, index, &mut token)
+                }
+                None => device.destroy_command_buffer(cmdbuf),
+            }
         }
     }
 
@@ -1661,6 +2137,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(device) => device,
                 Err(_) => break DeviceError::Invalid.into(),
             };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
             #[cfg(feature = "trace")]
             if let Some(ref trace) = device.trace {
                 trace.lock().add(trace::Action::CreateQuerySet {
@@ -1750,6 +2229,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(device) => device,
                 Err(_) => break DeviceError::Invalid.into(),
             };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
             let adapter = &adapter_guard[device.adapter_id.value];
             #[cfg(feature = "trace")]
             if let Some(ref trace) = device.trace {
@@ -1893,6 +2375,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(device) => device,
                 Err(_) => break DeviceError::Invalid.into(),
             };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
             #[cfg(feature = "trace")]
             if let Some(ref trace) = device.trace {
                 trace.lock().add(trace::Action::CreateComputePipeline {
@@ -2119,6 +2604,27 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 );
                 config.composite_alpha_mode = new_alpha_mode;
             }
+            if !config.color_space.is_compatible_with(config.format) {
+                return Err(E::IncompatibleColorSpace {
+                    color_space: config.color_space,
+                    format: config.format,
+                });
+            }
+            if !caps
+                .color_spaces
+                .contains(&(config.format, config.color_space))
+            {
+                return Err(E::UnsupportedColorSpace {
+                    requested: config.color_space,
+                    format: config.format,
+                    available: caps
+                        .color_spaces
+                        .iter()
+                        .filter(|&&(format, _)| format == config.format)
+                        .map(|&(_, color_space)| color_space)
+                        .collect(),
+                });
+            }
             if !caps.usage.contains(config.usage) {
                 return Err(E::UnsupportedUsage);
             }
@@ -2141,6 +2647,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(device) => device,
                 Err(_) => break DeviceError::Invalid.into(),
             };
+            if !device.is_valid() {
+                break DeviceError::Lost.into();
+            }
             #[cfg(feature = "trace")]
             if let Some(ref trace) = device.trace {
                 trace
@@ -2153,10 +2662,14 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Err(_) => break E::InvalidSurface,
             };
 
+            // A suspended surface has no platform surface to configure.
+            let suf = match A::get_surface(surface) {
+                Some(suf) => suf,
+                None => break E::InvalidSurface,
+            };
             let caps = unsafe {
-                let suf = A::get_surface(surface);
                 let adapter = &adapter_guard[device.adapter_id.value];
-                match adapter.raw.adapter.surface_capabilities(&suf.unwrap().raw) {
+                match adapter.raw.adapter.surface_capabilities(&suf.raw) {
                     Some(caps) => caps,
                     None => break E::UnsupportedQueueFamily,
                 }
@@ -2186,13 +2699,25 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     break 'outer E::MissingDownlevelFlags(missing_flag);
                 }
             }
+            let protected = config.usage.contains(wgt::TextureUsages::PROTECTED);
+            if protected {
+                if let Err(missing) = device.require_features(wgt::Features::PROTECTED_CONTENT) {
+                    break 'outer E::MissingFeatures(missing);
+                }
+            }
 
-            let num_frames = present::DESIRED_NUM_FRAMES
+            // One more texture than the queued frames is needed for rendering the next one.
+            let num_frames = config
+                .desired_maximum_frame_latency
+                .max(1)
+                .saturating_add(1)
                 .clamp(*caps.swap_chain_sizes.start(), *caps.swap_chain_sizes.end());
             let mut hal_config = hal::SurfaceConfiguration {
                 swap_chain_size: num_frames,
+                maximum_frame_latency: config.desired_maximum_frame_latency.clamp(1, num_frames),
                 present_mode: config.present_mode,
                 composite_alpha_mode: config.alpha_mode,
+                color_space: config.color_space,
                 format: config.format,
                 extent: wgt::Extent3d {
                     width: config.width,
@@ -2201,6 +2726,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 },
                 usage: conv::map_texture_usage(config.usage, hal::FormatAspects::COLOR),
                 view_formats: hal_view_formats,
+                protected,
             };
 
             if let Err(error) = validate_surface_configuration(&mut hal_config, &caps) {
@@ -2259,6 +2785,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         device.lock_life(&mut token).triage_suspected(
             hub,
             &device.trackers,
+            &device.bgl_pool,
             #[cfg(feature = "trace")]
             None,
             &mut token,
@@ -2274,8 +2801,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         device_id: DeviceId,
         maintain: wgt::Maintain<queue::WrappedSubmissionIndex>,
     ) -> Result<bool, WaitIdleError> {
-        let (closures, queue_empty) = {
-            if let wgt::Maintain::WaitForSubmissionIndex(submission_index) = maintain {
+        let (closures, queue_empty, valid) = {
+            if let wgt::Maintain::WaitForSubmissionIndex(submission_index)
+            | wgt::Maintain::WaitForSubmissionIndexWithTimeout(submission_index, _) = maintain
+            {
                 if submission_index.queue_id != device_id {
                     return Err(WaitIdleError::WrongSubmissionIndex(
                         submission_index.queue_id,
@@ -2287,14 +2816,18 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             let hub = A::hub(self);
             let mut token = Token::root();
             let (device_guard, mut token) = hub.devices.read(&mut token);
-            device_guard
+            let device = device_guard
                 .get(device_id)
-                .map_err(|_| DeviceError::Invalid)?
-                .maintain(hub, maintain, &mut token)?
+                .map_err(|_| DeviceError::Invalid)?;
+            let (closures, queue_empty) = device.maintain(hub, maintain, &mut token)?;
+            (closures, queue_empty, device.is_valid())
         };
 
         closures.fire();
 
+        if !valid {
+            return Err(DeviceError::Lost.into());
+        }
         Ok(queue_empty)
     }
 
@@ -2378,12 +2911,103 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             all_queue_empty =
                 self.poll_devices::<hal::api::Gles>(force_wait, &mut closures)? && all_queue_empty;
         }
+        #[cfg(feature = "noop")]
+        {
+            all_queue_empty =
+                self.poll_devices::<hal::api::Empty>(force_wait, &mut closures)? && all_queue_empty;
+        }
 
         closures.fire();
 
         Ok(all_queue_empty)
     }
 
+    /// Counts the live resources of `device_id`, to help find leaks.
+    pub fn device_generate_report<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+    ) -> Result<DeviceReport, InvalidDevice> {
+        profiling::scope!("Device::generate_report");
+
+        fn count<T, I: id::TypedId>(
+            storage: &Storage<T, I>,
+            device_id: DeviceId,
+            owner: impl Fn(&T) -> DeviceId,
+        ) -> usize {
+            storage
+                .iter(device_id.backend())
+                .filter(|&(_, resource)| owner(resource) == device_id)
+                .count()
+        }
+
+        let hub = A::hub(self);
+        let allocator = {
+            let device_guard = hub.devices.data.read();
+            let device = device_guard.get(device_id).map_err(|_| InvalidDevice)?;
+            unsafe { device.raw.allocator_report() }
+        };
+
+        let mut report = DeviceReport {
+            allocator,
+            ..Default::default()
+        };
+
+        for (_, buffer) in hub.buffers.data.read().iter(A::VARIANT) {
+            if buffer.device_id.value.0 == device_id {
+                report.buffers.count += 1;
+                if buffer.raw.is_some() {
+                    report.buffers.bytes += buffer.size;
+                }
+            }
+        }
+        for (_, texture) in hub.textures.data.read().iter(A::VARIANT) {
+            if texture.device_id.value.0 == device_id {
+                report.textures.count += 1;
+                if texture.inner.as_raw().is_some() {
+                    report.textures.bytes += texture.estimated_size();
+                }
+            }
+        }
+
+        report.texture_views = count(&hub.texture_views.data.read(), device_id, |view| {
+            view.device_id.value.0
+        });
+        report.samplers = count(&hub.samplers.data.read(), device_id, |sampler| {
+            sampler.device_id.value.0
+        });
+        report.bind_groups = count(&hub.bind_groups.data.read(), device_id, |group| {
+            group.device_id.value.0
+        });
+        report.bind_group_layouts =
+            count(&hub.bind_group_layouts.data.read(), device_id, |layout| {
+                layout.device_id.value.0
+            });
+        report.pipeline_layouts = count(&hub.pipeline_layouts.data.read(), device_id, |layout| {
+            layout.device_id.value.0
+        });
+        report.shader_modules = count(&hub.shader_modules.data.read(), device_id, |module| {
+            module.device_id.value.0
+        });
+        report.render_pipelines = count(&hub.render_pipelines.data.read(), device_id, |pipeline| {
+            pipeline.device_id.value.0
+        });
+        report.compute_pipelines =
+            count(&hub.compute_pipelines.data.read(), device_id, |pipeline| {
+                pipeline.device_id.value.0
+            });
+        report.command_buffers = count(&hub.command_buffers.data.read(), device_id, |cmd_buf| {
+            cmd_buf.device_id.value.0
+        });
+        report.render_bundles = count(&hub.render_bundles.data.read(), device_id, |bundle| {
+            bundle.device_id.value.0
+        });
+        report.query_sets = count(&hub.query_sets.data.read(), device_id, |query_set| {
+            query_set.device_id.value.0
+        });
+
+        Ok(report)
+    }
+
     pub fn device_label<A: HalApi>(&self, id: DeviceId) -> String {
         A::hub(self).devices.label_for_resource(id)
     }
@@ -2417,10 +3041,79 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         // stands for the user's reference to the device. We'll take care of
         // cleaning up the device when we're polled, once its queue submissions
         // have completed and it is no longer needed by other resources.
+        let invocation = {
+            let (mut device_guard, _) = hub.devices.write(&mut token);
+            match device_guard.get_mut(device_id) {
+                Ok(device) => {
+                    device.life_guard.ref_count.take().unwrap();
+                    device.take_device_lost_invocation(
+                        wgt::DeviceLostReason::Dropped,
+                        "Device was dropped",
+                    )
+                }
+                Err(_) => None,
+            }
+        };
+
+        // The closure should execute with nothing locked!
+        if let Some(invocation) = invocation {
+            invocation.call();
+        }
+    }
+
+    /// Enable or disable the zero initialization of the buffers and textures
+    /// created by `device_id` from now on. It is enabled by default.
+    ///
+    /// Zero initialization clears the parts of a resource that are read before
+    /// being written, as WebGPU requires. Applications that always write their
+    /// resources before reading them can skip its tracking and clearing costs.
+    ///
+    /// # Safety
+    ///
+    /// - With zero initialization disabled, the contents of new resources are
+    ///   undefined and may hold data left by other applications. The caller is
+    ///   responsible for never exposing them.
+    pub unsafe fn device_set_zero_initialization<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        enabled: bool,
+    ) -> Result<(), InvalidDevice> {
+        let hub = A::hub(self);
+        let mut token = Token::root();
         let (mut device_guard, _) = hub.devices.write(&mut token);
-        if let Ok(device) = device_guard.get_mut(device_id) {
-            device.life_guard.ref_count.take().unwrap();
+        let device = device_guard.get_mut(device_id).map_err(|_| InvalidDevice)?;
+        device.zero_initialize = enabled;
+        Ok(())
+    }
+
+    /// Set the closure called when the device is lost, replacing the previous
+    /// one. The closure is called right away if the device is already lost.
+    pub fn device_set_device_lost_closure<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        closure: DeviceLostClosure,
+    ) -> Result<(), InvalidDevice> {
+        let hub = A::hub(self);
+        let mut token = Token::root();
+
+        let invocation = {
+            let (device_guard, _) = hub.devices.read(&mut token);
+            let device = device_guard.get(device_id).map_err(|_| InvalidDevice)?;
+            *device.device_lost_closure.lock() = Some(closure);
+            if device.is_valid() {
+                None
+            } else {
+                device.take_device_lost_invocation(
+                    wgt::DeviceLostReason::Unknown,
+                    "Device was lost by the backend",
+                )
+            }
+        };
+
+        if let Some(invocation) = invocation {
+            invocation.call();
         }
+        Ok(())
     }
 
     /// Exit the unreferenced, inactive device `device_id`.
@@ -2530,22 +3223,56 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 ));
             }
 
-            buffer.map_state = match buffer.map_state {
-                resource::BufferMapState::Init { .. } | resource::BufferMapState::Active { .. } => {
+            // Disjoint ranges of a buffer can be mapped concurrently, as long as
+            // they are all mapped with the same mode.
+            // Empty ranges still conflict with ranges that start at the same offset.
+            let overlaps = |other: &Range<BufferAddress>| {
+                range.start == other.start || (range.start < other.end && other.start < range.end)
+            };
+            match buffer.map_state {
+                resource::BufferMapState::Init { .. } => {
                     return Err((op, BufferAccessError::AlreadyMapped));
                 }
-                resource::BufferMapState::Waiting(_) => {
-                    return Err((op, BufferAccessError::MapAlreadyPending));
+                resource::BufferMapState::Waiting(ref pending) => {
+                    if pending
+                        .iter()
+                        .any(|pending| pending.op.host != op.host || overlaps(&pending.range))
+                    {
+                        return Err((op, BufferAccessError::MapAlreadyPending));
+                    }
                 }
-                resource::BufferMapState::Idle => {
-                    resource::BufferMapState::Waiting(resource::BufferPendingMapping {
-                        range,
-                        op,
-                        _parent_ref_count: buffer.life_guard.add_ref(),
-                    })
+                resource::BufferMapState::Active {
+                    ref ranges,
+                    ref pending,
+                    host,
+                    ..
+                } => {
+                    if host != op.host || ranges.iter().any(overlaps) {
+                        return Err((op, BufferAccessError::AlreadyMapped));
+                    }
+                    if pending.iter().any(|pending| overlaps(&pending.range)) {
+                        return Err((op, BufferAccessError::MapAlreadyPending));
+                    }
                 }
+                resource::BufferMapState::Idle => {}
+            }
+
+            let pending_mapping = resource::BufferPendingMapping {
+                range,
+                op,
+                _parent_ref_count: buffer.life_guard.add_ref(),
             };
-            log::debug!("Buffer {:?} map state -> Waiting", buffer_id);
+            match buffer.map_state {
+                resource::BufferMapState::Waiting(ref mut pending)
+                | resource::BufferMapState::Active {
+                    ref mut pending, ..
+                } => pending.push(pending_mapping),
+                resource::BufferMapState::Idle => {
+                    buffer.map_state = resource::BufferMapState::Waiting(vec![pending_mapping]);
+                    log::debug!("Buffer {:?} map state -> Waiting", buffer_id);
+                }
+                resource::BufferMapState::Init { .. } => unreachable!(),
+            }
 
             let device = &device_guard[buffer.device_id.value];
 
@@ -2610,23 +3337,34 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 }
                 unsafe { Ok((ptr.as_ptr().offset(offset as isize), range_size)) }
             }
-            resource::BufferMapState::Active { ptr, ref range, .. } => {
-                if offset < range.start {
-                    return Err(BufferAccessError::OutOfBoundsUnderrun {
-                        index: offset,
-                        min: range.start,
-                    });
-                }
-                if offset + range_size > range.end {
-                    return Err(BufferAccessError::OutOfBoundsOverrun {
-                        index: offset + range_size - 1,
-                        max: range.end,
-                    });
+            resource::BufferMapState::Active {
+                ref mapping,
+                ref ranges,
+                ..
+            } => {
+                // The accessed range must lie within a single mapped range. Report
+                // errors against the closest mapped range starting before it.
+                let closest = ranges
+                    .iter()
+                    .filter(|range| range.start <= offset)
+                    .max_by_key(|range| range.start);
+                match closest {
+                    None => {
+                        return Err(BufferAccessError::OutOfBoundsUnderrun {
+                            index: offset,
+                            min: ranges.iter().map(|range| range.start).min().unwrap_or(0),
+                        });
+                    }
+                    Some(range) if offset + range_size > range.end => {
+                        return Err(BufferAccessError::OutOfBoundsOverrun {
+                            index: offset + range_size - 1,
+                            max: range.end,
+                        });
+                    }
+                    Some(_) => {}
                 }
-                // ptr points to the beginning of the range we mapped in map_async
-                // rather thant the beginning of the buffer.
-                let relative_offset = (offset - range.start) as isize;
-                unsafe { Ok((ptr.as_ptr().offset(relative_offset), range_size)) }
+                // The whole buffer is mapped, so the offset is relative to `ptr`.
+                unsafe { Ok((mapping.ptr.as_ptr().offset(offset as isize), range_size)) }
             }
             resource::BufferMapState::Idle | resource::BufferMapState::Waiting(_) => {
                 Err(BufferAccessError::NotMapped)
@@ -2639,7 +3377,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         buffer_id: id::BufferId,
         buffer: &mut Buffer<A>,
         device: &mut Device<A>,
-    ) -> Result<Option<BufferMapPendingClosure>, BufferAccessError> {
+    ) -> Result<Vec<BufferMapPendingClosure>, BufferAccessError> {
         log::debug!("Buffer {:?} map state -> Idle", buffer_id);
         match mem::replace(&mut buffer.map_state, resource::BufferMapState::Idle) {
             resource::BufferMapState::Init {
@@ -2703,41 +3441,68 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 return Err(BufferAccessError::NotMapped);
             }
             resource::BufferMapState::Waiting(pending) => {
-                return Ok(Some((pending.op, Err(BufferAccessError::MapAborted))));
-            }
-            resource::BufferMapState::Active { ptr, range, host } => {
+                return Ok(pending
+                    .into_iter()
+                    .map(|pending| (pending.op, Err(BufferAccessError::MapAborted)))
+                    .collect());
+            }
+            resource::BufferMapState::Active {
+                mapping,
+                ranges,
+                pending,
+                host,
+            } => {
                 if host == HostMap::Write {
                     #[cfg(feature = "trace")]
                     if let Some(ref trace) = device.trace {
                         let mut trace = trace.lock();
-                        let size = range.end - range.start;
-                        let data = trace.make_binary("bin", unsafe {
-                            std::slice::from_raw_parts(ptr.as_ptr(), size as usize)
-                        });
-                        trace.add(trace::Action::WriteBuffer {
-                            id: buffer_id,
-                            data,
-                            range: range.clone(),
-                            queued: false,
-                        });
+                        for range in ranges.iter() {
+                            let size = range.end - range.start;
+                            let data = trace.make_binary("bin", unsafe {
+                                std::slice::from_raw_parts(
+                                    mapping.ptr.as_ptr().add(range.start as usize),
+                                    size as usize,
+                                )
+                            });
+                            trace.add(trace::Action::WriteBuffer {
+                                id: buffer_id,
+                                data,
+                                range: range.clone(),
+                                queued: false,
+                            });
+                        }
                     }
-                    let _ = (ptr, range);
+                    let _ = (&mapping, ranges);
                 }
-                unsafe {
-                    device
-                        .raw
-                        .unmap_buffer(buffer.raw.as_ref().unwrap())
-                        .map_err(DeviceError::from)?
-                };
+                let raw_buf = buffer.raw.as_ref().unwrap();
+                if let Some(flush_range) = buffer.sync_mapped_writes.take() {
+                    unsafe {
+                        device
+                            .raw
+                            .flush_mapped_ranges(raw_buf, iter::once(flush_range))
+                    };
+                }
+                if buffer.size != 0 {
+                    unsafe {
+                        device
+                            .raw
+                            .unmap_buffer(raw_buf)
+                            .map_err(DeviceError::from)?
+                    };
+                }
+                return Ok(pending
+                    .into_iter()
+                    .map(|pending| (pending.op, Err(BufferAccessError::MapAborted)))
+                    .collect());
             }
         }
-        Ok(None)
+        Ok(Vec::new())
     }
 
     pub fn buffer_unmap<A: HalApi>(&self, buffer_id: id::BufferId) -> BufferAccessResult {
         profiling::scope!("unmap", "Buffer");
 
-        let closure;
+        let closures;
         {
             // Restrict the locks to this scope.
             let hub = A::hub(self);
@@ -2750,11 +3515,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 .map_err(|_| BufferAccessError::Invalid)?;
             let device = &mut device_guard[buffer.device_id.value];
 
-            closure = self.buffer_unmap_inner(buffer_id, buffer, device)
+            closures = self.buffer_unmap_inner(buffer_id, buffer, device)
         }
 
         // Note: outside the scope where locks are held when calling the callback
-        if let Some((operation, status)) = closure? {
+        for (operation, status) in closures? {
             operation.callback.call(status);
         }
         Ok(())
@@ -1,6 +1,7 @@
 #[cfg(feature = "trace")]
 use crate::device::trace;
 use crate::{
+    binding_model::BindGroupLayoutPool,
     device::{
         queue::{EncoderInFlight, SubmittedWorkDoneClosure, TempResource},
         DeviceError,
@@ -15,8 +16,8 @@ use crate::{
 };
 use smallvec::SmallVec;
 
+use crate::lock::Mutex;
 use hal::Device as _;
-use parking_lot::Mutex;
 use thiserror::Error;
 
 use std::mem;
@@ -28,6 +29,7 @@ pub(super) struct SuspectedResources {
     pub(super) textures: Vec<id::Valid<id::TextureId>>,
     pub(super) texture_views: Vec<id::Valid<id::TextureViewId>>,
     pub(super) samplers: Vec<id::Valid<id::SamplerId>>,
+    pub(super) acceleration_structures: Vec<id::Valid<id::AccelerationStructureId>>,
     pub(super) bind_groups: Vec<id::Valid<id::BindGroupId>>,
     pub(super) compute_pipelines: Vec<id::Valid<id::ComputePipelineId>>,
     pub(super) render_pipelines: Vec<id::Valid<id::RenderPipelineId>>,
@@ -43,6 +45,7 @@ impl SuspectedResources {
         self.textures.clear();
         self.texture_views.clear();
         self.samplers.clear();
+        self.acceleration_structures.clear();
         self.bind_groups.clear();
         self.compute_pipelines.clear();
         self.render_pipelines.clear();
@@ -57,6 +60,8 @@ impl SuspectedResources {
         self.textures.extend_from_slice(&other.textures);
         self.texture_views.extend_from_slice(&other.texture_views);
         self.samplers.extend_from_slice(&other.samplers);
+        self.acceleration_structures
+            .extend_from_slice(&other.acceleration_structures);
         self.bind_groups.extend_from_slice(&other.bind_groups);
         self.compute_pipelines
             .extend_from_slice(&other.compute_pipelines);
@@ -84,6 +89,8 @@ impl SuspectedResources {
         self.textures.extend(trackers.textures.used());
         self.texture_views.extend(trackers.views.used());
         self.samplers.extend(trackers.samplers.used());
+        self.acceleration_structures
+            .extend(trackers.acceleration_structures.used());
     }
 }
 
@@ -94,6 +101,7 @@ struct NonReferencedResources<A: hal::Api> {
     textures: Vec<A::Texture>,
     texture_views: Vec<A::TextureView>,
     samplers: Vec<A::Sampler>,
+    acceleration_structures: Vec<A::AccelerationStructure>,
     bind_groups: Vec<A::BindGroup>,
     compute_pipes: Vec<A::ComputePipeline>,
     render_pipes: Vec<A::RenderPipeline>,
@@ -109,6 +117,7 @@ impl<A: hal::Api> NonReferencedResources<A> {
             textures: Vec::new(),
             texture_views: Vec::new(),
             samplers: Vec::new(),
+            acceleration_structures: Vec::new(),
             bind_groups: Vec::new(),
             compute_pipes: Vec::new(),
             render_pipes: Vec::new(),
@@ -123,6 +132,8 @@ impl<A: hal::Api> NonReferencedResources<A> {
         self.textures.extend(other.textures);
         self.texture_views.extend(other.texture_views);
         self.samplers.extend(other.samplers);
+        self.acceleration_structures
+            .extend(other.acceleration_structures);
         self.bind_groups.extend(other.bind_groups);
         self.compute_pipes.extend(other.compute_pipes);
         self.render_pipes.extend(other.render_pipes);
@@ -156,6 +167,12 @@ impl<A: hal::Api> NonReferencedResources<A> {
                 unsafe { device.destroy_sampler(raw) };
             }
         }
+        if !self.acceleration_structures.is_empty() {
+            profiling::scope!("destroy_acceleration_structures");
+            for raw in self.acceleration_structures.drain(..) {
+                unsafe { device.destroy_acceleration_structure(raw) };
+            }
+        }
         if !self.bind_groups.is_empty() {
             profiling::scope!("destroy_bind_groups");
             for raw in self.bind_groups.drain(..) {
@@ -349,6 +366,7 @@ impl<A: hal::Api> LifetimeTracker<A> {
                     last_resources.textures.push(raw);
                     last_resources.texture_views.extend(views);
                 }
+                TempResource::BindGroup(raw) => last_resources.bind_groups.push(raw),
             }
         }
 
@@ -421,8 +439,11 @@ impl<A: hal::Api> LifetimeTracker<A> {
             self.free_resources.extend(a.last_resources);
             self.ready_to_map.extend(a.mapped);
             for encoder in a.encoders {
+                let (protected, additional_queue) = (encoder.protected, encoder.additional_queue);
                 let raw = unsafe { encoder.land() };
-                command_allocator.lock().release_encoder(raw);
+                command_allocator
+                    .lock()
+                    .release_encoder(raw, protected, additional_queue);
             }
             work_done_closures.extend(a.work_done_closures);
         }
@@ -452,6 +473,27 @@ impl<A: hal::Api> LifetimeTracker<A> {
                 resources.texture_views.extend(views);
                 resources.textures.push(raw);
             }
+            TempResource::BindGroup(raw) => resources.bind_groups.push(raw),
+        }
+    }
+
+    /// Releases the encoder of a reusable command buffer once its last
+    /// submission is done. Gives it back if that already happened.
+    pub fn schedule_encoder_release(
+        &mut self,
+        encoder: EncoderInFlight<A>,
+        last_submit_index: SubmissionIndex,
+    ) -> Option<EncoderInFlight<A>> {
+        match self
+            .active
+            .iter_mut()
+            .find(|a| a.index == last_submit_index)
+        {
+            Some(a) => {
+                a.encoders.push(encoder);
+                None
+            }
+            None => Some(encoder),
         }
     }
 
@@ -513,6 +555,7 @@ impl<A: HalApi> LifetimeTracker<A> {
         &mut self,
         hub: &Hub<A, G>,
         trackers: &Mutex<Tracker<A>>,
+        bgl_pool: &Mutex<BindGroupLayoutPool>,
         #[cfg(feature = "trace")] trace: Option<&Mutex<trace::Trace>>,
         token: &mut Token<super::Device<A>>,
     ) {
@@ -658,6 +701,30 @@ impl<A: HalApi> LifetimeTracker<A> {
             }
         }
 
+        if !self.suspected_resources.acceleration_structures.is_empty() {
+            let (mut guard, _) = hub.acceleration_structures.write(token);
+            let mut trackers = trackers.lock();
+
+            for id in self.suspected_resources.acceleration_structures.drain(..) {
+                if trackers.acceleration_structures.remove_abandoned(id) {
+                    log::debug!("Acceleration structure {:?} will be destroyed", id);
+
+                    if let Some(res) = hub
+                        .acceleration_structures
+                        .unregister_locked(id.0, &mut *guard)
+                    {
+                        let submit_index = res.life_guard.life_count();
+                        self.active
+                            .iter_mut()
+                            .find(|a| a.index == submit_index)
+                            .map_or(&mut self.free_resources, |a| &mut a.last_resources)
+                            .acceleration_structures
+                            .push(res.raw);
+                    }
+                }
+            }
+        }
+
         if !self.suspected_resources.buffers.is_empty() {
             let (mut guard, _) = hub.buffers.write(token);
             let mut trackers = trackers.lock();
@@ -777,6 +844,9 @@ impl<A: HalApi> LifetimeTracker<A> {
                         // If This layout points to a compatible one, go over the latter
                         // to decrement the ref count and potentially destroy it.
                         bgl_to_check = bgl.compatible_layout;
+                        if bgl.compatible_layout.is_none() {
+                            bgl_pool.lock().remove(&bgl.entries, id);
+                        }
 
                         log::debug!("Bind group layout {:?} will be destroyed", id);
                         #[cfg(feature = "trace")]
@@ -883,48 +953,67 @@ impl<A: HalApi> LifetimeTracker<A> {
                     self.free_resources.buffers.extend(buf.raw);
                 }
             } else {
-                let mapping = match std::mem::replace(
-                    &mut buffer.map_state,
-                    resource::BufferMapState::Idle,
-                ) {
-                    resource::BufferMapState::Waiting(pending_mapping) => pending_mapping,
+                let pending = match buffer.map_state {
+                    resource::BufferMapState::Waiting(ref mut pending)
+                    | resource::BufferMapState::Active {
+                        ref mut pending, ..
+                    } => std::mem::take(pending),
                     // Mapping cancelled
                     resource::BufferMapState::Idle => continue,
-                    // Mapping queued at least twice by map -> unmap -> map
-                    // and was already successfully mapped below
-                    active @ resource::BufferMapState::Active { .. } => {
-                        buffer.map_state = active;
-                        continue;
-                    }
                     _ => panic!("No pending mapping."),
                 };
-                let status = if mapping.range.start != mapping.range.end {
+                // Mapping queued at least twice, by mapping several ranges or by
+                // map -> unmap -> map, and all ranges were already mapped below
+                if pending.is_empty() {
+                    continue;
+                }
+
+                if let resource::BufferMapState::Waiting(_) = buffer.map_state {
                     log::debug!("Buffer {:?} map state -> Active", buffer_id);
-                    let host = mapping.op.host;
-                    let size = mapping.range.end - mapping.range.start;
-                    match super::map_buffer(raw, buffer, mapping.range.start, size, host) {
-                        Ok(ptr) => {
+                    match super::map_buffer(raw, buffer) {
+                        Ok(mapping) => {
                             buffer.map_state = resource::BufferMapState::Active {
-                                ptr,
-                                range: mapping.range.start..mapping.range.start + size,
-                                host,
+                                mapping,
+                                ranges: Vec::with_capacity(pending.len()),
+                                pending: Vec::new(),
+                                host: pending[0].op.host,
                             };
-                            Ok(())
                         }
                         Err(e) => {
                             log::error!("Mapping failed {:?}", e);
-                            Err(e)
+                            buffer.map_state = resource::BufferMapState::Idle;
+                            pending_callbacks.extend(
+                                pending
+                                    .into_iter()
+                                    .map(|pending| (pending.op, Err(e.clone()))),
+                            );
+                            continue;
                         }
                     }
-                } else {
-                    buffer.map_state = resource::BufferMapState::Active {
-                        ptr: std::ptr::NonNull::dangling(),
-                        range: mapping.range,
-                        host: mapping.op.host,
-                    };
-                    Ok(())
+                }
+
+                let mapping = match buffer.map_state {
+                    resource::BufferMapState::Active { ref mapping, .. } => mapping.clone(),
+                    _ => unreachable!(),
                 };
-                pending_callbacks.push((mapping.op, status));
+                for pending in pending {
+                    let range = pending.range;
+                    let size = range.end - range.start;
+                    super::map_buffer_range(
+                        raw,
+                        buffer,
+                        &mapping,
+                        range.start,
+                        size,
+                        pending.op.host,
+                    );
+                    if let resource::BufferMapState::Active { ref mut ranges, .. } =
+                        buffer.map_state
+                    {
+                        ranges.push(range);
+                    }
+                    pending_callbacks.push((pending.op, Ok(())));
+                }
             }
         }
         pending_callbacks
@@ -1,13 +1,12 @@
 use crate::{
     binding_model,
-    device::life::WaitIdleError,
     hal_api::HalApi,
     hub::Hub,
     id,
     identity::{GlobalIdentityHandlerFactory, Input},
     resource::{Buffer, BufferAccessResult},
     resource::{BufferAccessError, BufferMapOperation},
-    Label, DOWNLEVEL_ERROR_MESSAGE,
+    Label, RawString, DOWNLEVEL_ERROR_MESSAGE,
 };
 
 use arrayvec::ArrayVec;
@@ -16,14 +15,16 @@ use smallvec::SmallVec;
 use thiserror::Error;
 use wgt::{BufferAddress, TextureFormat};
 
-use std::{iter, num::NonZeroU32, ptr};
+use std::{ffi::CString, iter, num::NonZeroU32, ptr};
 
 pub mod global;
 mod life;
 pub mod queue;
 pub mod resource;
+pub mod snapshot;
 #[cfg(any(feature = "trace", feature = "replay"))]
 pub mod trace;
+pub use life::WaitIdleError;
 pub use resource::Device;
 
 pub const SHADER_STAGE_COUNT: usize = 3;
@@ -170,12 +171,15 @@ pub type BufferMapPendingClosure = (BufferMapOperation, BufferAccessResult);
 pub struct UserClosures {
     pub mappings: Vec<BufferMapPendingClosure>,
     pub submissions: SmallVec<[queue::SubmittedWorkDoneClosure; 1]>,
+    pub device_lost_invocations: SmallVec<[DeviceLostInvocation; 1]>,
 }
 
 impl UserClosures {
     fn extend(&mut self, other: Self) {
         self.mappings.extend(other.mappings);
         self.submissions.extend(other.submissions);
+        self.device_lost_invocations
+            .extend(other.device_lost_invocations);
     }
 
     fn fire(self) {
@@ -190,32 +194,146 @@ impl UserClosures {
         for closure in self.submissions {
             closure.call();
         }
+        // The device lost callbacks come last: everything pending on the device
+        // has been resolved by now.
+        for invocation in self.device_lost_invocations {
+            invocation.call();
+        }
+    }
+}
+
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+pub type DeviceLostCallback = Box<dyn FnOnce(wgt::DeviceLostReason, String) + Send + 'static>;
+#[cfg(not(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+)))]
+pub type DeviceLostCallback = Box<dyn FnOnce(wgt::DeviceLostReason, String) + 'static>;
+
+#[repr(C)]
+pub struct DeviceLostClosureC {
+    pub callback: unsafe extern "C" fn(user_data: *mut u8, reason: u8, message: RawString),
+    pub user_data: *mut u8,
+}
+
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+unsafe impl Send for DeviceLostClosureC {}
+
+/// Called at most once, when the device is lost or dropped.
+pub struct DeviceLostClosure {
+    // We wrap this so creating the enum in the C variant can be unsafe,
+    // allowing our call function to be safe.
+    inner: DeviceLostClosureInner,
+}
+
+enum DeviceLostClosureInner {
+    Rust { callback: DeviceLostCallback },
+    C { inner: DeviceLostClosureC },
+}
+
+impl DeviceLostClosure {
+    pub fn from_rust(callback: DeviceLostCallback) -> Self {
+        Self {
+            inner: DeviceLostClosureInner::Rust { callback },
+        }
+    }
+
+    /// # Safety
+    ///
+    /// - The callback pointer must be valid to call with the provided `user_data`
+    ///   pointer.
+    ///
+    /// - Both pointers must point to `'static` data, as the callback may happen at
+    ///   an unspecified time.
+    pub unsafe fn from_c(inner: DeviceLostClosureC) -> Self {
+        Self {
+            inner: DeviceLostClosureInner::C { inner },
+        }
+    }
+
+    pub(crate) fn call(self, reason: wgt::DeviceLostReason, message: String) {
+        match self.inner {
+            DeviceLostClosureInner::Rust { callback } => callback(reason, message),
+            // SAFETY: the contract of the call to from_c says that this unsafe is sound.
+            DeviceLostClosureInner::C { inner } => unsafe {
+                // The message is only borrowed for the duration of the call.
+                let message = CString::new(message).unwrap_or_default();
+                (inner.callback)(inner.user_data, reason as u8, message.as_ptr())
+            },
+        }
     }
 }
 
+pub struct DeviceLostInvocation {
+    closure: DeviceLostClosure,
+    reason: wgt::DeviceLostReason,
+    message: String,
+}
+
+impl DeviceLostInvocation {
+    fn call(self) {
+        self.closure.call(self.reason, self.message);
+    }
+}
+
+/// Map all of `buffer` for host access.
 fn map_buffer<A: hal::Api>(
     raw: &A::Device,
-    buffer: &mut Buffer<A>,
-    offset: BufferAddress,
-    size: BufferAddress,
-    kind: HostMap,
-) -> Result<ptr::NonNull<u8>, BufferAccessError> {
+    buffer: &Buffer<A>,
+) -> Result<hal::BufferMapping, BufferAccessError> {
+    if buffer.size == 0 {
+        return Ok(hal::BufferMapping {
+            ptr: ptr::NonNull::dangling(),
+            is_coherent: true,
+        });
+    }
     let mapping = unsafe {
-        raw.map_buffer(buffer.raw.as_ref().unwrap(), offset..offset + size)
+        raw.map_buffer(buffer.raw.as_ref().unwrap(), 0..buffer.size)
             .map_err(DeviceError::from)?
     };
+    Ok(mapping)
+}
 
-    buffer.sync_mapped_writes = match kind {
+/// Prepare `offset..offset + size` of a buffer mapped as `mapping` for host access.
+fn map_buffer_range<A: hal::Api>(
+    raw: &A::Device,
+    buffer: &mut Buffer<A>,
+    mapping: &hal::BufferMapping,
+    offset: BufferAddress,
+    size: BufferAddress,
+    kind: HostMap,
+) {
+    match kind {
         HostMap::Read if !mapping.is_coherent => unsafe {
             raw.invalidate_mapped_ranges(
                 buffer.raw.as_ref().unwrap(),
                 iter::once(offset..offset + size),
             );
-            None
         },
-        HostMap::Write if !mapping.is_coherent => Some(offset..offset + size),
-        _ => None,
-    };
+        HostMap::Write if !mapping.is_coherent => {
+            // Flush all mapped ranges at once when unmapping.
+            buffer.sync_mapped_writes = Some(match buffer.sync_mapped_writes.take() {
+                Some(range) => range.start.min(offset)..range.end.max(offset + size),
+                None => offset..offset + size,
+            });
+        }
+        _ => {}
+    }
 
     assert_eq!(offset % wgt::COPY_BUFFER_ALIGNMENT, 0);
     assert_eq!(size % wgt::COPY_BUFFER_ALIGNMENT, 0);
@@ -235,7 +353,9 @@ fn map_buffer<A: hal::Api>(
 
     // No need to flush if it is flushed later anyways.
     let zero_init_needs_flush_now = mapping.is_coherent && buffer.sync_mapped_writes.is_none();
-    let mapped = unsafe { std::slice::from_raw_parts_mut(mapping.ptr.as_ptr(), size as usize) };
+    let mapped = unsafe {
+        std::slice::from_raw_parts_mut(mapping.ptr.as_ptr().add(offset as usize), size as usize)
+    };
 
     for uninitialized in buffer.initialization_status.drain(offset..(size + offset)) {
         // The mapping's pointer is already offset, however we track the
@@ -250,36 +370,75 @@ fn map_buffer<A: hal::Api>(
             };
         }
     }
-
-    Ok(mapping.ptr)
 }
 
 struct CommandAllocator<A: hal::Api> {
     free_encoders: Vec<A::CommandEncoder>,
+    /// Encoders of protected command buffers, which can't be used for
+    /// writes to unprotected resources.
+    free_protected_encoders: Vec<A::CommandEncoder>,
+    /// Encoders of each additional queue of the device.
+    free_additional_encoders: Vec<Vec<A::CommandEncoder>>,
 }
 
 impl<A: hal::Api> CommandAllocator<A> {
+    /// Returns a free encoder for `queue`, which is the additional queue
+    /// `additional_queue` if it is given, or the main queue otherwise.
     fn acquire_encoder(
         &mut self,
         device: &A::Device,
         queue: &A::Queue,
+        protected: bool,
+        additional_queue: Option<usize>,
     ) -> Result<A::CommandEncoder, hal::DeviceError> {
-        match self.free_encoders.pop() {
+        let free_encoders = self.free_encoders_mut(protected, additional_queue);
+        match free_encoders.pop() {
             Some(encoder) => Ok(encoder),
             None => unsafe {
-                let hal_desc = hal::CommandEncoderDescriptor { label: None, queue };
+                let hal_desc = hal::CommandEncoderDescriptor {
+                    label: None,
+                    queue,
+                    protected,
+                };
                 device.create_command_encoder(&hal_desc)
             },
         }
     }
 
-    fn release_encoder(&mut self, encoder: A::CommandEncoder) {
-        self.free_encoders.push(encoder);
+    fn release_encoder(
+        &mut self,
+        encoder: A::CommandEncoder,
+        protected: bool,
+        additional_queue: Option<usize>,
+    ) {
+        self.free_encoders_mut(protected, additional_queue)
+            .push(encoder);
+    }
+
+    fn free_encoders_mut(
+        &mut self,
+        protected: bool,
+        additional_queue: Option<usize>,
+    ) -> &mut Vec<A::CommandEncoder> {
+        match additional_queue {
+            Some(index) => &mut self.free_additional_encoders[index],
+            None if protected => &mut self.free_protected_encoders,
+            None => &mut self.free_encoders,
+        }
     }
 
     fn dispose(self, device: &A::Device) {
-        log::info!("Destroying {} command encoders", self.free_encoders.len());
-        for cmd_encoder in self.free_encoders {
+        let additional_count: usize = self.free_additional_encoders.iter().map(Vec::len).sum();
+        log::info!(
+            "Destroying {} command encoders",
+            self.free_encoders.len() + self.free_protected_encoders.len() + additional_count
+        );
+        for cmd_encoder in self
+            .free_encoders
+            .into_iter()
+            .chain(self.free_protected_encoders)
+            .chain(self.free_additional_encoders.into_iter().flatten())
+        {
             unsafe {
                 device.destroy_command_encoder(cmd_encoder);
             }
@@ -291,6 +450,37 @@ impl<A: hal::Api> CommandAllocator<A> {
 #[error("Device is invalid")]
 pub struct InvalidDevice;
 
+/// Number and total size of the live resources of one type.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ResourceReport {
+    pub count: usize,
+    /// Size in bytes of the resources that still have memory, excluding the
+    /// destroyed ones.
+    pub bytes: wgt::BufferAddress,
+}
+
+/// Resources of a single device tracked by `wgpu-core`, see
+/// [`Global::device_generate_report`](global::Global::device_generate_report).
+#[derive(Clone, Debug, Default)]
+pub struct DeviceReport {
+    pub buffers: ResourceReport,
+    /// Texture sizes are estimated from their descriptors.
+    pub textures: ResourceReport,
+    pub texture_views: usize,
+    pub samplers: usize,
+    pub bind_groups: usize,
+    pub bind_group_layouts: usize,
+    pub pipeline_layouts: usize,
+    pub shader_modules: usize,
+    pub render_pipelines: usize,
+    pub compute_pipelines: usize,
+    pub command_buffers: usize,
+    pub render_bundles: usize,
+    pub query_sets: usize,
+    /// Statistics from the backend's memory allocator, if it keeps any.
+    pub allocator: Option<hal::AllocatorReport>,
+}
+
 #[derive(Clone, Debug, Error)]
 pub enum DeviceError {
     #[error("Parent device is invalid")]
@@ -3,7 +3,7 @@ use crate::device::trace::Action;
 use crate::{
     command::{
         extract_texture_selector, validate_linear_texture_data, validate_texture_copy_range,
-        ClearError, CommandBuffer, CopySide, ImageCopyTexture, TransferError,
+        BakedCommands, ClearError, CommandBuffer, CopySide, ImageCopyTexture, TransferError,
     },
     conv,
     device::{DeviceError, WaitIdleError},
@@ -18,10 +18,10 @@ use crate::{
     track, FastHashSet, SubmissionIndex,
 };
 
+use crate::lock::Mutex;
 use hal::{CommandEncoder as _, Device as _, Queue as _};
-use parking_lot::Mutex;
 use smallvec::SmallVec;
-use std::{iter, mem, ptr};
+use std::{collections::VecDeque, iter, mem, ptr, sync::atomic::Ordering};
 use thiserror::Error;
 
 /// Number of command buffers that we generate from the same pool
@@ -112,9 +112,9 @@ pub struct WrappedSubmissionIndex {
     pub index: SubmissionIndex,
 }
 
-/// A texture or buffer to be freed soon.
+/// A texture, buffer or bind group to be freed soon.
 ///
-/// This is just a tagged raw texture or buffer, generally about to be added to
+/// This is just a tagged raw resource, generally about to be added to
 /// some other more specific container like:
 ///
 /// - `PendingWrites::temp_resources`: resources used by queue writes and
@@ -129,12 +129,17 @@ pub struct WrappedSubmissionIndex {
 pub enum TempResource<A: hal::Api> {
     Buffer(A::Buffer),
     Texture(A::Texture, SmallVec<[A::TextureView; 1]>),
+    BindGroup(A::BindGroup),
 }
 
 /// A queue execution for a particular command encoder.
 pub(super) struct EncoderInFlight<A: hal::Api> {
-    raw: A::CommandEncoder,
-    cmd_buffers: Vec<A::CommandBuffer>,
+    pub(super) raw: A::CommandEncoder,
+    pub(super) cmd_buffers: Vec<A::CommandBuffer>,
+    /// Whether the encoder was created for protected command buffers.
+    pub(super) protected: bool,
+    /// Index of the additional queue the encoder was created for, if any.
+    pub(super) additional_queue: Option<usize>,
 }
 
 impl<A: hal::Api> EncoderInFlight<A> {
@@ -144,6 +149,19 @@ impl<A: hal::Api> EncoderInFlight<A> {
     }
 }
 
+/// A queue opened in addition to the main queue of a device.
+///
+/// A submission to it first waits for the main queue to signal the device
+/// fence with the submission index, then signals `fence` with the same index.
+pub(crate) struct AdditionalQueue<A: hal::Api> {
+    pub(crate) raw: A::Queue,
+    pub(crate) kind: wgt::QueueKind,
+    pub(crate) fence: A::Fence,
+    /// Indices of the submissions to this queue which may still be executing,
+    /// in increasing order.
+    pub(super) in_flight: Mutex<VecDeque<SubmissionIndex>>,
+}
+
 /// A private command encoder for writes made directly on the device
 /// or queue.
 ///
@@ -167,6 +185,9 @@ pub(crate) struct PendingWrites<A: hal::Api> {
     pub temp_resources: Vec<TempResource<A>>,
     pub dst_buffers: FastHashSet<id::BufferId>,
     pub dst_textures: FastHashSet<id::TextureId>,
+    /// Indices of the submissions which last used the written resources,
+    /// which may have executed on an additional queue.
+    pub previous_uses: FastHashSet<SubmissionIndex>,
     pub executing_command_buffers: Vec<A::CommandBuffer>,
 }
 
@@ -178,6 +199,7 @@ impl<A: hal::Api> PendingWrites<A> {
             temp_resources: Vec::new(),
             dst_buffers: FastHashSet::default(),
             dst_textures: FastHashSet::default(),
+            previous_uses: FastHashSet::default(),
             executing_command_buffers: Vec::new(),
         }
     }
@@ -203,6 +225,9 @@ impl<A: hal::Api> PendingWrites<A> {
                     }
                     device.destroy_texture(texture);
                 },
+                TempResource::BindGroup(bind_group) => unsafe {
+                    device.destroy_bind_group(bind_group);
+                },
             }
         }
     }
@@ -239,11 +264,13 @@ impl<A: hal::Api> PendingWrites<A> {
         if self.executing_command_buffers.len() >= WRITE_COMMAND_BUFFERS_PER_POOL {
             let new_encoder = command_allocator
                 .lock()
-                .acquire_encoder(device, queue)
+                .acquire_encoder(device, queue, false, None)
                 .unwrap();
             Some(EncoderInFlight {
                 raw: mem::replace(&mut self.command_encoder, new_encoder),
                 cmd_buffers: mem::take(&mut self.executing_command_buffers),
+                protected: false,
+                additional_queue: None,
             })
         } else {
             None
@@ -254,7 +281,7 @@ impl<A: hal::Api> PendingWrites<A> {
         if !self.is_active {
             unsafe {
                 self.command_encoder
-                    .begin_encoding(Some("(wgpu internal) PendingWrites"))
+                    .begin_encoding(Some("(wgpu internal) PendingWrites"), false)
                     .unwrap();
             }
             self.is_active = true;
@@ -334,6 +361,14 @@ pub enum QueueSubmitError {
     Unmap(#[from] BufferAccessError),
     #[error("Buffer {0:?} is still mapped")]
     BufferStillMapped(id::BufferId),
+    #[error("Command buffer {0:?} is submitted more than once in the same submission")]
+    DuplicateCommandBuffer(id::CommandBufferId),
+    #[error("Command buffer {0:?} is submitted again before its previous submission is done")]
+    CommandBufferInFlight(id::CommandBufferId),
+    #[error("Command buffer {0:?} was recorded for a different queue")]
+    WrongQueue(id::CommandBufferId),
+    #[error("Device has no additional queue {0}")]
+    InvalidAdditionalQueue(usize),
     #[error("Surface output was dropped before the command buffer got submitted")]
     SurfaceOutputDropped,
     #[error("Surface was unconfigured before the command buffer got submitted")]
@@ -361,6 +396,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let device = device_guard
             .get_mut(queue_id)
             .map_err(|_| DeviceError::Invalid)?;
+        if !device.is_valid() {
+            return Err(DeviceError::Lost.into());
+        }
 
         let data_size = data.len() as wgt::BufferAddress;
 
@@ -422,6 +460,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let device = device_guard
             .get_mut(queue_id)
             .map_err(|_| DeviceError::Invalid)?;
+        if !device.is_valid() {
+            return Err(DeviceError::Lost.into());
+        }
 
         let (staging_buffer, staging_buffer_ptr) =
             prepare_staging_buffer(&mut device.raw, buffer_size.get())?;
@@ -447,6 +488,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let device = device_guard
             .get_mut(queue_id)
             .map_err(|_| DeviceError::Invalid)?;
+        if !device.is_valid() {
+            return Err(DeviceError::Lost.into());
+        }
 
         let staging_buffer = hub
             .staging_buffers
@@ -511,6 +555,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 None,
             ));
         }
+        // The staging copy is recorded into an unprotected command buffer.
+        if buffer.usage.contains(wgt::BufferUsages::PROTECTED) {
+            return Err(TransferError::ProtectedDestination(Some(buffer_id), None));
+        }
         if buffer_size % wgt::COPY_BUFFER_ALIGNMENT != 0 {
             return Err(TransferError::UnalignedCopySize(buffer_size));
         }
@@ -554,6 +602,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let src_buffer_size = staging_buffer.size;
         self.queue_validate_write_buffer_impl(dst, buffer_id, buffer_offset, src_buffer_size)?;
 
+        device
+            .pending_writes
+            .previous_uses
+            .insert(dst.life_guard.life_count());
         dst.life_guard.use_at(device.active_submission_index + 1);
 
         let region = wgt::BufferSize::new(src_buffer_size).map(|size| hal::BufferCopy {
@@ -604,6 +656,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let device = device_guard
             .get_mut(queue_id)
             .map_err(|_| DeviceError::Invalid)?;
+        if !device.is_valid() {
+            return Err(DeviceError::Lost.into());
+        }
 
         #[cfg(feature = "trace")]
         if let Some(ref trace) = device.trace {
@@ -632,6 +687,12 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 TransferError::MissingCopyDstUsageFlag(None, Some(destination.texture)).into(),
             );
         }
+        // The staging copy is recorded into an unprotected command buffer.
+        if dst.desc.usage.contains(wgt::TextureUsages::PROTECTED) {
+            return Err(
+                TransferError::ProtectedDestination(None, Some(destination.texture)).into(),
+            );
+        }
 
         // Note: Doing the copy range validation early is important because ensures that the
         // dimensions are not going to cause overflow in other parts of the validation.
@@ -696,6 +757,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let stage_size = stage_bytes_per_row as u64 * block_rows_in_copy as u64;
 
         let mut trackers = device.trackers.lock();
+        device
+            .pending_writes
+            .previous_uses
+            .insert(dst.life_guard.life_count());
         let encoder = device.pending_writes.activate();
 
         // If the copy does not fully cover the layers, we need to initialize to
@@ -843,6 +908,135 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         Ok(())
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn queue_copy_external_image_to_texture<A: HalApi>(
+        &self,
+        queue_id: id::QueueId,
+        source: &wgt::ImageCopyExternalImage,
+        destination: crate::command::ImageCopyTextureTagged,
+        size: wgt::Extent3d,
+    ) -> Result<(), QueueWriteError> {
+        profiling::scope!("Queue::copy_external_image_to_texture");
+
+        let wgt::ExternalImageSource::CpuImage(ref image) = source.source;
+
+        if size.width == 0 || size.height == 0 || size.depth_or_array_layers == 0 {
+            log::trace!("Ignoring copy_external_image_to_texture of size 0");
+            return Ok(());
+        }
+
+        let dst_format = {
+            let hub = A::hub(self);
+            let mut token = Token::root();
+            let (_, mut token) = hub.devices.read(&mut token);
+            let (texture_guard, _) = hub.textures.read(&mut token);
+            texture_guard
+                .get(destination.texture)
+                .map_err(|_| TransferError::InvalidTexture(destination.texture))?
+                .desc
+                .format
+        };
+
+        let swap_red_blue = match dst_format {
+            wgt::TextureFormat::Rgba8Unorm | wgt::TextureFormat::Rgba8UnormSrgb => false,
+            wgt::TextureFormat::Bgra8Unorm | wgt::TextureFormat::Bgra8UnormSrgb => true,
+            _ => {
+                return Err(TransferError::ExternalCopyToForbiddenTextureFormat(dst_format).into())
+            }
+        };
+        if destination.color_space != wgt::PredefinedColorSpace::Srgb {
+            return Err(TransferError::ExternalCopyToUnsupportedColorSpace(
+                destination.color_space,
+            )
+            .into());
+        }
+
+        let required_len = image.width as u64 * image.height as u64 * 4;
+        if (image.data.len() as u64) < required_len {
+            return Err(TransferError::BufferOverrun {
+                start_offset: 0,
+                end_offset: required_len,
+                buffer_size: image.data.len() as u64,
+                side: CopySide::Source,
+            }
+            .into());
+        }
+        if source.origin.x + size.width > image.width {
+            return Err(TransferError::TextureOverrun {
+                start_offset: source.origin.x,
+                end_offset: source.origin.x + size.width,
+                texture_size: image.width,
+                dimension: crate::resource::TextureErrorDimension::X,
+                side: CopySide::Source,
+            }
+            .into());
+        }
+        if source.origin.y + size.height > image.height {
+            return Err(TransferError::TextureOverrun {
+                start_offset: source.origin.y,
+                end_offset: source.origin.y + size.height,
+                texture_size: image.height,
+                dimension: crate::resource::TextureErrorDimension::Y,
+                side: CopySide::Source,
+            }
+            .into());
+        }
+        if size.depth_or_array_layers != 1 {
+            return Err(TransferError::TextureOverrun {
+                start_offset: 0,
+                end_offset: size.depth_or_array_layers,
+                texture_size: 1,
+                dimension: crate::resource::TextureErrorDimension::Z,
+                side: CopySide::Source,
+            }
+            .into());
+        }
+
+        // Convert the source region into the layout of the destination, then
+        // upload it like any other texture write.
+        let row_len = size.width as usize * 4;
+        let mut data = Vec::with_capacity(row_len * size.height as usize);
+        for y in 0..size.height {
+            let src_y = if source.flip_y {
+                source.origin.y + size.height - 1 - y
+            } else {
+                source.origin.y + y
+            };
+            let row_start = (src_y as usize * image.width as usize + source.origin.x as usize) * 4;
+            for texel in image.data[row_start..row_start + row_len].chunks_exact(4) {
+                let mut texel = [texel[0], texel[1], texel[2], texel[3]];
+                let alpha = texel[3] as u32;
+                if destination.premultiplied_alpha && !image.premultiplied_alpha {
+                    for c in &mut texel[..3] {
+                        *c = ((*c as u32 * alpha + 127) / 255) as u8;
+                    }
+                } else if !destination.premultiplied_alpha && image.premultiplied_alpha {
+                    for c in &mut texel[..3] {
+                        *c = (*c as u32 * 255 + alpha / 2)
+                            .checked_div(alpha)
+                            .map_or(0, |c| c.min(255)) as u8;
+                    }
+                }
+                if swap_red_blue {
+                    texel.swap(0, 2);
+                }
+                data.extend_from_slice(&texel);
+            }
+        }
+
+        self.queue_write_texture::<A>(
+            queue_id,
+            &destination.to_untagged(),
+            &data,
+            &wgt::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(row_len as u32),
+                rows_per_image: None,
+            },
+            &size,
+        )
+    }
+
     #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
     pub fn queue_copy_external_image_to_texture<A: HalApi>(
         &self,
@@ -859,6 +1053,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let device = device_guard
             .get_mut(queue_id)
             .map_err(|_| DeviceError::Invalid)?;
+        if !device.is_valid() {
+            return Err(DeviceError::Lost.into());
+        }
 
         if size.width == 0 || size.height == 0 || size.depth_or_array_layers == 0 {
             log::trace!("Ignoring write_texture of size 0");
@@ -900,6 +1097,12 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 TransferError::MissingCopyDstUsageFlag(None, Some(destination.texture)).into(),
             );
         }
+        // The staging copy is recorded into an unprotected command buffer.
+        if dst.desc.usage.contains(wgt::TextureUsages::PROTECTED) {
+            return Err(
+                TransferError::ProtectedDestination(None, Some(destination.texture)).into(),
+            );
+        }
         if !dst
             .desc
             .usage
@@ -960,6 +1163,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             extract_texture_selector(&destination.to_untagged(), &size, dst)?;
 
         let mut trackers = device.trackers.lock();
+        device
+            .pending_writes
+            .previous_uses
+            .insert(dst.life_guard.life_count());
         let encoder = device.pending_writes.activate();
 
         // If the copy does not fully cover the layers, we need to initialize to
@@ -1045,10 +1252,41 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         Ok(())
     }
 
+    /// Submit the given command buffers to the queue.
+    ///
+    /// The `label`, if any, is forwarded to the backend to tag the submission
+    /// for external profilers.
     pub fn queue_submit<A: HalApi>(
         &self,
         queue_id: id::QueueId,
         command_buffer_ids: &[id::CommandBufferId],
+        label: Option<&str>,
+    ) -> Result<WrappedSubmissionIndex, QueueSubmitError> {
+        self.queue_submit_impl::<A>(queue_id, None, command_buffer_ids, label)
+    }
+
+    /// Submit the given command buffers to the additional queue
+    /// `queue_index` of the device.
+    ///
+    /// The main queue executes the pending writes and the memory
+    /// initializations of the command buffers first. It waits for the
+    /// additional queues still using the resources of the submission.
+    pub fn queue_submit_additional<A: HalApi>(
+        &self,
+        queue_id: id::QueueId,
+        queue_index: usize,
+        command_buffer_ids: &[id::CommandBufferId],
+        label: Option<&str>,
+    ) -> Result<WrappedSubmissionIndex, QueueSubmitError> {
+        self.queue_submit_impl::<A>(queue_id, Some(queue_index), command_buffer_ids, label)
+    }
+
+    fn queue_submit_impl<A: HalApi>(
+        &self,
+        queue_id: id::QueueId,
+        additional_queue: Option<usize>,
+        command_buffer_ids: &[id::CommandBufferId],
+        label: Option<&str>,
     ) -> Result<WrappedSubmissionIndex, QueueSubmitError> {
         profiling::scope!("Queue::submit");
 
@@ -1060,10 +1298,29 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             let device = device_guard
                 .get_mut(queue_id)
                 .map_err(|_| DeviceError::Invalid)?;
+            if !device.is_valid() {
+                return Err(DeviceError::Lost.into());
+            }
+            if let Some(index) = additional_queue {
+                if index >= device.additional_queues.len() {
+                    return Err(QueueSubmitError::InvalidAdditionalQueue(index));
+                }
+            }
             device.temp_suspected.clear();
             device.active_submission_index += 1;
             let submit_index = device.active_submission_index;
             let mut active_executions = Vec::new();
+            // The native command buffers to submit to the main queue, in
+            // order: those of an encoder in `active_executions`, followed by
+            // those of a reusable command buffer, if any.
+            let mut submit_parts = Vec::new();
+            // The same for the additional queue, where the encoder is optional
+            // as the memory initializations are submitted to the main queue.
+            let mut additional_parts = Vec::new();
+            // The submissions which last used the resources of this one.
+            let track_previous_uses = !device.additional_queues.is_empty();
+            let mut previous_uses = mem::take(&mut device.pending_writes.previous_uses);
+            let mut used_temp_resources = Vec::new();
             let mut used_surface_textures = track::TextureUsageScope::new();
 
             {
@@ -1081,6 +1338,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     let (mut texture_guard, mut token) = hub.textures.write(&mut token);
                     let (texture_view_guard, mut token) = hub.texture_views.read(&mut token);
                     let (sampler_guard, mut token) = hub.samplers.read(&mut token);
+                    let (acceleration_structure_guard, mut token) =
+                        hub.acceleration_structures.read(&mut token);
                     let (query_set_guard, _) = hub.query_sets.read(&mut token);
 
                     //Note: locking the trackers has to be done after the storages
@@ -1096,25 +1355,54 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         // it, so make sure to set_size on it.
                         used_surface_textures.set_size(texture_guard.len());
 
-                        #[allow(unused_mut)]
-                        let mut cmdbuf = match hub
-                            .command_buffers
-                            .unregister_locked(cmb_id, &mut *command_buffer_guard)
-                        {
-                            Some(cmdbuf) => cmdbuf,
-                            None => continue,
+                        // Reusable command buffers stay registered, the others
+                        // are consumed by the submission.
+                        let reusable = command_buffer_guard
+                            .get(cmb_id)
+                            .map_or(false, |cmdbuf| cmdbuf.reusable);
+                        let mut owned = None;
+                        let cmdbuf = if reusable {
+                            command_buffer_guard.get_mut(cmb_id).unwrap()
+                        } else {
+                            match hub
+                                .command_buffers
+                                .unregister_locked(cmb_id, &mut *command_buffer_guard)
+                            {
+                                Some(cmdbuf) => owned.insert(cmdbuf),
+                                None => continue,
+                            }
                         };
                         #[cfg(feature = "trace")]
                         if let Some(ref trace) = device.trace {
-                            trace.lock().add(Action::Submit(
-                                submit_index,
-                                cmdbuf.commands.take().unwrap(),
-                            ));
+                            let commands = if reusable {
+                                cmdbuf.commands.clone()
+                            } else {
+                                cmdbuf.commands.take()
+                            };
+                            trace
+                                .lock()
+                                .add(Action::Submit(submit_index, commands.unwrap()));
                         }
                         if !cmdbuf.is_finished() {
-                            device.destroy_command_buffer(cmdbuf);
+                            device.destroy_command_buffer(owned.take().unwrap());
                             continue;
                         }
+                        if cmdbuf.additional_queue.map(|(index, _)| index) != additional_queue {
+                            if let Some(cmdbuf) = owned.take() {
+                                device.destroy_command_buffer(cmdbuf);
+                            }
+                            return Err(QueueSubmitError::WrongQueue(cmb_id));
+                        }
+                        if let Some(last_submission) = cmdbuf.last_submission {
+                            if last_submission == submit_index {
+                                return Err(QueueSubmitError::DuplicateCommandBuffer(cmb_id));
+                            }
+                            // The native command buffers can't be pending twice.
+                            let last_done = device.last_done_index().map_err(DeviceError::from)?;
+                            if last_done < last_submission {
+                                return Err(QueueSubmitError::CommandBufferInFlight(cmb_id));
+                            }
+                        }
 
                         // optimize the tracked states
                         // cmdbuf.trackers.optimize();
@@ -1128,6 +1416,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                     return Err(QueueSubmitError::DestroyedBuffer(id.0));
                                 }
                             };
+                            if track_previous_uses {
+                                previous_uses.insert(buffer.life_guard.life_count());
+                            }
                             if !buffer.life_guard.use_at(submit_index) {
                                 if let BufferMapState::Active { .. } = buffer.map_state {
                                     log::warn!("Dropped buffer has a pending mapping.");
@@ -1156,6 +1447,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                     true
                                 }
                             };
+                            if track_previous_uses {
+                                previous_uses.insert(texture.life_guard.life_count());
+                            }
                             if !texture.life_guard.use_at(submit_index) {
                                 device.temp_suspected.textures.push(id);
                             }
@@ -1193,6 +1487,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             for sub_id in bg.used.samplers.used() {
                                 sampler_guard[sub_id].life_guard.use_at(submit_index);
                             }
+                            for sub_id in bg.used.acceleration_structures.used() {
+                                acceleration_structure_guard[sub_id]
+                                    .life_guard
+                                    .use_at(submit_index);
+                            }
                         }
                         // assert!(cmdbuf.trackers.samplers.is_empty());
                         for id in cmdbuf.trackers.compute_pipelines.used() {
@@ -1226,27 +1525,77 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             }
                         }
 
-                        let mut baked = cmdbuf.into_baked();
+                        let mut baked = match owned {
+                            Some(cmdbuf) => cmdbuf.into_baked(),
+                            // The memory initializations and barriers of a reusable
+                            // command buffer are recorded into a separate encoder.
+                            None => {
+                                let cmdbuf = command_buffer_guard.get(cmb_id).unwrap();
+                                let transit = device
+                                    .command_allocator
+                                    .lock()
+                                    .acquire_encoder(
+                                        &device.raw,
+                                        &device.queue,
+                                        cmdbuf.protected,
+                                        None,
+                                    )
+                                    .map_err(DeviceError::from)?;
+                                BakedCommands::for_reusable(cmdbuf, transit)
+                            }
+                        };
+                        // The commands recorded for an additional queue are
+                        // submitted to it, the memory initializations and
+                        // barriers are recorded into an encoder of the main queue.
+                        let mut additional_execution = None;
+                        if let Some(index) = baked.additional_queue.take() {
+                            let transit = device
+                                .command_allocator
+                                .lock()
+                                .acquire_encoder(&device.raw, &device.queue, false, None)
+                                .map_err(DeviceError::from)?;
+                            additional_execution = Some(EncoderInFlight {
+                                raw: mem::replace(&mut baked.encoder, transit),
+                                cmd_buffers: mem::take(&mut baked.list),
+                                protected: false,
+                                additional_queue: Some(index),
+                            });
+                        }
                         // execute resource transitions
                         unsafe {
                             baked
                                 .encoder
-                                .begin_encoding(Some("(wgpu internal) Transit"))
+                                .begin_encoding(Some("(wgpu internal) Transit"), false)
                                 .map_err(DeviceError::from)?
                         };
                         log::trace!("Stitching command buffer {:?} before submission", cmb_id);
                         baked
-                            .initialize_buffer_memory(&mut *trackers, &mut *buffer_guard)
+                            .initialize_buffer_memory(
+                                &mut *trackers,
+                                &mut *buffer_guard,
+                                &mut device.pending_writes,
+                            )
                             .map_err(|err| QueueSubmitError::DestroyedBuffer(err.0))?;
                         baked
-                            .initialize_texture_memory(&mut *trackers, &mut *texture_guard, device)
+                            .initialize_texture_memory(
+                                &mut *trackers,
+                                &mut *texture_guard,
+                                &mut device.pending_writes,
+                                &device.alignments,
+                                &device.zero_buffer,
+                            )
                             .map_err(|err| QueueSubmitError::DestroyedTexture(err.0))?;
+                        let head_trackers = if reusable {
+                            &command_buffer_guard.get(cmb_id).unwrap().trackers
+                        } else {
+                            &baked.trackers
+                        };
                         //Note: stateless trackers are not merged:
                         // device already knows these resources exist.
                         CommandBuffer::insert_barriers_from_tracker(
                             &mut baked.encoder,
                             &mut *trackers,
-                            &baked.trackers,
+                            head_trackers,
                             &*buffer_guard,
                             &*texture_guard,
                         );
@@ -1261,7 +1610,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             unsafe {
                                 baked
                                     .encoder
-                                    .begin_encoding(Some("(wgpu internal) Present"))
+                                    .begin_encoding(Some("(wgpu internal) Present"), false)
                                     .map_err(DeviceError::from)?
                             };
                             trackers
@@ -1280,9 +1629,23 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         }
 
                         // done
+                        used_temp_resources.append(&mut baked.temp_resources);
+                        let reusable_id = reusable.then_some(cmb_id);
+                        if additional_queue.is_some() {
+                            let index = additional_execution.map(|execution| {
+                                active_executions.push(execution);
+                                active_executions.len() - 1
+                            });
+                            additional_parts.push((index, reusable_id));
+                            submit_parts.push((active_executions.len(), None));
+                        } else {
+                            submit_parts.push((active_executions.len(), reusable_id));
+                        }
                         active_executions.push(EncoderInFlight {
                             raw: baked.encoder,
                             cmd_buffers: baked.list,
+                            protected: baked.protected,
+                            additional_queue: None,
                         });
                     }
 
@@ -1293,8 +1656,17 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     ref mut pending_writes,
                     ref mut queue,
                     ref mut fence,
+                    ref mut additional_queues,
+                    ref valid,
                     ..
                 } = *device;
+                let map_submit_error = |error| {
+                    if let hal::DeviceError::Lost = error {
+                        // The closure is called by the next `maintain`.
+                        valid.store(false, Ordering::Release);
+                    }
+                    DeviceError::from(error)
+                };
 
                 {
                     // TODO: These blocks have a few organizational issues, and
@@ -1359,16 +1731,64 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 let refs = pending_writes
                     .pre_submit()
                     .into_iter()
-                    .chain(
-                        active_executions
-                            .iter()
-                            .flat_map(|pool_execution| pool_execution.cmd_buffers.iter()),
-                    )
+                    .chain(submit_parts.iter().flat_map(|&(index, reusable_id)| {
+                        let reusable = reusable_id.map_or(&[][..], |id| {
+                            command_buffer_guard.get(id).unwrap().raw_command_buffers()
+                        });
+                        active_executions[index].cmd_buffers.iter().chain(reusable)
+                    }))
                     .collect::<Vec<_>>();
+                // Wait for the additional queues still using the resources.
+                for additional in additional_queues.iter() {
+                    let last_use = additional
+                        .in_flight
+                        .lock()
+                        .iter()
+                        .rev()
+                        .find(|&index| previous_uses.contains(index))
+                        .copied();
+                    if let Some(value) = last_use {
+                        unsafe { queue.wait_for_fence(&additional.fence, value) }
+                            .map_err(map_submit_error)?;
+                    }
+                }
                 unsafe {
                     queue
-                        .submit(&refs, Some((fence, submit_index)))
-                        .map_err(DeviceError::from)?;
+                        .submit(&refs, Some((fence, submit_index)), label)
+                        .map_err(map_submit_error)?;
+                }
+                if let Some(index) = additional_queue {
+                    let additional = &mut additional_queues[index];
+                    let refs = additional_parts
+                        .iter()
+                        .flat_map(|&(index, reusable_id)| {
+                            let reusable = reusable_id.map_or(&[][..], |id| {
+                                command_buffer_guard.get(id).unwrap().raw_command_buffers()
+                            });
+                            index
+                                .map_or(&[][..], |index| &active_executions[index].cmd_buffers[..])
+                                .iter()
+                                .chain(reusable)
+                        })
+                        .collect::<Vec<_>>();
+                    unsafe {
+                        additional
+                            .raw
+                            .wait_for_fence(fence, submit_index)
+                            .map_err(map_submit_error)?;
+                        additional
+                            .raw
+                            .submit(&refs, Some((&mut additional.fence, submit_index)), label)
+                            .map_err(map_submit_error)?;
+                    }
+                    additional.in_flight.lock().push_back(submit_index);
+                }
+                let reusable_ids = submit_parts
+                    .iter()
+                    .map(|&(_, id)| id)
+                    .chain(additional_parts.iter().map(|&(_, id)| id));
+                for id in reusable_ids.flatten() {
+                    command_buffer_guard.get_mut(id).unwrap().last_submission = Some(submit_index);
                 }
             }
 
@@ -1385,7 +1805,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             let mut pending_write_resources = mem::take(&mut device.pending_writes.temp_resources);
             device.lock_life(&mut token).track_submission(
                 submit_index,
-                pending_write_resources.drain(..),
+                pending_write_resources.drain(..).chain(used_temp_resources),
                 active_executions,
             );
 
@@ -1401,6 +1821,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             // pending_write_resources has been drained, so it's empty, but we
             // want to retain its heap allocation.
             device.pending_writes.temp_resources = pending_write_resources;
+            previous_uses.clear();
+            device.pending_writes.previous_uses = previous_uses;
             device.temp_suspected.clear();
             device.lock_life(&mut token).post_submit();
 
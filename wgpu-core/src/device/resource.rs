@@ -12,6 +12,7 @@ use crate::{
     hub::{Hub, Token},
     id,
     identity::GlobalIdentityHandlerFactory,
+    indirect_validation::IndirectValidation,
     init_tracker::{
         BufferInitTracker, BufferInitTrackerAction, MemoryInitKind, TextureInitRange,
         TextureInitTracker, TextureInitTrackerAction,
@@ -22,21 +23,28 @@ use crate::{
     storage::Storage,
     track::{BindGroupStates, TextureSelector, Tracker},
     validation::{self, check_buffer_usage, check_texture_usage},
-    FastHashMap, LabelHelpers as _, LifeGuard, MultiRefCount, RefCount, Stored, SubmissionIndex,
+    FastHashMap, Label, LabelHelpers as _, LifeGuard, MultiRefCount, RefCount, Stored,
+    SubmissionIndex,
 };
 
+use crate::lock::{Mutex, MutexGuard};
 use arrayvec::ArrayVec;
 use hal::{CommandEncoder as _, Device as _};
-use parking_lot::{Mutex, MutexGuard};
 use smallvec::SmallVec;
 use thiserror::Error;
 use wgt::{TextureFormat, TextureSampleType, TextureViewDimension};
 
-use std::{borrow::Cow, iter, num::NonZeroU32};
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    iter,
+    num::NonZeroU32,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use super::{
-    life, queue, DeviceDescriptor, DeviceError, ImplicitPipelineContext, UserClosures, EP_FAILURE,
-    IMPLICIT_FAILURE, ZERO_BUFFER_SIZE,
+    life, queue, snapshot, DeviceDescriptor, DeviceError, DeviceLostClosure, DeviceLostInvocation,
+    ImplicitPipelineContext, UserClosures, EP_FAILURE, IMPLICIT_FAILURE, ZERO_BUFFER_SIZE,
 };
 
 /// Structure describing a logical device. Some members are internally mutable,
@@ -54,7 +62,12 @@ pub struct Device<A: HalApi> {
     pub(crate) raw: A::Device,
     pub(crate) adapter_id: Stored<id::AdapterId>,
     pub(crate) queue: A::Queue,
+    /// The queues requested in `DeviceDescriptor::additional_queues`, in order.
+    pub(crate) additional_queues: Vec<queue::AdditionalQueue<A>>,
     pub(crate) zero_buffer: A::Buffer,
+    /// Validates the arguments of indirect draws, unless the adapter executes
+    /// them robustly.
+    pub(crate) indirect_validation: Option<IndirectValidation<A>>,
     //pub(crate) cmd_allocator: command::CommandAllocator<A>,
     //mem_allocator: Mutex<alloc::MemoryAllocator<A>>,
     //desc_allocator: Mutex<descriptor::DescriptorAllocator<A>>,
@@ -70,7 +83,16 @@ pub struct Device<A: HalApi> {
 
     pub(super) command_allocator: Mutex<CommandAllocator<A>>,
     pub(crate) active_submission_index: SubmissionIndex,
-    pub(super) fence: A::Fence,
+    pub(crate) fence: A::Fence,
+
+    /// Cleared once the backend reports the device as lost. From then on,
+    /// the device only accepts being polled and dropped.
+    pub(super) valid: AtomicBool,
+    /// Taken when the device is lost or dropped, whichever comes first.
+    pub(super) device_lost_closure: Mutex<Option<DeviceLostClosure>>,
+    /// Whether new buffers and textures are cleared before their first use,
+    /// as WebGPU requires. See `Global::device_set_zero_initialization`.
+    pub(super) zero_initialize: bool,
 
     /// All live resources allocated with this [`Device`].
     ///
@@ -78,6 +100,9 @@ pub struct Device<A: HalApi> {
     pub(crate) trackers: Mutex<Tracker<A>>,
     // Life tracker should be locked right after the device and before anything else.
     life_tracker: Mutex<life::LifetimeTracker<A>>,
+    /// Canonical bind group layouts, used to deduplicate layouts with
+    /// identical entries.
+    pub(super) bgl_pool: Mutex<binding_model::BindGroupLayoutPool>,
     /// Temporary storage for resource management functions. Cleared at the end
     /// of every call (unless an error occurs).
     pub(super) temp_suspected: life::SuspectedResources,
@@ -85,6 +110,9 @@ pub struct Device<A: HalApi> {
     pub(crate) limits: wgt::Limits,
     pub(crate) features: wgt::Features,
     pub(crate) downlevel: wgt::DownlevelCapabilities,
+    /// Flags of the instance the device was created from, which control how
+    /// labels are passed to the backend.
+    pub(crate) instance_flags: wgt::InstanceFlags,
     // TODO: move this behind another mutex. This would allow several methods to
     // switch to borrow Device immutably, such as `write_buffer`, `write_texture`,
     // and `buffer_unmap`.
@@ -100,9 +128,38 @@ pub enum CreateDeviceError {
     OutOfMemory,
     #[error("Failed to create internal buffer for initializing textures")]
     FailedToCreateZeroBuffer(#[from] DeviceError),
+    #[error("Failed to create internal pipeline for validating indirect draws")]
+    FailedToCreateIndirectValidation(#[source] DeviceError),
 }
 
 impl<A: HalApi> Device<A> {
+    /// Returns `false` once the backend has reported the device as lost.
+    pub(crate) fn is_valid(&self) -> bool {
+        self.valid.load(Ordering::Acquire)
+    }
+
+    pub(super) fn mark_lost(&self) {
+        if self.valid.swap(false, Ordering::AcqRel) {
+            log::error!("Device is lost");
+        }
+    }
+
+    /// Takes the device lost closure, to be called once nothing is locked.
+    pub(super) fn take_device_lost_invocation(
+        &self,
+        reason: wgt::DeviceLostReason,
+        message: &str,
+    ) -> Option<DeviceLostInvocation> {
+        self.device_lost_closure
+            .lock()
+            .take()
+            .map(|closure| DeviceLostInvocation {
+                closure,
+                reason,
+                message: message.to_string(),
+            })
+    }
+
     pub(crate) fn require_features(&self, feature: wgt::Features) -> Result<(), MissingFeatures> {
         if self.features.contains(feature) {
             Ok(())
@@ -130,6 +187,7 @@ impl<A: HalApi> Device<A> {
         alignments: hal::Alignments,
         downlevel: wgt::DownlevelCapabilities,
         desc: &DeviceDescriptor,
+        instance_flags: wgt::InstanceFlags,
         trace_path: Option<&std::path::Path>,
     ) -> Result<Self, CreateDeviceError> {
         #[cfg(not(feature = "trace"))]
@@ -139,11 +197,31 @@ impl<A: HalApi> Device<A> {
         let fence =
             unsafe { open.device.create_fence() }.map_err(|_| CreateDeviceError::OutOfMemory)?;
 
+        let mut additional_queues = Vec::with_capacity(open.additional_queues.len());
+        for (raw, &kind) in open
+            .additional_queues
+            .into_iter()
+            .zip(&desc.additional_queues)
+        {
+            let fence = unsafe { open.device.create_fence() }
+                .map_err(|_| CreateDeviceError::OutOfMemory)?;
+            additional_queues.push(queue::AdditionalQueue {
+                raw,
+                kind,
+                fence,
+                in_flight: Mutex::new(VecDeque::new()),
+            });
+        }
+
         let mut com_alloc = CommandAllocator {
             free_encoders: Vec::new(),
+            free_protected_encoders: Vec::new(),
+            free_additional_encoders: additional_queues.iter().map(|_| Vec::new()).collect(),
         };
+        // Pending writes upload unprotected staging data, so they are never
+        // recorded into protected command buffers.
         let pending_encoder = com_alloc
-            .acquire_encoder(&open.device, &open.queue)
+            .acquire_encoder(&open.device, &open.queue, false, None)
             .map_err(|_| CreateDeviceError::OutOfMemory)?;
         let mut pending_writes = queue::PendingWrites::<A>::new(pending_encoder);
 
@@ -177,20 +255,44 @@ impl<A: HalApi> Device<A> {
                 }));
         }
 
+        let allow_first_instance = desc
+            .features
+            .contains(wgt::Features::INDIRECT_FIRST_INSTANCE);
+        let indirect_validation = if downlevel.flags.contains(
+            wgt::DownlevelFlags::COMPUTE_SHADERS | wgt::DownlevelFlags::INDIRECT_EXECUTION,
+        ) && !(downlevel
+            .flags
+            .contains(wgt::DownlevelFlags::ROBUST_INDIRECT_EXECUTION)
+            && allow_first_instance)
+        {
+            Some(
+                IndirectValidation::new(&open.device, allow_first_instance)
+                    .map_err(CreateDeviceError::FailedToCreateIndirectValidation)?,
+            )
+        } else {
+            None
+        };
+
         let life_guard = LifeGuard::new("<device>");
         let ref_count = life_guard.add_ref();
         Ok(Self {
             raw: open.device,
             adapter_id,
             queue: open.queue,
+            additional_queues,
             zero_buffer,
+            indirect_validation,
             life_guard,
             ref_count,
             command_allocator: Mutex::new(com_alloc),
             active_submission_index: 0,
             fence,
+            valid: AtomicBool::new(true),
+            device_lost_closure: Mutex::new(None),
+            zero_initialize: true,
             trackers: Mutex::new(Tracker::new()),
             life_tracker: Mutex::new(life::LifetimeTracker::new()),
+            bgl_pool: Mutex::new(binding_model::BindGroupLayoutPool::default()),
             temp_suspected: life::SuspectedResources::default(),
             #[cfg(feature = "trace")]
             trace: trace_path.and_then(|path| match trace::Trace::new(path) {
@@ -208,8 +310,17 @@ impl<A: HalApi> Device<A> {
             }),
             alignments,
             limits: desc.limits.clone(),
-            features: desc.features,
+            // The full BCn family implies its BC1-3 subset.
+            features: if desc
+                .features
+                .contains(wgt::Features::TEXTURE_COMPRESSION_BC)
+            {
+                desc.features | wgt::Features::TEXTURE_COMPRESSION_S3TC
+            } else {
+                desc.features
+            },
             downlevel,
+            instance_flags,
             pending_writes,
         })
     }
@@ -256,33 +367,47 @@ impl<A: HalApi> Device<A> {
         life_tracker.triage_suspected(
             hub,
             &self.trackers,
+            &self.bgl_pool,
             #[cfg(feature = "trace")]
             self.trace.as_ref(),
             token,
         );
         life_tracker.triage_mapped(hub, token);
 
-        let last_done_index = if maintain.is_wait() {
-            let index_to_wait_for = match maintain {
+        let fence_value = if !self.is_valid() {
+            Err(hal::DeviceError::Lost)
+        } else if maintain.is_wait() {
+            // We don't need to check to see if the queue id of a submission index
+            // matches as we already checked this from inside the poll call.
+            let (index_to_wait_for, timeout_ms) = match maintain {
                 wgt::Maintain::WaitForSubmissionIndex(submission_index) => {
-                    // We don't need to check to see if the queue id matches
-                    // as we already checked this from inside the poll call.
-                    submission_index.index
+                    (submission_index.index, CLEANUP_WAIT_MS)
                 }
-                _ => self.active_submission_index,
-            };
-            unsafe {
-                self.raw
-                    .wait(&self.fence, index_to_wait_for, CLEANUP_WAIT_MS)
-                    .map_err(DeviceError::from)?
+                wgt::Maintain::WaitForSubmissionIndexWithTimeout(submission_index, timeout) => (
+                    submission_index.index,
+                    timeout.as_millis().min(u32::MAX as u128) as u32,
+                ),
+                _ => (self.active_submission_index, CLEANUP_WAIT_MS),
             };
-            index_to_wait_for
-        } else {
-            unsafe {
-                self.raw
-                    .get_fence_value(&self.fence)
-                    .map_err(DeviceError::from)?
+            match self.wait_for_index(index_to_wait_for, timeout_ms) {
+                Ok(true) => Ok(index_to_wait_for),
+                // Timed out, only consider what has actually completed.
+                Ok(false) => self.last_done_index(),
+                Err(error) => Err(error),
             }
+        } else {
+            self.last_done_index()
+        };
+        let last_done_index = match fence_value {
+            Ok(index) => index,
+            // A lost device never signals its fence again. Consider all the
+            // submissions done, so that pending callbacks fire and resources
+            // get freed.
+            Err(hal::DeviceError::Lost) => {
+                self.mark_lost();
+                self.active_submission_index
+            }
+            Err(error) => return Err(DeviceError::from(error).into()),
         };
 
         let submission_closures =
@@ -290,13 +415,62 @@ impl<A: HalApi> Device<A> {
         let mapping_closures = life_tracker.handle_mapping(hub, &self.raw, &self.trackers, token);
         life_tracker.cleanup(&self.raw);
 
+        let mut device_lost_invocations = SmallVec::new();
+        if !self.is_valid() {
+            device_lost_invocations.extend(self.take_device_lost_invocation(
+                wgt::DeviceLostReason::Unknown,
+                "Device was lost by the backend",
+            ));
+        }
+
         let closures = UserClosures {
             mappings: mapping_closures,
             submissions: submission_closures,
+            device_lost_invocations,
         };
         Ok((closures, life_tracker.queue_empty()))
     }
 
+    /// Returns the index of the last submission that completed on every queue.
+    pub(super) fn last_done_index(&self) -> Result<SubmissionIndex, hal::DeviceError> {
+        let mut last_done = unsafe { self.raw.get_fence_value(&self.fence) }?;
+        for queue in self.additional_queues.iter() {
+            let completed = unsafe { self.raw.get_fence_value(&queue.fence) }?;
+            let mut in_flight = queue.in_flight.lock();
+            while in_flight.front().map_or(false, |&index| index <= completed) {
+                in_flight.pop_front();
+            }
+            if let Some(&index) = in_flight.front() {
+                last_done = last_done.min(index - 1);
+            }
+        }
+        Ok(last_done)
+    }
+
+    /// Waits for the submissions up to `index` to complete on every queue.
+    ///
+    /// Returns `false` if any of the waits timed out.
+    fn wait_for_index(
+        &self,
+        index: SubmissionIndex,
+        timeout_ms: u32,
+    ) -> Result<bool, hal::DeviceError> {
+        let mut done = unsafe { self.raw.wait(&self.fence, index, timeout_ms) }?;
+        for queue in self.additional_queues.iter() {
+            let last_submitted = queue
+                .in_flight
+                .lock()
+                .iter()
+                .rev()
+                .find(|&&submitted| submitted <= index)
+                .copied();
+            if let Some(value) = last_submitted {
+                done &= unsafe { self.raw.wait(&queue.fence, value, timeout_ms) }?;
+            }
+        }
+        Ok(done)
+    }
+
     pub(super) fn untrack<'this, 'token: 'this, G: GlobalIdentityHandlerFactory>(
         &'this mut self,
         hub: &Hub<A, G>,
@@ -314,7 +488,8 @@ impl<A: HalApi> Device<A> {
             let (buffer_guard, mut token) = hub.buffers.read(&mut token);
             let (texture_guard, mut token) = hub.textures.read(&mut token);
             let (texture_view_guard, mut token) = hub.texture_views.read(&mut token);
-            let (sampler_guard, _) = hub.samplers.read(&mut token);
+            let (sampler_guard, mut token) = hub.samplers.read(&mut token);
+            let (acceleration_structure_guard, _) = hub.acceleration_structures.read(&mut token);
 
             for id in trackers.buffers.used() {
                 if buffer_guard[id].life_guard.ref_count.is_none() {
@@ -341,6 +516,15 @@ impl<A: HalApi> Device<A> {
                     self.temp_suspected.samplers.push(id);
                 }
             }
+            for id in trackers.acceleration_structures.used() {
+                if acceleration_structure_guard[id]
+                    .life_guard
+                    .ref_count
+                    .is_none()
+                {
+                    self.temp_suspected.acceleration_structures.push(id);
+                }
+            }
             for id in trackers.compute_pipelines.used() {
                 if compute_pipe_guard[id].life_guard.ref_count.is_none() {
                     self.temp_suspected.compute_pipelines.push(id);
@@ -397,6 +581,18 @@ impl<A: HalApi> Device<A> {
             return Err(resource::CreateBufferError::InvalidUsage(desc.usage));
         }
 
+        let protected = desc.usage.contains(wgt::BufferUsages::PROTECTED);
+        if protected {
+            self.require_features(wgt::Features::PROTECTED_CONTENT)?;
+            if desc.mapped_at_creation
+                || desc
+                    .usage
+                    .intersects(wgt::BufferUsages::MAP_READ | wgt::BufferUsages::MAP_WRITE)
+            {
+                return Err(resource::CreateBufferError::MappedProtected);
+            }
+        }
+
         if !self
             .features
             .contains(wgt::Features::MAPPABLE_PRIMARY_BUFFERS)
@@ -425,6 +621,11 @@ impl<A: HalApi> Device<A> {
             usage |= hal::BufferUses::COPY_DST;
         }
 
+        if self.indirect_validation.is_some() && desc.usage.contains(wgt::BufferUsages::INDIRECT) {
+            // Indirect draw arguments are copied out to be validated.
+            usage |= hal::BufferUses::COPY_SRC;
+        }
+
         let actual_size = if desc.size == 0 {
             wgt::COPY_BUFFER_ALIGNMENT
         } else if desc.usage.contains(wgt::BufferUsages::VERTEX) {
@@ -443,9 +644,10 @@ impl<A: HalApi> Device<A> {
 
         let mut memory_flags = hal::MemoryFlags::empty();
         memory_flags.set(hal::MemoryFlags::TRANSIENT, transient);
+        memory_flags.set(hal::MemoryFlags::PROTECTED, protected);
 
         let hal_desc = hal::BufferDescriptor {
-            label: desc.label.borrow_option(),
+            label: desc.label.to_hal(self.instance_flags),
             size: aligned_size,
             usage,
             memory_flags,
@@ -460,7 +662,13 @@ impl<A: HalApi> Device<A> {
             },
             usage: desc.usage,
             size: desc.size,
-            initialization_status: BufferInitTracker::new(desc.size),
+            // Without zero initialization, the buffer is considered
+            // initialized with whatever the memory contains.
+            initialization_status: BufferInitTracker::new(if self.zero_initialize {
+                desc.size
+            } else {
+                0
+            }),
             sync_mapped_writes: None,
             map_state: resource::BufferMapState::Idle,
             life_guard: LifeGuard::new(desc.label.borrow_or_default()),
@@ -491,7 +699,11 @@ impl<A: HalApi> Device<A> {
             format_features,
             initialization_status: TextureInitTracker::new(
                 desc.mip_level_count,
-                desc.array_layer_count(),
+                if self.zero_initialize {
+                    desc.array_layer_count()
+                } else {
+                    0
+                },
             ),
             full_range: TextureSelector {
                 mips: 0..desc.mip_level_count,
@@ -537,6 +749,27 @@ impl<A: HalApi> Device<A> {
             return Err(CreateTextureError::InvalidUsage(desc.usage));
         }
 
+        let protected = desc.usage.contains(wgt::TextureUsages::PROTECTED);
+        if protected {
+            self.require_features(wgt::Features::PROTECTED_CONTENT)
+                .map_err(|error| {
+                    CreateTextureError::MissingUsageFeatures(wgt::TextureUsages::PROTECTED, error)
+                })?;
+        }
+
+        let transient = desc.usage.contains(wgt::TextureUsages::TRANSIENT);
+        if transient {
+            let other_usages = desc.usage
+                - wgt::TextureUsages::TRANSIENT
+                - wgt::TextureUsages::PROTECTED
+                - wgt::TextureUsages::RENDER_ATTACHMENT;
+            if !other_usages.is_empty()
+                || !desc.usage.contains(wgt::TextureUsages::RENDER_ATTACHMENT)
+            {
+                return Err(CreateTextureError::InvalidTransientUsage(desc.usage));
+            }
+        }
+
         conv::check_texture_dimension_size(
             desc.dimension,
             desc.size,
@@ -552,25 +785,68 @@ impl<A: HalApi> Device<A> {
                     desc.format,
                 ));
             }
-            // Renderable textures can only be 2D
-            if desc.usage.contains(wgt::TextureUsages::RENDER_ATTACHMENT) {
+            // Renderable textures can only be 2D or 3D
+            if desc.dimension == wgt::TextureDimension::D1
+                && desc.usage.contains(wgt::TextureUsages::RENDER_ATTACHMENT)
+            {
                 return Err(CreateTextureError::InvalidDimensionUsages(
                     wgt::TextureUsages::RENDER_ATTACHMENT,
                     desc.dimension,
                 ));
             }
 
-            // Compressed textures can only be 2D
+            // Compressed textures can only be 2D, or 3D with the sliced 3D features
             if desc.format.is_compressed() {
-                return Err(CreateTextureError::InvalidCompressedDimension(
+                let format_features = desc.format.required_features();
+                let sliced_3d_feature = if format_features.intersects(
+                    wgt::Features::TEXTURE_COMPRESSION_BC | wgt::Features::TEXTURE_COMPRESSION_S3TC,
+                ) {
+                    wgt::Features::TEXTURE_COMPRESSION_BC_SLICED_3D
+                } else if format_features.intersects(
+                    wgt::Features::TEXTURE_COMPRESSION_ASTC
+                        | wgt::Features::TEXTURE_COMPRESSION_ASTC_HDR,
+                ) {
+                    wgt::Features::TEXTURE_COMPRESSION_ASTC_SLICED_3D
+                } else {
+                    wgt::Features::empty()
+                };
+                if desc.dimension != wgt::TextureDimension::D3 || sliced_3d_feature.is_empty() {
+                    return Err(CreateTextureError::InvalidCompressedDimension(
+                        desc.dimension,
+                        desc.format,
+                    ));
+                }
+                self.require_features(sliced_3d_feature)
+                    .map_err(|error| CreateTextureError::MissingFeatures(desc.format, error))?;
+            }
+
+            // Multi-planar textures can only be 2D
+            if desc.format.is_multi_planar_format() {
+                return Err(CreateTextureError::InvalidMultiPlanarDimension(
                     desc.dimension,
                     desc.format,
                 ));
             }
         }
 
-        if desc.format.is_compressed() {
-            let (block_width, block_height) = desc.format.block_dimensions();
+        if desc.format.is_multi_planar_format() {
+            if desc.size.depth_or_array_layers != 1 {
+                return Err(CreateTextureError::InvalidMultiPlanarArrayLayerCount(
+                    desc.size.depth_or_array_layers,
+                    desc.format,
+                ));
+            }
+
+            if desc.mip_level_count != 1 {
+                return Err(CreateTextureError::InvalidMipLevelCount {
+                    requested: desc.mip_level_count,
+                    maximum: 1,
+                });
+            }
+        }
+
+        {
+            let (block_width, block_height) = desc.format.size_multiple_requirement();
 
             if desc.size.width % block_width != 0 {
                 return Err(CreateTextureError::InvalidDimension(
@@ -650,14 +926,17 @@ impl<A: HalApi> Device<A> {
             });
         }
 
-        let missing_allowed_usages = desc.usage - format_features.allowed_usages;
+        // `PROTECTED` and `TRANSIENT` only affect the memory the texture is allocated from
+        let format_usage =
+            desc.usage - wgt::TextureUsages::PROTECTED - wgt::TextureUsages::TRANSIENT;
+        let missing_allowed_usages = format_usage - format_features.allowed_usages;
         if !missing_allowed_usages.is_empty() {
             // detect downlevel incompatibilities
             let wgpu_allowed_usages = desc
                 .format
                 .guaranteed_format_features(self.features)
                 .allowed_usages;
-            let wgpu_missing_usages = desc.usage - wgpu_allowed_usages;
+            let wgpu_missing_usages = format_usage - wgpu_allowed_usages;
             return Err(CreateTextureError::InvalidFormatUsages(
                 missing_allowed_usages,
                 desc.format,
@@ -692,7 +971,7 @@ impl<A: HalApi> Device<A> {
                     .allowed_usages
                     .contains(wgt::TextureUsages::RENDER_ATTACHMENT)
                     && desc.dimension == wgt::TextureDimension::D2
-                // Render targets dimension must be 2d
+                // Only 2D textures are cleared with render passes
                 {
                     hal::TextureUses::COLOR_TARGET
                 } else {
@@ -700,15 +979,19 @@ impl<A: HalApi> Device<A> {
                 }
             };
 
+        let mut memory_flags = hal::MemoryFlags::empty();
+        memory_flags.set(hal::MemoryFlags::TRANSIENT, transient);
+        memory_flags.set(hal::MemoryFlags::PROTECTED, protected);
+
         let hal_desc = hal::TextureDescriptor {
-            label: desc.label.borrow_option(),
+            label: desc.label.to_hal(self.instance_flags),
             size: desc.size,
             mip_level_count: desc.mip_level_count,
             sample_count: desc.sample_count,
             dimension: desc.dimension,
             format: desc.format,
             usage: hal_usage,
-            memory_flags: hal::MemoryFlags::empty(),
+            memory_flags,
             view_formats: hal_view_formats,
         };
 
@@ -718,8 +1001,10 @@ impl<A: HalApi> Device<A> {
                 .map_err(DeviceError::from)?
         };
 
-        let clear_mode = if hal_usage
-            .intersects(hal::TextureUses::DEPTH_STENCIL_WRITE | hal::TextureUses::COLOR_TARGET)
+        // 3D textures are cleared with copies, as they always have COPY_DST.
+        let clear_mode = if desc.dimension != wgt::TextureDimension::D3
+            && hal_usage
+                .intersects(hal::TextureUses::DEPTH_STENCIL_WRITE | hal::TextureUses::COLOR_TARGET)
         {
             let (is_color, usage) = if desc.format.is_depth_stencil_format() {
                 (false, hal::TextureUses::DEPTH_STENCIL_WRITE)
@@ -747,6 +1032,7 @@ impl<A: HalApi> Device<A> {
                             base_array_layer: array_layer,
                             array_layer_count: Some(1),
                         },
+                        swizzle: wgt::TextureSwizzle::IDENTITY,
                     };
                     clear_views.push(
                         unsafe { self.raw.create_texture_view(&raw_texture, &desc) }
@@ -833,8 +1119,14 @@ impl<A: HalApi> Device<A> {
                     }
                 });
 
+        let swizzle = desc.swizzle.unwrap_or_default();
+
         // validate TextureViewDescriptor
 
+        if !swizzle.is_identity() {
+            self.require_features(wgt::Features::TEXTURE_COMPONENT_SWIZZLE)?;
+        }
+
         let aspects = hal::FormatAspects::new(texture.desc.format, desc.range.aspect);
         if aspects.is_empty() {
             return Err(resource::CreateTextureViewError::InvalidAspect {
@@ -842,6 +1134,12 @@ impl<A: HalApi> Device<A> {
                 requested_aspect: desc.range.aspect,
             });
         }
+        if texture.desc.format.is_multi_planar_format() && !aspects.is_one() {
+            return Err(resource::CreateTextureViewError::InvalidMultiPlaneAspect {
+                texture_format: texture.desc.format,
+                requested_aspect: desc.range.aspect,
+            });
+        }
 
         let format_is_good = if desc.range.aspect == wgt::TextureAspect::All {
             resolved_format == texture.desc.format
@@ -963,6 +1261,7 @@ impl<A: HalApi> Device<A> {
             }
 
             if !(resolved_dimension == TextureViewDimension::D2
+                || resolved_dimension == TextureViewDimension::D3
                 || (self.features.contains(wgt::Features::MULTIVIEW)
                     && resolved_dimension == TextureViewDimension::D2Array))
             {
@@ -989,6 +1288,10 @@ impl<A: HalApi> Device<A> {
                 break 'b Err(TextureViewNotRenderableReason::Aspects(aspects));
             }
 
+            if !swizzle.is_identity() {
+                break 'b Err(TextureViewNotRenderableReason::Swizzle(swizzle));
+            }
+
             break 'b Ok(texture
                 .desc
                 .compute_render_extent(desc.range.base_mip_level));
@@ -1003,6 +1306,7 @@ impl<A: HalApi> Device<A> {
                 }
                 wgt::TextureViewDimension::D3 => {
                     hal::TextureUses::RESOURCE
+                        | hal::TextureUses::COLOR_TARGET
                         | hal::TextureUses::STORAGE_READ
                         | hal::TextureUses::STORAGE_READ_WRITE
                 }
@@ -1013,7 +1317,12 @@ impl<A: HalApi> Device<A> {
             } else {
                 hal::TextureUses::RESOURCE
             };
-            texture.hal_usage & mask_copy & mask_dimension & mask_mip_level
+            let mask_swizzle = if swizzle.is_identity() {
+                hal::TextureUses::all()
+            } else {
+                hal::TextureUses::RESOURCE
+            };
+            texture.hal_usage & mask_copy & mask_dimension & mask_mip_level & mask_swizzle
         };
 
         log::debug!(
@@ -1038,11 +1347,12 @@ impl<A: HalApi> Device<A> {
         };
 
         let hal_desc = hal::TextureViewDescriptor {
-            label: desc.label.borrow_option(),
+            label: desc.label.to_hal(self.instance_flags),
             format,
             dimension: resolved_dimension,
             usage,
             range: resolved_range,
+            swizzle,
         };
 
         let raw = unsafe {
@@ -1067,6 +1377,7 @@ impl<A: HalApi> Device<A> {
                 format: resolved_format,
                 dimension: resolved_dimension,
                 range: resolved_range,
+                swizzle,
             },
             format_features: texture.format_features,
             render_extent,
@@ -1093,6 +1404,10 @@ impl<A: HalApi> Device<A> {
             self.require_features(wgt::Features::ADDRESS_MODE_CLAMP_TO_ZERO)?;
         }
 
+        if let Some(wgt::SamplerBorderColor::Custom(_)) = desc.border_color {
+            self.require_features(wgt::Features::ADDRESS_MODE_CLAMP_TO_BORDER_CUSTOM)?;
+        }
+
         if desc.lod_min_clamp < 0.0 {
             return Err(resource::CreateSamplerError::InvalidLodMinClamp(
                 desc.lod_min_clamp,
@@ -1156,7 +1471,7 @@ impl<A: HalApi> Device<A> {
         //TODO: check for wgt::DownlevelFlags::COMPARISON_SAMPLERS
 
         let hal_desc = hal::SamplerDescriptor {
-            label: desc.label.borrow_option(),
+            label: desc.label.to_hal(self.instance_flags),
             address_modes: desc.address_modes,
             mag_filter: desc.mag_filter,
             min_filter: desc.min_filter,
@@ -1191,7 +1506,7 @@ impl<A: HalApi> Device<A> {
         desc: &pipeline::ShaderModuleDescriptor<'a>,
         source: pipeline::ShaderModuleSource<'a>,
     ) -> Result<pipeline::ShaderModule<A>, pipeline::CreateShaderModuleError> {
-        let (module, source) = match source {
+        let (module, source, is_wgsl) = match source {
             #[cfg(feature = "wgsl")]
             pipeline::ShaderModuleSource::Wgsl(code) => {
                 profiling::scope!("naga::wgsl::parse_str");
@@ -1202,9 +1517,22 @@ impl<A: HalApi> Device<A> {
                         inner: Box::new(inner),
                     })
                 })?;
-                (Cow::Owned(module), code.into_owned())
+                (Cow::Owned(module), code.into_owned(), true)
+            }
+            #[cfg(feature = "glsl")]
+            pipeline::ShaderModuleSource::Glsl(code, options) => {
+                profiling::scope!("naga::glsl::parse");
+                let mut parser = naga::front::glsl::Frontend::default();
+                let module = parser.parse(&options, &code).map_err(|errors| {
+                    pipeline::CreateShaderModuleError::ParsingGlsl(pipeline::ShaderError {
+                        source: code.to_string(),
+                        label: desc.label.as_ref().map(|l| l.to_string()),
+                        inner: Box::new(pipeline::GlslParseError { errors }),
+                    })
+                })?;
+                (Cow::Owned(module), code.into_owned(), false)
             }
-            pipeline::ShaderModuleSource::Naga(module) => (module, String::new()),
+            pipeline::ShaderModuleSource::Naga(module) => (module, String::new(), false),
             pipeline::ShaderModuleSource::Dummy(_) => panic!("found `ShaderModuleSource::Dummy`"),
         };
         for (_, var) in module.global_variables.iter() {
@@ -1276,22 +1604,34 @@ impl<A: HalApi> Device<A> {
                 .flags
                 .contains(wgt::DownlevelFlags::MULTISAMPLED_SHADING),
         );
+        caps.set(
+            Caps::RAY_QUERY,
+            self.features.contains(wgt::Features::RAY_QUERY),
+        );
 
         let info = naga::valid::Validator::new(naga::valid::ValidationFlags::all(), caps)
             .validate(&module)
             .map_err(|inner| {
                 pipeline::CreateShaderModuleError::Validation(pipeline::ShaderError {
-                    source,
+                    source: source.clone(),
                     label: desc.label.as_ref().map(|l| l.to_string()),
                     inner: Box::new(inner),
                 })
             })?;
         let interface = validation::Interface::new(&module, &info, self.limits.clone());
+        let snapshot_source = if is_wgsl {
+            snapshot::ShaderSourceSnapshot::Wgsl(source)
+        } else {
+            snapshot::ShaderSourceSnapshot::Naga(module.clone())
+        };
         let hal_shader = hal::ShaderInput::Naga(hal::NagaShader { module, info });
 
         let hal_desc = hal::ShaderModuleDescriptor {
-            label: desc.label.borrow_option(),
+            label: desc.label.to_hal(self.instance_flags),
             runtime_checks: desc.shader_bound_checks.runtime_checks(),
+            zero_initialize_workgroup_memory: !self
+                .features
+                .contains(wgt::Features::UNINITIALIZED_WORKGROUP_MEMORY),
         };
         let raw = match unsafe { self.raw.create_shader_module(&hal_desc, hal_shader) } {
             Ok(raw) => raw,
@@ -1315,6 +1655,8 @@ impl<A: HalApi> Device<A> {
                 ref_count: self.life_guard.add_ref(),
             },
             interface: Some(interface),
+            desc: desc.to_static(),
+            source: snapshot_source,
             #[cfg(debug_assertions)]
             label: desc.label.borrow_or_default().to_string(),
         })
@@ -1329,8 +1671,11 @@ impl<A: HalApi> Device<A> {
     ) -> Result<pipeline::ShaderModule<A>, pipeline::CreateShaderModuleError> {
         self.require_features(wgt::Features::SPIRV_SHADER_PASSTHROUGH)?;
         let hal_desc = hal::ShaderModuleDescriptor {
-            label: desc.label.borrow_option(),
+            label: desc.label.to_hal(self.instance_flags),
             runtime_checks: desc.shader_bound_checks.runtime_checks(),
+            zero_initialize_workgroup_memory: !self
+                .features
+                .contains(wgt::Features::UNINITIALIZED_WORKGROUP_MEMORY),
         };
         let hal_shader = hal::ShaderInput::SpirV(source);
         let raw = match unsafe { self.raw.create_shader_module(&hal_desc, hal_shader) } {
@@ -1355,27 +1700,53 @@ impl<A: HalApi> Device<A> {
                 ref_count: self.life_guard.add_ref(),
             },
             interface: None,
+            desc: desc.to_static(),
+            source: snapshot::ShaderSourceSnapshot::SpirV(source.to_vec()),
             #[cfg(debug_assertions)]
             label: desc.label.borrow_or_default().to_string(),
         })
     }
 
-    pub(super) fn deduplicate_bind_group_layout(
+    /// # Safety
+    ///
+    /// `desc.data` is passed to the driver, which may not validate it beyond its header.
+    pub(super) unsafe fn create_pipeline_cache(
+        &self,
         self_id: id::DeviceId,
+        desc: &pipeline::PipelineCacheDescriptor,
+    ) -> Result<pipeline::PipelineCache<A>, pipeline::CreatePipelineCacheError> {
+        self.require_features(wgt::Features::PIPELINE_CACHE)?;
+
+        let hal_desc = hal::PipelineCacheDescriptor {
+            label: desc.label.to_hal(self.instance_flags),
+            data: desc.data.as_deref(),
+        };
+        let raw =
+            unsafe { self.raw.create_pipeline_cache(&hal_desc) }.map_err(DeviceError::from)?;
+
+        Ok(pipeline::PipelineCache {
+            raw,
+            device_id: Stored {
+                value: id::Valid(self_id),
+                ref_count: self.life_guard.add_ref(),
+            },
+            #[cfg(debug_assertions)]
+            label: desc.label.borrow_or_default().to_string(),
+        })
+    }
+
+    /// Look up the canonical bind group layout with the given entries, adding
+    /// a reference to it if there is one.
+    pub(super) fn deduplicate_bind_group_layout(
+        &self,
         entry_map: &binding_model::BindEntryMap,
         guard: &Storage<binding_model::BindGroupLayout<A>, id::BindGroupLayoutId>,
     ) -> Option<id::BindGroupLayoutId> {
-        guard
-            .iter(self_id.backend())
-            .find(|&(_, bgl)| {
-                bgl.device_id.value.0 == self_id
-                    && bgl.compatible_layout.is_none()
-                    && bgl.entries == *entry_map
-            })
-            .map(|(id, value)| {
-                value.multi_ref_count.inc();
-                id
-            })
+        let id = self.bgl_pool.lock().get(entry_map)?;
+        let bgl = &guard[id];
+        debug_assert!(bgl.compatible_layout.is_none() && bgl.entries == *entry_map);
+        bgl.multi_ref_count.inc();
+        Some(id.0)
     }
 
     fn get_introspection_bind_group_layouts<'a>(
@@ -1540,6 +1911,10 @@ impl<A: HalApi> Device<A> {
                         },
                     )
                 }
+                Bt::AccelerationStructure => {
+                    required_features |= wgt::Features::RAY_QUERY;
+                    (None, WritableStorage::No)
+                }
             };
 
             // Validate the count parameter
@@ -1595,7 +1970,7 @@ impl<A: HalApi> Device<A> {
         let mut hal_bindings = entry_map.values().cloned().collect::<Vec<_>>();
         hal_bindings.sort_by_key(|b| b.binding);
         let hal_desc = hal::BindGroupLayoutDescriptor {
-            label,
+            label: crate::hal_label(label, self.instance_flags),
             flags: bgl_flags,
             entries: &hal_bindings,
         };
@@ -1833,7 +2208,8 @@ impl<A: HalApi> Device<A> {
         let (buffer_guard, mut token) = hub.buffers.read(token);
         let (texture_guard, mut token) = hub.textures.read(&mut token); //skip token
         let (texture_view_guard, mut token) = hub.texture_views.read(&mut token);
-        let (sampler_guard, _) = hub.samplers.read(&mut token);
+        let (sampler_guard, mut token) = hub.samplers.read(&mut token);
+        let (acceleration_structure_guard, _) = hub.acceleration_structures.read(&mut token);
 
         let mut used_buffer_ranges = Vec::new();
         let mut used_texture_ranges = Vec::new();
@@ -1841,6 +2217,7 @@ impl<A: HalApi> Device<A> {
         let mut hal_buffers = Vec::new();
         let mut hal_samplers = Vec::new();
         let mut hal_textures = Vec::new();
+        let mut hal_acceleration_structures = Vec::new();
         for entry in desc.entries.iter() {
             let binding = entry.binding;
             // Find the corresponding declaration in the layout
@@ -2003,6 +2380,24 @@ impl<A: HalApi> Device<A> {
 
                     (res_index, num_bindings)
                 }
+                Br::AccelerationStructure(id) => match decl.ty {
+                    wgt::BindingType::AccelerationStructure => {
+                        let acceleration_structure = used
+                            .acceleration_structures
+                            .add_single(&*acceleration_structure_guard, id)
+                            .ok_or(Error::InvalidAccelerationStructure(id))?;
+                        let res_index = hal_acceleration_structures.len();
+                        hal_acceleration_structures.push(&acceleration_structure.raw);
+                        (res_index, 1)
+                    }
+                    _ => {
+                        return Err(Error::WrongBindingType {
+                            binding,
+                            actual: decl.ty,
+                            expected: "AccelerationStructure",
+                        })
+                    }
+                },
             };
 
             hal_entries.push(hal::BindGroupEntry {
@@ -2013,6 +2408,16 @@ impl<A: HalApi> Device<A> {
         }
 
         used.optimize();
+        let writes_unprotected = used.buffers.written().any(|id| {
+            !buffer_guard[id]
+                .usage
+                .contains(wgt::BufferUsages::PROTECTED)
+        }) || used.textures.written().any(|id| {
+            !texture_guard[id]
+                .desc
+                .usage
+                .contains(wgt::TextureUsages::PROTECTED)
+        });
 
         hal_entries.sort_by_key(|entry| entry.binding);
         for (a, b) in hal_entries.iter().zip(hal_entries.iter().skip(1)) {
@@ -2022,12 +2427,13 @@ impl<A: HalApi> Device<A> {
         }
 
         let hal_desc = hal::BindGroupDescriptor {
-            label: desc.label.borrow_option(),
+            label: desc.label.to_hal(self.instance_flags),
             layout: &layout.raw,
             entries: &hal_entries,
             buffers: &hal_buffers,
             samplers: &hal_samplers,
             textures: &hal_textures,
+            acceleration_structures: &hal_acceleration_structures,
         };
         let raw = unsafe {
             self.raw
@@ -2056,6 +2462,7 @@ impl<A: HalApi> Device<A> {
                 .keys()
                 .flat_map(|binding| late_buffer_binding_sizes.get(binding).cloned())
                 .collect(),
+            writes_unprotected,
         })
     }
 
@@ -2137,8 +2544,8 @@ impl<A: HalApi> Device<A> {
                     (Tst::Float { filterable: false }, Tst::Depth) => {}
                     // if we expect filterable, also accept Float that is defined as
                     // unfilterable if filterable feature is explicitly enabled (only hit
-                    // if wgt::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES is
-                    // enabled)
+                    // if wgt::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES or
+                    // wgt::Features::FLOAT32_FILTERABLE is enabled)
                     (Tst::Float { filterable: true }, Tst::Float { .. }) if view.format_features.flags.contains(wgt::TextureFormatFeatureFlags::FILTERABLE) => {}
                     _ => {
                         return Err(Error::InvalidTextureSampleType {
@@ -2188,6 +2595,13 @@ impl<A: HalApi> Device<A> {
                     });
                 }
 
+                if !view.desc.swizzle.is_identity() {
+                    return Err(Error::InvalidStorageTextureSwizzle {
+                        binding,
+                        swizzle: view.desc.swizzle,
+                    });
+                }
+
                 let internal_use = match access {
                     wgt::StorageTextureAccess::WriteOnly => hal::TextureUses::STORAGE_READ_WRITE,
                     wgt::StorageTextureAccess::ReadOnly => {
@@ -2296,7 +2710,7 @@ impl<A: HalApi> Device<A> {
             .map(|&id| &try_get_bind_group_layout(bgl_guard, id).unwrap().raw)
             .collect::<Vec<_>>();
         let hal_desc = hal::PipelineLayoutDescriptor {
-            label: desc.label.borrow_option(),
+            label: desc.label.to_hal(self.instance_flags),
             flags: hal::PipelineLayoutFlags::BASE_VERTEX_INSTANCE,
             bind_group_layouts: &bgl_vec,
             push_constant_ranges: desc.push_constant_ranges.as_ref(),
@@ -2331,7 +2745,7 @@ impl<A: HalApi> Device<A> {
 
     //TODO: refactor this. It's the only method of `Device` that registers new objects
     // (the pipeline layout).
-    fn derive_pipeline_layout(
+    fn derive_pipeline_layout<G: GlobalIdentityHandlerFactory>(
         &self,
         self_id: id::DeviceId,
         implicit_context: Option<ImplicitPipelineContext>,
@@ -2357,12 +2771,22 @@ impl<A: HalApi> Device<A> {
         }
 
         for (bgl_id, map) in ids.group_ids.iter_mut().zip(derived_group_layouts) {
-            match Device::deduplicate_bind_group_layout(self_id, &map, bgl_guard) {
-                Some(dedup_id) => {
+            let compatible_layout = self.deduplicate_bind_group_layout(&map, bgl_guard);
+            match compatible_layout {
+                // Like in `device_create_bind_group_layout`, the existing id can
+                // only be handed out if the ids are generated in wgpu. Otherwise
+                // the caller expects the layout at the id it provided.
+                Some(dedup_id) if G::ids_are_generated_in_wgpu() => {
                     *bgl_id = dedup_id;
                 }
-                None => {
-                    let bgl = self.create_bind_group_layout(self_id, None, map)?;
+                _ => {
+                    let mut bgl = self.create_bind_group_layout(self_id, None, map)?;
+                    bgl.compatible_layout = compatible_layout.map(id::Valid);
+                    if compatible_layout.is_none() {
+                        self.bgl_pool
+                            .lock()
+                            .insert(&bgl.entries, id::Valid(*bgl_id));
+                    }
                     bgl_guard.force_replace(*bgl_id, bgl);
                 }
             };
@@ -2406,7 +2830,8 @@ impl<A: HalApi> Device<A> {
         let mut shader_binding_sizes = FastHashMap::default();
 
         let io = validation::StageIo::default();
-        let (shader_module_guard, _) = hub.shader_modules.read(&mut token);
+        let (shader_module_guard, mut token) = hub.shader_modules.read(&mut token);
+        let (pipeline_cache_guard, _) = hub.pipeline_caches.read(&mut token);
 
         let shader_module = shader_module_guard
             .get(desc.stage.module)
@@ -2438,12 +2863,13 @@ impl<A: HalApi> Device<A> {
                     io,
                     None,
                 )?;
+                interface.check_pipeline_constants(&desc.stage.constants)?;
             }
         }
 
         let pipeline_layout_id = match desc.layout {
             Some(id) => id,
-            None => self.derive_pipeline_layout(
+            None => self.derive_pipeline_layout::<G>(
                 self_id,
                 implicit_context,
                 derived_group_layouts,
@@ -2458,13 +2884,21 @@ impl<A: HalApi> Device<A> {
         let late_sized_buffer_groups =
             Device::make_late_sized_buffer_groups(&shader_binding_sizes, layout, &*bgl_guard);
 
+        let cache = desc
+            .cache
+            .map(|id| pipeline_cache_guard.get(id).map(|cache| &cache.raw))
+            .transpose()
+            .map_err(|_| pipeline::CreateComputePipelineError::InvalidCache)?;
+
         let pipeline_desc = hal::ComputePipelineDescriptor {
-            label: desc.label.borrow_option(),
+            label: desc.label.to_hal(self.instance_flags),
             layout: &layout.raw,
             stage: hal::ProgrammableStage {
                 entry_point: desc.stage.entry_point.as_ref(),
                 module: &shader_module.raw,
+                constants: desc.stage.constants.as_ref(),
             },
+            cache,
         };
 
         let raw =
@@ -2493,6 +2927,10 @@ impl<A: HalApi> Device<A> {
                 ref_count: self.life_guard.add_ref(),
             },
             late_sized_buffer_groups,
+            desc: pipeline::ComputePipelineDescriptor {
+                layout: Some(pipeline_layout_id),
+                ..desc.to_static()
+            },
             life_guard: LifeGuard::new(desc.label.borrow_or_default()),
         };
         Ok(pipeline)
@@ -2666,10 +3104,20 @@ impl<A: HalApi> Device<A> {
 
         for (i, cs) in color_targets.iter().enumerate() {
             if let Some(cs) = cs.as_ref() {
+                let uses_second_blend_source = cs.blend.map_or(false, |blend| {
+                    blend.color.uses_second_blend_source() || blend.alpha.uses_second_blend_source()
+                });
+                if uses_second_blend_source {
+                    self.require_features(wgt::Features::DUAL_SOURCE_BLENDING)?;
+                }
+
                 let error = loop {
                     if cs.write_mask.contains_invalid_bits() {
                         break Some(pipeline::ColorStateError::InvalidWriteMask(cs.write_mask));
                     }
+                    if uses_second_blend_source && i != 0 {
+                        break Some(pipeline::ColorStateError::DualSourceBlendingNotFirstTarget);
+                    }
 
                     let format_features = self.describe_format_features(adapter, cs.format)?;
                     if !format_features
@@ -2722,7 +3170,9 @@ impl<A: HalApi> Device<A> {
                 }
 
                 let aspect = hal::FormatAspects::from(ds.format);
-                if ds.is_depth_enabled() && !aspect.contains(hal::FormatAspects::DEPTH) {
+                if (ds.is_depth_enabled() || ds.depth_bounds.is_some())
+                    && !aspect.contains(hal::FormatAspects::DEPTH)
+                {
                     break Some(pipeline::DepthStencilStateError::FormatNotDepth(ds.format));
                 }
                 if ds.stencil.is_enabled() && !aspect.contains(hal::FormatAspects::STENCIL) {
@@ -2739,6 +3189,15 @@ impl<A: HalApi> Device<A> {
                         ds.format,
                     ));
                 }
+                if let Some(bounds) = ds.depth_bounds {
+                    if !(0.0..=1.0).contains(&bounds.min)
+                        || !(bounds.min..=1.0).contains(&bounds.max)
+                    {
+                        break Some(pipeline::DepthStencilStateError::InvalidDepthBounds(
+                            bounds.min, bounds.max,
+                        ));
+                    }
+                }
 
                 break None;
             };
@@ -2749,6 +3208,9 @@ impl<A: HalApi> Device<A> {
             if ds.bias.clamp != 0.0 {
                 self.require_downlevel_flags(wgt::DownlevelFlags::DEPTH_BIAS_CLAMP)?;
             }
+            if ds.depth_bounds.is_some() {
+                self.require_features(wgt::Features::DEPTH_BOUNDS_TEST)?;
+            }
         }
 
         if desc.layout.is_none() {
@@ -2765,7 +3227,8 @@ impl<A: HalApi> Device<A> {
             sc
         };
 
-        let (shader_module_guard, _) = hub.shader_modules.read(&mut token);
+        let (shader_module_guard, mut token) = hub.shader_modules.read(&mut token);
+        let (pipeline_cache_guard, _) = hub.pipeline_caches.read(&mut token);
 
         let vertex_stage = {
             let stage = &desc.vertex.stage;
@@ -2806,12 +3269,19 @@ impl<A: HalApi> Device<A> {
                         stage: flag,
                         error,
                     })?;
+                interface
+                    .check_pipeline_constants(&stage.constants)
+                    .map_err(|error| pipeline::CreateRenderPipelineError::Stage {
+                        stage: flag,
+                        error,
+                    })?;
                 validated_stages |= flag;
             }
 
             hal::ProgrammableStage {
                 module: &shader_module.raw,
                 entry_point: stage.entry_point.as_ref(),
+                constants: stage.constants.as_ref(),
             }
         };
 
@@ -2853,6 +3323,12 @@ impl<A: HalApi> Device<A> {
                                 stage: flag,
                                 error,
                             })?;
+                        interface
+                            .check_pipeline_constants(&fragment.stage.constants)
+                            .map_err(|error| pipeline::CreateRenderPipelineError::Stage {
+                                stage: flag,
+                                error,
+                            })?;
                         validated_stages |= flag;
                     }
                 }
@@ -2860,6 +3336,7 @@ impl<A: HalApi> Device<A> {
                 Some(hal::ProgrammableStage {
                     module: &shader_module.raw,
                     entry_point: fragment.stage.entry_point.as_ref(),
+                    constants: fragment.stage.constants.as_ref(),
                 })
             }
             None => None,
@@ -2903,7 +3380,7 @@ impl<A: HalApi> Device<A> {
 
         let pipeline_layout_id = match desc.layout {
             Some(id) => id,
-            None => self.derive_pipeline_layout(
+            None => self.derive_pipeline_layout::<G>(
                 self_id,
                 implicit_context,
                 derived_group_layouts,
@@ -2939,8 +3416,14 @@ impl<A: HalApi> Device<A> {
         let late_sized_buffer_groups =
             Device::make_late_sized_buffer_groups(&shader_binding_sizes, layout, &*bgl_guard);
 
+        let cache = desc
+            .cache
+            .map(|id| pipeline_cache_guard.get(id).map(|cache| &cache.raw))
+            .transpose()
+            .map_err(|_| pipeline::CreateRenderPipelineError::InvalidCache)?;
+
         let pipeline_desc = hal::RenderPipelineDescriptor {
-            label: desc.label.borrow_option(),
+            label: desc.label.to_hal(self.instance_flags),
             layout: &layout.raw,
             vertex_buffers: &vertex_buffers,
             vertex_stage,
@@ -2950,6 +3433,7 @@ impl<A: HalApi> Device<A> {
             fragment_stage,
             color_targets,
             multiview: desc.multiview,
+            cache,
         };
         let raw =
             unsafe { self.raw.create_render_pipeline(&pipeline_desc) }.map_err(
@@ -3015,8 +3499,13 @@ impl<A: HalApi> Device<A> {
             pass_context,
             flags,
             strip_index_format: desc.primitive.strip_index_format,
+            depth_bounds: depth_stencil_state.and_then(|ds| ds.depth_bounds),
             vertex_steps,
             late_sized_buffer_groups,
+            desc: pipeline::RenderPipelineDescriptor {
+                layout: Some(pipeline_layout_id),
+                ..desc.to_static()
+            },
             life_guard: LifeGuard::new(desc.label.borrow_or_default()),
         };
         Ok(pipeline)
@@ -3048,18 +3537,11 @@ impl<A: HalApi> Device<A> {
         submission_index: SubmissionIndex,
         token: &mut Token<Self>,
     ) -> Result<(), WaitIdleError> {
-        let last_done_index = unsafe {
-            self.raw
-                .get_fence_value(&self.fence)
-                .map_err(DeviceError::from)?
-        };
+        let last_done_index = self.last_done_index().map_err(DeviceError::from)?;
         if last_done_index < submission_index {
             log::info!("Waiting for submission {:?}", submission_index);
-            unsafe {
-                self.raw
-                    .wait(&self.fence, submission_index, !0)
-                    .map_err(DeviceError::from)?
-            };
+            self.wait_for_index(submission_index, !0)
+                .map_err(DeviceError::from)?;
             let closures = self
                 .lock_life(token)
                 .triage_submissions(submission_index, &self.command_allocator);
@@ -3071,6 +3553,52 @@ impl<A: HalApi> Device<A> {
         Ok(())
     }
 
+    /// Creates a command buffer recording for the main queue, or for the
+    /// additional queue `additional_queue` if it is given.
+    pub(super) fn create_command_buffer(
+        &self,
+        self_id: id::DeviceId,
+        desc: &wgt::CommandEncoderDescriptor<Label>,
+        additional_queue: Option<usize>,
+    ) -> Result<command::CommandBuffer<A>, command::CreateCommandEncoderError> {
+        if !self.is_valid() {
+            return Err(DeviceError::Lost.into());
+        }
+        if desc.protected {
+            self.require_features(wgt::Features::PROTECTED_CONTENT)?;
+            if additional_queue.is_some() {
+                return Err(command::CreateCommandEncoderError::ProtectedAdditionalQueue);
+            }
+        }
+        let dev_stored = Stored {
+            value: id::Valid(self_id),
+            ref_count: self.life_guard.add_ref(),
+        };
+        let queue = match additional_queue {
+            Some(index) => &self.additional_queues[index].raw,
+            None => &self.queue,
+        };
+        let encoder = self
+            .command_allocator
+            .lock()
+            .acquire_encoder(&self.raw, queue, desc.protected, additional_queue)
+            .map_err(|_| DeviceError::OutOfMemory)?;
+        Ok(command::CommandBuffer::new(
+            encoder,
+            dev_stored,
+            self.limits.clone(),
+            self.downlevel.clone(),
+            self.features,
+            #[cfg(feature = "trace")]
+            self.trace.is_some(),
+            &desc.label,
+            desc.reusable,
+            desc.protected,
+            self.instance_flags,
+            additional_queue.map(|index| (index, self.additional_queues[index].kind)),
+        ))
+    }
+
     pub(super) fn create_query_set(
         &self,
         self_id: id::DeviceId,
@@ -3129,6 +3657,43 @@ impl<A: HalApi> Device<A> {
         unsafe {
             self.raw.destroy_command_encoder(baked.encoder);
         }
+        for resource in baked.temp_resources {
+            match resource {
+                queue::TempResource::Buffer(raw) => unsafe { self.raw.destroy_buffer(raw) },
+                queue::TempResource::Texture(raw, views) => unsafe {
+                    for view in views {
+                        self.raw.destroy_texture_view(view);
+                    }
+                    self.raw.destroy_texture(raw);
+                },
+                queue::TempResource::BindGroup(raw) => unsafe { self.raw.destroy_bind_group(raw) },
+            }
+        }
+    }
+
+    /// Destroys a reusable command buffer once its last submission is done.
+    pub(super) fn schedule_command_buffer_destruction<'this, 'token: 'this>(
+        &'this self,
+        cmd_buf: command::CommandBuffer<A>,
+        last_submit_index: SubmissionIndex,
+        token: &mut Token<'token, Self>,
+    ) {
+        let baked = cmd_buf.into_baked();
+        let mut life_tracker = self.lock_life(token);
+        for resource in baked.temp_resources {
+            life_tracker.schedule_resource_destruction(resource, last_submit_index);
+        }
+        let encoder = queue::EncoderInFlight {
+            raw: baked.encoder,
+            cmd_buffers: baked.list,
+            protected: baked.protected,
+            additional_queue: baked.additional_queue,
+        };
+        if let Some(encoder) = life_tracker.schedule_encoder_release(encoder, last_submit_index) {
+            unsafe {
+                self.raw.destroy_command_encoder(encoder.land());
+            }
+        }
     }
 
     /// Wait for idle and remove resources that we can, before we die.
@@ -3136,7 +3701,7 @@ impl<A: HalApi> Device<A> {
         self.pending_writes.deactivate();
         let mut life_tracker = self.life_tracker.lock();
         let current_index = self.active_submission_index;
-        if let Err(error) = unsafe { self.raw.wait(&self.fence, current_index, CLEANUP_WAIT_MS) } {
+        if let Err(error) = self.wait_for_index(current_index, CLEANUP_WAIT_MS) {
             log::error!("failed to wait for the device: {:?}", error);
         }
         let _ = life_tracker.triage_submissions(current_index, &self.command_allocator);
@@ -3150,9 +3715,16 @@ impl<A: HalApi> Device<A> {
     pub(crate) fn dispose(self) {
         self.pending_writes.dispose(&self.raw);
         self.command_allocator.into_inner().dispose(&self.raw);
+        if let Some(indirect_validation) = self.indirect_validation {
+            indirect_validation.dispose(&self.raw);
+        }
         unsafe {
             self.raw.destroy_buffer(self.zero_buffer);
             self.raw.destroy_fence(self.fence);
+            for queue in self.additional_queues {
+                self.raw.destroy_fence(queue.fence);
+                self.raw.destroy_queue(queue.raw);
+            }
             self.raw.exit(self.queue);
         }
     }
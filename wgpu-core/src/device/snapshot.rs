@@ -0,0 +1,711 @@
+//! Snapshots of the contents of a device's buffers and textures.
+//!
+//! [`Global::device_snapshot`] reads back every live buffer and texture of a device into a
+//! [`DeviceSnapshot`], and [`Global::device_restore`] writes a snapshot back, e.g. to implement
+//! save states. Snapshots store tightly packed data keyed by resource id. Ids carry the backend
+//! they were created with, but [`Global::device_restore`] only uses their index and epoch, so a
+//! snapshot can be restored on another backend, e.g. to resources recreated by replaying an API
+//! trace. With the `trace` and `replay` features they can be serialized like the actions of an
+//! API trace.
+//!
+//! Buffers and textures are captured with their contents and descriptors. Pipelines are
+//! immutable, so only the descriptors they and their shader modules and layouts were created
+//! with are captured, see [`PipelineSnapshot`]. Everything else (bind groups, samplers, ...) is
+//! best recorded with an API trace and recreated by replaying it.
+
+use crate::{
+    binding_model::{BindGroupLayoutDescriptor, PipelineLayoutDescriptor},
+    command::{
+        CommandEncoderError, CopyError, CreateCommandEncoderError, ImageCopyBuffer,
+        ImageCopyTexture,
+    },
+    conv,
+    device::{queue::QueueSubmitError, queue::QueueWriteError, DeviceError, InvalidDevice},
+    global::Global,
+    hal_api::HalApi,
+    hub::Token,
+    id::{
+        self, BindGroupLayoutId, BufferId, ComputePipelineId, DeviceId, PipelineLayoutId,
+        RenderPipelineId, ShaderModuleId, TextureId,
+    },
+    identity::IdentityManagerFactory,
+    pipeline::{ComputePipelineDescriptor, RenderPipelineDescriptor, ShaderModuleDescriptor},
+    resource::{self, BufferAccessError, CreateBufferError, Resource as _},
+};
+
+use std::borrow::Cow;
+use thiserror::Error;
+use wgt::{BufferAddress, BufferUsages, TextureAspect, TextureUsages};
+
+/// The contents of the buffers and textures of a device, see [`Global::device_snapshot`].
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct DeviceSnapshot {
+    pub buffers: Vec<BufferSnapshot>,
+    pub textures: Vec<TextureSnapshot>,
+    #[cfg_attr(any(feature = "replay", feature = "trace"), serde(default))]
+    pub pipelines: PipelineSnapshot,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct BufferSnapshot {
+    pub id: BufferId,
+    pub size: BufferAddress,
+    pub usage: BufferUsages,
+    /// The first `size` bytes of the buffer, rounded down to [`wgt::COPY_BUFFER_ALIGNMENT`].
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct TextureSnapshot {
+    pub id: TextureId,
+    pub desc: wgt::TextureDescriptor<(), Vec<wgt::TextureFormat>>,
+    /// One entry per mip level and aspect.
+    pub subresources: Vec<TextureSubresourceSnapshot>,
+    /// Aspects that aren't captured because they can't be copied both from and to a buffer,
+    /// like the depth of `Depth24Plus` and `Depth32Float`.
+    #[cfg_attr(any(feature = "replay", feature = "trace"), serde(default))]
+    pub skipped_aspects: Vec<TextureAspect>,
+}
+
+/// All the array layers of one mip level and aspect of a texture.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct TextureSubresourceSnapshot {
+    pub mip_level: u32,
+    pub aspect: TextureAspect,
+    /// The physical size of the mip level.
+    pub size: wgt::Extent3d,
+    /// Rows are tightly packed, without the padding of buffer copies.
+    pub layout: wgt::ImageDataLayout,
+    pub data: Vec<u8>,
+}
+
+/// The descriptors of the pipelines of a device, and of the shader modules and layouts they
+/// were created with.
+///
+/// [`Global::device_restore`] doesn't use them: the objects are recreated from these
+/// descriptors, in the order of the fields. Ids in the descriptors refer to other entries of
+/// the snapshot and have to be replaced with the ids of the recreated objects. Pipelines that
+/// were created with an implicit layout have the derived layout in their descriptor.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct PipelineSnapshot {
+    pub shader_modules: Vec<ShaderModuleSnapshot>,
+    pub bind_group_layouts: Vec<(BindGroupLayoutId, BindGroupLayoutDescriptor<'static>)>,
+    pub pipeline_layouts: Vec<(PipelineLayoutId, PipelineLayoutDescriptor<'static>)>,
+    pub compute_pipelines: Vec<(ComputePipelineId, ComputePipelineDescriptor<'static>)>,
+    pub render_pipelines: Vec<(RenderPipelineId, RenderPipelineDescriptor<'static>)>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct ShaderModuleSnapshot {
+    pub id: ShaderModuleId,
+    pub desc: ShaderModuleDescriptor<'static>,
+    pub source: ShaderSourceSnapshot,
+}
+
+/// The source a shader module was created from.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub enum ShaderSourceSnapshot {
+    Wgsl(String),
+    /// The module given directly, or parsed from another shading language.
+    Naga(Cow<'static, naga::Module>),
+    /// The code passed through with [`wgt::Features::SPIRV_SHADER_PASSTHROUGH`].
+    SpirV(Vec<u32>),
+}
+
+#[derive(Clone, Debug, Error)]
+pub enum DeviceSnapshotError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error(transparent)]
+    InvalidDevice(#[from] InvalidDevice),
+    #[error(transparent)]
+    CreateBuffer(#[from] CreateBufferError),
+    #[error(transparent)]
+    Copy(#[from] CopyError),
+    #[error(transparent)]
+    CreateEncoder(#[from] CreateCommandEncoderError),
+    #[error(transparent)]
+    Encoder(#[from] CommandEncoderError),
+    #[error(transparent)]
+    Submit(#[from] QueueSubmitError),
+    #[error(transparent)]
+    Map(#[from] BufferAccessError),
+    #[error(transparent)]
+    Write(#[from] QueueWriteError),
+    #[error("Buffer {0:?} is invalid, destroyed, mapped or belongs to another device")]
+    InvalidBuffer(BufferId),
+    #[error("Texture {0:?} is invalid, destroyed or belongs to another device")]
+    InvalidTexture(TextureId),
+    #[error("Buffer {0:?} doesn't have the `COPY_DST` usage or is smaller than its snapshot")]
+    BufferMismatch(BufferId),
+    #[error("Texture {0:?} doesn't have the `COPY_DST` usage or doesn't match its snapshot")]
+    TextureMismatch(TextureId),
+    #[error("Snapshot of texture {texture:?} has invalid contents for mip level {mip_level} and aspect {aspect:?}")]
+    InvalidSubresource {
+        texture: TextureId,
+        mip_level: u32,
+        aspect: TextureAspect,
+    },
+}
+
+/// A copy from a live resource into a staging buffer, which is read back into `data`.
+struct Readback<'a> {
+    staging: BufferId,
+    bytes_per_row: u32,
+    padded_bytes_per_row: u32,
+    rows: u32,
+    data: &'a mut Vec<u8>,
+}
+
+impl<G: crate::identity::GlobalIdentityHandlerFactory> Global<G> {
+    /// Returns the buffers and textures of `device_id` that [`Global::device_snapshot`] can
+    /// capture, with empty contents, and the descriptors of its pipelines.
+    ///
+    /// These are the buffers and textures that the user still holds, that aren't destroyed and
+    /// that were created with `COPY_SRC`. Mapped buffers, multisampled textures and surface
+    /// textures are skipped. All pipelines the user still holds are captured, with the shader
+    /// modules and layouts of the device.
+    pub fn device_snapshot_resources<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+    ) -> Result<DeviceSnapshot, InvalidDevice> {
+        let (buffers, textures) = self.snapshot_buffers_and_textures::<A>(device_id)?;
+        let pipelines = self.snapshot_pipelines::<A>(device_id)?;
+        Ok(DeviceSnapshot {
+            buffers,
+            textures,
+            pipelines,
+        })
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn snapshot_buffers_and_textures<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+    ) -> Result<(Vec<BufferSnapshot>, Vec<TextureSnapshot>), InvalidDevice> {
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        device_guard.get(device_id).map_err(|_| InvalidDevice)?;
+        let (buffer_guard, mut token) = hub.buffers.read(&mut token);
+        let (texture_guard, _) = hub.textures.read(&mut token);
+
+        let buffers = buffer_guard
+            .iter(device_id.backend())
+            .filter(|&(_, buffer)| {
+                buffer.device_id.value.0 == device_id
+                    && buffer.life_guard.ref_count.is_some()
+                    && buffer.raw.is_some()
+                    && buffer.usage.contains(BufferUsages::COPY_SRC)
+                    && matches!(buffer.map_state, resource::BufferMapState::Idle)
+            })
+            .map(|(id, buffer)| BufferSnapshot {
+                id,
+                size: buffer.size,
+                usage: buffer.usage,
+                data: Vec::new(),
+            })
+            .collect();
+        let textures = texture_guard
+            .iter(device_id.backend())
+            .filter(|&(_, texture)| {
+                texture.device_id.value.0 == device_id
+                    && texture.life_guard.ref_count.is_some()
+                    && matches!(
+                        texture.inner,
+                        resource::TextureInner::Native { raw: Some(_) }
+                    )
+                    && texture.desc.usage.contains(TextureUsages::COPY_SRC)
+                    && texture.desc.sample_count == 1
+            })
+            .map(|(id, texture)| TextureSnapshot {
+                id,
+                desc: texture.desc.clone(),
+                subresources: Vec::new(),
+                skipped_aspects: Vec::new(),
+            })
+            .collect();
+
+        Ok((buffers, textures))
+    }
+
+    fn snapshot_pipelines<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+    ) -> Result<PipelineSnapshot, InvalidDevice> {
+        let hub = A::hub(self);
+        let backend = device_id.backend();
+        let label = |label: &str| (!label.is_empty()).then(|| Cow::Owned(label.to_string()));
+        let mut snapshot = PipelineSnapshot::default();
+
+        // Pipelines can't be locked after shader modules, so they are read separately.
+        {
+            let mut token = Token::root();
+            let (device_guard, mut token) = hub.devices.read(&mut token);
+            device_guard.get(device_id).map_err(|_| InvalidDevice)?;
+            let (pipeline_layout_guard, mut token) = hub.pipeline_layouts.read(&mut token);
+            let (bgl_guard, mut token) = hub.bind_group_layouts.read(&mut token);
+            let (shader_module_guard, _) = hub.shader_modules.read(&mut token);
+
+            for (id, module) in shader_module_guard.iter(backend) {
+                if module.device_id.value.0 == device_id {
+                    snapshot.shader_modules.push(ShaderModuleSnapshot {
+                        id,
+                        desc: module.desc.clone(),
+                        source: module.source.clone(),
+                    });
+                }
+            }
+            for (id, bgl) in bgl_guard.iter(backend) {
+                if bgl.device_id.value.0 == device_id {
+                    let mut entries: Vec<_> = bgl.entries.values().cloned().collect();
+                    entries.sort_by_key(|entry| entry.binding);
+                    let desc = BindGroupLayoutDescriptor {
+                        label: label(bgl.label()),
+                        entries: Cow::Owned(entries),
+                    };
+                    snapshot.bind_group_layouts.push((id, desc));
+                }
+            }
+            for (id, layout) in pipeline_layout_guard.iter(backend) {
+                if layout.device_id.value.0 == device_id {
+                    let desc = PipelineLayoutDescriptor {
+                        label: label(layout.label()),
+                        bind_group_layouts: layout
+                            .bind_group_layout_ids
+                            .iter()
+                            .map(|id| id.0)
+                            .collect(),
+                        push_constant_ranges: Cow::Owned(layout.push_constant_ranges.to_vec()),
+                    };
+                    snapshot.pipeline_layouts.push((id, desc));
+                }
+            }
+        }
+        {
+            let mut token = Token::root();
+            let (_device_guard, mut token) = hub.devices.read(&mut token);
+            let (compute_pipeline_guard, mut token) = hub.compute_pipelines.read(&mut token);
+            let (render_pipeline_guard, _) = hub.render_pipelines.read(&mut token);
+
+            for (id, pipeline) in compute_pipeline_guard.iter(backend) {
+                if pipeline.device_id.value.0 == device_id
+                    && pipeline.life_guard.ref_count.is_some()
+                {
+                    snapshot.compute_pipelines.push((id, pipeline.desc.clone()));
+                }
+            }
+            for (id, pipeline) in render_pipeline_guard.iter(backend) {
+                if pipeline.device_id.value.0 == device_id
+                    && pipeline.life_guard.ref_count.is_some()
+                {
+                    snapshot.render_pipelines.push((id, pipeline.desc.clone()));
+                }
+            }
+        }
+        Ok(snapshot)
+    }
+}
+
+impl Global<IdentityManagerFactory> {
+    /// Reads back the contents of the buffers and textures of `device_id`.
+    ///
+    /// See [`Global::device_snapshot_resources`] for the resources that are captured. Texture
+    /// aspects that couldn't be restored with a copy from a buffer, like the depth of
+    /// `Depth32Float`, are left out and listed in [`TextureSnapshot::skipped_aspects`].
+    ///
+    /// This submits the copies and waits for them to complete.
+    pub fn device_snapshot<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+    ) -> Result<DeviceSnapshot, DeviceSnapshotError> {
+        profiling::scope!("Device::snapshot");
+
+        let mut snapshot = self.device_snapshot_resources::<A>(device_id)?;
+        let mut readbacks = Vec::new();
+        let result = self.snapshot_copy::<A>(device_id, &mut snapshot, &mut readbacks);
+        let result = result.and_then(|()| {
+            for readback in readbacks.iter_mut() {
+                self.snapshot_read::<A>(readback)?;
+            }
+            Ok(())
+        });
+        for readback in readbacks {
+            self.buffer_drop::<A>(readback.staging, false);
+        }
+        result.map(|()| snapshot)
+    }
+
+    /// Records and submits the copies of all resources of `snapshot` to staging buffers, then
+    /// maps them.
+    fn snapshot_copy<'a, A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        snapshot: &'a mut DeviceSnapshot,
+        readbacks: &mut Vec<Readback<'a>>,
+    ) -> Result<(), DeviceSnapshotError> {
+        let (encoder, error) = self.device_create_command_encoder::<A>(
+            device_id,
+            &wgt::CommandEncoderDescriptor {
+                label: Some("(wgpu internal) snapshot".into()),
+                reusable: false,
+                protected: false,
+            },
+            (),
+        );
+        if let Some(error) = error {
+            self.command_encoder_drop::<A>(encoder);
+            return Err(error.into());
+        }
+
+        let result = self.snapshot_record::<A>(device_id, encoder, snapshot, readbacks);
+        if let Err(error) = result {
+            self.command_encoder_drop::<A>(encoder);
+            return Err(error);
+        }
+        let (command_buffer, error) =
+            self.command_encoder_finish::<A>(encoder, &wgt::CommandBufferDescriptor::default());
+        if let Some(error) = error {
+            self.command_encoder_drop::<A>(encoder);
+            return Err(error.into());
+        }
+        self.queue_submit::<A>(device_id, &[command_buffer], None)?;
+
+        for readback in readbacks.iter() {
+            let size = readback.padded_bytes_per_row as BufferAddress * readback.rows as u64;
+            let callback = resource::BufferMapCallback::from_rust(Box::new(|_| {}));
+            self.buffer_map_async::<A>(
+                readback.staging,
+                0..size,
+                resource::BufferMapOperation {
+                    host: super::HostMap::Read,
+                    callback,
+                },
+            )?;
+        }
+        self.device_poll::<A>(device_id, wgt::Maintain::Wait)
+            .map_err(|error| match error {
+                super::WaitIdleError::Device(error) => error,
+                super::WaitIdleError::WrongSubmissionIndex(..) => unreachable!(),
+                super::WaitIdleError::StuckGpu => DeviceError::Lost,
+            })?;
+        Ok(())
+    }
+
+    fn snapshot_record<'a, A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        encoder: id::CommandEncoderId,
+        snapshot: &'a mut DeviceSnapshot,
+        readbacks: &mut Vec<Readback<'a>>,
+    ) -> Result<(), DeviceSnapshotError> {
+        let DeviceSnapshot {
+            ref mut buffers,
+            ref mut textures,
+            pipelines: _,
+        } = *snapshot;
+        for buffer in buffers.iter_mut() {
+            let size = buffer.size - buffer.size % wgt::COPY_BUFFER_ALIGNMENT;
+            if size == 0 {
+                continue;
+            }
+            let staging = self.snapshot_staging_buffer::<A>(device_id, size)?;
+            readbacks.push(Readback {
+                staging,
+                bytes_per_row: size as u32,
+                padded_bytes_per_row: size as u32,
+                rows: 1,
+                data: &mut buffer.data,
+            });
+            self.command_encoder_copy_buffer_to_buffer::<A>(
+                encoder, buffer.id, 0, staging, 0, size,
+            )?;
+        }
+
+        for texture in textures.iter_mut() {
+            let format = texture.desc.format;
+            let aspects: &[TextureAspect] = if format.is_combined_depth_stencil_format() {
+                &[TextureAspect::DepthOnly, TextureAspect::StencilOnly]
+            } else {
+                &[TextureAspect::All]
+            };
+            let (aspects, skipped_aspects): (Vec<_>, Vec<_>) = aspects
+                .iter()
+                .partition(|&&aspect| is_restorable_aspect(format, aspect));
+            texture.skipped_aspects = skipped_aspects;
+            for mip_level in 0..texture.desc.mip_level_count {
+                for &aspect in aspects.iter() {
+                    let (size, layout) = subresource_layout(&texture.desc, mip_level, aspect);
+                    texture.subresources.push(TextureSubresourceSnapshot {
+                        mip_level,
+                        aspect,
+                        size,
+                        layout,
+                        data: Vec::new(),
+                    });
+                }
+            }
+
+            for subresource in texture.subresources.iter_mut() {
+                let bytes_per_row = subresource.layout.bytes_per_row.unwrap();
+                let rows_per_image = subresource.layout.rows_per_image.unwrap();
+                let padded_bytes_per_row =
+                    wgt::math::align_to(bytes_per_row, wgt::COPY_BYTES_PER_ROW_ALIGNMENT);
+                let rows = rows_per_image * subresource.size.depth_or_array_layers;
+                if rows == 0 {
+                    continue;
+                }
+                let staging = self.snapshot_staging_buffer::<A>(
+                    device_id,
+                    padded_bytes_per_row as BufferAddress * rows as BufferAddress,
+                )?;
+                readbacks.push(Readback {
+                    staging,
+                    bytes_per_row,
+                    padded_bytes_per_row,
+                    rows,
+                    data: &mut subresource.data,
+                });
+                self.command_encoder_copy_texture_to_buffer::<A>(
+                    encoder,
+                    &ImageCopyTexture {
+                        texture: texture.id,
+                        mip_level: subresource.mip_level,
+                        origin: wgt::Origin3d::ZERO,
+                        aspect: subresource.aspect,
+                    },
+                    &ImageCopyBuffer {
+                        buffer: staging,
+                        layout: wgt::ImageDataLayout {
+                            offset: 0,
+                            bytes_per_row: Some(padded_bytes_per_row),
+                            rows_per_image: Some(rows_per_image),
+                        },
+                    },
+                    &subresource.size,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn snapshot_staging_buffer<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        size: BufferAddress,
+    ) -> Result<BufferId, DeviceSnapshotError> {
+        let (staging, error) = self.device_create_buffer::<A>(
+            device_id,
+            &resource::BufferDescriptor {
+                label: Some("(wgpu internal) snapshot staging".into()),
+                size,
+                usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+            (),
+        );
+        match error {
+            Some(error) => {
+                self.buffer_drop::<A>(staging, false);
+                Err(error.into())
+            }
+            None => Ok(staging),
+        }
+    }
+
+    /// Copies the rows of a mapped staging buffer to `readback.data`, without their padding.
+    fn snapshot_read<A: HalApi>(&self, readback: &mut Readback) -> Result<(), DeviceSnapshotError> {
+        let size = readback.padded_bytes_per_row as BufferAddress * readback.rows as u64;
+        let (ptr, _) = self.buffer_get_mapped_range::<A>(readback.staging, 0, Some(size))?;
+        let mapped = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
+        readback
+            .data
+            .reserve(readback.bytes_per_row as usize * readback.rows as usize);
+        for row in mapped.chunks_exact(readback.padded_bytes_per_row as usize) {
+            readback
+                .data
+                .extend_from_slice(&row[..readback.bytes_per_row as usize]);
+        }
+        self.buffer_unmap::<A>(readback.staging)?;
+        Ok(())
+    }
+
+    /// Writes the contents of `snapshot` back to its resources with the queue of `device_id`.
+    ///
+    /// The resources must be alive, unmapped, have been created with `COPY_DST` and match their
+    /// snapshot. The ids of `snapshot` are used with the backend of `device_id`. To restore a
+    /// snapshot to resources that were recreated with other ids, replace the ids of `snapshot`
+    /// with theirs.
+    ///
+    /// All resources are validated before any of them is written, so on error nothing is
+    /// restored.
+    pub fn device_restore<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        snapshot: &DeviceSnapshot,
+    ) -> Result<(), DeviceSnapshotError> {
+        profiling::scope!("Device::restore");
+
+        let backend = device_id.backend();
+        self.restore_validate::<A>(device_id, snapshot)?;
+
+        for buffer in snapshot.buffers.iter() {
+            if !buffer.data.is_empty() {
+                let buffer_id = with_backend(buffer.id, backend);
+                self.queue_write_buffer::<A>(device_id, buffer_id, 0, &buffer.data)?;
+            }
+        }
+        for texture in snapshot.textures.iter() {
+            let texture_id = with_backend(texture.id, backend);
+            for subresource in texture.subresources.iter() {
+                if subresource.data.is_empty() {
+                    continue;
+                }
+                self.queue_write_texture::<A>(
+                    device_id,
+                    &ImageCopyTexture {
+                        texture: texture_id,
+                        mip_level: subresource.mip_level,
+                        origin: wgt::Origin3d::ZERO,
+                        aspect: subresource.aspect,
+                    },
+                    &subresource.data,
+                    &subresource.layout,
+                    &subresource.size,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every resource of `snapshot` can be restored on `device_id`.
+    fn restore_validate<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        snapshot: &DeviceSnapshot,
+    ) -> Result<(), DeviceSnapshotError> {
+        let hub = A::hub(self);
+        let backend = device_id.backend();
+        let mut token = Token::root();
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        device_guard.get(device_id).map_err(|_| InvalidDevice)?;
+        let (buffer_guard, mut token) = hub.buffers.read(&mut token);
+        let (texture_guard, _) = hub.textures.read(&mut token);
+
+        for entry in snapshot.buffers.iter() {
+            let buffer = buffer_guard
+                .get(with_backend(entry.id, backend))
+                .ok()
+                .filter(|buffer| {
+                    buffer.device_id.value.0 == device_id
+                        && buffer.raw.is_some()
+                        && matches!(buffer.map_state, resource::BufferMapState::Idle)
+                })
+                .ok_or(DeviceSnapshotError::InvalidBuffer(entry.id))?;
+            let len = entry.data.len() as BufferAddress;
+            if !buffer.usage.contains(BufferUsages::COPY_DST)
+                || len > buffer.size
+                || len % wgt::COPY_BUFFER_ALIGNMENT != 0
+            {
+                return Err(DeviceSnapshotError::BufferMismatch(entry.id));
+            }
+        }
+
+        for entry in snapshot.textures.iter() {
+            let texture = texture_guard
+                .get(with_backend(entry.id, backend))
+                .ok()
+                .filter(|texture| {
+                    texture.device_id.value.0 == device_id
+                        && matches!(
+                            texture.inner,
+                            resource::TextureInner::Native { raw: Some(_) }
+                        )
+                })
+                .ok_or(DeviceSnapshotError::InvalidTexture(entry.id))?;
+            let desc = &texture.desc;
+            if !desc.usage.contains(TextureUsages::COPY_DST)
+                || desc.size != entry.desc.size
+                || desc.format != entry.desc.format
+                || desc.dimension != entry.desc.dimension
+                || desc.mip_level_count != entry.desc.mip_level_count
+                || desc.sample_count != entry.desc.sample_count
+            {
+                return Err(DeviceSnapshotError::TextureMismatch(entry.id));
+            }
+            for subresource in entry.subresources.iter() {
+                let valid = subresource.mip_level < desc.mip_level_count
+                    && is_restorable_aspect(desc.format, subresource.aspect)
+                    && {
+                        let (size, layout) =
+                            subresource_layout(desc, subresource.mip_level, subresource.aspect);
+                        let len = layout.bytes_per_row.unwrap() as usize
+                            * layout.rows_per_image.unwrap() as usize
+                            * size.depth_or_array_layers as usize;
+                        size == subresource.size
+                            && layout.bytes_per_row == subresource.layout.bytes_per_row
+                            && layout.rows_per_image == subresource.layout.rows_per_image
+                            && subresource.layout.offset == 0
+                            && (subresource.data.is_empty() || subresource.data.len() == len)
+                    };
+                if !valid {
+                    return Err(DeviceSnapshotError::InvalidSubresource {
+                        texture: entry.id,
+                        mip_level: subresource.mip_level,
+                        aspect: subresource.aspect,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns `id` as an id of `backend`.
+fn with_backend<I: id::TypedId>(id: I, backend: wgt::Backend) -> I {
+    let (index, epoch, _) = id.unzip();
+    I::zip(index, epoch, backend)
+}
+
+/// Returns whether `aspect` of `format` can be copied both to and from a buffer.
+fn is_restorable_aspect(format: wgt::TextureFormat, aspect: TextureAspect) -> bool {
+    format.block_size(Some(aspect)).is_some()
+        && conv::is_valid_copy_src_texture_format(format, aspect)
+        && conv::is_valid_copy_dst_texture_format(format, aspect)
+}
+
+/// Returns the physical size of `mip_level` and the tightly packed layout of `aspect` in it.
+fn subresource_layout(
+    desc: &wgt::TextureDescriptor<(), Vec<wgt::TextureFormat>>,
+    mip_level: u32,
+    aspect: TextureAspect,
+) -> (wgt::Extent3d, wgt::ImageDataLayout) {
+    let format = desc.format;
+    let size = desc
+        .mip_level_size(mip_level)
+        .unwrap()
+        .physical_size(format);
+    let block_size = format.block_size(Some(aspect)).unwrap();
+    let (block_width, block_height) = format.block_dimensions();
+    let layout = wgt::ImageDataLayout {
+        offset: 0,
+        bytes_per_row: Some(size.width / block_width * block_size),
+        rows_per_image: Some(size.height / block_height),
+    };
+    (size, layout)
+}
@@ -47,6 +47,7 @@ pub enum Action<'a> {
     CreateBuffer(id::BufferId, crate::resource::BufferDescriptor<'a>),
     FreeBuffer(id::BufferId),
     DestroyBuffer(id::BufferId),
+    ResizeBuffer(id::BufferId, wgt::BufferAddress),
     CreateTexture(id::TextureId, crate::resource::TextureDescriptor<'a>),
     FreeTexture(id::TextureId),
     DestroyTexture(id::TextureId),
@@ -99,6 +100,11 @@ pub enum Action<'a> {
         implicit_context: Option<super::ImplicitPipelineContext>,
     },
     DestroyRenderPipeline(id::RenderPipelineId),
+    CreatePipelineCache {
+        id: id::PipelineCacheId,
+        desc: crate::pipeline::PipelineCacheDescriptor<'a>,
+    },
+    DestroyPipelineCache(id::PipelineCacheId),
     CreateRenderBundle {
         id: id::RenderBundleId,
         desc: crate::command::RenderBundleEncoderDescriptor<'a>,
@@ -125,7 +131,7 @@ pub enum Action<'a> {
     Submit(crate::SubmissionIndex, Vec<Command>),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "trace", derive(serde::Serialize))]
 #[cfg_attr(feature = "replay", derive(serde::Deserialize))]
 pub enum Command {
@@ -21,6 +21,8 @@ pub struct GlobalReport {
     pub dx11: Option<HubReport>,
     #[cfg(feature = "gles")]
     pub gl: Option<HubReport>,
+    #[cfg(feature = "noop")]
+    pub empty: Option<HubReport>,
 }
 
 pub struct Global<G: GlobalIdentityHandlerFactory> {
@@ -114,6 +116,12 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             } else {
                 None
             },
+            #[cfg(feature = "noop")]
+            empty: if self.instance.empty.is_some() {
+                Some(self.hubs.empty.generate_report())
+            } else {
+                None
+            },
         }
     }
 }
@@ -145,6 +153,10 @@ impl<G: GlobalIdentityHandlerFactory> Drop for Global<G> {
         {
             self.hubs.gl.clear(&mut surface_guard, true);
         }
+        #[cfg(feature = "noop")]
+        {
+            self.hubs.empty.clear(&mut surface_guard, true);
+        }
 
         // destroy surfaces
         for element in surface_guard.map.drain(..) {
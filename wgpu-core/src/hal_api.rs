@@ -16,6 +16,32 @@ pub trait HalApi: hal::Api {
     fn get_surface_mut(surface: &mut Surface) -> Option<&mut HalSurface<Self>>;
 }
 
+#[cfg(feature = "noop")]
+impl HalApi for hal::api::Empty {
+    const VARIANT: Backend = Backend::Empty;
+    fn create_instance_from_hal(name: &str, hal_instance: Self::Instance) -> Instance {
+        Instance {
+            name: name.to_owned(),
+            empty: Some(hal_instance),
+            ..Default::default()
+        }
+    }
+    fn instance_as_hal(instance: &Instance) -> Option<&Self::Instance> {
+        instance.empty.as_ref()
+    }
+    fn hub<G: GlobalIdentityHandlerFactory>(global: &Global<G>) -> &Hub<Self, G> {
+        &global.hubs.empty
+    }
+    // The noop backend can't present, so it never has a surface.
+    fn get_surface(_: &Surface) -> Option<&HalSurface<Self>> {
+        None
+    }
+    fn get_surface_mut(_: &mut Surface) -> Option<&mut HalSurface<Self>> {
+        None
+    }
+}
+
+#[cfg(not(feature = "noop"))]
 impl HalApi for hal::api::Empty {
     const VARIANT: Backend = Backend::Empty;
     fn create_instance_from_hal(_: &str, _: Self::Instance) -> Instance {
@@ -163,9 +163,12 @@ use crate::{
     id,
     identity::GlobalIdentityHandlerFactory,
     instance::{Adapter, HalSurface, Instance, Surface},
-    pipeline::{ComputePipeline, RenderPipeline, ShaderModule},
+    pipeline::{ComputePipeline, PipelineCache, RenderPipeline, ShaderModule},
     registry::Registry,
-    resource::{Buffer, QuerySet, Sampler, StagingBuffer, Texture, TextureClearMode, TextureView},
+    resource::{
+        AccelerationStructure, Buffer, QuerySet, Sampler, StagingBuffer, Texture, TextureClearMode,
+        TextureView,
+    },
     storage::{Element, Storage, StorageReport},
 };
 
@@ -199,11 +202,13 @@ use std::{fmt::Debug, marker::PhantomData};
 /// - [`ComputePipeline`]
 /// - [`RenderPipeline`]
 /// - [`ShaderModule`]
+/// - [`PipelineCache`]
 /// - [`Buffer`]
 /// - [`StagingBuffer`]
 /// - [`Texture`]
 /// - [`TextureView`]
 /// - [`Sampler`]
+/// - [`AccelerationStructure`]
 /// - [`QuerySet`]
 ///
 /// That is, you may only acquire a new lock on a `Hub` field if it
@@ -256,6 +261,8 @@ impl<A: HalApi> Access<RenderPipeline<A>> for BindGroup<A> {}
 impl<A: HalApi> Access<RenderPipeline<A>> for ComputePipeline<A> {}
 impl<A: HalApi> Access<ShaderModule<A>> for Device<A> {}
 impl<A: HalApi> Access<ShaderModule<A>> for BindGroupLayout<A> {}
+impl<A: HalApi> Access<PipelineCache<A>> for Device<A> {}
+impl<A: HalApi> Access<PipelineCache<A>> for ShaderModule<A> {}
 impl<A: HalApi> Access<Buffer<A>> for Root {}
 impl<A: HalApi> Access<Buffer<A>> for Device<A> {}
 impl<A: HalApi> Access<Buffer<A>> for BindGroupLayout<A> {}
@@ -280,6 +287,10 @@ impl<A: HalApi> Access<QuerySet<A>> for CommandBuffer<A> {}
 impl<A: HalApi> Access<QuerySet<A>> for RenderPipeline<A> {}
 impl<A: HalApi> Access<QuerySet<A>> for ComputePipeline<A> {}
 impl<A: HalApi> Access<QuerySet<A>> for Sampler<A> {}
+impl<A: HalApi> Access<AccelerationStructure<A>> for Root {}
+impl<A: HalApi> Access<AccelerationStructure<A>> for Device<A> {}
+impl<A: HalApi> Access<AccelerationStructure<A>> for Sampler<A> {}
+impl<A: HalApi> Access<QuerySet<A>> for AccelerationStructure<A> {}
 
 #[cfg(debug_assertions)]
 thread_local! {
@@ -367,11 +378,13 @@ pub struct HubReport {
     pub render_bundles: StorageReport,
     pub render_pipelines: StorageReport,
     pub compute_pipelines: StorageReport,
+    pub pipeline_caches: StorageReport,
     pub query_sets: StorageReport,
     pub buffers: StorageReport,
     pub textures: StorageReport,
     pub texture_views: StorageReport,
     pub samplers: StorageReport,
+    pub acceleration_structures: StorageReport,
 }
 
 impl HubReport {
@@ -457,12 +470,14 @@ pub struct Hub<A: HalApi, F: GlobalIdentityHandlerFactory> {
     pub render_bundles: Registry<RenderBundle<A>, id::RenderBundleId, F>,
     pub render_pipelines: Registry<RenderPipeline<A>, id::RenderPipelineId, F>,
     pub compute_pipelines: Registry<ComputePipeline<A>, id::ComputePipelineId, F>,
+    pub pipeline_caches: Registry<PipelineCache<A>, id::PipelineCacheId, F>,
     pub query_sets: Registry<QuerySet<A>, id::QuerySetId, F>,
     pub buffers: Registry<Buffer<A>, id::BufferId, F>,
     pub staging_buffers: Registry<StagingBuffer<A>, id::StagingBufferId, F>,
     pub textures: Registry<Texture<A>, id::TextureId, F>,
     pub texture_views: Registry<TextureView<A>, id::TextureViewId, F>,
     pub samplers: Registry<Sampler<A>, id::SamplerId, F>,
+    pub acceleration_structures: Registry<AccelerationStructure<A>, id::AccelerationStructureId, F>,
 }
 
 impl<A: HalApi, F: GlobalIdentityHandlerFactory> Hub<A, F> {
@@ -478,12 +493,14 @@ impl<A: HalApi, F: GlobalIdentityHandlerFactory> Hub<A, F> {
             render_bundles: Registry::new(A::VARIANT, factory),
             render_pipelines: Registry::new(A::VARIANT, factory),
             compute_pipelines: Registry::new(A::VARIANT, factory),
+            pipeline_caches: Registry::new(A::VARIANT, factory),
             query_sets: Registry::new(A::VARIANT, factory),
             buffers: Registry::new(A::VARIANT, factory),
             staging_buffers: Registry::new(A::VARIANT, factory),
             textures: Registry::new(A::VARIANT, factory),
             texture_views: Registry::new(A::VARIANT, factory),
             samplers: Registry::new(A::VARIANT, factory),
+            acceleration_structures: Registry::new(A::VARIANT, factory),
         }
     }
 
@@ -523,6 +540,16 @@ impl<A: HalApi, F: GlobalIdentityHandlerFactory> Hub<A, F> {
             }
         }
 
+        for element in self.acceleration_structures.data.write().map.drain(..) {
+            if let Element::Occupied(acceleration_structure, _) = element {
+                unsafe {
+                    devices[acceleration_structure.device_id.value]
+                        .raw
+                        .destroy_acceleration_structure(acceleration_structure.raw);
+                }
+            }
+        }
+
         for element in self.texture_views.data.write().map.drain(..) {
             if let Element::Occupied(texture_view, _) = element {
                 let device = &devices[texture_view.device_id.value];
@@ -604,6 +631,14 @@ impl<A: HalApi, F: GlobalIdentityHandlerFactory> Hub<A, F> {
                 }
             }
         }
+        for element in self.pipeline_caches.data.write().map.drain(..) {
+            if let Element::Occupied(cache, _) = element {
+                let device = &devices[cache.device_id.value];
+                unsafe {
+                    device.raw.destroy_pipeline_cache(cache.raw);
+                }
+            }
+        }
 
         for element in surface_guard.map.iter_mut() {
             if let Element::Occupied(ref mut surface, _epoch) = *element {
@@ -673,11 +708,13 @@ impl<A: HalApi, F: GlobalIdentityHandlerFactory> Hub<A, F> {
             render_bundles: self.render_bundles.data.read().generate_report(),
             render_pipelines: self.render_pipelines.data.read().generate_report(),
             compute_pipelines: self.compute_pipelines.data.read().generate_report(),
+            pipeline_caches: self.pipeline_caches.data.read().generate_report(),
             query_sets: self.query_sets.data.read().generate_report(),
             buffers: self.buffers.data.read().generate_report(),
             textures: self.textures.data.read().generate_report(),
             texture_views: self.texture_views.data.read().generate_report(),
             samplers: self.samplers.data.read().generate_report(),
+            acceleration_structures: self.acceleration_structures.data.read().generate_report(),
         }
     }
 }
@@ -693,12 +730,15 @@ pub struct Hubs<F: GlobalIdentityHandlerFactory> {
     pub(crate) dx11: Hub<hal::api::Dx11, F>,
     #[cfg(feature = "gles")]
     pub(crate) gl: Hub<hal::api::Gles, F>,
-    #[cfg(all(
-        not(all(feature = "vulkan", not(target_arch = "wasm32"))),
-        not(all(feature = "metal", any(target_os = "macos", target_os = "ios"))),
-        not(all(feature = "dx12", windows)),
-        not(all(feature = "dx11", windows)),
-        not(feature = "gles"),
+    #[cfg(any(
+        feature = "noop",
+        all(
+            not(all(feature = "vulkan", not(target_arch = "wasm32"))),
+            not(all(feature = "metal", any(target_os = "macos", target_os = "ios"))),
+            not(all(feature = "dx12", windows)),
+            not(all(feature = "dx11", windows)),
+            not(feature = "gles"),
+        )
     ))]
     pub(crate) empty: Hub<hal::api::Empty, F>,
 }
@@ -716,12 +756,15 @@ impl<F: GlobalIdentityHandlerFactory> Hubs<F> {
             dx11: Hub::new(factory),
             #[cfg(feature = "gles")]
             gl: Hub::new(factory),
-            #[cfg(all(
-                not(all(feature = "vulkan", not(target_arch = "wasm32"))),
-                not(all(feature = "metal", any(target_os = "macos", target_os = "ios"))),
-                not(all(feature = "dx12", windows)),
-                not(all(feature = "dx11", windows)),
-                not(feature = "gles"),
+            #[cfg(any(
+                feature = "noop",
+                all(
+                    not(all(feature = "vulkan", not(target_arch = "wasm32"))),
+                    not(all(feature = "metal", any(target_os = "macos", target_os = "ios"))),
+                    not(all(feature = "dx12", windows)),
+                    not(all(feature = "dx11", windows)),
+                    not(feature = "gles"),
+                )
             ))]
             empty: Hub::new(factory),
         }
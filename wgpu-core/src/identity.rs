@@ -1,4 +1,4 @@
-use parking_lot::Mutex;
+use crate::lock::Mutex;
 use wgt::Backend;
 
 use crate::{id, Epoch, Index};
@@ -154,12 +154,14 @@ pub trait GlobalIdentityHandlerFactory:
     + IdentityHandlerFactory<id::RenderBundleId>
     + IdentityHandlerFactory<id::RenderPipelineId>
     + IdentityHandlerFactory<id::ComputePipelineId>
+    + IdentityHandlerFactory<id::PipelineCacheId>
     + IdentityHandlerFactory<id::QuerySetId>
     + IdentityHandlerFactory<id::BufferId>
     + IdentityHandlerFactory<id::StagingBufferId>
     + IdentityHandlerFactory<id::TextureId>
     + IdentityHandlerFactory<id::TextureViewId>
     + IdentityHandlerFactory<id::SamplerId>
+    + IdentityHandlerFactory<id::AccelerationStructureId>
     + IdentityHandlerFactory<id::SurfaceId>
 {
     fn ids_are_generated_in_wgpu() -> bool;
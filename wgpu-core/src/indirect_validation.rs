@@ -0,0 +1,537 @@
+/*! GPU-side validation of indirect draw arguments.
+
+Indirect draws whose ranges reach past the end of the bound vertex, instance
+or index buffers read memory out of bounds, and some drivers crash on that.
+Indirect draws with a non-zero first instance are only supported with
+[`wgt::Features::INDIRECT_FIRST_INSTANCE`], and behave differently on each
+backend without it. Unless the adapter reports
+[`wgt::DownlevelFlags::ROBUST_INDIRECT_EXECUTION`] and that feature is enabled,
+the arguments of the indirect draws in a render pass are validated on the GPU:
+
+- Before the pass, the arguments are copied into a scratch buffer, along with
+  the limits of the buffers bound at the time of each draw.
+
+- A compute shader then turns every draw that is out of bounds, or that has a
+  non-zero first instance without the feature, into an empty draw.
+
+- The pass itself draws with the arguments from the scratch buffer.
+
+Indirect draws recorded in render bundles are validated the same way, with the
+limits of the buffers the bundle binds, which are recorded when it's finished.
+
+The indices of indexed draws are not read, so their base vertex is only checked
+to leave at least one index of the bound format within the vertex buffers.
+
+Validation needs the `wgsl` feature to build its shader. Without it, render
+passes with indirect draws fail on devices that require the validation.
+!*/
+
+use std::{borrow::Cow, iter, mem, ptr};
+
+use hal::{CommandEncoder as _, Device as _};
+
+use crate::{
+    command::{RenderBundle, RenderCommand, RenderPassErrorInner},
+    device::{queue::TempResource, DeviceError},
+    hal_api::HalApi,
+    id,
+    resource::Buffer,
+    storage::Storage,
+};
+
+const WORKGROUP_SIZE: u32 = 64;
+/// The smallest `max_compute_workgroups_per_dimension` of the backends.
+const MAX_WORKGROUPS_PER_DIMENSION: u32 = 65535;
+/// The smallest `max_storage_buffer_binding_size` of the backends.
+const MAX_BINDING_SIZE: wgt::BufferAddress = 1 << 27;
+/// Words before the draw parameters, see `indirect_validation.wgsl`.
+const HEADER_WORDS: u64 = 4;
+const PARAMS_WORDS: u64 = 6;
+
+#[cfg(feature = "wgsl")]
+fn shader_module() -> Option<naga::Module> {
+    let module = naga::front::wgsl::parse_str(include_str!("indirect_validation.wgsl"))
+        .expect("Failed to parse the indirect validation shader");
+    Some(module)
+}
+
+#[cfg(not(feature = "wgsl"))]
+fn shader_module() -> Option<naga::Module> {
+    None
+}
+
+fn args_stride(indexed: bool) -> wgt::BufferAddress {
+    match indexed {
+        false => mem::size_of::<wgt::DrawIndirectArgs>() as wgt::BufferAddress,
+        true => mem::size_of::<wgt::DrawIndexedIndirectArgs>() as wgt::BufferAddress,
+    }
+}
+
+/// Limits of the buffers bound for an indirect draw.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct DrawLimits {
+    /// Number of vertices, or indices for indexed draws, that can be drawn.
+    pub count: u32,
+    /// Number of instances that can be drawn.
+    pub instance: u32,
+    /// Number of vertices that indexed draws can reference.
+    pub vertex: u32,
+    /// Largest index that indexed draws can use to reach the vertices, given
+    /// the index format.
+    pub max_index: u32,
+}
+
+struct Pipeline<A: HalApi> {
+    module: A::ShaderModule,
+    bind_group_layout: A::BindGroupLayout,
+    pipeline_layout: A::PipelineLayout,
+    pipeline: A::ComputePipeline,
+}
+
+/// Per-device pipeline of the indirect draw validation.
+pub(crate) struct IndirectValidation<A: HalApi> {
+    /// `None` if the validation shader isn't available because the `wgsl`
+    /// feature is disabled.
+    pipeline: Option<Pipeline<A>>,
+    /// Whether [`wgt::Features::INDIRECT_FIRST_INSTANCE`] is enabled.
+    allow_first_instance: bool,
+}
+
+impl<A: HalApi> IndirectValidation<A> {
+    /// Creates the validation pipeline.
+    pub(crate) fn new(device: &A::Device, allow_first_instance: bool) -> Result<Self, DeviceError> {
+        let module = match shader_module() {
+            Some(module) => module,
+            None => {
+                log::error!(
+                    "Indirect draws can't be validated without the `wgsl` feature, \
+                    render passes that use them will fail"
+                );
+                return Ok(Self {
+                    pipeline: None,
+                    allow_first_instance,
+                });
+            }
+        };
+        let info = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .expect("Failed to validate the indirect validation shader");
+
+        let label = Some("(wgpu internal) Indirect validation");
+        let hal_shader = hal::ShaderInput::Naga(hal::NagaShader {
+            module: Cow::Owned(module),
+            info,
+        });
+        let hal_desc = hal::ShaderModuleDescriptor {
+            label,
+            runtime_checks: true,
+            zero_initialize_workgroup_memory: true,
+        };
+        let module =
+            unsafe { device.create_shader_module(&hal_desc, hal_shader) }.map_err(|error| {
+                match error {
+                    hal::ShaderError::Device(error) => DeviceError::from(error),
+                    hal::ShaderError::Compilation(ref msg) => {
+                        log::error!("Shader error: {}", msg);
+                        DeviceError::ResourceCreationFailed
+                    }
+                }
+            })?;
+
+        let entries = [wgt::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgt::ShaderStages::COMPUTE,
+            ty: wgt::BindingType::Buffer {
+                ty: wgt::BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }];
+        let bind_group_layout = match unsafe {
+            device.create_bind_group_layout(&hal::BindGroupLayoutDescriptor {
+                label,
+                flags: hal::BindGroupLayoutFlags::empty(),
+                entries: &entries,
+            })
+        } {
+            Ok(bind_group_layout) => bind_group_layout,
+            Err(error) => {
+                unsafe { device.destroy_shader_module(module) };
+                return Err(error.into());
+            }
+        };
+
+        let pipeline_layout = match unsafe {
+            device.create_pipeline_layout(&hal::PipelineLayoutDescriptor {
+                label,
+                flags: hal::PipelineLayoutFlags::empty(),
+                bind_group_layouts: &[&bind_group_layout],
+                push_constant_ranges: &[],
+            })
+        } {
+            Ok(pipeline_layout) => pipeline_layout,
+            Err(error) => {
+                unsafe {
+                    device.destroy_bind_group_layout(bind_group_layout);
+                    device.destroy_shader_module(module);
+                }
+                return Err(error.into());
+            }
+        };
+
+        let constants = hal::PipelineConstants::default();
+        let pipeline = match unsafe {
+            device.create_compute_pipeline(&hal::ComputePipelineDescriptor {
+                label,
+                layout: &pipeline_layout,
+                stage: hal::ProgrammableStage {
+                    module: &module,
+                    entry_point: "main",
+                    constants: &constants,
+                },
+                cache: None,
+            })
+        } {
+            Ok(pipeline) => pipeline,
+            Err(error) => {
+                unsafe {
+                    device.destroy_pipeline_layout(pipeline_layout);
+                    device.destroy_bind_group_layout(bind_group_layout);
+                    device.destroy_shader_module(module);
+                }
+                return Err(match error {
+                    hal::PipelineError::Device(error) => error.into(),
+                    other => {
+                        log::error!("Pipeline error: {}", other);
+                        DeviceError::ResourceCreationFailed
+                    }
+                });
+            }
+        };
+
+        Ok(Self {
+            pipeline: Some(Pipeline {
+                module,
+                bind_group_layout,
+                pipeline_layout,
+                pipeline,
+            }),
+            allow_first_instance,
+        })
+    }
+
+    pub(crate) fn dispose(self, device: &A::Device) {
+        if let Some(pipeline) = self.pipeline {
+            unsafe {
+                device.destroy_compute_pipeline(pipeline.pipeline);
+                device.destroy_pipeline_layout(pipeline.pipeline_layout);
+                device.destroy_bind_group_layout(pipeline.bind_group_layout);
+                device.destroy_shader_module(pipeline.module);
+            }
+        }
+    }
+
+    /// Allocates the scratch buffer for the indirect draws among `commands`,
+    /// including those of the render bundles they execute. The buffer is
+    /// `protected` if the commands are recorded into a protected command
+    /// buffer, which can't write to unprotected memory.
+    ///
+    /// Returns `None` if there are no indirect draws.
+    pub(crate) fn create_draws(
+        &self,
+        device: &A::Device,
+        commands: &[RenderCommand],
+        bundle_guard: &Storage<RenderBundle<A>, id::RenderBundleId>,
+        protected: bool,
+    ) -> Result<Option<Draws<A>>, RenderPassErrorInner> {
+        let mut draw_count = 0;
+        let mut args_size = 0;
+        for command in commands {
+            let (count, indexed) = match *command {
+                RenderCommand::MultiDrawIndirect { count, indexed, .. } => {
+                    (count.map_or(1, |c| c.get()), indexed)
+                }
+                RenderCommand::MultiDrawIndirectCount {
+                    max_count, indexed, ..
+                } => (max_count, indexed),
+                RenderCommand::ExecuteBundle(bundle_id) => {
+                    // Invalid bundles fail the pass when they are executed.
+                    if let Ok(bundle) = bundle_guard.get(bundle_id) {
+                        for &(indexed, _) in bundle.indirect_draws.iter() {
+                            draw_count += 1;
+                            args_size += args_stride(indexed);
+                        }
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            draw_count += count as u64;
+            args_size += count as u64 * args_stride(indexed);
+        }
+        if draw_count == 0 {
+            return Ok(None);
+        }
+        if self.pipeline.is_none() {
+            return Err(RenderPassErrorInner::IndirectValidationUnavailable);
+        }
+
+        let params_size = (HEADER_WORDS + draw_count * PARAMS_WORDS) * 4;
+        let size = params_size + args_size;
+        if size > MAX_BINDING_SIZE {
+            return Err(RenderPassErrorInner::TooManyIndirectDraws(draw_count));
+        }
+
+        let mut memory_flags = hal::MemoryFlags::TRANSIENT;
+        memory_flags.set(hal::MemoryFlags::PROTECTED, protected);
+        let scratch = unsafe {
+            device.create_buffer(&hal::BufferDescriptor {
+                label: Some("(wgpu internal) Indirect validation"),
+                size,
+                usage: hal::BufferUses::COPY_DST
+                    | hal::BufferUses::STORAGE_READ_WRITE
+                    | hal::BufferUses::INDIRECT,
+                memory_flags,
+            })
+        }
+        .map_err(DeviceError::from)?;
+
+        // The size limit keeps the draw count well within `u32`.
+        let draw_count = draw_count as u32;
+        let workgroups = (draw_count + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE;
+        let row_size = workgroups.min(MAX_WORKGROUPS_PER_DIMENSION) * WORKGROUP_SIZE;
+        let mut params = Vec::with_capacity((params_size / 4) as usize);
+        params.extend([draw_count, row_size, !self.allow_first_instance as u32, 0]);
+
+        Ok(Some(Draws {
+            scratch,
+            params,
+            copies: Vec::new(),
+            args_offset: params_size,
+        }))
+    }
+
+    /// Records the validation of `draws` into `encoder`, which has to be
+    /// executed before the render pass that draws from them.
+    ///
+    /// The resources used by the validation are appended to `temp_resources`.
+    pub(crate) fn encode(
+        &self,
+        device: &A::Device,
+        encoder: &mut A::CommandEncoder,
+        draws: Draws<A>,
+        buffer_guard: &Storage<Buffer<A>, id::BufferId>,
+        temp_resources: &mut Vec<TempResource<A>>,
+    ) -> Result<(), DeviceError> {
+        let params_size = (draws.params.len() * mem::size_of::<u32>()) as wgt::BufferAddress;
+        let resources = unsafe { self.upload_params(device, &draws.params) }.and_then(|staging| {
+            match unsafe { self.create_bind_group(device, &draws.scratch) } {
+                Ok(bind_group) => Ok((staging, bind_group)),
+                Err(error) => {
+                    unsafe { device.destroy_buffer(staging) };
+                    Err(error)
+                }
+            }
+        });
+        let (staging, bind_group) = match resources {
+            Ok(resources) => resources,
+            Err(error) => {
+                unsafe { device.destroy_buffer(draws.scratch) };
+                return Err(error.into());
+            }
+        };
+
+        // Draws are only created with a pipeline.
+        let pipeline = self.pipeline.as_ref().unwrap();
+        let draw_count = draws.params[0];
+        let row_size = draws.params[1];
+        let workgroups_x = row_size / WORKGROUP_SIZE;
+        let workgroups_y = (draw_count + row_size - 1) / row_size;
+
+        unsafe {
+            encoder.transition_buffers(
+                [
+                    hal::BufferBarrier {
+                        buffer: &staging,
+                        usage: hal::BufferUses::MAP_WRITE..hal::BufferUses::COPY_SRC,
+                    },
+                    hal::BufferBarrier {
+                        buffer: &draws.scratch,
+                        usage: hal::BufferUses::empty()..hal::BufferUses::COPY_DST,
+                    },
+                ]
+                .into_iter(),
+            );
+            encoder.copy_buffer_to_buffer(
+                &staging,
+                &draws.scratch,
+                iter::once(hal::BufferCopy {
+                    src_offset: 0,
+                    dst_offset: 0,
+                    size: wgt::BufferSize::new(params_size).unwrap(),
+                }),
+            );
+            for (buffer_id, region) in draws.copies {
+                // The pass has checked the buffers, and any arguments that
+                // are left uninitialized are still validated.
+                if let Some(raw) = buffer_guard
+                    .get(buffer_id)
+                    .ok()
+                    .and_then(|buffer| buffer.raw.as_ref())
+                {
+                    encoder.copy_buffer_to_buffer(raw, &draws.scratch, iter::once(region));
+                }
+            }
+            encoder.transition_buffers(iter::once(hal::BufferBarrier {
+                buffer: &draws.scratch,
+                usage: hal::BufferUses::COPY_DST..hal::BufferUses::STORAGE_READ_WRITE,
+            }));
+
+            encoder.begin_compute_pass(&hal::ComputePassDescriptor {
+                label: Some("(wgpu internal) Indirect validation"),
+                timestamp_writes: None,
+            });
+            encoder.set_compute_pipeline(&pipeline.pipeline);
+            encoder.set_bind_group(&pipeline.pipeline_layout, 0, &bind_group, &[]);
+            encoder.dispatch([workgroups_x, workgroups_y, 1]);
+            encoder.end_compute_pass();
+
+            encoder.transition_buffers(iter::once(hal::BufferBarrier {
+                buffer: &draws.scratch,
+                usage: hal::BufferUses::STORAGE_READ_WRITE..hal::BufferUses::INDIRECT,
+            }));
+        }
+
+        temp_resources.push(TempResource::Buffer(staging));
+        temp_resources.push(TempResource::Buffer(draws.scratch));
+        temp_resources.push(TempResource::BindGroup(bind_group));
+        Ok(())
+    }
+
+    unsafe fn upload_params(
+        &self,
+        device: &A::Device,
+        params: &[u32],
+    ) -> Result<A::Buffer, hal::DeviceError> {
+        let size = (params.len() * mem::size_of::<u32>()) as wgt::BufferAddress;
+        let staging = unsafe {
+            device.create_buffer(&hal::BufferDescriptor {
+                label: Some("(wgpu internal) Staging"),
+                size,
+                usage: hal::BufferUses::MAP_WRITE | hal::BufferUses::COPY_SRC,
+                memory_flags: hal::MemoryFlags::TRANSIENT,
+            })?
+        };
+        let result = unsafe {
+            device.map_buffer(&staging, 0..size).and_then(|mapping| {
+                ptr::copy_nonoverlapping(
+                    params.as_ptr().cast::<u8>(),
+                    mapping.ptr.as_ptr(),
+                    size as usize,
+                );
+                if !mapping.is_coherent {
+                    device.flush_mapped_ranges(&staging, iter::once(0..size));
+                }
+                device.unmap_buffer(&staging)
+            })
+        };
+        match result {
+            Ok(()) => Ok(staging),
+            Err(error) => {
+                unsafe { device.destroy_buffer(staging) };
+                Err(error)
+            }
+        }
+    }
+
+    unsafe fn create_bind_group(
+        &self,
+        device: &A::Device,
+        scratch: &A::Buffer,
+    ) -> Result<A::BindGroup, hal::DeviceError> {
+        // Draws are only created with a pipeline.
+        let bind_group_layout = &self.pipeline.as_ref().unwrap().bind_group_layout;
+        unsafe {
+            device.create_bind_group(&hal::BindGroupDescriptor {
+                label: Some("(wgpu internal) Indirect validation"),
+                layout: bind_group_layout,
+                buffers: &[hal::BufferBinding {
+                    buffer: scratch,
+                    offset: 0,
+                    size: None,
+                }],
+                samplers: &[],
+                textures: &[],
+                entries: &[hal::BindGroupEntry {
+                    binding: 0,
+                    resource_index: 0,
+                    count: 1,
+                }],
+                acceleration_structures: &[],
+            })
+        }
+    }
+}
+
+/// The indirect draws of a render pass, redirected to a scratch buffer
+/// holding their validated arguments.
+pub(crate) struct Draws<A: HalApi> {
+    scratch: A::Buffer,
+    /// Header and draw parameters, uploaded to the start of `scratch`.
+    params: Vec<u32>,
+    /// Copies of the draw arguments into `scratch`.
+    copies: Vec<(id::BufferId, hal::BufferCopy)>,
+    /// Offset of the next draw arguments in `scratch`.
+    args_offset: wgt::BufferAddress,
+}
+
+impl<A: HalApi> Draws<A> {
+    pub(crate) fn into_temp_resource(self) -> TempResource<A> {
+        TempResource::Buffer(self.scratch)
+    }
+
+    /// The buffer to draw from, instead of the indirect buffers.
+    pub(crate) fn buffer(&self) -> &A::Buffer {
+        &self.scratch
+    }
+
+    /// Records `count` draws with their arguments at `offset` in `buffer_id`,
+    /// and returns the offset of their validated arguments in [`Self::buffer`].
+    pub(crate) fn add(
+        &mut self,
+        buffer_id: id::BufferId,
+        offset: wgt::BufferAddress,
+        count: u32,
+        indexed: bool,
+        limits: DrawLimits,
+    ) -> wgt::BufferAddress {
+        let stride = args_stride(indexed);
+        let args_offset = self.args_offset;
+        for i in 0..count as u64 {
+            self.params.extend([
+                ((args_offset + i * stride) / 4) as u32,
+                indexed as u32,
+                limits.count,
+                limits.instance,
+                limits.vertex,
+                limits.max_index,
+            ]);
+        }
+        if let Some(size) = wgt::BufferSize::new(count as u64 * stride) {
+            self.copies.push((
+                buffer_id,
+                hal::BufferCopy {
+                    src_offset: offset,
+                    dst_offset: args_offset,
+                    size,
+                },
+            ));
+            self.args_offset += size.get();
+        }
+        args_offset
+    }
+}
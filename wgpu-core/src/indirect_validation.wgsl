@@ -0,0 +1,57 @@
+// Turns indirect draws that are out of bounds, or that have a disallowed
+// non-zero first instance, into empty draws.
+//
+// Layout of `data`, in 32-bit words:
+// - 0: number of draws
+// - 1: number of invocations per dispatched row of workgroups
+// - 2: whether the first instance must be zero
+// - 4 + 6 * i: parameters of draw `i`:
+//   - word offset of its arguments
+//   - whether it is an indexed draw
+//   - limit of its vertex or index range
+//   - limit of its instance range
+//   - limit of the vertices its indices can reach, for indexed draws
+//   - largest index that can reach the vertices, for indexed draws
+// - the arguments of all draws
+
+@group(0) @binding(0)
+var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let draw = id.y * data[1] + id.x;
+    if draw >= data[0] {
+        return;
+    }
+
+    let params = 4u + draw * 6u;
+    let args = data[params];
+    let indexed = data[params + 1u] != 0u;
+    let count_limit = data[params + 2u];
+    let instance_limit = data[params + 3u];
+    let vertex_limit = data[params + 4u];
+    let max_index = data[params + 5u];
+
+    let count = data[args];
+    let instance_count = data[args + 1u];
+    let first = data[args + 2u];
+    var first_instance = data[args + 3u];
+    // A base vertex that leaves no index within the vertex buffers.
+    var base_vertex_out_of_bounds = false;
+    if indexed {
+        first_instance = data[args + 4u];
+        let base_vertex = bitcast<i32>(data[args + 3u]);
+        if base_vertex >= 0 {
+            base_vertex_out_of_bounds = u32(base_vertex) >= vertex_limit;
+        } else {
+            base_vertex_out_of_bounds = 0u - u32(base_vertex) > max_index;
+        }
+    }
+
+    let first_instance_disallowed = data[2] != 0u && first_instance != 0u;
+
+    if count > count_limit || first > count_limit - count || instance_count > instance_limit || first_instance > instance_limit - instance_count || base_vertex_out_of_bounds || first_instance_disallowed {
+        data[args] = 0u;
+        data[args + 1u] = 0u;
+    }
+}
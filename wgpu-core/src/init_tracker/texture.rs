@@ -19,7 +19,9 @@ pub(crate) fn has_copy_partial_init_tracker_coverage(
     desc: &wgt::TextureDescriptor<(), Vec<wgt::TextureFormat>>,
 ) -> bool {
     let target_size = desc.mip_level_size(mip_level).unwrap();
-    copy_size.width != target_size.width
+    // Copies to multi-planar textures only ever cover a single plane.
+    desc.format.is_multi_planar_format()
+        || copy_size.width != target_size.width
         || copy_size.height != target_size.height
         || (desc.dimension == wgt::TextureDimension::D3
             && copy_size.depth_or_array_layers != target_size.depth_or_array_layers)
@@ -5,7 +5,7 @@ use crate::{
     hub::Token,
     id::{AdapterId, DeviceId, SurfaceId, Valid},
     identity::{GlobalIdentityHandlerFactory, Input},
-    present::Presentation,
+    present::{Presentation, SurfaceError},
     LabelHelpers, LifeGuard, Stored, DOWNLEVEL_WARNING_MESSAGE,
 };
 
@@ -57,6 +57,8 @@ fn downlevel_default_limits_less_than_default_limits() {
 pub struct Instance {
     #[allow(dead_code)]
     pub name: String,
+    /// Flags the instance was created with, after applying environment overrides.
+    pub flags: wgt::InstanceFlags,
     #[cfg(all(feature = "vulkan", not(target_arch = "wasm32")))]
     pub vulkan: Option<HalInstance<hal::api::Vulkan>>,
     #[cfg(all(feature = "metal", any(target_os = "macos", target_os = "ios")))]
@@ -67,20 +69,23 @@ pub struct Instance {
     pub dx11: Option<HalInstance<hal::api::Dx11>>,
     #[cfg(feature = "gles")]
     pub gl: Option<HalInstance<hal::api::Gles>>,
+    #[cfg(feature = "noop")]
+    pub empty: Option<HalInstance<hal::api::Empty>>,
 }
 
 impl Instance {
-    pub fn new(name: &str, instance_desc: wgt::InstanceDescriptor) -> Self {
+    /// Creates an instance for the backends in `instance_desc`.
+    ///
+    /// The backends and flags of `instance_desc` can be overridden by environment variables:
+    /// `WGPU_BACKEND` replaces the backends with a comma separated list (see
+    /// [`parse_backends_from_comma_list`]), and `WGPU_DEBUG`/`WGPU_VALIDATION` toggle the
+    /// corresponding flags (see [`wgt::InstanceFlags::with_env`]).
+    pub fn new(name: &str, mut instance_desc: wgt::InstanceDescriptor) -> Self {
         fn init<A: HalApi>(_: A, instance_desc: &wgt::InstanceDescriptor) -> Option<A::Instance> {
             if instance_desc.backends.contains(A::VARIANT.into()) {
-                let mut flags = hal::InstanceFlags::empty();
-                if cfg!(debug_assertions) {
-                    flags |= hal::InstanceFlags::VALIDATION;
-                    flags |= hal::InstanceFlags::DEBUG;
-                }
                 let hal_desc = hal::InstanceDescriptor {
                     name: "wgpu",
-                    flags,
+                    flags: instance_desc.flags,
                     dx12_shader_compiler: instance_desc.dx12_shader_compiler.clone(),
                     gles_minor_version: instance_desc.gles_minor_version,
                 };
@@ -90,8 +95,14 @@ impl Instance {
             }
         }
 
+        if let Ok(backends) = std::env::var("WGPU_BACKEND") {
+            instance_desc.backends = parse_backends_from_comma_list(&backends);
+        }
+        instance_desc.flags = instance_desc.flags.with_env();
+
         Self {
             name: name.to_string(),
+            flags: instance_desc.flags,
             #[cfg(all(feature = "vulkan", not(target_arch = "wasm32")))]
             vulkan: init(hal::api::Vulkan, &instance_desc),
             #[cfg(all(feature = "metal", any(target_os = "macos", target_os = "ios")))]
@@ -102,10 +113,17 @@ impl Instance {
             dx11: init(hal::api::Dx11, &instance_desc),
             #[cfg(feature = "gles")]
             gl: init(hal::api::Gles, &instance_desc),
+            #[cfg(feature = "noop")]
+            empty: init(hal::api::Empty, &instance_desc),
         }
     }
 
-    pub(crate) fn destroy_surface(&self, surface: Surface) {
+    pub(crate) fn destroy_surface(&self, mut surface: Surface) {
+        self.release_surface(&mut surface);
+    }
+
+    /// Destroys the platform surfaces of `surface`, leaving it without any.
+    fn release_surface(&self, surface: &mut Surface) {
         fn destroy<A: HalApi>(
             _: A,
             instance: &Option<A::Instance>,
@@ -118,15 +136,15 @@ impl Instance {
             }
         }
         #[cfg(all(feature = "vulkan", not(target_arch = "wasm32")))]
-        destroy(hal::api::Vulkan, &self.vulkan, surface.vulkan);
+        destroy(hal::api::Vulkan, &self.vulkan, surface.vulkan.take());
         #[cfg(all(feature = "metal", any(target_os = "macos", target_os = "ios")))]
-        destroy(hal::api::Metal, &self.metal, surface.metal);
+        destroy(hal::api::Metal, &self.metal, surface.metal.take());
         #[cfg(all(feature = "dx12", windows))]
-        destroy(hal::api::Dx12, &self.dx12, surface.dx12);
+        destroy(hal::api::Dx12, &self.dx12, surface.dx12.take());
         #[cfg(all(feature = "dx11", windows))]
-        destroy(hal::api::Dx11, &self.dx11, surface.dx11);
+        destroy(hal::api::Dx11, &self.dx11, surface.dx11.take());
         #[cfg(feature = "gles")]
-        destroy(hal::api::Gles, &self.gl, surface.gl);
+        destroy(hal::api::Gles, &self.gl, surface.gl.take());
     }
 }
 
@@ -286,6 +304,7 @@ impl<A: HalApi> Adapter<A> {
         self_id: AdapterId,
         open: hal::OpenDevice<A>,
         desc: &DeviceDescriptor,
+        instance_flags: wgt::InstanceFlags,
         trace_path: Option<&std::path::Path>,
     ) -> Result<Device<A>, RequestDeviceError> {
         let caps = &self.raw.capabilities;
@@ -298,6 +317,7 @@ impl<A: HalApi> Adapter<A> {
             caps.alignments.clone(),
             caps.downlevel.clone(),
             desc,
+            instance_flags,
             trace_path,
         )
         .or(Err(RequestDeviceError::OutOfMemory))
@@ -307,6 +327,7 @@ impl<A: HalApi> Adapter<A> {
         &self,
         self_id: AdapterId,
         desc: &DeviceDescriptor,
+        instance_flags: wgt::InstanceFlags,
         trace_path: Option<&std::path::Path>,
     ) -> Result<Device<A>, RequestDeviceError> {
         // Verify all features were exposed by the adapter
@@ -349,15 +370,18 @@ impl<A: HalApi> Adapter<A> {
             return Err(RequestDeviceError::LimitsExceeded(failed));
         }
 
-        let open = unsafe { self.raw.adapter.open(desc.features, &desc.limits) }.map_err(
-            |err| match err {
-                hal::DeviceError::Lost => RequestDeviceError::DeviceLost,
-                hal::DeviceError::OutOfMemory => RequestDeviceError::OutOfMemory,
-                hal::DeviceError::ResourceCreationFailed => RequestDeviceError::Internal,
-            },
-        )?;
+        let open = unsafe {
+            self.raw
+                .adapter
+                .open(desc.features, &desc.limits, &desc.additional_queues)
+        }
+        .map_err(|err| match err {
+            hal::DeviceError::Lost => RequestDeviceError::DeviceLost,
+            hal::DeviceError::OutOfMemory => RequestDeviceError::OutOfMemory,
+            hal::DeviceError::ResourceCreationFailed => RequestDeviceError::Internal,
+        })?;
 
-        self.create_device_from_hal(self_id, open, desc, trace_path)
+        self.create_device_from_hal(self_id, open, desc, instance_flags, trace_path)
     }
 }
 
@@ -442,6 +466,39 @@ pub enum RequestAdapterError {
     InvalidSurface(SurfaceId),
 }
 
+#[cfg(feature = "raw-window-handle")]
+fn init_surface<A: hal::Api>(
+    inst: &Option<A::Instance>,
+    display_handle: raw_window_handle::RawDisplayHandle,
+    window_handle: raw_window_handle::RawWindowHandle,
+) -> Option<HalSurface<A>> {
+    let mut error = None;
+    reinit_surface(inst, display_handle, window_handle, &mut error)
+}
+
+/// Like `init_surface`, but keeps the first error in `error`.
+#[cfg(feature = "raw-window-handle")]
+fn reinit_surface<A: hal::Api>(
+    inst: &Option<A::Instance>,
+    display_handle: raw_window_handle::RawDisplayHandle,
+    window_handle: raw_window_handle::RawWindowHandle,
+    error: &mut Option<hal::InstanceError>,
+) -> Option<HalSurface<A>> {
+    inst.as_ref().and_then(|inst| unsafe {
+        match inst.create_surface(display_handle, window_handle) {
+            Ok(raw) => Some(HalSurface {
+                raw,
+                //acquired_texture: None,
+            }),
+            Err(e) => {
+                log::warn!("Error: {:?}", e);
+                error.get_or_insert(e);
+                None
+            }
+        }
+    })
+}
+
 impl<G: GlobalIdentityHandlerFactory> Global<G> {
     #[cfg(feature = "raw-window-handle")]
     pub fn instance_create_surface(
@@ -452,37 +509,34 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
     ) -> SurfaceId {
         profiling::scope!("Instance::create_surface");
 
-        fn init<A: hal::Api>(
-            inst: &Option<A::Instance>,
-            display_handle: raw_window_handle::RawDisplayHandle,
-            window_handle: raw_window_handle::RawWindowHandle,
-        ) -> Option<HalSurface<A>> {
-            inst.as_ref().and_then(|inst| unsafe {
-                match inst.create_surface(display_handle, window_handle) {
-                    Ok(raw) => Some(HalSurface {
-                        raw,
-                        //acquired_texture: None,
-                    }),
-                    Err(e) => {
-                        log::warn!("Error: {:?}", e);
-                        None
-                    }
-                }
-            })
-        }
-
         let surface = Surface {
             presentation: None,
             #[cfg(all(feature = "vulkan", not(target_arch = "wasm32")))]
-            vulkan: init::<hal::api::Vulkan>(&self.instance.vulkan, display_handle, window_handle),
+            vulkan: init_surface::<hal::api::Vulkan>(
+                &self.instance.vulkan,
+                display_handle,
+                window_handle,
+            ),
             #[cfg(all(feature = "metal", any(target_os = "macos", target_os = "ios")))]
-            metal: init::<hal::api::Metal>(&self.instance.metal, display_handle, window_handle),
+            metal: init_surface::<hal::api::Metal>(
+                &self.instance.metal,
+                display_handle,
+                window_handle,
+            ),
             #[cfg(all(feature = "dx12", windows))]
-            dx12: init::<hal::api::Dx12>(&self.instance.dx12, display_handle, window_handle),
+            dx12: init_surface::<hal::api::Dx12>(
+                &self.instance.dx12,
+                display_handle,
+                window_handle,
+            ),
             #[cfg(all(feature = "dx11", windows))]
-            dx11: init::<hal::api::Dx11>(&self.instance.dx11, display_handle, window_handle),
+            dx11: init_surface::<hal::api::Dx11>(
+                &self.instance.dx11,
+                display_handle,
+                window_handle,
+            ),
             #[cfg(feature = "gles")]
-            gl: init::<hal::api::Gles>(&self.instance.gl, display_handle, window_handle),
+            gl: init_surface::<hal::api::Gles>(&self.instance.gl, display_handle, window_handle),
         };
 
         let mut token = Token::root();
@@ -522,6 +576,56 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         id.0
     }
 
+    /// Lists the displays that [`Self::instance_create_surface_from_display`] can present to.
+    #[cfg(all(feature = "vulkan", not(target_arch = "wasm32")))]
+    pub fn instance_enumerate_displays(&self) -> Vec<wgt::DisplayInfo> {
+        profiling::scope!("Instance::enumerate_displays");
+
+        self.instance
+            .vulkan
+            .as_ref()
+            .map_or_else(Vec::new, |inst| inst.enumerate_displays())
+    }
+
+    /// Creates a Vulkan surface presenting directly to a display through `VK_KHR_display`,
+    /// for systems without a window system.
+    ///
+    /// `display` indexes the list returned by [`Self::instance_enumerate_displays`].
+    #[cfg(all(feature = "vulkan", not(target_arch = "wasm32")))]
+    pub fn instance_create_surface_from_display(
+        &self,
+        display: usize,
+        id_in: Input<G, SurfaceId>,
+    ) -> Result<SurfaceId, hal::InstanceError> {
+        profiling::scope!("Instance::create_surface_from_display");
+
+        let surface = Surface {
+            presentation: None,
+            vulkan: self
+                .instance
+                .vulkan
+                .as_ref()
+                .map(|inst| {
+                    Ok(HalSurface {
+                        raw: inst.create_surface_from_display(display)?,
+                    })
+                })
+                .transpose()?,
+            #[cfg(all(feature = "metal", any(target_os = "macos", target_os = "ios")))]
+            metal: None,
+            #[cfg(all(feature = "dx12", windows))]
+            dx12: None,
+            #[cfg(all(feature = "dx11", windows))]
+            dx11: None,
+            #[cfg(feature = "gles")]
+            gl: None,
+        };
+
+        let mut token = Token::root();
+        let id = self.surfaces.prepare(id_in).assign(surface, &mut token);
+        Ok(id.0)
+    }
+
     #[cfg(all(
         target_arch = "wasm32",
         not(target_os = "emscripten"),
@@ -646,6 +750,125 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let (surface, _) = self.surfaces.unregister(id, &mut token);
         let mut surface = surface.unwrap();
 
+        self.unconfigure_surface(&mut surface);
+        self.instance.destroy_surface(surface);
+    }
+
+    /// Releases the platform surface behind `id`, keeping the surface id and the
+    /// devices that presented to it alive.
+    ///
+    /// This is for platforms that destroy the window of an application going to the
+    /// background, like Android. Call [`Global::surface_resume`] with the new window
+    /// and configure the surface again before presenting to it.
+    pub fn surface_suspend(&self, id: SurfaceId) -> Result<(), SurfaceError> {
+        profiling::scope!("Surface::suspend");
+        let mut token = Token::root();
+        let (mut surface_guard, _) = self.surfaces.write(&mut token);
+        let surface = surface_guard
+            .get_mut(id)
+            .map_err(|_| SurfaceError::Invalid)?;
+        self.suspend_surface(surface)
+    }
+
+    /// Recreates the platform surface behind `id` for a new window, after
+    /// [`Global::surface_suspend`].
+    ///
+    /// The surface is not configured afterwards. A surface that wasn't suspended is
+    /// suspended first. Fails with [`SurfaceError::Creation`] if no backend could
+    /// create a platform surface for the window.
+    #[cfg(feature = "raw-window-handle")]
+    pub fn surface_resume(
+        &self,
+        id: SurfaceId,
+        display_handle: raw_window_handle::RawDisplayHandle,
+        window_handle: raw_window_handle::RawWindowHandle,
+    ) -> Result<(), SurfaceError> {
+        profiling::scope!("Surface::resume");
+        let mut token = Token::root();
+        let (mut surface_guard, _) = self.surfaces.write(&mut token);
+        let surface = surface_guard
+            .get_mut(id)
+            .map_err(|_| SurfaceError::Invalid)?;
+        self.suspend_surface(surface)?;
+
+        let mut error = None;
+        #[cfg(all(feature = "vulkan", not(target_arch = "wasm32")))]
+        {
+            surface.vulkan = reinit_surface::<hal::api::Vulkan>(
+                &self.instance.vulkan,
+                display_handle,
+                window_handle,
+                &mut error,
+            );
+        }
+        #[cfg(all(feature = "metal", any(target_os = "macos", target_os = "ios")))]
+        {
+            surface.metal = reinit_surface::<hal::api::Metal>(
+                &self.instance.metal,
+                display_handle,
+                window_handle,
+                &mut error,
+            );
+        }
+        #[cfg(all(feature = "dx12", windows))]
+        {
+            surface.dx12 = reinit_surface::<hal::api::Dx12>(
+                &self.instance.dx12,
+                display_handle,
+                window_handle,
+                &mut error,
+            );
+        }
+        #[cfg(all(feature = "dx11", windows))]
+        {
+            surface.dx11 = reinit_surface::<hal::api::Dx11>(
+                &self.instance.dx11,
+                display_handle,
+                window_handle,
+                &mut error,
+            );
+        }
+        #[cfg(feature = "gles")]
+        {
+            surface.gl = reinit_surface::<hal::api::Gles>(
+                &self.instance.gl,
+                display_handle,
+                window_handle,
+                &mut error,
+            );
+        }
+
+        let created_any = [
+            #[cfg(all(feature = "vulkan", not(target_arch = "wasm32")))]
+            surface.vulkan.is_some(),
+            #[cfg(all(feature = "metal", any(target_os = "macos", target_os = "ios")))]
+            surface.metal.is_some(),
+            #[cfg(all(feature = "dx12", windows))]
+            surface.dx12.is_some(),
+            #[cfg(all(feature = "dx11", windows))]
+            surface.dx11.is_some(),
+            #[cfg(feature = "gles")]
+            surface.gl.is_some(),
+        ]
+        .contains(&true);
+        match error {
+            Some(error) if !created_any => Err(SurfaceError::Creation(error)),
+            _ => Ok(()),
+        }
+    }
+
+    fn suspend_surface(&self, surface: &mut Surface) -> Result<(), SurfaceError> {
+        if let Some(ref present) = surface.presentation {
+            if present.acquired_texture.is_some() {
+                return Err(SurfaceError::AlreadyAcquired);
+            }
+        }
+        self.unconfigure_surface(surface);
+        self.instance.release_surface(surface);
+        Ok(())
+    }
+
+    fn unconfigure_surface(&self, surface: &mut Surface) {
         fn unconfigure<G: GlobalIdentityHandlerFactory, A: HalApi>(
             global: &Global<G>,
             surface: &mut HalSurface<A>,
@@ -670,8 +893,6 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 _ => unreachable!(),
             }
         }
-
-        self.instance.destroy_surface(surface);
     }
 
     fn enumerate<A: HalApi>(
@@ -731,6 +952,13 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         self.enumerate(hal::api::Dx11, &self.instance.dx11, &inputs, &mut adapters);
         #[cfg(feature = "gles")]
         self.enumerate(hal::api::Gles, &self.instance.gl, &inputs, &mut adapters);
+        #[cfg(feature = "noop")]
+        self.enumerate(
+            hal::api::Empty,
+            &self.instance.empty,
+            &inputs,
+            &mut adapters,
+        );
 
         adapters
     }
@@ -857,6 +1085,16 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             desc.force_fallback_adapter,
             &mut device_types,
         );
+        // Gathered last, so that any real adapter of the same type wins.
+        #[cfg(feature = "noop")]
+        let (id_empty, adapters_empty) = gather(
+            hal::api::Empty,
+            self.instance.empty.as_ref(),
+            &inputs,
+            compatible_surface,
+            desc.force_fallback_adapter,
+            &mut device_types,
+        );
 
         // need to free the token to be used by `select`
         drop(surface_guard);
@@ -932,6 +1170,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         if let Some(id) = self.select(&mut selected, id_gl, adapters_gl) {
             return Ok(id);
         }
+        #[cfg(feature = "noop")]
+        if let Some(id) = self.select(&mut selected, id_empty, adapters_empty) {
+            return Ok(id);
+        }
         let _ = selected;
 
         log::warn!("Some adapters are present, but enumerating them failed!");
@@ -962,6 +1204,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             Backend::Dx11 => fid.assign(Adapter::new(hal_adapter), &mut token).0,
             #[cfg(feature = "gles")]
             Backend::Gl => fid.assign(Adapter::new(hal_adapter), &mut token).0,
+            #[cfg(feature = "noop")]
+            Backend::Empty => fid.assign(Adapter::new(hal_adapter), &mut token).0,
             _ => unreachable!(),
         }
     }
@@ -1082,10 +1326,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(adapter) => adapter,
                 Err(_) => break RequestDeviceError::InvalidAdapter,
             };
-            let device = match adapter.create_device(adapter_id, desc, trace_path) {
-                Ok(device) => device,
-                Err(e) => break e,
-            };
+            let device =
+                match adapter.create_device(adapter_id, desc, self.instance.flags, trace_path) {
+                    Ok(device) => device,
+                    Err(e) => break e,
+                };
             let id = fid.assign(device, &mut token);
             return (id.0, None);
         };
@@ -1098,6 +1343,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
     ///
     /// - `hal_device` must be created from `adapter_id` or its internal handle.
     /// - `desc` must be a subset of `hal_device` features and limits.
+    /// - `hal_device` must have been opened with `desc.additional_queues`.
     pub unsafe fn create_device_from_hal<A: HalApi>(
         &self,
         adapter_id: AdapterId,
@@ -1118,11 +1364,16 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(adapter) => adapter,
                 Err(_) => break RequestDeviceError::InvalidAdapter,
             };
-            let device =
-                match adapter.create_device_from_hal(adapter_id, hal_device, desc, trace_path) {
-                    Ok(device) => device,
-                    Err(e) => break e,
-                };
+            let device = match adapter.create_device_from_hal(
+                adapter_id,
+                hal_device,
+                desc,
+                self.instance.flags,
+                trace_path,
+            ) {
+                Ok(device) => device,
+                Err(e) => break e,
+            };
             let id = fid.assign(device, &mut token);
             return (id.0, None);
         };
@@ -1155,6 +1406,7 @@ pub fn parse_backends_from_comma_list(string: &str) -> Backends {
             "metal" | "mtl" => Backends::METAL,
             "opengl" | "gles" | "gl" => Backends::GL,
             "webgpu" => Backends::BROWSER_WEBGPU,
+            "noop" | "empty" => Backends::NOOP,
             b => {
                 log::warn!("unknown backend string '{}'", b);
                 continue;
@@ -58,8 +58,10 @@ pub mod hal_api;
 pub mod hub;
 pub mod id;
 pub mod identity;
+mod indirect_validation;
 mod init_tracker;
 pub mod instance;
+mod lock;
 pub mod pipeline;
 pub mod present;
 pub mod registry;
@@ -69,6 +71,7 @@ mod track;
 mod validation;
 
 pub use hal::{api, MAX_BIND_GROUPS, MAX_COLOR_ATTACHMENTS, MAX_VERTEX_BUFFERS};
+pub use naga;
 
 use atomic::{AtomicUsize, Ordering};
 
@@ -87,17 +90,30 @@ pub type Label<'a> = Option<Cow<'a, str>>;
 
 trait LabelHelpers<'a> {
     fn borrow_option(&'a self) -> Option<&'a str>;
+    fn to_hal(&'a self, flags: wgt::InstanceFlags) -> Option<&'a str>;
     fn borrow_or_default(&'a self) -> &'a str;
 }
 impl<'a> LabelHelpers<'a> for Label<'a> {
     fn borrow_option(&'a self) -> Option<&'a str> {
         self.as_ref().map(|cow| cow.as_ref())
     }
+    fn to_hal(&'a self, flags: wgt::InstanceFlags) -> Option<&'a str> {
+        hal_label(self.borrow_option(), flags)
+    }
     fn borrow_or_default(&'a self) -> &'a str {
         self.borrow_option().unwrap_or_default()
     }
 }
 
+/// Returns the label to give to a hal object, which is none when the instance
+/// discards labels.
+pub(crate) fn hal_label(label: Option<&str>, flags: wgt::InstanceFlags) -> Option<&str> {
+    if flags.contains(wgt::InstanceFlags::DISCARD_HAL_LABELS) {
+        return None;
+    }
+    label
+}
+
 /// Reference count object that is 1:1 with each reference.
 ///
 /// All the clones of a given `RefCount` point to the same
@@ -353,6 +369,7 @@ define_backend_caller! { gfx_if_metal, gfx_if_metal_hidden, "metal" if all(featu
 define_backend_caller! { gfx_if_dx12, gfx_if_dx12_hidden, "dx12" if all(feature = "dx12", windows) }
 define_backend_caller! { gfx_if_dx11, gfx_if_dx11_hidden, "dx11" if all(feature = "dx11", windows) }
 define_backend_caller! { gfx_if_gles, gfx_if_gles_hidden, "gles" if feature = "gles" }
+define_backend_caller! { gfx_if_noop, gfx_if_noop_hidden, "noop" if feature = "noop" }
 
 /// Dispatch on an [`Id`]'s backend to a backend-generic method.
 ///
@@ -408,6 +425,7 @@ macro_rules! gfx_select {
             wgt::Backend::Dx12 => $crate::gfx_if_dx12!($global.$method::<$crate::api::Dx12>( $($param),* )),
             wgt::Backend::Dx11 => $crate::gfx_if_dx11!($global.$method::<$crate::api::Dx11>( $($param),* )),
             wgt::Backend::Gl => $crate::gfx_if_gles!($global.$method::<$crate::api::Gles>( $($param),+ )),
+            wgt::Backend::Empty => $crate::gfx_if_noop!($global.$method::<$crate::api::Empty>( $($param),* )),
             other => panic!("Unexpected backend {:?}", other),
         }
     };
@@ -0,0 +1,103 @@
+/*! Lock types used by `wgpu-core`.
+ *
+ * Normally these are just re-exports of the `parking_lot` locks. With the
+ * `single-threaded` feature on a WebAssembly target built without the
+ * `atomics` target feature, there is only ever one thread, so the locks are
+ * replaced with thin wrappers around [`RefCell`] to keep the generated code
+ * as small as possible. Reentrant locking panics instead of deadlocking,
+ * which is the same class of bug either way.
+ *
+ * Atomics don't need the same treatment: without the `atomics` target
+ * feature they already lower to plain loads and stores.
+ */
+
+#[cfg(not(all(
+    feature = "single-threaded",
+    target_arch = "wasm32",
+    not(target_feature = "atomics")
+)))]
+pub use parking_lot::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[cfg(all(
+    feature = "single-threaded",
+    target_arch = "wasm32",
+    not(target_feature = "atomics")
+))]
+pub use single_threaded::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[cfg(all(
+    feature = "single-threaded",
+    target_arch = "wasm32",
+    not(target_feature = "atomics")
+))]
+mod single_threaded {
+    use std::{cell, fmt};
+
+    pub type MutexGuard<'a, T> = cell::RefMut<'a, T>;
+    pub type RwLockReadGuard<'a, T> = cell::Ref<'a, T>;
+    pub type RwLockWriteGuard<'a, T> = cell::RefMut<'a, T>;
+
+    /// A [`RefCell`](cell::RefCell) with the interface of `parking_lot::Mutex`.
+    pub struct Mutex<T>(cell::RefCell<T>);
+
+    // SAFETY: the wasm target this is compiled for has no threads.
+    unsafe impl<T: Send> Sync for Mutex<T> {}
+
+    impl<T> Mutex<T> {
+        pub fn new(value: T) -> Self {
+            Self(cell::RefCell::new(value))
+        }
+
+        pub fn lock(&self) -> MutexGuard<T> {
+            self.0.borrow_mut()
+        }
+
+        pub fn into_inner(self) -> T {
+            self.0.into_inner()
+        }
+
+        pub fn get_mut(&mut self) -> &mut T {
+            self.0.get_mut()
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for Mutex<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.debug_tuple("Mutex").field(&self.0).finish()
+        }
+    }
+
+    /// A [`RefCell`](cell::RefCell) with the interface of `parking_lot::RwLock`.
+    pub struct RwLock<T>(cell::RefCell<T>);
+
+    // SAFETY: the wasm target this is compiled for has no threads.
+    unsafe impl<T: Send> Sync for RwLock<T> {}
+
+    impl<T> RwLock<T> {
+        pub fn new(value: T) -> Self {
+            Self(cell::RefCell::new(value))
+        }
+
+        pub fn read(&self) -> RwLockReadGuard<T> {
+            self.0.borrow()
+        }
+
+        pub fn write(&self) -> RwLockWriteGuard<T> {
+            self.0.borrow_mut()
+        }
+
+        pub fn into_inner(self) -> T {
+            self.0.into_inner()
+        }
+
+        pub fn get_mut(&mut self) -> &mut T {
+            self.0.get_mut()
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for RwLock<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.debug_tuple("RwLock").field(&self.0).finish()
+        }
+    }
+}
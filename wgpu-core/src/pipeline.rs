@@ -1,8 +1,11 @@
 use crate::{
     binding_model::{CreateBindGroupLayoutError, CreatePipelineLayoutError},
     command::ColorAttachmentError,
-    device::{DeviceError, MissingDownlevelFlags, MissingFeatures, RenderPassContext},
-    id::{DeviceId, PipelineLayoutId, ShaderModuleId},
+    device::{
+        snapshot::ShaderSourceSnapshot, DeviceError, MissingDownlevelFlags, MissingFeatures,
+        RenderPassContext,
+    },
+    id::{DeviceId, PipelineCacheId, PipelineLayoutId, ShaderModuleId},
     resource::Resource,
     validation, Label, LifeGuard, Stored,
 };
@@ -13,7 +16,7 @@ use thiserror::Error;
 /// Information about buffer bindings, which
 /// is validated against the shader (and pipeline)
 /// at draw time as opposed to initialization time.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub(crate) struct LateSizedBufferGroup {
     // The order has to match `BindGroup::late_buffer_binding_sizes`.
     pub(crate) shader_sizes: Vec<wgt::BufferAddress>,
@@ -23,6 +26,8 @@ pub(crate) struct LateSizedBufferGroup {
 pub enum ShaderModuleSource<'a> {
     #[cfg(feature = "wgsl")]
     Wgsl(Cow<'a, str>),
+    #[cfg(feature = "glsl")]
+    Glsl(Cow<'a, str>, naga::front::glsl::Options),
     Naga(Cow<'static, naga::Module>),
     /// Dummy variant because `Naga` doesn't have a lifetime and without enough active features it
     /// could be the last one active.
@@ -39,11 +44,26 @@ pub struct ShaderModuleDescriptor<'a> {
     pub shader_bound_checks: wgt::ShaderBoundChecks,
 }
 
+impl ShaderModuleDescriptor<'_> {
+    pub(crate) fn to_static(&self) -> ShaderModuleDescriptor<'static> {
+        ShaderModuleDescriptor {
+            label: self
+                .label
+                .as_ref()
+                .map(|label| Cow::Owned(label.to_string())),
+            shader_bound_checks: self.shader_bound_checks.clone(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ShaderModule<A: hal::Api> {
     pub(crate) raw: A::ShaderModule,
     pub(crate) device_id: Stored<DeviceId>,
     pub(crate) interface: Option<validation::Interface>,
+    /// Kept for [`crate::device::snapshot`].
+    pub(crate) desc: ShaderModuleDescriptor<'static>,
+    pub(crate) source: ShaderSourceSnapshot,
     #[cfg(debug_assertions)]
     pub(crate) label: String,
 }
@@ -77,6 +97,14 @@ impl fmt::Display for ShaderError<naga::front::wgsl::ParseError> {
         write!(f, "\nShader '{label}' parsing {string}")
     }
 }
+#[cfg(feature = "glsl")]
+impl fmt::Display for ShaderError<GlslParseError> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = self.label.as_deref().unwrap_or_default();
+        let string = self.inner.emit_to_string(&self.source);
+        write!(f, "\nShader '{label}' parsing {string}")
+    }
+}
 impl fmt::Display for ShaderError<naga::WithSpan<naga::valid::ValidationError>> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use codespan_reporting::{
@@ -118,6 +146,62 @@ where
     }
 }
 
+/// All the errors reported by the GLSL frontend for a single shader.
+#[cfg(feature = "glsl")]
+#[derive(Debug)]
+pub struct GlslParseError {
+    pub errors: Vec<naga::front::glsl::Error>,
+}
+
+#[cfg(feature = "glsl")]
+impl GlslParseError {
+    pub fn emit_to_string(&self, source: &str) -> String {
+        use codespan_reporting::{
+            diagnostic::{Diagnostic, Label},
+            files::SimpleFile,
+            term,
+        };
+
+        let files = SimpleFile::new("glsl", source);
+        let config = term::Config::default();
+        let mut writer = term::termcolor::NoColor::new(Vec::new());
+
+        for err in self.errors.iter() {
+            let mut diagnostic = Diagnostic::error().with_message(err.kind.to_string());
+            if let Some(range) = err.meta.to_range() {
+                diagnostic = diagnostic.with_labels(vec![Label::primary((), range)]);
+            }
+            term::emit(&mut writer, &config, &files, &diagnostic).expect("cannot write error");
+        }
+
+        String::from_utf8_lossy(&writer.into_inner()).into_owned()
+    }
+
+    /// Returns the location of the first error.
+    pub fn location(&self, source: &str) -> Option<naga::SourceLocation> {
+        self.errors
+            .first()
+            .filter(|err| err.meta.is_defined())
+            .map(|err| err.meta.location(source))
+    }
+}
+
+#[cfg(feature = "glsl")]
+impl fmt::Display for GlslParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, err) in self.errors.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{err}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "glsl")]
+impl Error for GlslParseError {}
+
 //Note: `Clone` would require `WithSpan: Clone`.
 #[derive(Debug, Error)]
 #[non_exhaustive]
@@ -125,6 +209,9 @@ pub enum CreateShaderModuleError {
     #[cfg(feature = "wgsl")]
     #[error(transparent)]
     Parsing(#[from] ShaderError<naga::front::wgsl::ParseError>),
+    #[cfg(feature = "glsl")]
+    #[error(transparent)]
+    ParsingGlsl(#[from] ShaderError<GlslParseError>),
     #[error("Failed to generate the backend-specific code")]
     Generation,
     #[error(transparent)]
@@ -148,6 +235,8 @@ impl CreateShaderModuleError {
         match *self {
             #[cfg(feature = "wgsl")]
             CreateShaderModuleError::Parsing(ref err) => err.inner.location(source),
+            #[cfg(feature = "glsl")]
+            CreateShaderModuleError::ParsingGlsl(ref err) => err.inner.location(source),
             CreateShaderModuleError::Validation(ref err) => err.inner.location(source),
             _ => None,
         }
@@ -164,6 +253,20 @@ pub struct ProgrammableStageDescriptor<'a> {
     /// The name of the entry point in the compiled shader. There must be a function with this name
     /// in the shader.
     pub entry_point: Cow<'a, str>,
+    /// Values for the pipeline-overridable constants of the shader, keyed by
+    /// the constant's name or numeric id.
+    #[cfg_attr(any(feature = "replay", feature = "trace"), serde(default))]
+    pub constants: Cow<'a, hal::PipelineConstants>,
+}
+
+impl ProgrammableStageDescriptor<'_> {
+    fn to_static(&self) -> ProgrammableStageDescriptor<'static> {
+        ProgrammableStageDescriptor {
+            module: self.module,
+            entry_point: Cow::Owned(self.entry_point.to_string()),
+            constants: Cow::Owned(self.constants.as_ref().clone()),
+        }
+    }
 }
 
 /// Number of implicit bind groups derived at pipeline creation.
@@ -192,6 +295,23 @@ pub struct ComputePipelineDescriptor<'a> {
     pub layout: Option<PipelineLayoutId>,
     /// The compiled compute stage and its entry point.
     pub stage: ProgrammableStageDescriptor<'a>,
+    /// The cache to look the pipeline up in and record it into.
+    #[cfg_attr(any(feature = "replay", feature = "trace"), serde(default))]
+    pub cache: Option<PipelineCacheId>,
+}
+
+impl ComputePipelineDescriptor<'_> {
+    pub(crate) fn to_static(&self) -> ComputePipelineDescriptor<'static> {
+        ComputePipelineDescriptor {
+            label: self
+                .label
+                .as_ref()
+                .map(|label| Cow::Owned(label.to_string())),
+            layout: self.layout,
+            stage: self.stage.to_static(),
+            cache: self.cache,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Error)]
@@ -201,6 +321,8 @@ pub enum CreateComputePipelineError {
     Device(#[from] DeviceError),
     #[error("Pipeline layout is invalid")]
     InvalidLayout,
+    #[error("Pipeline cache is invalid")]
+    InvalidCache,
     #[error("Unable to derive an implicit layout")]
     Implicit(#[from] ImplicitLayoutError),
     #[error("Error matching shader requirements against the pipeline")]
@@ -217,6 +339,8 @@ pub struct ComputePipeline<A: hal::Api> {
     pub(crate) layout_id: Stored<PipelineLayoutId>,
     pub(crate) device_id: Stored<DeviceId>,
     pub(crate) late_sized_buffer_groups: ArrayVec<LateSizedBufferGroup, { hal::MAX_BIND_GROUPS }>,
+    /// Kept for [`crate::device::snapshot`], with the layout that was used.
+    pub(crate) desc: ComputePipelineDescriptor<'static>,
     pub(crate) life_guard: LifeGuard,
 }
 
@@ -228,6 +352,54 @@ impl<A: hal::Api> Resource for ComputePipeline<A> {
     }
 }
 
+/// Describes a pipeline cache.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct PipelineCacheDescriptor<'a> {
+    pub label: Label<'a>,
+    /// Data previously returned by [`Global::pipeline_cache_get_data`], to seed the cache
+    /// with. It is ignored if it was produced by a different adapter or driver.
+    ///
+    /// [`Global::pipeline_cache_get_data`]: crate::global::Global::pipeline_cache_get_data
+    ///
+    /// Traces don't record it: replaying with an empty cache gives the same results.
+    #[cfg_attr(any(feature = "replay", feature = "trace"), serde(skip))]
+    pub data: Option<Cow<'a, [u8]>>,
+}
+
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum CreatePipelineCacheError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+}
+
+#[derive(Debug)]
+pub struct PipelineCache<A: hal::Api> {
+    pub(crate) raw: A::PipelineCache,
+    pub(crate) device_id: Stored<DeviceId>,
+    #[cfg(debug_assertions)]
+    pub(crate) label: String,
+}
+
+impl<A: hal::Api> Resource for PipelineCache<A> {
+    const TYPE: &'static str = "PipelineCache";
+
+    fn life_guard(&self) -> &LifeGuard {
+        unreachable!()
+    }
+
+    fn label(&self) -> &str {
+        #[cfg(debug_assertions)]
+        return &self.label;
+        #[cfg(not(debug_assertions))]
+        return "";
+    }
+}
+
 /// Describes how the vertex buffer is interpreted.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "trace", derive(serde::Serialize))]
@@ -288,6 +460,43 @@ pub struct RenderPipelineDescriptor<'a> {
     /// If the pipeline will be used with a multiview render pass, this indicates how many array
     /// layers the attachments will have.
     pub multiview: Option<NonZeroU32>,
+    /// The cache to look the pipeline up in and record it into.
+    #[cfg_attr(any(feature = "replay", feature = "trace"), serde(default))]
+    pub cache: Option<PipelineCacheId>,
+}
+
+impl RenderPipelineDescriptor<'_> {
+    pub(crate) fn to_static(&self) -> RenderPipelineDescriptor<'static> {
+        RenderPipelineDescriptor {
+            label: self
+                .label
+                .as_ref()
+                .map(|label| Cow::Owned(label.to_string())),
+            layout: self.layout,
+            vertex: VertexState {
+                stage: self.vertex.stage.to_static(),
+                buffers: self
+                    .vertex
+                    .buffers
+                    .iter()
+                    .map(|buffer| VertexBufferLayout {
+                        array_stride: buffer.array_stride,
+                        step_mode: buffer.step_mode,
+                        attributes: Cow::Owned(buffer.attributes.to_vec()),
+                    })
+                    .collect(),
+            },
+            primitive: self.primitive,
+            depth_stencil: self.depth_stencil.clone(),
+            multisample: self.multisample,
+            fragment: self.fragment.as_ref().map(|fragment| FragmentState {
+                stage: fragment.stage.to_static(),
+                targets: Cow::Owned(fragment.targets.to_vec()),
+            }),
+            multiview: self.multiview,
+            cache: self.cache,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Error)]
@@ -310,6 +519,10 @@ pub enum ColorStateError {
     InvalidMinMaxBlendFactors(wgt::BlendComponent),
     #[error("Invalid write mask {0:?}")]
     InvalidWriteMask(wgt::ColorWrites),
+    #[error(
+        "Blend factors reading the second blend source are only allowed on the first color target"
+    )]
+    DualSourceBlendingNotFirstTarget,
 }
 
 #[derive(Clone, Debug, Error)]
@@ -317,12 +530,14 @@ pub enum ColorStateError {
 pub enum DepthStencilStateError {
     #[error("Format {0:?} is not renderable")]
     FormatNotRenderable(wgt::TextureFormat),
-    #[error("Format {0:?} does not have a depth aspect, but depth test/write is enabled")]
+    #[error("Format {0:?} does not have a depth aspect, but depth test/write/bounds is enabled")]
     FormatNotDepth(wgt::TextureFormat),
     #[error("Format {0:?} does not have a stencil aspect, but stencil test/write is enabled")]
     FormatNotStencil(wgt::TextureFormat),
     #[error("Format {0:?} can't be multisampled")]
     FormatNotMultisampled(wgt::TextureFormat),
+    #[error("Depth bounds min {0} and/or max {1} are not in [0, 1], or min is greater than max")]
+    InvalidDepthBounds(f32, f32),
 }
 
 #[derive(Clone, Debug, Error)]
@@ -334,6 +549,8 @@ pub enum CreateRenderPipelineError {
     Device(#[from] DeviceError),
     #[error("Pipeline layout is invalid")]
     InvalidLayout,
+    #[error("Pipeline cache is invalid")]
+    InvalidCache,
     #[error("Unable to derive an implicit layout")]
     Implicit(#[from] ImplicitLayoutError),
     #[error("Color state [{0}] is invalid")]
@@ -424,8 +641,11 @@ pub struct RenderPipeline<A: hal::Api> {
     pub(crate) pass_context: RenderPassContext,
     pub(crate) flags: PipelineFlags,
     pub(crate) strip_index_format: Option<wgt::IndexFormat>,
+    pub(crate) depth_bounds: Option<wgt::DepthBoundsState>,
     pub(crate) vertex_steps: Vec<VertexStep>,
     pub(crate) late_sized_buffer_groups: ArrayVec<LateSizedBufferGroup, { hal::MAX_BIND_GROUPS }>,
+    /// Kept for [`crate::device::snapshot`], with the layout that was used.
+    pub(crate) desc: RenderPipelineDescriptor<'static>,
     pub(crate) life_guard: LifeGuard,
 }
 
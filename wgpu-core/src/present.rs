@@ -15,7 +15,7 @@ use std::borrow::Borrow;
 use crate::device::trace::Action;
 use crate::{
     conv,
-    device::{DeviceError, MissingDownlevelFlags},
+    device::{DeviceError, MissingDownlevelFlags, MissingFeatures},
     global::Global,
     hal_api::HalApi,
     hub::Token,
@@ -30,7 +30,6 @@ use thiserror::Error;
 use wgt::SurfaceStatus as Status;
 
 const FRAME_TIMEOUT_MS: u32 = 1000;
-pub const DESIRED_NUM_FRAMES: u32 = 3;
 
 #[derive(Debug)]
 pub(crate) struct Presentation {
@@ -60,6 +59,8 @@ pub enum SurfaceError {
     AlreadyAcquired,
     #[error("Acquired frame is still referenced")]
     StillReferenced,
+    #[error("Failed to create a platform surface for the window")]
+    Creation(#[source] hal::InstanceError),
 }
 
 #[derive(Clone, Debug, Error)]
@@ -73,6 +74,8 @@ pub enum ConfigureSurfaceError {
     InvalidViewFormat(wgt::TextureFormat, wgt::TextureFormat),
     #[error(transparent)]
     MissingDownlevelFlags(#[from] MissingDownlevelFlags),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
     #[error("`SurfaceOutput` must be dropped before a new `Surface` is made")]
     PreviousOutputExists,
     #[error("Both `Surface` width and height must be non-zero. Wait to recreate the `Surface` until the window has non-zero area.")]
@@ -94,6 +97,17 @@ pub enum ConfigureSurfaceError {
         requested: wgt::CompositeAlphaMode,
         available: Vec<wgt::CompositeAlphaMode>,
     },
+    #[error("Requested color space {requested:?} is not supported with format {format:?}, which supports: {available:?}")]
+    UnsupportedColorSpace {
+        requested: wgt::SurfaceColorSpace,
+        format: wgt::TextureFormat,
+        available: Vec<wgt::SurfaceColorSpace>,
+    },
+    #[error("Color space {color_space:?} can't be used with format {format:?}")]
+    IncompatibleColorSpace {
+        color_space: wgt::SurfaceColorSpace,
+        format: wgt::TextureFormat,
+    },
     #[error("Requested usage is not supported")]
     UnsupportedUsage,
 }
@@ -156,6 +170,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     dimension: wgt::TextureViewDimension::D2,
                     usage: hal::TextureUses::COLOR_TARGET,
                     range: wgt::ImageSubresourceRange::default(),
+                    swizzle: wgt::TextureSwizzle::IDENTITY,
                 };
                 let mut clear_views = smallvec::SmallVec::new();
                 clear_views.push(
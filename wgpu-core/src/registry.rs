@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 
-use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use crate::lock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use wgt::Backend;
 
 use crate::{
@@ -51,7 +51,6 @@ pub(crate) struct FutureId<'a, I: id::TypedId, T> {
 }
 
 impl<I: id::TypedId + Copy, T> FutureId<'_, I, T> {
-    #[cfg(feature = "trace")]
     pub fn id(&self) -> I {
         self.id
     }
@@ -3,7 +3,7 @@ use crate::{
     global::Global,
     hal_api::HalApi,
     hub::Token,
-    id::{AdapterId, DeviceId, SurfaceId, TextureId, Valid},
+    id::{AdapterId, BufferId, DeviceId, QueueId, SurfaceId, TextureId, TextureViewId, Valid},
     identity::GlobalIdentityHandlerFactory,
     init_tracker::{BufferInitTracker, TextureInitTracker},
     track::TextureSelector,
@@ -67,12 +67,16 @@ pub(crate) enum BufferMapState<A: hal::Api> {
         stage_buffer: A::Buffer,
         needs_flush: bool,
     },
-    /// Waiting for GPU to be done before mapping
-    Waiting(BufferPendingMapping),
+    /// Waiting for GPU to be done before mapping the given disjoint ranges
+    Waiting(Vec<BufferPendingMapping>),
     /// Mapped
     Active {
-        ptr: NonNull<u8>,
-        range: hal::MemoryRange,
+        /// Mapping of the whole buffer.
+        mapping: hal::BufferMapping,
+        /// Disjoint ranges that are mapped.
+        ranges: Vec<hal::MemoryRange>,
+        /// Further disjoint ranges that are waiting to be mapped.
+        pending: Vec<BufferPendingMapping>,
         host: HostMap,
     },
     /// Not mapped
@@ -298,8 +302,12 @@ pub enum CreateBufferError {
     UsageMismatch(wgt::BufferUsages),
     #[error("Buffer size {requested} is greater than the maximum buffer size ({maximum})")]
     MaxBufferSize { requested: u64, maximum: u64 },
+    #[error("Protected buffers can't be mapped")]
+    MappedProtected,
     #[error(transparent)]
     MissingDownlevelFlags(#[from] MissingDownlevelFlags),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
 }
 
 impl<A: hal::Api> Resource for Buffer<A> {
@@ -432,6 +440,40 @@ impl<A: hal::Api> Texture<A> {
             }
         }
     }
+
+    /// Estimates the memory used by the texture from its descriptor, ignoring any
+    /// padding or compression done by the backend.
+    pub(crate) fn estimated_size(&self) -> wgt::BufferAddress {
+        use wgt::TextureAspect as Ta;
+
+        let format = self.desc.format;
+        let aspects: &[Ta] = if format.is_multi_planar_format() {
+            &[Ta::Plane0, Ta::Plane1]
+        } else if format.is_combined_depth_stencil_format() {
+            &[Ta::DepthOnly, Ta::StencilOnly]
+        } else {
+            &[Ta::All]
+        };
+        let (block_width, block_height) = format.block_dimensions();
+
+        let mut size = 0;
+        for mip_level in 0..self.desc.mip_level_count {
+            let extent = self
+                .desc
+                .size
+                .mip_level_size(mip_level, self.desc.dimension)
+                .physical_size(format);
+            for &aspect in aspects {
+                let (sub_width, sub_height) = format.subsampling_factors(aspect);
+                let blocks = (extent.width / block_width / sub_width) as u64
+                    * (extent.height / block_height / sub_height) as u64
+                    * extent.depth_or_array_layers as u64;
+                // `Depth24Plus` has no defined size, but is usually stored in 4 bytes.
+                size += blocks * format.block_size(Some(aspect)).unwrap_or(4) as u64;
+            }
+        }
+        size * self.desc.sample_count as u64
+    }
 }
 
 impl<G: GlobalIdentityHandlerFactory> Global<G> {
@@ -454,6 +496,44 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         hal_texture_callback(hal_texture);
     }
 
+    /// # Safety
+    ///
+    /// - The raw buffer handle must not be manually destroyed
+    pub unsafe fn buffer_as_hal<A: HalApi, F: FnOnce(Option<&A::Buffer>) -> R, R>(
+        &self,
+        id: BufferId,
+        hal_buffer_callback: F,
+    ) -> R {
+        profiling::scope!("Buffer::as_hal");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let (guard, _) = hub.buffers.read(&mut token);
+        let buffer = guard.try_get(id).ok().flatten();
+        let hal_buffer = buffer.and_then(|buffer| buffer.raw.as_ref());
+
+        hal_buffer_callback(hal_buffer)
+    }
+
+    /// # Safety
+    ///
+    /// - The raw texture view handle must not be manually destroyed
+    pub unsafe fn texture_view_as_hal<A: HalApi, F: FnOnce(Option<&A::TextureView>) -> R, R>(
+        &self,
+        id: TextureViewId,
+        hal_texture_view_callback: F,
+    ) -> R {
+        profiling::scope!("TextureView::as_hal");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let (guard, _) = hub.texture_views.read(&mut token);
+        let texture_view = guard.try_get(id).ok().flatten();
+        let hal_texture_view = texture_view.map(|view| &view.raw);
+
+        hal_texture_view_callback(hal_texture_view)
+    }
+
     /// # Safety
     ///
     /// - The raw adapter handle must not be manually destroyed
@@ -493,6 +573,49 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         hal_device_callback(hal_device)
     }
 
+    /// Run `hal_fence_callback` on the device's submission fence.
+    ///
+    /// The fence is signaled with the index of each submission, as returned
+    /// by [`Global::queue_submit`].
+    ///
+    /// # Safety
+    ///
+    /// - The raw fence handle must not be manually destroyed or signaled
+    pub unsafe fn device_fence_as_hal<A: HalApi, F: FnOnce(Option<&A::Fence>) -> R, R>(
+        &self,
+        id: DeviceId,
+        hal_fence_callback: F,
+    ) -> R {
+        profiling::scope!("Device::fence_as_hal");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let (guard, _) = hub.devices.read(&mut token);
+        let device = guard.try_get(id).ok().flatten();
+        let hal_fence = device.map(|device| &device.fence);
+
+        hal_fence_callback(hal_fence)
+    }
+
+    /// # Safety
+    ///
+    /// - The raw queue handle must not be manually destroyed
+    pub unsafe fn queue_as_hal_mut<A: HalApi, F: FnOnce(Option<&mut A::Queue>) -> R, R>(
+        &self,
+        id: QueueId,
+        hal_queue_callback: F,
+    ) -> R {
+        profiling::scope!("Queue::as_hal_mut");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let (mut guard, _) = hub.devices.write(&mut token);
+        let device = guard.get_mut(id).ok();
+        let hal_queue = device.map(|device| &mut device.queue);
+
+        hal_queue_callback(hal_queue)
+    }
+
     /// # Safety
     /// - The raw surface handle must not be manually destroyed
     pub unsafe fn surface_as_hal_mut<A: HalApi, F: FnOnce(Option<&mut A::Surface>) -> R, R>(
@@ -556,12 +679,18 @@ pub enum CreateTextureError {
     Device(#[from] DeviceError),
     #[error("Invalid usage flags {0:?}")]
     InvalidUsage(wgt::TextureUsages),
+    #[error("Transient textures can only be used as render attachments, not {0:?}")]
+    InvalidTransientUsage(wgt::TextureUsages),
     #[error(transparent)]
     InvalidDimension(#[from] TextureDimensionError),
     #[error("Depth texture ({1:?}) can't be created as {0:?}")]
     InvalidDepthDimension(wgt::TextureDimension, wgt::TextureFormat),
     #[error("Compressed texture ({1:?}) can't be created as {0:?}")]
     InvalidCompressedDimension(wgt::TextureDimension, wgt::TextureFormat),
+    #[error("Multi-planar texture ({1:?}) can't be created as {0:?}")]
+    InvalidMultiPlanarDimension(wgt::TextureDimension, wgt::TextureFormat),
+    #[error("Multi-planar texture ({1:?}) must have a single array layer, got {0}")]
+    InvalidMultiPlanarArrayLayerCount(u32, wgt::TextureFormat),
     #[error(
         "Texture descriptor mip level count {requested} is invalid, maximum allowed is {maximum}"
     )]
@@ -585,6 +714,8 @@ pub enum CreateTextureError {
     MultisampledNotRenderAttachment,
     #[error("Texture format {0:?} can't be used due to missing features")]
     MissingFeatures(wgt::TextureFormat, #[source] MissingFeatures),
+    #[error("Texture usages {0:?} can't be used due to missing features")]
+    MissingUsageFeatures(wgt::TextureUsages, #[source] MissingFeatures),
     #[error(transparent)]
     MissingDownlevelFlags(#[from] MissingDownlevelFlags),
 }
@@ -625,6 +756,13 @@ pub struct TextureViewDescriptor<'a> {
     pub dimension: Option<wgt::TextureViewDimension>,
     /// Range within the texture that is accessible via this view.
     pub range: wgt::ImageSubresourceRange,
+    /// Remapping of the components read through the view, or `None` for the
+    /// identity.
+    ///
+    /// Anything other than the identity requires
+    /// [`wgt::Features::TEXTURE_COMPONENT_SWIZZLE`], and makes the view
+    /// usable only as a sampled texture.
+    pub swizzle: Option<wgt::TextureSwizzle>,
 }
 
 #[derive(Debug)]
@@ -632,6 +770,7 @@ pub(crate) struct HalTextureViewDescriptor {
     pub format: wgt::TextureFormat,
     pub dimension: wgt::TextureViewDimension,
     pub range: wgt::ImageSubresourceRange,
+    pub swizzle: wgt::TextureSwizzle,
 }
 
 impl HalTextureViewDescriptor {
@@ -654,6 +793,8 @@ pub enum TextureViewNotRenderableReason {
         "The aspects of this texture view are a subset of the aspects in the original texture. Aspects: {0:?}"
     )]
     Aspects(hal::FormatAspects),
+    #[error("This texture view swizzles its components. Swizzle: {0:?}")]
+    Swizzle(wgt::TextureSwizzle),
 }
 
 #[derive(Debug)]
@@ -713,11 +854,18 @@ pub enum CreateTextureViewError {
         texture_format: wgt::TextureFormat,
         requested_aspect: wgt::TextureAspect,
     },
+    #[error("Views of the multi-planar texture format {texture_format:?} must select a single plane, not {requested_aspect:?}")]
+    InvalidMultiPlaneAspect {
+        texture_format: wgt::TextureFormat,
+        requested_aspect: wgt::TextureAspect,
+    },
     #[error("Unable to view texture {texture:?} as {view:?}")]
     FormatReinterpretation {
         texture: wgt::TextureFormat,
         view: wgt::TextureFormat,
     },
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
 }
 
 #[derive(Clone, Debug, Error)]
@@ -856,6 +1004,60 @@ impl<A: hal::Api> Resource for QuerySet<A> {
     }
 }
 
+/// Describes an [`AccelerationStructure`] created from a `wgpu-hal` one.
+#[derive(Clone, Debug, Default)]
+pub struct AccelerationStructureDescriptor<'a> {
+    /// Debug label of the acceleration structure.
+    pub label: Label<'a>,
+}
+
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum CreateAccelerationStructureError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+}
+
+/// A ray tracing acceleration structure.
+///
+/// Acceleration structures are created and built with `wgpu-hal`, wgpu-core
+/// only keeps them alive while they are bound.
+#[derive(Debug)]
+pub struct AccelerationStructure<A: hal::Api> {
+    pub(crate) raw: A::AccelerationStructure,
+    pub(crate) device_id: Stored<DeviceId>,
+    pub(crate) life_guard: LifeGuard,
+}
+
+impl<A: hal::Api> Resource for AccelerationStructure<A> {
+    const TYPE: &'static str = "AccelerationStructure";
+
+    fn life_guard(&self) -> &LifeGuard {
+        &self.life_guard
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum ResizeBufferError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error("Buffer is invalid")]
+    Invalid,
+    #[error("Buffer is destroyed")]
+    Destroyed,
+    #[error("Buffer must be unmapped to be resized")]
+    Mapped,
+    #[error("Buffer must have the `COPY_SRC` usage to be resized")]
+    MissingCopySrcUsage,
+    #[error("Buffer is still used by a bind group, a render bundle, a reusable command buffer or a command buffer that isn't submitted")]
+    InUse,
+    #[error(transparent)]
+    Create(#[from] CreateBufferError),
+}
+
 #[derive(Clone, Debug, Error)]
 #[non_exhaustive]
 pub enum DestroyError {
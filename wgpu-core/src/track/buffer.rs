@@ -39,6 +39,10 @@ impl ResourceUses for BufferUses {
     fn any_exclusive(self) -> bool {
         self.intersects(Self::EXCLUSIVE)
     }
+
+    fn is_copy_within(self) -> bool {
+        self == Self::COPY_SRC | Self::COPY_DST
+    }
 }
 
 /// Stores all the buffers that a bind group stores.
@@ -70,6 +74,14 @@ impl<A: HalApi> BufferBindGroupState<A> {
         self.buffers.iter().map(|&(id, _, _)| id)
     }
 
+    /// Returns a list of all buffers bound for writing. May contain duplicates.
+    pub fn written(&self) -> impl Iterator<Item = Valid<BufferId>> + '_ {
+        self.buffers
+            .iter()
+            .filter(|&&(_, _, state)| state.contains(BufferUses::STORAGE_READ_WRITE))
+            .map(|&(id, _, _)| id)
+    }
+
     /// Adds the given resource with the given state.
     pub fn add_single<'a>(
         &mut self,
@@ -192,6 +192,9 @@ pub(crate) trait ResourceUses:
     fn all_ordered(self) -> bool;
     /// Returns true if any of the uses are exclusive.
     fn any_exclusive(self) -> bool;
+    /// Returns true if the uses are both sides of a copy within the resource,
+    /// which is the only allowed combination of an exclusive use with another.
+    fn is_copy_within(self) -> bool;
 }
 
 /// Returns true if the given states violates the usage scope rule
@@ -199,7 +202,7 @@ pub(crate) trait ResourceUses:
 fn invalid_resource_state<T: ResourceUses>(state: T) -> bool {
     // Is power of two also means "is one bit set". We check for this as if
     // we're in any exclusive state, we must only be in a single state.
-    state.any_exclusive() && !conv::is_power_of_two_u16(state.bits())
+    state.any_exclusive() && !conv::is_power_of_two_u16(state.bits()) && !state.is_copy_within()
 }
 
 /// Returns true if the transition from one state to another does not require
@@ -320,6 +323,8 @@ pub(crate) struct BindGroupStates<A: HalApi> {
     pub textures: TextureBindGroupState<A>,
     pub views: StatelessBindGroupSate<resource::TextureView<A>, id::TextureViewId>,
     pub samplers: StatelessBindGroupSate<resource::Sampler<A>, id::SamplerId>,
+    pub acceleration_structures:
+        StatelessBindGroupSate<resource::AccelerationStructure<A>, id::AccelerationStructureId>,
 }
 
 impl<A: HalApi> BindGroupStates<A> {
@@ -329,6 +334,7 @@ impl<A: HalApi> BindGroupStates<A> {
             textures: TextureBindGroupState::new(),
             views: StatelessBindGroupSate::new(),
             samplers: StatelessBindGroupSate::new(),
+            acceleration_structures: StatelessBindGroupSate::new(),
         }
     }
 
@@ -341,6 +347,7 @@ impl<A: HalApi> BindGroupStates<A> {
         self.textures.optimize();
         self.views.optimize();
         self.samplers.optimize();
+        self.acceleration_structures.optimize();
     }
 }
 
@@ -482,6 +489,8 @@ pub(crate) struct Tracker<A: HalApi> {
     pub textures: TextureTracker<A>,
     pub views: StatelessTracker<A, resource::TextureView<A>, id::TextureViewId>,
     pub samplers: StatelessTracker<A, resource::Sampler<A>, id::SamplerId>,
+    pub acceleration_structures:
+        StatelessTracker<A, resource::AccelerationStructure<A>, id::AccelerationStructureId>,
     pub bind_groups: StatelessTracker<A, binding_model::BindGroup<A>, id::BindGroupId>,
     pub compute_pipelines: StatelessTracker<A, pipeline::ComputePipeline<A>, id::ComputePipelineId>,
     pub render_pipelines: StatelessTracker<A, pipeline::RenderPipeline<A>, id::RenderPipelineId>,
@@ -496,6 +505,7 @@ impl<A: HalApi> Tracker<A> {
             textures: TextureTracker::new(),
             views: StatelessTracker::new(),
             samplers: StatelessTracker::new(),
+            acceleration_structures: StatelessTracker::new(),
             bind_groups: StatelessTracker::new(),
             compute_pipelines: StatelessTracker::new(),
             render_pipelines: StatelessTracker::new(),
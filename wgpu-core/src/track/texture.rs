@@ -63,6 +63,10 @@ impl ResourceUses for TextureUses {
     fn any_exclusive(self) -> bool {
         self.intersects(Self::EXCLUSIVE)
     }
+
+    fn is_copy_within(self) -> bool {
+        false
+    }
 }
 
 /// Represents the complex state of textures where every subresource is potentially
@@ -182,6 +186,14 @@ impl<A: HalApi> TextureBindGroupState<A> {
         self.textures.iter().map(|&(id, _, _, _)| id)
     }
 
+    /// Returns a list of all textures bound for writing. May contain duplicates.
+    pub fn written(&self) -> impl Iterator<Item = Valid<TextureId>> + '_ {
+        self.textures
+            .iter()
+            .filter(|&&(_, _, _, state)| state.contains(TextureUses::STORAGE_READ_WRITE))
+            .map(|&(id, _, _, _)| id)
+    }
+
     /// Adds the given resource with the given state.
     pub fn add_single<'a>(
         &mut self,
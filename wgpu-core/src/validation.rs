@@ -16,6 +16,7 @@ enum ResourceType {
     Sampler {
         comparison: bool,
     },
+    AccelerationStructure,
 }
 
 #[derive(Debug)]
@@ -25,6 +26,8 @@ struct Resource {
     bind: naga::ResourceBinding,
     ty: ResourceType,
     class: naga::AddressSpace,
+    /// Size of the binding array, if the resource is one.
+    array_size: Option<naga::ArraySize>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -100,11 +103,35 @@ enum Varying {
     BuiltIn(naga::BuiltIn),
 }
 
-#[allow(unused)]
+/// A pipeline-overridable constant of a shader module.
 #[derive(Debug)]
-struct SpecializationConstant {
-    id: u32,
-    ty: NumericType,
+struct Override {
+    name: Option<String>,
+    id: Option<u32>,
+    kind: naga::ScalarKind,
+    width: naga::Bytes,
+}
+
+impl Override {
+    fn matches(&self, key: &str) -> bool {
+        self.name.as_deref() == Some(key)
+            || self.id.map_or(false, |id| key.parse::<u32>() == Ok(id))
+    }
+
+    fn can_represent(&self, value: f64) -> bool {
+        match self.kind {
+            naga::ScalarKind::Bool => !value.is_nan(),
+            naga::ScalarKind::Sint => {
+                value.fract() == 0.0 && value >= i32::MIN as f64 && value <= i32::MAX as f64
+            }
+            naga::ScalarKind::Uint => {
+                value.fract() == 0.0 && value >= 0.0 && value <= u32::MAX as f64
+            }
+            naga::ScalarKind::Float => {
+                value.is_finite() && (self.width == 8 || value.abs() <= f32::MAX as f64)
+            }
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -112,10 +139,10 @@ struct EntryPoint {
     inputs: Vec<Varying>,
     outputs: Vec<Varying>,
     resources: Vec<naga::Handle<Resource>>,
-    #[allow(unused)]
-    spec_constants: Vec<SpecializationConstant>,
     sampling_pairs: FastHashSet<(naga::Handle<Resource>, naga::Handle<Resource>)>,
     workgroup_size: [u32; 3],
+    /// Bytes of workgroup storage used, or `None` if that doesn't fit in a `u32`.
+    workgroup_storage_size: Option<u32>,
 }
 
 #[derive(Debug)]
@@ -123,6 +150,7 @@ pub struct Interface {
     limits: wgt::Limits,
     resources: naga::Arena<Resource>,
     entry_points: FastHashMap<(naga::ShaderStage, String), EntryPoint>,
+    overrides: Vec<Override>,
 }
 
 #[derive(Clone, Debug, Error)]
@@ -202,6 +230,12 @@ pub enum BindingError {
         "Storage texture with access {0:?} doesn't have a matching supported `StorageTextureAccess`"
     )]
     UnsupportedTextureStorageAccess(naga::StorageAccess),
+    #[error("Binding is an array in the shader, but not in the pipeline layout")]
+    NotAnArray,
+    #[error("Binding array has {shader} elements in the shader, but the pipeline layout only declares {layout}")]
+    ArrayTooSmall { shader: u32, layout: u32 },
+    #[error("Binding array has no fixed size in the shader, so its count can't be derived for the pipeline layout")]
+    UnsizedArray,
 }
 
 #[derive(Clone, Debug, Error)]
@@ -241,6 +275,15 @@ pub enum StageError {
         limit: [u32; 3],
         total: u32,
     },
+    #[error(
+        "Shader entry point uses {used} bytes of workgroup storage, above the limit of {limit}"
+    )]
+    WorkgroupStorageTooLarge { used: u32, limit: u32 },
+    #[error(
+        "Shader entry point uses more than {} bytes of workgroup storage, above the limit of {limit}",
+        u32::MAX
+    )]
+    WorkgroupStorageOverflow { limit: u32 },
     #[error("Shader uses {used} inter-stage components above the limit of {limit}")]
     TooManyVaryings { used: u32, limit: u32 },
     #[error("Unable to find entry point '{0}'")]
@@ -263,6 +306,14 @@ pub enum StageError {
     },
     #[error("Location[{location}] is provided by the previous stage output but is not consumed as input by this stage.")]
     InputNotConsumed { location: wgt::ShaderLocation },
+    #[error("Pipeline constant {0:?} doesn't match any overridable constant of the shader")]
+    UnknownPipelineConstant(String),
+    #[error("Value {value} of pipeline constant {key:?} is not representable as {kind:?}")]
+    InvalidPipelineConstantValue {
+        key: String,
+        value: f64,
+        kind: naga::ScalarKind,
+    },
 }
 
 fn map_storage_format_to_naga(format: wgt::TextureFormat) -> Option<naga::StorageFormat> {
@@ -373,6 +424,17 @@ fn map_storage_format_from_naga(format: naga::StorageFormat) -> wgt::TextureForm
 
 impl Resource {
     fn check_binding_use(&self, entry: &BindGroupLayoutEntry) -> Result<(), BindingError> {
+        match (self.array_size, entry.count) {
+            (Some(_), None) => return Err(BindingError::NotAnArray),
+            (Some(naga::ArraySize::Constant(size)), Some(count)) if size > count => {
+                return Err(BindingError::ArrayTooSmall {
+                    shader: size.get(),
+                    layout: count.get(),
+                })
+            }
+            _ => (),
+        }
+
         match self.ty {
             ResourceType::Buffer { size } => {
                 let min_size = match entry.ty {
@@ -416,6 +478,10 @@ impl Resource {
                 }
                 _ => return Err(BindingError::WrongType),
             },
+            ResourceType::AccelerationStructure => match entry.ty {
+                BindingType::AccelerationStructure => (),
+                _ => return Err(BindingError::WrongType),
+            },
             ResourceType::Texture {
                 dim,
                 arrayed,
@@ -510,6 +576,14 @@ impl Resource {
         Ok(())
     }
 
+    fn derive_binding_count(&self) -> Result<Option<std::num::NonZeroU32>, BindingError> {
+        match self.array_size {
+            None => Ok(None),
+            Some(naga::ArraySize::Constant(size)) => Ok(Some(size)),
+            Some(naga::ArraySize::Dynamic) => Err(BindingError::UnsizedArray),
+        }
+    }
+
     fn derive_binding_type(&self) -> Result<BindingType, BindingError> {
         Ok(match self.ty {
             ResourceType::Buffer { size } => BindingType::Buffer {
@@ -528,6 +602,7 @@ impl Resource {
             } else {
                 wgt::SamplerBindingType::Filtering
             }),
+            ResourceType::AccelerationStructure => BindingType::AccelerationStructure,
             ResourceType::Texture {
                 dim,
                 arrayed,
@@ -680,6 +755,7 @@ impl NumericType {
             | Tf::Depth24PlusStencil8 => {
                 panic!("Unexpected depth format")
             }
+            Tf::NV12 => panic!("Unexpected nv12 format"),
             Tf::Rgb9e5Ufloat => (NumericDimension::Vector(Vs::Tri), Sk::Float),
             Tf::Bc1RgbaUnorm
             | Tf::Bc1RgbaUnormSrgb
@@ -839,9 +915,11 @@ impl Interface {
             };
             let naga_ty = &module.types[var.ty].inner;
 
-            let inner_ty = match *naga_ty {
-                naga::TypeInner::BindingArray { base, .. } => &module.types[base].inner,
-                ref ty => ty,
+            let (inner_ty, array_size) = match *naga_ty {
+                naga::TypeInner::BindingArray { base, size } => {
+                    (&module.types[base].inner, Some(size))
+                }
+                ref ty => (ty, None),
             };
 
             let ty = match *inner_ty {
@@ -855,6 +933,7 @@ impl Interface {
                     class,
                 },
                 naga::TypeInner::Sampler { comparison } => ResourceType::Sampler { comparison },
+                naga::TypeInner::AccelerationStructure => ResourceType::AccelerationStructure,
                 naga::TypeInner::Array { stride, .. } => ResourceType::Buffer {
                     size: wgt::BufferSize::new(stride as u64).unwrap(),
                 },
@@ -868,6 +947,7 @@ impl Interface {
                     bind,
                     ty,
                     class: var.space,
+                    array_size,
                 },
                 Default::default(),
             );
@@ -891,12 +971,20 @@ impl Interface {
                 );
             }
 
+            let mut workgroup_storage_size = Some(0u32);
             for (var_handle, var) in module.global_variables.iter() {
                 let usage = info[var_handle];
                 if !usage.is_empty() && var.binding.is_some() {
                     ep.resources.push(resource_mapping[&var_handle]);
                 }
+                if !usage.is_empty() && var.space == naga::AddressSpace::WorkGroup {
+                    // Each variable occupies a multiple of 16 bytes, as in the WebGPU spec.
+                    let size = module.types[var.ty].inner.size(module.to_ctx());
+                    workgroup_storage_size = workgroup_storage_size
+                        .and_then(|total| total.checked_add(size.checked_add(15)? & !15));
+                }
             }
+            ep.workgroup_storage_size = workgroup_storage_size;
 
             for key in info.sampling_set.iter() {
                 ep.sampling_pairs
@@ -908,13 +996,58 @@ impl Interface {
             entry_points.insert((entry_point.stage, entry_point.name.clone()), ep);
         }
 
+        let overrides = module
+            .constants
+            .iter()
+            .filter_map(|(_, constant)| {
+                let id = match constant.r#override {
+                    naga::Override::None => return None,
+                    naga::Override::ByName => None,
+                    naga::Override::ByNameOrId(id) => Some(id),
+                };
+                match module.types[constant.ty].inner {
+                    naga::TypeInner::Scalar { kind, width } => Some(Override {
+                        name: constant.name.clone(),
+                        id,
+                        kind,
+                        width,
+                    }),
+                    _ => None,
+                }
+            })
+            .collect();
+
         Self {
             limits,
             resources,
             entry_points,
+            overrides,
         }
     }
 
+    /// Check that the given pipeline constants all refer to overridable
+    /// constants of the module, and that their values fit the constant types.
+    pub fn check_pipeline_constants(
+        &self,
+        constants: &hal::PipelineConstants,
+    ) -> Result<(), StageError> {
+        for (key, &value) in constants.iter() {
+            let over = self
+                .overrides
+                .iter()
+                .find(|over| over.matches(key))
+                .ok_or_else(|| StageError::UnknownPipelineConstant(key.clone()))?;
+            if !over.can_represent(value) {
+                return Err(StageError::InvalidPipelineConstantValue {
+                    key: key.clone(),
+                    value,
+                    kind: over.kind,
+                });
+            }
+        }
+        Ok(())
+    }
+
     pub fn check_stage(
         &self,
         given_layouts: Option<&[&BindEntryMap]>,
@@ -973,8 +1106,9 @@ impl Interface {
                     .ok_or(BindingError::Missing)
                     .and_then(|set| {
                         let ty = res.derive_binding_type()?;
+                        let count = res.derive_binding_count()?;
                         match set.entry(res.bind.binding) {
-                            Entry::Occupied(e) if e.get().ty != ty => {
+                            Entry::Occupied(e) if e.get().ty != ty || e.get().count != count => {
                                 return Err(BindingError::InconsistentlyDerivedType)
                             }
                             Entry::Occupied(e) => {
@@ -985,7 +1119,7 @@ impl Interface {
                                     binding: res.bind.binding,
                                     ty,
                                     visibility: stage_bit,
-                                    count: None,
+                                    count,
                                 });
                             }
                         }
@@ -1057,6 +1191,13 @@ impl Interface {
                     total: self.limits.max_compute_invocations_per_workgroup,
                 });
             }
+
+            let limit = self.limits.max_compute_workgroup_storage_size;
+            match entry_point.workgroup_storage_size {
+                Some(used) if used <= limit => {}
+                Some(used) => return Err(StageError::WorkgroupStorageTooLarge { used, limit }),
+                None => return Err(StageError::WorkgroupStorageOverflow { limit }),
+            }
         }
 
         let mut inter_stage_components = 0;
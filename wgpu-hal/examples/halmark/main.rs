@@ -117,9 +117,11 @@ impl<A: hal::Api> Example<A> {
             unsafe { adapter.surface_capabilities(&surface) }.ok_or(hal::InstanceError)?;
         log::info!("Surface caps: {:#?}", surface_caps);
 
-        let hal::OpenDevice { device, mut queue } = unsafe {
+        let hal::OpenDevice {
+            device, mut queue, ..
+        } = unsafe {
             adapter
-                .open(wgt::Features::empty(), &wgt::Limits::default())
+                .open(wgt::Features::empty(), &wgt::Limits::default(), &[])
                 .unwrap()
         };
 
@@ -129,8 +131,10 @@ impl<A: hal::Api> Example<A> {
                 *surface_caps.swap_chain_sizes.start(),
                 *surface_caps.swap_chain_sizes.end(),
             ),
+            maximum_frame_latency: 2,
             present_mode: wgt::PresentMode::Fifo,
             composite_alpha_mode: wgt::CompositeAlphaMode::Opaque,
+            color_space: wgt::SurfaceColorSpace::Srgb,
             format: wgt::TextureFormat::Bgra8UnormSrgb,
             extent: wgt::Extent3d {
                 width: window_size.0,
@@ -139,6 +143,7 @@ impl<A: hal::Api> Example<A> {
             },
             usage: hal::TextureUses::COLOR_TARGET,
             view_formats: vec![],
+            protected: false,
         };
         unsafe {
             surface.configure(&device, &surface_config).unwrap();
@@ -165,6 +170,7 @@ impl<A: hal::Api> Example<A> {
         let shader_desc = hal::ShaderModuleDescriptor {
             label: None,
             runtime_checks: false,
+            zero_initialize_workgroup_memory: true,
         };
         let shader = unsafe {
             device
@@ -243,11 +249,13 @@ impl<A: hal::Api> Example<A> {
             vertex_stage: hal::ProgrammableStage {
                 module: &shader,
                 entry_point: "vs_main",
+                constants: &Default::default(),
             },
             vertex_buffers: &[],
             fragment_stage: Some(hal::ProgrammableStage {
                 module: &shader,
                 entry_point: "fs_main",
+                constants: &Default::default(),
             }),
             primitive: wgt::PrimitiveState {
                 topology: wgt::PrimitiveTopology::TriangleStrip,
@@ -261,6 +269,7 @@ impl<A: hal::Api> Example<A> {
                 write_mask: wgt::ColorWrites::default(),
             })],
             multiview: None,
+            cache: None,
         };
         let pipeline = unsafe { device.create_render_pipeline(&pipeline_desc).unwrap() };
 
@@ -306,9 +315,10 @@ impl<A: hal::Api> Example<A> {
         let cmd_encoder_desc = hal::CommandEncoderDescriptor {
             label: None,
             queue: &queue,
+            protected: false,
         };
         let mut cmd_encoder = unsafe { device.create_command_encoder(&cmd_encoder_desc).unwrap() };
-        unsafe { cmd_encoder.begin_encoding(Some("init")).unwrap() };
+        unsafe { cmd_encoder.begin_encoding(Some("init"), false).unwrap() };
         {
             let buffer_barrier = hal::BufferBarrier {
                 buffer: &staging_buffer,
@@ -414,6 +424,7 @@ impl<A: hal::Api> Example<A> {
             dimension: wgt::TextureViewDimension::D2,
             usage: hal::TextureUses::RESOURCE,
             range: wgt::ImageSubresourceRange::default(),
+            swizzle: wgt::TextureSwizzle::IDENTITY,
         };
         let texture_view = unsafe { device.create_texture_view(&texture, &view_desc).unwrap() };
 
@@ -433,6 +444,7 @@ impl<A: hal::Api> Example<A> {
                 buffers: &[global_buffer_binding],
                 samplers: &[&sampler],
                 textures: &[texture_binding],
+                acceleration_structures: &[],
                 entries: &[
                     hal::BindGroupEntry {
                         binding: 0,
@@ -466,6 +478,7 @@ impl<A: hal::Api> Example<A> {
                 buffers: &[local_buffer_binding],
                 samplers: &[],
                 textures: &[],
+                acceleration_structures: &[],
                 entries: &[hal::BindGroupEntry {
                     binding: 0,
                     resource_index: 0,
@@ -480,7 +493,7 @@ impl<A: hal::Api> Example<A> {
             let mut fence = device.create_fence().unwrap();
             let init_cmd = cmd_encoder.end_encoding().unwrap();
             queue
-                .submit(&[&init_cmd], Some((&mut fence, init_fence_value)))
+                .submit(&[&init_cmd], Some((&mut fence, init_fence_value)), None)
                 .unwrap();
             device.wait(&fence, init_fence_value, !0).unwrap();
             device.destroy_buffer(staging_buffer);
@@ -532,7 +545,7 @@ impl<A: hal::Api> Example<A> {
             {
                 let ctx = &mut self.contexts[self.context_index];
                 self.queue
-                    .submit(&[], Some((&mut ctx.fence, ctx.fence_value)))
+                    .submit(&[], Some((&mut ctx.fence, ctx.fence_value)), None)
                     .unwrap();
             }
 
@@ -640,7 +653,7 @@ impl<A: hal::Api> Example<A> {
             usage: hal::TextureUses::UNINITIALIZED..hal::TextureUses::COLOR_TARGET,
         };
         unsafe {
-            ctx.encoder.begin_encoding(Some("frame")).unwrap();
+            ctx.encoder.begin_encoding(Some("frame"), false).unwrap();
             ctx.encoder.transition_textures(iter::once(target_barrier0));
         }
 
@@ -650,6 +663,7 @@ impl<A: hal::Api> Example<A> {
             dimension: wgt::TextureViewDimension::D2,
             usage: hal::TextureUses::COLOR_TARGET,
             range: wgt::ImageSubresourceRange::default(),
+            swizzle: wgt::TextureSwizzle::IDENTITY,
         };
         let surface_tex_view = unsafe {
             self.device
@@ -669,6 +683,7 @@ impl<A: hal::Api> Example<A> {
                     view: &surface_tex_view,
                     usage: hal::TextureUses::COLOR_TARGET,
                 },
+                depth_slice: None,
                 resolve_target: None,
                 ops: hal::AttachmentOps::STORE,
                 clear_value: wgt::Color {
@@ -719,7 +734,7 @@ impl<A: hal::Api> Example<A> {
             } else {
                 None
             };
-            self.queue.submit(&[&cmd_buf], fence_param).unwrap();
+            self.queue.submit(&[&cmd_buf], fence_param, None).unwrap();
             self.queue.present(&mut self.surface, surface_tex).unwrap();
             ctx.used_cmd_bufs.push(cmd_buf);
             ctx.used_views.push(surface_tex_view);
@@ -732,6 +747,7 @@ impl<A: hal::Api> Example<A> {
                 let hal_desc = hal::CommandEncoderDescriptor {
                     label: None,
                     queue: &self.queue,
+                    protected: false,
                 };
                 self.contexts.push(unsafe {
                     ExecutionContext {
@@ -124,9 +124,11 @@ fn fill_screen(exposed: &hal::ExposedAdapter<hal::api::Gles>, width: u32, height
     use hal::{Adapter as _, CommandEncoder as _, Device as _, Queue as _};
 
     let mut od = unsafe {
-        exposed
-            .adapter
-            .open(wgt::Features::empty(), &wgt::Limits::downlevel_defaults())
+        exposed.adapter.open(
+            wgt::Features::empty(),
+            &wgt::Limits::downlevel_defaults(),
+            &[],
+        )
     }
     .unwrap();
 
@@ -142,6 +144,7 @@ fn fill_screen(exposed: &hal::ExposedAdapter<hal::api::Gles>, width: u32, height
                     dimension: wgt::TextureViewDimension::D2,
                     usage: hal::TextureUses::COLOR_TARGET,
                     range: wgt::ImageSubresourceRange::default(),
+                    swizzle: wgt::TextureSwizzle::IDENTITY,
                 },
             )
             .unwrap()
@@ -153,6 +156,7 @@ fn fill_screen(exposed: &hal::ExposedAdapter<hal::api::Gles>, width: u32, height
             .create_command_encoder(&hal::CommandEncoderDescriptor {
                 label: None,
                 queue: &od.queue,
+                protected: false,
             })
             .unwrap()
     };
@@ -169,6 +173,7 @@ fn fill_screen(exposed: &hal::ExposedAdapter<hal::api::Gles>, width: u32, height
                 view: &view,
                 usage: hal::TextureUses::COLOR_TARGET,
             },
+            depth_slice: None,
             resolve_target: None,
             ops: hal::AttachmentOps::STORE,
             clear_value: wgt::Color::BLUE,
@@ -179,10 +184,10 @@ fn fill_screen(exposed: &hal::ExposedAdapter<hal::api::Gles>, width: u32, height
         occlusion_query_set: None,
     };
     unsafe {
-        encoder.begin_encoding(None).unwrap();
+        encoder.begin_encoding(None, false).unwrap();
         encoder.begin_render_pass(&rp_desc);
         encoder.end_render_pass();
         let cmd_buf = encoder.end_encoding().unwrap();
-        od.queue.submit(&[&cmd_buf], None).unwrap();
+        od.queue.submit(&[&cmd_buf], None, None).unwrap();
     }
 }
@@ -53,6 +53,7 @@ pub fn map_texture_format_failable(format: wgt::TextureFormat) -> Option<dxgifor
         Tf::Depth24PlusStencil8 => DXGI_FORMAT_D24_UNORM_S8_UINT,
         Tf::Depth32Float => DXGI_FORMAT_D32_FLOAT,
         Tf::Depth32FloatStencil8 => DXGI_FORMAT_D32_FLOAT_S8X24_UINT,
+        Tf::NV12 => DXGI_FORMAT_NV12,
         Tf::Bc1RgbaUnorm => DXGI_FORMAT_BC1_UNORM,
         Tf::Bc1RgbaUnormSrgb => DXGI_FORMAT_BC1_UNORM_SRGB,
         Tf::Bc2RgbaUnorm => DXGI_FORMAT_BC2_UNORM,
@@ -130,6 +131,13 @@ pub fn map_texture_format_for_srv_uav(
             crate::FormatAspects::STENCIL,
         ) => dxgiformat::DXGI_FORMAT_X24_TYPELESS_G8_UINT,
 
+        (wgt::TextureFormat::NV12, crate::FormatAspects::PLANE_0) => {
+            dxgiformat::DXGI_FORMAT_R8_UNORM
+        }
+        (wgt::TextureFormat::NV12, crate::FormatAspects::PLANE_1) => {
+            dxgiformat::DXGI_FORMAT_R8G8_UNORM
+        }
+
         (format, crate::FormatAspects::COLOR) => map_texture_format(format),
 
         _ => return None,
@@ -157,6 +165,13 @@ pub fn map_texture_format_for_copy(
             crate::FormatAspects::STENCIL,
         ) => dxgiformat::DXGI_FORMAT_R8_UINT,
 
+        (wgt::TextureFormat::NV12, crate::FormatAspects::PLANE_0) => {
+            dxgiformat::DXGI_FORMAT_R8_UNORM
+        }
+        (wgt::TextureFormat::NV12, crate::FormatAspects::PLANE_1) => {
+            dxgiformat::DXGI_FORMAT_R8G8_UNORM
+        }
+
         (format, crate::FormatAspects::COLOR) => map_texture_format(format),
 
         _ => return None,
@@ -253,6 +268,28 @@ pub fn map_vertex_format(format: wgt::VertexFormat) -> dxgiformat::DXGI_FORMAT {
     }
 }
 
-pub fn map_acomposite_alpha_mode(_mode: wgt::CompositeAlphaMode) -> d3d12::AlphaMode {
-    d3d12::AlphaMode::Ignore
+pub fn map_acomposite_alpha_mode(mode: wgt::CompositeAlphaMode) -> d3d12::AlphaMode {
+    match mode {
+        wgt::CompositeAlphaMode::Opaque => d3d12::AlphaMode::Ignore,
+        wgt::CompositeAlphaMode::PreMultiplied => d3d12::AlphaMode::Premultiplied,
+        wgt::CompositeAlphaMode::PostMultiplied => d3d12::AlphaMode::Straight,
+        wgt::CompositeAlphaMode::Inherit => d3d12::AlphaMode::Unspecified,
+        wgt::CompositeAlphaMode::Auto => unreachable!(),
+    }
+}
+
+/// Returns `None` if the default color space of the swapchain should be kept.
+pub fn map_color_space(
+    color_space: wgt::SurfaceColorSpace,
+) -> Option<winapi::shared::dxgitype::DXGI_COLOR_SPACE_TYPE> {
+    use winapi::shared::dxgitype;
+    match color_space {
+        wgt::SurfaceColorSpace::Srgb => None,
+        wgt::SurfaceColorSpace::ExtendedSrgbLinear => {
+            Some(dxgitype::DXGI_COLOR_SPACE_RGB_FULL_G10_NONE_P709)
+        }
+        wgt::SurfaceColorSpace::Hdr10 => Some(dxgitype::DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020),
+        // DXGI has no Display P3 color space, so it isn't exposed.
+        wgt::SurfaceColorSpace::DisplayP3 => unreachable!(),
+    }
 }
@@ -46,6 +46,23 @@ pub mod db {
 /// offset at some intermediate point, internally, as i32.
 pub const MAX_I32_BINDING_SIZE: u32 = 1 << 31;
 
+/// Pairs every format with each of the color spaces that it is compatible with,
+/// for backends that can't query the supported combinations.
+pub fn compatible_color_spaces(
+    formats: &[wgt::TextureFormat],
+    color_spaces: &[wgt::SurfaceColorSpace],
+) -> Vec<(wgt::TextureFormat, wgt::SurfaceColorSpace)> {
+    formats
+        .iter()
+        .flat_map(|&format| {
+            color_spaces
+                .iter()
+                .filter(move |color_space| color_space.is_compatible_with(format))
+                .map(move |&color_space| (format, color_space))
+        })
+        .collect()
+}
+
 pub fn map_naga_stage(stage: naga::ShaderStage) -> wgt::ShaderStages {
     match stage {
         naga::ShaderStage::Vertex => wgt::ShaderStages::VERTEX,
@@ -116,6 +133,57 @@ impl crate::TextureCopy {
     }
 }
 
+/// Specialize the pipeline-overridable constants of `module` with the values
+/// from `constants`.
+///
+/// Constants are looked up by name first, then by numeric id. The values are
+/// expected to have been validated against the constant types by the caller.
+/// The module is only cloned if there is something to specialize.
+#[allow(dead_code)]
+pub(crate) fn apply_pipeline_constants<'a>(
+    module: &'a naga::Module,
+    constants: &crate::PipelineConstants,
+) -> std::borrow::Cow<'a, naga::Module> {
+    use std::borrow::Cow;
+
+    if constants.is_empty() {
+        return Cow::Borrowed(module);
+    }
+
+    let mut module = module.clone();
+    for (_, constant) in module.constants.iter_mut() {
+        let id = match constant.r#override {
+            naga::Override::None => continue,
+            naga::Override::ByName => None,
+            naga::Override::ByNameOrId(id) => Some(id),
+        };
+        let value = constant
+            .name
+            .as_ref()
+            .and_then(|name| constants.get(name))
+            .or_else(|| id.and_then(|id| constants.get(&id.to_string())));
+        let value = match value {
+            Some(&value) => value,
+            None => continue,
+        };
+        let literal = match module.types[constant.ty].inner {
+            naga::TypeInner::Scalar { kind, width } => match (kind, width) {
+                (naga::ScalarKind::Bool, _) => naga::Literal::Bool(value != 0.0),
+                (naga::ScalarKind::Sint, _) => naga::Literal::I32(value as i32),
+                (naga::ScalarKind::Uint, _) => naga::Literal::U32(value as u32),
+                (naga::ScalarKind::Float, 8) => naga::Literal::F64(value),
+                (naga::ScalarKind::Float, _) => naga::Literal::F32(value as f32),
+            },
+            _ => continue,
+        };
+        // Replace the initializer in place, so that the expression arena keeps
+        // matching the module's analysis info.
+        module.const_expressions[constant.init] = naga::Expression::Literal(literal);
+        constant.r#override = naga::Override::None;
+    }
+    Cow::Owned(module)
+}
+
 /// Construct a `CStr` from a byte slice, up to the first zero byte.
 ///
 /// Return a `CStr` extending from the start of `bytes` up to and
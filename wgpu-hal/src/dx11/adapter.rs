@@ -7,8 +7,12 @@ impl crate::Adapter<super::Api> for super::Adapter {
         &self,
         features: wgt::Features,
         limits: &wgt::Limits,
+        additional_queues: &[wgt::QueueKind],
     ) -> Result<crate::OpenDevice<super::Api>, crate::DeviceError> {
-        todo!()
+        // Buffers, fences and submission work, but textures, views, pipelines, bind
+        // groups and command recording don't yet, so a device would be unusable.
+        log::error!("Opening a device is not supported on DX11 yet");
+        Err(crate::DeviceError::ResourceCreationFailed)
     }
 
     unsafe fn texture_format_capabilities(
@@ -99,7 +103,8 @@ impl super::Adapter {
         let mut downlevel = wgt::DownlevelFlags::BASE_VERTEX
             | wgt::DownlevelFlags::READ_ONLY_DEPTH_STENCIL
             | wgt::DownlevelFlags::UNRESTRICTED_INDEX_BUFFER
-            | wgt::DownlevelFlags::UNRESTRICTED_EXTERNAL_TEXTURE_COPIES;
+            | wgt::DownlevelFlags::UNRESTRICTED_EXTERNAL_TEXTURE_COPIES
+            | wgt::DownlevelFlags::ROBUST_INDIRECT_EXECUTION;
 
         // Features from queries
         downlevel.set(
@@ -146,6 +151,7 @@ impl super::Adapter {
             downlevel |= wgt::DownlevelFlags::INDIRECT_EXECUTION;
             downlevel |= wgt::DownlevelFlags::WEBGPU_TEXTURE_FORMAT_SUPPORT;
             features |= wgt::Features::TEXTURE_COMPRESSION_BC;
+            features |= wgt::Features::TEXTURE_COMPRESSION_S3TC;
         }
 
         if feature_level >= FL11_1 {
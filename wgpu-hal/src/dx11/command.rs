@@ -1,5 +1,9 @@
 impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
-    unsafe fn begin_encoding(&mut self, label: crate::Label) -> Result<(), crate::DeviceError> {
+    unsafe fn begin_encoding(
+        &mut self,
+        label: crate::Label,
+        _reusable: bool,
+    ) -> Result<(), crate::DeviceError> {
         todo!()
     }
 
@@ -92,6 +96,16 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         todo!()
     }
 
+    unsafe fn set_bind_group_dynamic_offsets(
+        &mut self,
+        layout: &super::PipelineLayout,
+        index: u32,
+        group: &super::BindGroup,
+        dynamic_offsets: &[wgt::DynamicOffset],
+    ) {
+        todo!()
+    }
+
     unsafe fn set_push_constants(
         &mut self,
         layout: &super::PipelineLayout,
@@ -177,6 +191,19 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         todo!()
     }
 
+    unsafe fn set_viewport_indexed(
+        &mut self,
+        index: u32,
+        rect: &crate::Rect<f32>,
+        depth_range: std::ops::Range<f32>,
+    ) {
+        todo!()
+    }
+
+    unsafe fn set_scissor_rect_indexed(&mut self, index: u32, rect: &crate::Rect<u32>) {
+        todo!()
+    }
+
     unsafe fn set_stencil_reference(&mut self, value: u32) {
         todo!()
     }
@@ -185,6 +212,14 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         todo!()
     }
 
+    unsafe fn set_shading_rate(&mut self, rate: wgt::ShadingRate) {
+        todo!()
+    }
+
+    unsafe fn set_depth_bounds(&mut self, bounds: std::ops::Range<f32>) {
+        todo!()
+    }
+
     unsafe fn draw(
         &mut self,
         start_vertex: u32,
@@ -268,4 +303,18 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
     unsafe fn dispatch_indirect(&mut self, buffer: &super::Buffer, offset: wgt::BufferAddress) {
         todo!()
     }
+
+    unsafe fn build_acceleration_structures(
+        &mut self,
+        descriptors: &[crate::BuildAccelerationStructureDescriptor<super::Api>],
+    ) {
+        if let Some(desc) = descriptors.first() {
+            match *desc.destination_acceleration_structure {}
+        }
+    }
+    unsafe fn place_acceleration_structure_barrier(
+        &mut self,
+        _barrier: crate::AccelerationStructureBarrier,
+    ) {
+    }
 }
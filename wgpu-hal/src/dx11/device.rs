@@ -1,49 +1,151 @@
-use std::{ffi::c_void, mem};
+use std::{ffi::c_void, mem, ptr, thread, time::Duration};
 
-use winapi::um::d3d11;
+use parking_lot::Mutex;
+use winapi::{
+    shared::{minwindef::FALSE, winerror},
+    um::d3d11,
+};
 
 use crate::auxil::dxgi::result::HResult;
 
 impl crate::Device<super::Api> for super::Device {
     unsafe fn exit(self, queue: super::Queue) {
-        todo!()
+        // Let the driver release everything that is still in flight.
+        let context = self.context.lock();
+        unsafe {
+            context.ClearState();
+            context.Flush();
+        }
     }
 
+    unsafe fn destroy_queue(&self, _queue: super::Queue) {}
+
     unsafe fn create_buffer(
         &self,
         desc: &crate::BufferDescriptor,
     ) -> Result<super::Buffer, crate::DeviceError> {
-        todo!()
-    }
+        let mappable = desc
+            .usage
+            .intersects(crate::BufferUses::MAP_READ | crate::BufferUses::MAP_WRITE);
+
+        let mut size = desc.size;
+        let mut bind_flags = 0;
+        let mut misc_flags = 0;
+        let (usage, cpu_access_flags) = if mappable {
+            // Staging buffers can only be copied from and to, which is all
+            // wgpu-core allows mappable buffers to be used for.
+            let mut cpu_access_flags = 0;
+            if desc.usage.contains(crate::BufferUses::MAP_READ) {
+                cpu_access_flags |= d3d11::D3D11_CPU_ACCESS_READ;
+            }
+            if desc.usage.contains(crate::BufferUses::MAP_WRITE) {
+                cpu_access_flags |= d3d11::D3D11_CPU_ACCESS_WRITE;
+            }
+            (d3d11::D3D11_USAGE_STAGING, cpu_access_flags)
+        } else {
+            if desc.usage.contains(crate::BufferUses::VERTEX) {
+                bind_flags |= d3d11::D3D11_BIND_VERTEX_BUFFER;
+            }
+            if desc.usage.contains(crate::BufferUses::INDEX) {
+                bind_flags |= d3d11::D3D11_BIND_INDEX_BUFFER;
+            }
+            if desc
+                .usage
+                .intersects(crate::BufferUses::STORAGE_READ | crate::BufferUses::STORAGE_READ_WRITE)
+            {
+                // naga's HLSL backend accesses storage buffers as `ByteAddressBuffer`s.
+                bind_flags |= d3d11::D3D11_BIND_SHADER_RESOURCE;
+                misc_flags |= d3d11::D3D11_RESOURCE_MISC_BUFFER_ALLOW_RAW_VIEWS;
+                size = wgt::math::align_to(size, 4);
+            }
+            if desc.usage.contains(crate::BufferUses::STORAGE_READ_WRITE) {
+                bind_flags |= d3d11::D3D11_BIND_UNORDERED_ACCESS;
+            }
+            if desc.usage.contains(crate::BufferUses::UNIFORM) {
+                // Constant buffers can't have any other bind flag, those are
+                // going to need a shadow copy when bound.
+                if bind_flags == 0 {
+                    bind_flags = d3d11::D3D11_BIND_CONSTANT_BUFFER;
+                }
+                size = wgt::math::align_to(size, 16);
+            }
+            if desc.usage.contains(crate::BufferUses::INDIRECT) {
+                misc_flags |= d3d11::D3D11_RESOURCE_MISC_DRAWINDIRECT_ARGS;
+            }
+            (d3d11::D3D11_USAGE_DEFAULT, 0)
+        };
+
+        let raw_desc = d3d11::D3D11_BUFFER_DESC {
+            ByteWidth: size
+                .try_into()
+                .map_err(|_| crate::DeviceError::OutOfMemory)?,
+            Usage: usage,
+            BindFlags: bind_flags,
+            CPUAccessFlags: cpu_access_flags,
+            MiscFlags: misc_flags,
+            StructureByteStride: 0,
+        };
+
+        let mut raw = d3d12::ComPtr::<d3d11::ID3D11Buffer>::null();
+        unsafe {
+            self.raw
+                .CreateBuffer(&raw_desc, ptr::null(), raw.mut_self())
+        }
+        .into_device_result("Buffer creation")?;
 
-    unsafe fn destroy_buffer(&self, buffer: super::Buffer) {
-        todo!()
+        if let Some(label) = desc.label {
+            unsafe { set_name(raw.as_mut_ptr() as *mut _, label) };
+        }
+
+        Ok(super::Buffer { raw, mappable })
     }
 
+    unsafe fn destroy_buffer(&self, buffer: super::Buffer) {}
+
     unsafe fn map_buffer(
         &self,
         buffer: &super::Buffer,
         range: crate::MemoryRange,
     ) -> Result<crate::BufferMapping, crate::DeviceError> {
-        todo!()
+        debug_assert!(buffer.mappable);
+        let mut mapped = unsafe { mem::zeroed::<d3d11::D3D11_MAPPED_SUBRESOURCE>() };
+        unsafe {
+            self.context.lock().Map(
+                buffer.raw.as_mut_ptr() as *mut _,
+                0,
+                d3d11::D3D11_MAP_READ_WRITE,
+                0,
+                &mut mapped,
+            )
+        }
+        .into_device_result("Map buffer")?;
+
+        let ptr = unsafe { (mapped.pData as *mut u8).offset(range.start as isize) };
+        Ok(crate::BufferMapping {
+            ptr: ptr::NonNull::new(ptr).ok_or(crate::DeviceError::Lost)?,
+            is_coherent: true,
+        })
     }
 
     unsafe fn unmap_buffer(&self, buffer: &super::Buffer) -> Result<(), crate::DeviceError> {
-        todo!()
+        unsafe {
+            self.context
+                .lock()
+                .Unmap(buffer.raw.as_mut_ptr() as *mut _, 0)
+        };
+        Ok(())
     }
 
     unsafe fn flush_mapped_ranges<I>(&self, buffer: &super::Buffer, ranges: I)
     where
         I: Iterator<Item = crate::MemoryRange>,
     {
-        todo!()
     }
 
     unsafe fn invalidate_mapped_ranges<I>(&self, buffer: &super::Buffer, ranges: I)
     where
         I: Iterator<Item = crate::MemoryRange>,
     {
-        todo!()
     }
 
     unsafe fn create_texture(
@@ -157,6 +259,18 @@ impl crate::Device<super::Api> for super::Device {
     unsafe fn destroy_compute_pipeline(&self, pipeline: super::ComputePipeline) {
         todo!()
     }
+    unsafe fn create_pipeline_cache(
+        &self,
+        desc: &crate::PipelineCacheDescriptor,
+    ) -> Result<super::PipelineCache, crate::DeviceError> {
+        todo!()
+    }
+    unsafe fn destroy_pipeline_cache(&self, cache: super::PipelineCache) {
+        todo!()
+    }
+    unsafe fn pipeline_cache_get_data(&self, cache: &super::PipelineCache) -> Option<Vec<u8>> {
+        todo!()
+    }
 
     unsafe fn create_query_set(
         &self,
@@ -170,18 +284,23 @@ impl crate::Device<super::Api> for super::Device {
     }
 
     unsafe fn create_fence(&self) -> Result<super::Fence, crate::DeviceError> {
-        todo!()
+        Ok(super::Fence {
+            state: Mutex::new(super::FenceState {
+                completed: 0,
+                pending: Vec::new(),
+            }),
+        })
     }
 
-    unsafe fn destroy_fence(&self, fence: super::Fence) {
-        todo!()
-    }
+    unsafe fn destroy_fence(&self, fence: super::Fence) {}
 
     unsafe fn get_fence_value(
         &self,
         fence: &super::Fence,
     ) -> Result<crate::FenceValue, crate::DeviceError> {
-        todo!()
+        let mut state = fence.state.lock();
+        let context = self.context.lock();
+        state.poll(&context)
     }
 
     unsafe fn wait(
@@ -190,15 +309,53 @@ impl crate::Device<super::Api> for super::Device {
         value: crate::FenceValue,
         timeout_ms: u32,
     ) -> Result<bool, crate::DeviceError> {
-        todo!()
+        let start = std::time::Instant::now();
+        let timeout = Duration::from_millis(timeout_ms as u64);
+        loop {
+            if unsafe { self.get_fence_value(fence) }? >= value {
+                return Ok(true);
+            }
+            if start.elapsed() >= timeout {
+                return Ok(false);
+            }
+            // Event queries can't be waited on, only polled.
+            thread::sleep(Duration::from_micros(100));
+        }
     }
 
     unsafe fn start_capture(&self) -> bool {
-        todo!()
+        false
     }
 
-    unsafe fn stop_capture(&self) {
-        todo!()
+    unsafe fn stop_capture(&self) {}
+
+    unsafe fn allocator_report(&self) -> Option<crate::AllocatorReport> {
+        None
+    }
+
+    unsafe fn create_acceleration_structure(
+        &self,
+        _desc: &crate::AccelerationStructureDescriptor,
+    ) -> Result<super::AccelerationStructure, crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn get_acceleration_structure_build_sizes(
+        &self,
+        _desc: &crate::GetAccelerationStructureBuildSizesDescriptor<super::Api>,
+    ) -> crate::AccelerationStructureBuildSizes {
+        Default::default()
+    }
+    unsafe fn get_acceleration_structure_device_address(
+        &self,
+        acceleration_structure: &super::AccelerationStructure,
+    ) -> wgt::BufferAddress {
+        match *acceleration_structure {}
+    }
+    unsafe fn destroy_acceleration_structure(
+        &self,
+        acceleration_structure: super::AccelerationStructure,
+    ) {
+        match acceleration_structure {}
     }
 }
 
@@ -207,8 +364,40 @@ impl crate::Queue<super::Api> for super::Queue {
         &mut self,
         command_buffers: &[&super::CommandBuffer],
         signal_fence: Option<(&mut super::Fence, crate::FenceValue)>,
+        label: crate::Label,
     ) -> Result<(), crate::DeviceError> {
-        todo!()
+        let context = self.context.lock();
+        for cmd_buf in command_buffers {
+            unsafe { context.ExecuteCommandList(cmd_buf.raw.as_mut_ptr(), FALSE) };
+        }
+
+        if let Some((fence, value)) = signal_fence {
+            let mut device = d3d12::ComPtr::<d3d11::ID3D11Device>::null();
+            unsafe { context.GetDevice(device.mut_self()) };
+
+            let query_desc = d3d11::D3D11_QUERY_DESC {
+                Query: d3d11::D3D11_QUERY_EVENT,
+                MiscFlags: 0,
+            };
+            let mut query = d3d12::ComPtr::<d3d11::ID3D11Query>::null();
+            unsafe { device.CreateQuery(&query_desc, query.mut_self()) }
+                .into_device_result("Fence query creation")?;
+            unsafe { context.End(query.as_mut_ptr() as *mut _) };
+            fence.state.get_mut().pending.push((value, query));
+        }
+
+        // Unlike other APIs, nothing reaches the GPU until the context is flushed.
+        unsafe { context.Flush() };
+        Ok(())
+    }
+
+    unsafe fn wait_for_fence(
+        &mut self,
+        _fence: &super::Fence,
+        _value: crate::FenceValue,
+    ) -> Result<(), crate::DeviceError> {
+        // The immediate context executes the submissions in order.
+        Ok(())
     }
 
     unsafe fn present(
@@ -224,6 +413,47 @@ impl crate::Queue<super::Api> for super::Queue {
     }
 }
 
+impl super::FenceState {
+    fn poll(
+        &mut self,
+        context: &d3d11::ID3D11DeviceContext,
+    ) -> Result<crate::FenceValue, crate::DeviceError> {
+        // Queries complete in submission order.
+        let mut completed = 0;
+        for (value, query) in self.pending.iter() {
+            let hr = unsafe {
+                context.GetData(
+                    query.as_mut_ptr() as *mut _,
+                    ptr::null_mut(),
+                    0,
+                    d3d11::D3D11_ASYNC_GETDATA_DONOTFLUSH,
+                )
+            };
+            match hr {
+                winerror::S_OK => {
+                    self.completed = self.completed.max(*value);
+                    completed += 1;
+                }
+                winerror::S_FALSE => break,
+                _ => return Err(crate::DeviceError::Lost),
+            }
+        }
+        self.pending.drain(..completed);
+        Ok(self.completed)
+    }
+}
+
+/// Attaches a debug name to the object, shown by the debug layer and graphics debuggers.
+unsafe fn set_name(object: *mut d3d11::ID3D11DeviceChild, name: &str) {
+    unsafe {
+        (*object).SetPrivateData(
+            &winapi::um::d3dcommon::WKPDID_D3DDebugObjectName,
+            name.len() as u32,
+            name.as_ptr() as *const c_void,
+        )
+    };
+}
+
 impl super::D3D11Device {
     #[allow(trivial_casts)] // come on
     pub unsafe fn check_feature_support<T>(&self, feature: d3d11::D3D11_FEATURE) -> T {
@@ -1,6 +1,9 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
 
+use std::sync::Arc;
+
+use parking_lot::Mutex;
 use winapi::um::{d3d11, d3d11_1, d3d11_2};
 
 mod adapter;
@@ -36,6 +39,9 @@ impl crate::Api for Api {
     type ShaderModule = ShaderModule;
     type RenderPipeline = RenderPipeline;
     type ComputePipeline = ComputePipeline;
+    type PipelineCache = PipelineCache;
+
+    type AccelerationStructure = AccelerationStructure;
 }
 
 pub struct Instance {
@@ -65,21 +71,48 @@ d3d12::weak_com_inheritance_chain! {
     }
 }
 
-pub struct Device {}
+/// The immediate context, shared between the device and the queue.
+///
+/// D3D11 contexts aren't thread safe, so every use goes through the lock.
+type ImmediateContext = Arc<Mutex<d3d12::ComPtr<d3d11::ID3D11DeviceContext>>>;
+
+pub struct Device {
+    raw: D3D11Device,
+    context: ImmediateContext,
+}
 
 unsafe impl Send for Device {}
 unsafe impl Sync for Device {}
 
-pub struct Queue {}
+pub struct Queue {
+    context: ImmediateContext,
+}
+
+unsafe impl Send for Queue {}
+unsafe impl Sync for Queue {}
 
 #[derive(Debug)]
 pub struct CommandEncoder {}
 
 #[derive(Debug)]
-pub struct CommandBuffer {}
+pub struct CommandBuffer {
+    /// Recorded on a deferred context, replayed on the immediate one.
+    raw: d3d12::ComPtr<d3d11::ID3D11CommandList>,
+}
+
+unsafe impl Send for CommandBuffer {}
+unsafe impl Sync for CommandBuffer {}
 
 #[derive(Debug)]
-pub struct Buffer {}
+pub struct Buffer {
+    raw: d3d12::ComPtr<d3d11::ID3D11Buffer>,
+    /// Staging buffers are the only ones the CPU can map.
+    mappable: bool,
+}
+
+unsafe impl Send for Buffer {}
+unsafe impl Sync for Buffer {}
+
 #[derive(Debug)]
 pub struct Texture {}
 #[derive(Debug)]
@@ -97,8 +130,21 @@ pub struct TextureView {}
 pub struct Sampler {}
 #[derive(Debug)]
 pub struct QuerySet {}
+/// D3D11 has no fences before 11.3, so each signal is an event query
+/// which is polled for completion.
 #[derive(Debug)]
-pub struct Fence {}
+pub struct Fence {
+    state: Mutex<FenceState>,
+}
+
+#[derive(Debug)]
+struct FenceState {
+    completed: crate::FenceValue,
+    pending: Vec<(crate::FenceValue, d3d12::ComPtr<d3d11::ID3D11Query>)>,
+}
+
+unsafe impl Send for Fence {}
+unsafe impl Sync for Fence {}
 #[derive(Debug)]
 
 pub struct BindGroupLayout {}
@@ -110,6 +156,11 @@ pub struct PipelineLayout {}
 pub struct ShaderModule {}
 pub struct RenderPipeline {}
 pub struct ComputePipeline {}
+#[derive(Debug)]
+pub struct PipelineCache {}
+/// Acceleration structures are not supported, so none can be created.
+#[derive(Debug)]
+pub enum AccelerationStructure {}
 
 impl crate::Surface<Api> for Surface {
     unsafe fn configure(
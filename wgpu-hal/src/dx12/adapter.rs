@@ -4,8 +4,8 @@ use crate::{
 };
 use std::{mem, ptr, sync::Arc, thread};
 use winapi::{
-    shared::{dxgi, dxgi1_2, minwindef::DWORD, windef, winerror},
-    um::{d3d12 as d3d12_ty, d3d12sdklayers, winuser},
+    shared::{dxgi, dxgi1_2, minwindef::DWORD, winerror},
+    um::{d3d12 as d3d12_ty, d3d12sdklayers},
 };
 
 impl Drop for super::Adapter {
@@ -198,6 +198,8 @@ impl super::Adapter {
             casting_fully_typed_format_supported,
             // See https://github.com/gfx-rs/wgpu/issues/3552
             suballocation_supported: !info.name.contains("Iris(R) Xe"),
+            shader_model_6: shader_model_support.HighestShaderModel
+                >= d3d12_ty::D3D_SHADER_MODEL_6_0,
         };
 
         // Theoretically vram limited, but in practice 2^20 is the limit
@@ -235,27 +237,32 @@ impl super::Adapter {
         let mut features = wgt::Features::empty()
             | wgt::Features::DEPTH_CLIP_CONTROL
             | wgt::Features::DEPTH32FLOAT_STENCIL8
+            | wgt::Features::DUAL_SOURCE_BLENDING
+            | wgt::Features::FLOAT32_FILTERABLE
             | wgt::Features::INDIRECT_FIRST_INSTANCE
             | wgt::Features::MAPPABLE_PRIMARY_BUFFERS
             | wgt::Features::MULTI_DRAW_INDIRECT
             | wgt::Features::MULTI_DRAW_INDIRECT_COUNT
             | wgt::Features::ADDRESS_MODE_CLAMP_TO_BORDER
+            | wgt::Features::ADDRESS_MODE_CLAMP_TO_BORDER_CUSTOM
             | wgt::Features::ADDRESS_MODE_CLAMP_TO_ZERO
             | wgt::Features::POLYGON_MODE_LINE
+            | wgt::Features::REUSABLE_COMMAND_BUFFERS
+            | wgt::Features::UNINITIALIZED_WORKGROUP_MEMORY
             | wgt::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
             | wgt::Features::TIMESTAMP_QUERY
             | wgt::Features::TIMESTAMP_QUERY_INSIDE_PASSES
             | wgt::Features::TEXTURE_COMPRESSION_BC
+            | wgt::Features::TEXTURE_COMPRESSION_BC_SLICED_3D
+            | wgt::Features::TEXTURE_COMPRESSION_S3TC
             | wgt::Features::CLEAR_TEXTURE
             | wgt::Features::TEXTURE_FORMAT_16BIT_NORM
             | wgt::Features::PUSH_CONSTANTS
             | wgt::Features::SHADER_PRIMITIVE_INDEX
-            | wgt::Features::RG11B10UFLOAT_RENDERABLE;
-        //TODO: in order to expose this, we need to run a compute shader
-        // that extract the necessary statistics out of the D3D12 result.
-        // Alternatively, we could allocate a buffer for the query set,
-        // write the results there, and issue a bunch of copy commands.
-        //| wgt::Features::PIPELINE_STATISTICS_QUERY
+            | wgt::Features::RG11B10UFLOAT_RENDERABLE
+            | wgt::Features::RG11B10UFLOAT_STORAGE
+            | wgt::Features::TEXTURE_COMPONENT_SWIZZLE
+            | wgt::Features::PIPELINE_STATISTICS_QUERY;
 
         if max_feature_level as u32 >= d3d12::FeatureLevel::L11_1 as u32 {
             features |= wgt::Features::VERTEX_WRITABLE_STORAGE;
@@ -384,6 +391,7 @@ impl crate::Adapter<super::Api> for super::Adapter {
         &self,
         _features: wgt::Features,
         limits: &wgt::Limits,
+        additional_queues: &[wgt::QueueKind],
     ) -> Result<crate::OpenDevice<super::Api>, crate::DeviceError> {
         let queue = {
             profiling::scope!("ID3D12Device::CreateCommandQueue");
@@ -399,18 +407,49 @@ impl crate::Adapter<super::Api> for super::Adapter {
 
         let device = super::Device::new(
             self.device.clone(),
+            &self.raw,
             queue.clone(),
             limits,
             self.private_caps,
             &self.library,
             self.dx12_shader_compiler.clone(),
         )?;
+        let additional_queues = additional_queues
+            .iter()
+            .map(|&kind| {
+                let list_type = match kind {
+                    wgt::QueueKind::Compute => d3d12::CmdListType::Compute,
+                    wgt::QueueKind::Transfer => d3d12::CmdListType::Copy,
+                };
+                let raw = {
+                    profiling::scope!("ID3D12Device::CreateCommandQueue");
+                    self.device
+                        .create_command_queue(
+                            list_type,
+                            d3d12::Priority::Normal,
+                            d3d12::CommandQueueFlags::empty(),
+                            0,
+                        )
+                        .into_device_result("Queue creation")?
+                };
+                Ok(super::Queue {
+                    raw,
+                    device: self.device.clone(),
+                    list_type,
+                    temp_lists: Vec::new(),
+                })
+            })
+            .collect::<Result<Vec<_>, crate::DeviceError>>()?;
+
         Ok(crate::OpenDevice {
             device,
             queue: super::Queue {
                 raw: queue,
+                device: self.device.clone(),
+                list_type: d3d12::CmdListType::Direct,
                 temp_lists: Vec::new(),
             },
+            additional_queues,
         })
     }
 
@@ -431,6 +470,9 @@ impl crate::Adapter<super::Api> for super::Adapter {
                 // use the depth aspect here as opposed to stencil since it has more capabilities
                 crate::FormatAspects::DEPTH,
             )
+        } else if format.is_multi_planar_format() {
+            // Planes are viewed with their own formats, but support is reported for the whole format.
+            Some(raw_format)
         } else {
             auxil::dxgi::conv::map_texture_format_for_srv_uav(
                 format,
@@ -563,39 +605,34 @@ impl crate::Adapter<super::Api> for super::Adapter {
         &self,
         surface: &super::Surface,
     ) -> Option<crate::SurfaceCapabilities> {
-        let current_extent = {
-            match surface.target {
-                SurfaceTarget::WndHandle(wnd_handle) => {
-                    let mut rect: windef::RECT = unsafe { mem::zeroed() };
-                    if unsafe { winuser::GetClientRect(wnd_handle, &mut rect) } != 0 {
-                        Some(wgt::Extent3d {
-                            width: (rect.right - rect.left) as u32,
-                            height: (rect.bottom - rect.top) as u32,
-                            depth_or_array_layers: 1,
-                        })
-                    } else {
-                        log::warn!("Unable to get the window client rect");
-                        None
-                    }
-                }
-                SurfaceTarget::Visual(_) | SurfaceTarget::SurfaceHandle(_) => None,
-            }
-        };
+        let current_extent = surface.window_extent();
 
         let mut present_modes = vec![wgt::PresentMode::Mailbox, wgt::PresentMode::Fifo];
         if surface.supports_allow_tearing {
             present_modes.push(wgt::PresentMode::Immediate);
         }
 
-        Some(crate::SurfaceCapabilities {
-            formats: vec![
-                wgt::TextureFormat::Bgra8UnormSrgb,
-                wgt::TextureFormat::Bgra8Unorm,
-                wgt::TextureFormat::Rgba8UnormSrgb,
-                wgt::TextureFormat::Rgba8Unorm,
-                wgt::TextureFormat::Rgb10a2Unorm,
-                wgt::TextureFormat::Rgba16Float,
+        let formats = vec![
+            wgt::TextureFormat::Bgra8UnormSrgb,
+            wgt::TextureFormat::Bgra8Unorm,
+            wgt::TextureFormat::Rgba8UnormSrgb,
+            wgt::TextureFormat::Rgba8Unorm,
+            wgt::TextureFormat::Rgb10a2Unorm,
+            wgt::TextureFormat::Rgba16Float,
+        ];
+        // Support for the HDR color spaces depends on the output and is
+        // checked against the swapchain when configuring.
+        let color_spaces = crate::auxil::compatible_color_spaces(
+            &formats,
+            &[
+                wgt::SurfaceColorSpace::Srgb,
+                wgt::SurfaceColorSpace::ExtendedSrgbLinear,
+                wgt::SurfaceColorSpace::Hdr10,
             ],
+        );
+
+        Some(crate::SurfaceCapabilities {
+            formats,
             // we currently use a flip effect which supports 2..=16 buffers
             swap_chain_sizes: 2..=16,
             current_extent,
@@ -613,7 +650,16 @@ impl crate::Adapter<super::Api> for super::Adapter {
                 | crate::TextureUses::COPY_SRC
                 | crate::TextureUses::COPY_DST,
             present_modes,
-            composite_alpha_modes: vec![wgt::CompositeAlphaMode::Opaque],
+            // Only swapchains created for composition can be transparent.
+            composite_alpha_modes: match surface.target {
+                SurfaceTarget::WndHandle(_) => vec![wgt::CompositeAlphaMode::Opaque],
+                SurfaceTarget::Visual(_) | SurfaceTarget::SurfaceHandle(_) => vec![
+                    wgt::CompositeAlphaMode::Opaque,
+                    wgt::CompositeAlphaMode::PreMultiplied,
+                    wgt::CompositeAlphaMode::PostMultiplied,
+                ],
+            },
+            color_spaces,
         })
     }
 
@@ -60,6 +60,28 @@ impl super::Temp {
 }
 
 impl super::CommandEncoder {
+    /// Returns the resource states that barriers of the command lists can use.
+    ///
+    /// Compute and copy lists can't transition from or to the states of the
+    /// graphics pipeline, which they don't need.
+    fn state_mask(&self) -> d3d12_ty::D3D12_RESOURCE_STATES {
+        match self.list_type {
+            d3d12::CmdListType::Compute => {
+                d3d12_ty::D3D12_RESOURCE_STATE_VERTEX_AND_CONSTANT_BUFFER
+                    | d3d12_ty::D3D12_RESOURCE_STATE_UNORDERED_ACCESS
+                    | d3d12_ty::D3D12_RESOURCE_STATE_NON_PIXEL_SHADER_RESOURCE
+                    | d3d12_ty::D3D12_RESOURCE_STATE_INDIRECT_ARGUMENT
+                    | d3d12_ty::D3D12_RESOURCE_STATE_COPY_DEST
+                    | d3d12_ty::D3D12_RESOURCE_STATE_COPY_SOURCE
+            }
+            d3d12::CmdListType::Copy => {
+                d3d12_ty::D3D12_RESOURCE_STATE_COPY_DEST
+                    | d3d12_ty::D3D12_RESOURCE_STATE_COPY_SOURCE
+            }
+            _ => !0,
+        }
+    }
+
     unsafe fn begin_pass(&mut self, kind: super::PassKind, label: crate::Label) {
         let list = self.list.as_ref().unwrap();
         self.pass.kind = kind;
@@ -237,6 +259,8 @@ impl super::CommandEncoder {
                     &crate::dx12::QuerySet {
                         raw: query_set_raw,
                         raw_ty: d3d12_ty::D3D12_QUERY_TYPE_TIMESTAMP,
+                        resolve_buffer: None,
+                        statistics_offsets: arrayvec::ArrayVec::new(),
                     },
                     index,
                 );
@@ -246,7 +270,11 @@ impl super::CommandEncoder {
 }
 
 impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
-    unsafe fn begin_encoding(&mut self, label: crate::Label) -> Result<(), crate::DeviceError> {
+    unsafe fn begin_encoding(
+        &mut self,
+        label: crate::Label,
+        _reusable: bool,
+    ) -> Result<(), crate::DeviceError> {
         let list = loop {
             if let Some(list) = self.free_lists.pop() {
                 let reset_result = list
@@ -265,7 +293,7 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         } else {
             self.device
                 .create_graphics_command_list(
-                    d3d12::CmdListType::Direct,
+                    self.list_type,
                     &self.allocator,
                     d3d12::PipelineState::null(),
                     0,
@@ -321,8 +349,8 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                 barrier.usage.start,
                 barrier.usage.end
             );
-            let s0 = conv::map_buffer_usage_to_state(barrier.usage.start);
-            let s1 = conv::map_buffer_usage_to_state(barrier.usage.end);
+            let s0 = conv::map_buffer_usage_to_state(barrier.usage.start) & self.state_mask();
+            let s1 = conv::map_buffer_usage_to_state(barrier.usage.end) & self.state_mask();
             if s0 != s1 {
                 let mut raw = d3d12_ty::D3D12_RESOURCE_BARRIER {
                     Type: d3d12_ty::D3D12_RESOURCE_BARRIER_TYPE_TRANSITION,
@@ -381,8 +409,8 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                 barrier.usage.end,
                 barrier.range
             );
-            let s0 = conv::map_texture_usage_to_state(barrier.usage.start);
-            let s1 = conv::map_texture_usage_to_state(barrier.usage.end);
+            let s0 = conv::map_texture_usage_to_state(barrier.usage.start) & self.state_mask();
+            let s1 = conv::map_texture_usage_to_state(barrier.usage.end) & self.state_mask();
             if s0 != s1 {
                 let mut raw = d3d12_ty::D3D12_RESOURCE_BARRIER {
                     Type: d3d12_ty::D3D12_RESOURCE_BARRIER_TYPE_TRANSITION,
@@ -415,7 +443,10 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                             wgt::TextureAspect::All => 0..2,
                             wgt::TextureAspect::DepthOnly => 0..1,
                             wgt::TextureAspect::StencilOnly => 1..2,
+                            _ => unreachable!(),
                         }
+                    } else if let Some(planes) = barrier.texture.format.planes() {
+                        0..planes
                     } else {
                         match barrier.texture.format {
                             wgt::TextureFormat::Stencil8 => 1..2,
@@ -653,18 +684,82 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         range: Range<u32>,
         buffer: &super::Buffer,
         offset: wgt::BufferAddress,
-        _stride: wgt::BufferSize,
+        stride: wgt::BufferSize,
     ) {
+        let list = self.list.as_ref().unwrap();
+        let resolve_buffer = match set.resolve_buffer {
+            Some(ref resolve_buffer) => resolve_buffer,
+            None => {
+                unsafe {
+                    list.ResolveQueryData(
+                        set.raw.as_mut_ptr(),
+                        set.raw_ty,
+                        range.start,
+                        range.end - range.start,
+                        buffer.resource.as_mut_ptr(),
+                        offset,
+                    )
+                };
+                return;
+            }
+        };
+
+        // Pipeline statistics: resolve everything, then pick the requested values.
+        let resolved_size = mem::size_of::<d3d12_ty::D3D12_QUERY_DATA_PIPELINE_STATISTICS>() as u64;
         unsafe {
-            self.list.as_ref().unwrap().ResolveQueryData(
+            list.ResolveQueryData(
                 set.raw.as_mut_ptr(),
                 set.raw_ty,
                 range.start,
                 range.end - range.start,
-                buffer.resource.as_mut_ptr(),
-                offset,
+                resolve_buffer.resource.as_mut_ptr(),
+                range.start as u64 * resolved_size,
             )
         };
+
+        let transition = |before, after| {
+            let mut raw = d3d12_ty::D3D12_RESOURCE_BARRIER {
+                Type: d3d12_ty::D3D12_RESOURCE_BARRIER_TYPE_TRANSITION,
+                Flags: d3d12_ty::D3D12_RESOURCE_BARRIER_FLAG_NONE,
+                u: unsafe { mem::zeroed() },
+            };
+            unsafe {
+                *raw.u.Transition_mut() = d3d12_ty::D3D12_RESOURCE_TRANSITION_BARRIER {
+                    pResource: resolve_buffer.resource.as_mut_ptr(),
+                    Subresource: d3d12_ty::D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES,
+                    StateBefore: before,
+                    StateAfter: after,
+                }
+            };
+            raw
+        };
+
+        let barrier = transition(
+            d3d12_ty::D3D12_RESOURCE_STATE_COPY_DEST,
+            d3d12_ty::D3D12_RESOURCE_STATE_COPY_SOURCE,
+        );
+        unsafe { list.ResourceBarrier(1, &barrier) };
+        for (i, query) in range.enumerate() {
+            let src_base = query as u64 * resolved_size;
+            let dst_base = offset + i as u64 * stride.get();
+            for (j, &field_offset) in set.statistics_offsets.iter().enumerate() {
+                unsafe {
+                    list.CopyBufferRegion(
+                        buffer.resource.as_mut_ptr(),
+                        dst_base + j as u64 * 8,
+                        resolve_buffer.resource.as_mut_ptr(),
+                        src_base + field_offset,
+                        8,
+                    )
+                };
+            }
+        }
+        // Leave it ready for the next resolve.
+        let barrier = transition(
+            d3d12_ty::D3D12_RESOURCE_STATE_COPY_SOURCE,
+            d3d12_ty::D3D12_RESOURCE_STATE_COPY_DEST,
+        );
+        unsafe { list.ResourceBarrier(1, &barrier) };
     }
 
     // render
@@ -687,7 +782,10 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         let mut color_views = [d3d12::CpuDescriptor { ptr: 0 }; crate::MAX_COLOR_ATTACHMENTS];
         for (rtv, cat) in color_views.iter_mut().zip(desc.color_attachments.iter()) {
             if let Some(cat) = cat.as_ref() {
-                *rtv = cat.target.view.handle_rtv.unwrap().raw;
+                *rtv = match cat.depth_slice {
+                    Some(slice) => cat.target.view.depth_slice_rtvs[slice as usize].raw,
+                    None => cat.target.view.handle_rtv.unwrap().raw,
+                };
             } else {
                 *rtv = self.null_rtv_handle.raw;
             }
@@ -907,6 +1005,18 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
             self.reset_signature(&layout.shared);
         };
     }
+
+    unsafe fn set_bind_group_dynamic_offsets(
+        &mut self,
+        layout: &super::PipelineLayout,
+        index: u32,
+        group: &super::BindGroup,
+        dynamic_offsets: &[wgt::DynamicOffset],
+    ) {
+        // Rebinding the whole group is already a single call.
+        unsafe { self.set_bind_group(layout, index, group, dynamic_offsets) };
+    }
+
     unsafe fn set_push_constants(
         &mut self,
         layout: &super::PipelineLayout,
@@ -1021,12 +1131,29 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         };
         unsafe { self.list.as_ref().unwrap().RSSetScissorRects(1, &raw_rect) };
     }
+    unsafe fn set_viewport_indexed(
+        &mut self,
+        _index: u32,
+        _rect: &crate::Rect<f32>,
+        _depth_range: Range<f32>,
+    ) {
+        unreachable!("MULTI_VIEWPORT is not supported")
+    }
+    unsafe fn set_scissor_rect_indexed(&mut self, _index: u32, _rect: &crate::Rect<u32>) {
+        unreachable!("MULTI_VIEWPORT is not supported")
+    }
     unsafe fn set_stencil_reference(&mut self, value: u32) {
         self.list.as_ref().unwrap().set_stencil_reference(value);
     }
     unsafe fn set_blend_constants(&mut self, color: &[f32; 4]) {
         self.list.as_ref().unwrap().set_blend_factor(*color);
     }
+    unsafe fn set_shading_rate(&mut self, _rate: wgt::ShadingRate) {
+        unreachable!("SHADING_RATE is not supported")
+    }
+    unsafe fn set_depth_bounds(&mut self, _bounds: Range<f32>) {
+        unreachable!("DEPTH_BOUNDS_TEST is not supported")
+    }
 
     unsafe fn draw(
         &mut self,
@@ -1191,4 +1318,18 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
             )
         };
     }
+
+    unsafe fn build_acceleration_structures(
+        &mut self,
+        descriptors: &[crate::BuildAccelerationStructureDescriptor<super::Api>],
+    ) {
+        if let Some(desc) = descriptors.first() {
+            match *desc.destination_acceleration_structure {}
+        }
+    }
+    unsafe fn place_acceleration_structure_barrier(
+        &mut self,
+        _barrier: crate::AccelerationStructureBarrier,
+    ) {
+    }
 }
@@ -83,6 +83,7 @@ pub fn map_border_color(border_color: Option<wgt::SamplerBorderColor>) -> [f32;
         Some(Sbc::TransparentBlack) | Some(Sbc::Zero) | None => [0.0; 4],
         Some(Sbc::OpaqueBlack) => [0.0, 0.0, 0.0, 1.0],
         Some(Sbc::OpaqueWhite) => [1.0; 4],
+        Some(Sbc::Custom(color)) => color,
     }
 }
 
@@ -112,6 +113,7 @@ pub fn map_binding_type(ty: &wgt::BindingType) -> d3d12::DescriptorRangeType {
             ..
         }
         | Bt::StorageTexture { .. } => d3d12::DescriptorRangeType::UAV,
+        Bt::AccelerationStructure => unreachable!("`Features::RAY_QUERY` is not supported"),
     }
 }
 
@@ -123,11 +125,12 @@ pub fn map_buffer_usage_to_state(usage: crate::BufferUses) -> d3d12_ty::D3D12_RE
     use crate::BufferUses as Bu;
     let mut state = d3d12_ty::D3D12_RESOURCE_STATE_COMMON;
 
-    if usage.intersects(Bu::COPY_SRC) {
-        state |= d3d12_ty::D3D12_RESOURCE_STATE_COPY_SOURCE;
-    }
+    // `COPY_DEST` is a write state and can't be combined with `COPY_SOURCE`. A buffer
+    // copied within itself stays in `COPY_DEST`, which covers both sides of the copy.
     if usage.intersects(Bu::COPY_DST) {
         state |= d3d12_ty::D3D12_RESOURCE_STATE_COPY_DEST;
+    } else if usage.intersects(Bu::COPY_SRC) {
+        state |= d3d12_ty::D3D12_RESOURCE_STATE_COPY_SOURCE;
     }
     if usage.intersects(Bu::INDEX) {
         state |= d3d12_ty::D3D12_RESOURCE_STATE_INDEX_BUFFER;
@@ -242,12 +245,12 @@ fn map_blend_factor(factor: wgt::BlendFactor, is_alpha: bool) -> d3d12_ty::D3D12
         Bf::Constant => d3d12_ty::D3D12_BLEND_BLEND_FACTOR,
         Bf::OneMinusConstant => d3d12_ty::D3D12_BLEND_INV_BLEND_FACTOR,
         Bf::SrcAlphaSaturated => d3d12_ty::D3D12_BLEND_SRC_ALPHA_SAT,
-        //Bf::Src1Color if is_alpha => d3d12_ty::D3D12_BLEND_SRC1_ALPHA,
-        //Bf::Src1Color => d3d12_ty::D3D12_BLEND_SRC1_COLOR,
-        //Bf::OneMinusSrc1Color if is_alpha => d3d12_ty::D3D12_BLEND_INV_SRC1_ALPHA,
-        //Bf::OneMinusSrc1Color => d3d12_ty::D3D12_BLEND_INV_SRC1_COLOR,
-        //Bf::Src1Alpha => d3d12_ty::D3D12_BLEND_SRC1_ALPHA,
-        //Bf::OneMinusSrc1Alpha => d3d12_ty::D3D12_BLEND_INV_SRC1_ALPHA,
+        Bf::Src1 if is_alpha => d3d12_ty::D3D12_BLEND_SRC1_ALPHA,
+        Bf::Src1 => d3d12_ty::D3D12_BLEND_SRC1_COLOR,
+        Bf::OneMinusSrc1 if is_alpha => d3d12_ty::D3D12_BLEND_INV_SRC1_ALPHA,
+        Bf::OneMinusSrc1 => d3d12_ty::D3D12_BLEND_INV_SRC1_COLOR,
+        Bf::Src1Alpha => d3d12_ty::D3D12_BLEND_SRC1_ALPHA,
+        Bf::OneMinusSrc1Alpha => d3d12_ty::D3D12_BLEND_INV_SRC1_ALPHA,
     }
 }
 
@@ -348,3 +351,21 @@ pub fn map_depth_stencil(ds: &wgt::DepthStencilState) -> d3d12_ty::D3D12_DEPTH_S
         BackFace: map_stencil_face(&ds.stencil.back),
     }
 }
+
+/// Returns the byte offsets of the requested statistics within
+/// `D3D12_QUERY_DATA_PIPELINE_STATISTICS`, in the order wgpu writes them.
+pub fn map_pipeline_statistics(types: wgt::PipelineStatisticsTypes) -> arrayvec::ArrayVec<u64, 5> {
+    use wgt::PipelineStatisticsTypes as Pst;
+    const FIELD_SIZE: u64 = 8;
+    [
+        (Pst::VERTEX_SHADER_INVOCATIONS, 2),   // VSInvocations
+        (Pst::CLIPPER_INVOCATIONS, 5),         // CInvocations
+        (Pst::CLIPPER_PRIMITIVES_OUT, 6),      // CPrimitives
+        (Pst::FRAGMENT_SHADER_INVOCATIONS, 7), // PSInvocations
+        (Pst::COMPUTE_SHADER_INVOCATIONS, 10), // CSInvocations
+    ]
+    .into_iter()
+    .filter(|&(ty, _)| types.contains(ty))
+    .map(|(_, field)| field * FIELD_SIZE)
+    .collect()
+}
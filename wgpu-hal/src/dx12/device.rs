@@ -15,6 +15,7 @@ const NAGA_LOCATION_SEMANTIC: &[u8] = b"LOC\0";
 impl super::Device {
     pub(super) fn new(
         raw: d3d12::Device,
+        adapter: &d3d12::DxgiAdapter,
         present_queue: d3d12::CommandQueue,
         limits: &wgt::Limits,
         private_caps: super::PrivateCapabilities,
@@ -28,6 +29,12 @@ impl super::Device {
         };
 
         let dxc_container = match dx12_shader_compiler {
+            wgt::Dx12Compiler::Dxc { .. } if !private_caps.shader_model_6 => {
+                log::warn!(
+                    "Shader model 6.0 is not supported by the device. Defaulting to Fxc instead"
+                );
+                None
+            }
             wgt::Dx12Compiler::Dxc {
                 dxil_path,
                 dxc_path,
@@ -178,6 +185,7 @@ impl super::Device {
             null_rtv_handle,
             mem_allocator,
             dxc_container,
+            residency: super::residency::Residency::new(adapter),
         })
     }
 
@@ -208,14 +216,23 @@ impl super::Device {
         use naga::back::hlsl;
 
         let stage_bit = crate::auxil::map_naga_stage(naga_stage);
-        let module = &stage.module.naga.module;
+        let module =
+            crate::auxil::apply_pipeline_constants(&stage.module.naga.module, stage.constants);
+        let mut temp_options;
+        let naga_options = if stage.module.zero_initialize_workgroup_memory {
+            &layout.naga_options
+        } else {
+            temp_options = layout.naga_options.clone();
+            temp_options.zero_initialize_workgroup_memory = false;
+            &temp_options
+        };
         //TODO: reuse the writer
         let mut source = String::new();
-        let mut writer = hlsl::Writer::new(&mut source, &layout.naga_options);
+        let mut writer = hlsl::Writer::new(&mut source, naga_options);
         let reflection_info = {
             profiling::scope!("naga::back::hlsl::write");
             writer
-                .write(module, &stage.module.naga.info)
+                .write(&module, &stage.module.naga.info)
                 .map_err(|e| crate::PipelineError::Linkage(stage_bit, format!("HLSL: {e:?}")))?
         };
 
@@ -319,6 +336,8 @@ impl crate::Device<super::Api> for super::Device {
         self.mem_allocator = None;
     }
 
+    unsafe fn destroy_queue(&self, _queue: super::Queue) {}
+
     unsafe fn create_buffer(
         &self,
         desc: &crate::BufferDescriptor,
@@ -520,6 +539,29 @@ impl crate::Device<super::Api> for super::Device {
             } else {
                 None
             },
+            depth_slice_rtvs: if desc.dimension == wgt::TextureViewDimension::D3
+                && desc.usage.intersects(crate::TextureUses::COLOR_TARGET)
+            {
+                let depth =
+                    (texture.size.depth_or_array_layers >> desc.range.base_mip_level).max(1);
+                let mut pool = self.rtv_pool.lock();
+                (0..depth)
+                    .map(|slice| {
+                        let raw_desc = unsafe { view_desc.to_rtv_depth_slice(slice) };
+                        let handle = pool.alloc_handle();
+                        unsafe {
+                            self.raw.CreateRenderTargetView(
+                                texture.resource.as_mut_ptr(),
+                                &raw_desc,
+                                handle.raw,
+                            )
+                        };
+                        handle
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            },
             handle_dsv_ro: if desc
                 .usage
                 .intersects(crate::TextureUses::DEPTH_STENCIL_READ)
@@ -566,8 +608,14 @@ impl crate::Device<super::Api> for super::Device {
                 pool.free_handle(handle);
             }
         }
-        if let Some(handle) = view.handle_rtv {
-            self.rtv_pool.lock().free_handle(handle);
+        if view.handle_rtv.is_some() || !view.depth_slice_rtvs.is_empty() {
+            let mut pool = self.rtv_pool.lock();
+            if let Some(handle) = view.handle_rtv {
+                pool.free_handle(handle);
+            }
+            for handle in view.depth_slice_rtvs {
+                pool.free_handle(handle);
+            }
         }
         if view.handle_dsv_ro.is_some() || view.handle_dsv_rw.is_some() {
             let mut pool = self.dsv_pool.lock();
@@ -628,7 +676,7 @@ impl crate::Device<super::Api> for super::Device {
     ) -> Result<super::CommandEncoder, crate::DeviceError> {
         let allocator = self
             .raw
-            .create_command_allocator(d3d12::CmdListType::Direct)
+            .create_command_allocator(desc.queue.list_type)
             .into_device_result("Command allocator creation")?;
 
         if let Some(label) = desc.label {
@@ -643,6 +691,7 @@ impl crate::Device<super::Api> for super::Device {
             null_rtv_handle: self.null_rtv_handle,
             list: None,
             free_lists: Vec::new(),
+            list_type: desc.queue.list_type,
             pass: super::PassState::new(),
             temp: super::Temp::default(),
             end_of_pass_timer_query: None,
@@ -671,6 +720,9 @@ impl crate::Device<super::Api> for super::Device {
                     num_texture_views += count
                 }
                 wgt::BindingType::Sampler { .. } => num_samplers += count,
+                wgt::BindingType::AccelerationStructure => {
+                    unreachable!("`Features::RAY_QUERY` is not supported")
+                }
             }
         }
 
@@ -820,15 +872,15 @@ impl crate::Device<super::Api> for super::Device {
             };
 
             let mut visibility_view_static = wgt::ShaderStages::empty();
-            let mut visibility_view_dynamic = wgt::ShaderStages::empty();
             let mut visibility_sampler = wgt::ShaderStages::empty();
             for entry in bgl.entries.iter() {
                 match entry.ty {
                     wgt::BindingType::Sampler { .. } => visibility_sampler |= entry.visibility,
+                    // Dynamic buffers are root descriptors with their own visibility.
                     wgt::BindingType::Buffer {
                         has_dynamic_offset: true,
                         ..
-                    } => visibility_view_dynamic |= entry.visibility,
+                    } => {}
                     _ => visibility_view_static |= entry.visibility,
                 }
             }
@@ -921,8 +973,7 @@ impl crate::Device<super::Api> for super::Device {
                 info.tables |= super::TableTypes::SAMPLERS;
             }
 
-            // Root (dynamic) descriptor tables
-            let dynamic_buffers_visibility = conv::map_visibility(visibility_view_dynamic);
+            // Root (dynamic) descriptors
             for entry in bgl.entries.iter() {
                 let buffer_ty = match entry.ty {
                     wgt::BindingType::Buffer {
@@ -963,6 +1014,9 @@ impl crate::Device<super::Api> for super::Device {
                 );
                 info.dynamic_buffers.push(kind);
 
+                // Only expose the descriptor to the stages using it, which lets
+                // the driver skip loading it for the others.
+                let dynamic_buffers_visibility = conv::map_visibility(entry.visibility);
                 log::debug!(
                     "\tParam[{}] = dynamic {:?} (vis = {:?})",
                     parameters.len(),
@@ -1192,6 +1246,9 @@ impl crate::Device<super::Api> for super::Device {
                         cpu_samplers.as_mut().unwrap().stage.push(data.handle.raw);
                     }
                 }
+                wgt::BindingType::AccelerationStructure => {
+                    unreachable!("`Features::RAY_QUERY` is not supported")
+                }
             }
         }
 
@@ -1246,7 +1303,11 @@ impl crate::Device<super::Api> for super::Device {
     ) -> Result<super::ShaderModule, crate::ShaderError> {
         let raw_name = desc.label.and_then(|label| ffi::CString::new(label).ok());
         match shader {
-            crate::ShaderInput::Naga(naga) => Ok(super::ShaderModule { naga, raw_name }),
+            crate::ShaderInput::Naga(naga) => Ok(super::ShaderModule {
+                naga,
+                zero_initialize_workgroup_memory: desc.zero_initialize_workgroup_memory,
+                raw_name,
+            }),
             crate::ShaderInput::SpirV(_) => {
                 panic!("SPIRV_SHADER_PASSTHROUGH is not enabled for this backend")
             }
@@ -1476,6 +1537,17 @@ impl crate::Device<super::Api> for super::Device {
     }
     unsafe fn destroy_compute_pipeline(&self, _pipeline: super::ComputePipeline) {}
 
+    unsafe fn create_pipeline_cache(
+        &self,
+        _desc: &crate::PipelineCacheDescriptor,
+    ) -> Result<super::PipelineCache, crate::DeviceError> {
+        Ok(super::PipelineCache)
+    }
+    unsafe fn destroy_pipeline_cache(&self, _cache: super::PipelineCache) {}
+    unsafe fn pipeline_cache_get_data(&self, _cache: &super::PipelineCache) -> Option<Vec<u8>> {
+        None
+    }
+
     unsafe fn create_query_set(
         &self,
         desc: &wgt::QuerySetDescriptor<crate::Label>,
@@ -1505,9 +1577,35 @@ impl crate::Device<super::Api> for super::Device {
             unsafe { raw.SetName(cwstr.as_ptr()) };
         }
 
-        Ok(super::QuerySet { raw, raw_ty })
+        let (resolve_buffer, statistics_offsets) = match desc.ty {
+            wgt::QueryType::PipelineStatistics(statistics) => {
+                let buffer = unsafe {
+                    self.create_buffer(&crate::BufferDescriptor {
+                        label: Some("(wgpu internal) pipeline statistics resolve"),
+                        size: desc.count as u64
+                            * mem::size_of::<d3d12_ty::D3D12_QUERY_DATA_PIPELINE_STATISTICS>()
+                                as u64,
+                        usage: crate::BufferUses::COPY_SRC | crate::BufferUses::COPY_DST,
+                        memory_flags: crate::MemoryFlags::empty(),
+                    })
+                }?;
+                (Some(buffer), conv::map_pipeline_statistics(statistics))
+            }
+            _ => (None, arrayvec::ArrayVec::new()),
+        };
+
+        Ok(super::QuerySet {
+            raw,
+            raw_ty,
+            resolve_buffer,
+            statistics_offsets,
+        })
+    }
+    unsafe fn destroy_query_set(&self, set: super::QuerySet) {
+        if let Some(buffer) = set.resolve_buffer {
+            unsafe { self.destroy_buffer(buffer) };
+        }
     }
-    unsafe fn destroy_query_set(&self, _set: super::QuerySet) {}
 
     unsafe fn create_fence(&self) -> Result<super::Fence, crate::DeviceError> {
         let mut raw = d3d12::Fence::null();
@@ -1571,4 +1669,37 @@ impl crate::Device<super::Api> for super::Device {
                 .end_frame_capture(self.raw.as_mut_ptr() as *mut _, ptr::null_mut())
         }
     }
+
+    unsafe fn allocator_report(&self) -> Option<crate::AllocatorReport> {
+        let budget = self.memory_budget()?;
+        Some(crate::AllocatorReport {
+            allocation_count: None,
+            allocated_bytes: Some(budget.local.current_usage + budget.non_local.current_usage),
+        })
+    }
+
+    unsafe fn create_acceleration_structure(
+        &self,
+        _desc: &crate::AccelerationStructureDescriptor,
+    ) -> Result<super::AccelerationStructure, crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn get_acceleration_structure_build_sizes(
+        &self,
+        _desc: &crate::GetAccelerationStructureBuildSizesDescriptor<super::Api>,
+    ) -> crate::AccelerationStructureBuildSizes {
+        Default::default()
+    }
+    unsafe fn get_acceleration_structure_device_address(
+        &self,
+        acceleration_structure: &super::AccelerationStructure,
+    ) -> wgt::BufferAddress {
+        match *acceleration_structure {}
+    }
+    unsafe fn destroy_acceleration_structure(
+        &self,
+        acceleration_structure: super::AccelerationStructure,
+    ) {
+        match acceleration_structure {}
+    }
 }
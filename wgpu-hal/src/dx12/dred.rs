@@ -0,0 +1,267 @@
+//! Device Removed Extended Data (DRED), used to report what the GPU was doing
+//! when a device is removed.
+//!
+//! With automatic breadcrumbs, the runtime records the operations of every
+//! command list, and how far the GPU got through them. With page fault
+//! reporting, it records the objects whose memory was around the faulting
+//! address. Command lists and resources are named after their labels, so
+//! the report points at the passes and objects involved.
+//!
+//! See <https://learn.microsoft.com/en-us/windows/win32/direct3d12/use-dred>.
+
+use std::{mem, slice};
+
+use winapi::{ctypes, shared::winerror, Interface as _};
+
+use super::types::*;
+use crate::auxil::dxgi::result::HResult as _;
+
+/// Number of operations logged on each side of the first one that didn't complete.
+const BREADCRUMB_CONTEXT: usize = 4;
+
+/// Turns on automatic breadcrumbs and page fault reporting for the devices
+/// created afterwards.
+pub(super) fn enable() {
+    type GetDebugInterface = unsafe extern "system" fn(
+        winapi::shared::guiddef::REFIID,
+        *mut *mut ctypes::c_void,
+    ) -> winerror::HRESULT;
+
+    let lib = match unsafe { libloading::Library::new("d3d12.dll") } {
+        Ok(lib) => lib,
+        Err(err) => {
+            log::warn!("Unable to load d3d12.dll to enable DRED: {:?}", err);
+            return;
+        }
+    };
+    let get_debug_interface =
+        match unsafe { lib.get::<GetDebugInterface>(b"D3D12GetDebugInterface") } {
+            Ok(func) => func,
+            Err(err) => {
+                log::warn!("Debug interface function for D3D12 not found: {:?}", err);
+                return;
+            }
+        };
+
+    let mut settings = d3d12::ComPtr::<ID3D12DeviceRemovedExtendedDataSettings>::null();
+    let hr = unsafe {
+        get_debug_interface(
+            &ID3D12DeviceRemovedExtendedDataSettings::uuidof(),
+            settings.mut_void(),
+        )
+    };
+    match hr.into_result() {
+        Ok(()) => unsafe {
+            settings.SetAutoBreadcrumbsEnablement(D3D12_DRED_ENABLEMENT_FORCED_ON);
+            settings.SetPageFaultEnablement(D3D12_DRED_ENABLEMENT_FORCED_ON);
+        },
+        // DRED needs Windows 10 1903 or later.
+        Err(err) => log::warn!("Unable to enable DRED: {}", err),
+    }
+}
+
+/// Logs why `device` was removed, with its DRED breadcrumbs and page fault
+/// if DRED was enabled.
+///
+/// Does nothing if `device` wasn't removed.
+pub(super) unsafe fn report(device: &d3d12::Device) {
+    match unsafe { device.GetDeviceRemovedReason() }.into_result() {
+        Ok(()) => return,
+        Err(err) => log::error!("Device removed: {}", err),
+    }
+
+    let dred = match unsafe { device.cast::<ID3D12DeviceRemovedExtendedData>() }.into_result() {
+        Ok(dred) => dred,
+        Err(_) => return,
+    };
+
+    let mut breadcrumbs = unsafe { mem::zeroed::<D3D12_DRED_AUTO_BREADCRUMBS_OUTPUT>() };
+    if unsafe { dred.GetAutoBreadcrumbsOutput(&mut breadcrumbs) }
+        .into_result()
+        .is_ok()
+    {
+        unsafe { report_breadcrumbs(&breadcrumbs) };
+    }
+
+    let mut page_fault = unsafe { mem::zeroed::<D3D12_DRED_PAGE_FAULT_OUTPUT>() };
+    if unsafe { dred.GetPageFaultAllocationOutput(&mut page_fault) }
+        .into_result()
+        .is_ok()
+    {
+        unsafe { report_page_fault(&page_fault) };
+    }
+}
+
+unsafe fn report_breadcrumbs(output: &D3D12_DRED_AUTO_BREADCRUMBS_OUTPUT) {
+    let mut reported_any = false;
+    let mut node = output.pHeadAutoBreadcrumbNode;
+    while let Some(current) = unsafe { node.as_ref() } {
+        node = current.pNext;
+
+        let count = current.BreadcrumbCount as usize;
+        let completed = unsafe { current.pLastBreadcrumbValue.as_ref() }.map_or(0, |&v| v as usize);
+        // Command lists the GPU finished, or didn't start, aren't interesting.
+        if completed == 0 || completed >= count || current.pCommandHistory.is_null() {
+            continue;
+        }
+
+        if !reported_any {
+            log::error!("Command lists executing when the device was removed:");
+            reported_any = true;
+        }
+        let list = unsafe {
+            name(
+                current.pCommandListDebugNameW,
+                current.pCommandListDebugNameA,
+            )
+        };
+        let queue = unsafe {
+            name(
+                current.pCommandQueueDebugNameW,
+                current.pCommandQueueDebugNameA,
+            )
+        };
+        log::error!(
+            "\t{} on queue {}, {} of {} operations completed:",
+            list,
+            queue,
+            completed,
+            count
+        );
+
+        let history = unsafe { slice::from_raw_parts(current.pCommandHistory, count) };
+        let start = completed.saturating_sub(BREADCRUMB_CONTEXT);
+        let end = count.min(completed + BREADCRUMB_CONTEXT);
+        for (index, &op) in history.iter().enumerate().take(end).skip(start) {
+            let marker = if index == completed {
+                " <- not completed"
+            } else {
+                ""
+            };
+            log::error!("\t\t{}: {}{}", index, breadcrumb_op_name(op), marker);
+        }
+    }
+
+    if !reported_any {
+        log::error!("No command lists were executing when the device was removed");
+    }
+}
+
+unsafe fn report_page_fault(output: &D3D12_DRED_PAGE_FAULT_OUTPUT) {
+    if output.PageFaultVA == 0 {
+        return;
+    }
+    log::error!(
+        "Page fault at GPU virtual address 0x{:x}",
+        output.PageFaultVA
+    );
+
+    let lists = [
+        (
+            "Objects at the faulting address:",
+            output.pHeadExistingAllocationNode,
+        ),
+        (
+            "Recently freed objects at the faulting address:",
+            output.pHeadRecentFreedAllocationNode,
+        ),
+    ];
+    for (title, head) in lists {
+        let mut node = head;
+        if !node.is_null() {
+            log::error!("{}", title);
+        }
+        while let Some(current) = unsafe { node.as_ref() } {
+            let object = unsafe { name(current.ObjectNameW, current.ObjectNameA) };
+            log::error!(
+                "\t{} {}",
+                allocation_type_name(current.AllocationType),
+                object
+            );
+            node = current.pNext;
+        }
+    }
+}
+
+/// Reads an object name given as either a wide or a narrow string.
+unsafe fn name(wide: *const ctypes::wchar_t, narrow: *const ctypes::c_char) -> String {
+    if !wide.is_null() {
+        let len = (0..).take_while(|&i| unsafe { *wide.add(i) } != 0).count();
+        let chars = unsafe { slice::from_raw_parts(wide, len) };
+        format!("{:?}", String::from_utf16_lossy(chars))
+    } else if !narrow.is_null() {
+        let name = unsafe { std::ffi::CStr::from_ptr(narrow) };
+        format!("{:?}", name.to_string_lossy())
+    } else {
+        "<unnamed>".to_string()
+    }
+}
+
+fn breadcrumb_op_name(op: D3D12_AUTO_BREADCRUMB_OP) -> String {
+    const NAMES: [&str; 43] = [
+        "SetMarker",
+        "BeginEvent",
+        "EndEvent",
+        "DrawInstanced",
+        "DrawIndexedInstanced",
+        "ExecuteIndirect",
+        "Dispatch",
+        "CopyBufferRegion",
+        "CopyTextureRegion",
+        "CopyResource",
+        "CopyTiles",
+        "ResolveSubresource",
+        "ClearRenderTargetView",
+        "ClearUnorderedAccessView",
+        "ClearDepthStencilView",
+        "ResourceBarrier",
+        "ExecuteBundle",
+        "Present",
+        "ResolveQueryData",
+        "BeginSubmission",
+        "EndSubmission",
+        "DecodeFrame",
+        "ProcessFrames",
+        "AtomicCopyBufferUint",
+        "AtomicCopyBufferUint64",
+        "ResolveSubresourceRegion",
+        "WriteBufferImmediate",
+        "DecodeFrame1",
+        "SetProtectedResourceSession",
+        "DecodeFrame2",
+        "ProcessFrames1",
+        "BuildRaytracingAccelerationStructure",
+        "EmitRaytracingAccelerationStructurePostbuildInfo",
+        "CopyRaytracingAccelerationStructure",
+        "DispatchRays",
+        "InitializeMetaCommand",
+        "ExecuteMetaCommand",
+        "EstimateMotion",
+        "ResolveMotionVectorHeap",
+        "SetPipelineState1",
+        "InitializeExtensionCommand",
+        "ExecuteExtensionCommand",
+        "DispatchMesh",
+    ];
+    match NAMES.get(op as usize) {
+        Some(name) => name.to_string(),
+        None => format!("operation {op}"),
+    }
+}
+
+fn allocation_type_name(ty: D3D12_DRED_ALLOCATION_TYPE) -> &'static str {
+    match ty {
+        19 => "command queue",
+        20 => "command allocator",
+        21 => "pipeline state",
+        22 => "command list",
+        23 => "fence",
+        24 => "descriptor heap",
+        25 => "heap",
+        27 => "query heap",
+        28 => "command signature",
+        29 => "pipeline library",
+        34 => "resource",
+        _ => "object",
+    }
+}
@@ -31,6 +31,11 @@ impl crate::Instance<super::Api> for super::Instance {
             }
         }
 
+        if desc.flags.contains(crate::InstanceFlags::DEBUG) {
+            // Record breadcrumbs and page faults, to report them when a device is removed.
+            super::dred::enable();
+        }
+
         // Create DXGIFactory4
         let (lib_dxgi, factory) = auxil::dxgi::factory::create_factory(
             auxil::dxgi::factory::DxgiFactoryType::Factory4,
@@ -66,7 +71,7 @@ impl crate::Instance<super::Api> for super::Instance {
 
             match hr.into_result() {
                 Err(err) => log::warn!("Unable to check for tearing support: {}", err),
-                Ok(()) => supports_allow_tearing = true,
+                Ok(()) => supports_allow_tearing = allow_tearing != minwindef::FALSE,
             }
         }
 
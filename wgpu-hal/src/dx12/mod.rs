@@ -38,7 +38,9 @@ mod command;
 mod conv;
 mod descriptor;
 mod device;
+mod dred;
 mod instance;
+mod residency;
 mod shader_compilation;
 mod suballocation;
 mod types;
@@ -46,12 +48,14 @@ mod view;
 
 use crate::auxil::{self, dxgi::result::HResult as _};
 
+pub use residency::{MemoryBudget, MemoryBudgetCallback, MemorySegmentInfo};
+
 use arrayvec::ArrayVec;
 use parking_lot::Mutex;
 use std::{ffi, fmt, mem, num::NonZeroU32, sync::Arc};
 use winapi::{
     shared::{dxgi, dxgi1_4, dxgitype, windef, winerror},
-    um::{d3d12 as d3d12_ty, dcomp, synchapi, winbase, winnt},
+    um::{d3d12 as d3d12_ty, dcomp, synchapi, winbase, winnt, winuser},
     Interface as _,
 };
 
@@ -82,6 +86,9 @@ impl crate::Api for Api {
     type ShaderModule = ShaderModule;
     type RenderPipeline = RenderPipeline;
     type ComputePipeline = ComputePipeline;
+    type PipelineCache = PipelineCache;
+
+    type AccelerationStructure = AccelerationStructure;
 }
 
 // Limited by D3D12's root signature size of 64. Each element takes 1 or 2 entries.
@@ -136,7 +143,10 @@ struct SwapChain {
     resources: Vec<d3d12::Resource>,
     waitable: winnt::HANDLE,
     acquired_count: usize,
+    /// Whether the last present found the window occluded.
+    occluded: bool,
     present_mode: wgt::PresentMode,
+    composite_alpha_mode: wgt::CompositeAlphaMode,
     format: wgt::TextureFormat,
     size: wgt::Extent3d,
 }
@@ -158,6 +168,28 @@ pub struct Surface {
 unsafe impl Send for Surface {}
 unsafe impl Sync for Surface {}
 
+impl Surface {
+    /// Returns the size of the window client area, if the surface targets a window.
+    fn window_extent(&self) -> Option<wgt::Extent3d> {
+        match self.target {
+            SurfaceTarget::WndHandle(wnd_handle) => {
+                let mut rect: windef::RECT = unsafe { mem::zeroed() };
+                if unsafe { winuser::GetClientRect(wnd_handle, &mut rect) } != 0 {
+                    Some(wgt::Extent3d {
+                        width: (rect.right - rect.left) as u32,
+                        height: (rect.bottom - rect.top) as u32,
+                        depth_or_array_layers: 1,
+                    })
+                } else {
+                    log::warn!("Unable to get the window client rect");
+                    None
+                }
+            }
+            SurfaceTarget::Visual(_) | SurfaceTarget::SurfaceHandle(_) => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 enum MemoryArchitecture {
     Unified {
@@ -177,6 +209,8 @@ struct PrivateCapabilities {
     heap_create_not_zeroed: bool,
     casting_fully_typed_format_supported: bool,
     suballocation_supported: bool,
+    /// Whether the driver accepts DXIL, required by shaders compiled with DXC.
+    shader_model_6: bool,
 }
 
 #[derive(Default)]
@@ -238,6 +272,7 @@ pub struct Device {
     null_rtv_handle: descriptor::Handle,
     mem_allocator: Option<Mutex<suballocation::GpuAllocatorWrapper>>,
     dxc_container: Option<shader_compilation::DxcContainer>,
+    residency: residency::Residency,
 }
 
 unsafe impl Send for Device {}
@@ -245,6 +280,10 @@ unsafe impl Sync for Device {}
 
 pub struct Queue {
     raw: d3d12::CommandQueue,
+    /// Device owning the queue, used to report why it was removed.
+    device: d3d12::Device,
+    /// Type of the command lists the queue executes.
+    list_type: d3d12::CmdListType,
     temp_lists: Vec<d3d12::CommandList>,
 }
 
@@ -345,6 +384,9 @@ pub struct CommandEncoder {
     null_rtv_handle: descriptor::Handle,
     list: Option<d3d12::GraphicsCommandList>,
     free_lists: Vec<d3d12::GraphicsCommandList>,
+    /// Type of the command lists, which is the type of the queue they are
+    /// executed on.
+    list_type: d3d12::CmdListType,
     pass: PassState,
     temp: Temp,
 
@@ -428,6 +470,8 @@ impl Texture {
         let plane = match base.aspect {
             crate::FormatAspects::COLOR | crate::FormatAspects::DEPTH => 0,
             crate::FormatAspects::STENCIL => 1,
+            crate::FormatAspects::PLANE_0 => 0,
+            crate::FormatAspects::PLANE_1 => 1,
             _ => unreachable!(),
         };
         self.calc_subresource(base.mip_level, base.array_layer, plane)
@@ -442,6 +486,8 @@ pub struct TextureView {
     handle_srv: Option<descriptor::Handle>,
     handle_uav: Option<descriptor::Handle>,
     handle_rtv: Option<descriptor::Handle>,
+    /// Render target views of each depth slice, for 3D views used as color targets.
+    depth_slice_rtvs: Vec<descriptor::Handle>,
     handle_dsv_ro: Option<descriptor::Handle>,
     handle_dsv_rw: Option<descriptor::Handle>,
 }
@@ -461,6 +507,11 @@ unsafe impl Sync for Sampler {}
 pub struct QuerySet {
     raw: d3d12::QueryHeap,
     raw_ty: d3d12_ty::D3D12_QUERY_TYPE,
+    /// D3D12 always resolves all pipeline statistics, so they are resolved
+    /// here first and the requested ones copied out of it.
+    resolve_buffer: Option<Buffer>,
+    /// Offsets of the requested statistics within a resolved query.
+    statistics_offsets: arrayvec::ArrayVec<u64, 5>,
 }
 
 unsafe impl Send for QuerySet {}
@@ -541,6 +592,7 @@ pub struct PipelineLayout {
 #[derive(Debug)]
 pub struct ShaderModule {
     naga: crate::NagaShader,
+    zero_initialize_workgroup_memory: bool,
     raw_name: Option<ffi::CString>,
 }
 
@@ -579,6 +631,15 @@ pub struct ComputePipeline {
 unsafe impl Send for ComputePipeline {}
 unsafe impl Sync for ComputePipeline {}
 
+/// Pipeline caches hold no data yet: cached PSO blobs are per pipeline, and would
+/// need to be keyed by the pipeline description to be looked up again.
+#[derive(Debug)]
+pub struct PipelineCache;
+
+/// Acceleration structures are not supported, so none can be created.
+#[derive(Debug)]
+pub enum AccelerationStructure {}
+
 impl SwapChain {
     unsafe fn release_resources(self) -> d3d12::ComPtr<dxgi1_4::IDXGISwapChain3> {
         self.raw
@@ -620,6 +681,13 @@ impl crate::Surface<Api> for Surface {
 
         let non_srgb_format = auxil::dxgi::conv::map_texture_format_nosrgb(config.format);
 
+        // `ResizeBuffers` can't change the alpha mode, so the swapchain has to be recreated.
+        if self.swap_chain.as_ref().map_or(false, |sc| {
+            sc.composite_alpha_mode != config.composite_alpha_mode
+        }) {
+            unsafe { crate::Surface::unconfigure(self, device) };
+        }
+
         let swap_chain = match self.swap_chain.take() {
             //Note: this path doesn't properly re-initialize all of the things
             Some(sc) => {
@@ -747,7 +815,26 @@ impl crate::Surface<Api> for Surface {
             SurfaceTarget::Visual(_) | SurfaceTarget::SurfaceHandle(_) => {}
         }
 
-        unsafe { swap_chain.SetMaximumFrameLatency(config.swap_chain_size) };
+        // The default color space of the swapchain matches `Srgb` for 8-bit formats
+        // and `ExtendedSrgbLinear` for `Rgba16Float`, so it only needs changing for HDR.
+        if let Some(color_space) = auxil::dxgi::conv::map_color_space(config.color_space) {
+            let mut support = 0;
+            let hr = unsafe { swap_chain.CheckColorSpaceSupport(color_space, &mut support) };
+            if hr.into_result().is_err()
+                || support & dxgi1_4::DXGI_SWAP_CHAIN_COLOR_SPACE_SUPPORT_FLAG_PRESENT == 0
+            {
+                log::error!("Color space {:?} is not supported", config.color_space);
+                return Err(crate::SurfaceError::Other("unsupported color space"));
+            }
+            if let Err(err) = unsafe { swap_chain.SetColorSpace1(color_space) }.into_result() {
+                log::error!("SetColorSpace1 failed: {}", err);
+                return Err(crate::SurfaceError::Other(
+                    "IDXGISwapChain3::SetColorSpace1",
+                ));
+            }
+        }
+
+        unsafe { swap_chain.SetMaximumFrameLatency(config.maximum_frame_latency) };
         let waitable = unsafe { swap_chain.GetFrameLatencyWaitableObject() };
 
         let mut resources = Vec::with_capacity(config.swap_chain_size as usize);
@@ -764,7 +851,9 @@ impl crate::Surface<Api> for Surface {
             resources,
             waitable,
             acquired_count: 0,
+            occluded: false,
             present_mode: config.present_mode,
+            composite_alpha_mode: config.composite_alpha_mode,
             format: config.format,
             size: config.extent,
         });
@@ -788,6 +877,7 @@ impl crate::Surface<Api> for Surface {
         &mut self,
         timeout: Option<std::time::Duration>,
     ) -> Result<Option<crate::AcquiredSurfaceTexture<Api>>, crate::SurfaceError> {
+        let window_extent = self.window_extent();
         let sc = self.swap_chain.as_mut().unwrap();
 
         unsafe { sc.wait(timeout) }?;
@@ -805,9 +895,14 @@ impl crate::Surface<Api> for Surface {
             sample_count: 1,
             allocation: None,
         };
+        // The window was resized or hidden, so a reconfiguration would serve it better.
+        let suboptimal = sc.occluded
+            || window_extent.map_or(false, |extent| {
+                extent.width != 0 && extent.height != 0 && extent != sc.size
+            });
         Ok(Some(crate::AcquiredSurfaceTexture {
             texture,
-            suboptimal: false,
+            suboptimal,
         }))
     }
     unsafe fn discard_texture(&mut self, _texture: Texture) {
@@ -816,29 +911,65 @@ impl crate::Surface<Api> for Surface {
     }
 }
 
+impl Queue {
+    /// Logs why the device was removed, if `err` comes from its removal.
+    fn report_removal(&self, err: crate::DeviceError) -> crate::DeviceError {
+        if let crate::DeviceError::Lost = err {
+            unsafe { dred::report(&self.device) };
+        }
+        err
+    }
+}
+
 impl crate::Queue<Api> for Queue {
     unsafe fn submit(
         &mut self,
         command_buffers: &[&CommandBuffer],
         signal_fence: Option<(&mut Fence, crate::FenceValue)>,
+        label: crate::Label,
     ) -> Result<(), crate::DeviceError> {
         self.temp_lists.clear();
         for cmd_buf in command_buffers {
             self.temp_lists.push(cmd_buf.raw.as_list());
         }
 
+        // Bracket the submission in a PIX event on the queue timeline.
+        if let Some(label) = label {
+            let wide_label = label.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+            let size = wide_label.len() as u32 * 2;
+            unsafe {
+                self.raw
+                    .BeginEvent(0, wide_label.as_ptr() as *const _, size)
+            };
+        }
+
         {
             profiling::scope!("ID3D12CommandQueue::ExecuteCommandLists");
             self.raw.execute_command_lists(&self.temp_lists);
         }
 
+        if label.is_some() {
+            unsafe { self.raw.EndEvent() };
+        }
+
         if let Some((fence, value)) = signal_fence {
             self.raw
                 .signal(&fence.raw, value)
-                .into_device_result("Signal fence")?;
+                .into_device_result("Signal fence")
+                .map_err(|err| self.report_removal(err))?;
         }
         Ok(())
     }
+    unsafe fn wait_for_fence(
+        &mut self,
+        fence: &Fence,
+        value: crate::FenceValue,
+    ) -> Result<(), crate::DeviceError> {
+        // The queue waits on the GPU, so `value` may be submitted later.
+        unsafe { self.raw.Wait(fence.raw.as_mut_ptr(), value) }
+            .into_device_result("Wait for fence")
+            .map_err(|err| self.report_removal(err))
+    }
     unsafe fn present(
         &mut self,
         surface: &mut Surface,
@@ -856,9 +987,18 @@ impl crate::Queue<Api> for Queue {
         };
 
         profiling::scope!("IDXGISwapchain3::Present");
-        unsafe { sc.raw.Present(interval, flags) };
-
-        Ok(())
+        let hr = unsafe { sc.raw.Present(interval, flags) };
+        sc.occluded = hr == winerror::DXGI_STATUS_OCCLUDED;
+        match hr {
+            winerror::DXGI_ERROR_DEVICE_REMOVED | winerror::DXGI_ERROR_DEVICE_RESET => {
+                unsafe { dred::report(&self.device) };
+                Err(crate::DeviceError::Lost.into())
+            }
+            _ => hr.into_result().map_err(|err| {
+                log::error!("Present failed: {}", err);
+                crate::SurfaceError::Lost
+            }),
+        }
     }
 
     unsafe fn get_timestamp_period(&self) -> f32 {
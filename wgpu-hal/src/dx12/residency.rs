@@ -0,0 +1,249 @@
+use parking_lot::Mutex;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+use winapi::{
+    shared::{dxgi1_4, minwindef::DWORD},
+    um::{d3d12 as d3d12_ty, handleapi, synchapi, winbase},
+};
+
+use crate::auxil::dxgi::result::HResult as _;
+
+/// Video memory usage of a memory segment group, in bytes.
+///
+/// See [`DXGI_QUERY_VIDEO_MEMORY_INFO`](https://learn.microsoft.com/en-us/windows/win32/api/dxgi1_4/ns-dxgi1_4-dxgi_query_video_memory_info).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MemorySegmentInfo {
+    /// How much memory the OS lets the process use before it starts demoting heaps.
+    pub budget: u64,
+    pub current_usage: u64,
+    pub available_for_reservation: u64,
+    pub current_reservation: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MemoryBudget {
+    /// Memory local to the GPU, video memory on discrete adapters.
+    pub local: MemorySegmentInfo,
+    /// System memory visible to the GPU.
+    pub non_local: MemorySegmentInfo,
+}
+
+/// Called from a background thread whenever the OS changes the budget.
+pub type MemoryBudgetCallback = Box<dyn Fn(&MemoryBudget) + Send + Sync>;
+
+struct Adapter3(d3d12::ComPtr<dxgi1_4::IDXGIAdapter3>);
+
+// DXGI adapters are free-threaded.
+unsafe impl Send for Adapter3 {}
+unsafe impl Sync for Adapter3 {}
+
+impl Adapter3 {
+    fn query_budget(&self) -> Option<MemoryBudget> {
+        let query = |group| {
+            let mut info = unsafe { std::mem::zeroed::<dxgi1_4::DXGI_QUERY_VIDEO_MEMORY_INFO>() };
+            unsafe { self.0.QueryVideoMemoryInfo(0, group, &mut info) }
+                .into_result()
+                .map_err(|err| log::warn!("Unable to query video memory info: {}", err))
+                .ok()?;
+            Some(MemorySegmentInfo {
+                budget: info.Budget,
+                current_usage: info.CurrentUsage,
+                available_for_reservation: info.AvailableForReservation,
+                current_reservation: info.CurrentReservation,
+            })
+        };
+        Some(MemoryBudget {
+            local: query(dxgi1_4::DXGI_MEMORY_SEGMENT_GROUP_LOCAL)?,
+            non_local: query(dxgi1_4::DXGI_MEMORY_SEGMENT_GROUP_NON_LOCAL)?,
+        })
+    }
+}
+
+/// Tracks the memory budget of a device and reports its changes.
+pub(super) struct Residency {
+    adapter: Option<Arc<Adapter3>>,
+    notifier: Mutex<Option<BudgetNotifier>>,
+}
+
+impl Residency {
+    pub(super) fn new(adapter: &d3d12::DxgiAdapter) -> Self {
+        Self {
+            adapter: adapter
+                .as_adapter3()
+                .map(|adapter3| Arc::new(Adapter3(adapter3.clone()))),
+            notifier: Mutex::new(None),
+        }
+    }
+}
+
+struct BudgetNotifier {
+    adapter: Arc<Adapter3>,
+    cookie: DWORD,
+    event: d3d12::Event,
+    stop: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Drop for BudgetNotifier {
+    fn drop(&mut self) {
+        unsafe {
+            self.adapter
+                .0
+                .UnregisterVideoMemoryBudgetChangeNotification(self.cookie)
+        };
+        self.stop.store(true, Ordering::Release);
+        unsafe { synchapi::SetEvent(self.event.0) };
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        unsafe { handleapi::CloseHandle(self.event.0) };
+    }
+}
+
+struct SendEvent(d3d12::Event);
+
+unsafe impl Send for SendEvent {}
+
+impl super::Device {
+    /// Returns the current video memory usage and budget of the adapter.
+    ///
+    /// Returns `None` when the adapter doesn't implement `IDXGIAdapter3`.
+    pub fn memory_budget(&self) -> Option<MemoryBudget> {
+        self.residency.adapter.as_ref()?.query_budget()
+    }
+
+    /// Sets the function called whenever the OS changes the memory budget of
+    /// the process, replacing the previous one. `None` stops the notifications.
+    ///
+    /// Applications should react to a shrinking budget by releasing or
+    /// [evicting](Self::evict) resources, otherwise Windows demotes heaps to
+    /// system memory on its own.
+    pub fn set_memory_budget_callback(
+        &self,
+        callback: Option<MemoryBudgetCallback>,
+    ) -> Result<(), crate::DeviceError> {
+        let mut notifier = self.residency.notifier.lock();
+        // Stop the previous notifications first.
+        *notifier = None;
+
+        let (callback, adapter) = match (callback, self.residency.adapter.as_ref()) {
+            (Some(callback), Some(adapter)) => (callback, Arc::clone(adapter)),
+            (Some(_), None) => {
+                log::warn!("Memory budget notifications require IDXGIAdapter3");
+                return Ok(());
+            }
+            (None, _) => return Ok(()),
+        };
+
+        let event = d3d12::Event::create(false, false);
+        let mut cookie: DWORD = 0;
+        unsafe {
+            adapter
+                .0
+                .RegisterVideoMemoryBudgetChangeNotificationEvent(event.0, &mut cookie)
+        }
+        .into_device_result("Budget notification registration")?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let adapter = Arc::clone(&adapter);
+            let stop = Arc::clone(&stop);
+            let event = SendEvent(event);
+            thread::Builder::new()
+                .name("wgpu-hal dx12 memory budget".to_string())
+                .spawn(move || {
+                    let event = event;
+                    loop {
+                        unsafe { synchapi::WaitForSingleObject(event.0 .0, winbase::INFINITE) };
+                        if stop.load(Ordering::Acquire) {
+                            break;
+                        }
+                        if let Some(budget) = adapter.query_budget() {
+                            callback(&budget);
+                        }
+                    }
+                })
+                .map_err(|_| crate::DeviceError::OutOfMemory)?
+        };
+
+        *notifier = Some(BudgetNotifier {
+            adapter,
+            cookie,
+            event,
+            stop,
+            thread: Some(thread),
+        });
+        Ok(())
+    }
+
+    /// Evicts the buffers and textures from video memory, making their
+    /// memory available to other resources until they are
+    /// [made resident](Self::make_resident) again.
+    ///
+    /// Only resources with their own heap can be evicted, suballocated ones
+    /// are skipped.
+    ///
+    /// # Safety
+    ///
+    /// - The resources must not be used by the GPU until made resident again.
+    pub unsafe fn evict(
+        &self,
+        buffers: &[&super::Buffer],
+        textures: &[&super::Texture],
+    ) -> Result<(), crate::DeviceError> {
+        let mut pageables = Self::dedicated_pageables(buffers, textures);
+        if pageables.is_empty() {
+            return Ok(());
+        }
+        unsafe {
+            self.raw
+                .Evict(pageables.len() as u32, pageables.as_mut_ptr())
+        }
+        .into_device_result("Evict")
+    }
+
+    /// Brings evicted buffers and textures back into video memory, blocking
+    /// until they are.
+    ///
+    /// # Safety
+    ///
+    /// - The resources must have been passed to [`Self::evict`].
+    pub unsafe fn make_resident(
+        &self,
+        buffers: &[&super::Buffer],
+        textures: &[&super::Texture],
+    ) -> Result<(), crate::DeviceError> {
+        let mut pageables = Self::dedicated_pageables(buffers, textures);
+        if pageables.is_empty() {
+            return Ok(());
+        }
+        unsafe {
+            self.raw
+                .MakeResident(pageables.len() as u32, pageables.as_mut_ptr())
+        }
+        .into_device_result("MakeResident")
+    }
+
+    fn dedicated_pageables(
+        buffers: &[&super::Buffer],
+        textures: &[&super::Texture],
+    ) -> Vec<*mut d3d12_ty::ID3D12Pageable> {
+        // Placed resources can't be paged on their own, only their whole heap can.
+        buffers
+            .iter()
+            .filter(|buffer| buffer.allocation.is_none())
+            .map(|buffer| buffer.resource.as_mut_ptr() as *mut _)
+            .chain(
+                textures
+                    .iter()
+                    .filter(|texture| texture.allocation.is_none())
+                    .map(|texture| texture.resource.as_mut_ptr() as *mut _),
+            )
+            .collect()
+    }
+}
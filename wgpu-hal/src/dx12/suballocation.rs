@@ -13,7 +13,9 @@ use placed as allocation;
 // and will use the fast path of suballocating buffers and textures using gpu_allocator if
 // the windows_rs feature is enabled.
 
-// This is the fast path using gpu_allocator to suballocate buffers and textures.
+// This is the fast path using gpu_allocator to suballocate buffers and textures
+// into heap blocks per memory location, except for render targets and
+// depth-stencil textures which keep dedicated heaps.
 #[cfg(feature = "windows_rs")]
 mod placed {
     use d3d12::ComPtr;
@@ -120,7 +122,7 @@ mod placed {
     pub(crate) fn create_texture_resource(
         device: &crate::dx12::Device,
         desc: &crate::TextureDescriptor,
-        raw_desc: d3d12_ty::D3D12_RESOURCE_DESC,
+        mut raw_desc: d3d12_ty::D3D12_RESOURCE_DESC,
         resource: &mut ComPtr<ID3D12Resource>,
     ) -> Result<(HRESULT, Option<AllocationWrapper>), crate::DeviceError> {
         // It's a workaround for Intel Xe drivers.
@@ -129,6 +131,25 @@ mod placed {
                 .map(|(hr, _)| (hr, None));
         }
 
+        // Render targets and depth-stencil textures get a dedicated heap, as
+        // drivers may only enable compression for committed resources.
+        if raw_desc.Flags
+            & (d3d12_ty::D3D12_RESOURCE_FLAG_ALLOW_RENDER_TARGET
+                | d3d12_ty::D3D12_RESOURCE_FLAG_ALLOW_DEPTH_STENCIL)
+            != 0
+        {
+            return super::committed::create_texture_resource(device, desc, raw_desc, resource)
+                .map(|(hr, _)| (hr, None));
+        }
+
+        // Small textures can be placed at 4KiB instead of 64KiB boundaries,
+        // the runtime reports whether this one is eligible.
+        raw_desc.Alignment = d3d12_ty::D3D12_SMALL_RESOURCE_PLACEMENT_ALIGNMENT as u64;
+        let small_info = unsafe { device.raw.GetResourceAllocationInfo(0, 1, &raw_desc) };
+        if small_info.Alignment != raw_desc.Alignment {
+            raw_desc.Alignment = 0;
+        }
+
         let location = MemoryLocation::GpuOnly;
 
         let name = desc.label.unwrap_or("Unlabeled texture");
@@ -1,6 +1,12 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
+use winapi::{
+    shared::winerror::HRESULT,
+    um::unknwnbase::{IUnknown, IUnknownVtbl},
+    RIDL,
+};
+
 winapi::ENUM! {
     enum D3D12_VIEW_INSTANCING_TIER {
         D3D12_VIEW_INSTANCING_TIER_NOT_SUPPORTED  = 0,
@@ -32,3 +38,79 @@ winapi::STRUCT! {
         BarycentricsSupported: winapi::shared::minwindef::BOOL,
     }
 }
+
+// Device Removed Extended Data (DRED), from the Windows 10 1903 SDK `d3d12.h`.
+
+winapi::ENUM! {
+    enum D3D12_DRED_ENABLEMENT {
+        D3D12_DRED_ENABLEMENT_SYSTEM_CONTROLLED = 0,
+        D3D12_DRED_ENABLEMENT_FORCED_OFF = 1,
+        D3D12_DRED_ENABLEMENT_FORCED_ON = 2,
+    }
+}
+
+pub type D3D12_AUTO_BREADCRUMB_OP = u32;
+pub type D3D12_DRED_ALLOCATION_TYPE = u32;
+
+winapi::STRUCT! {
+    struct D3D12_AUTO_BREADCRUMB_NODE {
+        pCommandListDebugNameA: *const winapi::ctypes::c_char,
+        pCommandListDebugNameW: *const winapi::ctypes::wchar_t,
+        pCommandQueueDebugNameA: *const winapi::ctypes::c_char,
+        pCommandQueueDebugNameW: *const winapi::ctypes::wchar_t,
+        pCommandList: *mut winapi::um::d3d12::ID3D12GraphicsCommandList,
+        pCommandQueue: *mut winapi::um::d3d12::ID3D12CommandQueue,
+        BreadcrumbCount: u32,
+        pLastBreadcrumbValue: *const u32,
+        pCommandHistory: *const D3D12_AUTO_BREADCRUMB_OP,
+        pNext: *const D3D12_AUTO_BREADCRUMB_NODE,
+    }
+}
+
+winapi::STRUCT! {
+    struct D3D12_DRED_AUTO_BREADCRUMBS_OUTPUT {
+        pHeadAutoBreadcrumbNode: *const D3D12_AUTO_BREADCRUMB_NODE,
+    }
+}
+
+winapi::STRUCT! {
+    struct D3D12_DRED_ALLOCATION_NODE {
+        ObjectNameA: *const winapi::ctypes::c_char,
+        ObjectNameW: *const winapi::ctypes::wchar_t,
+        AllocationType: D3D12_DRED_ALLOCATION_TYPE,
+        pNext: *const D3D12_DRED_ALLOCATION_NODE,
+    }
+}
+
+winapi::STRUCT! {
+    struct D3D12_DRED_PAGE_FAULT_OUTPUT {
+        PageFaultVA: winapi::um::d3d12::D3D12_GPU_VIRTUAL_ADDRESS,
+        pHeadExistingAllocationNode: *const D3D12_DRED_ALLOCATION_NODE,
+        pHeadRecentFreedAllocationNode: *const D3D12_DRED_ALLOCATION_NODE,
+    }
+}
+
+RIDL! {#[uuid(0x82bc481c, 0x6b9b, 0x4030, 0xae, 0xdb, 0x7e, 0xe3, 0xd1, 0xdf, 0x1e, 0x63)]
+interface ID3D12DeviceRemovedExtendedDataSettings(ID3D12DeviceRemovedExtendedDataSettingsVtbl):
+    IUnknown(IUnknownVtbl) {
+    fn SetAutoBreadcrumbsEnablement(
+        Enablement: D3D12_DRED_ENABLEMENT,
+    ) -> (),
+    fn SetPageFaultEnablement(
+        Enablement: D3D12_DRED_ENABLEMENT,
+    ) -> (),
+    fn SetWatsonDumpEnablement(
+        Enablement: D3D12_DRED_ENABLEMENT,
+    ) -> (),
+}}
+
+RIDL! {#[uuid(0x98931d33, 0x5ae8, 0x4791, 0xaa, 0x3c, 0x1a, 0x73, 0xa2, 0x93, 0x4e, 0x71)]
+interface ID3D12DeviceRemovedExtendedData(ID3D12DeviceRemovedExtendedDataVtbl):
+    IUnknown(IUnknownVtbl) {
+    fn GetAutoBreadcrumbsOutput(
+        pOutput: *mut D3D12_DRED_AUTO_BREADCRUMBS_OUTPUT,
+    ) -> HRESULT,
+    fn GetPageFaultAllocationOutput(
+        pOutput: *mut D3D12_DRED_PAGE_FAULT_OUTPUT,
+    ) -> HRESULT,
+}}
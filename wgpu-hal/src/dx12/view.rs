@@ -4,6 +4,34 @@ use winapi::um::d3d12 as d3d12_ty;
 
 pub(crate) const D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING: u32 = 0x1688;
 
+/// Equivalent of the `D3D12_ENCODE_SHADER_4_COMPONENT_MAPPING` macro.
+fn map_swizzle(swizzle: wgt::TextureSwizzle) -> u32 {
+    fn map_component(component: wgt::SwizzleComponent) -> u32 {
+        match component {
+            wgt::SwizzleComponent::R => {
+                d3d12_ty::D3D12_SHADER_COMPONENT_MAPPING_FROM_MEMORY_COMPONENT_0
+            }
+            wgt::SwizzleComponent::G => {
+                d3d12_ty::D3D12_SHADER_COMPONENT_MAPPING_FROM_MEMORY_COMPONENT_1
+            }
+            wgt::SwizzleComponent::B => {
+                d3d12_ty::D3D12_SHADER_COMPONENT_MAPPING_FROM_MEMORY_COMPONENT_2
+            }
+            wgt::SwizzleComponent::A => {
+                d3d12_ty::D3D12_SHADER_COMPONENT_MAPPING_FROM_MEMORY_COMPONENT_3
+            }
+            wgt::SwizzleComponent::Zero => d3d12_ty::D3D12_SHADER_COMPONENT_MAPPING_FORCE_VALUE_0,
+            wgt::SwizzleComponent::One => d3d12_ty::D3D12_SHADER_COMPONENT_MAPPING_FORCE_VALUE_1,
+        }
+    }
+
+    map_component(swizzle.r)
+        | map_component(swizzle.g) << 3
+        | map_component(swizzle.b) << 6
+        | map_component(swizzle.a) << 9
+        | 1 << 12
+}
+
 pub(super) struct ViewDescriptor {
     dimension: wgt::TextureViewDimension,
     pub aspects: crate::FormatAspects,
@@ -14,32 +42,47 @@ pub(super) struct ViewDescriptor {
     array_layer_count: u32,
     mip_level_base: u32,
     mip_level_count: u32,
+    swizzle: wgt::TextureSwizzle,
 }
 
 impl crate::TextureViewDescriptor<'_> {
     pub(super) fn to_internal(&self, texture: &super::Texture) -> ViewDescriptor {
-        let aspects = crate::FormatAspects::new(self.format, self.range.aspect);
+        let aspects = crate::FormatAspects::new(texture.format, self.range.aspect);
+        // Plane views are described by the multi-planar format of the texture.
+        let srv_uav_format = if texture.format.is_multi_planar_format() {
+            auxil::dxgi::conv::map_texture_format_for_srv_uav(texture.format, aspects)
+        } else {
+            auxil::dxgi::conv::map_texture_format_for_srv_uav(self.format, aspects)
+        };
 
         ViewDescriptor {
             dimension: self.dimension,
             aspects,
             rtv_dsv_format: auxil::dxgi::conv::map_texture_format(self.format),
-            srv_uav_format: auxil::dxgi::conv::map_texture_format_for_srv_uav(self.format, aspects),
+            srv_uav_format,
             multisampled: texture.sample_count > 1,
             mip_level_base: self.range.base_mip_level,
             mip_level_count: self.range.mip_level_count.unwrap_or(!0),
             array_layer_base: self.range.base_array_layer,
             array_layer_count: self.range.array_layer_count.unwrap_or(!0),
+            swizzle: self.swizzle,
         }
     }
 }
 
 impl ViewDescriptor {
+    fn plane_slice(&self) -> u32 {
+        match self.aspects {
+            crate::FormatAspects::PLANE_1 => 1,
+            _ => 0,
+        }
+    }
+
     pub(crate) unsafe fn to_srv(&self) -> Option<d3d12_ty::D3D12_SHADER_RESOURCE_VIEW_DESC> {
         let mut desc = d3d12_ty::D3D12_SHADER_RESOURCE_VIEW_DESC {
             Format: self.srv_uav_format?,
             ViewDimension: 0,
-            Shader4ComponentMapping: D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING,
+            Shader4ComponentMapping: map_swizzle(self.swizzle),
             u: unsafe { mem::zeroed() },
         };
 
@@ -79,7 +122,7 @@ impl ViewDescriptor {
                     *desc.u.Texture2D_mut() = d3d12_ty::D3D12_TEX2D_SRV {
                         MostDetailedMip: self.mip_level_base,
                         MipLevels: self.mip_level_count,
-                        PlaneSlice: 0,
+                        PlaneSlice: self.plane_slice(),
                         ResourceMinLODClamp: 0.0,
                     }
                 }
@@ -103,7 +146,7 @@ impl ViewDescriptor {
                         MipLevels: self.mip_level_count,
                         FirstArraySlice: self.array_layer_base,
                         ArraySize: self.array_layer_count,
-                        PlaneSlice: 0,
+                        PlaneSlice: self.plane_slice(),
                         ResourceMinLODClamp: 0.0,
                     }
                 }
@@ -294,6 +337,22 @@ impl ViewDescriptor {
         desc
     }
 
+    /// Describes a render target view of a single depth slice of a 3D view.
+    pub(crate) unsafe fn to_rtv_depth_slice(
+        &self,
+        slice: u32,
+    ) -> d3d12_ty::D3D12_RENDER_TARGET_VIEW_DESC {
+        let mut desc = unsafe { self.to_rtv() };
+        unsafe {
+            *desc.u.Texture3D_mut() = d3d12_ty::D3D12_TEX3D_RTV {
+                MipSlice: self.mip_level_base,
+                FirstWSlice: slice,
+                WSize: 1,
+            }
+        }
+        desc
+    }
+
     pub(crate) unsafe fn to_dsv(&self, read_only: bool) -> d3d12_ty::D3D12_DEPTH_STENCIL_VIEW_DESC {
         let mut desc = d3d12_ty::D3D12_DEPTH_STENCIL_VIEW_DESC {
             Format: self.rtv_dsv_format,
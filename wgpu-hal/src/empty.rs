@@ -1,6 +1,16 @@
+//! A backend that doesn't talk to any GPU.
+//!
+//! It exposes a single CPU adapter whose device accepts every call and does no
+//! rendering. Buffers are backed by host memory, so mapping works and buffer
+//! copies and clears are carried out as soon as they are recorded. Fences are
+//! signaled on submission, so all submitted work completes immediately.
+//!
+//! This makes it possible to exercise `wgpu-core` validation and application
+//! logic in environments without any graphics drivers.
+
 #![allow(unused_variables)]
 
-use std::ops::Range;
+use std::{cell::UnsafeCell, fmt, ops::Range, ptr};
 
 #[derive(Clone)]
 pub struct Api;
@@ -10,6 +20,36 @@ pub struct Encoder;
 #[derive(Debug)]
 pub struct Resource;
 
+/// A buffer whose contents live in host memory.
+pub struct Buffer {
+    storage: Box<[UnsafeCell<u8>]>,
+}
+
+// Access to the contents goes through raw pointers, and it is up to the user
+// to not read and write the same range concurrently, as with a real GPU buffer.
+unsafe impl Sync for Buffer {}
+
+impl fmt::Debug for Buffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Buffer")
+            .field("size", &self.storage.len())
+            .finish()
+    }
+}
+
+impl Buffer {
+    fn ptr(&self, offset: wgt::BufferAddress) -> *mut u8 {
+        assert!(offset <= self.storage.len() as wgt::BufferAddress);
+        // `UnsafeCell<u8>` has the same layout as `u8`.
+        unsafe { (self.storage.as_ptr() as *mut u8).add(offset as usize) }
+    }
+}
+
+#[derive(Debug)]
+pub struct Fence {
+    value: crate::FenceValue,
+}
+
 type DeviceResult<T> = Result<T, crate::DeviceError>;
 
 impl crate::Api for Api {
@@ -22,13 +62,13 @@ impl crate::Api for Api {
     type CommandEncoder = Encoder;
     type CommandBuffer = Resource;
 
-    type Buffer = Resource;
+    type Buffer = Buffer;
     type Texture = Resource;
     type SurfaceTexture = Resource;
     type TextureView = Resource;
     type Sampler = Resource;
     type QuerySet = Resource;
-    type Fence = Resource;
+    type Fence = Fence;
 
     type BindGroupLayout = Resource;
     type BindGroup = Resource;
@@ -36,6 +76,9 @@ impl crate::Api for Api {
     type ShaderModule = Resource;
     type RenderPipeline = Resource;
     type ComputePipeline = Resource;
+    type PipelineCache = Resource;
+
+    type AccelerationStructure = Resource;
 }
 
 impl crate::Instance<Api> for Context {
@@ -51,7 +94,27 @@ impl crate::Instance<Api> for Context {
     }
     unsafe fn destroy_surface(&self, surface: Context) {}
     unsafe fn enumerate_adapters(&self) -> Vec<crate::ExposedAdapter<Api>> {
-        Vec::new()
+        vec![crate::ExposedAdapter {
+            adapter: Context,
+            info: wgt::AdapterInfo {
+                name: String::from("Noop"),
+                vendor: 0,
+                device: 0,
+                device_type: wgt::DeviceType::Cpu,
+                driver: String::from("wgpu"),
+                driver_info: String::new(),
+                backend: wgt::Backend::Empty,
+            },
+            features: wgt::Features::empty(),
+            capabilities: crate::Capabilities {
+                limits: wgt::Limits::default(),
+                alignments: crate::Alignments {
+                    buffer_copy_offset: wgt::BufferSize::new(4).unwrap(),
+                    buffer_copy_pitch: wgt::BufferSize::new(256).unwrap(),
+                },
+                downlevel: wgt::DownlevelCapabilities::default(),
+            },
+        }]
     }
 }
 
@@ -80,8 +143,13 @@ impl crate::Adapter<Api> for Context {
         &self,
         features: wgt::Features,
         _limits: &wgt::Limits,
+        additional_queues: &[wgt::QueueKind],
     ) -> DeviceResult<crate::OpenDevice<Api>> {
-        Err(crate::DeviceError::Lost)
+        Ok(crate::OpenDevice {
+            device: Context,
+            queue: Context,
+            additional_queues: additional_queues.iter().map(|_| Context).collect(),
+        })
     }
     unsafe fn texture_format_capabilities(
         &self,
@@ -103,8 +171,21 @@ impl crate::Queue<Api> for Context {
     unsafe fn submit(
         &mut self,
         command_buffers: &[&Resource],
-        signal_fence: Option<(&mut Resource, crate::FenceValue)>,
+        signal_fence: Option<(&mut Fence, crate::FenceValue)>,
+        label: crate::Label,
+    ) -> DeviceResult<()> {
+        // All the work was done while recording.
+        if let Some((fence, value)) = signal_fence {
+            fence.value = value;
+        }
+        Ok(())
+    }
+    unsafe fn wait_for_fence(
+        &mut self,
+        fence: &Fence,
+        value: crate::FenceValue,
     ) -> DeviceResult<()> {
+        // All the work is done by the time it's submitted.
         Ok(())
     }
     unsafe fn present(
@@ -122,22 +203,34 @@ impl crate::Queue<Api> for Context {
 
 impl crate::Device<Api> for Context {
     unsafe fn exit(self, queue: Context) {}
-    unsafe fn create_buffer(&self, desc: &crate::BufferDescriptor) -> DeviceResult<Resource> {
-        Ok(Resource)
-    }
-    unsafe fn destroy_buffer(&self, buffer: Resource) {}
+    unsafe fn destroy_queue(&self, queue: Context) {}
+    unsafe fn create_buffer(&self, desc: &crate::BufferDescriptor) -> DeviceResult<Buffer> {
+        let size = usize::try_from(desc.size).map_err(|_| crate::DeviceError::OutOfMemory)?;
+        let mut storage = Vec::new();
+        storage
+            .try_reserve_exact(size)
+            .map_err(|_| crate::DeviceError::OutOfMemory)?;
+        storage.resize_with(size, || UnsafeCell::new(0));
+        Ok(Buffer {
+            storage: storage.into_boxed_slice(),
+        })
+    }
+    unsafe fn destroy_buffer(&self, buffer: Buffer) {}
     unsafe fn map_buffer(
         &self,
-        buffer: &Resource,
+        buffer: &Buffer,
         range: crate::MemoryRange,
     ) -> DeviceResult<crate::BufferMapping> {
-        Err(crate::DeviceError::Lost)
+        Ok(crate::BufferMapping {
+            ptr: ptr::NonNull::new(buffer.ptr(range.start)).unwrap(),
+            is_coherent: true,
+        })
     }
-    unsafe fn unmap_buffer(&self, buffer: &Resource) -> DeviceResult<()> {
+    unsafe fn unmap_buffer(&self, buffer: &Buffer) -> DeviceResult<()> {
         Ok(())
     }
-    unsafe fn flush_mapped_ranges<I>(&self, buffer: &Resource, ranges: I) {}
-    unsafe fn invalidate_mapped_ranges<I>(&self, buffer: &Resource, ranges: I) {}
+    unsafe fn flush_mapped_ranges<I>(&self, buffer: &Buffer, ranges: I) {}
+    unsafe fn invalidate_mapped_ranges<I>(&self, buffer: &Buffer, ranges: I) {}
 
     unsafe fn create_texture(&self, desc: &crate::TextureDescriptor) -> DeviceResult<Resource> {
         Ok(Resource)
@@ -208,6 +301,16 @@ impl crate::Device<Api> for Context {
         Ok(Resource)
     }
     unsafe fn destroy_compute_pipeline(&self, pipeline: Resource) {}
+    unsafe fn create_pipeline_cache(
+        &self,
+        desc: &crate::PipelineCacheDescriptor,
+    ) -> DeviceResult<Resource> {
+        Ok(Resource)
+    }
+    unsafe fn destroy_pipeline_cache(&self, cache: Resource) {}
+    unsafe fn pipeline_cache_get_data(&self, cache: &Resource) -> Option<Vec<u8>> {
+        None
+    }
 
     unsafe fn create_query_set(
         &self,
@@ -216,30 +319,55 @@ impl crate::Device<Api> for Context {
         Ok(Resource)
     }
     unsafe fn destroy_query_set(&self, set: Resource) {}
-    unsafe fn create_fence(&self) -> DeviceResult<Resource> {
-        Ok(Resource)
+    unsafe fn create_fence(&self) -> DeviceResult<Fence> {
+        Ok(Fence { value: 0 })
     }
-    unsafe fn destroy_fence(&self, fence: Resource) {}
-    unsafe fn get_fence_value(&self, fence: &Resource) -> DeviceResult<crate::FenceValue> {
-        Ok(0)
+    unsafe fn destroy_fence(&self, fence: Fence) {}
+    unsafe fn get_fence_value(&self, fence: &Fence) -> DeviceResult<crate::FenceValue> {
+        Ok(fence.value)
     }
     unsafe fn wait(
         &self,
-        fence: &Resource,
+        fence: &Fence,
         value: crate::FenceValue,
         timeout_ms: u32,
     ) -> DeviceResult<bool> {
-        Ok(true)
+        // Nothing is ever in flight, so a value that hasn't been signaled yet never will be.
+        Ok(fence.value >= value)
     }
 
     unsafe fn start_capture(&self) -> bool {
         false
     }
     unsafe fn stop_capture(&self) {}
+
+    unsafe fn allocator_report(&self) -> Option<crate::AllocatorReport> {
+        None
+    }
+
+    unsafe fn create_acceleration_structure(
+        &self,
+        desc: &crate::AccelerationStructureDescriptor,
+    ) -> DeviceResult<Resource> {
+        Ok(Resource)
+    }
+    unsafe fn get_acceleration_structure_build_sizes(
+        &self,
+        desc: &crate::GetAccelerationStructureBuildSizesDescriptor<Api>,
+    ) -> crate::AccelerationStructureBuildSizes {
+        Default::default()
+    }
+    unsafe fn get_acceleration_structure_device_address(
+        &self,
+        acceleration_structure: &Resource,
+    ) -> wgt::BufferAddress {
+        0
+    }
+    unsafe fn destroy_acceleration_structure(&self, acceleration_structure: Resource) {}
 }
 
 impl crate::CommandEncoder<Api> for Encoder {
-    unsafe fn begin_encoding(&mut self, label: crate::Label) -> DeviceResult<()> {
+    unsafe fn begin_encoding(&mut self, label: crate::Label, _reusable: bool) -> DeviceResult<()> {
         Ok(())
     }
     unsafe fn discard_encoding(&mut self) {}
@@ -260,9 +388,33 @@ impl crate::CommandEncoder<Api> for Encoder {
     {
     }
 
-    unsafe fn clear_buffer(&mut self, buffer: &Resource, range: crate::MemoryRange) {}
+    unsafe fn clear_buffer(&mut self, buffer: &Buffer, range: crate::MemoryRange) {
+        unsafe {
+            ptr::write_bytes(
+                buffer.ptr(range.start),
+                0,
+                (range.end - range.start) as usize,
+            )
+        };
+    }
 
-    unsafe fn copy_buffer_to_buffer<T>(&mut self, src: &Resource, dst: &Resource, regions: T) {}
+    unsafe fn copy_buffer_to_buffer<T>(&mut self, src: &Buffer, dst: &Buffer, regions: T)
+    where
+        T: Iterator<Item = crate::BufferCopy>,
+    {
+        for copy in regions {
+            let size = copy.size.get();
+            assert!(copy.src_offset + size <= src.storage.len() as wgt::BufferAddress);
+            assert!(copy.dst_offset + size <= dst.storage.len() as wgt::BufferAddress);
+            unsafe {
+                ptr::copy(
+                    src.ptr(copy.src_offset),
+                    dst.ptr(copy.dst_offset),
+                    size as usize,
+                )
+            };
+        }
+    }
 
     #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
     unsafe fn copy_external_image_to_texture<T>(
@@ -285,13 +437,13 @@ impl crate::CommandEncoder<Api> for Encoder {
     ) {
     }
 
-    unsafe fn copy_buffer_to_texture<T>(&mut self, src: &Resource, dst: &Resource, regions: T) {}
+    unsafe fn copy_buffer_to_texture<T>(&mut self, src: &Buffer, dst: &Resource, regions: T) {}
 
     unsafe fn copy_texture_to_buffer<T>(
         &mut self,
         src: &Resource,
         src_usage: crate::TextureUses,
-        dst: &Resource,
+        dst: &Buffer,
         regions: T,
     ) {
     }
@@ -304,7 +456,7 @@ impl crate::CommandEncoder<Api> for Encoder {
         &mut self,
         set: &Resource,
         range: Range<u32>,
-        buffer: &Resource,
+        buffer: &Buffer,
         offset: wgt::BufferAddress,
         stride: wgt::BufferSize,
     ) {
@@ -323,6 +475,14 @@ impl crate::CommandEncoder<Api> for Encoder {
         dynamic_offsets: &[wgt::DynamicOffset],
     ) {
     }
+    unsafe fn set_bind_group_dynamic_offsets(
+        &mut self,
+        layout: &Resource,
+        index: u32,
+        group: &Resource,
+        dynamic_offsets: &[wgt::DynamicOffset],
+    ) {
+    }
     unsafe fn set_push_constants(
         &mut self,
         layout: &Resource,
@@ -348,8 +508,18 @@ impl crate::CommandEncoder<Api> for Encoder {
     }
     unsafe fn set_viewport(&mut self, rect: &crate::Rect<f32>, depth_range: Range<f32>) {}
     unsafe fn set_scissor_rect(&mut self, rect: &crate::Rect<u32>) {}
+    unsafe fn set_viewport_indexed(
+        &mut self,
+        index: u32,
+        rect: &crate::Rect<f32>,
+        depth_range: Range<f32>,
+    ) {
+    }
+    unsafe fn set_scissor_rect_indexed(&mut self, index: u32, rect: &crate::Rect<u32>) {}
     unsafe fn set_stencil_reference(&mut self, value: u32) {}
     unsafe fn set_blend_constants(&mut self, color: &[f32; 4]) {}
+    unsafe fn set_shading_rate(&mut self, rate: wgt::ShadingRate) {}
+    unsafe fn set_depth_bounds(&mut self, bounds: Range<f32>) {}
 
     unsafe fn draw(
         &mut self,
@@ -370,32 +540,32 @@ impl crate::CommandEncoder<Api> for Encoder {
     }
     unsafe fn draw_indirect(
         &mut self,
-        buffer: &Resource,
+        buffer: &Buffer,
         offset: wgt::BufferAddress,
         draw_count: u32,
     ) {
     }
     unsafe fn draw_indexed_indirect(
         &mut self,
-        buffer: &Resource,
+        buffer: &Buffer,
         offset: wgt::BufferAddress,
         draw_count: u32,
     ) {
     }
     unsafe fn draw_indirect_count(
         &mut self,
-        buffer: &Resource,
+        buffer: &Buffer,
         offset: wgt::BufferAddress,
-        count_buffer: &Resource,
+        count_buffer: &Buffer,
         count_offset: wgt::BufferAddress,
         max_count: u32,
     ) {
     }
     unsafe fn draw_indexed_indirect_count(
         &mut self,
-        buffer: &Resource,
+        buffer: &Buffer,
         offset: wgt::BufferAddress,
-        count_buffer: &Resource,
+        count_buffer: &Buffer,
         count_offset: wgt::BufferAddress,
         max_count: u32,
     ) {
@@ -409,5 +579,16 @@ impl crate::CommandEncoder<Api> for Encoder {
     unsafe fn set_compute_pipeline(&mut self, pipeline: &Resource) {}
 
     unsafe fn dispatch(&mut self, count: [u32; 3]) {}
-    unsafe fn dispatch_indirect(&mut self, buffer: &Resource, offset: wgt::BufferAddress) {}
+    unsafe fn dispatch_indirect(&mut self, buffer: &Buffer, offset: wgt::BufferAddress) {}
+
+    unsafe fn build_acceleration_structures(
+        &mut self,
+        descriptors: &[crate::BuildAccelerationStructureDescriptor<Api>],
+    ) {
+    }
+    unsafe fn place_acceleration_structure_barrier(
+        &mut self,
+        barrier: crate::AccelerationStructureBarrier,
+    ) {
+    }
 }
@@ -8,6 +8,7 @@ use crate::auxil::db;
 
 const GL_UNMASKED_VENDOR_WEBGL: u32 = 0x9245;
 const GL_UNMASKED_RENDERER_WEBGL: u32 = 0x9246;
+const GL_CONTEXT_ROBUST_ACCESS: u32 = 0x90F3;
 
 impl super::Adapter {
     /// According to the OpenGL specification, the version information is
@@ -124,18 +125,26 @@ impl super::Adapter {
             "v3d",
             "apple m", // all apple m are integrated
         ];
-        let strings_that_imply_cpu = ["mesa offscreen", "swiftshader", "llvmpipe"];
+        let strings_that_imply_cpu = [
+            "mesa offscreen",
+            "swiftshader",
+            "llvmpipe",
+            "softpipe",
+            "microsoft basic render driver", // WARP, e.g. through ANGLE
+        ];
 
+        // Software renderers are checked first, as wrappers like ANGLE or Zink include the
+        // name of the underlying device, which may also match the integrated GPU strings.
         //TODO: handle Intel Iris XE as discreet
-        let inferred_device_type = if vendor.contains("qualcomm")
+        let inferred_device_type = if strings_that_imply_cpu.iter().any(|&s| renderer.contains(s)) {
+            wgt::DeviceType::Cpu
+        } else if vendor.contains("qualcomm")
             || vendor.contains("intel")
             || strings_that_imply_integrated
                 .iter()
                 .any(|&s| renderer.contains(s))
         {
             wgt::DeviceType::IntegratedGpu
-        } else if strings_that_imply_cpu.iter().any(|&s| renderer.contains(s)) {
-            wgt::DeviceType::Cpu
         } else {
             // At this point the Device type is Unknown.
             // It's most likely DiscreteGpu, but we do not know for sure.
@@ -179,6 +188,53 @@ impl super::Adapter {
         }
     }
 
+    /// Detect the driver workarounds needed by a context, based on its vendor
+    /// and renderer strings.
+    ///
+    /// This is the one place where driver quirks are accumulated; the device and
+    /// queue code only ever consult the resulting [`super::Workarounds`].
+    fn make_workarounds(
+        vendor: &str,
+        renderer: &str,
+        context_is_owned: bool,
+    ) -> super::Workarounds {
+        let mut workarounds = super::Workarounds::empty();
+
+        workarounds.set(
+            super::Workarounds::EMULATE_BUFFER_MAP,
+            cfg!(target_arch = "wasm32"),
+        );
+
+        let v = vendor.to_lowercase();
+        let r = renderer.to_lowercase();
+        // Check for Mesa sRGB clear bug. See
+        // [`super::Workarounds::MESA_I915_SRGB_SHADER_CLEAR`].
+        if context_is_owned
+            && r.contains("mesa")
+            && r.contains("intel")
+            && r.split(&[' ', '(', ')'][..])
+                .any(|substr| substr.len() == 3 && substr.chars().nth(2) == Some('l'))
+        {
+            log::warn!(
+                "Detected skylake derivative running on mesa i915. Clears to srgb textures will \
+                use manual shader clears."
+            );
+            workarounds.insert(super::Workarounds::MESA_I915_SRGB_SHADER_CLEAR);
+        }
+
+        if r.contains("adreno") || v.contains("qualcomm") {
+            log::info!("Detected Adreno driver. Flushing the context after buffer unmaps.");
+            workarounds.insert(super::Workarounds::FLUSH_AFTER_UNMAP);
+        }
+
+        if r.contains("mali") {
+            log::warn!("Detected Mali driver. Storage buffers in the vertex stage are disabled.");
+            workarounds.insert(super::Workarounds::BROKEN_VERTEX_STORAGE);
+        }
+
+        workarounds
+    }
+
     pub(super) unsafe fn expose(
         context: super::AdapterContext,
     ) -> Option<crate::ExposedAdapter<super::Api>> {
@@ -224,6 +280,8 @@ impl super::Adapter {
             return None;
         }
 
+        let workarounds = Self::make_workarounds(&vendor, &renderer, context.is_owned());
+
         let supports_storage = ver >= (3, 1);
         let supports_work_group_params = ver >= (3, 1);
 
@@ -312,7 +370,8 @@ impl super::Adapter {
             wgt::DownlevelFlags::VERTEX_STORAGE,
             max_storage_block_size != 0
                 && max_storage_buffers_per_shader_stage != 0
-                && (vertex_shader_storage_blocks != 0 || vertex_ssbo_false_zero),
+                && (vertex_shader_storage_blocks != 0 || vertex_ssbo_false_zero)
+                && !workarounds.contains(super::Workarounds::BROKEN_VERTEX_STORAGE),
         );
         downlevel_flags.set(wgt::DownlevelFlags::FRAGMENT_STORAGE, supports_storage);
         if extensions.contains("EXT_texture_filter_anisotropic") {
@@ -341,24 +400,47 @@ impl super::Adapter {
             wgt::DownlevelFlags::MULTISAMPLED_SHADING,
             ver >= (3, 2) || extensions.contains("OES_sample_variables"),
         );
+        // ES can only read back depth and stencil through `glReadPixels`
+        // with this extension, which also covers the individual aspects.
+        downlevel_flags.set(
+            wgt::DownlevelFlags::DEPTH_TEXTURE_AND_BUFFER_COPIES,
+            extensions.contains("GL_NV_read_depth_stencil"),
+        );
 
         let mut features = wgt::Features::empty()
             | wgt::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
             | wgt::Features::CLEAR_TEXTURE
             | wgt::Features::PUSH_CONSTANTS;
         features.set(
-            wgt::Features::ADDRESS_MODE_CLAMP_TO_BORDER | wgt::Features::ADDRESS_MODE_CLAMP_TO_ZERO,
+            wgt::Features::ADDRESS_MODE_CLAMP_TO_BORDER
+                | wgt::Features::ADDRESS_MODE_CLAMP_TO_ZERO
+                | wgt::Features::ADDRESS_MODE_CLAMP_TO_BORDER_CUSTOM,
             extensions.contains("GL_EXT_texture_border_clamp"),
         );
         features.set(
             wgt::Features::DEPTH_CLIP_CONTROL,
             extensions.contains("GL_EXT_depth_clamp"),
         );
+        features.set(
+            wgt::Features::DUAL_SOURCE_BLENDING,
+            extensions.contains("GL_EXT_blend_func_extended"),
+        );
+        features.set(
+            wgt::Features::FLOAT32_FILTERABLE,
+            extensions.contains("OES_texture_float_linear"),
+        );
         features.set(
             wgt::Features::VERTEX_WRITABLE_STORAGE,
             downlevel_flags.contains(wgt::DownlevelFlags::VERTEX_STORAGE)
                 && vertex_shader_storage_textures != 0,
         );
+        // WebGL2 always bounds buffer accesses. Elsewhere we need a robust context,
+        // which we ask for in `egl.rs`, and a guarantee covering buffers.
+        let robust_buffer_access = cfg!(target_arch = "wasm32")
+            || (extensions.contains("GL_KHR_robust_buffer_access_behavior")
+                && (ver >= (3, 2) || extensions.contains("GL_EXT_robustness"))
+                && unsafe { gl.get_parameter_i32(GL_CONTEXT_ROBUST_ACCESS) } != 0);
+        features.set(wgt::Features::ROBUST_BUFFER_ACCESS, robust_buffer_access);
         features.set(
             wgt::Features::MULTIVIEW,
             extensions.contains("OVR_multiview2"),
@@ -368,6 +450,33 @@ impl super::Adapter {
             ver >= (3, 2) || extensions.contains("OES_geometry_shader"),
         );
         features.set(wgt::Features::SHADER_EARLY_DEPTH_TEST, ver >= (3, 1));
+        // The first instance of direct draws is emulated by offsetting the instance
+        // attributes, which isn't possible for indirect draws.
+        features.set(
+            wgt::Features::INDIRECT_FIRST_INSTANCE,
+            ver >= (3, 1) && extensions.contains("GL_EXT_base_instance"),
+        );
+        // Command buffers are replayed on the CPU, so they can be replayed again.
+        features.insert(wgt::Features::REUSABLE_COMMAND_BUFFERS);
+        features.insert(wgt::Features::UNINITIALIZED_WORKGROUP_MEMORY);
+        // Texture swizzle is core in GLES 3.0, but WebGL 2 dropped it.
+        features.set(
+            wgt::Features::TEXTURE_COMPONENT_SWIZZLE,
+            !cfg!(target_arch = "wasm32"),
+        );
+        let gles_s3tc_exts = [
+            "GL_EXT_texture_compression_s3tc",
+            "GL_EXT_texture_compression_s3tc_srgb",
+        ];
+        let webgl_s3tc_exts = [
+            "WEBGL_compressed_texture_s3tc",
+            "WEBGL_compressed_texture_s3tc_srgb",
+        ];
+        let s3tc_exts = if cfg!(target_arch = "wasm32") {
+            &webgl_s3tc_exts[..]
+        } else {
+            &gles_s3tc_exts[..]
+        };
         let gles_bcn_exts = [
             "GL_EXT_texture_compression_s3tc_srgb",
             "GL_EXT_texture_compression_rgtc",
@@ -384,9 +493,11 @@ impl super::Adapter {
         } else {
             &gles_bcn_exts[..]
         };
+        let s3tc = s3tc_exts.iter().all(|&ext| extensions.contains(ext));
+        features.set(wgt::Features::TEXTURE_COMPRESSION_S3TC, s3tc);
         features.set(
             wgt::Features::TEXTURE_COMPRESSION_BC,
-            bcn_exts.iter().all(|&ext| extensions.contains(ext)),
+            s3tc && bcn_exts.iter().all(|&ext| extensions.contains(ext)),
         );
         features.set(
             wgt::Features::TEXTURE_COMPRESSION_ETC2,
@@ -443,6 +554,12 @@ impl super::Adapter {
             extensions.contains("GL_EXT_texture_shadow_lod"),
         );
         private_caps.set(super::PrivateCapabilities::MEMORY_BARRIERS, ver >= (3, 1));
+        private_caps.set(super::PrivateCapabilities::IMAGE_LOAD_STORE, ver >= (3, 1));
+        // ES 3.0 requires integer renderbuffers to have zero samples.
+        private_caps.set(
+            super::PrivateCapabilities::INTEGER_MULTISAMPLE,
+            ver >= (3, 1),
+        );
         private_caps.set(
             super::PrivateCapabilities::VERTEX_BUFFER_LAYOUT,
             ver >= (3, 1),
@@ -579,30 +696,18 @@ impl super::Adapter {
             max_non_sampler_bindings: std::u32::MAX,
         };
 
-        let mut workarounds = super::Workarounds::empty();
-
-        workarounds.set(
-            super::Workarounds::EMULATE_BUFFER_MAP,
-            cfg!(target_arch = "wasm32"),
-        );
+        let downlevel_defaults = wgt::DownlevelLimits {};
 
-        let r = renderer.to_lowercase();
-        // Check for Mesa sRGB clear bug. See
-        // [`super::PrivateCapabilities::MESA_I915_SRGB_SHADER_CLEAR`].
-        if context.is_owned()
-            && r.contains("mesa")
-            && r.contains("intel")
-            && r.split(&[' ', '(', ')'][..])
-                .any(|substr| substr.len() == 3 && substr.chars().nth(2) == Some('l'))
-        {
-            log::warn!(
-                "Detected skylake derivative running on mesa i915. Clears to srgb textures will \
-                use manual shader clears."
-            );
-            workarounds.set(super::Workarounds::MESA_I915_SRGB_SHADER_CLEAR, true);
+        // Prefer the real PCI IDs over the ones guessed from the strings, where EGL can find them.
+        let mut info = Self::make_info(vendor, renderer);
+        if let Some((vendor_id, device_id)) = context.pci_ids() {
+            info.vendor = vendor_id;
+            info.device = device_id;
         }
-
-        let downlevel_defaults = wgt::DownlevelLimits {};
+        if let Some(driver) = context.driver_name() {
+            info.driver = driver;
+        }
+        info.driver_info = version;
 
         // Drop the GL guard so we can move the context into AdapterShared
         // ( on Wasm the gl handle is just a ref so we tell clippy to allow
@@ -623,7 +728,7 @@ impl super::Adapter {
                     program_cache: Default::default(),
                 }),
             },
-            info: Self::make_info(vendor, renderer),
+            info,
             features,
             capabilities: crate::Capabilities {
                 limits,
@@ -669,7 +774,12 @@ impl crate::Adapter<super::Api> for super::Adapter {
         &self,
         features: wgt::Features,
         _limits: &wgt::Limits,
+        additional_queues: &[wgt::QueueKind],
     ) -> Result<crate::OpenDevice<super::Api>, crate::DeviceError> {
+        if !additional_queues.is_empty() {
+            log::error!("Additional queues are not supported on GLES");
+            return Err(crate::DeviceError::ResourceCreationFailed);
+        }
         let gl = &self.shared.context.lock();
         unsafe { gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1) };
         unsafe { gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1) };
@@ -684,9 +794,16 @@ impl crate::Adapter<super::Api> for super::Adapter {
         unsafe { gl.buffer_data_u8_slice(glow::COPY_READ_BUFFER, &zeroes, glow::STATIC_DRAW) };
 
         // Compile the shader program we use for doing manual clears to work around Mesa fastclear
-        // bug.
-        let (shader_clear_program, shader_clear_program_color_uniform_location) =
-            unsafe { Self::create_shader_clear_program(gl) };
+        // bug, only on the devices that need it.
+        let shader_clear_program = if self
+            .shared
+            .workarounds
+            .contains(super::Workarounds::MESA_I915_SRGB_SHADER_CLEAR)
+        {
+            Some(unsafe { Self::create_shader_clear_program(gl) })
+        } else {
+            None
+        };
 
         Ok(crate::OpenDevice {
             device: super::Device {
@@ -703,12 +820,12 @@ impl crate::Adapter<super::Api> for super::Adapter {
                 copy_fbo: unsafe { gl.create_framebuffer() }
                     .map_err(|_| crate::DeviceError::OutOfMemory)?,
                 shader_clear_program,
-                shader_clear_program_color_uniform_location,
                 zero_buffer,
                 temp_query_results: Vec::new(),
                 draw_buffer_count: 1,
                 current_index_buffer: None,
             },
+            additional_queues: Vec::new(),
         })
     }
 
@@ -719,13 +836,8 @@ impl crate::Adapter<super::Api> for super::Adapter {
         use crate::TextureFormatCapabilities as Tfc;
         use wgt::TextureFormat as Tf;
 
-        let sample_count = {
-            let max_samples = unsafe {
-                self.shared
-                    .context
-                    .lock()
-                    .get_parameter_i32(glow::MAX_SAMPLES)
-            };
+        let gl = self.shared.context.lock();
+        let sample_count_fn = |max_samples: i32| {
             if max_samples >= 16 {
                 Tfc::MULTISAMPLE_X2
                     | Tfc::MULTISAMPLE_X4
@@ -733,14 +845,31 @@ impl crate::Adapter<super::Api> for super::Adapter {
                     | Tfc::MULTISAMPLE_X16
             } else if max_samples >= 8 {
                 Tfc::MULTISAMPLE_X2 | Tfc::MULTISAMPLE_X4 | Tfc::MULTISAMPLE_X8
-            } else {
-                // The lowest supported level in GLE3.0/WebGL2 is 4X
-                // (see GL_MAX_SAMPLES in https://registry.khronos.org/OpenGL-Refpages/es3.0/html/glGet.xhtml).
-                // On some platforms, like iOS Safari, `get_parameter_i32(MAX_SAMPLES)` returns 0,
-                // so we always fall back to supporting 4x here.
+            } else if max_samples >= 4 {
                 Tfc::MULTISAMPLE_X2 | Tfc::MULTISAMPLE_X4
+            } else if max_samples >= 2 {
+                Tfc::MULTISAMPLE_X2
+            } else {
+                Tfc::empty()
             }
         };
+        // The lowest supported level in GLE3.0/WebGL2 is 4X
+        // (see GL_MAX_SAMPLES in https://registry.khronos.org/OpenGL-Refpages/es3.0/html/glGet.xhtml).
+        // On some platforms, like iOS Safari, `get_parameter_i32(MAX_SAMPLES)` returns 0,
+        // so we always fall back to supporting 4x here.
+        let sample_count =
+            sample_count_fn(unsafe { gl.get_parameter_i32(glow::MAX_SAMPLES) }.max(4));
+        // Integer formats have their own, possibly lower, limit.
+        let integer_sample_count = if self
+            .shared
+            .private_caps
+            .contains(super::PrivateCapabilities::INTEGER_MULTISAMPLE)
+        {
+            sample_count_fn(unsafe { gl.get_parameter_i32(glow::MAX_INTEGER_SAMPLES) })
+        } else {
+            Tfc::empty()
+        };
+        drop(gl);
 
         // Base types are pulled from the table in the OpenGLES 3.0 spec in section 3.8.
         //
@@ -753,8 +882,8 @@ impl crate::Adapter<super::Api> for super::Adapter {
         let filterable = unfilterable | Tfc::SAMPLED_LINEAR;
         let renderable =
             unfilterable | Tfc::COLOR_ATTACHMENT | sample_count | Tfc::MULTISAMPLE_RESOLVE;
+        let integer_renderable = unfilterable | Tfc::COLOR_ATTACHMENT | integer_sample_count;
         let filterable_renderable = filterable | renderable | Tfc::COLOR_ATTACHMENT_BLEND;
-        let storage = base | Tfc::STORAGE | Tfc::STORAGE_READ_WRITE;
 
         let feature_fn = |f, caps| {
             if self.shared.features.contains(f) {
@@ -764,6 +893,7 @@ impl crate::Adapter<super::Api> for super::Adapter {
             }
         };
 
+        let s3tc_features = feature_fn(wgt::Features::TEXTURE_COMPRESSION_S3TC, filterable);
         let bcn_features = feature_fn(wgt::Features::TEXTURE_COMPRESSION_BC, filterable);
         let etc2_features = feature_fn(wgt::Features::TEXTURE_COMPRESSION_ETC2, filterable);
         let astc_features = feature_fn(wgt::Features::TEXTURE_COMPRESSION_ASTC, filterable);
@@ -793,48 +923,53 @@ impl crate::Adapter<super::Api> for super::Adapter {
                 | Tfc::MULTISAMPLE_RESOLVE,
         );
 
+        let storage = private_caps_fn(
+            super::PrivateCapabilities::IMAGE_LOAD_STORE,
+            base | Tfc::STORAGE | Tfc::STORAGE_READ_WRITE,
+        );
+
         let texture_float_linear =
             private_caps_fn(super::PrivateCapabilities::TEXTURE_FLOAT_LINEAR, filterable);
 
-        match format {
+        let caps = match format {
             Tf::R8Unorm => filterable_renderable,
             Tf::R8Snorm => filterable,
-            Tf::R8Uint => renderable,
-            Tf::R8Sint => renderable,
-            Tf::R16Uint => renderable,
-            Tf::R16Sint => renderable,
+            Tf::R8Uint => integer_renderable,
+            Tf::R8Sint => integer_renderable,
+            Tf::R16Uint => integer_renderable,
+            Tf::R16Sint => integer_renderable,
             Tf::R16Unorm => empty,
             Tf::R16Snorm => empty,
             Tf::R16Float => filterable | half_float_renderable,
             Tf::Rg8Unorm => filterable_renderable,
             Tf::Rg8Snorm => filterable,
-            Tf::Rg8Uint => renderable,
-            Tf::Rg8Sint => renderable,
-            Tf::R32Uint => renderable | storage,
-            Tf::R32Sint => renderable | storage,
+            Tf::Rg8Uint => integer_renderable,
+            Tf::Rg8Sint => integer_renderable,
+            Tf::R32Uint => integer_renderable | storage,
+            Tf::R32Sint => integer_renderable | storage,
             Tf::R32Float => unfilterable | storage | float_renderable | texture_float_linear,
-            Tf::Rg16Uint => renderable,
-            Tf::Rg16Sint => renderable,
+            Tf::Rg16Uint => integer_renderable,
+            Tf::Rg16Sint => integer_renderable,
             Tf::Rg16Unorm => empty,
             Tf::Rg16Snorm => empty,
             Tf::Rg16Float => filterable | half_float_renderable,
             Tf::Rgba8Unorm | Tf::Rgba8UnormSrgb => filterable_renderable | storage,
             Tf::Bgra8Unorm | Tf::Bgra8UnormSrgb => filterable_renderable,
             Tf::Rgba8Snorm => filterable,
-            Tf::Rgba8Uint => renderable | storage,
-            Tf::Rgba8Sint => renderable | storage,
+            Tf::Rgba8Uint => integer_renderable | storage,
+            Tf::Rgba8Sint => integer_renderable | storage,
             Tf::Rgb10a2Unorm => filterable_renderable,
             Tf::Rg11b10Float => filterable | float_renderable,
-            Tf::Rg32Uint => renderable,
-            Tf::Rg32Sint => renderable,
+            Tf::Rg32Uint => integer_renderable,
+            Tf::Rg32Sint => integer_renderable,
             Tf::Rg32Float => unfilterable | float_renderable | texture_float_linear,
-            Tf::Rgba16Uint => renderable | storage,
-            Tf::Rgba16Sint => renderable | storage,
+            Tf::Rgba16Uint => integer_renderable | storage,
+            Tf::Rgba16Sint => integer_renderable | storage,
             Tf::Rgba16Unorm => empty,
             Tf::Rgba16Snorm => empty,
             Tf::Rgba16Float => filterable | storage | half_float_renderable,
-            Tf::Rgba32Uint => renderable | storage,
-            Tf::Rgba32Sint => renderable | storage,
+            Tf::Rgba32Uint => integer_renderable | storage,
+            Tf::Rgba32Sint => integer_renderable | storage,
             Tf::Rgba32Float => unfilterable | storage | float_renderable | texture_float_linear,
             Tf::Stencil8
             | Tf::Depth16Unorm
@@ -842,14 +977,15 @@ impl crate::Adapter<super::Api> for super::Adapter {
             | Tf::Depth32FloatStencil8
             | Tf::Depth24Plus
             | Tf::Depth24PlusStencil8 => depth,
+            Tf::NV12 => empty,
             Tf::Rgb9e5Ufloat => filterable,
             Tf::Bc1RgbaUnorm
             | Tf::Bc1RgbaUnormSrgb
             | Tf::Bc2RgbaUnorm
             | Tf::Bc2RgbaUnormSrgb
             | Tf::Bc3RgbaUnorm
-            | Tf::Bc3RgbaUnormSrgb
-            | Tf::Bc4RUnorm
+            | Tf::Bc3RgbaUnormSrgb => s3tc_features,
+            Tf::Bc4RUnorm
             | Tf::Bc4RSnorm
             | Tf::Bc5RgUnorm
             | Tf::Bc5RgSnorm
@@ -875,6 +1011,63 @@ impl crate::Adapter<super::Api> for super::Adapter {
                 block: _,
                 channel: AstcChannel::Hdr,
             } => astc_hdr_features,
+        };
+
+        // The table above is an upper bound. Narrow the rendering capabilities down to what the
+        // driver reports for the format; `glGetInternalformativ` is core in GLES 3.0, which
+        // every adapter requires.
+        let attachment = Tfc::COLOR_ATTACHMENT | Tfc::DEPTH_STENCIL_ATTACHMENT;
+        if !caps.intersects(attachment) {
+            return caps;
+        }
+        let multisample = Tfc::MULTISAMPLE_X2
+            | Tfc::MULTISAMPLE_X4
+            | Tfc::MULTISAMPLE_X8
+            | Tfc::MULTISAMPLE_X16
+            | Tfc::MULTISAMPLE_RESOLVE;
+        let internal_format = self.shared.describe_texture_format(format).internal;
+        let gl = self.shared.context.lock();
+        // Clear the errors left by earlier calls. Some implementations keep reporting
+        // `CONTEXT_LOST` once the context is lost, so don't wait for `NO_ERROR` forever.
+        for _ in 0..8 {
+            match unsafe { gl.get_error() } {
+                glow::NO_ERROR | glow::CONTEXT_LOST => break,
+                _ => {}
+            }
+        }
+        let samples = unsafe { self.shared.context.internal_format_samples(internal_format) };
+        let renderable = unsafe { gl.get_error() } != glow::INVALID_ENUM;
+        drop(gl);
+
+        match samples {
+            Some(_) if !renderable => {
+                caps - (attachment | Tfc::COLOR_ATTACHMENT_BLEND | multisample)
+            }
+            Some(samples) => {
+                let mut sample_count = samples.into_iter().fold(Tfc::empty(), |caps, count| {
+                    caps | match count {
+                        2 => Tfc::MULTISAMPLE_X2,
+                        4 => Tfc::MULTISAMPLE_X4,
+                        8 => Tfc::MULTISAMPLE_X8,
+                        16 => Tfc::MULTISAMPLE_X16,
+                        _ => Tfc::empty(),
+                    }
+                });
+                // Keep the 4x fallback for drivers that under-report, see `sample_count`.
+                if !matches!(
+                    format.sample_type(None),
+                    Some(wgt::TextureSampleType::Uint | wgt::TextureSampleType::Sint)
+                ) {
+                    sample_count |= Tfc::MULTISAMPLE_X4;
+                }
+                sample_count &= caps;
+                if sample_count.is_empty() {
+                    caps - multisample
+                } else {
+                    (caps - multisample) | sample_count | (caps & Tfc::MULTISAMPLE_RESOLVE)
+                }
+            }
+            None => caps,
         }
     }
 
@@ -893,7 +1086,7 @@ impl crate::Adapter<super::Api> for super::Adapter {
                     wgt::TextureFormat::Rgba8UnormSrgb,
                     #[cfg(not(target_arch = "wasm32"))]
                     wgt::TextureFormat::Bgra8UnormSrgb,
-                ])
+                ]);
             }
             if self
                 .shared
@@ -904,9 +1097,13 @@ impl crate::Adapter<super::Api> for super::Adapter {
             }
 
             Some(crate::SurfaceCapabilities {
+                color_spaces: crate::auxil::compatible_color_spaces(
+                    &formats,
+                    &[wgt::SurfaceColorSpace::Srgb], //TODO
+                ),
                 formats,
                 present_modes: vec![wgt::PresentMode::Fifo], //TODO
-                composite_alpha_modes: vec![wgt::CompositeAlphaMode::Opaque], //TODO
+                composite_alpha_modes: surface.composite_alpha_modes(),
                 swap_chain_sizes: 2..=2,
                 current_extent: None,
                 extents: wgt::Extent3d {
@@ -17,7 +17,7 @@ pub(super) struct State {
     vertex_buffers:
         [(super::VertexBufferDesc, Option<super::BufferBinding>); crate::MAX_VERTEX_BUFFERS],
     vertex_attributes: ArrayVec<super::AttributeDesc, { super::MAX_VERTEX_ATTRIBUTES }>,
-    color_targets: ArrayVec<super::ColorTargetDesc, { crate::MAX_COLOR_ATTACHMENTS }>,
+    color_targets: ArrayVec<(u32, super::ColorTargetDesc), { crate::MAX_COLOR_ATTACHMENTS }>,
     stencil: super::StencilState,
     depth_bias: wgt::DepthBiasState,
     alpha_to_coverage_enabled: bool,
@@ -201,7 +201,11 @@ impl super::CommandEncoder {
 }
 
 impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
-    unsafe fn begin_encoding(&mut self, label: crate::Label) -> Result<(), crate::DeviceError> {
+    unsafe fn begin_encoding(
+        &mut self,
+        label: crate::Label,
+        _reusable: bool,
+    ) -> Result<(), crate::DeviceError> {
         self.state = State::default();
         self.cmd_buffer.label = label.map(str::to_string);
         Ok(())
@@ -484,10 +488,14 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                 for (i, cat) in desc.color_attachments.iter().enumerate() {
                     if let Some(cat) = cat.as_ref() {
                         let attachment = glow::COLOR_ATTACHMENT0 + i as u32;
-                        self.cmd_buffer.commands.push(C::BindAttachment {
-                            attachment,
-                            view: cat.target.view.clone(),
-                        });
+                        let mut view = cat.target.view.clone();
+                        if let Some(slice) = cat.depth_slice {
+                            // 3D textures are attached one depth slice at a time.
+                            view.array_layers = slice..slice + 1;
+                        }
+                        self.cmd_buffer
+                            .commands
+                            .push(C::BindAttachment { attachment, view });
                         if let Some(ref rat) = cat.resolve_target {
                             self.state
                                 .resolve_attachments
@@ -550,8 +558,8 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         for (i, cat) in desc
             .color_attachments
             .iter()
-            .filter_map(|at| at.as_ref())
             .enumerate()
+            .filter_map(|(i, at)| Some((i, at.as_ref()?)))
         {
             if !cat.ops.contains(crate::AttachmentOps::LOAD) {
                 let c = &cat.clear_value;
@@ -682,6 +690,7 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                     raw,
                     target,
                     aspects,
+                    swizzle,
                 } => {
                     dirty_textures |= 1 << slot;
                     self.state.texture_slots[slot as usize].tex_target = target;
@@ -690,6 +699,7 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                         texture: raw,
                         target,
                         aspects,
+                        swizzle,
                     });
                 }
                 super::RawBinding::Image(ref binding) => {
@@ -704,6 +714,50 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         self.rebind_sampler_states(dirty_textures, dirty_samplers);
     }
 
+    unsafe fn set_bind_group_dynamic_offsets(
+        &mut self,
+        layout: &super::PipelineLayout,
+        index: u32,
+        group: &super::BindGroup,
+        dynamic_offsets: &[wgt::DynamicOffset],
+    ) {
+        let group_info = &layout.group_infos[index as usize];
+        let dynamic_buffers = group_info
+            .entries
+            .iter()
+            .zip(group.contents.iter())
+            .filter_map(
+                |(binding_layout, raw_binding)| match (binding_layout.ty, raw_binding) {
+                    (
+                        wgt::BindingType::Buffer {
+                            ty,
+                            has_dynamic_offset: true,
+                            min_binding_size: _,
+                        },
+                        &super::RawBinding::Buffer { raw, offset, size },
+                    ) => Some((binding_layout.binding, ty, raw, offset, size)),
+                    _ => None,
+                },
+            );
+
+        // Only the ranges of buffers with dynamic offsets change.
+        for ((binding, ty, raw, base_offset, size), &dynamic_offset) in
+            dynamic_buffers.zip(dynamic_offsets)
+        {
+            let target = match ty {
+                wgt::BufferBindingType::Uniform => glow::UNIFORM_BUFFER,
+                wgt::BufferBindingType::Storage { .. } => glow::SHADER_STORAGE_BUFFER,
+            };
+            self.cmd_buffer.commands.push(C::BindBuffer {
+                target,
+                slot: group_info.binding_to_slot[binding as usize] as u32,
+                buffer: raw,
+                offset: base_offset + dynamic_offset as i32,
+                size,
+            });
+        }
+    }
+
     unsafe fn set_push_constants(
         &mut self,
         _layout: &super::PipelineLayout,
@@ -858,18 +912,22 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                 .color_targets
                 .iter()
                 .skip(1)
-                .any(|ct| *ct != pipeline.color_targets[0])
+                .any(|&(_, ref ct)| *ct != pipeline.color_targets[0].1)
             {
-                for (index, ct) in pipeline.color_targets.iter().enumerate() {
+                for &(index, ref ct) in pipeline.color_targets.iter() {
                     self.cmd_buffer.commands.push(C::SetColorTarget {
-                        draw_buffer_index: Some(index as u32),
+                        draw_buffer_index: Some(index),
                         desc: ct.clone(),
                     });
                 }
             } else {
                 self.cmd_buffer.commands.push(C::SetColorTarget {
                     draw_buffer_index: None,
-                    desc: pipeline.color_targets.first().cloned().unwrap_or_default(),
+                    desc: pipeline
+                        .color_targets
+                        .first()
+                        .map(|&(_, ref ct)| ct.clone())
+                        .unwrap_or_default(),
                 });
             }
         }
@@ -921,6 +979,17 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
             h: rect.h as i32,
         }));
     }
+    unsafe fn set_viewport_indexed(
+        &mut self,
+        _index: u32,
+        _rect: &crate::Rect<f32>,
+        _depth_range: Range<f32>,
+    ) {
+        unreachable!("MULTI_VIEWPORT is not supported")
+    }
+    unsafe fn set_scissor_rect_indexed(&mut self, _index: u32, _rect: &crate::Rect<u32>) {
+        unreachable!("MULTI_VIEWPORT is not supported")
+    }
     unsafe fn set_stencil_reference(&mut self, value: u32) {
         self.state.stencil.front.reference = value;
         self.state.stencil.back.reference = value;
@@ -929,6 +998,12 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
     unsafe fn set_blend_constants(&mut self, color: &[f32; 4]) {
         self.cmd_buffer.commands.push(C::SetBlendConstant(*color));
     }
+    unsafe fn set_shading_rate(&mut self, _rate: wgt::ShadingRate) {
+        unreachable!("SHADING_RATE is not supported")
+    }
+    unsafe fn set_depth_bounds(&mut self, _bounds: Range<f32>) {
+        unreachable!("DEPTH_BOUNDS_TEST is not supported")
+    }
 
     unsafe fn draw(
         &mut self,
@@ -1057,4 +1132,18 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
             indirect_offset: offset,
         });
     }
+
+    unsafe fn build_acceleration_structures(
+        &mut self,
+        descriptors: &[crate::BuildAccelerationStructureDescriptor<super::Api>],
+    ) {
+        if let Some(desc) = descriptors.first() {
+            match *desc.destination_acceleration_structure {}
+        }
+    }
+    unsafe fn place_acceleration_structure_barrier(
+        &mut self,
+        _barrier: crate::AccelerationStructureBarrier,
+    ) {
+    }
 }
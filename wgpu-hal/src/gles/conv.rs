@@ -30,9 +30,11 @@ impl super::AdapterShared {
             Tf::Rg16Float => (glow::RG16F, glow::RG, glow::HALF_FLOAT),
             Tf::Rgba8Unorm => (glow::RGBA8, glow::RGBA, glow::UNSIGNED_BYTE),
             Tf::Rgba8UnormSrgb => (glow::SRGB8_ALPHA8, glow::RGBA, glow::UNSIGNED_BYTE),
-            Tf::Bgra8UnormSrgb => (glow::SRGB8_ALPHA8, glow::BGRA, glow::UNSIGNED_BYTE), //TODO?
+            // GLES has no BGRA internal formats. The texels are stored as RGBA, and
+            // red and blue are swapped when copying to and from buffers.
+            Tf::Bgra8UnormSrgb => (glow::SRGB8_ALPHA8, glow::RGBA, glow::UNSIGNED_BYTE),
             Tf::Rgba8Snorm => (glow::RGBA8_SNORM, glow::RGBA, glow::BYTE),
-            Tf::Bgra8Unorm => (glow::RGBA8, glow::BGRA, glow::UNSIGNED_BYTE), //TODO?
+            Tf::Bgra8Unorm => (glow::RGBA8, glow::RGBA, glow::UNSIGNED_BYTE),
             Tf::Rgba8Uint => (glow::RGBA8UI, glow::RGBA_INTEGER, glow::UNSIGNED_BYTE),
             Tf::Rgba8Sint => (glow::RGBA8I, glow::RGBA_INTEGER, glow::BYTE),
             Tf::Rgb10a2Unorm => (
@@ -82,6 +84,7 @@ impl super::AdapterShared {
                 glow::DEPTH_STENCIL,
                 glow::UNSIGNED_INT_24_8,
             ),
+            Tf::NV12 => unreachable!(),
             Tf::Rgb9e5Ufloat => (glow::RGB9_E5, glow::RGB, glow::UNSIGNED_INT_5_9_9_9_REV),
             Tf::Bc1RgbaUnorm => (glow::COMPRESSED_RGBA_S3TC_DXT1_EXT, glow::RGBA, 0),
             Tf::Bc1RgbaUnormSrgb => (glow::COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT, glow::RGBA, 0),
@@ -253,6 +256,17 @@ pub fn map_address_mode(mode: wgt::AddressMode) -> u32 {
     }
 }
 
+pub fn map_swizzle_component(component: wgt::SwizzleComponent) -> u32 {
+    match component {
+        wgt::SwizzleComponent::Zero => glow::ZERO,
+        wgt::SwizzleComponent::One => glow::ONE,
+        wgt::SwizzleComponent::R => glow::RED,
+        wgt::SwizzleComponent::G => glow::GREEN,
+        wgt::SwizzleComponent::B => glow::BLUE,
+        wgt::SwizzleComponent::A => glow::ALPHA,
+    }
+}
+
 pub fn map_compare_func(fun: wgt::CompareFunction) -> u32 {
     use wgt::CompareFunction as Cf;
     match fun {
@@ -375,6 +389,10 @@ fn map_blend_factor(factor: wgt::BlendFactor) -> u32 {
         Bf::OneMinusDstAlpha => glow::ONE_MINUS_DST_ALPHA,
         Bf::Constant => glow::CONSTANT_COLOR,
         Bf::OneMinusConstant => glow::ONE_MINUS_CONSTANT_COLOR,
+        Bf::Src1 => glow::SRC1_COLOR,
+        Bf::OneMinusSrc1 => glow::ONE_MINUS_SRC1_COLOR,
+        Bf::Src1Alpha => glow::SRC1_ALPHA,
+        Bf::OneMinusSrc1Alpha => glow::ONE_MINUS_SRC1_ALPHA,
         Bf::SrcAlphaSaturated => glow::SRC_ALPHA_SATURATE,
     }
 }
@@ -201,8 +201,8 @@ impl super::Device {
         };
 
         let shader = &stage.module.naga;
-        let entry_point_index = shader
-            .module
+        let module = crate::auxil::apply_pipeline_constants(&shader.module, stage.constants);
+        let entry_point_index = module
             .entry_points
             .iter()
             .position(|ep| ep.name.as_str() == stage.entry_point)
@@ -226,12 +226,21 @@ impl super::Device {
             binding_array: BoundsCheckPolicy::Unchecked,
         };
 
+        let mut temp_options;
+        let naga_options = if stage.module.zero_initialize_workgroup_memory {
+            &context.layout.naga_options
+        } else {
+            temp_options = context.layout.naga_options.clone();
+            temp_options.zero_initialize_workgroup_memory = false;
+            &temp_options
+        };
+
         let mut output = String::new();
         let mut writer = glsl::Writer::new(
             &mut output,
-            &shader.module,
+            &module,
             &shader.info,
-            &context.layout.naga_options,
+            naga_options,
             &pipeline_options,
             policies,
         )
@@ -248,7 +257,7 @@ impl super::Device {
         log::debug!("Naga generated shader:\n{}", output);
 
         context.consume_reflection(
-            &shader.module,
+            &module,
             shader.info.get_entry_point(entry_point_index),
             reflection_info,
         );
@@ -270,10 +279,17 @@ impl super::Device {
             group_to_binding_to_slot.push(group.binding_to_slot.clone());
         }
         for &(naga_stage, stage) in &shaders {
+            let mut constants = stage
+                .constants
+                .iter()
+                .map(|(key, value)| (key.clone(), value.to_bits()))
+                .collect::<Vec<_>>();
+            constants.sort();
             program_stages.push(super::ProgramStage {
                 naga_stage: naga_stage.to_owned(),
                 shader_id: stage.module.id,
                 entry_point: stage.entry_point.to_owned(),
+                constants,
             });
         }
         let glsl_version = match self.shared.shading_language_version {
@@ -455,8 +471,13 @@ impl crate::Device<super::Api> for super::Device {
         unsafe { gl.delete_framebuffer(queue.draw_fbo) };
         unsafe { gl.delete_framebuffer(queue.copy_fbo) };
         unsafe { gl.delete_buffer(queue.zero_buffer) };
+        if let Some((program, _)) = queue.shader_clear_program {
+            unsafe { gl.delete_program(program) };
+        }
     }
 
+    unsafe fn destroy_queue(&self, _queue: super::Queue) {}
+
     unsafe fn create_buffer(
         &self,
         desc: &crate::BufferDescriptor,
@@ -631,6 +652,13 @@ impl crate::Device<super::Api> for super::Device {
                 unsafe { gl.bind_buffer(buffer.target, Some(raw)) };
                 unsafe { gl.unmap_buffer(buffer.target) };
                 unsafe { gl.bind_buffer(buffer.target, None) };
+                if self
+                    .shared
+                    .workarounds
+                    .contains(super::Workarounds::FLUSH_AFTER_UNMAP)
+                {
+                    unsafe { gl.flush() };
+                }
             }
         }
         Ok(())
@@ -818,6 +846,7 @@ impl crate::Device<super::Api> for super::Device {
             mip_levels: desc.range.mip_range(texture.mip_level_count),
             array_layers: desc.range.layer_range(texture.array_layer_count),
             format: texture.format,
+            swizzle: desc.swizzle,
         })
     }
     unsafe fn destroy_texture_view(&self, _view: super::TextureView) {}
@@ -865,6 +894,7 @@ impl crate::Device<super::Api> for super::Device {
                 }
                 wgt::SamplerBorderColor::OpaqueBlack => [0.0, 0.0, 0.0, 1.0],
                 wgt::SamplerBorderColor::OpaqueWhite => [1.0; 4],
+                wgt::SamplerBorderColor::Custom(color) => color,
             };
             unsafe { gl.sampler_parameter_f32_slice(raw, glow::TEXTURE_BORDER_COLOR, &border) };
         }
@@ -988,6 +1018,9 @@ impl crate::Device<super::Api> for super::Device {
                         ty: wgt::BufferBindingType::Storage { .. },
                         ..
                     } => &mut num_storage_buffers,
+                    wgt::BindingType::AccelerationStructure => {
+                        unreachable!("`Features::RAY_QUERY` is not supported")
+                    }
                 };
 
                 binding_to_slot[entry.binding as usize] = *counter;
@@ -1051,6 +1084,7 @@ impl crate::Device<super::Api> for super::Device {
                         raw,
                         target,
                         aspects: view.aspects,
+                        swizzle: view.swizzle,
                     }
                 }
                 wgt::BindingType::StorageTexture {
@@ -1073,6 +1107,9 @@ impl crate::Device<super::Api> for super::Device {
                         format: format_desc.internal,
                     })
                 }
+                wgt::BindingType::AccelerationStructure => {
+                    unreachable!("`Features::RAY_QUERY` is not supported")
+                }
             };
             contents.push(binding);
         }
@@ -1095,6 +1132,7 @@ impl crate::Device<super::Api> for super::Device {
                 }
                 crate::ShaderInput::Naga(naga) => naga,
             },
+            zero_initialize_workgroup_memory: desc.zero_initialize_workgroup_memory,
             label: desc.label.map(|str| str.to_string()),
             id: self.shared.next_shader_id.fetch_add(1, Ordering::Relaxed),
         })
@@ -1137,14 +1175,18 @@ impl crate::Device<super::Api> for super::Device {
 
         let color_targets = {
             let mut targets = Vec::new();
-            for ct in desc.color_targets.iter().filter_map(|at| at.as_ref()) {
-                targets.push(super::ColorTargetDesc {
-                    mask: ct.write_mask,
-                    blend: ct.blend.as_ref().map(conv::map_blend),
-                });
+            for (index, ct) in desc.color_targets.iter().enumerate() {
+                if let Some(ct) = ct.as_ref() {
+                    targets.push((
+                        index as u32,
+                        super::ColorTargetDesc {
+                            mask: ct.write_mask,
+                            blend: ct.blend.as_ref().map(conv::map_blend),
+                        },
+                    ));
+                }
             }
-            //Note: if any of the states are different, and `INDEPENDENT_BLEND` flag
-            // is not exposed, then this pipeline will not bind correctly.
+            // wgpu-core rejects differing states unless `INDEPENDENT_BLEND` is exposed.
             targets.into_boxed_slice()
         };
 
@@ -1186,6 +1228,17 @@ impl crate::Device<super::Api> for super::Device {
         }
     }
 
+    unsafe fn create_pipeline_cache(
+        &self,
+        _desc: &crate::PipelineCacheDescriptor,
+    ) -> Result<super::PipelineCache, crate::DeviceError> {
+        Ok(super::PipelineCache)
+    }
+    unsafe fn destroy_pipeline_cache(&self, _cache: super::PipelineCache) {}
+    unsafe fn pipeline_cache_get_data(&self, _cache: &super::PipelineCache) -> Option<Vec<u8>> {
+        None
+    }
+
     unsafe fn create_compute_pipeline(
         &self,
         desc: &crate::ComputePipelineDescriptor<super::Api>,
@@ -1324,6 +1377,35 @@ impl crate::Device<super::Api> for super::Device {
                 .end_frame_capture(ptr::null_mut(), ptr::null_mut())
         }
     }
+
+    unsafe fn allocator_report(&self) -> Option<crate::AllocatorReport> {
+        None
+    }
+
+    unsafe fn create_acceleration_structure(
+        &self,
+        _desc: &crate::AccelerationStructureDescriptor,
+    ) -> Result<super::AccelerationStructure, crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn get_acceleration_structure_build_sizes(
+        &self,
+        _desc: &crate::GetAccelerationStructureBuildSizesDescriptor<super::Api>,
+    ) -> crate::AccelerationStructureBuildSizes {
+        Default::default()
+    }
+    unsafe fn get_acceleration_structure_device_address(
+        &self,
+        acceleration_structure: &super::AccelerationStructure,
+    ) -> wgt::BufferAddress {
+        match *acceleration_structure {}
+    }
+    unsafe fn destroy_acceleration_structure(
+        &self,
+        acceleration_structure: super::AccelerationStructure,
+    ) {
+        match acceleration_structure {}
+    }
 }
 
 #[cfg(all(
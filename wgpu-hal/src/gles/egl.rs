@@ -17,6 +17,10 @@ const EGL_PLATFORM_ANGLE_DEBUG_LAYERS_ENABLED: u32 = 0x3451;
 const EGL_PLATFORM_SURFACELESS_MESA: u32 = 0x31DD;
 const EGL_GL_COLORSPACE_KHR: u32 = 0x309D;
 const EGL_GL_COLORSPACE_SRGB_KHR: u32 = 0x3089;
+const EGL_GL_COLORSPACE_LINEAR_KHR: u32 = 0x308A;
+const EGL_PRESENT_OPAQUE_EXT: i32 = 0x31DF;
+const EGL_DEVICE_EXT: i32 = 0x322C;
+const EGL_DRM_DEVICE_FILE_EXT: i32 = 0x3233;
 
 type XOpenDisplayFun =
     unsafe extern "system" fn(display_name: *const raw::c_char) -> *mut raw::c_void;
@@ -84,6 +88,28 @@ type EglDebugMessageControlFun = unsafe extern "system" fn(
     attrib_list: *const khronos_egl::Attrib,
 ) -> raw::c_int;
 
+type EglQueryDisplayAttribFun = unsafe extern "system" fn(
+    display: *mut raw::c_void,
+    attribute: khronos_egl::Int,
+    value: *mut khronos_egl::Attrib,
+) -> khronos_egl::Boolean;
+
+type EglQueryDeviceStringFun = unsafe extern "system" fn(
+    device: *mut raw::c_void,
+    name: khronos_egl::Int,
+) -> *const raw::c_char;
+
+type EglGetDisplayDriverNameFun =
+    unsafe extern "system" fn(display: *mut raw::c_void) -> *const raw::c_char;
+
+type GlGetInternalformativFun = unsafe extern "system" fn(
+    target: u32,
+    internal_format: u32,
+    pname: u32,
+    buf_size: i32,
+    params: *mut i32,
+);
+
 unsafe extern "system" fn egl_debug_proc(
     error: khronos_egl::Enum,
     command_raw: *const raw::c_char,
@@ -362,6 +388,8 @@ impl EglContext {
 pub struct AdapterContext {
     glow: Mutex<glow::Context>,
     egl: Option<EglContext>,
+    /// `glGetInternalformativ`, which glow doesn't expose.
+    get_internalformativ: Option<GlGetInternalformativFun>,
 }
 
 unsafe impl Sync for AdapterContext {}
@@ -399,6 +427,129 @@ impl AdapterContext {
             None => ptr::null_mut(),
         }
     }
+
+    /// Returns the PCI vendor and device IDs of the GPU driving the display.
+    ///
+    /// This finds the DRM device node through `EGL_EXT_device_query` and `EGL_EXT_device_drm`,
+    /// and reads the IDs from sysfs, so it only works on Linux.
+    pub(super) fn pci_ids(&self) -> Option<(u32, u32)> {
+        let egl = self.egl.as_ref()?;
+        if !cfg!(target_os = "linux")
+            || !egl
+                .instance
+                .query_string(None, khronos_egl::EXTENSIONS)
+                .ok()?
+                .to_string_lossy()
+                .contains("EGL_EXT_device_query")
+        {
+            return None;
+        }
+
+        let query_display_attrib: EglQueryDisplayAttribFun = {
+            let addr = egl.instance.get_proc_address("eglQueryDisplayAttribEXT")?;
+            unsafe { std::mem::transmute(addr) }
+        };
+        let query_device_string: EglQueryDeviceStringFun = {
+            let addr = egl.instance.get_proc_address("eglQueryDeviceStringEXT")?;
+            unsafe { std::mem::transmute(addr) }
+        };
+
+        let mut device: khronos_egl::Attrib = 0;
+        let ok = unsafe { query_display_attrib(egl.display.as_ptr(), EGL_DEVICE_EXT, &mut device) };
+        if ok != khronos_egl::TRUE || device == 0 {
+            return None;
+        }
+        let device = device as *mut raw::c_void;
+
+        let device_extensions = unsafe { query_device_string(device, khronos_egl::EXTENSIONS) };
+        if device_extensions.is_null()
+            || !unsafe { ffi::CStr::from_ptr(device_extensions) }
+                .to_string_lossy()
+                .contains("EGL_EXT_device_drm")
+        {
+            return None;
+        }
+        let node = unsafe { query_device_string(device, EGL_DRM_DEVICE_FILE_EXT) };
+        if node.is_null() {
+            return None;
+        }
+        let node = unsafe { ffi::CStr::from_ptr(node) }.to_str().ok()?;
+
+        // `node` is something like "/dev/dri/card0", described by "/sys/class/drm/card0/device".
+        let sysfs = std::path::Path::new("/sys/class/drm")
+            .join(std::path::Path::new(node).file_name()?)
+            .join("device");
+        let read_id = |name: &str| {
+            let id = std::fs::read_to_string(sysfs.join(name)).ok()?;
+            u32::from_str_radix(id.trim().trim_start_matches("0x"), 16).ok()
+        };
+        Some((read_id("vendor")?, read_id("device")?))
+    }
+
+    /// Returns the name of the driver behind the display, using `EGL_MESA_query_driver`.
+    pub(super) fn driver_name(&self) -> Option<String> {
+        let egl = self.egl.as_ref()?;
+        if !egl
+            .instance
+            .query_string(Some(egl.display), khronos_egl::EXTENSIONS)
+            .ok()?
+            .to_string_lossy()
+            .contains("EGL_MESA_query_driver")
+        {
+            return None;
+        }
+
+        let get_driver_name: EglGetDisplayDriverNameFun = {
+            let addr = egl.instance.get_proc_address("eglGetDisplayDriverName")?;
+            unsafe { std::mem::transmute(addr) }
+        };
+        let name = unsafe { get_driver_name(egl.display.as_ptr()) };
+        if name.is_null() {
+            return None;
+        }
+        Some(
+            unsafe { ffi::CStr::from_ptr(name) }
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+
+    /// Returns the sample counts the driver supports for renderbuffers of `internal_format`,
+    /// using `glGetInternalformativ`.
+    ///
+    /// Returns [`None`] if the function couldn't be loaded. A format that isn't renderable
+    /// raises `GL_INVALID_ENUM`, which is left for the caller to check.
+    ///
+    /// # Safety
+    ///
+    /// The context must be locked and support GLES 3.0.
+    pub(super) unsafe fn internal_format_samples(&self, internal_format: u32) -> Option<Vec<i32>> {
+        let get_internalformativ = self.get_internalformativ?;
+
+        let mut count = 0;
+        unsafe {
+            get_internalformativ(
+                glow::RENDERBUFFER,
+                internal_format,
+                glow::NUM_SAMPLE_COUNTS,
+                1,
+                &mut count,
+            )
+        };
+        let mut samples = vec![0; count.max(0) as usize];
+        if !samples.is_empty() {
+            unsafe {
+                get_internalformativ(
+                    glow::RENDERBUFFER,
+                    internal_format,
+                    glow::SAMPLES,
+                    count,
+                    samples.as_mut_ptr(),
+                )
+            };
+        }
+        Some(samples)
+    }
 }
 
 struct EglContextLock<'a> {
@@ -486,6 +637,10 @@ struct Inner {
     force_gles_minor_version: wgt::Gles3MinorVersion,
     /// Method by which the framebuffer should support srgb
     srgb_kind: SrgbFrameBufferKind,
+    /// Whether the chosen config has an alpha channel the compositor may blend with.
+    config_has_alpha: bool,
+    /// Whether window surfaces can be forced to be opaque with `EGL_EXT_present_opaque`.
+    supports_present_opaque: bool,
 }
 
 impl Inner {
@@ -537,6 +692,10 @@ impl Inner {
         }
 
         let (config, supports_native_window) = choose_config(&egl, display, srgb_kind)?;
+        let config_has_alpha = egl
+            .get_config_attrib(display, config, khronos_egl::ALPHA_SIZE)
+            .map_or(false, |size| size > 0);
+        let supports_present_opaque = display_extensions.contains("EGL_EXT_present_opaque");
         egl.bind_api(khronos_egl::OPENGL_ES_API).unwrap();
 
         let needs_robustness = true;
@@ -641,6 +800,8 @@ impl Inner {
             config,
             wl_display: None,
             srgb_kind,
+            config_has_alpha,
+            supports_present_opaque,
             force_gles_minor_version,
         })
     }
@@ -965,6 +1126,9 @@ impl crate::Instance<super::Api> for Instance {
             raw_window_handle: window_handle,
             swapchain: None,
             srgb_kind: inner.srgb_kind,
+            // Wayland compositors blend surfaces with an alpha channel as premultiplied.
+            supports_transparency: inner.config_has_alpha && self.wsi.kind == WindowKind::Wayland,
+            supports_present_opaque: inner.supports_present_opaque,
         })
     }
     unsafe fn destroy_surface(&self, _surface: Surface) {}
@@ -997,10 +1161,17 @@ impl crate::Instance<super::Api> for Instance {
 
         inner.egl.unmake_current();
 
+        let get_internalformativ = inner
+            .egl
+            .instance
+            .get_proc_address("glGetInternalformativ")
+            .map(|addr| unsafe { std::mem::transmute::<_, GlGetInternalformativFun>(addr) });
+
         unsafe {
             super::Adapter::expose(AdapterContext {
                 glow: Mutex::new(gl),
                 egl: Some(inner.egl.clone()),
+                get_internalformativ,
             })
         }
         .into_iter()
@@ -1017,13 +1188,17 @@ impl super::Adapter {
     /// - The underlying OpenGL ES context must be current when interfacing with any objects returned by
     ///   wgpu-hal from this adapter.
     pub unsafe fn new_external(
-        fun: impl FnMut(&str) -> *const ffi::c_void,
+        mut fun: impl FnMut(&str) -> *const ffi::c_void,
     ) -> Option<crate::ExposedAdapter<super::Api>> {
+        let get_internalformativ = Some(fun("glGetInternalformativ"))
+            .filter(|addr| !addr.is_null())
+            .map(|addr| unsafe { std::mem::transmute::<_, GlGetInternalformativFun>(addr) });
         let context = unsafe { glow::Context::from_loader_function(fun) };
         unsafe {
             Self::expose(AdapterContext {
                 glow: Mutex::new(context),
                 egl: None,
+                get_internalformativ,
             })
         }
     }
@@ -1049,6 +1224,7 @@ pub struct Swapchain {
     /// Extent because the window lies
     extent: wgt::Extent3d,
     format: wgt::TextureFormat,
+    composite_alpha_mode: wgt::CompositeAlphaMode,
     format_desc: super::TextureFormatDesc,
     #[allow(unused)]
     sample_type: wgt::TextureSampleType,
@@ -1063,6 +1239,8 @@ pub struct Surface {
     raw_window_handle: raw_window_handle::RawWindowHandle,
     swapchain: Option<Swapchain>,
     srgb_kind: SrgbFrameBufferKind,
+    supports_transparency: bool,
+    supports_present_opaque: bool,
 }
 
 unsafe impl Send for Surface {}
@@ -1152,6 +1330,14 @@ impl Surface {
             _ => true,
         }
     }
+
+    pub fn composite_alpha_modes(&self) -> Vec<wgt::CompositeAlphaMode> {
+        let mut modes = vec![wgt::CompositeAlphaMode::Opaque];
+        if self.supports_transparency {
+            modes.push(wgt::CompositeAlphaMode::PreMultiplied);
+        }
+        modes
+    }
 }
 
 impl crate::Surface<super::Api> for Surface {
@@ -1162,6 +1348,17 @@ impl crate::Surface<super::Api> for Surface {
     ) -> Result<(), crate::SurfaceError> {
         use raw_window_handle::RawWindowHandle as Rwh;
 
+        // The colorspace and opaqueness are fixed when the EGL surface is created,
+        // so switching between sRGB and linear formats or alpha modes needs a new one.
+        let needs_new_surface = self.swapchain.as_ref().map_or(false, |sc| {
+            (self.supports_srgb() && sc.format.is_srgb() != config.format.is_srgb())
+                || (self.supports_present_opaque
+                    && sc.composite_alpha_mode != config.composite_alpha_mode)
+        });
+        if needs_new_surface {
+            unsafe { self.unconfigure(device) };
+        }
+
         let (surface, wl_window) = match unsafe { self.unconfigure_impl(device) } {
             Some(pair) => pair,
             None => {
@@ -1233,19 +1430,33 @@ impl crate::Surface<super::Api> for Surface {
                         khronos_egl::SINGLE_BUFFER
                     },
                 ];
-                if config.format.is_srgb() {
-                    match self.srgb_kind {
-                        SrgbFrameBufferKind::None => {}
-                        SrgbFrameBufferKind::Core => {
-                            attributes.push(khronos_egl::GL_COLORSPACE);
-                            attributes.push(khronos_egl::GL_COLORSPACE_SRGB);
-                        }
-                        SrgbFrameBufferKind::Khr => {
-                            attributes.push(EGL_GL_COLORSPACE_KHR as i32);
-                            attributes.push(EGL_GL_COLORSPACE_SRGB_KHR as i32);
-                        }
+                // Always ask for the colorspace explicitly: some drivers default
+                // to sRGB, which would encode linear swapchains a second time.
+                match self.srgb_kind {
+                    SrgbFrameBufferKind::None => {}
+                    SrgbFrameBufferKind::Core => {
+                        attributes.push(khronos_egl::GL_COLORSPACE);
+                        attributes.push(if config.format.is_srgb() {
+                            khronos_egl::GL_COLORSPACE_SRGB
+                        } else {
+                            khronos_egl::GL_COLORSPACE_LINEAR
+                        });
+                    }
+                    SrgbFrameBufferKind::Khr => {
+                        attributes.push(EGL_GL_COLORSPACE_KHR as i32);
+                        attributes.push(if config.format.is_srgb() {
+                            EGL_GL_COLORSPACE_SRGB_KHR as i32
+                        } else {
+                            EGL_GL_COLORSPACE_LINEAR_KHR as i32
+                        });
                     }
                 }
+                if self.supports_present_opaque
+                    && config.composite_alpha_mode == wgt::CompositeAlphaMode::Opaque
+                {
+                    attributes.push(EGL_PRESENT_OPAQUE_EXT);
+                    attributes.push(khronos_egl::TRUE as i32);
+                }
                 attributes.push(khronos_egl::ATTRIB_NONE as i32);
 
                 #[cfg(not(target_os = "emscripten"))]
@@ -1341,6 +1552,7 @@ impl crate::Surface<super::Api> for Surface {
             framebuffer,
             extent: config.extent,
             format: config.format,
+            composite_alpha_mode: config.composite_alpha_mode,
             format_desc,
             sample_type: wgt::TextureSampleType::Float { filterable: false },
         });
@@ -1389,9 +1601,26 @@ impl crate::Surface<super::Api> for Surface {
             },
             is_cubemap: false,
         };
+        // The window may have been resized since the surface was configured.
+        // Wayland windows are sized by us, so this only triggers on other platforms.
+        let query_size = |attribute| {
+            self.egl
+                .instance
+                .query_surface(self.egl.display, sc.surface, attribute)
+                .ok()
+        };
+        let suboptimal = match (
+            query_size(khronos_egl::WIDTH),
+            query_size(khronos_egl::HEIGHT),
+        ) {
+            (Some(width), Some(height)) => {
+                width as u32 != sc.extent.width || height as u32 != sc.extent.height
+            }
+            _ => false,
+        };
         Ok(Some(crate::AcquiredSurfaceTexture {
             texture,
-            suboptimal: false,
+            suboptimal,
         }))
     }
     unsafe fn discard_texture(&mut self, _texture: super::Texture) {}
@@ -126,6 +126,9 @@ impl crate::Api for Api {
     type ShaderModule = ShaderModule;
     type RenderPipeline = RenderPipeline;
     type ComputePipeline = ComputePipeline;
+    type PipelineCache = PipelineCache;
+
+    type AccelerationStructure = AccelerationStructure;
 }
 
 bitflags::bitflags! {
@@ -156,11 +159,17 @@ bitflags::bitflags! {
         const COLOR_BUFFER_FLOAT = 1 << 9;
         /// Supports linear flitering `f32` textures.
         const TEXTURE_FLOAT_LINEAR = 1 << 10;
+        /// Supports image load/store on textures.
+        const IMAGE_LOAD_STORE = 1 << 11;
+        /// Supports multisampled integer color buffers, up to `GL_MAX_INTEGER_SAMPLES`.
+        const INTEGER_MULTISAMPLE = 1 << 12;
     }
 }
 
 bitflags::bitflags! {
-    /// Flags that indicate necessary workarounds for specific devices or driver bugs
+    /// Flags that indicate necessary workarounds for specific devices or driver bugs.
+    ///
+    /// These are detected once per adapter, in `Adapter::make_workarounds`.
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
     struct Workarounds: u32 {
         // Needs workaround for Intel Mesa bug:
@@ -172,6 +181,12 @@ bitflags::bitflags! {
         const MESA_I915_SRGB_SHADER_CLEAR = 1 << 0;
         /// Buffer map must emulated becuase it is not supported natively
         const EMULATE_BUFFER_MAP = 1 << 1;
+        /// Writes through a buffer mapping are not seen by later commands unless
+        /// the context is flushed after unmapping. Seen on Adreno drivers.
+        const FLUSH_AFTER_UNMAP = 1 << 2;
+        /// Programs accessing storage buffers from the vertex stage fail to link,
+        /// even though the driver reports support for them. Seen on Mali drivers.
+        const BROKEN_VERTEX_STORAGE = 1 << 3;
     }
 }
 
@@ -225,10 +240,8 @@ pub struct Queue {
     draw_fbo: glow::Framebuffer,
     copy_fbo: glow::Framebuffer,
     /// Shader program used to clear the screen for [`Workarounds::MESA_I915_SRGB_SHADER_CLEAR`]
-    /// devices.
-    shader_clear_program: glow::Program,
-    /// The uniform location of the color uniform in the shader clear program
-    shader_clear_program_color_uniform_location: glow::UniformLocation,
+    /// devices, with the uniform location of its color uniform. Only created on those devices.
+    shader_clear_program: Option<(glow::Program, glow::UniformLocation)>,
     /// Keep a reasonably large buffer filled with zeroes, so that we can implement `ClearBuffer` of
     /// zeroes by copying from it.
     zero_buffer: glow::Buffer,
@@ -361,6 +374,8 @@ pub struct TextureView {
     mip_levels: Range<u32>,
     array_layers: Range<u32>,
     format: wgt::TextureFormat,
+    /// Swizzle applied when the view is sampled.
+    swizzle: wgt::TextureSwizzle,
 }
 
 #[derive(Debug)]
@@ -413,6 +428,7 @@ enum RawBinding {
         raw: glow::Texture,
         target: BindTarget,
         aspects: crate::FormatAspects,
+        swizzle: wgt::TextureSwizzle,
         //TODO: mip levels, array layers
     },
     Image(ImageBinding),
@@ -429,6 +445,7 @@ type ShaderId = u32;
 #[derive(Debug)]
 pub struct ShaderModule {
     naga: crate::NagaShader,
+    zero_initialize_workgroup_memory: bool,
     label: Option<String>,
     id: ShaderId,
 }
@@ -529,6 +546,9 @@ struct ProgramStage {
     naga_stage: naga::ShaderStage,
     shader_id: ShaderId,
     entry_point: String,
+    /// Pipeline constants sorted by key, with the values stored as bits so
+    /// that they can be hashed.
+    constants: Vec<(String, u64)>,
 }
 
 #[derive(PartialEq, Eq, Hash)]
@@ -544,7 +564,8 @@ pub struct RenderPipeline {
     primitive: wgt::PrimitiveState,
     vertex_buffers: Box<[VertexBufferDesc]>,
     vertex_attributes: Box<[AttributeDesc]>,
-    color_targets: Box<[ColorTargetDesc]>,
+    /// Color target states, keyed by their draw buffer index, skipping empty targets.
+    color_targets: Box<[(u32, ColorTargetDesc)]>,
     depth: Option<DepthState>,
     depth_bias: wgt::DepthBiasState,
     stencil: Option<StencilState>,
@@ -581,6 +602,14 @@ unsafe impl Sync for ComputePipeline {}
 ))]
 unsafe impl Send for ComputePipeline {}
 
+/// Program binaries aren't exposed by `glow`, so pipeline caches hold no data.
+#[derive(Debug)]
+pub struct PipelineCache;
+
+/// Acceleration structures are not supported, so none can be created.
+#[derive(Debug)]
+pub enum AccelerationStructure {}
+
 #[derive(Debug)]
 pub struct QuerySet {
     queries: Box<[glow::Query]>,
@@ -858,6 +887,7 @@ enum Command {
         texture: glow::Texture,
         target: BindTarget,
         aspects: crate::FormatAspects,
+        swizzle: wgt::TextureSwizzle,
     },
     BindImage {
         slot: u32,
@@ -20,6 +20,20 @@ fn extract_marker<'a>(data: &'a [u8], range: &std::ops::Range<u32>) -> &'a str {
     std::str::from_utf8(&data[range.start as usize..range.end as usize]).unwrap()
 }
 
+fn is_bgra(format: wgt::TextureFormat) -> bool {
+    matches!(
+        format,
+        wgt::TextureFormat::Bgra8Unorm | wgt::TextureFormat::Bgra8UnormSrgb
+    )
+}
+
+/// Swaps the red and blue channels of 4-byte texels, between RGBA and BGRA order.
+fn swap_red_blue(texels: &mut [u8]) {
+    for texel in texels.chunks_exact_mut(4) {
+        texel.swap(0, 2);
+    }
+}
+
 fn is_layered_target(target: super::BindTarget) -> bool {
     match target {
         glow::TEXTURE_2D_ARRAY | glow::TEXTURE_3D | glow::TEXTURE_CUBE_MAP_ARRAY => true,
@@ -30,10 +44,11 @@ fn is_layered_target(target: super::BindTarget) -> bool {
 impl super::Queue {
     /// Performs a manual shader clear, used as a workaround for a clearing bug on mesa
     unsafe fn perform_shader_clear(&self, gl: &glow::Context, draw_buffer: u32, color: [f32; 4]) {
-        unsafe { gl.use_program(Some(self.shader_clear_program)) };
+        let &(program, ref color_uniform_location) = self.shader_clear_program.as_ref().unwrap();
+        unsafe { gl.use_program(Some(program)) };
         unsafe {
             gl.uniform_4_f32(
-                Some(&self.shader_clear_program_color_uniform_location),
+                Some(color_uniform_location),
                 color[0],
                 color[1],
                 color[2],
@@ -61,6 +76,258 @@ impl super::Queue {
         }
     }
 
+    /// Reads the bytes of `src` starting at `offset` into `data`.
+    unsafe fn read_buffer(
+        &self,
+        gl: &glow::Context,
+        src: &super::Buffer,
+        offset: usize,
+        data: &mut [u8],
+    ) {
+        if data.is_empty() {
+            return;
+        }
+        match src.raw {
+            Some(buffer) => {
+                // The source may not be mappable for reading, so go through a scratch buffer.
+                let scratch = unsafe { gl.create_buffer() }.unwrap();
+                unsafe { gl.bind_buffer(glow::COPY_READ_BUFFER, Some(buffer)) };
+                unsafe { gl.bind_buffer(glow::COPY_WRITE_BUFFER, Some(scratch)) };
+                unsafe {
+                    gl.buffer_data_size(
+                        glow::COPY_WRITE_BUFFER,
+                        data.len() as i32,
+                        glow::STREAM_READ,
+                    )
+                };
+                unsafe {
+                    gl.copy_buffer_sub_data(
+                        glow::COPY_READ_BUFFER,
+                        glow::COPY_WRITE_BUFFER,
+                        offset as i32,
+                        0,
+                        data.len() as i32,
+                    )
+                };
+                unsafe {
+                    self.shared
+                        .get_buffer_sub_data(gl, glow::COPY_WRITE_BUFFER, 0, data)
+                };
+                unsafe { gl.delete_buffer(scratch) };
+            }
+            None => {
+                let src_data = src.data.as_ref().unwrap().lock().unwrap();
+                data.copy_from_slice(&src_data[offset..offset + data.len()]);
+            }
+        }
+    }
+
+    /// Writes `data` to `dst` starting at `offset`.
+    unsafe fn write_buffer(
+        &self,
+        gl: &glow::Context,
+        dst: &super::Buffer,
+        offset: usize,
+        data: &[u8],
+    ) {
+        match dst.raw {
+            Some(buffer) => {
+                // The destination may have immutable storage, so go through a scratch buffer.
+                let scratch = unsafe { gl.create_buffer() }.unwrap();
+                unsafe { gl.bind_buffer(glow::COPY_READ_BUFFER, Some(scratch)) };
+                unsafe { gl.buffer_data_u8_slice(glow::COPY_READ_BUFFER, data, glow::STREAM_DRAW) };
+                unsafe { gl.bind_buffer(glow::COPY_WRITE_BUFFER, Some(buffer)) };
+                unsafe {
+                    gl.copy_buffer_sub_data(
+                        glow::COPY_READ_BUFFER,
+                        glow::COPY_WRITE_BUFFER,
+                        0,
+                        offset as i32,
+                        data.len() as i32,
+                    )
+                };
+                unsafe { gl.delete_buffer(scratch) };
+            }
+            None => {
+                let mut dst_data = dst.data.as_ref().unwrap().lock().unwrap();
+                dst_data[offset..offset + data.len()].copy_from_slice(data);
+            }
+        }
+    }
+
+    /// Reads the texels of `copy` from `src` for a BGRA texture, which GL stores as
+    /// RGBA, so with red and blue swapped.
+    ///
+    /// `row_texels` and `column_texels` are the unpack row length and image height,
+    /// zero for tightly packed rows and images.
+    unsafe fn read_swapped_texels(
+        &self,
+        gl: &glow::Context,
+        src: &super::Buffer,
+        copy: &crate::BufferTextureCopy,
+        row_texels: u32,
+        column_texels: u32,
+    ) -> Vec<u8> {
+        let size = copy.size;
+        let row_texels = if row_texels == 0 {
+            size.width
+        } else {
+            row_texels
+        };
+        let column_texels = if column_texels == 0 {
+            size.height
+        } else {
+            column_texels
+        };
+        let texels = if size.width == 0 || size.height == 0 || size.depth == 0 {
+            0
+        } else {
+            ((size.depth - 1) * column_texels + size.height - 1) * row_texels + size.width
+        };
+        let mut data = vec![0; texels as usize * 4];
+        unsafe { self.read_buffer(gl, src, copy.buffer_layout.offset as usize, &mut data) };
+        swap_red_blue(&mut data);
+        data
+    }
+
+    /// Writes the stencil aspect of a combined depth-stencil texture.
+    ///
+    /// GL can't upload a single aspect of these formats, so each layer is read back
+    /// whole through the copy framebuffer, patched with the new stencil values and
+    /// uploaded again. Only the stencil aspect of these formats is a valid copy destination.
+    unsafe fn copy_buffer_to_stencil_aspect(
+        &self,
+        gl: &glow::Context,
+        src: &super::Buffer,
+        dst: glow::Texture,
+        dst_target: super::BindTarget,
+        dst_format: wgt::TextureFormat,
+        copy: &crate::BufferTextureCopy,
+    ) {
+        let format_desc = self.shared.describe_texture_format(dst_format);
+        // The stencil value sits in the low byte of the last 32-bit word of each texel.
+        let texel_size = match dst_format {
+            wgt::TextureFormat::Depth32FloatStencil8 => 8,
+            _ => 4,
+        };
+        let width = copy.size.width as usize;
+        let height = copy.size.height as usize;
+        if width == 0 || height == 0 {
+            return;
+        }
+        let bytes_per_row = copy.buffer_layout.bytes_per_row.unwrap_or(copy.size.width) as usize;
+        let rows_per_image = copy
+            .buffer_layout
+            .rows_per_image
+            .unwrap_or(copy.size.height) as usize;
+
+        unsafe { gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(self.copy_fbo)) };
+        unsafe { gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None) };
+        unsafe { gl.bind_buffer(glow::PIXEL_UNPACK_BUFFER, None) };
+        unsafe { gl.pixel_store_i32(glow::PACK_ROW_LENGTH, 0) };
+        unsafe { gl.pixel_store_i32(glow::UNPACK_ROW_LENGTH, 0) };
+        unsafe { gl.pixel_store_i32(glow::UNPACK_IMAGE_HEIGHT, 0) };
+
+        let mut texels = vec![0u8; width * height * texel_size];
+        let mut stencil = vec![0u8; (height - 1) * bytes_per_row + width];
+        for layer in 0..copy.size.depth {
+            let array_layer = copy.texture_base.array_layer + layer;
+            let face_target = match dst_target {
+                glow::TEXTURE_CUBE_MAP => CUBEMAP_FACES[array_layer as usize],
+                _ => dst_target,
+            };
+            if is_layered_target(dst_target) {
+                unsafe {
+                    gl.framebuffer_texture_layer(
+                        glow::READ_FRAMEBUFFER,
+                        glow::DEPTH_STENCIL_ATTACHMENT,
+                        Some(dst),
+                        copy.texture_base.mip_level as i32,
+                        array_layer as i32,
+                    )
+                };
+            } else {
+                unsafe {
+                    gl.framebuffer_texture_2d(
+                        glow::READ_FRAMEBUFFER,
+                        glow::DEPTH_STENCIL_ATTACHMENT,
+                        face_target,
+                        Some(dst),
+                        copy.texture_base.mip_level as i32,
+                    )
+                };
+            }
+            unsafe {
+                gl.read_pixels(
+                    copy.texture_base.origin.x as i32,
+                    copy.texture_base.origin.y as i32,
+                    copy.size.width as i32,
+                    copy.size.height as i32,
+                    format_desc.external,
+                    format_desc.data_type,
+                    glow::PixelPackData::Slice(&mut texels),
+                )
+            };
+
+            let offset = copy.buffer_layout.offset as usize
+                + layer as usize * rows_per_image * bytes_per_row;
+            unsafe { self.read_buffer(gl, src, offset, &mut stencil) };
+
+            for (row, texel_row) in texels.chunks_exact_mut(width * texel_size).enumerate() {
+                let stencil_row = &stencil[row * bytes_per_row..row * bytes_per_row + width];
+                for (texel, &value) in texel_row.chunks_exact_mut(texel_size).zip(stencil_row) {
+                    let word = &mut texel[texel_size - 4..];
+                    let packed = u32::from_ne_bytes(word.try_into().unwrap());
+                    word.copy_from_slice(&(packed & !0xff | value as u32).to_ne_bytes());
+                }
+            }
+
+            unsafe { gl.bind_texture(dst_target, Some(dst)) };
+            if is_layered_target(dst_target) {
+                unsafe {
+                    gl.tex_sub_image_3d(
+                        dst_target,
+                        copy.texture_base.mip_level as i32,
+                        copy.texture_base.origin.x as i32,
+                        copy.texture_base.origin.y as i32,
+                        array_layer as i32,
+                        copy.size.width as i32,
+                        copy.size.height as i32,
+                        1,
+                        format_desc.external,
+                        format_desc.data_type,
+                        glow::PixelUnpackData::Slice(&texels),
+                    )
+                };
+            } else {
+                unsafe {
+                    gl.tex_sub_image_2d(
+                        face_target,
+                        copy.texture_base.mip_level as i32,
+                        copy.texture_base.origin.x as i32,
+                        copy.texture_base.origin.y as i32,
+                        copy.size.width as i32,
+                        copy.size.height as i32,
+                        format_desc.external,
+                        format_desc.data_type,
+                        glow::PixelUnpackData::Slice(&texels),
+                    )
+                };
+            }
+        }
+
+        // Don't leave the depth-stencil attachment behind for later color copies.
+        unsafe {
+            gl.framebuffer_texture_2d(
+                glow::READ_FRAMEBUFFER,
+                glow::DEPTH_STENCIL_ATTACHMENT,
+                glow::TEXTURE_2D,
+                None,
+                0,
+            )
+        };
+    }
+
     unsafe fn reset_state(&mut self, gl: &glow::Context) {
         unsafe { gl.use_program(None) };
         unsafe { gl.bind_framebuffer(glow::FRAMEBUFFER, None) };
@@ -355,7 +622,18 @@ impl super::Queue {
                         };
                     }
                     (None, None) => {
-                        todo!()
+                        let (src_data, dst_data) = (src.data.as_ref(), dst.data.as_ref());
+                        let (src_data, dst_data) = (src_data.unwrap(), dst_data.unwrap());
+                        let src_range = copy.src_offset as usize..copy.src_offset as usize + size;
+                        if Arc::ptr_eq(src_data, dst_data) {
+                            let mut data = dst_data.lock().unwrap();
+                            data.copy_within(src_range, copy.dst_offset as usize);
+                        } else {
+                            let src_data = src_data.lock().unwrap();
+                            let mut dst_data = dst_data.lock().unwrap();
+                            dst_data[copy.dst_offset as usize..copy.dst_offset as usize + size]
+                                .copy_from_slice(&src_data[src_range]);
+                        }
                     }
                 }
                 unsafe { gl.bind_buffer(copy_src_target, None) };
@@ -598,6 +876,14 @@ impl super::Queue {
                 dst_format,
                 ref copy,
             } => {
+                if dst_format.is_combined_depth_stencil_format() {
+                    unsafe {
+                        self.copy_buffer_to_stencil_aspect(
+                            gl, src, dst, dst_target, dst_format, copy,
+                        )
+                    };
+                    return;
+                }
                 let (block_width, block_height) = dst_format.block_dimensions();
                 let block_size = dst_format.block_size(None).unwrap();
                 let format_desc = self.shared.describe_texture_format(dst_format);
@@ -616,17 +902,27 @@ impl super::Queue {
                 let mut unbind_unpack_buffer = false;
                 if !dst_format.is_compressed() {
                     let buffer_data;
-                    let unpack_data = match src.raw {
-                        Some(buffer) => {
-                            unsafe { gl.bind_buffer(glow::PIXEL_UNPACK_BUFFER, Some(buffer)) };
-                            unbind_unpack_buffer = true;
-                            glow::PixelUnpackData::BufferOffset(copy.buffer_layout.offset as u32)
-                        }
-                        None => {
-                            buffer_data = src.data.as_ref().unwrap().lock().unwrap();
-                            let src_data =
-                                &buffer_data.as_slice()[copy.buffer_layout.offset as usize..];
-                            glow::PixelUnpackData::Slice(src_data)
+                    let swapped_data;
+                    let unpack_data = if is_bgra(dst_format) {
+                        swapped_data = unsafe {
+                            self.read_swapped_texels(gl, src, copy, row_texels, column_texels)
+                        };
+                        glow::PixelUnpackData::Slice(&swapped_data)
+                    } else {
+                        match src.raw {
+                            Some(buffer) => {
+                                unsafe { gl.bind_buffer(glow::PIXEL_UNPACK_BUFFER, Some(buffer)) };
+                                unbind_unpack_buffer = true;
+                                glow::PixelUnpackData::BufferOffset(
+                                    copy.buffer_layout.offset as u32,
+                                )
+                            }
+                            None => {
+                                buffer_data = src.data.as_ref().unwrap().lock().unwrap();
+                                let src_data =
+                                    &buffer_data.as_slice()[copy.buffer_layout.offset as usize..];
+                                glow::PixelUnpackData::Slice(src_data)
+                            }
                         }
                     };
                     if is_layered_target(dst_target) {
@@ -757,7 +1053,8 @@ impl super::Queue {
                 dst_target: _,
                 ref copy,
             } => {
-                let block_size = src_format.block_size(None).unwrap();
+                let aspect = copy.texture_base.aspect;
+                let block_size = src_format.block_size(Some(aspect.map())).unwrap();
                 if src_format.is_compressed() {
                     log::error!("Not implemented yet: compressed texture copy to buffer");
                     return;
@@ -769,6 +1066,28 @@ impl super::Queue {
                     return;
                 }
                 let format_desc = self.shared.describe_texture_format(src_format);
+                // Depth and stencil are read back one aspect at a time,
+                // from the matching attachment point.
+                let (attachment, read_format, read_type) = match aspect {
+                    crate::FormatAspects::DEPTH => (
+                        glow::DEPTH_ATTACHMENT,
+                        glow::DEPTH_COMPONENT,
+                        match src_format {
+                            wgt::TextureFormat::Depth16Unorm => glow::UNSIGNED_SHORT,
+                            _ => glow::FLOAT,
+                        },
+                    ),
+                    crate::FormatAspects::STENCIL => (
+                        glow::STENCIL_ATTACHMENT,
+                        glow::STENCIL_INDEX,
+                        glow::UNSIGNED_BYTE,
+                    ),
+                    _ => (
+                        glow::COLOR_ATTACHMENT0,
+                        format_desc.external,
+                        format_desc.data_type,
+                    ),
+                };
                 let row_texels = copy
                     .buffer_layout
                     .bytes_per_row
@@ -780,7 +1099,36 @@ impl super::Queue {
 
                 unsafe { gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(self.copy_fbo)) };
 
-                let read_pixels = |offset| {
+                let swap_channels = aspect == crate::FormatAspects::COLOR && is_bgra(src_format);
+                let read_pixels = |offset: wgt::BufferAddress| {
+                    if swap_channels {
+                        // Read into a tightly packed copy, and write its rows back
+                        // out with red and blue swapped into BGRA order.
+                        let row_bytes = copy.size.width as usize * 4;
+                        if row_bytes == 0 || copy.size.height == 0 {
+                            return;
+                        }
+                        let mut texels = vec![0; row_bytes * copy.size.height as usize];
+                        unsafe { gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None) };
+                        unsafe { gl.pixel_store_i32(glow::PACK_ROW_LENGTH, 0) };
+                        unsafe {
+                            gl.read_pixels(
+                                copy.texture_base.origin.x as i32,
+                                copy.texture_base.origin.y as i32,
+                                copy.size.width as i32,
+                                copy.size.height as i32,
+                                read_format,
+                                read_type,
+                                glow::PixelPackData::Slice(&mut texels),
+                            )
+                        };
+                        swap_red_blue(&mut texels);
+                        for (row, data) in texels.chunks_exact(row_bytes).enumerate() {
+                            let row_offset = offset as usize + row * row_texels as usize * 4;
+                            unsafe { self.write_buffer(gl, dst, row_offset, data) };
+                        }
+                        return;
+                    }
                     let mut buffer_data;
                     let unpack_data = match dst.raw {
                         Some(buffer) => {
@@ -800,8 +1148,8 @@ impl super::Queue {
                             copy.texture_base.origin.y as i32,
                             copy.size.width as i32,
                             copy.size.height as i32,
-                            format_desc.external,
-                            format_desc.data_type,
+                            read_format,
+                            read_type,
                             unpack_data,
                         )
                     };
@@ -812,7 +1160,7 @@ impl super::Queue {
                         unsafe {
                             gl.framebuffer_texture_2d(
                                 glow::READ_FRAMEBUFFER,
-                                glow::COLOR_ATTACHMENT0,
+                                attachment,
                                 src_target,
                                 Some(src),
                                 copy.texture_base.mip_level as i32,
@@ -824,7 +1172,7 @@ impl super::Queue {
                         unsafe {
                             gl.framebuffer_texture_layer(
                                 glow::READ_FRAMEBUFFER,
-                                glow::COLOR_ATTACHMENT0,
+                                attachment,
                                 Some(src),
                                 copy.texture_base.mip_level as i32,
                                 copy.texture_base.array_layer as i32,
@@ -837,7 +1185,7 @@ impl super::Queue {
                             unsafe {
                                 gl.framebuffer_texture_layer(
                                     glow::READ_FRAMEBUFFER,
-                                    glow::COLOR_ATTACHMENT0,
+                                    attachment,
                                     Some(src),
                                     copy.texture_base.mip_level as i32,
                                     z as i32,
@@ -851,6 +1199,19 @@ impl super::Queue {
                     glow::TEXTURE_CUBE_MAP | glow::TEXTURE_CUBE_MAP_ARRAY => unimplemented!(),
                     _ => unreachable!(),
                 }
+
+                if attachment != glow::COLOR_ATTACHMENT0 {
+                    // Don't leave a depth or stencil attachment behind for later color copies.
+                    unsafe {
+                        gl.framebuffer_texture_2d(
+                            glow::READ_FRAMEBUFFER,
+                            attachment,
+                            glow::TEXTURE_2D,
+                            None,
+                            0,
+                        )
+                    };
+                }
             }
             C::SetIndexBuffer(buffer) => {
                 unsafe { gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(buffer)) };
@@ -1332,10 +1693,31 @@ impl super::Queue {
                 texture,
                 target,
                 aspects,
+                swizzle,
             } => {
                 unsafe { gl.active_texture(glow::TEXTURE0 + slot) };
                 unsafe { gl.bind_texture(target, Some(texture)) };
 
+                // The swizzle is texture state, so it's set on every bind to undo
+                // the one of any other view of the same texture. WebGL doesn't have it.
+                if !cfg!(target_arch = "wasm32") {
+                    let components = [
+                        (glow::TEXTURE_SWIZZLE_R, swizzle.r),
+                        (glow::TEXTURE_SWIZZLE_G, swizzle.g),
+                        (glow::TEXTURE_SWIZZLE_B, swizzle.b),
+                        (glow::TEXTURE_SWIZZLE_A, swizzle.a),
+                    ];
+                    for (pname, component) in components {
+                        unsafe {
+                            gl.tex_parameter_i32(
+                                target,
+                                pname,
+                                super::conv::map_swizzle_component(component) as i32,
+                            )
+                        };
+                    }
+                }
+
                 let version = gl.version();
                 let is_min_es_3_1 = version.is_embedded && (version.major, version.minor) >= (3, 1);
                 let is_min_4_3 = !version.is_embedded && (version.major, version.minor) >= (4, 3);
@@ -1472,9 +1854,14 @@ impl crate::Queue<super::Api> for super::Queue {
         &mut self,
         command_buffers: &[&super::CommandBuffer],
         signal_fence: Option<(&mut super::Fence, crate::FenceValue)>,
+        label: crate::Label,
     ) -> Result<(), crate::DeviceError> {
         let shared = Arc::clone(&self.shared);
         let gl = &shared.context.lock();
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(label) = label {
+            unsafe { gl.push_debug_group(glow::DEBUG_SOURCE_APPLICATION, DEBUG_ID, label) };
+        }
         for cmd_buf in command_buffers.iter() {
             // The command encoder assumes a default state when encoding the command buffer.
             // Always reset the state between command_buffers to reflect this assumption. Do
@@ -1496,6 +1883,13 @@ impl crate::Queue<super::Api> for super::Queue {
             }
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+        if label.is_some() {
+            unsafe { gl.pop_debug_group() };
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = label;
+
         if let Some((fence, value)) = signal_fence {
             fence.maintain(gl);
             let sync = unsafe { gl.fence_sync(glow::SYNC_GPU_COMMANDS_COMPLETE, 0) }
@@ -1506,6 +1900,15 @@ impl crate::Queue<super::Api> for super::Queue {
         Ok(())
     }
 
+    unsafe fn wait_for_fence(
+        &mut self,
+        _fence: &super::Fence,
+        _value: crate::FenceValue,
+    ) -> Result<(), crate::DeviceError> {
+        // There is a single queue, which executes the submissions in order.
+        Ok(())
+    }
+
     unsafe fn present(
         &mut self,
         surface: &mut super::Surface,
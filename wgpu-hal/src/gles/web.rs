@@ -8,6 +8,8 @@ use super::TextureFormatDesc;
 /// with the `AdapterContext` API from the EGL implementation.
 pub struct AdapterContext {
     pub glow_context: glow::Context,
+    /// The context `glow_context` wraps, for the queries glow doesn't expose.
+    webgl2_context: web_sys::WebGl2RenderingContext,
 }
 
 impl AdapterContext {
@@ -15,6 +17,32 @@ impl AdapterContext {
         false
     }
 
+    /// WebGL doesn't expose PCI IDs; the vendor is guessed from the unmasked strings instead.
+    pub(super) fn pci_ids(&self) -> Option<(u32, u32)> {
+        None
+    }
+
+    pub(super) fn driver_name(&self) -> Option<String> {
+        None
+    }
+
+    /// Returns the sample counts the browser supports for renderbuffers of `internal_format`,
+    /// using `getInternalformatParameter`.
+    ///
+    /// A format that isn't renderable raises `GL_INVALID_ENUM`, which is left for the caller
+    /// to check.
+    pub(super) unsafe fn internal_format_samples(&self, internal_format: u32) -> Option<Vec<i32>> {
+        let samples = self
+            .webgl2_context
+            .get_internalformat_parameter(glow::RENDERBUFFER, internal_format, glow::SAMPLES)
+            .ok()?;
+        Some(
+            samples
+                .dyn_into::<js_sys::Int32Array>()
+                .map_or_else(|_| Vec::new(), |samples| samples.to_vec()),
+        )
+    }
+
     /// Obtain a lock to the EGL context and get handle to the [`glow::Context`] that can be used to
     /// do rendering.
     #[track_caller]
@@ -129,14 +157,20 @@ impl crate::Instance<super::Api> for Instance {
 
     unsafe fn enumerate_adapters(&self) -> Vec<crate::ExposedAdapter<super::Api>> {
         let context_guard = self.webgl2_context.lock();
-        let gl = match *context_guard {
-            Some(ref webgl2_context) => glow::Context::from_webgl2_context(webgl2_context.clone()),
+        let webgl2_context = match *context_guard {
+            Some(ref webgl2_context) => webgl2_context.clone(),
             None => return Vec::new(),
         };
+        let gl = glow::Context::from_webgl2_context(webgl2_context.clone());
 
-        unsafe { super::Adapter::expose(AdapterContext { glow_context: gl }) }
-            .into_iter()
-            .collect()
+        unsafe {
+            super::Adapter::expose(AdapterContext {
+                glow_context: gl,
+                webgl2_context,
+            })
+        }
+        .into_iter()
+        .collect()
     }
 
     unsafe fn create_surface(
@@ -145,9 +179,11 @@ impl crate::Instance<super::Api> for Instance {
         window_handle: raw_window_handle::RawWindowHandle,
     ) -> Result<Surface, crate::InstanceError> {
         if let raw_window_handle::RawWindowHandle::Web(handle) = window_handle {
-            let canvas: web_sys::HtmlCanvasElement = web_sys::window()
+            // Workers have no document, their surfaces are created from an `OffscreenCanvas`.
+            let document = web_sys::window()
                 .and_then(|win| win.document())
-                .expect("Cannot get document")
+                .ok_or(crate::InstanceError)?;
+            let canvas: web_sys::HtmlCanvasElement = document
                 .query_selector(&format!("canvas[data-raw-handle=\"{}\"]", handle.id))
                 .expect("Cannot query for canvas")
                 .expect("Canvas is not found")
@@ -288,6 +324,10 @@ impl Surface {
         // present.frag takes care of handling srgb conversion
         true
     }
+
+    pub fn composite_alpha_modes(&self) -> Vec<wgt::CompositeAlphaMode> {
+        vec![wgt::CompositeAlphaMode::Opaque]
+    }
 }
 
 impl crate::Surface<super::Api> for Surface {
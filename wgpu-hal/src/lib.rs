@@ -95,6 +95,7 @@ use std::{
 
 use bitflags::bitflags;
 use thiserror::Error;
+pub use wgt::InstanceFlags;
 use wgt::{WasmNotSend, WasmNotSync};
 
 pub const MAX_ANISOTROPY: u8 = 16;
@@ -108,6 +109,15 @@ pub const QUERY_SIZE: wgt::BufferAddress = 8;
 pub type Label<'a> = Option<&'a str>;
 pub type MemoryRange = Range<wgt::BufferAddress>;
 pub type FenceValue = u64;
+/// Values of the pipeline-overridable constants of a shader stage.
+///
+/// Keys are either the name of the overridable constant, or its numeric id
+/// formatted as a decimal string.
+///
+/// The overridable constants are those naga marks with an [`naga::Override`].
+/// With the naga version in use, only the SPIR-V frontend produces them, from
+/// specialization constants; WGSL `override` declarations don't parse yet.
+pub type PipelineConstants = std::collections::HashMap<String, f64>;
 
 /// Drop guard to signal wgpu-hal is no longer using an externally created object.
 pub type DropGuard = Box<dyn std::any::Any + Send + Sync>;
@@ -180,6 +190,9 @@ pub trait Api: Clone + Sized {
     type ShaderModule: fmt::Debug + WasmNotSend + WasmNotSync;
     type RenderPipeline: WasmNotSend + WasmNotSync;
     type ComputePipeline: WasmNotSend + WasmNotSync;
+    type PipelineCache: fmt::Debug + WasmNotSend + WasmNotSync;
+
+    type AccelerationStructure: fmt::Debug + WasmNotSend + WasmNotSync + 'static;
 }
 
 pub trait Instance<A: Api>: Sized + WasmNotSend + WasmNotSync {
@@ -220,10 +233,15 @@ pub trait Surface<A: Api>: WasmNotSend + WasmNotSync {
 }
 
 pub trait Adapter<A: Api>: WasmNotSend + WasmNotSync {
+    /// Opens a device with its main queue, and a queue of each kind in
+    /// `additional_queues`.
+    ///
+    /// Backends that can't provide the additional queues return an error.
     unsafe fn open(
         &self,
         features: wgt::Features,
         limits: &wgt::Limits,
+        additional_queues: &[wgt::QueueKind],
     ) -> Result<OpenDevice<A>, DeviceError>;
 
     /// Return the set of supported capabilities for a texture format.
@@ -246,6 +264,10 @@ pub trait Adapter<A: Api>: WasmNotSend + WasmNotSync {
 pub trait Device<A: Api>: WasmNotSend + WasmNotSync {
     /// Exit connection to this logical device.
     unsafe fn exit(self, queue: A::Queue);
+    /// Destroys one of the [`OpenDevice::additional_queues`].
+    ///
+    /// All of its submissions must be done.
+    unsafe fn destroy_queue(&self, queue: A::Queue);
     /// Creates a new buffer.
     ///
     /// The initial usage is `BufferUses::empty()`.
@@ -318,6 +340,17 @@ pub trait Device<A: Api>: WasmNotSend + WasmNotSync {
         desc: &ComputePipelineDescriptor<A>,
     ) -> Result<A::ComputePipeline, PipelineError>;
     unsafe fn destroy_compute_pipeline(&self, pipeline: A::ComputePipeline);
+    /// Creates a pipeline cache, seeded with `desc.data`.
+    ///
+    /// Data that isn't compatible with this device is ignored, leaving the cache empty.
+    unsafe fn create_pipeline_cache(
+        &self,
+        desc: &PipelineCacheDescriptor,
+    ) -> Result<A::PipelineCache, DeviceError>;
+    unsafe fn destroy_pipeline_cache(&self, cache: A::PipelineCache);
+    /// Returns the contents of `cache`, to be passed to [`Device::create_pipeline_cache`]
+    /// on a later run, or `None` if the backend can't serialize pipeline caches.
+    unsafe fn pipeline_cache_get_data(&self, cache: &A::PipelineCache) -> Option<Vec<u8>>;
 
     unsafe fn create_query_set(
         &self,
@@ -337,6 +370,35 @@ pub trait Device<A: Api>: WasmNotSend + WasmNotSync {
 
     unsafe fn start_capture(&self) -> bool;
     unsafe fn stop_capture(&self);
+
+    /// Returns statistics from the device's memory allocator, or `None` if the
+    /// backend doesn't track any.
+    unsafe fn allocator_report(&self) -> Option<AllocatorReport>;
+
+    /// Creates a new acceleration structure, with storage for `desc.size` bytes.
+    ///
+    /// The size should come from [`Device::get_acceleration_structure_build_sizes`].
+    /// Requires [`wgt::Features::RAY_TRACING_ACCELERATION_STRUCTURE`].
+    unsafe fn create_acceleration_structure(
+        &self,
+        desc: &AccelerationStructureDescriptor,
+    ) -> Result<A::AccelerationStructure, DeviceError>;
+    /// Returns the storage and scratch sizes needed to build an acceleration structure
+    /// from `desc.entries`. The buffers of the entries are ignored.
+    unsafe fn get_acceleration_structure_build_sizes(
+        &self,
+        desc: &GetAccelerationStructureBuildSizesDescriptor<A>,
+    ) -> AccelerationStructureBuildSizes;
+    /// Returns the address of a bottom-level acceleration structure, to be written
+    /// into the instances of a top-level acceleration structure.
+    unsafe fn get_acceleration_structure_device_address(
+        &self,
+        acceleration_structure: &A::AccelerationStructure,
+    ) -> wgt::BufferAddress;
+    unsafe fn destroy_acceleration_structure(
+        &self,
+        acceleration_structure: A::AccelerationStructure,
+    );
 }
 
 pub trait Queue<A: Api>: WasmNotSend + WasmNotSync {
@@ -346,10 +408,26 @@ pub trait Queue<A: Api>: WasmNotSend + WasmNotSync {
     /// - all of the command buffers were created from command pools
     ///   that are associated with this queue.
     /// - all of the command buffers had `CommadBuffer::finish()` called.
+    /// - a command buffer is only submitted again, without being reset, once its
+    ///   previous submission is done, and only if it was begun as reusable.
+    ///
+    /// The `label`, if any, tags the submission for external profilers and
+    /// debuggers, where the backend has a way to do so.
     unsafe fn submit(
         &mut self,
         command_buffers: &[&A::CommandBuffer],
         signal_fence: Option<(&mut A::Fence, FenceValue)>,
+        label: Label,
+    ) -> Result<(), DeviceError>;
+    /// Makes the next submission on this queue wait until `fence`, which is signaled by
+    /// another queue of the same device, reaches `value`.
+    ///
+    /// Backends wait on the GPU where they can, and otherwise block until the fence is
+    /// signaled. Fails if the backend has to block and `value` was never submitted.
+    unsafe fn wait_for_fence(
+        &mut self,
+        fence: &A::Fence,
+        value: FenceValue,
     ) -> Result<(), DeviceError>;
     unsafe fn present(
         &mut self,
@@ -365,7 +443,10 @@ pub trait Queue<A: Api>: WasmNotSend + WasmNotSync {
 /// then submitted to a queue, and then it needs to be `reset_all()`.
 pub trait CommandEncoder<A: Api>: WasmNotSend + WasmNotSync + fmt::Debug {
     /// Begin encoding a new command buffer.
-    unsafe fn begin_encoding(&mut self, label: Label) -> Result<(), DeviceError>;
+    ///
+    /// If `reusable`, the command buffer may be submitted more than once, see
+    /// `Features::REUSABLE_COMMAND_BUFFERS`.
+    unsafe fn begin_encoding(&mut self, label: Label, reusable: bool) -> Result<(), DeviceError>;
     /// Discard currently recorded list, if any.
     unsafe fn discard_encoding(&mut self);
     unsafe fn end_encoding(&mut self) -> Result<A::CommandBuffer, DeviceError>;
@@ -388,6 +469,7 @@ pub trait CommandEncoder<A: Api>: WasmNotSend + WasmNotSync + fmt::Debug {
 
     unsafe fn clear_buffer(&mut self, buffer: &A::Buffer, range: MemoryRange);
 
+    /// `src` and `dst` may be the same buffer, in which case the regions don't overlap.
     unsafe fn copy_buffer_to_buffer<T>(&mut self, src: &A::Buffer, dst: &A::Buffer, regions: T)
     where
         T: Iterator<Item = BufferCopy>;
@@ -451,6 +533,21 @@ pub trait CommandEncoder<A: Api>: WasmNotSend + WasmNotSync + fmt::Debug {
         dynamic_offsets: &[wgt::DynamicOffset],
     );
 
+    /// Changes the dynamic offsets of the bind group at `index`.
+    ///
+    /// `group` must be the group last set at `index` with [`set_bind_group`], and `layout`
+    /// must be compatible with the layout it was set with. Backends can skip the bindings
+    /// without dynamic offsets.
+    ///
+    /// [`set_bind_group`]: CommandEncoder::set_bind_group
+    unsafe fn set_bind_group_dynamic_offsets(
+        &mut self,
+        layout: &A::PipelineLayout,
+        index: u32,
+        group: &A::BindGroup,
+        dynamic_offsets: &[wgt::DynamicOffset],
+    );
+
     unsafe fn set_push_constants(
         &mut self,
         layout: &A::PipelineLayout,
@@ -500,8 +597,32 @@ pub trait CommandEncoder<A: Api>: WasmNotSend + WasmNotSync + fmt::Debug {
     unsafe fn set_vertex_buffer<'a>(&mut self, index: u32, binding: BufferBinding<'a, A>);
     unsafe fn set_viewport(&mut self, rect: &Rect<f32>, depth_range: Range<f32>);
     unsafe fn set_scissor_rect(&mut self, rect: &Rect<u32>);
+    /// Sets the viewport at `index` of the viewport array, used by primitives
+    /// whose shader selects that viewport.
+    ///
+    /// Only called when `wgt::Features::MULTI_VIEWPORT` is enabled, with `index` less
+    /// than `wgt::MAX_VIEWPORTS`. All viewports and scissor rects cover the whole
+    /// render target at the start of every render pass.
+    unsafe fn set_viewport_indexed(
+        &mut self,
+        index: u32,
+        rect: &Rect<f32>,
+        depth_range: Range<f32>,
+    );
+    /// Sets the scissor rect at `index` of the scissor array, see [`Self::set_viewport_indexed`].
+    unsafe fn set_scissor_rect_indexed(&mut self, index: u32, rect: &Rect<u32>);
     unsafe fn set_stencil_reference(&mut self, value: u32);
     unsafe fn set_blend_constants(&mut self, color: &[f32; 4]);
+    /// Sets the shading rate of the following draws.
+    ///
+    /// Only called when `wgt::Features::SHADING_RATE` is enabled. The rate is reset
+    /// to `Rate1x1` at the start of every render pass.
+    unsafe fn set_shading_rate(&mut self, rate: wgt::ShadingRate);
+    /// Sets the bounds of the depth bounds test of the following draws.
+    ///
+    /// Only called when `wgt::Features::DEPTH_BOUNDS_TEST` is enabled. The bounds
+    /// are reset to `0.0..1.0` at the start of every render pass.
+    unsafe fn set_depth_bounds(&mut self, bounds: Range<f32>);
 
     unsafe fn draw(
         &mut self,
@@ -557,18 +678,25 @@ pub trait CommandEncoder<A: Api>: WasmNotSend + WasmNotSync + fmt::Debug {
 
     unsafe fn dispatch(&mut self, count: [u32; 3]);
     unsafe fn dispatch_indirect(&mut self, buffer: &A::Buffer, offset: wgt::BufferAddress);
-}
 
-bitflags!(
-    /// Instance initialization flags.
-    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-    pub struct InstanceFlags: u32 {
-        /// Generate debug information in shaders and objects.
-        const DEBUG = 1 << 0;
-        /// Enable validation, if possible.
-        const VALIDATION = 1 << 1;
-    }
-);
+    // ray tracing
+
+    /// Builds or updates each acceleration structure in `descriptors`.
+    ///
+    /// Must be called outside of any pass. The input buffers must be in the
+    /// `*_ACCELERATION_STRUCTURE_INPUT` uses, the scratch buffers in
+    /// [`BufferUses::ACCELERATION_STRUCTURE_SCRATCH`], and the scratch ranges must not
+    /// overlap. Bottom-level structures referenced by a top-level one must be built by
+    /// an earlier call, followed by [`CommandEncoder::place_acceleration_structure_barrier`].
+    unsafe fn build_acceleration_structures(
+        &mut self,
+        descriptors: &[BuildAccelerationStructureDescriptor<A>],
+    );
+    unsafe fn place_acceleration_structure_barrier(
+        &mut self,
+        barrier: AccelerationStructureBarrier,
+    );
+}
 
 bitflags!(
     /// Pipeline layout creation flags.
@@ -641,6 +769,8 @@ bitflags!(
         const COLOR = 1 << 0;
         const DEPTH = 1 << 1;
         const STENCIL = 1 << 2;
+        const PLANE_0 = 1 << 3;
+        const PLANE_1 = 1 << 4;
     }
 );
 
@@ -650,6 +780,8 @@ impl FormatAspects {
             wgt::TextureAspect::All => Self::all(),
             wgt::TextureAspect::DepthOnly => Self::DEPTH,
             wgt::TextureAspect::StencilOnly => Self::STENCIL,
+            wgt::TextureAspect::Plane0 => Self::PLANE_0,
+            wgt::TextureAspect::Plane1 => Self::PLANE_1,
         };
         Self::from(format) & aspect_mask
     }
@@ -664,6 +796,8 @@ impl FormatAspects {
             Self::COLOR => wgt::TextureAspect::All,
             Self::DEPTH => wgt::TextureAspect::DepthOnly,
             Self::STENCIL => wgt::TextureAspect::StencilOnly,
+            Self::PLANE_0 => wgt::TextureAspect::Plane0,
+            Self::PLANE_1 => wgt::TextureAspect::Plane1,
             _ => unreachable!(),
         }
     }
@@ -679,6 +813,7 @@ impl From<wgt::TextureFormat> for FormatAspects {
             wgt::TextureFormat::Depth32FloatStencil8 | wgt::TextureFormat::Depth24PlusStencil8 => {
                 Self::DEPTH | Self::STENCIL
             }
+            wgt::TextureFormat::NV12 => Self::PLANE_0 | Self::PLANE_1,
             _ => Self::COLOR,
         }
     }
@@ -687,8 +822,12 @@ impl From<wgt::TextureFormat> for FormatAspects {
 bitflags!(
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
     pub struct MemoryFlags: u32 {
+        /// Short-lived resource. For textures, the contents never outlive a render
+        /// pass, so tile-based GPUs may not back them with memory.
         const TRANSIENT = 1 << 0;
         const PREFER_COHERENT = 1 << 1;
+        /// Allocate from protected memory. Requires `Features::PROTECTED_CONTENT`.
+        const PROTECTED = 1 << 2;
     }
 );
 
@@ -728,12 +867,21 @@ bitflags::bitflags! {
         const INDIRECT = 1 << 9;
         /// A buffer used to store query results.
         const QUERY_RESOLVE = 1 << 10;
+        /// The scratch memory of an acceleration structure build.
+        const ACCELERATION_STRUCTURE_SCRATCH = 1 << 11;
+        /// The vertices, indices, transforms or AABBs of a bottom-level acceleration structure build.
+        const BOTTOM_LEVEL_ACCELERATION_STRUCTURE_INPUT = 1 << 12;
+        /// The instances of a top-level acceleration structure build.
+        const TOP_LEVEL_ACCELERATION_STRUCTURE_INPUT = 1 << 13;
         /// The combination of states that a buffer may be in _at the same time_.
         const INCLUSIVE = Self::MAP_READ.bits() | Self::COPY_SRC.bits() |
             Self::INDEX.bits() | Self::VERTEX.bits() | Self::UNIFORM.bits() |
-            Self::STORAGE_READ.bits() | Self::INDIRECT.bits();
+            Self::STORAGE_READ.bits() | Self::INDIRECT.bits() |
+            Self::BOTTOM_LEVEL_ACCELERATION_STRUCTURE_INPUT.bits() |
+            Self::TOP_LEVEL_ACCELERATION_STRUCTURE_INPUT.bits();
         /// The combination of states that a buffer must exclusively be in.
-        const EXCLUSIVE = Self::MAP_WRITE.bits() | Self::COPY_DST.bits() | Self::STORAGE_READ_WRITE.bits();
+        const EXCLUSIVE = Self::MAP_WRITE.bits() | Self::COPY_DST.bits() | Self::STORAGE_READ_WRITE.bits() |
+            Self::ACCELERATION_STRUCTURE_SCRATCH.bits();
         /// The combination of all usages that the are guaranteed to be be ordered by the hardware.
         /// If a usage is ordered, then if the buffer state doesn't change between draw calls, there
         /// are no barriers needed for synchronization.
@@ -851,6 +999,12 @@ pub struct SurfaceCapabilities {
     ///
     /// Must be at least one.
     pub composite_alpha_modes: Vec<wgt::CompositeAlphaMode>,
+
+    /// List of supported pairs of format and color space.
+    ///
+    /// Every format in `formats` must appear in at least one pair, and every
+    /// color space must be compatible with the format it is paired with.
+    pub color_spaces: Vec<(wgt::TextureFormat, wgt::SurfaceColorSpace)>,
 }
 
 #[derive(Debug)]
@@ -866,6 +1020,8 @@ pub struct AcquiredSurfaceTexture<A: Api> {
 pub struct OpenDevice<A: Api> {
     pub device: A::Device,
     pub queue: A::Queue,
+    /// The queues requested in addition to `queue`, in the requested order.
+    pub additional_queues: Vec<A::Queue>,
 }
 
 #[derive(Clone, Debug)]
@@ -874,6 +1030,18 @@ pub struct BufferMapping {
     pub is_coherent: bool,
 }
 
+/// Statistics from a device's memory allocator, see [`Device::allocator_report`].
+#[derive(Clone, Debug, Default)]
+pub struct AllocatorReport {
+    /// Number of memory allocations currently made from the driver, if known.
+    pub allocation_count: Option<u64>,
+    /// Total size in bytes of the memory currently allocated, if known.
+    ///
+    /// On some backends this is the usage of the whole process, not only of
+    /// this device.
+    pub allocated_bytes: Option<u64>,
+}
+
 #[derive(Clone, Debug)]
 pub struct BufferDescriptor<'a> {
     pub label: Label<'a>,
@@ -931,6 +1099,8 @@ pub struct TextureViewDescriptor<'a> {
     pub dimension: wgt::TextureViewDimension,
     pub usage: TextureUses,
     pub range: wgt::ImageSubresourceRange,
+    /// Must be the identity unless the view is only used as [`TextureUses::RESOURCE`].
+    pub swizzle: wgt::TextureSwizzle,
 }
 
 #[derive(Clone, Debug)]
@@ -1045,12 +1215,16 @@ pub struct BindGroupDescriptor<'a, A: Api> {
     pub samplers: &'a [&'a A::Sampler],
     pub textures: &'a [TextureBinding<'a, A>],
     pub entries: &'a [BindGroupEntry],
+    pub acceleration_structures: &'a [&'a A::AccelerationStructure],
 }
 
 #[derive(Clone, Debug)]
 pub struct CommandEncoderDescriptor<'a, A: Api> {
     pub label: Label<'a>,
     pub queue: &'a A::Queue,
+    /// Record command buffers that can access protected resources, but must not write to
+    /// unprotected ones. Requires `Features::PROTECTED_CONTENT`.
+    pub protected: bool,
 }
 
 /// Naga shader module.
@@ -1079,6 +1253,8 @@ pub enum ShaderInput<'a> {
 pub struct ShaderModuleDescriptor<'a> {
     pub label: Label<'a>,
     pub runtime_checks: bool,
+    /// Whether `var<workgroup>` memory is zero-initialized at the start of each workgroup.
+    pub zero_initialize_workgroup_memory: bool,
 }
 
 /// Describes a programmable pipeline stage.
@@ -1089,6 +1265,11 @@ pub struct ProgrammableStage<'a, A: Api> {
     /// The name of the entry point in the compiled shader. There must be a function with this name
     ///  in the shader.
     pub entry_point: &'a str,
+    /// Values for the pipeline-overridable constants of the shader.
+    ///
+    /// The constants must exist in the shader, and the values must be representable
+    /// by their types.
+    pub constants: &'a PipelineConstants,
 }
 
 // Rust gets confused about the impl requirements for `A`
@@ -1097,10 +1278,18 @@ impl<A: Api> Clone for ProgrammableStage<'_, A> {
         Self {
             module: self.module,
             entry_point: self.entry_point,
+            constants: self.constants,
         }
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct PipelineCacheDescriptor<'a> {
+    pub label: Label<'a>,
+    /// Data previously returned by [`Device::pipeline_cache_get_data`].
+    pub data: Option<&'a [u8]>,
+}
+
 /// Describes a compute pipeline.
 #[derive(Clone, Debug)]
 pub struct ComputePipelineDescriptor<'a, A: Api> {
@@ -1109,6 +1298,8 @@ pub struct ComputePipelineDescriptor<'a, A: Api> {
     pub layout: &'a A::PipelineLayout,
     /// The compiled compute stage and its entry point.
     pub stage: ProgrammableStage<'a, A>,
+    /// The cache to look the pipeline up in and record it into.
+    pub cache: Option<&'a A::PipelineCache>,
 }
 
 /// Describes how the vertex buffer is interpreted.
@@ -1145,6 +1336,8 @@ pub struct RenderPipelineDescriptor<'a, A: Api> {
     /// If the pipeline will be used with a multiview render pass, this indicates how many array
     /// layers the attachments will have.
     pub multiview: Option<NonZeroU32>,
+    /// The cache to look the pipeline up in and record it into.
+    pub cache: Option<&'a A::PipelineCache>,
 }
 
 #[derive(Debug, Clone)]
@@ -1152,10 +1345,16 @@ pub struct SurfaceConfiguration {
     /// Number of textures in the swap chain. Must be in
     /// `SurfaceCapabilities::swap_chain_size` range.
     pub swap_chain_size: u32,
+    /// Maximum number of frames queued for presentation. Must be at least 1
+    /// and at most `swap_chain_size`.
+    pub maximum_frame_latency: u32,
     /// Vertical synchronization mode.
     pub present_mode: wgt::PresentMode,
     /// Alpha composition mode.
     pub composite_alpha_mode: wgt::CompositeAlphaMode,
+    /// Color space of the surface textures. Must be paired with `format` in
+    /// `SurfaceCapabilities::color_spaces`.
+    pub color_space: wgt::SurfaceColorSpace,
     /// Format of the surface textures.
     pub format: wgt::TextureFormat,
     /// Requested texture extent. Must be in
@@ -1166,6 +1365,9 @@ pub struct SurfaceConfiguration {
     /// Allows views of swapchain texture to have a different format
     /// than the texture does.
     pub view_formats: Vec<wgt::TextureFormat>,
+    /// Create protected surface textures, which only protected command
+    /// buffers can access. Requires `Features::PROTECTED_CONTENT`.
+    pub protected: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -1248,6 +1450,8 @@ impl<A: Api> Clone for Attachment<'_, A> {
 #[derive(Debug)]
 pub struct ColorAttachment<'a, A: Api> {
     pub target: Attachment<'a, A>,
+    /// The depth slice to render to, if `target` is a 3D texture view.
+    pub depth_slice: Option<u32>,
     pub resolve_target: Option<Attachment<'a, A>>,
     pub ops: AttachmentOps,
     pub clear_value: wgt::Color,
@@ -1258,6 +1462,7 @@ impl<A: Api> Clone for ColorAttachment<'_, A> {
     fn clone(&self) -> Self {
         Self {
             target: self.target.clone(),
+            depth_slice: self.depth_slice,
             resolve_target: self.resolve_target.clone(),
             ops: self.ops,
             clear_value: self.clear_value,
@@ -1327,6 +1532,160 @@ pub struct ComputePassDescriptor<'a, A: Api> {
     pub timestamp_writes: Option<ComputePassTimestampWrites<'a, A>>,
 }
 
+#[derive(Clone, Debug)]
+pub struct AccelerationStructureDescriptor<'a> {
+    pub label: Label<'a>,
+    pub size: wgt::BufferAddress,
+    pub format: AccelerationStructureFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccelerationStructureFormat {
+    /// Contains instances of bottom-level acceleration structures.
+    TopLevel,
+    /// Contains triangles or axis-aligned bounding boxes.
+    BottomLevel,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccelerationStructureBuildMode {
+    /// Build from scratch.
+    Build,
+    /// Refit the source acceleration structure, which must have been built with
+    /// [`AccelerationStructureBuildFlags::ALLOW_UPDATE`] from the same geometry layout.
+    Update,
+}
+
+/// Sizes returned by [`Device::get_acceleration_structure_build_sizes`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AccelerationStructureBuildSizes {
+    pub acceleration_structure_size: wgt::BufferAddress,
+    pub update_scratch_size: wgt::BufferAddress,
+    pub build_scratch_size: wgt::BufferAddress,
+}
+
+#[derive(Debug)]
+pub struct GetAccelerationStructureBuildSizesDescriptor<'a, A: Api> {
+    pub entries: &'a AccelerationStructureEntries<'a, A>,
+    pub flags: AccelerationStructureBuildFlags,
+}
+
+/// The geometry of an acceleration structure.
+///
+/// Top-level structures take instances, bottom-level ones take either triangles or AABBs.
+#[derive(Debug)]
+pub enum AccelerationStructureEntries<'a, A: Api> {
+    Instances(AccelerationStructureInstances<'a, A>),
+    Triangles(&'a [AccelerationStructureTriangles<'a, A>]),
+    AABBs(&'a [AccelerationStructureAABBs<'a, A>]),
+}
+
+/// Instances of a top-level acceleration structure.
+///
+/// Each instance is laid out as `VkAccelerationStructureInstanceKHR` (64 bytes), with
+/// the acceleration structure reference coming from
+/// [`Device::get_acceleration_structure_device_address`].
+#[derive(Debug)]
+pub struct AccelerationStructureInstances<'a, A: Api> {
+    /// Only `None` when querying build sizes.
+    pub buffer: Option<&'a A::Buffer>,
+    pub offset: u32,
+    pub count: u32,
+}
+
+#[derive(Debug)]
+pub struct AccelerationStructureTriangles<'a, A: Api> {
+    /// Only `None` when querying build sizes.
+    pub vertex_buffer: Option<&'a A::Buffer>,
+    pub vertex_format: wgt::VertexFormat,
+    pub first_vertex: u32,
+    pub vertex_count: u32,
+    pub vertex_stride: wgt::BufferAddress,
+    pub indices: Option<AccelerationStructureTriangleIndices<'a, A>>,
+    pub transform: Option<AccelerationStructureTriangleTransform<'a, A>>,
+    pub flags: AccelerationStructureGeometryFlags,
+}
+
+#[derive(Debug)]
+pub struct AccelerationStructureTriangleIndices<'a, A: Api> {
+    pub format: wgt::IndexFormat,
+    /// Only `None` when querying build sizes.
+    pub buffer: Option<&'a A::Buffer>,
+    pub offset: u32,
+    pub count: u32,
+}
+
+/// A row-major 3x4 matrix of `f32`s, applied to the vertices of the triangles.
+#[derive(Debug)]
+pub struct AccelerationStructureTriangleTransform<'a, A: Api> {
+    pub buffer: &'a A::Buffer,
+    pub offset: u32,
+}
+
+/// Axis-aligned bounding boxes, each made of six `f32`s: the minimum then the maximum corner.
+#[derive(Debug)]
+pub struct AccelerationStructureAABBs<'a, A: Api> {
+    /// Only `None` when querying build sizes.
+    pub buffer: Option<&'a A::Buffer>,
+    pub offset: u32,
+    pub count: u32,
+    pub stride: wgt::BufferAddress,
+    pub flags: AccelerationStructureGeometryFlags,
+}
+
+#[derive(Debug)]
+pub struct BuildAccelerationStructureDescriptor<'a, A: Api> {
+    pub entries: &'a AccelerationStructureEntries<'a, A>,
+    pub mode: AccelerationStructureBuildMode,
+    pub flags: AccelerationStructureBuildFlags,
+    /// Required by [`AccelerationStructureBuildMode::Update`], may be the same as the destination.
+    pub source_acceleration_structure: Option<&'a A::AccelerationStructure>,
+    pub destination_acceleration_structure: &'a A::AccelerationStructure,
+    pub scratch_buffer: &'a A::Buffer,
+    pub scratch_buffer_offset: wgt::BufferAddress,
+}
+
+bitflags!(
+    /// Acceleration structure build flags.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    pub struct AccelerationStructureBuildFlags: u32 {
+        const PREFER_FAST_TRACE = 1 << 0;
+        const PREFER_FAST_BUILD = 1 << 1;
+        /// Allow later builds in [`AccelerationStructureBuildMode::Update`] mode.
+        const ALLOW_UPDATE = 1 << 2;
+        const ALLOW_COMPACTION = 1 << 3;
+        const LOW_MEMORY = 1 << 4;
+    }
+);
+
+bitflags!(
+    /// Acceleration structure geometry flags.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    pub struct AccelerationStructureGeometryFlags: u32 {
+        /// The geometry never invokes any-hit logic.
+        const OPAQUE = 1 << 0;
+        const NO_DUPLICATE_ANY_HIT_INVOCATION = 1 << 1;
+    }
+);
+
+bitflags!(
+    /// Similar to `BufferUses` but for acceleration structures.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    pub struct AccelerationStructureUses: u8 {
+        /// The source of an update, or a bottom-level structure used by a top-level build.
+        const BUILD_INPUT = 1 << 0;
+        /// The destination of a build.
+        const BUILD_OUTPUT = 1 << 1;
+        /// Traced against in a shader.
+        const SHADER_INPUT = 1 << 2;
+    }
+);
+
+#[derive(Debug, Clone)]
+pub struct AccelerationStructureBarrier {
+    pub usage: Range<AccelerationStructureUses>,
+}
+
 /// Stores if any API validation error has occurred in this process
 /// since it was last reset.
 ///
@@ -21,7 +21,12 @@ impl crate::Adapter<super::Api> for super::Adapter {
         &self,
         features: wgt::Features,
         _limits: &wgt::Limits,
+        additional_queues: &[wgt::QueueKind],
     ) -> Result<crate::OpenDevice<super::Api>, crate::DeviceError> {
+        if !additional_queues.is_empty() {
+            log::error!("Additional queues are not supported on Metal");
+            return Err(crate::DeviceError::ResourceCreationFailed);
+        }
         let queue = self
             .shared
             .device
@@ -56,6 +61,7 @@ impl crate::Adapter<super::Api> for super::Adapter {
 
         Ok(crate::OpenDevice {
             device: super::Device {
+                pipeline_cache: Mutex::new(super::PipelineArchive::new(&self.shared, None)),
                 shared: Arc::clone(&self.shared),
                 features,
             },
@@ -63,6 +69,7 @@ impl crate::Adapter<super::Api> for super::Adapter {
                 raw: Arc::new(Mutex::new(queue)),
                 timestamp_period,
             },
+            additional_queues: Vec::new(),
         })
     }
 
@@ -225,6 +232,9 @@ impl crate::Adapter<super::Api> for super::Adapter {
                 }
                 flags
             }
+            // Metal has no multi-planar pixel formats, video frames are
+            // exposed as one texture per plane instead.
+            Tf::NV12 => Tfc::empty(),
             Tf::Rgb9e5Ufloat => {
                 if pc.msaa_apple3 {
                     all_caps
@@ -309,9 +319,21 @@ impl crate::Adapter<super::Api> for super::Adapter {
             formats.push(wgt::TextureFormat::Rgb10a2Unorm);
         }
 
+        let mut color_spaces = vec![wgt::SurfaceColorSpace::Srgb];
+        if cfg!(target_os = "macos") {
+            color_spaces.push(wgt::SurfaceColorSpace::DisplayP3);
+            color_spaces.push(wgt::SurfaceColorSpace::ExtendedSrgbLinear);
+            if self.shared.private_caps.format_rgb10a2_unorm_all {
+                color_spaces.push(wgt::SurfaceColorSpace::Hdr10);
+            }
+        }
+
+        let color_spaces = crate::auxil::compatible_color_spaces(&formats, &color_spaces);
+
         let pc = &self.shared.private_caps;
         Some(crate::SurfaceCapabilities {
             formats,
+            color_spaces,
             //Note: this is hardcoded in `CAMetalLayer` documentation
             swap_chain_sizes: if pc.can_set_maximum_drawables_count {
                 2..=3
@@ -464,6 +486,71 @@ const DEPTH_CLIP_MODE: &[MTLFeatureSet] = &[
 
 const OS_NOT_SUPPORT: (usize, usize) = (10000, 0);
 
+/// The `MTLGPUFamily` generations of a device, which most limits follow.
+///
+/// See the Metal feature set tables: <https://developer.apple.com/metal/Metal-Feature-Set-Tables.pdf>
+#[derive(Clone, Copy, Debug)]
+struct GpuFamilies {
+    /// The highest `AppleN` family, 0 for GPUs not designed by Apple.
+    apple: u32,
+    /// The highest `MacN` family, 0 outside of macOS.
+    mac: u32,
+}
+
+impl GpuFamilies {
+    fn new(device: &metal::DeviceRef, family_check: bool) -> Self {
+        const APPLE: &[(MTLGPUFamily, u32)] = &[
+            (MTLGPUFamily::Apple7, 7),
+            (MTLGPUFamily::Apple6, 6),
+            (MTLGPUFamily::Apple5, 5),
+            (MTLGPUFamily::Apple4, 4),
+            (MTLGPUFamily::Apple3, 3),
+            (MTLGPUFamily::Apple2, 2),
+            (MTLGPUFamily::Apple1, 1),
+        ];
+        const MAC: &[(MTLGPUFamily, u32)] = &[(MTLGPUFamily::Mac2, 2), (MTLGPUFamily::Mac1, 1)];
+        // `supportsFamily:` isn't available before macOS 10.15 and iOS 13, the
+        // families are deduced from the equivalent feature sets there.
+        const APPLE_FEATURE_SETS: &[(MTLFeatureSet, u32)] = &[
+            (MTLFeatureSet::iOS_GPUFamily5_v1, 5),
+            (MTLFeatureSet::iOS_GPUFamily4_v1, 4),
+            (MTLFeatureSet::tvOS_GPUFamily2_v1, 4),
+            (MTLFeatureSet::iOS_GPUFamily3_v1, 3),
+            (MTLFeatureSet::iOS_GPUFamily2_v1, 2),
+            (MTLFeatureSet::tvOS_GPUFamily1_v1, 2),
+            (MTLFeatureSet::iOS_GPUFamily1_v1, 1),
+        ];
+        const MAC_FEATURE_SETS: &[(MTLFeatureSet, u32)] = &[
+            (MTLFeatureSet::macOS_GPUFamily2_v1, 2),
+            (MTLFeatureSet::macOS_GPUFamily1_v1, 1),
+        ];
+
+        if family_check {
+            let highest = |families: &[(MTLGPUFamily, u32)]| {
+                families
+                    .iter()
+                    .find(|&&(family, _)| device.supports_family(family))
+                    .map_or(0, |&(_, generation)| generation)
+            };
+            Self {
+                apple: highest(APPLE),
+                mac: highest(MAC),
+            }
+        } else {
+            let highest = |feature_sets: &[(MTLFeatureSet, u32)]| {
+                feature_sets
+                    .iter()
+                    .find(|&&(feature_set, _)| device.supports_feature_set(feature_set))
+                    .map_or(0, |&(_, generation)| generation)
+            };
+            Self {
+                apple: highest(APPLE_FEATURE_SETS),
+                mac: highest(MAC_FEATURE_SETS),
+            }
+        }
+    }
+}
+
 impl super::PrivateCapabilities {
     fn supports_any(raw: &metal::DeviceRef, features_sets: &[MTLFeatureSet]) -> bool {
         features_sets
@@ -512,6 +599,7 @@ impl super::PrivateCapabilities {
         // https://developer.apple.com/documentation/metal/developing_metal_apps_that_run_in_simulator
         let os_is_xr = version.major < 8 && device.supports_family(MTLGPUFamily::Apple2);
         let family_check = os_is_xr || version.at_least((10, 15), (13, 0), os_is_mac);
+        let families = GpuFamilies::new(device, family_check);
 
         let mut sample_count_mask = crate::TextureFormatCapabilities::MULTISAMPLE_X4; // 1 and 4 samples are supported on all devices
         if device.supports_texture_sample_count(2) {
@@ -560,11 +648,11 @@ impl super::PrivateCapabilities {
             read_write_texture_tier: rw_texture_tier,
             msaa_desktop: os_is_mac,
             msaa_apple3: if family_check {
-                device.supports_family(MTLGPUFamily::Apple3)
+                families.apple >= 3
             } else {
                 device.supports_feature_set(MTLFeatureSet::iOS_GPUFamily3_v4)
             },
-            msaa_apple7: family_check && device.supports_family(MTLGPUFamily::Apple7),
+            msaa_apple7: families.apple >= 7,
             resource_heaps: Self::supports_any(device, RESOURCE_HEAP_SUPPORT),
             argument_buffers: Self::supports_any(device, ARGUMENT_BUFFER_SUPPORT),
             shared_textures: !os_is_mac,
@@ -590,12 +678,12 @@ impl super::PrivateCapabilities {
             format_bc: os_is_mac,
             format_eac_etc: !os_is_mac
                 // M1 in macOS supports EAC/ETC2
-                || (family_check && device.supports_family(MTLGPUFamily::Apple7)),
+                || families.apple >= 7,
             // A8(Apple2) and later always support ASTC pixel formats
-            format_astc: (family_check && device.supports_family(MTLGPUFamily::Apple2))
+            format_astc: families.apple >= 2
                 || Self::supports_any(device, ASTC_PIXEL_FORMAT_FEATURES),
             // A13(Apple6) M1(Apple7) and later always support HDR ASTC pixel formats
-            format_astc_hdr: family_check && device.supports_family(MTLGPUFamily::Apple6),
+            format_astc_hdr: family_check && families.apple >= 6,
             format_any8_unorm_srgb_all: Self::supports_any(device, ANY8_UNORM_SRGB_ALL),
             format_any8_unorm_srgb_no_write: !Self::supports_any(device, ANY8_UNORM_SRGB_ALL)
                 && !os_is_mac,
@@ -650,17 +738,20 @@ impl super::PrivateCapabilities {
             format_bgr10a2_no_write: !Self::supports_any(device, BGR10A2_ALL),
             max_buffers_per_stage: 31,
             max_vertex_buffers: 31,
-            max_textures_per_stage: if os_is_mac
-                || (family_check && device.supports_family(MTLGPUFamily::Apple6))
-            {
+            max_textures_per_stage: if families.mac >= 1 || families.apple >= 6 {
                 128
-            } else if family_check && device.supports_family(MTLGPUFamily::Apple4) {
+            } else if families.apple >= 4 {
                 96
             } else {
                 31
             },
             max_samplers_per_stage: 16,
-            buffer_alignment: if os_is_mac || os_is_xr { 256 } else { 64 },
+            // Apple GPUs, including Apple silicon Macs, have much finer alignments
+            buffer_alignment: if os_is_xr || families.apple == 0 {
+                256
+            } else {
+                64
+            },
             max_buffer_size: if version.at_least((10, 14), (12, 0), os_is_mac) {
                 // maxBufferLength available on macOS 10.14+ and iOS 12.0+
                 let buffer_size: metal::NSInteger =
@@ -671,64 +762,34 @@ impl super::PrivateCapabilities {
             } else {
                 1 << 28 // 256MB on iOS 8.0+
             },
-            max_texture_size: if Self::supports_any(
-                device,
-                &[
-                    MTLFeatureSet::iOS_GPUFamily3_v1,
-                    MTLFeatureSet::tvOS_GPUFamily2_v1,
-                    MTLFeatureSet::macOS_GPUFamily1_v1,
-                ],
-            ) {
+            max_texture_size: if families.mac >= 1 || families.apple >= 3 {
                 16384
             } else {
                 8192
             },
             max_texture_3d_size: 2048,
             max_texture_layers: 2048,
-            max_fragment_input_components: if os_is_mac
-                || device.supports_feature_set(MTLFeatureSet::iOS_GPUFamily4_v1)
-            {
+            max_fragment_input_components: if families.mac >= 1 || families.apple >= 4 {
                 124
             } else {
                 60
             },
-            max_color_render_targets: if Self::supports_any(
-                device,
-                &[
-                    MTLFeatureSet::iOS_GPUFamily2_v1,
-                    MTLFeatureSet::tvOS_GPUFamily1_v1,
-                    MTLFeatureSet::macOS_GPUFamily1_v1,
-                ],
-            ) {
+            max_color_render_targets: if families.mac >= 1 || families.apple >= 2 {
                 8
             } else {
                 4
             },
-            max_varying_components: if device
-                .supports_feature_set(MTLFeatureSet::macOS_GPUFamily1_v1)
-            {
+            max_varying_components: if families.mac >= 1 || families.apple >= 4 {
                 124
             } else {
                 60
             },
-            max_threads_per_group: if Self::supports_any(
-                device,
-                &[
-                    MTLFeatureSet::iOS_GPUFamily4_v2,
-                    MTLFeatureSet::macOS_GPUFamily1_v1,
-                ],
-            ) {
+            max_threads_per_group: if families.mac >= 1 || families.apple >= 4 {
                 1024
             } else {
                 512
             },
-            max_total_threadgroup_memory: if Self::supports_any(
-                device,
-                &[
-                    MTLFeatureSet::iOS_GPUFamily4_v1,
-                    MTLFeatureSet::macOS_GPUFamily1_v2,
-                ],
-            ) {
+            max_total_threadgroup_memory: if families.mac >= 1 || families.apple >= 4 {
                 32 << 10
             } else {
                 16 << 10
@@ -742,11 +803,13 @@ impl super::PrivateCapabilities {
                     MTLFeatureSet::tvOS_GPUFamily1_v2,
                 ],
             ),
-            supports_binary_archives: family_check
-                && (device.supports_family(MTLGPUFamily::Apple3)
-                    || device.supports_family(MTLGPUFamily::Mac1)),
+            supports_binary_archives: version.at_least((11, 0), (14, 0), os_is_mac)
+                && (families.apple >= 3 || families.mac >= 1),
+            supports_shared_event: version.at_least((10, 14), (12, 0), os_is_mac),
+            supports_memoryless_storage: family_check && families.apple >= 2,
             supports_capture_manager: version.at_least((10, 13), (11, 0), os_is_mac),
             can_set_maximum_drawables_count: version.at_least((10, 14), (11, 2), os_is_mac),
+            can_present_after_minimum_duration: version.at_least((11, 0), (10, 3), os_is_mac),
             can_set_display_sync: version.at_least((10, 13), OS_NOT_SUPPORT, os_is_mac),
             can_set_next_drawable_timeout: version.at_least((10, 13), (11, 0), os_is_mac),
             supports_arrays_of_textures: Self::supports_any(
@@ -758,13 +821,12 @@ impl super::PrivateCapabilities {
                 ],
             ),
             supports_arrays_of_textures_write: family_check
-                && (device.supports_family(MTLGPUFamily::Apple6)
-                    || device.supports_family(MTLGPUFamily::Mac1)
+                && (families.apple >= 6
+                    || families.mac >= 1
                     || device.supports_family(MTLGPUFamily::MacCatalyst1)),
             supports_mutability: version.at_least((10, 13), (11, 0), os_is_mac),
             //Depth clipping is supported on all macOS GPU families and iOS family 4 and later
-            supports_depth_clip_control: os_is_mac
-                || device.supports_feature_set(MTLFeatureSet::iOS_GPUFamily4_v1),
+            supports_depth_clip_control: families.mac >= 1 || families.apple >= 4,
             supports_preserve_invariance: version.at_least((11, 0), (13, 0), os_is_mac),
             // Metal 2.2 on mac, 2.3 on iOS.
             supports_shader_primitive_index: version.at_least((10, 15), (14, 0), os_is_mac),
@@ -796,6 +858,7 @@ impl super::PrivateCapabilities {
 
         let mut features = F::empty()
             | F::INDIRECT_FIRST_INSTANCE
+            | F::UNINITIALIZED_WORKGROUP_MEMORY
             | F::MAPPABLE_PRIMARY_BUFFERS
             | F::VERTEX_WRITABLE_STORAGE
             | F::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
@@ -816,9 +879,17 @@ impl super::PrivateCapabilities {
         features.set(F::TEXTURE_COMPRESSION_ASTC, self.format_astc);
         features.set(F::TEXTURE_COMPRESSION_ASTC_HDR, self.format_astc_hdr);
         features.set(F::TEXTURE_COMPRESSION_BC, self.format_bc);
+        features.set(F::TEXTURE_COMPRESSION_BC_SLICED_3D, self.format_bc);
+        features.set(F::TEXTURE_COMPRESSION_S3TC, self.format_bc);
         features.set(F::TEXTURE_COMPRESSION_ETC2, self.format_eac_etc);
 
         features.set(F::DEPTH_CLIP_CONTROL, self.supports_depth_clip_control);
+        features.set(F::DUAL_SOURCE_BLENDING, self.dual_source_blending);
+        features.set(F::PIPELINE_CACHE, self.supports_binary_archives);
+        features.set(
+            F::FLOAT32_FILTERABLE,
+            self.format_r32float_all && self.format_rg32float_all && self.format_rgba32float_all,
+        );
         features.set(
             F::SHADER_PRIMITIVE_INDEX,
             self.supports_shader_primitive_index,
@@ -847,6 +918,7 @@ impl super::PrivateCapabilities {
         features.set(F::ADDRESS_MODE_CLAMP_TO_ZERO, true);
 
         features.set(F::RG11B10UFLOAT_RENDERABLE, self.format_rg11b10_all);
+        features.set(F::RG11B10UFLOAT_STORAGE, self.format_rg11b10_all);
 
         features
     }
@@ -869,6 +941,10 @@ impl super::PrivateCapabilities {
         downlevel
             .flags
             .set(wgt::DownlevelFlags::ANISOTROPIC_FILTERING, true);
+        // Vertex fetches past the end of a buffer are undefined in Metal.
+        downlevel
+            .flags
+            .remove(wgt::DownlevelFlags::ROBUST_INDIRECT_EXECUTION);
 
         let base = wgt::Limits::default();
         crate::Capabilities {
@@ -977,6 +1053,7 @@ impl super::PrivateCapabilities {
                     Depth32Float_Stencil8
                 }
             }
+            Tf::NV12 => Invalid,
             Tf::Rgb9e5Ufloat => RGB9E5Float,
             Tf::Bc1RgbaUnorm => BC1_RGBA,
             Tf::Bc1RgbaUnormSrgb => BC1_RGBA_sRGB,
@@ -4,6 +4,8 @@ use std::{borrow::Cow, mem, ops::Range};
 
 // has to match `Temp::binding_sizes`
 const WORD_SIZE: usize = 4;
+// `MTLMaxBlitPassSampleBuffers`, also the limit for compute and render passes
+const MAX_SAMPLE_BUFFER_ATTACHMENTS: usize = 4;
 
 impl Default for super::CommandState {
     fn default() -> Self {
@@ -18,6 +20,7 @@ impl Default for super::CommandState {
             storage_buffer_length_map: Default::default(),
             work_group_memory_sizes: Vec::new(),
             push_constants: Vec::new(),
+            pending_timer_queries: Vec::new(),
         }
     }
 }
@@ -26,14 +29,45 @@ impl super::CommandEncoder {
     fn enter_blit(&mut self) -> &metal::BlitCommandEncoderRef {
         if self.state.blit.is_none() {
             debug_assert!(self.state.render.is_none() && self.state.compute.is_none());
+            let cmd_buf = self.raw_cmd_buf.as_ref().unwrap();
+            let pending = &mut self.state.pending_timer_queries;
+            let blit = &mut self.state.blit;
             objc::rc::autoreleasepool(|| {
-                let cmd_buf = self.raw_cmd_buf.as_ref().unwrap();
-                self.state.blit = Some(cmd_buf.new_blit_command_encoder().to_owned());
+                let encoder = if pending.is_empty() {
+                    cmd_buf.new_blit_command_encoder()
+                } else {
+                    let descriptor = metal::BlitPassDescriptor::new();
+                    let count = pending.len().min(MAX_SAMPLE_BUFFER_ATTACHMENTS);
+                    for (i, (sample_buffer, index)) in pending.drain(..count).enumerate() {
+                        let sba_descriptor = descriptor
+                            .sample_buffer_attachments()
+                            .object_at(i as _)
+                            .unwrap();
+                        sba_descriptor.set_sample_buffer(&sample_buffer);
+                        sba_descriptor.set_start_of_encoder_sample_index(index as _);
+                    }
+                    cmd_buf.blit_command_encoder_with_descriptor(descriptor)
+                };
+                *blit = Some(encoder.to_owned());
             });
         }
         self.state.blit.as_ref().unwrap()
     }
 
+    /// Takes the pending timestamps to sample at the start of a pass, leaving
+    /// room for `reserved` attachments of the pass itself.
+    fn take_pending_timer_queries(
+        &mut self,
+        reserved: usize,
+    ) -> Vec<(metal::CounterSampleBuffer, u32)> {
+        // Flush the excess through empty blit encoders.
+        while self.state.pending_timer_queries.len() + reserved > MAX_SAMPLE_BUFFER_ATTACHMENTS {
+            self.enter_blit();
+            self.leave_blit();
+        }
+        mem::take(&mut self.state.pending_timer_queries)
+    }
+
     pub(super) fn leave_blit(&mut self) {
         if let Some(encoder) = self.state.blit.take() {
             encoder.end_encoding();
@@ -93,7 +127,11 @@ impl super::CommandState {
 }
 
 impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
-    unsafe fn begin_encoding(&mut self, label: crate::Label) -> Result<(), crate::DeviceError> {
+    unsafe fn begin_encoding(
+        &mut self,
+        label: crate::Label,
+        _reusable: bool,
+    ) -> Result<(), crate::DeviceError> {
         let queue = &self.raw_queue.lock();
         let retain_references = self.shared.settings.retain_command_buffer_references;
         let raw = objc::rc::autoreleasepool(move || {
@@ -123,16 +161,22 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         if let Some(encoder) = self.state.compute.take() {
             encoder.end_encoding();
         }
+        self.state.pending_timer_queries.clear();
         self.raw_cmd_buf = None;
     }
 
     unsafe fn end_encoding(&mut self) -> Result<super::CommandBuffer, crate::DeviceError> {
         self.leave_blit();
+        // Timestamps written last still need an encoder to be sampled at.
+        while !self.state.pending_timer_queries.is_empty() {
+            self.enter_blit();
+            self.leave_blit();
+        }
         debug_assert!(self.state.render.is_none());
         debug_assert!(self.state.compute.is_none());
-        Ok(super::CommandBuffer {
-            raw: self.raw_cmd_buf.take().unwrap(),
-        })
+        let raw = self.raw_cmd_buf.take().unwrap();
+        let label = raw.label().to_string();
+        Ok(super::CommandBuffer { raw, label })
     }
 
     unsafe fn reset_all<I>(&mut self, _cmd_bufs: I)
@@ -322,16 +366,19 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
             _ => {}
         }
     }
-    unsafe fn write_timestamp(&mut self, _set: &super::QuerySet, _index: u32) {
-        // TODO: If MTLCounterSamplingPoint::AtDrawBoundary/AtBlitBoundary/AtDispatchBoundary is supported,
-        //       we don't need to insert a new encoder, but can instead use respective current one.
-        //let encoder = self.enter_any().unwrap_or_else(|| self.enter_blit());
-
-        // TODO: Otherwise, we need to create a new blit command encoder with a descriptor that inserts the timestamps.
-        // Note that as of writing creating a new encoder is not exposed by the metal crate.
-        // https://developer.apple.com/documentation/metal/mtlcommandbuffer/3564431-makeblitcommandencoder
-
-        // TODO: Enable respective test in `examples/timestamp-queries/src/tests.rs`.
+    unsafe fn write_timestamp(&mut self, set: &super::QuerySet, index: u32) {
+        // Timestamps inside of passes aren't exposed, so we only get here between passes.
+        // Counters can only be sampled at encoder boundaries (`MTLCounterSamplingPoint::AtStageBoundary`),
+        // so the sample is deferred to the start of whichever encoder comes next.
+        debug_assert!(self.state.render.is_none() && self.state.compute.is_none());
+        self.leave_blit();
+        if self.state.pending_timer_queries.len() == MAX_SAMPLE_BUFFER_ATTACHMENTS {
+            self.enter_blit();
+            self.leave_blit();
+        }
+        self.state
+            .pending_timer_queries
+            .push((set.counter_sample_buffer.clone().unwrap(), index));
     }
 
     unsafe fn reset_queries(&mut self, set: &super::QuerySet, range: Range<u32>) {
@@ -363,9 +410,11 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                 );
             }
             wgt::QueryType::Timestamp => {
+                // The resolved values are GPU ticks, converted to nanoseconds
+                // with the queue's timestamp period.
                 encoder.resolve_counters(
                     set.counter_sample_buffer.as_ref().unwrap(),
-                    metal::NSRange::new(range.start as u64, range.end as u64),
+                    metal::NSRange::new(range.start as u64, (range.end - range.start) as u64),
                     &buffer.raw,
                     offset,
                 );
@@ -384,6 +433,9 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         assert!(self.state.compute.is_none());
         assert!(self.state.render.is_none());
 
+        let pending_timer_queries =
+            self.take_pending_timer_queries(desc.timestamp_writes.is_some() as usize);
+
         objc::rc::autoreleasepool(|| {
             let descriptor = metal::RenderPassDescriptor::new();
 
@@ -391,6 +443,9 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                 if let Some(at) = at.as_ref() {
                     let at_descriptor = descriptor.color_attachments().object_at(i as u64).unwrap();
                     at_descriptor.set_texture(Some(&at.target.view.raw));
+                    if let Some(depth_slice) = at.depth_slice {
+                        at_descriptor.set_depth_plane(depth_slice as u64);
+                    }
                     if let Some(ref resolve) = at.resolve_target {
                         //Note: the selection of levels and slices is already handled by `TextureView`
                         at_descriptor.set_resolve_texture(Some(&resolve.view.raw));
@@ -472,6 +527,16 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                 }
             }
 
+            let first_pending = desc.timestamp_writes.is_some() as usize;
+            for (i, (sample_buffer, index)) in pending_timer_queries.iter().enumerate() {
+                let sba_descriptor = descriptor
+                    .sample_buffer_attachments()
+                    .object_at((first_pending + i) as _)
+                    .unwrap();
+                sba_descriptor.set_sample_buffer(sample_buffer);
+                sba_descriptor.set_start_of_vertex_sample_index(*index as _);
+            }
+
             if let Some(occlusion_query_set) = desc.occlusion_query_set {
                 descriptor
                     .set_visibility_result_buffer(Some(occlusion_query_set.raw_buffer.as_ref()))
@@ -657,6 +722,16 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         }
     }
 
+    unsafe fn set_bind_group_dynamic_offsets(
+        &mut self,
+        layout: &super::PipelineLayout,
+        index: u32,
+        group: &super::BindGroup,
+        dynamic_offsets: &[wgt::DynamicOffset],
+    ) {
+        unsafe { self.set_bind_group(layout, index, group, dynamic_offsets) };
+    }
+
     unsafe fn set_push_constants(
         &mut self,
         layout: &super::PipelineLayout,
@@ -817,6 +892,17 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         let encoder = self.state.render.as_ref().unwrap();
         encoder.set_scissor_rect(scissor);
     }
+    unsafe fn set_viewport_indexed(
+        &mut self,
+        _index: u32,
+        _rect: &crate::Rect<f32>,
+        _depth_range: Range<f32>,
+    ) {
+        unreachable!("MULTI_VIEWPORT is not supported")
+    }
+    unsafe fn set_scissor_rect_indexed(&mut self, _index: u32, _rect: &crate::Rect<u32>) {
+        unreachable!("MULTI_VIEWPORT is not supported")
+    }
     unsafe fn set_stencil_reference(&mut self, value: u32) {
         let encoder = self.state.render.as_ref().unwrap();
         encoder.set_stencil_front_back_reference_value(value, value);
@@ -825,6 +911,12 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         let encoder = self.state.render.as_ref().unwrap();
         encoder.set_blend_color(color[0], color[1], color[2], color[3]);
     }
+    unsafe fn set_shading_rate(&mut self, _rate: wgt::ShadingRate) {
+        unreachable!("SHADING_RATE is not supported")
+    }
+    unsafe fn set_depth_bounds(&mut self, _bounds: Range<f32>) {
+        unreachable!("DEPTH_BOUNDS_TEST is not supported")
+    }
 
     unsafe fn draw(
         &mut self,
@@ -964,6 +1056,8 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         debug_assert!(self.state.compute.is_none());
         debug_assert!(self.state.render.is_none());
 
+        let pending_timer_queries =
+            self.take_pending_timer_queries(desc.timestamp_writes.is_some() as usize);
         let raw = self.raw_cmd_buf.as_ref().unwrap();
 
         objc::rc::autoreleasepool(|| {
@@ -990,6 +1084,16 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                 }
             }
 
+            let first_pending = desc.timestamp_writes.is_some() as usize;
+            for (i, (sample_buffer, index)) in pending_timer_queries.iter().enumerate() {
+                let sba_descriptor = descriptor
+                    .sample_buffer_attachments()
+                    .object_at((first_pending + i) as _)
+                    .unwrap();
+                sba_descriptor.set_sample_buffer(sample_buffer);
+                sba_descriptor.set_start_of_encoder_sample_index(*index as _);
+            }
+
             let encoder = raw.compute_command_encoder_with_descriptor(descriptor);
             if let Some(label) = desc.label {
                 encoder.set_label(label);
@@ -1054,6 +1158,20 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         let encoder = self.state.compute.as_ref().unwrap();
         encoder.dispatch_thread_groups_indirect(&buffer.raw, offset, self.state.raw_wg_size);
     }
+
+    unsafe fn build_acceleration_structures(
+        &mut self,
+        descriptors: &[crate::BuildAccelerationStructureDescriptor<super::Api>],
+    ) {
+        if let Some(desc) = descriptors.first() {
+            match *desc.destination_acceleration_structure {}
+        }
+    }
+    unsafe fn place_acceleration_structure_barrier(
+        &mut self,
+        _barrier: crate::AccelerationStructureBarrier,
+    ) {
+    }
 }
 
 impl Drop for super::CommandEncoder {
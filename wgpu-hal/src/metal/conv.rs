@@ -21,10 +21,14 @@ pub fn map_texture_usage(
         usage.intersects(Tu::STORAGE_READ_WRITE),
     );
     // needed for combined depth/stencil formats since we might
-    // create a stencil-only view from them
+    // create a stencil-only view from them, and for copy destinations
+    // since copies between formats differing in srgb-ness go through
+    // a view of the source format
     mtl_usage.set(
         metal::MTLTextureUsage::PixelFormatView,
-        format.is_combined_depth_stencil_format(),
+        format.is_combined_depth_stencil_format()
+            || (usage.contains(Tu::COPY_DST)
+                && format.remove_srgb_suffix() != format.add_srgb_suffix()),
     );
 
     mtl_usage
@@ -84,7 +88,7 @@ pub fn map_border_color(border_color: wgt::SamplerBorderColor) -> metal::MTLSamp
         wgt::SamplerBorderColor::TransparentBlack => TransparentBlack,
         wgt::SamplerBorderColor::OpaqueBlack => OpaqueBlack,
         wgt::SamplerBorderColor::OpaqueWhite => OpaqueWhite,
-        wgt::SamplerBorderColor::Zero => unreachable!(),
+        wgt::SamplerBorderColor::Zero | wgt::SamplerBorderColor::Custom(_) => unreachable!(),
     }
 }
 
@@ -155,10 +159,10 @@ pub fn map_blend_factor(factor: wgt::BlendFactor) -> metal::MTLBlendFactor {
         //Bf::ConstantAlpha => BlendAlpha,
         //Bf::OneMinusConstantAlpha => OneMinusBlendAlpha,
         Bf::SrcAlphaSaturated => SourceAlphaSaturated,
-        //Bf::Src1 => Source1Color,
-        //Bf::OneMinusSrc1 => OneMinusSource1Color,
-        //Bf::Src1Alpha => Source1Alpha,
-        //Bf::OneMinusSrc1Alpha => OneMinusSource1Alpha,
+        Bf::Src1 => Source1Color,
+        Bf::OneMinusSrc1 => OneMinusSource1Color,
+        Bf::Src1Alpha => Source1Alpha,
+        Bf::OneMinusSrc1Alpha => OneMinusSource1Alpha,
     }
 }
 
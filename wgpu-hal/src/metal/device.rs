@@ -1,3 +1,4 @@
+use objc::{msg_send, sel, sel_impl};
 use parking_lot::Mutex;
 use std::{
     num::NonZeroU32,
@@ -69,7 +70,8 @@ impl super::Device {
     ) -> Result<CompiledShader, crate::PipelineError> {
         let stage_bit = map_naga_stage(naga_stage);
 
-        let module = &stage.module.naga.module;
+        let module =
+            crate::auxil::apply_pipeline_constants(&stage.module.naga.module, stage.constants);
         let ep_resources = &layout.per_stage_map[naga_stage];
 
         let bounds_check_policy = if stage.module.runtime_checks {
@@ -104,7 +106,7 @@ impl super::Device {
                 // TODO: support bounds checks on binding arrays
                 binding_array: naga::proc::BoundsCheckPolicy::Unchecked,
             },
-            zero_initialize_workgroup_memory: true,
+            zero_initialize_workgroup_memory: stage.module.zero_initialize_workgroup_memory,
         };
 
         let pipeline_options = naga::back::msl::PipelineOptions {
@@ -115,7 +117,7 @@ impl super::Device {
         };
 
         let (source, info) = naga::back::msl::write_string(
-            module,
+            &module,
             &stage.module.naga.info,
             &options,
             &pipeline_options,
@@ -258,8 +260,10 @@ impl super::Device {
     }
 
     pub unsafe fn device_from_raw(raw: metal::Device, features: wgt::Features) -> super::Device {
+        let shared = Arc::new(super::AdapterShared::new(raw));
         super::Device {
-            shared: Arc::new(super::AdapterShared::new(raw)),
+            pipeline_cache: Mutex::new(super::PipelineArchive::new(&shared, None)),
+            shared,
             features,
         }
     }
@@ -271,10 +275,147 @@ impl super::Device {
     pub fn raw_device(&self) -> &Mutex<metal::Device> {
         &self.shared.device
     }
+
+    /// Creates a fence from an `MTLSharedEvent`, e.g. one created by another API.
+    ///
+    /// The fence value is the signaled value of the event.
+    ///
+    /// # Safety
+    ///
+    /// - `event` must have been created on the device of `self`.
+    pub unsafe fn fence_from_shared_event(event: metal::SharedEvent) -> super::Fence {
+        super::Fence {
+            completed_value: Arc::new(atomic::AtomicU64::new(0)),
+            pending_command_buffers: Vec::new(),
+            shared_event: Some(event),
+        }
+    }
+
+    /// Returns the contents of the pipeline cache, a serialized `MTLBinaryArchive`, to
+    /// be saved and passed to [`Self::seed_pipeline_cache`] on a later run so
+    /// pipelines don't have to be compiled from scratch again.
+    ///
+    /// Returns no data if binary archives aren't supported.
+    pub fn pipeline_cache_data(&self) -> DeviceResult<Vec<u8>> {
+        match self.pipeline_archive(None) {
+            Some(archive) => super::PipelineArchive::serialize(&archive)
+                .ok_or(crate::DeviceError::ResourceCreationFailed),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Replaces the pipeline cache with data returned by [`Self::pipeline_cache_data`].
+    ///
+    /// Returns `false`, leaving the cache untouched, if Metal can't load `data`.
+    ///
+    /// # Safety
+    ///
+    /// - Must not be called while pipelines are being created on this device.
+    pub unsafe fn seed_pipeline_cache(&self, data: &[u8]) -> DeviceResult<bool> {
+        if !self.shared.private_caps.supports_binary_archives {
+            return Ok(false);
+        }
+        let path = super::PipelineArchive::write_temp(data)?;
+        match super::PipelineArchive::new(&self.shared, Some(path)) {
+            Some(cache) => {
+                *self.pipeline_cache.lock() = Some(cache);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Returns the archive to look pipelines up in and record them into: the one of
+    /// `cache` if given, otherwise the device-wide one.
+    fn pipeline_archive(
+        &self,
+        cache: Option<&super::PipelineCache>,
+    ) -> Option<metal::BinaryArchive> {
+        match cache {
+            Some(cache) => cache.archive.as_ref().map(|a| a.archive.clone()),
+            None => self
+                .pipeline_cache
+                .lock()
+                .as_ref()
+                .map(|cache| cache.archive.clone()),
+        }
+    }
+}
+
+impl super::PipelineArchive {
+    /// Creates an archive, loading the contents of `path` if given.
+    ///
+    /// Returns `None` if binary archives aren't supported or creation failed.
+    pub(super) fn new(
+        shared: &super::AdapterShared,
+        path: Option<std::path::PathBuf>,
+    ) -> Option<Self> {
+        if !shared.private_caps.supports_binary_archives {
+            return None;
+        }
+        objc::rc::autoreleasepool(|| {
+            let descriptor = metal::BinaryArchiveDescriptor::new();
+            if let Some(ref path) = path {
+                descriptor.set_url(&Self::url(path));
+            }
+            match shared
+                .device
+                .lock()
+                .new_binary_archive_with_descriptor(&descriptor)
+            {
+                Ok(archive) => Some(Self { archive, path }),
+                Err(e) => {
+                    log::warn!("new_binary_archive_with_descriptor: {}", e);
+                    if let Some(ref path) = path {
+                        let _ = std::fs::remove_file(path);
+                    }
+                    None
+                }
+            }
+        })
+    }
+
+    /// Serializes `archive`, going through a temporary file.
+    fn serialize(archive: &metal::BinaryArchive) -> Option<Vec<u8>> {
+        let path = Self::temp_path();
+        let result = objc::rc::autoreleasepool(|| {
+            archive
+                .serialize_to_url(&Self::url(&path))
+                .map_err(|e| log::error!("serialize_to_url: {}", e))
+        })
+        .and_then(|_| std::fs::read(&path).map_err(|e| log::error!("{:?}: {}", path, e)));
+        let _ = std::fs::remove_file(&path);
+        result.ok()
+    }
+
+    /// Writes `data` to a temporary file, for [`Self::new`] to load.
+    fn write_temp(data: &[u8]) -> DeviceResult<std::path::PathBuf> {
+        let path = Self::temp_path();
+        std::fs::write(&path, data).map_err(|e| {
+            log::error!("{:?}: {}", path, e);
+            crate::DeviceError::ResourceCreationFailed
+        })?;
+        Ok(path)
+    }
+
+    fn temp_path() -> std::path::PathBuf {
+        static COUNTER: atomic::AtomicUsize = atomic::AtomicUsize::new(0);
+        let index = COUNTER.fetch_add(1, atomic::Ordering::Relaxed);
+        std::env::temp_dir().join(format!(
+            "wgpu-pipeline-cache-{}-{}.metallib",
+            std::process::id(),
+            index
+        ))
+    }
+
+    fn url(path: &std::path::Path) -> metal::URL {
+        metal::URL::new_with_string(&format!("file://{}", path.display()))
+    }
 }
 
 impl crate::Device<super::Api> for super::Device {
     unsafe fn exit(self, _queue: super::Queue) {}
+    unsafe fn destroy_queue(&self, _queue: super::Queue) {}
 
     unsafe fn create_buffer(&self, desc: &crate::BufferDescriptor) -> DeviceResult<super::Buffer> {
         let map_read = desc.usage.contains(crate::BufferUses::MAP_READ);
@@ -362,7 +503,16 @@ impl crate::Device<super::Api> for super::Device {
             descriptor.set_mipmap_level_count(desc.mip_level_count as u64);
             descriptor.set_pixel_format(mtl_format);
             descriptor.set_usage(conv::map_texture_usage(desc.format, desc.usage));
-            descriptor.set_storage_mode(metal::MTLStorageMode::Private);
+            // Transient attachments only ever live in tile memory on Apple GPUs
+            descriptor.set_storage_mode(
+                if desc.memory_flags.contains(crate::MemoryFlags::TRANSIENT)
+                    && self.shared.private_caps.supports_memoryless_storage
+                {
+                    metal::MTLStorageMode::Memoryless
+                } else {
+                    metal::MTLStorageMode::Private
+                },
+            );
 
             let raw = self.shared.device.lock().new_texture(&descriptor);
             if raw.as_ptr().is_null() {
@@ -637,6 +787,9 @@ impl crate::Device<super::Api> for super::Device {
                                 wgt::StorageTextureAccess::ReadWrite => true,
                             };
                         }
+                        wgt::BindingType::AccelerationStructure => {
+                            unreachable!("`Features::RAY_QUERY` is not supported")
+                        }
                     }
 
                     let br = naga::ResourceBinding {
@@ -768,6 +921,9 @@ impl crate::Device<super::Api> for super::Device {
                         );
                         counter.textures += size;
                     }
+                    wgt::BindingType::AccelerationStructure => {
+                        unreachable!("`Features::RAY_QUERY` is not supported")
+                    }
                 }
             }
         }
@@ -786,6 +942,7 @@ impl crate::Device<super::Api> for super::Device {
             crate::ShaderInput::Naga(naga) => Ok(super::ShaderModule {
                 naga,
                 runtime_checks: desc.runtime_checks,
+                zero_initialize_workgroup_memory: desc.zero_initialize_workgroup_memory,
             }),
             crate::ShaderInput::SpirV(_) => {
                 panic!("SPIRV_SHADER_PASSTHROUGH is not enabled for this backend")
@@ -990,6 +1147,11 @@ impl crate::Device<super::Api> for super::Device {
                 descriptor.set_label(name);
             }
 
+            let archive = self.pipeline_archive(desc.cache);
+            if let Some(ref archive) = archive {
+                descriptor.set_binary_archives(&[&**archive]);
+            }
+
             let raw = self
                 .shared
                 .device
@@ -1002,6 +1164,12 @@ impl crate::Device<super::Api> for super::Device {
                     )
                 })?;
 
+            if let Some(archive) = archive {
+                if let Err(e) = archive.add_render_pipeline_functions_with_descriptor(&descriptor) {
+                    log::warn!("add_render_pipeline_functions_with_descriptor: {}", e);
+                }
+            }
+
             Ok(super::RenderPipeline {
                 raw,
                 vs_lib,
@@ -1059,6 +1227,11 @@ impl crate::Device<super::Api> for super::Device {
                 descriptor.set_label(name);
             }
 
+            let archive = self.pipeline_archive(desc.cache);
+            if let Some(ref archive) = archive {
+                descriptor.set_binary_archives(&[&**archive]);
+            }
+
             let raw = self
                 .shared
                 .device
@@ -1071,6 +1244,13 @@ impl crate::Device<super::Api> for super::Device {
                     )
                 })?;
 
+            if let Some(archive) = archive {
+                if let Err(e) = archive.add_compute_pipeline_functions_with_descriptor(&descriptor)
+                {
+                    log::warn!("add_compute_pipeline_functions_with_descriptor: {}", e);
+                }
+            }
+
             Ok(super::ComputePipeline {
                 raw,
                 cs_info,
@@ -1082,6 +1262,30 @@ impl crate::Device<super::Api> for super::Device {
     }
     unsafe fn destroy_compute_pipeline(&self, _pipeline: super::ComputePipeline) {}
 
+    unsafe fn create_pipeline_cache(
+        &self,
+        desc: &crate::PipelineCacheDescriptor,
+    ) -> DeviceResult<super::PipelineCache> {
+        let path = match desc.data {
+            Some(data) if self.shared.private_caps.supports_binary_archives => {
+                Some(super::PipelineArchive::write_temp(data)?)
+            }
+            _ => None,
+        };
+        let loaded = path.is_some();
+        let mut archive = super::PipelineArchive::new(&self.shared, path);
+        if archive.is_none() && loaded {
+            log::info!("Ignoring pipeline cache data Metal can't load");
+            archive = super::PipelineArchive::new(&self.shared, None);
+        }
+        Ok(super::PipelineCache { archive })
+    }
+    unsafe fn destroy_pipeline_cache(&self, _cache: super::PipelineCache) {}
+    unsafe fn pipeline_cache_get_data(&self, cache: &super::PipelineCache) -> Option<Vec<u8>> {
+        let archive = cache.archive.as_ref()?;
+        super::PipelineArchive::serialize(&archive.archive)
+    }
+
     unsafe fn create_query_set(
         &self,
         desc: &wgt::QuerySetDescriptor<crate::Label>,
@@ -1150,20 +1354,20 @@ impl crate::Device<super::Api> for super::Device {
     unsafe fn destroy_query_set(&self, _set: super::QuerySet) {}
 
     unsafe fn create_fence(&self) -> DeviceResult<super::Fence> {
+        let shared_event = if self.shared.private_caps.supports_shared_event {
+            Some(self.shared.device.lock().new_shared_event())
+        } else {
+            None
+        };
         Ok(super::Fence {
             completed_value: Arc::new(atomic::AtomicU64::new(0)),
             pending_command_buffers: Vec::new(),
+            shared_event,
         })
     }
     unsafe fn destroy_fence(&self, _fence: super::Fence) {}
     unsafe fn get_fence_value(&self, fence: &super::Fence) -> DeviceResult<crate::FenceValue> {
-        let mut max_value = fence.completed_value.load(atomic::Ordering::Acquire);
-        for &(value, ref cmd_buf) in fence.pending_command_buffers.iter() {
-            if cmd_buf.status() == metal::MTLCommandBufferStatus::Completed {
-                max_value = value;
-            }
-        }
-        Ok(max_value)
+        Ok(fence.get_latest())
     }
     unsafe fn wait(
         &self,
@@ -1175,6 +1379,19 @@ impl crate::Device<super::Api> for super::Device {
             return Ok(true);
         }
 
+        if let Some(ref event) = fence.shared_event {
+            let start = time::Instant::now();
+            loop {
+                if event.signaled_value() >= wait_value {
+                    return Ok(true);
+                }
+                if start.elapsed().as_millis() >= timeout_ms as u128 {
+                    return Ok(false);
+                }
+                thread::sleep(time::Duration::from_millis(1));
+            }
+        }
+
         let cmd_buf = match fence
             .pending_command_buffers
             .iter()
@@ -1218,4 +1435,38 @@ impl crate::Device<super::Api> for super::Device {
         }
         shared_capture_manager.stop_capture();
     }
+
+    unsafe fn allocator_report(&self) -> Option<crate::AllocatorReport> {
+        let device = self.shared.device.lock();
+        let size: metal::NSUInteger = unsafe { msg_send![device.as_ref(), currentAllocatedSize] };
+        Some(crate::AllocatorReport {
+            allocation_count: None,
+            allocated_bytes: Some(size as u64),
+        })
+    }
+
+    unsafe fn create_acceleration_structure(
+        &self,
+        _desc: &crate::AccelerationStructureDescriptor,
+    ) -> Result<super::AccelerationStructure, crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn get_acceleration_structure_build_sizes(
+        &self,
+        _desc: &crate::GetAccelerationStructureBuildSizesDescriptor<super::Api>,
+    ) -> crate::AccelerationStructureBuildSizes {
+        Default::default()
+    }
+    unsafe fn get_acceleration_structure_device_address(
+        &self,
+        acceleration_structure: &super::AccelerationStructure,
+    ) -> wgt::BufferAddress {
+        match *acceleration_structure {}
+    }
+    unsafe fn destroy_acceleration_structure(
+        &self,
+        acceleration_structure: super::AccelerationStructure,
+    ) {
+        match acceleration_structure {}
+    }
 }
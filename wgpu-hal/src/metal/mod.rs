@@ -34,6 +34,7 @@ use std::{
 
 use arrayvec::ArrayVec;
 use metal::foreign_types::ForeignTypeRef as _;
+use objc::{msg_send, sel, sel_impl};
 use parking_lot::Mutex;
 
 #[derive(Clone)]
@@ -65,6 +66,9 @@ impl crate::Api for Api {
     type ShaderModule = ShaderModule;
     type RenderPipeline = RenderPipeline;
     type ComputePipeline = ComputePipeline;
+    type PipelineCache = PipelineCache;
+
+    type AccelerationStructure = AccelerationStructure;
 }
 
 pub struct Instance {
@@ -226,8 +230,11 @@ struct PrivateCapabilities {
     sample_count_mask: crate::TextureFormatCapabilities,
     supports_debug_markers: bool,
     supports_binary_archives: bool,
+    supports_shared_event: bool,
+    supports_memoryless_storage: bool,
     supports_capture_manager: bool,
     can_set_maximum_drawables_count: bool,
+    can_present_after_minimum_duration: bool,
     can_set_display_sync: bool,
     can_set_next_drawable_timeout: bool,
     supports_arrays_of_textures: bool,
@@ -300,11 +307,44 @@ impl Queue {
             timestamp_period,
         }
     }
+
+    /// Makes work submitted after this call wait on the GPU until `event` reaches
+    /// `value`, e.g. for frames produced by a video decoder.
+    pub fn wait_for_shared_event(&self, event: &metal::SharedEventRef, value: u64) {
+        objc::rc::autoreleasepool(|| {
+            let command_buffer = self.raw.lock().new_command_buffer().to_owned();
+            command_buffer.set_label("(wgpu internal) Wait");
+            command_buffer.encode_wait_for_event(event, value);
+            command_buffer.commit();
+        });
+    }
 }
 
 pub struct Device {
     shared: Arc<AdapterShared>,
     features: wgt::Features,
+    /// Archive recording every pipeline created on this device without a
+    /// [`PipelineCache`], see [`Device::pipeline_cache_data`].
+    pipeline_cache: Mutex<Option<PipelineArchive>>,
+}
+
+/// An `MTLBinaryArchive`, with the file it was loaded from.
+#[derive(Debug)]
+struct PipelineArchive {
+    archive: metal::BinaryArchive,
+    /// Temporary file holding the data the archive was loaded from.
+    path: Option<std::path::PathBuf>,
+}
+
+unsafe impl Send for PipelineArchive {}
+unsafe impl Sync for PipelineArchive {}
+
+impl Drop for PipelineArchive {
+    fn drop(&mut self) {
+        if let Some(ref path) = self.path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
 }
 
 pub struct Surface {
@@ -316,6 +356,15 @@ pub struct Surface {
     // Useful for UI-intensive applications that are sensitive to
     // window resizing.
     pub present_with_transaction: bool,
+    /// Minimum time each frame stays on screen, to present at a steady cadence on
+    /// variable refresh rate displays, e.g. 1/60 s for 60 fps on ProMotion.
+    ///
+    /// Ignored before macOS 11 and iOS 10.3.
+    pub minimum_present_duration: Option<std::time::Duration>,
+    /// Number of drawables of the layer, 2 or 3, instead of the swapchain size of
+    /// the configuration. Applied on the next configuration.
+    pub maximum_drawable_count: Option<u32>,
+    can_present_after_minimum_duration: bool,
 }
 
 unsafe impl Send for Surface {}
@@ -326,6 +375,7 @@ pub struct SurfaceTexture {
     texture: Texture,
     drawable: metal::MetalDrawable,
     present_with_transaction: bool,
+    minimum_present_duration: Option<std::time::Duration>,
 }
 
 impl std::borrow::Borrow<Texture> for SurfaceTexture {
@@ -342,8 +392,20 @@ impl crate::Queue<Api> for Queue {
         &mut self,
         command_buffers: &[&CommandBuffer],
         signal_fence: Option<(&mut Fence, crate::FenceValue)>,
+        label: crate::Label,
     ) -> Result<(), crate::DeviceError> {
         objc::rc::autoreleasepool(|| {
+            // Prefix the labels of the command buffers, which is what Instruments shows.
+            if let Some(label) = label {
+                for cmd_buffer in command_buffers {
+                    let cmd_label = match cmd_buffer.label.as_str() {
+                        "" => label.to_string(),
+                        existing => format!("{label}: {existing}"),
+                    };
+                    cmd_buffer.raw.set_label(&cmd_label);
+                }
+            }
+
             let extra_command_buffer = match signal_fence {
                 Some((fence, value)) => {
                     let completed_value = Arc::clone(&fence.completed_value);
@@ -356,13 +418,17 @@ impl crate::Queue<Api> for Queue {
                         Some(&cmd_buf) => cmd_buf.raw.to_owned(),
                         None => {
                             let queue = self.raw.lock();
-                            queue
+                            let raw = queue
                                 .new_command_buffer_with_unretained_references()
-                                .to_owned()
+                                .to_owned();
+                            raw.set_label("(wgpu internal) Signal");
+                            raw
                         }
                     };
-                    raw.set_label("(wgpu internal) Signal");
                     raw.add_completed_handler(&block);
+                    if let Some(ref event) = fence.shared_event {
+                        raw.encode_signal_event(event, value);
+                    }
 
                     fence.maintain();
                     fence.pending_command_buffers.push((value, raw.to_owned()));
@@ -397,7 +463,20 @@ impl crate::Queue<Api> for Queue {
 
             // https://developer.apple.com/documentation/quartzcore/cametallayer/1478157-presentswithtransaction?language=objc
             if !texture.present_with_transaction {
-                command_buffer.present_drawable(&texture.drawable);
+                match texture.minimum_present_duration {
+                    Some(duration) => {
+                        let raw_command_buffer =
+                            command_buffer.as_ptr() as *mut objc::runtime::Object;
+                        let drawable: &metal::MetalDrawableRef = &texture.drawable;
+                        let raw_drawable = drawable.as_ptr() as *mut objc::runtime::Object;
+                        let () = msg_send![
+                            raw_command_buffer,
+                            presentDrawable: raw_drawable
+                            afterMinimumDuration: duration.as_secs_f64()
+                        ];
+                    }
+                    None => command_buffer.present_drawable(&texture.drawable),
+                }
             }
 
             command_buffer.commit();
@@ -410,6 +489,15 @@ impl crate::Queue<Api> for Queue {
         Ok(())
     }
 
+    unsafe fn wait_for_fence(
+        &mut self,
+        _fence: &Fence,
+        _value: crate::FenceValue,
+    ) -> Result<(), crate::DeviceError> {
+        // There is a single queue, which executes the submissions in order.
+        Ok(())
+    }
+
     unsafe fn get_timestamp_period(&self) -> f32 {
         self.timestamp_period
     }
@@ -639,6 +727,7 @@ unsafe impl Sync for BindGroup {}
 pub struct ShaderModule {
     naga: crate::NagaShader,
     runtime_checks: bool,
+    zero_initialize_workgroup_memory: bool,
 }
 
 #[derive(Debug, Default)]
@@ -702,6 +791,16 @@ pub struct ComputePipeline {
 unsafe impl Send for ComputePipeline {}
 unsafe impl Sync for ComputePipeline {}
 
+/// Pipeline cache backed by an `MTLBinaryArchive`, if binary archives are supported.
+#[derive(Debug)]
+pub struct PipelineCache {
+    archive: Option<PipelineArchive>,
+}
+
+/// Acceleration structures are not supported, so none can be created.
+#[derive(Debug)]
+pub enum AccelerationStructure {}
+
 #[derive(Debug)]
 pub struct QuerySet {
     raw_buffer: metal::Buffer,
@@ -718,13 +817,27 @@ pub struct Fence {
     completed_value: Arc<atomic::AtomicU64>,
     /// The pending fence values have to be ascending.
     pending_command_buffers: Vec<(crate::FenceValue, metal::CommandBuffer)>,
+    /// Event signaled by the GPU with the fence values, when supported.
+    ///
+    /// It takes precedence over the command buffer tracking above, and can be
+    /// shared with other APIs, see [`Fence::raw_shared_event`].
+    shared_event: Option<metal::SharedEvent>,
 }
 
 unsafe impl Send for Fence {}
 unsafe impl Sync for Fence {}
 
 impl Fence {
+    /// Returns the `MTLSharedEvent` signaled with the fence values, e.g. to have a
+    /// video encoder wait for frames without stalling the CPU.
+    pub fn raw_shared_event(&self) -> Option<&metal::SharedEventRef> {
+        self.shared_event.as_deref()
+    }
+
     fn get_latest(&self) -> crate::FenceValue {
+        if let Some(ref event) = self.shared_event {
+            return event.signaled_value();
+        }
         let mut max_value = self.completed_value.load(atomic::Ordering::Acquire);
         for &(value, ref cmd_buf) in self.pending_command_buffers.iter() {
             if cmd_buf.status() == metal::MTLCommandBufferStatus::Completed {
@@ -785,6 +898,10 @@ struct CommandState {
 
     work_group_memory_sizes: Vec<u32>,
     push_constants: Vec<u32>,
+
+    /// Timestamps written outside of any pass, which are sampled at the start
+    /// of the next encoder created on the command buffer.
+    pending_timer_queries: Vec<(metal::CounterSampleBuffer, u32)>,
 }
 
 pub struct CommandEncoder {
@@ -810,6 +927,9 @@ unsafe impl Sync for CommandEncoder {}
 #[derive(Debug)]
 pub struct CommandBuffer {
     raw: metal::CommandBuffer,
+    /// The label the command buffer was encoded with, which submission labels
+    /// are prefixed to.
+    label: String,
 }
 
 unsafe impl Send for CommandBuffer {}
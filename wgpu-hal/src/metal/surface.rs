@@ -23,6 +23,17 @@ extern "C" {
     static kCAGravityTopLeft: *mut Object;
 }
 
+#[cfg_attr(feature = "link", link(name = "CoreGraphics", kind = "framework"))]
+#[allow(non_upper_case_globals)]
+extern "C" {
+    static kCGColorSpaceDisplayP3: *const c_void;
+    static kCGColorSpaceExtendedLinearSRGB: *const c_void;
+    static kCGColorSpaceITUR_2100_PQ: *const c_void;
+
+    fn CGColorSpaceCreateWithName(name: *const c_void) -> *mut c_void;
+    fn CGColorSpaceRelease(space: *mut c_void);
+}
+
 extern "C" fn layer_should_inherit_contents_scale_from_window(
     _: &Class,
     _: Sel,
@@ -67,6 +78,9 @@ impl super::Surface {
             extent: wgt::Extent3d::default(),
             main_thread_id: thread::current().id(),
             present_with_transaction: false,
+            minimum_present_duration: None,
+            maximum_drawable_count: None,
+            can_present_after_minimum_duration: false,
         }
     }
 
@@ -180,6 +194,7 @@ impl crate::Surface<super::Api> for super::Surface {
         let caps = &device.shared.private_caps;
         self.swapchain_format = Some(config.format);
         self.extent = config.extent;
+        self.can_present_after_minimum_duration = caps.can_present_after_minimum_duration;
 
         let render_layer = self.render_layer.lock();
         let framebuffer_only = config.usage == crate::TextureUses::COLOR_TARGET;
@@ -215,13 +230,43 @@ impl crate::Surface<super::Api> for super::Surface {
         render_layer.set_presents_with_transaction(self.present_with_transaction);
         // opt-in to Metal EDR
         // EDR potentially more power used in display and more bandwidth, memory footprint.
-        let wants_edr = config.format == wgt::TextureFormat::Rgba16Float;
+        let wants_edr = config.format == wgt::TextureFormat::Rgba16Float
+            || matches!(
+                config.color_space,
+                wgt::SurfaceColorSpace::ExtendedSrgbLinear | wgt::SurfaceColorSpace::Hdr10
+            );
         if wants_edr != render_layer.wants_extended_dynamic_range_content() {
             render_layer.set_wants_extended_dynamic_range_content(wants_edr);
         }
 
+        let responds: BOOL = msg_send![*render_layer, respondsToSelector: sel!(setColorspace:)];
+        if responds == YES {
+            // A null color space leaves the drawable contents unmatched, as sRGB.
+            let name = match config.color_space {
+                wgt::SurfaceColorSpace::Srgb => None,
+                wgt::SurfaceColorSpace::DisplayP3 => Some(unsafe { kCGColorSpaceDisplayP3 }),
+                wgt::SurfaceColorSpace::ExtendedSrgbLinear => {
+                    Some(unsafe { kCGColorSpaceExtendedLinearSRGB })
+                }
+                wgt::SurfaceColorSpace::Hdr10 => Some(unsafe { kCGColorSpaceITUR_2100_PQ }),
+            };
+            let space = match name {
+                Some(name) => unsafe { CGColorSpaceCreateWithName(name) },
+                None => std::ptr::null_mut(),
+            };
+            let () = msg_send![*render_layer, setColorspace: space];
+            if !space.is_null() {
+                unsafe { CGColorSpaceRelease(space) };
+            }
+        } else if config.color_space != wgt::SurfaceColorSpace::Srgb {
+            log::warn!("Unable to set the color space of the layer");
+        }
+
         // this gets ignored on iOS for certain OS/device combinations (iphone5s iOS 10.3)
-        render_layer.set_maximum_drawable_count(config.swap_chain_size as _);
+        let drawable_count = self
+            .maximum_drawable_count
+            .unwrap_or(config.swap_chain_size);
+        render_layer.set_maximum_drawable_count(drawable_count as _);
         render_layer.set_drawable_size(drawable_size);
         if caps.can_set_next_drawable_timeout {
             let () = msg_send![*render_layer, setAllowsNextDrawableTimeout:false];
@@ -266,6 +311,9 @@ impl crate::Surface<super::Api> for super::Surface {
             },
             drawable,
             present_with_transaction: self.present_with_transaction,
+            minimum_present_duration: self
+                .minimum_present_duration
+                .filter(|_| self.can_present_after_minimum_duration),
         };
 
         Ok(Some(crate::AcquiredSurfaceTexture {
@@ -1,6 +1,9 @@
 use super::conv;
 
-use ash::{extensions::khr, vk};
+use ash::{
+    extensions::{khr, nv},
+    vk,
+};
 use parking_lot::Mutex;
 
 use std::{collections::BTreeMap, ffi::CStr, sync::Arc};
@@ -25,14 +28,22 @@ pub struct PhysicalDeviceFeatures {
     timeline_semaphore: Option<vk::PhysicalDeviceTimelineSemaphoreFeaturesKHR>,
     image_robustness: Option<vk::PhysicalDeviceImageRobustnessFeaturesEXT>,
     robustness2: Option<vk::PhysicalDeviceRobustness2FeaturesEXT>,
+    custom_border_color: Option<vk::PhysicalDeviceCustomBorderColorFeaturesEXT>,
     multiview: Option<vk::PhysicalDeviceMultiviewFeaturesKHR>,
     astc_hdr: Option<vk::PhysicalDeviceTextureCompressionASTCHDRFeaturesEXT>,
     shader_float16: Option<(
         vk::PhysicalDeviceShaderFloat16Int8Features,
         vk::PhysicalDevice16BitStorageFeatures,
     )>,
+    shader_atomic_int64: Option<vk::PhysicalDeviceShaderAtomicInt64Features>,
     zero_initialize_workgroup_memory:
         Option<vk::PhysicalDeviceZeroInitializeWorkgroupMemoryFeatures>,
+    fragment_shading_rate: Option<vk::PhysicalDeviceFragmentShadingRateFeaturesKHR>,
+    buffer_device_address: Option<vk::PhysicalDeviceBufferDeviceAddressFeaturesKHR>,
+    acceleration_structure: Option<vk::PhysicalDeviceAccelerationStructureFeaturesKHR>,
+    ray_query: Option<vk::PhysicalDeviceRayQueryFeaturesKHR>,
+    protected_memory: Option<vk::PhysicalDeviceProtectedMemoryFeatures>,
+    pipeline_executable_info: Option<vk::PhysicalDevicePipelineExecutablePropertiesFeaturesKHR>,
 }
 
 // This is safe because the structs have `p_next: *mut c_void`, which we null out/never read.
@@ -61,6 +72,9 @@ impl PhysicalDeviceFeatures {
         if let Some(ref mut feature) = self.robustness2 {
             info = info.push_next(feature);
         }
+        if let Some(ref mut feature) = self.custom_border_color {
+            info = info.push_next(feature);
+        }
         if let Some(ref mut feature) = self.astc_hdr {
             info = info.push_next(feature);
         }
@@ -68,9 +82,30 @@ impl PhysicalDeviceFeatures {
             info = info.push_next(f16_i8_feature);
             info = info.push_next(_16bit_feature);
         }
+        if let Some(ref mut feature) = self.shader_atomic_int64 {
+            info = info.push_next(feature);
+        }
         if let Some(ref mut feature) = self.zero_initialize_workgroup_memory {
             info = info.push_next(feature);
         }
+        if let Some(ref mut feature) = self.fragment_shading_rate {
+            info = info.push_next(feature);
+        }
+        if let Some(ref mut feature) = self.buffer_device_address {
+            info = info.push_next(feature);
+        }
+        if let Some(ref mut feature) = self.acceleration_structure {
+            info = info.push_next(feature);
+        }
+        if let Some(ref mut feature) = self.ray_query {
+            info = info.push_next(feature);
+        }
+        if let Some(ref mut feature) = self.protected_memory {
+            info = info.push_next(feature);
+        }
+        if let Some(ref mut feature) = self.pipeline_executable_info {
+            info = info.push_next(feature);
+        }
         info
     }
 
@@ -104,7 +139,10 @@ impl PhysicalDeviceFeatures {
         );
         let needs_partially_bound =
             requested_features.intersects(wgt::Features::PARTIALLY_BOUND_BINDING_ARRAY);
-
+        // Partially bound binding arrays are allocated from update-after-bind pools when
+        // possible, see `PrivateCapabilities::update_after_bind_descriptors`.
+        let needs_update_after_bind =
+            needs_partially_bound && private_caps.update_after_bind_descriptors;
         Self {
             // vk::PhysicalDeviceFeatures is a struct composed of Bool32's while
             // Features is a bitfield so we need to map everything manually
@@ -120,16 +158,16 @@ impl PhysicalDeviceFeatures {
                 .draw_indirect_first_instance(
                     requested_features.contains(wgt::Features::INDIRECT_FIRST_INSTANCE),
                 )
-                //.dual_src_blend(requested_features.contains(wgt::Features::DUAL_SRC_BLENDING))
+                .dual_src_blend(requested_features.contains(wgt::Features::DUAL_SOURCE_BLENDING))
                 .multi_draw_indirect(
                     requested_features.contains(wgt::Features::MULTI_DRAW_INDIRECT),
                 )
                 .fill_mode_non_solid(requested_features.intersects(
                     wgt::Features::POLYGON_MODE_LINE | wgt::Features::POLYGON_MODE_POINT,
                 ))
-                //.depth_bounds(requested_features.contains(wgt::Features::DEPTH_BOUNDS))
+                .depth_bounds(requested_features.contains(wgt::Features::DEPTH_BOUNDS_TEST))
                 //.alpha_to_one(requested_features.contains(wgt::Features::ALPHA_TO_ONE))
-                //.multi_viewport(requested_features.contains(wgt::Features::MULTI_VIEWPORTS))
+                .multi_viewport(requested_features.contains(wgt::Features::MULTI_VIEWPORT))
                 .sampler_anisotropy(
                     downlevel_flags.contains(wgt::DownlevelFlags::ANISOTROPIC_FILTERING),
                 )
@@ -153,7 +191,9 @@ impl PhysicalDeviceFeatures {
                     downlevel_flags.contains(wgt::DownlevelFlags::FRAGMENT_WRITABLE_STORAGE),
                 )
                 //.shader_image_gather_extended(
-                //.shader_storage_image_extended_formats(
+                .shader_storage_image_extended_formats(
+                    requested_features.contains(wgt::Features::RG11B10UFLOAT_STORAGE),
+                )
                 .shader_uniform_buffer_array_dynamic_indexing(
                     requested_features.contains(wgt::Features::BUFFER_BINDING_ARRAY),
                 )
@@ -172,7 +212,11 @@ impl PhysicalDeviceFeatures {
                 //.shader_clip_distance(requested_features.contains(wgt::Features::SHADER_CLIP_DISTANCE))
                 //.shader_cull_distance(requested_features.contains(wgt::Features::SHADER_CULL_DISTANCE))
                 .shader_float64(requested_features.contains(wgt::Features::SHADER_F64))
-                //.shader_int64(requested_features.contains(wgt::Features::SHADER_INT64))
+                .shader_int64(
+                    requested_features.intersects(
+                        wgt::Features::SHADER_INT64 | wgt::Features::SHADER_INT64_ATOMIC,
+                    ),
+                )
                 .shader_int16(requested_features.contains(wgt::Features::SHADER_I16))
                 //.shader_resource_residency(requested_features.contains(wgt::Features::SHADER_RESOURCE_RESIDENCY))
                 .geometry_shader(requested_features.contains(wgt::Features::SHADER_PRIMITIVE_INDEX))
@@ -194,6 +238,11 @@ impl PhysicalDeviceFeatures {
                             needs_storage_buffer_non_uniform,
                         )
                         .descriptor_binding_partially_bound(needs_partially_bound)
+                        .descriptor_binding_sampled_image_update_after_bind(needs_update_after_bind)
+                        .descriptor_binding_storage_image_update_after_bind(needs_update_after_bind)
+                        .descriptor_binding_storage_buffer_update_after_bind(
+                            needs_update_after_bind,
+                        )
                         .build(),
                 )
             } else {
@@ -245,6 +294,18 @@ impl PhysicalDeviceFeatures {
             } else {
                 None
             },
+            custom_border_color: if requested_features
+                .contains(wgt::Features::ADDRESS_MODE_CLAMP_TO_BORDER_CUSTOM)
+            {
+                Some(
+                    vk::PhysicalDeviceCustomBorderColorFeaturesEXT::builder()
+                        .custom_border_colors(true)
+                        .custom_border_color_without_format(true)
+                        .build(),
+                )
+            } else {
+                None
+            },
             multiview: if effective_api_version >= vk::API_VERSION_1_1
                 || enabled_extensions.contains(&vk::KhrMultiviewFn::name())
             {
@@ -278,6 +339,16 @@ impl PhysicalDeviceFeatures {
             } else {
                 None
             },
+            shader_atomic_int64: if requested_features.contains(wgt::Features::SHADER_INT64_ATOMIC)
+            {
+                Some(
+                    vk::PhysicalDeviceShaderAtomicInt64Features::builder()
+                        .shader_buffer_int64_atomics(true)
+                        .build(),
+                )
+            } else {
+                None
+            },
             zero_initialize_workgroup_memory: if effective_api_version >= vk::API_VERSION_1_3
                 || enabled_extensions.contains(&vk::KhrZeroInitializeWorkgroupMemoryFn::name())
             {
@@ -291,6 +362,68 @@ impl PhysicalDeviceFeatures {
             } else {
                 None
             },
+            fragment_shading_rate: if enabled_extensions
+                .contains(&vk::KhrFragmentShadingRateFn::name())
+            {
+                Some(
+                    vk::PhysicalDeviceFragmentShadingRateFeaturesKHR::builder()
+                        .pipeline_fragment_shading_rate(true)
+                        .build(),
+                )
+            } else {
+                None
+            },
+            buffer_device_address: if requested_features
+                .contains(wgt::Features::RAY_TRACING_ACCELERATION_STRUCTURE)
+            {
+                Some(
+                    vk::PhysicalDeviceBufferDeviceAddressFeaturesKHR::builder()
+                        .buffer_device_address(true)
+                        .build(),
+                )
+            } else {
+                None
+            },
+            acceleration_structure: if enabled_extensions
+                .contains(&vk::KhrAccelerationStructureFn::name())
+            {
+                Some(
+                    vk::PhysicalDeviceAccelerationStructureFeaturesKHR::builder()
+                        .acceleration_structure(true)
+                        .build(),
+                )
+            } else {
+                None
+            },
+            ray_query: if enabled_extensions.contains(&vk::KhrRayQueryFn::name()) {
+                Some(
+                    vk::PhysicalDeviceRayQueryFeaturesKHR::builder()
+                        .ray_query(true)
+                        .build(),
+                )
+            } else {
+                None
+            },
+            protected_memory: if requested_features.contains(wgt::Features::PROTECTED_CONTENT) {
+                Some(
+                    vk::PhysicalDeviceProtectedMemoryFeatures::builder()
+                        .protected_memory(true)
+                        .build(),
+                )
+            } else {
+                None
+            },
+            pipeline_executable_info: if enabled_extensions
+                .contains(&vk::KhrPipelineExecutablePropertiesFn::name())
+            {
+                Some(
+                    vk::PhysicalDevicePipelineExecutablePropertiesFeaturesKHR::builder()
+                        .pipeline_executable_info(true)
+                        .build(),
+                )
+            } else {
+                None
+            },
         }
     }
 
@@ -311,6 +444,10 @@ impl PhysicalDeviceFeatures {
             | F::TIMESTAMP_QUERY
             | F::TIMESTAMP_QUERY_INSIDE_PASSES
             | F::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
+            | F::REUSABLE_COMMAND_BUFFERS
+            | F::UNINITIALIZED_WORKGROUP_MEMORY
+            | F::PIPELINE_CACHE
+            | F::TEXTURE_COMPONENT_SWIZZLE
             | F::CLEAR_TEXTURE;
 
         let mut dl_flags = Df::COMPUTE_SHADERS
@@ -333,6 +470,13 @@ impl PhysicalDeviceFeatures {
         );
         dl_flags.set(Df::CUBE_ARRAY_TEXTURES, self.core.image_cube_array != 0);
         dl_flags.set(Df::ANISOTROPIC_FILTERING, self.core.sampler_anisotropy != 0);
+        // `robustBufferAccess` is enabled on every device that supports it, regardless of the
+        // requested features (see `PrivateCapabilities::robust_buffer_access`), and it covers
+        // vertex input.
+        dl_flags.set(
+            Df::ROBUST_INDIRECT_EXECUTION,
+            self.core.robust_buffer_access != 0,
+        );
         dl_flags.set(
             Df::FRAGMENT_WRITABLE_STORAGE,
             self.core.fragment_stores_and_atomics != 0,
@@ -349,13 +493,17 @@ impl PhysicalDeviceFeatures {
             F::INDIRECT_FIRST_INSTANCE,
             self.core.draw_indirect_first_instance != 0,
         );
-        //if self.core.dual_src_blend != 0
+        features.set(F::DUAL_SOURCE_BLENDING, self.core.dual_src_blend != 0);
         features.set(F::MULTI_DRAW_INDIRECT, self.core.multi_draw_indirect != 0);
         features.set(F::POLYGON_MODE_LINE, self.core.fill_mode_non_solid != 0);
         features.set(F::POLYGON_MODE_POINT, self.core.fill_mode_non_solid != 0);
-        //if self.core.depth_bounds != 0 {
+        features.set(F::DEPTH_BOUNDS_TEST, self.core.depth_bounds != 0);
         //if self.core.alpha_to_one != 0 {
-        //if self.core.multi_viewport != 0 {
+        features.set(
+            F::MULTI_VIEWPORT,
+            self.core.multi_viewport != 0
+                && caps.properties.limits.max_viewports >= wgt::MAX_VIEWPORTS,
+        );
         features.set(
             F::TEXTURE_COMPRESSION_ETC2,
             self.core.texture_compression_etc2 != 0,
@@ -368,6 +516,7 @@ impl PhysicalDeviceFeatures {
             F::TEXTURE_COMPRESSION_BC,
             self.core.texture_compression_bc != 0,
         );
+        features.set(F::ROBUST_BUFFER_ACCESS, self.core.robust_buffer_access != 0);
         features.set(
             F::PIPELINE_STATISTICS_QUERY,
             self.core.pipeline_statistics_query != 0,
@@ -477,6 +626,35 @@ impl PhysicalDeviceFeatures {
             );
         }
 
+        features.set(
+            F::TEXTURE_COMPRESSION_BC_SLICED_3D,
+            self.core.texture_compression_bc != 0
+                && supports_3d_formats(
+                    instance,
+                    phd,
+                    vk::Format::BC1_RGB_UNORM_BLOCK.as_raw()..=vk::Format::BC7_SRGB_BLOCK.as_raw(),
+                ),
+        );
+        // The HDR formats need to work in 3D as well if the adapter supports them.
+        let astc_hdr_3d = !features.contains(F::TEXTURE_COMPRESSION_ASTC_HDR)
+            || supports_3d_formats(
+                instance,
+                phd,
+                vk::Format::ASTC_4X4_SFLOAT_BLOCK.as_raw()
+                    ..=vk::Format::ASTC_12X12_SFLOAT_BLOCK.as_raw(),
+            );
+        features.set(
+            F::TEXTURE_COMPRESSION_ASTC_SLICED_3D,
+            self.core.texture_compression_astc_ldr != 0
+                && astc_hdr_3d
+                && supports_3d_formats(
+                    instance,
+                    phd,
+                    vk::Format::ASTC_4X4_UNORM_BLOCK.as_raw()
+                        ..=vk::Format::ASTC_12X12_SRGB_BLOCK.as_raw(),
+                ),
+        );
+
         if let Some((ref f16_i8, ref bit16)) = self.shader_float16 {
             features.set(
                 F::SHADER_F16,
@@ -486,6 +664,14 @@ impl PhysicalDeviceFeatures {
             );
         }
 
+        features.set(F::SHADER_INT64, self.core.shader_int64 != 0);
+        if let Some(ref atomic_int64) = self.shader_atomic_int64 {
+            features.set(
+                F::SHADER_INT64_ATOMIC,
+                self.core.shader_int64 != 0 && atomic_int64.shader_buffer_int64_atomics != 0,
+            );
+        }
+
         let supports_depth_format = |format| {
             supports_format(
                 instance,
@@ -521,6 +707,76 @@ impl PhysicalDeviceFeatures {
         );
         features.set(F::RG11B10UFLOAT_RENDERABLE, rg11b10ufloat_renderable);
 
+        let rg11b10ufloat_storage = self.core.shader_storage_image_extended_formats != 0
+            && supports_format(
+                instance,
+                phd,
+                vk::Format::B10G11R11_UFLOAT_PACK32,
+                vk::ImageTiling::OPTIMAL,
+                vk::FormatFeatureFlags::STORAGE_IMAGE,
+            );
+        features.set(F::RG11B10UFLOAT_STORAGE, rg11b10ufloat_storage);
+
+        // Custom border colors are passed without a format, so both bits are required.
+        features.set(
+            F::ADDRESS_MODE_CLAMP_TO_BORDER_CUSTOM,
+            self.custom_border_color.map_or(false, |f| {
+                f.custom_border_colors != 0 && f.custom_border_color_without_format != 0
+            }),
+        );
+
+        let float32_filterable = [
+            vk::Format::R32_SFLOAT,
+            vk::Format::R32G32_SFLOAT,
+            vk::Format::R32G32B32A32_SFLOAT,
+        ]
+        .into_iter()
+        .all(|format| {
+            supports_format(
+                instance,
+                phd,
+                format,
+                vk::ImageTiling::OPTIMAL,
+                vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR,
+            )
+        });
+        features.set(F::FLOAT32_FILTERABLE, float32_filterable);
+
+        features.set(
+            F::SHADING_RATE,
+            self.fragment_shading_rate
+                .map_or(false, |f| f.pipeline_fragment_shading_rate != 0),
+        );
+
+        let acceleration_structure = caps
+            .supports_extension(vk::KhrDeferredHostOperationsFn::name())
+            && self
+                .buffer_device_address
+                .map_or(false, |f| f.buffer_device_address != 0)
+            && self
+                .acceleration_structure
+                .map_or(false, |f| f.acceleration_structure != 0);
+        features.set(
+            F::RAY_TRACING_ACCELERATION_STRUCTURE,
+            acceleration_structure,
+        );
+        features.set(
+            F::RAY_QUERY,
+            acceleration_structure && self.ray_query.map_or(false, |f| f.ray_query != 0),
+        );
+        features.set(
+            F::PROTECTED_CONTENT,
+            self.protected_memory
+                .map_or(false, |f| f.protected_memory != 0),
+        );
+
+        // `textureCompressionBC` is all-or-nothing, but some drivers (mostly Android ones)
+        // expose the BC1-3 formats individually without it.
+        features.set(
+            F::TEXTURE_COMPRESSION_S3TC,
+            self.core.texture_compression_bc != 0 || is_format_s3tc_supported(instance, phd),
+        );
+
         (features, dl_flags)
     }
 
@@ -651,6 +907,11 @@ impl PhysicalDeviceCapabilities {
                 }
             }
 
+            // Require `VK_KHR_shader_atomic_int64` if the associated feature was requested
+            if requested_features.contains(wgt::Features::SHADER_INT64_ATOMIC) {
+                extensions.push(vk::KhrShaderAtomicInt64Fn::name());
+            }
+
             //extensions.push(vk::KhrSamplerMirrorClampToEdgeFn::name());
             //extensions.push(vk::ExtSamplerFilterMinmaxFn::name());
         }
@@ -672,6 +933,37 @@ impl PhysicalDeviceCapabilities {
             extensions.push(vk::ExtRobustness2Fn::name());
         }
 
+        // Require `VK_EXT_custom_border_color` if the associated feature was requested
+        if requested_features.contains(wgt::Features::ADDRESS_MODE_CLAMP_TO_BORDER_CUSTOM) {
+            extensions.push(vk::ExtCustomBorderColorFn::name());
+        }
+
+        // Optional `VK_KHR_external_memory_fd`, `VK_EXT_external_memory_dma_buf` and
+        // `VK_EXT_image_drm_format_modifier`, used to share textures with other APIs.
+        // Their dependencies are only guaranteed to be present in Vulkan 1.1 and 1.2.
+        if cfg!(unix)
+            && self.effective_api_version >= vk::API_VERSION_1_1
+            && self.supports_extension(vk::KhrExternalMemoryFdFn::name())
+        {
+            extensions.push(vk::KhrExternalMemoryFdFn::name());
+            if self.supports_extension(vk::ExtExternalMemoryDmaBufFn::name()) {
+                extensions.push(vk::ExtExternalMemoryDmaBufFn::name());
+                if self.effective_api_version >= vk::API_VERSION_1_2
+                    && self.supports_extension(vk::ExtImageDrmFormatModifierFn::name())
+                {
+                    extensions.push(vk::ExtImageDrmFormatModifierFn::name());
+                }
+            }
+        }
+
+        // Optional `VK_KHR_external_semaphore_fd`, used to share fences with other APIs.
+        if cfg!(unix)
+            && self.effective_api_version >= vk::API_VERSION_1_1
+            && self.supports_extension(vk::KhrExternalSemaphoreFdFn::name())
+        {
+            extensions.push(vk::KhrExternalSemaphoreFdFn::name());
+        }
+
         // Require `VK_KHR_draw_indirect_count` if the associated feature was requested
         // Even though Vulkan 1.2 has promoted the extension to core, we must require the extension to avoid
         // large amounts of spaghetti involved with using PhysicalDeviceVulkan12Features.
@@ -688,6 +980,23 @@ impl PhysicalDeviceCapabilities {
         #[cfg(any(target_os = "macos", target_os = "ios"))]
         extensions.push(vk::KhrPortabilitySubsetFn::name());
 
+        // Require `VK_KHR_fragment_shading_rate` if the associated feature was requested
+        if requested_features.contains(wgt::Features::SHADING_RATE) {
+            extensions.push(vk::KhrFragmentShadingRateFn::name());
+        }
+
+        // Require `VK_KHR_acceleration_structure` and its dependency `VK_KHR_deferred_host_operations`
+        // if the associated feature was requested
+        if requested_features.contains(wgt::Features::RAY_TRACING_ACCELERATION_STRUCTURE) {
+            extensions.push(vk::KhrDeferredHostOperationsFn::name());
+            extensions.push(vk::KhrAccelerationStructureFn::name());
+        }
+
+        // Require `VK_KHR_ray_query` if the associated feature was requested
+        if requested_features.contains(wgt::Features::RAY_QUERY) {
+            extensions.push(vk::KhrRayQueryFn::name());
+        }
+
         // Require `VK_EXT_texture_compression_astc_hdr` if the associated feature was requested
         if requested_features.contains(wgt::Features::TEXTURE_COMPRESSION_ASTC_HDR) {
             extensions.push(vk::ExtTextureCompressionAstcHdrFn::name());
@@ -879,6 +1188,12 @@ impl super::InstanceShared {
                     .insert(vk::PhysicalDeviceRobustness2FeaturesEXT::default());
                 builder = builder.push_next(next);
             }
+            if capabilities.supports_extension(vk::ExtCustomBorderColorFn::name()) {
+                let next = features
+                    .custom_border_color
+                    .insert(vk::PhysicalDeviceCustomBorderColorFeaturesEXT::default());
+                builder = builder.push_next(next);
+            }
             if capabilities.supports_extension(vk::ExtTextureCompressionAstcHdrFn::name()) {
                 let next = features
                     .astc_hdr
@@ -896,6 +1211,16 @@ impl super::InstanceShared {
                 builder = builder.push_next(&mut next.1);
             }
 
+            // `VK_KHR_shader_atomic_int64` is promoted to 1.2
+            if capabilities.effective_api_version >= vk::API_VERSION_1_2
+                || capabilities.supports_extension(vk::KhrShaderAtomicInt64Fn::name())
+            {
+                let next = features
+                    .shader_atomic_int64
+                    .insert(vk::PhysicalDeviceShaderAtomicInt64Features::default());
+                builder = builder.push_next(next);
+            }
+
             // `VK_KHR_zero_initialize_workgroup_memory` is promoted to 1.3
             if capabilities.effective_api_version >= vk::API_VERSION_1_3
                 || capabilities.supports_extension(vk::KhrZeroInitializeWorkgroupMemoryFn::name())
@@ -906,6 +1231,47 @@ impl super::InstanceShared {
                 builder = builder.push_next(next);
             }
 
+            // `VK_KHR_fragment_shading_rate` depends on `VK_KHR_create_renderpass2`, promoted to 1.2
+            if capabilities.effective_api_version >= vk::API_VERSION_1_2
+                && capabilities.supports_extension(vk::KhrFragmentShadingRateFn::name())
+            {
+                let next = features
+                    .fragment_shading_rate
+                    .insert(vk::PhysicalDeviceFragmentShadingRateFeaturesKHR::default());
+                builder = builder.push_next(next);
+            }
+
+            // `VK_KHR_acceleration_structure` depends on `VK_KHR_buffer_device_address` and
+            // `VK_EXT_descriptor_indexing`, both promoted to 1.2
+            if capabilities.effective_api_version >= vk::API_VERSION_1_2
+                && capabilities.supports_extension(vk::KhrAccelerationStructureFn::name())
+            {
+                let next = features
+                    .buffer_device_address
+                    .insert(vk::PhysicalDeviceBufferDeviceAddressFeaturesKHR::default());
+                builder = builder.push_next(next);
+                let next = features
+                    .acceleration_structure
+                    .insert(vk::PhysicalDeviceAccelerationStructureFeaturesKHR::default());
+                builder = builder.push_next(next);
+
+                // `VK_KHR_ray_query` also depends on `VK_KHR_spirv_1_4`, promoted to 1.2
+                if capabilities.supports_extension(vk::KhrRayQueryFn::name()) {
+                    let next = features
+                        .ray_query
+                        .insert(vk::PhysicalDeviceRayQueryFeaturesKHR::default());
+                    builder = builder.push_next(next);
+                }
+            }
+
+            // `VkPhysicalDeviceProtectedMemoryFeatures` only exists in core 1.1
+            if capabilities.effective_api_version >= vk::API_VERSION_1_1 {
+                let next = features
+                    .protected_memory
+                    .insert(vk::PhysicalDeviceProtectedMemoryFeatures::default());
+                builder = builder.push_next(next);
+            }
+
             let mut features2 = builder.build();
             unsafe {
                 get_device_properties.get_physical_device_features2(phd, &mut features2);
@@ -961,23 +1327,32 @@ impl super::Instance {
                     .as_ref()
                     .and_then(|driver| cstr_from_bytes_until_nul(&driver.driver_info))
                     .and_then(|name| name.to_str().ok())
-                    .unwrap_or("?")
-                    .to_owned()
+                    .map(str::to_owned)
+                    // Without `VK_KHR_driver_properties`, report the raw driver version instead.
+                    .unwrap_or_else(|| {
+                        format_driver_version(
+                            phd_capabilities.properties.vendor_id,
+                            phd_capabilities.properties.driver_version,
+                        )
+                    })
             },
             backend: wgt::Backend::Vulkan,
         };
 
-        let (available_features, downlevel_flags) =
+        let (mut available_features, downlevel_flags) =
             phd_features.to_wgpu(&self.shared.raw, phd, &phd_capabilities);
         let mut workarounds = super::Workarounds::empty();
         {
             // see https://github.com/gfx-rs/gfx/issues/1930
-            let _is_windows_intel_dual_src_bug = cfg!(windows)
+            let is_windows_intel_dual_src_bug = cfg!(windows)
                 && phd_capabilities.properties.vendor_id == db::intel::VENDOR
                 && (phd_capabilities.properties.device_id & db::intel::DEVICE_KABY_LAKE_MASK
                     == db::intel::DEVICE_KABY_LAKE_MASK
                     || phd_capabilities.properties.device_id & db::intel::DEVICE_SKY_LAKE_MASK
                         == db::intel::DEVICE_SKY_LAKE_MASK);
+            if is_windows_intel_dual_src_bug {
+                available_features.remove(wgt::Features::DUAL_SOURCE_BLENDING);
+            }
             // TODO: only enable for particular devices
             workarounds |= super::Workarounds::SEPARATE_ENTRY_POINTS;
             workarounds.set(
@@ -1016,8 +1391,19 @@ impl super::Instance {
             log::warn!("The first queue only exposes {:?}", queue_flags);
             return None;
         }
+        // Protected submissions need a queue family that supports them
+        if !queue_flags.contains(vk::QueueFlags::PROTECTED) {
+            available_features.remove(wgt::Features::PROTECTED_CONTENT);
+        }
+        // Timestamps can only be written on queues that report valid bits for them
+        if queue_families[0].timestamp_valid_bits == 0 {
+            log::info!("The first queue doesn't support timestamps");
+            available_features.remove(
+                wgt::Features::TIMESTAMP_QUERY | wgt::Features::TIMESTAMP_QUERY_INSIDE_PASSES,
+            );
+        }
 
-        let private_caps = super::PrivateCapabilities {
+        let mut private_caps = super::PrivateCapabilities {
             flip_y_requires_shift: phd_capabilities.effective_api_version >= vk::API_VERSION_1_1
                 || phd_capabilities.supports_extension(vk::KhrMaintenance1Fn::name()),
             imageless_framebuffers: match phd_features.imageless_framebuffer {
@@ -1070,6 +1456,11 @@ impl super::Instance {
                 .as_ref()
                 .map(|r| r.robust_buffer_access2 == 1)
                 .unwrap_or_default(),
+            update_after_bind_descriptors: phd_features.descriptor_indexing.map_or(false, |di| {
+                di.descriptor_binding_sampled_image_update_after_bind != 0
+                    && di.descriptor_binding_storage_image_update_after_bind != 0
+                    && di.descriptor_binding_storage_buffer_update_after_bind != 0
+            }),
             robust_image_access2: phd_features
                 .robustness2
                 .as_ref()
@@ -1080,7 +1471,30 @@ impl super::Instance {
                 .map_or(false, |ext| {
                     ext.shader_zero_initialize_workgroup_memory == vk::TRUE
                 }),
+            external_timeline_semaphores: false,
         };
+        if cfg!(unix)
+            && private_caps.timeline_semaphores
+            && phd_capabilities.effective_api_version >= vk::API_VERSION_1_1
+            && phd_capabilities.supports_extension(vk::KhrExternalSemaphoreFdFn::name())
+        {
+            let mut type_info =
+                vk::SemaphoreTypeCreateInfo::builder().semaphore_type(vk::SemaphoreType::TIMELINE);
+            let info = vk::PhysicalDeviceExternalSemaphoreInfo::builder()
+                .handle_type(vk::ExternalSemaphoreHandleTypeFlags::OPAQUE_FD)
+                .push_next(&mut type_info);
+            let mut properties = vk::ExternalSemaphoreProperties::default();
+            unsafe {
+                self.shared
+                    .raw
+                    .get_physical_device_external_semaphore_properties(phd, &info, &mut properties)
+            };
+            private_caps.external_timeline_semaphores =
+                properties.external_semaphore_features.contains(
+                    vk::ExternalSemaphoreFeatureFlags::EXPORTABLE
+                        | vk::ExternalSemaphoreFeatureFlags::IMPORTABLE,
+                );
+        }
         let capabilities = crate::Capabilities {
             limits: phd_capabilities.to_wgpu_limits(),
             alignments: phd_capabilities.to_hal_alignments(),
@@ -1116,11 +1530,81 @@ impl super::Instance {
     }
 }
 
+/// Formats `VkPhysicalDeviceProperties::driverVersion`, whose encoding is vendor-specific.
+fn format_driver_version(vendor_id: u32, version: u32) -> String {
+    use crate::auxil::db;
+
+    match vendor_id {
+        db::nvidia::VENDOR => format!(
+            "{}.{}.{}.{}",
+            version >> 22,
+            (version >> 14) & 0xff,
+            (version >> 6) & 0xff,
+            version & 0x3f
+        ),
+        db::intel::VENDOR if cfg!(windows) => format!("{}.{}", version >> 14, version & 0x3fff),
+        _ => format!(
+            "{}.{}.{}",
+            vk::api_version_major(version),
+            vk::api_version_minor(version),
+            vk::api_version_patch(version)
+        ),
+    }
+}
+
 impl super::Adapter {
     pub fn raw_physical_device(&self) -> ash::vk::PhysicalDevice {
         self.raw
     }
 
+    /// Returns the size, budget and current usage of each memory heap.
+    ///
+    /// The budget and usage come from `VK_EXT_memory_budget`, and take other processes
+    /// into account. Without the extension, the budget is the size of the heap and the
+    /// usage is unknown.
+    pub fn memory_budget(&self) -> Vec<super::MemoryHeapBudget> {
+        let mut budget_properties = vk::PhysicalDeviceMemoryBudgetPropertiesEXT::default();
+        let supports_budget = self
+            .phd_capabilities
+            .supports_extension(vk::ExtMemoryBudgetFn::name());
+        let mem_properties = match self.instance.get_physical_device_properties {
+            Some(ref functor) if supports_budget => {
+                let mut properties2 = vk::PhysicalDeviceMemoryProperties2::builder()
+                    .push_next(&mut budget_properties)
+                    .build();
+                unsafe {
+                    functor.get_physical_device_memory_properties2(self.raw, &mut properties2)
+                };
+                properties2.memory_properties
+            }
+            _ => unsafe {
+                self.instance
+                    .raw
+                    .get_physical_device_memory_properties(self.raw)
+            },
+        };
+        let with_budget = supports_budget && self.instance.get_physical_device_properties.is_some();
+
+        mem_properties.memory_heaps[..mem_properties.memory_heap_count as usize]
+            .iter()
+            .enumerate()
+            .map(|(index, heap)| super::MemoryHeapBudget {
+                size: heap.size,
+                device_local: heap.flags.contains(vk::MemoryHeapFlags::DEVICE_LOCAL),
+                budget: if with_budget {
+                    budget_properties.heap_budget[index]
+                } else {
+                    heap.size
+                },
+                usage: if with_budget {
+                    Some(budget_properties.heap_usage[index])
+                } else {
+                    None
+                },
+            })
+            .collect()
+    }
+
     pub fn physical_device_capabilities(&self) -> &PhysicalDeviceCapabilities {
         &self.phd_capabilities
     }
@@ -1130,9 +1614,39 @@ impl super::Adapter {
     }
 
     pub fn required_device_extensions(&self, features: wgt::Features) -> Vec<&'static CStr> {
-        let (supported_extensions, unsupported_extensions) = self
-            .phd_capabilities
-            .get_required_extensions(features)
+        let mut requested_extensions = self.phd_capabilities.get_required_extensions(features);
+
+        // Optional `VK_NV_device_diagnostic_checkpoints`, used to report the last
+        // debug markers reached by the GPU when the device is lost.
+        if self.instance.flags.contains(crate::InstanceFlags::DEBUG)
+            && self
+                .phd_capabilities
+                .supports_extension(vk::NvDeviceDiagnosticCheckpointsFn::name())
+        {
+            requested_extensions.push(vk::NvDeviceDiagnosticCheckpointsFn::name());
+        }
+
+        // Optional `VK_AMD_buffer_marker`, used in the same way on AMD drivers,
+        // which don't have diagnostic checkpoints.
+        if self.instance.flags.contains(crate::InstanceFlags::DEBUG)
+            && self
+                .phd_capabilities
+                .supports_extension(vk::AmdBufferMarkerFn::name())
+        {
+            requested_extensions.push(vk::AmdBufferMarkerFn::name());
+        }
+
+        // Optional `VK_KHR_pipeline_executable_properties`, used to report shader
+        // compiler statistics. Capturing them can slow down pipeline creation.
+        if self.instance.flags.contains(crate::InstanceFlags::DEBUG)
+            && self
+                .phd_capabilities
+                .supports_extension(vk::KhrPipelineExecutablePropertiesFn::name())
+        {
+            requested_extensions.push(vk::KhrPipelineExecutablePropertiesFn::name());
+        }
+
+        let (supported_extensions, unsupported_extensions) = requested_extensions
             .iter()
             .partition::<Vec<&CStr>, _>(|&&extension| {
                 self.phd_capabilities.supports_extension(extension)
@@ -1166,6 +1680,8 @@ impl super::Adapter {
     /// - `raw_device` must be created from this adapter.
     /// - `raw_device` must be created using `family_index`, `enabled_extensions` and `physical_device_features()`
     /// - `enabled_extensions` must be a superset of `required_device_extensions()`.
+    /// - If `features` contains `PROTECTED_CONTENT`, the queue must be created with
+    ///   `VK_DEVICE_QUEUE_CREATE_PROTECTED_BIT`.
     #[allow(clippy::too_many_arguments)]
     pub unsafe fn device_from_raw(
         &self,
@@ -1175,6 +1691,32 @@ impl super::Adapter {
         features: wgt::Features,
         family_index: u32,
         queue_index: u32,
+    ) -> Result<crate::OpenDevice<super::Api>, crate::DeviceError> {
+        unsafe {
+            self.device_from_raw_impl(
+                raw_device,
+                handle_is_owned,
+                enabled_extensions,
+                features,
+                family_index,
+                queue_index,
+                Vec::new(),
+            )
+        }
+    }
+
+    /// Like `device_from_raw`, with the queue families that resources are shared
+    /// between when queues from several families are used.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn device_from_raw_impl(
+        &self,
+        raw_device: ash::Device,
+        handle_is_owned: bool,
+        enabled_extensions: &[&'static CStr],
+        features: wgt::Features,
+        family_index: u32,
+        queue_index: u32,
+        concurrent_queue_families: Vec<u32>,
     ) -> Result<crate::OpenDevice<super::Api>, crate::DeviceError> {
         let mem_properties = {
             profiling::scope!("vkGetPhysicalDeviceMemoryProperties");
@@ -1193,6 +1735,18 @@ impl super::Adapter {
                 u
             }
         });
+        let protected_ash_memory_types = memory_types.iter().enumerate().fold(0, |u, (i, mem)| {
+            if mem
+                .property_flags
+                .contains(vk::MemoryPropertyFlags::PROTECTED)
+                && (self.known_memory_flags | vk::MemoryPropertyFlags::PROTECTED)
+                    .contains(mem.property_flags)
+            {
+                u | (1 << i)
+            } else {
+                u
+            }
+        });
 
         let swapchain_fn = khr::Swapchain::new(&self.instance.raw, &raw_device);
 
@@ -1201,6 +1755,84 @@ impl super::Adapter {
         } else {
             None
         };
+        let diagnostic_checkpoints_fn =
+            if enabled_extensions.contains(&nv::DeviceDiagnosticCheckpoints::name()) {
+                Some(nv::DeviceDiagnosticCheckpoints::new(
+                    &self.instance.raw,
+                    &raw_device,
+                ))
+            } else {
+                None
+            };
+        let buffer_marker_fn = if enabled_extensions.contains(&vk::AmdBufferMarkerFn::name()) {
+            Some(vk::AmdBufferMarkerFn::load(|name| unsafe {
+                std::mem::transmute(
+                    self.instance
+                        .raw
+                        .get_device_proc_addr(raw_device.handle(), name.as_ptr()),
+                )
+            }))
+        } else {
+            None
+        };
+        let buffer_markers = if buffer_marker_fn.is_some() {
+            match unsafe { super::BufferMarkers::new(&raw_device, memory_types) } {
+                Ok(markers) => Some(markers),
+                Err(err) => {
+                    log::warn!(
+                        "Unable to create the buffer for AMD buffer markers: {}",
+                        err
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let pipeline_executable_properties_fn =
+            if enabled_extensions.contains(&khr::PipelineExecutableProperties::name()) {
+                Some(khr::PipelineExecutableProperties::new(
+                    &self.instance.raw,
+                    &raw_device,
+                ))
+            } else {
+                None
+            };
+        let fragment_shading_rate_fn =
+            if enabled_extensions.contains(&vk::KhrFragmentShadingRateFn::name()) {
+                Some(vk::KhrFragmentShadingRateFn::load(|name| unsafe {
+                    std::mem::transmute(
+                        self.instance
+                            .raw
+                            .get_device_proc_addr(raw_device.handle(), name.as_ptr()),
+                    )
+                }))
+            } else {
+                None
+            };
+        let acceleration_structure_fn =
+            if enabled_extensions.contains(&khr::AccelerationStructure::name()) {
+                Some(khr::AccelerationStructure::new(
+                    &self.instance.raw,
+                    &raw_device,
+                ))
+            } else {
+                None
+            };
+        let external_memory_fd_fn = if enabled_extensions.contains(&khr::ExternalMemoryFd::name()) {
+            Some(khr::ExternalMemoryFd::new(&self.instance.raw, &raw_device))
+        } else {
+            None
+        };
+        let external_semaphore_fd_fn =
+            if enabled_extensions.contains(&khr::ExternalSemaphoreFd::name()) {
+                Some(khr::ExternalSemaphoreFd::new(
+                    &self.instance.raw,
+                    &raw_device,
+                ))
+            } else {
+                None
+            };
         let timeline_semaphore_fn = if enabled_extensions.contains(&khr::TimelineSemaphore::name())
         {
             Some(super::ExtensionFn::Extension(khr::TimelineSemaphore::new(
@@ -1247,6 +1879,10 @@ impl super::Adapter {
                 capabilities.push(spv::Capability::ShaderNonUniform);
             }
 
+            if features.contains(wgt::Features::RAY_QUERY) {
+                capabilities.push(spv::Capability::RayQueryKHR);
+            }
+
             let mut flags = spv::WriterFlags::empty();
             flags.set(
                 spv::WriterFlags::DEBUG,
@@ -1269,7 +1905,7 @@ impl super::Adapter {
                 capabilities: Some(capabilities.iter().cloned().collect()),
                 bounds_check_policies: naga::proc::BoundsCheckPolicies {
                     index: naga::proc::BoundsCheckPolicy::Restrict,
-                    buffer: if self.private_caps.robust_buffer_access {
+                    buffer: if features.contains(wgt::Features::ROBUST_BUFFER_ACCESS) {
                         naga::proc::BoundsCheckPolicy::Unchecked
                     } else {
                         naga::proc::BoundsCheckPolicy::Restrict
@@ -1297,11 +1933,25 @@ impl super::Adapter {
             }
         };
 
-        let raw_queue = {
+        let protected_content = features.contains(wgt::Features::PROTECTED_CONTENT);
+        let raw_queue = if protected_content {
+            profiling::scope!("vkGetDeviceQueue2");
+            let info = vk::DeviceQueueInfo2::builder()
+                .flags(vk::DeviceQueueCreateFlags::PROTECTED)
+                .queue_family_index(family_index)
+                .queue_index(queue_index);
+            unsafe { raw_device.get_device_queue2(&info) }
+        } else {
             profiling::scope!("vkGetDeviceQueue");
             unsafe { raw_device.get_device_queue(family_index, queue_index) }
         };
 
+        let pipeline_cache = {
+            profiling::scope!("vkCreatePipelineCache");
+            let vk_info = vk::PipelineCacheCreateInfo::builder();
+            unsafe { raw_device.create_pipeline_cache(&vk_info, None)? }
+        };
+
         let shared = Arc::new(super::DeviceShared {
             raw: raw_device,
             family_index,
@@ -1314,29 +1964,47 @@ impl super::Adapter {
             extension_fns: super::DeviceExtensionFunctions {
                 draw_indirect_count: indirect_count_fn,
                 timeline_semaphore: timeline_semaphore_fn,
+                diagnostic_checkpoints: diagnostic_checkpoints_fn,
+                buffer_marker: buffer_marker_fn,
+                external_memory_fd: external_memory_fd_fn,
+                external_semaphore_fd: external_semaphore_fd_fn,
+                fragment_shading_rate: fragment_shading_rate_fn,
+                acceleration_structure: acceleration_structure_fn,
+                pipeline_executable_properties: pipeline_executable_properties_fn,
             },
             vendor_id: self.phd_capabilities.properties.vendor_id,
+            device_id: self.phd_capabilities.properties.device_id,
             timestamp_period: self.phd_capabilities.properties.limits.timestamp_period,
+            pipeline_cache,
+            pipeline_cache_uuid: self.phd_capabilities.properties.pipeline_cache_uuid,
+            viewport_count: if features.contains(wgt::Features::MULTI_VIEWPORT) {
+                wgt::MAX_VIEWPORTS
+            } else {
+                1
+            },
+            depth_bounds_test: features.contains(wgt::Features::DEPTH_BOUNDS_TEST),
+            concurrent_queue_families,
             private_caps: self.private_caps.clone(),
             workarounds: self.workarounds,
             render_passes: Mutex::new(Default::default()),
             framebuffers: Mutex::new(Default::default()),
+            checkpoint_labels: Mutex::new(Default::default()),
+            buffer_markers,
         });
-        let mut relay_semaphores = [vk::Semaphore::null(); 2];
-        for sem in relay_semaphores.iter_mut() {
-            unsafe {
-                *sem = shared
-                    .raw
-                    .create_semaphore(&vk::SemaphoreCreateInfo::builder(), None)?
-            };
-        }
-        let queue = super::Queue {
-            raw: raw_queue,
-            swapchain_fn,
-            device: Arc::clone(&shared),
-            family_index,
-            relay_semaphores,
-            relay_index: None,
+        let family_flags = unsafe {
+            self.instance
+                .raw
+                .get_physical_device_queue_family_properties(self.raw)
+        }[family_index as usize]
+            .queue_flags;
+        let queue = unsafe {
+            super::Queue::new(
+                Arc::clone(&shared),
+                raw_queue,
+                family_index,
+                family_flags,
+                swapchain_fn,
+            )?
         };
 
         let mem_allocator = {
@@ -1368,7 +2036,8 @@ impl super::Adapter {
                         size: memory_heap.size,
                     })
                     .collect(),
-                buffer_device_address: false,
+                buffer_device_address: features
+                    .contains(wgt::Features::RAY_TRACING_ACCELERATION_STRUCTURE),
             };
             gpu_alloc::GpuAllocator::new(config, properties)
         };
@@ -1383,14 +2052,26 @@ impl super::Adapter {
         let device = super::Device {
             shared,
             mem_allocator: Mutex::new(mem_allocator),
+            max_memory_allocation_count: self
+                .phd_capabilities
+                .properties
+                .limits
+                .max_memory_allocation_count,
             desc_allocator: Mutex::new(desc_allocator),
+            recycled_desc_sets: Mutex::new(Default::default()),
             valid_ash_memory_types,
+            protected_ash_memory_types,
+            memory_types: memory_types.to_vec(),
             naga_options,
             #[cfg(feature = "renderdoc")]
             render_doc: Default::default(),
         };
 
-        Ok(crate::OpenDevice { device, queue })
+        Ok(crate::OpenDevice {
+            device,
+            queue,
+            additional_queues: Vec::new(),
+        })
     }
 }
 
@@ -1399,16 +2080,75 @@ impl crate::Adapter<super::Api> for super::Adapter {
         &self,
         features: wgt::Features,
         _limits: &wgt::Limits,
+        additional_queues: &[wgt::QueueKind],
     ) -> Result<crate::OpenDevice<super::Api>, crate::DeviceError> {
         let enabled_extensions = self.required_device_extensions(features);
         let mut enabled_phd_features = self.physical_device_features(&enabled_extensions, features);
 
+        if features.contains(wgt::Features::PROTECTED_CONTENT) && !additional_queues.is_empty() {
+            log::error!("Additional queues are not supported with `PROTECTED_CONTENT`");
+            return Err(crate::DeviceError::ResourceCreationFailed);
+        }
+
+        let queue_families = unsafe {
+            self.instance
+                .raw
+                .get_physical_device_queue_family_properties(self.raw)
+        };
+        let find_family = |required: vk::QueueFlags, excluded: vk::QueueFlags| {
+            queue_families
+                .iter()
+                .position(|family| {
+                    family.queue_flags.contains(required)
+                        && !family.queue_flags.intersects(excluded)
+                })
+                .map(|index| index as u32)
+        };
+
         let family_index = 0; //TODO
-        let family_info = vk::DeviceQueueCreateInfo::builder()
-            .queue_family_index(family_index)
-            .queue_priorities(&[1.0])
-            .build();
-        let family_infos = [family_info];
+        let mut queue_counts = vec![0u32; queue_families.len()];
+        queue_counts[family_index as usize] = 1;
+        let mut queue_locations = Vec::with_capacity(additional_queues.len());
+        for &kind in additional_queues {
+            let family = match kind {
+                wgt::QueueKind::Compute => {
+                    find_family(vk::QueueFlags::COMPUTE, vk::QueueFlags::GRAPHICS)
+                }
+                wgt::QueueKind::Transfer => find_family(
+                    vk::QueueFlags::TRANSFER,
+                    vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE,
+                )
+                .or_else(|| find_family(vk::QueueFlags::COMPUTE, vk::QueueFlags::GRAPHICS)),
+            }
+            .unwrap_or(family_index);
+            let queue_index = queue_counts[family as usize];
+            if queue_index >= queue_families[family as usize].queue_count {
+                log::error!("Queue family {} has no queue left for {:?}", family, kind);
+                return Err(crate::DeviceError::ResourceCreationFailed);
+            }
+            queue_counts[family as usize] += 1;
+            queue_locations.push((family, queue_index));
+        }
+
+        let mut queue_flags = vk::DeviceQueueCreateFlags::empty();
+        if features.contains(wgt::Features::PROTECTED_CONTENT) {
+            queue_flags |= vk::DeviceQueueCreateFlags::PROTECTED;
+        }
+        let priorities = vec![1.0; queue_counts.iter().copied().max().unwrap_or(1) as usize];
+        let family_infos = queue_counts
+            .iter()
+            .enumerate()
+            .filter(|&(_, &count)| count != 0)
+            .map(|(family, &count)| {
+                let mut info = vk::DeviceQueueCreateInfo::builder()
+                    .queue_family_index(family as u32)
+                    .queue_priorities(&priorities[..count as usize]);
+                if family as u32 == family_index {
+                    info = info.flags(queue_flags);
+                }
+                info.build()
+            })
+            .collect::<Vec<_>>();
 
         let str_pointers = enabled_extensions
             .iter()
@@ -1429,16 +2169,42 @@ impl crate::Adapter<super::Api> for super::Adapter {
             unsafe { self.instance.raw.create_device(self.raw, &info, None)? }
         };
 
-        unsafe {
-            self.device_from_raw(
+        let concurrent_queue_families = if family_infos.len() > 1 {
+            family_infos
+                .iter()
+                .map(|info| info.queue_family_index)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let mut open = unsafe {
+            self.device_from_raw_impl(
                 raw_device,
                 true,
                 &enabled_extensions,
                 features,
-                family_info.queue_family_index,
+                family_index,
                 0,
-            )
+                concurrent_queue_families,
+            )?
+        };
+
+        let shared = &open.device.shared;
+        for (family, queue_index) in queue_locations {
+            let raw = unsafe { shared.raw.get_device_queue(family, queue_index) };
+            let swapchain_fn = khr::Swapchain::new(&self.instance.raw, &shared.raw);
+            let queue = unsafe {
+                super::Queue::new(
+                    Arc::clone(shared),
+                    raw,
+                    family,
+                    queue_families[family as usize].queue_flags,
+                    swapchain_fn,
+                )
+            }?;
+            open.additional_queues.push(queue);
         }
+        Ok(open)
     }
 
     unsafe fn texture_format_capabilities(
@@ -1447,6 +2213,14 @@ impl crate::Adapter<super::Api> for super::Adapter {
     ) -> crate::TextureFormatCapabilities {
         use crate::TextureFormatCapabilities as Tfc;
 
+        // Multi-planar formats were introduced with `VK_KHR_sampler_ycbcr_conversion`,
+        // which was promoted to core in Vulkan 1.1.
+        if format.is_multi_planar_format()
+            && self.phd_capabilities.effective_api_version < vk::API_VERSION_1_1
+        {
+            return Tfc::empty();
+        }
+
         let vk_format = self.private_caps.map_texture_format(format);
         let properties = unsafe {
             self.instance
@@ -1497,13 +2271,18 @@ impl crate::Adapter<super::Api> for super::Adapter {
             features.intersects(vk::FormatFeatureFlags::TRANSFER_DST),
         );
         // Vulkan is very permissive about MSAA
-        flags.set(Tfc::MULTISAMPLE_RESOLVE, !format.is_compressed());
+        flags.set(
+            Tfc::MULTISAMPLE_RESOLVE,
+            !format.is_compressed() && !format.is_multi_planar_format(),
+        );
 
         // get the supported sample counts
         let format_aspect = crate::FormatAspects::from(format);
         let limits = self.phd_capabilities.properties.limits;
 
-        let sample_flags = if format_aspect.contains(crate::FormatAspects::DEPTH) {
+        let sample_flags = if format.is_multi_planar_format() {
+            vk::SampleCountFlags::TYPE_1
+        } else if format_aspect.contains(crate::FormatAspects::DEPTH) {
             limits
                 .framebuffer_depth_sample_counts
                 .min(limits.sampled_image_depth_sample_counts)
@@ -1645,12 +2424,30 @@ impl crate::Adapter<super::Api> for super::Adapter {
             }
         };
 
-        let formats = raw_surface_formats
-            .into_iter()
-            .filter_map(conv::map_vk_surface_formats)
-            .collect();
+        // A format is only supported in the color spaces the surface reports it with.
+        let mut formats = Vec::new();
+        let mut color_spaces = Vec::new();
+        for sf in raw_surface_formats {
+            let pair = match (
+                conv::map_vk_surface_format(sf.format),
+                conv::map_vk_color_space(sf.color_space),
+            ) {
+                (Some(format), Some(color_space)) if color_space.is_compatible_with(format) => {
+                    (format, color_space)
+                }
+                _ => continue,
+            };
+            let format = pair.0;
+            if !formats.contains(&format) {
+                formats.push(format);
+            }
+            if !color_spaces.contains(&pair) {
+                color_spaces.push(pair);
+            }
+        }
         Some(crate::SurfaceCapabilities {
             formats,
+            color_spaces,
             swap_chain_sizes: caps.min_image_count..=max_image_count,
             current_extent,
             extents: min_extent..=max_extent,
@@ -1717,6 +2514,43 @@ fn is_format_16bit_norm_supported(instance: &ash::Instance, phd: vk::PhysicalDev
     r16unorm && r16snorm && rg16unorm && rg16snorm && rgba16unorm && rgba16snorm
 }
 
+fn is_format_s3tc_supported(instance: &ash::Instance, phd: vk::PhysicalDevice) -> bool {
+    let tiling = vk::ImageTiling::OPTIMAL;
+    let features =
+        vk::FormatFeatureFlags::SAMPLED_IMAGE | vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR;
+    [
+        vk::Format::BC1_RGBA_UNORM_BLOCK,
+        vk::Format::BC1_RGBA_SRGB_BLOCK,
+        vk::Format::BC2_UNORM_BLOCK,
+        vk::Format::BC2_SRGB_BLOCK,
+        vk::Format::BC3_UNORM_BLOCK,
+        vk::Format::BC3_SRGB_BLOCK,
+    ]
+    .iter()
+    .all(|&format| supports_format(instance, phd, format, tiling, features))
+}
+
+/// Whether all of the raw `formats` can be used for sampled 3D images.
+fn supports_3d_formats(
+    instance: &ash::Instance,
+    phd: vk::PhysicalDevice,
+    formats: impl IntoIterator<Item = i32>,
+) -> bool {
+    formats.into_iter().all(|format| {
+        unsafe {
+            instance.get_physical_device_image_format_properties(
+                phd,
+                vk::Format::from_raw(format),
+                vk::ImageType::TYPE_3D,
+                vk::ImageTiling::OPTIMAL,
+                vk::ImageUsageFlags::SAMPLED | vk::ImageUsageFlags::TRANSFER_DST,
+                vk::ImageCreateFlags::empty(),
+            )
+        }
+        .is_ok()
+    })
+}
+
 fn supports_format(
     instance: &ash::Instance,
     phd: vk::PhysicalDevice,
@@ -40,9 +40,26 @@ impl super::Texture {
 }
 
 impl super::DeviceShared {
-    fn debug_messenger(&self) -> Option<&ext::DebugUtils> {
+    pub(super) fn debug_messenger(&self) -> Option<&ext::DebugUtils> {
         Some(&self.instance.debug_utils.as_ref()?.extension)
     }
+
+    /// Sets the pipeline fragment shading rate, if `VK_KHR_fragment_shading_rate` is enabled.
+    ///
+    /// The rate isn't combined with the primitive and attachment rates, which wgpu doesn't use.
+    unsafe fn set_fragment_shading_rate(&self, raw: vk::CommandBuffer, rate: wgt::ShadingRate) {
+        if let Some(ref fsr) = self.extension_fns.fragment_shading_rate {
+            let (width, height) = rate.size();
+            let combiner_ops = [vk::FragmentShadingRateCombinerOpKHR::KEEP; 2];
+            unsafe {
+                (fsr.cmd_set_fragment_shading_rate_khr)(
+                    raw,
+                    &vk::Extent2D { width, height },
+                    &combiner_ops,
+                )
+            };
+        }
+    }
 }
 
 impl super::CommandEncoder {
@@ -61,7 +78,11 @@ impl super::CommandEncoder {
 }
 
 impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
-    unsafe fn begin_encoding(&mut self, label: crate::Label) -> Result<(), crate::DeviceError> {
+    unsafe fn begin_encoding(
+        &mut self,
+        label: crate::Label,
+        reusable: bool,
+    ) -> Result<(), crate::DeviceError> {
         if self.free.is_empty() {
             let vk_info = vk::CommandBufferAllocateInfo::builder()
                 .command_pool(self.raw)
@@ -84,10 +105,14 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
 
         // Reset this in case the last renderpass was never ended.
         self.rpass_debug_marker_active = false;
+        self.checkpoint_groups.clear();
 
-        let vk_info = vk::CommandBufferBeginInfo::builder()
-            .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT)
-            .build();
+        let flags = if reusable {
+            vk::CommandBufferUsageFlags::empty()
+        } else {
+            vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT
+        };
+        let vk_info = vk::CommandBufferBeginInfo::builder().flags(flags).build();
         unsafe { self.device.raw.begin_command_buffer(raw, &vk_info) }?;
         self.active = raw;
 
@@ -98,7 +123,10 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         let raw = self.active;
         self.active = vk::CommandBuffer::null();
         unsafe { self.device.raw.end_command_buffer(raw) }?;
-        Ok(super::CommandBuffer { raw })
+        Ok(super::CommandBuffer {
+            raw,
+            protected: self.protected,
+        })
     }
 
     unsafe fn discard_encoding(&mut self) {
@@ -114,6 +142,7 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         self.free
             .extend(cmd_bufs.into_iter().map(|cmd_buf| cmd_buf.raw));
         self.free.append(&mut self.discarded);
+        self.device.release_checkpoints(&mut self.checkpoint_labels);
         let _ = unsafe {
             self.device
                 .raw
@@ -128,34 +157,41 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         //Note: this is done so that we never end up with empty stage flags
         let mut src_stages = vk::PipelineStageFlags::TOP_OF_PIPE;
         let mut dst_stages = vk::PipelineStageFlags::BOTTOM_OF_PIPE;
-        let vk_barriers = &mut self.temp.buffer_barriers;
-        vk_barriers.clear();
+        let mut src_access = vk::AccessFlags::empty();
+        let mut dst_access = vk::AccessFlags::empty();
+        let mut needs_barrier = false;
 
         for bar in barriers {
-            let (src_stage, src_access) = conv::map_buffer_usage_to_barrier(bar.usage.start);
+            let (src_stage, src_buffer_access) = conv::map_buffer_usage_to_barrier(bar.usage.start);
             src_stages |= src_stage;
-            let (dst_stage, dst_access) = conv::map_buffer_usage_to_barrier(bar.usage.end);
+            src_access |= src_buffer_access;
+            let (dst_stage, dst_buffer_access) = conv::map_buffer_usage_to_barrier(bar.usage.end);
             dst_stages |= dst_stage;
-
-            vk_barriers.push(
-                vk::BufferMemoryBarrier::builder()
-                    .buffer(bar.buffer.raw)
-                    .size(vk::WHOLE_SIZE)
-                    .src_access_mask(src_access)
-                    .dst_access_mask(dst_access)
-                    .build(),
-            )
+            dst_access |= dst_buffer_access;
+            needs_barrier = true;
         }
 
-        if !vk_barriers.is_empty() {
+        // Usages of shaders map to the graphics stages too, which queues without
+        // graphics support don't accept.
+        src_stages &= self.supported_stages;
+        dst_stages &= self.supported_stages;
+
+        // We never transfer queue family ownership, so all of the buffer barriers can be
+        // coalesced into a single global memory barrier, which drivers handle at least
+        // as efficiently.
+        if needs_barrier {
+            let vk_barrier = vk::MemoryBarrier::builder()
+                .src_access_mask(src_access)
+                .dst_access_mask(dst_access)
+                .build();
             unsafe {
                 self.device.raw.cmd_pipeline_barrier(
                     self.active,
                     src_stages,
                     dst_stages,
                     vk::DependencyFlags::empty(),
+                    &[vk_barrier],
                     &[],
-                    vk_barriers,
                     &[],
                 )
             };
@@ -168,22 +204,34 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
     {
         let mut src_stages = vk::PipelineStageFlags::empty();
         let mut dst_stages = vk::PipelineStageFlags::empty();
+        let mut global_src_access = vk::AccessFlags::empty();
+        let mut global_dst_access = vk::AccessFlags::empty();
+        let mut needs_global_barrier = false;
         let vk_barriers = &mut self.temp.image_barriers;
         vk_barriers.clear();
 
         for bar in barriers {
-            let range = conv::map_subresource_range_combined_aspect(
-                &bar.range,
-                bar.texture.format,
-                &self.device.private_caps,
-            );
             let (src_stage, src_access) = conv::map_texture_usage_to_barrier(bar.usage.start);
-            let src_layout = conv::derive_image_layout(bar.usage.start, bar.texture.format);
             src_stages |= src_stage;
             let (dst_stage, dst_access) = conv::map_texture_usage_to_barrier(bar.usage.end);
-            let dst_layout = conv::derive_image_layout(bar.usage.end, bar.texture.format);
             dst_stages |= dst_stage;
 
+            // Like for buffers, transitions that keep the layout only need to make memory
+            // available and visible, so they are coalesced into a global memory barrier.
+            let src_layout = conv::derive_image_layout(bar.usage.start, bar.texture.format);
+            let dst_layout = conv::derive_image_layout(bar.usage.end, bar.texture.format);
+            if src_layout == dst_layout {
+                global_src_access |= src_access;
+                global_dst_access |= dst_access;
+                needs_global_barrier = true;
+                continue;
+            }
+
+            let range = conv::map_subresource_range_combined_aspect(
+                &bar.range,
+                bar.texture.format,
+                &self.device.private_caps,
+            );
             vk_barriers.push(
                 vk::ImageMemoryBarrier::builder()
                     .image(bar.texture.raw)
@@ -196,14 +244,27 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
             );
         }
 
-        if !vk_barriers.is_empty() {
+        if needs_global_barrier || !vk_barriers.is_empty() {
+            // Like for buffers, only keep the stages that the queue supports.
+            src_stages = (src_stages & self.supported_stages) | vk::PipelineStageFlags::TOP_OF_PIPE;
+            dst_stages =
+                (dst_stages & self.supported_stages) | vk::PipelineStageFlags::BOTTOM_OF_PIPE;
+            let global_barrier = vk::MemoryBarrier::builder()
+                .src_access_mask(global_src_access)
+                .dst_access_mask(global_dst_access)
+                .build();
+            let global_barriers = if needs_global_barrier {
+                slice::from_ref(&global_barrier)
+            } else {
+                &[]
+            };
             unsafe {
                 self.device.raw.cmd_pipeline_barrier(
                     self.active,
                     src_stages,
                     dst_stages,
                     vk::DependencyFlags::empty(),
-                    &[],
+                    global_barriers,
                     &[],
                     vk_barriers,
                 )
@@ -404,7 +465,12 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                 vk_clear_values.push(vk::ClearValue {
                     color: unsafe { cat.make_vk_clear_color() },
                 });
-                vk_image_views.push(cat.target.view.raw);
+                let (raw_view, attachment) = cat
+                    .target
+                    .view
+                    .attachment_for(&self.device, cat.depth_slice)
+                    .unwrap();
+                vk_image_views.push(raw_view);
                 let color = super::ColorAttachmentKey {
                     base: cat.target.make_attachment_key(cat.ops, caps),
                     resolve: cat.resolve_target.as_ref().map(|target| {
@@ -413,7 +479,7 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                 };
 
                 rp_key.colors.push(Some(color));
-                fb_key.attachments.push(cat.target.view.attachment.clone());
+                fb_key.attachments.push(attachment);
                 if let Some(ref at) = cat.resolve_target {
                     vk_clear_values.push(unsafe { mem::zeroed() });
                     vk_image_views.push(at.view.raw);
@@ -462,7 +528,7 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                 height: desc.extent.height,
             },
         };
-        let vk_viewports = [vk::Viewport {
+        let vk_viewport = vk::Viewport {
             x: 0.0,
             y: if self.device.private_caps.flip_y_requires_shift {
                 desc.extent.height as f32
@@ -473,7 +539,15 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
             height: -(desc.extent.height as f32),
             min_depth: 0.0,
             max_depth: 1.0,
-        }];
+        };
+        // Pipelines use all viewports with `MULTI_VIEWPORT`, so they all need to be set.
+        let viewport_count = self.device.viewport_count as usize;
+        let vk_viewports = (0..viewport_count)
+            .map(|_| vk_viewport)
+            .collect::<ArrayVec<_, { wgt::MAX_VIEWPORTS as usize }>>();
+        let vk_scissors = (0..viewport_count)
+            .map(|_| render_area)
+            .collect::<ArrayVec<_, { wgt::MAX_VIEWPORTS as usize }>>();
 
         let raw_pass = self.device.make_render_pass(rp_key).unwrap();
         let raw_framebuffer = self
@@ -522,12 +596,17 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                 .cmd_set_viewport(self.active, 0, &vk_viewports);
             self.device
                 .raw
-                .cmd_set_scissor(self.active, 0, &[render_area]);
+                .cmd_set_scissor(self.active, 0, &vk_scissors);
             self.device.raw.cmd_begin_render_pass(
                 self.active,
                 &vk_info,
                 vk::SubpassContents::INLINE,
             );
+            self.device
+                .set_fragment_shading_rate(self.active, wgt::ShadingRate::Rate1x1);
+            if self.device.depth_bounds_test {
+                self.device.raw.cmd_set_depth_bounds(self.active, 0.0, 1.0);
+            }
         };
 
         self.bind_point = vk::PipelineBindPoint::GRAPHICS;
@@ -567,6 +646,18 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
             )
         };
     }
+
+    unsafe fn set_bind_group_dynamic_offsets(
+        &mut self,
+        layout: &super::PipelineLayout,
+        index: u32,
+        group: &super::BindGroup,
+        dynamic_offsets: &[wgt::DynamicOffset],
+    ) {
+        // Rebinding the whole group is already a single call.
+        unsafe { self.set_bind_group(layout, index, group, dynamic_offsets) };
+    }
+
     unsafe fn set_push_constants(
         &mut self,
         layout: &super::PipelineLayout,
@@ -591,6 +682,9 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
             let vk_label = vk::DebugUtilsLabelEXT::builder().label_name(cstr).build();
             unsafe { ext.cmd_insert_debug_utils_label(self.active, &vk_label) };
         }
+        if let Some(index) = unsafe { self.device.set_checkpoint(self.active, label, false) } {
+            self.checkpoint_labels.push(index);
+        }
     }
     unsafe fn begin_debug_marker(&mut self, group_label: &str) {
         if let Some(ext) = self.device.debug_messenger() {
@@ -598,11 +692,19 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
             let vk_label = vk::DebugUtilsLabelEXT::builder().label_name(cstr).build();
             unsafe { ext.cmd_begin_debug_utils_label(self.active, &vk_label) };
         }
+        if let Some(index) = unsafe { self.device.set_checkpoint(self.active, group_label, false) }
+        {
+            self.checkpoint_groups.push(index);
+            self.checkpoint_labels.push(index);
+        }
     }
     unsafe fn end_debug_marker(&mut self) {
         if let Some(ext) = self.device.debug_messenger() {
             unsafe { ext.cmd_end_debug_utils_label(self.active) };
         }
+        if let Some(index) = self.checkpoint_groups.pop() {
+            unsafe { self.device.set_end_checkpoint(self.active, index) };
+        }
     }
 
     unsafe fn set_render_pipeline(&mut self, pipeline: &super::RenderPipeline) {
@@ -643,6 +745,17 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         };
     }
     unsafe fn set_viewport(&mut self, rect: &crate::Rect<f32>, depth_range: Range<f32>) {
+        unsafe { self.set_viewport_indexed(0, rect, depth_range) };
+    }
+    unsafe fn set_scissor_rect(&mut self, rect: &crate::Rect<u32>) {
+        unsafe { self.set_scissor_rect_indexed(0, rect) };
+    }
+    unsafe fn set_viewport_indexed(
+        &mut self,
+        index: u32,
+        rect: &crate::Rect<f32>,
+        depth_range: Range<f32>,
+    ) {
         let vk_viewports = [vk::Viewport {
             x: rect.x,
             y: if self.device.private_caps.flip_y_requires_shift {
@@ -658,10 +771,10 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         unsafe {
             self.device
                 .raw
-                .cmd_set_viewport(self.active, 0, &vk_viewports)
+                .cmd_set_viewport(self.active, index, &vk_viewports)
         };
     }
-    unsafe fn set_scissor_rect(&mut self, rect: &crate::Rect<u32>) {
+    unsafe fn set_scissor_rect_indexed(&mut self, index: u32, rect: &crate::Rect<u32>) {
         let vk_scissors = [vk::Rect2D {
             offset: vk::Offset2D {
                 x: rect.x as i32,
@@ -675,7 +788,7 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         unsafe {
             self.device
                 .raw
-                .cmd_set_scissor(self.active, 0, &vk_scissors)
+                .cmd_set_scissor(self.active, index, &vk_scissors)
         };
     }
     unsafe fn set_stencil_reference(&mut self, value: u32) {
@@ -690,6 +803,16 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
     unsafe fn set_blend_constants(&mut self, color: &[f32; 4]) {
         unsafe { self.device.raw.cmd_set_blend_constants(self.active, color) };
     }
+    unsafe fn set_shading_rate(&mut self, rate: wgt::ShadingRate) {
+        unsafe { self.device.set_fragment_shading_rate(self.active, rate) };
+    }
+    unsafe fn set_depth_bounds(&mut self, bounds: Range<f32>) {
+        unsafe {
+            self.device
+                .raw
+                .cmd_set_depth_bounds(self.active, bounds.start, bounds.end)
+        };
+    }
 
     unsafe fn draw(
         &mut self,
@@ -865,6 +988,99 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                 .cmd_dispatch_indirect(self.active, buffer.raw, offset)
         }
     }
+
+    unsafe fn build_acceleration_structures(
+        &mut self,
+        descriptors: &[crate::BuildAccelerationStructureDescriptor<super::Api>],
+    ) {
+        let ray_tracing_fn = self
+            .device
+            .extension_fns
+            .acceleration_structure
+            .as_ref()
+            .expect("Feature `RAY_TRACING_ACCELERATION_STRUCTURE` not enabled");
+
+        // The geometries have to outlive the build infos pointing to them.
+        let geometries_and_ranges = descriptors
+            .iter()
+            .map(|desc| unsafe { self.device.map_acceleration_structure_entries(desc.entries) })
+            .collect::<Vec<_>>();
+
+        let vk_infos = descriptors
+            .iter()
+            .zip(geometries_and_ranges.iter())
+            .map(|(desc, &(ref geometries, _))| {
+                let scratch_address = unsafe {
+                    self.device.raw.get_buffer_device_address(
+                        &vk::BufferDeviceAddressInfo::builder().buffer(desc.scratch_buffer.raw),
+                    )
+                } + desc.scratch_buffer_offset;
+                let (ty, mode) = (
+                    match *desc.entries {
+                        crate::AccelerationStructureEntries::Instances(_) => {
+                            vk::AccelerationStructureTypeKHR::TOP_LEVEL
+                        }
+                        _ => vk::AccelerationStructureTypeKHR::BOTTOM_LEVEL,
+                    },
+                    match desc.mode {
+                        crate::AccelerationStructureBuildMode::Build => {
+                            vk::BuildAccelerationStructureModeKHR::BUILD
+                        }
+                        crate::AccelerationStructureBuildMode::Update => {
+                            vk::BuildAccelerationStructureModeKHR::UPDATE
+                        }
+                    },
+                );
+
+                let mut vk_info = vk::AccelerationStructureBuildGeometryInfoKHR::builder()
+                    .ty(ty)
+                    .mode(mode)
+                    .flags(conv::map_acceleration_structure_build_flags(desc.flags))
+                    .geometries(geometries)
+                    .dst_acceleration_structure(desc.destination_acceleration_structure.raw)
+                    .scratch_data(vk::DeviceOrHostAddressKHR {
+                        device_address: scratch_address,
+                    });
+                if let Some(source) = desc.source_acceleration_structure {
+                    vk_info = vk_info.src_acceleration_structure(source.raw);
+                }
+                vk_info.build()
+            })
+            .collect::<Vec<_>>();
+        let vk_ranges = geometries_and_ranges
+            .iter()
+            .map(|&(_, ref ranges)| &ranges[..])
+            .collect::<Vec<_>>();
+
+        unsafe {
+            ray_tracing_fn.cmd_build_acceleration_structures(self.active, &vk_infos, &vk_ranges)
+        };
+    }
+
+    unsafe fn place_acceleration_structure_barrier(
+        &mut self,
+        barrier: crate::AccelerationStructureBarrier,
+    ) {
+        let (src_stage, src_access) =
+            conv::map_acceleration_structure_usage_to_barrier(barrier.usage.start);
+        let (dst_stage, dst_access) =
+            conv::map_acceleration_structure_usage_to_barrier(barrier.usage.end);
+
+        unsafe {
+            self.device.raw.cmd_pipeline_barrier(
+                self.active,
+                src_stage | vk::PipelineStageFlags::TOP_OF_PIPE,
+                dst_stage | vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                vk::DependencyFlags::empty(),
+                &[vk::MemoryBarrier::builder()
+                    .src_access_mask(src_access)
+                    .dst_access_mask(dst_access)
+                    .build()],
+                &[],
+                &[],
+            )
+        };
+    }
 }
 
 #[test]
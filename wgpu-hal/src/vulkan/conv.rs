@@ -73,6 +73,7 @@ impl super::PrivateCapabilities {
                 }
             }
             Tf::Depth16Unorm => F::D16_UNORM,
+            Tf::NV12 => F::G8_B8R8_2PLANE_420_UNORM,
             Tf::Rgb9e5Ufloat => F::E5B9G9R9_UFLOAT_PACK32,
             Tf::Bc1RgbaUnorm => F::BC1_RGBA_UNORM_BLOCK,
             Tf::Bc1RgbaUnormSrgb => F::BC1_RGBA_SRGB_BLOCK,
@@ -152,30 +153,45 @@ impl super::PrivateCapabilities {
     }
 }
 
-pub fn map_vk_surface_formats(sf: vk::SurfaceFormatKHR) -> Option<wgt::TextureFormat> {
+pub fn map_vk_surface_format(format: vk::Format) -> Option<wgt::TextureFormat> {
     use ash::vk::Format as F;
     use wgt::TextureFormat as Tf;
     // List we care about pulled from https://vulkan.gpuinfo.org/listsurfaceformats.php
-    Some(match sf.color_space {
-        vk::ColorSpaceKHR::SRGB_NONLINEAR => match sf.format {
-            F::B8G8R8A8_UNORM => Tf::Bgra8Unorm,
-            F::B8G8R8A8_SRGB => Tf::Bgra8UnormSrgb,
-            F::R8G8B8A8_SNORM => Tf::Rgba8Snorm,
-            F::R8G8B8A8_UNORM => Tf::Rgba8Unorm,
-            F::R8G8B8A8_SRGB => Tf::Rgba8UnormSrgb,
-            _ => return None,
-        },
-        vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT => match sf.format {
-            F::R16G16B16A16_SFLOAT => Tf::Rgba16Float,
-            F::R16G16B16A16_SNORM => Tf::Rgba16Snorm,
-            F::R16G16B16A16_UNORM => Tf::Rgba16Unorm,
-            F::A2B10G10R10_UNORM_PACK32 => Tf::Rgb10a2Unorm,
-            _ => return None,
-        },
+    Some(match format {
+        F::B8G8R8A8_UNORM => Tf::Bgra8Unorm,
+        F::B8G8R8A8_SRGB => Tf::Bgra8UnormSrgb,
+        F::R8G8B8A8_SNORM => Tf::Rgba8Snorm,
+        F::R8G8B8A8_UNORM => Tf::Rgba8Unorm,
+        F::R8G8B8A8_SRGB => Tf::Rgba8UnormSrgb,
+        F::R16G16B16A16_SFLOAT => Tf::Rgba16Float,
+        F::R16G16B16A16_SNORM => Tf::Rgba16Snorm,
+        F::R16G16B16A16_UNORM => Tf::Rgba16Unorm,
+        F::A2B10G10R10_UNORM_PACK32 => Tf::Rgb10a2Unorm,
+        _ => return None,
+    })
+}
+
+pub fn map_vk_color_space(color_space: vk::ColorSpaceKHR) -> Option<wgt::SurfaceColorSpace> {
+    use wgt::SurfaceColorSpace as Scs;
+    Some(match color_space {
+        vk::ColorSpaceKHR::SRGB_NONLINEAR => Scs::Srgb,
+        vk::ColorSpaceKHR::DISPLAY_P3_NONLINEAR_EXT => Scs::DisplayP3,
+        vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT => Scs::ExtendedSrgbLinear,
+        vk::ColorSpaceKHR::HDR10_ST2084_EXT => Scs::Hdr10,
         _ => return None,
     })
 }
 
+pub fn map_color_space(color_space: wgt::SurfaceColorSpace) -> vk::ColorSpaceKHR {
+    use wgt::SurfaceColorSpace as Scs;
+    match color_space {
+        Scs::Srgb => vk::ColorSpaceKHR::SRGB_NONLINEAR,
+        Scs::DisplayP3 => vk::ColorSpaceKHR::DISPLAY_P3_NONLINEAR_EXT,
+        Scs::ExtendedSrgbLinear => vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT,
+        Scs::Hdr10 => vk::ColorSpaceKHR::HDR10_ST2084_EXT,
+    }
+}
+
 impl crate::Attachment<'_, super::Api> {
     pub(super) fn make_attachment_key(
         &self,
@@ -220,7 +236,7 @@ pub fn derive_image_layout(
     format: wgt::TextureFormat,
 ) -> vk::ImageLayout {
     // Note: depth textures are always sampled with RODS layout
-    let is_color = crate::FormatAspects::from(format).contains(crate::FormatAspects::COLOR);
+    let is_color = format.has_color_aspect();
     match usage {
         crate::TextureUses::UNINITIALIZED => vk::ImageLayout::UNDEFINED,
         crate::TextureUses::COPY_SRC => vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
@@ -411,6 +427,12 @@ pub fn map_aspects(aspects: crate::FormatAspects) -> vk::ImageAspectFlags {
     if aspects.contains(crate::FormatAspects::STENCIL) {
         flags |= vk::ImageAspectFlags::STENCIL;
     }
+    if aspects.contains(crate::FormatAspects::PLANE_0) {
+        flags |= vk::ImageAspectFlags::PLANE_0;
+    }
+    if aspects.contains(crate::FormatAspects::PLANE_1) {
+        flags |= vk::ImageAspectFlags::PLANE_1;
+    }
     flags
 }
 
@@ -508,9 +530,37 @@ pub fn map_buffer_usage(usage: crate::BufferUses) -> vk::BufferUsageFlags {
     if usage.contains(crate::BufferUses::INDIRECT) {
         flags |= vk::BufferUsageFlags::INDIRECT_BUFFER;
     }
+    if usage.contains(crate::BufferUses::ACCELERATION_STRUCTURE_SCRATCH) {
+        flags |= vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS | vk::BufferUsageFlags::STORAGE_BUFFER;
+    }
+    if usage.intersects(
+        crate::BufferUses::BOTTOM_LEVEL_ACCELERATION_STRUCTURE_INPUT
+            | crate::BufferUses::TOP_LEVEL_ACCELERATION_STRUCTURE_INPUT,
+    ) {
+        flags |= vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS
+            | vk::BufferUsageFlags::ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY_KHR;
+    }
     flags
 }
 
+/// Returns the pipeline stages that barriers can use on queues with `flags`.
+pub fn map_queue_flags_to_stages(flags: vk::QueueFlags) -> vk::PipelineStageFlags {
+    if flags.contains(vk::QueueFlags::GRAPHICS) {
+        return vk::PipelineStageFlags::from_raw(!0);
+    }
+    let mut stages = vk::PipelineStageFlags::TOP_OF_PIPE
+        | vk::PipelineStageFlags::BOTTOM_OF_PIPE
+        | vk::PipelineStageFlags::TRANSFER
+        | vk::PipelineStageFlags::HOST
+        | vk::PipelineStageFlags::ALL_COMMANDS;
+    if flags.contains(vk::QueueFlags::COMPUTE) {
+        stages |= vk::PipelineStageFlags::DRAW_INDIRECT
+            | vk::PipelineStageFlags::COMPUTE_SHADER
+            | vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR;
+    }
+    stages
+}
+
 pub fn map_buffer_usage_to_barrier(
     usage: crate::BufferUses,
 ) -> (vk::PipelineStageFlags, vk::AccessFlags) {
@@ -560,10 +610,93 @@ pub fn map_buffer_usage_to_barrier(
         stages |= vk::PipelineStageFlags::DRAW_INDIRECT;
         access |= vk::AccessFlags::INDIRECT_COMMAND_READ;
     }
+    if usage.contains(crate::BufferUses::ACCELERATION_STRUCTURE_SCRATCH) {
+        stages |= vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR;
+        access |= vk::AccessFlags::ACCELERATION_STRUCTURE_READ_KHR
+            | vk::AccessFlags::ACCELERATION_STRUCTURE_WRITE_KHR;
+    }
+    if usage.intersects(
+        crate::BufferUses::BOTTOM_LEVEL_ACCELERATION_STRUCTURE_INPUT
+            | crate::BufferUses::TOP_LEVEL_ACCELERATION_STRUCTURE_INPUT,
+    ) {
+        stages |= vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR;
+        access |= vk::AccessFlags::SHADER_READ;
+    }
+
+    (stages, access)
+}
+
+pub fn map_acceleration_structure_usage_to_barrier(
+    usage: crate::AccelerationStructureUses,
+) -> (vk::PipelineStageFlags, vk::AccessFlags) {
+    let mut stages = vk::PipelineStageFlags::empty();
+    let mut access = vk::AccessFlags::empty();
+
+    if usage.contains(crate::AccelerationStructureUses::BUILD_INPUT) {
+        stages |= vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR;
+        access |= vk::AccessFlags::ACCELERATION_STRUCTURE_READ_KHR;
+    }
+    if usage.contains(crate::AccelerationStructureUses::BUILD_OUTPUT) {
+        stages |= vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR;
+        access |= vk::AccessFlags::ACCELERATION_STRUCTURE_WRITE_KHR;
+    }
+    if usage.contains(crate::AccelerationStructureUses::SHADER_INPUT) {
+        stages |= vk::PipelineStageFlags::VERTEX_SHADER
+            | vk::PipelineStageFlags::FRAGMENT_SHADER
+            | vk::PipelineStageFlags::COMPUTE_SHADER;
+        access |= vk::AccessFlags::ACCELERATION_STRUCTURE_READ_KHR;
+    }
 
     (stages, access)
 }
 
+pub fn map_acceleration_structure_format(
+    format: crate::AccelerationStructureFormat,
+) -> vk::AccelerationStructureTypeKHR {
+    match format {
+        crate::AccelerationStructureFormat::TopLevel => vk::AccelerationStructureTypeKHR::TOP_LEVEL,
+        crate::AccelerationStructureFormat::BottomLevel => {
+            vk::AccelerationStructureTypeKHR::BOTTOM_LEVEL
+        }
+    }
+}
+
+pub fn map_acceleration_structure_build_flags(
+    flags: crate::AccelerationStructureBuildFlags,
+) -> vk::BuildAccelerationStructureFlagsKHR {
+    use crate::AccelerationStructureBuildFlags as Abf;
+    let mut vk_flags = vk::BuildAccelerationStructureFlagsKHR::empty();
+    if flags.contains(Abf::PREFER_FAST_TRACE) {
+        vk_flags |= vk::BuildAccelerationStructureFlagsKHR::PREFER_FAST_TRACE;
+    }
+    if flags.contains(Abf::PREFER_FAST_BUILD) {
+        vk_flags |= vk::BuildAccelerationStructureFlagsKHR::PREFER_FAST_BUILD;
+    }
+    if flags.contains(Abf::ALLOW_UPDATE) {
+        vk_flags |= vk::BuildAccelerationStructureFlagsKHR::ALLOW_UPDATE;
+    }
+    if flags.contains(Abf::ALLOW_COMPACTION) {
+        vk_flags |= vk::BuildAccelerationStructureFlagsKHR::ALLOW_COMPACTION;
+    }
+    if flags.contains(Abf::LOW_MEMORY) {
+        vk_flags |= vk::BuildAccelerationStructureFlagsKHR::LOW_MEMORY;
+    }
+    vk_flags
+}
+
+pub fn map_acceleration_structure_geometry_flags(
+    flags: crate::AccelerationStructureGeometryFlags,
+) -> vk::GeometryFlagsKHR {
+    let mut vk_flags = vk::GeometryFlagsKHR::empty();
+    if flags.contains(crate::AccelerationStructureGeometryFlags::OPAQUE) {
+        vk_flags |= vk::GeometryFlagsKHR::OPAQUE;
+    }
+    if flags.contains(crate::AccelerationStructureGeometryFlags::NO_DUPLICATE_ANY_HIT_INVOCATION) {
+        vk_flags |= vk::GeometryFlagsKHR::NO_DUPLICATE_ANY_HIT_INVOCATION;
+    }
+    vk_flags
+}
+
 pub fn map_view_dimension(dim: wgt::TextureViewDimension) -> vk::ImageViewType {
     match dim {
         wgt::TextureViewDimension::D1 => vk::ImageViewType::TYPE_1D,
@@ -575,6 +708,26 @@ pub fn map_view_dimension(dim: wgt::TextureViewDimension) -> vk::ImageViewType {
     }
 }
 
+pub fn map_swizzle(swizzle: wgt::TextureSwizzle) -> vk::ComponentMapping {
+    fn map_component(component: wgt::SwizzleComponent) -> vk::ComponentSwizzle {
+        match component {
+            wgt::SwizzleComponent::Zero => vk::ComponentSwizzle::ZERO,
+            wgt::SwizzleComponent::One => vk::ComponentSwizzle::ONE,
+            wgt::SwizzleComponent::R => vk::ComponentSwizzle::R,
+            wgt::SwizzleComponent::G => vk::ComponentSwizzle::G,
+            wgt::SwizzleComponent::B => vk::ComponentSwizzle::B,
+            wgt::SwizzleComponent::A => vk::ComponentSwizzle::A,
+        }
+    }
+
+    vk::ComponentMapping {
+        r: map_component(swizzle.r),
+        g: map_component(swizzle.g),
+        b: map_component(swizzle.b),
+        a: map_component(swizzle.a),
+    }
+}
+
 pub fn map_copy_extent(extent: &crate::CopyExtent) -> vk::Extent3D {
     vk::Extent3D {
         width: extent.width,
@@ -599,7 +752,8 @@ pub fn map_subresource_range(
 }
 
 // Special subresource range mapping for dealing with barriers
-// so that we account for the "hidden" depth aspect in emulated Stencil8.
+// so that we account for the "hidden" depth aspect in emulated Stencil8,
+// and transition non-disjoint multi-planar images as a whole.
 pub(super) fn map_subresource_range_combined_aspect(
     range: &wgt::ImageSubresourceRange,
     format: wgt::TextureFormat,
@@ -609,6 +763,9 @@ pub(super) fn map_subresource_range_combined_aspect(
     if !private_caps.texture_s8 && format == wgt::TextureFormat::Stencil8 {
         range.aspect_mask |= vk::ImageAspectFlags::DEPTH;
     }
+    if format.is_multi_planar_format() {
+        range.aspect_mask = vk::ImageAspectFlags::COLOR;
+    }
     range
 }
 
@@ -660,6 +817,7 @@ pub fn map_border_color(border_color: wgt::SamplerBorderColor) -> vk::BorderColo
         }
         wgt::SamplerBorderColor::OpaqueBlack => vk::BorderColor::FLOAT_OPAQUE_BLACK,
         wgt::SamplerBorderColor::OpaqueWhite => vk::BorderColor::FLOAT_OPAQUE_WHITE,
+        wgt::SamplerBorderColor::Custom(_) => vk::BorderColor::FLOAT_CUSTOM_EXT,
     }
 }
 
@@ -691,6 +849,20 @@ pub fn map_shader_stage(stage: wgt::ShaderStages) -> vk::ShaderStageFlags {
     flags
 }
 
+pub fn map_vk_shader_stages(flags: vk::ShaderStageFlags) -> wgt::ShaderStages {
+    let mut stages = wgt::ShaderStages::empty();
+    if flags.contains(vk::ShaderStageFlags::VERTEX) {
+        stages |= wgt::ShaderStages::VERTEX;
+    }
+    if flags.contains(vk::ShaderStageFlags::FRAGMENT) {
+        stages |= wgt::ShaderStages::FRAGMENT;
+    }
+    if flags.contains(vk::ShaderStageFlags::COMPUTE) {
+        stages |= wgt::ShaderStages::COMPUTE;
+    }
+    stages
+}
+
 pub fn map_binding_type(ty: wgt::BindingType) -> vk::DescriptorType {
     match ty {
         wgt::BindingType::Buffer {
@@ -710,6 +882,7 @@ pub fn map_binding_type(ty: wgt::BindingType) -> vk::DescriptorType {
         wgt::BindingType::Sampler { .. } => vk::DescriptorType::SAMPLER,
         wgt::BindingType::Texture { .. } => vk::DescriptorType::SAMPLED_IMAGE,
         wgt::BindingType::StorageTexture { .. } => vk::DescriptorType::STORAGE_IMAGE,
+        wgt::BindingType::AccelerationStructure => vk::DescriptorType::ACCELERATION_STRUCTURE_KHR,
     }
 }
 
@@ -792,6 +965,10 @@ fn map_blend_factor(factor: wgt::BlendFactor) -> vk::BlendFactor {
         Bf::SrcAlphaSaturated => vk::BlendFactor::SRC_ALPHA_SATURATE,
         Bf::Constant => vk::BlendFactor::CONSTANT_COLOR,
         Bf::OneMinusConstant => vk::BlendFactor::ONE_MINUS_CONSTANT_COLOR,
+        Bf::Src1 => vk::BlendFactor::SRC1_COLOR,
+        Bf::OneMinusSrc1 => vk::BlendFactor::ONE_MINUS_SRC1_COLOR,
+        Bf::Src1Alpha => vk::BlendFactor::SRC1_ALPHA,
+        Bf::OneMinusSrc1Alpha => vk::BlendFactor::ONE_MINUS_SRC1_ALPHA,
     }
 }
 
@@ -13,6 +13,16 @@ use std::{
     sync::Arc,
 };
 
+/// Closes a file descriptor passed to wgpu to import memory, when importing fails.
+fn close_fd(fd: i32) {
+    #[cfg(unix)]
+    unsafe {
+        libc::close(fd)
+    };
+    #[cfg(not(unix))]
+    let _ = fd;
+}
+
 impl super::DeviceShared {
     pub(super) unsafe fn set_object_name(
         &self,
@@ -62,6 +72,139 @@ impl super::DeviceShared {
         };
     }
 
+    /// Checks the header of pipeline cache data, as laid out by
+    /// `VK_PIPELINE_CACHE_HEADER_VERSION_ONE`, against this device.
+    fn is_pipeline_cache_compatible(&self, data: &[u8]) -> bool {
+        const HEADER_SIZE: usize = 16 + vk::UUID_SIZE;
+        if data.len() < HEADER_SIZE {
+            return false;
+        }
+        let read_u32 = |offset: usize| {
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(&data[offset..offset + 4]);
+            u32::from_ne_bytes(bytes)
+        };
+        read_u32(0) as usize >= HEADER_SIZE
+            && read_u32(4) == vk::PipelineCacheHeaderVersion::ONE.as_raw() as u32
+            && read_u32(8) == self.vendor_id
+            && read_u32(12) == self.device_id
+            && data[16..HEADER_SIZE] == self.pipeline_cache_uuid
+    }
+
+    /// Returns the flags every pipeline is created with.
+    ///
+    /// Compiler statistics are captured when `VK_KHR_pipeline_executable_properties`
+    /// is enabled, see [`super::Device::render_pipeline_executables`].
+    fn pipeline_create_flags(&self) -> vk::PipelineCreateFlags {
+        if self.extension_fns.pipeline_executable_properties.is_some() {
+            vk::PipelineCreateFlags::CAPTURE_STATISTICS_KHR
+                | vk::PipelineCreateFlags::CAPTURE_INTERNAL_REPRESENTATIONS_KHR
+        } else {
+            vk::PipelineCreateFlags::empty()
+        }
+    }
+
+    /// Record a diagnostic checkpoint for a debug label, if
+    /// `VK_NV_device_diagnostic_checkpoints` or `VK_AMD_buffer_marker` is enabled.
+    ///
+    /// Returns the index of the interned label.
+    pub(super) unsafe fn set_checkpoint(
+        &self,
+        raw: vk::CommandBuffer,
+        label: &str,
+        is_end: bool,
+    ) -> Option<usize> {
+        if self.extension_fns.diagnostic_checkpoints.is_none() && self.buffer_markers.is_none() {
+            return None;
+        }
+        let index = self.checkpoint_labels.lock().intern(label);
+        unsafe { self.set_checkpoint_marker(raw, index, is_end) };
+        Some(index)
+    }
+
+    /// Release the labels of checkpoints recorded in command buffers that
+    /// have retired, clearing `indices`.
+    pub(super) fn release_checkpoints(&self, indices: &mut Vec<usize>) {
+        if !indices.is_empty() {
+            self.checkpoint_labels.lock().release(indices.drain(..));
+        }
+    }
+
+    /// Record a diagnostic checkpoint marking the end of the debug group
+    /// whose label was interned at `index`.
+    pub(super) unsafe fn set_end_checkpoint(&self, raw: vk::CommandBuffer, index: usize) {
+        unsafe { self.set_checkpoint_marker(raw, index, true) };
+    }
+
+    unsafe fn set_checkpoint_marker(&self, raw: vk::CommandBuffer, index: usize, is_end: bool) {
+        let marker = super::CheckpointLabels::marker(index, is_end);
+        if let Some(ref extension) = self.extension_fns.diagnostic_checkpoints {
+            unsafe { extension.cmd_set_checkpoint(raw, marker as *const _) };
+        }
+        if let (Some(extension), Some(markers)) = (
+            self.extension_fns.buffer_marker.as_ref(),
+            self.buffer_markers.as_ref(),
+        ) {
+            let stages = [
+                vk::PipelineStageFlags::TOP_OF_PIPE,
+                vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+            ];
+            for (offset, stage) in stages.into_iter().enumerate() {
+                unsafe {
+                    (extension.cmd_write_buffer_marker_amd)(
+                        raw,
+                        stage,
+                        markers.raw,
+                        (offset * 4) as vk::DeviceSize,
+                        marker as u32,
+                    )
+                };
+            }
+        }
+    }
+
+    /// Log the last diagnostic checkpoints reached by the queue.
+    ///
+    /// This is meant to be called after the device is lost, to point at the
+    /// passes and debug groups that were executing at the time.
+    pub(super) unsafe fn report_checkpoints(&self) {
+        let labels = self.checkpoint_labels.lock();
+        let describe = |marker: usize| {
+            labels
+                .describe(marker)
+                .unwrap_or_else(|| "<unknown>".to_string())
+        };
+
+        if let Some(ref extension) = self.extension_fns.diagnostic_checkpoints {
+            let count = unsafe { extension.get_queue_checkpoint_data_len(self.raw_queue) };
+            let mut checkpoints = vec![vk::CheckpointDataNV::default(); count];
+            unsafe { extension.get_queue_checkpoint_data(self.raw_queue, &mut checkpoints) };
+
+            if checkpoints.is_empty() {
+                log::error!("Device lost, no diagnostic checkpoints were reached");
+            } else {
+                log::error!("Device lost, last diagnostic checkpoints reached:");
+                for checkpoint in checkpoints.iter() {
+                    let description = describe(checkpoint.p_checkpoint_marker as usize);
+                    log::error!("\t{:?}: {}", checkpoint.stage, description);
+                }
+            }
+        }
+
+        if let Some(ref markers) = self.buffer_markers {
+            let [reached, completed] = unsafe { ptr::read_volatile(markers.mapped.as_ptr()) };
+            if reached == 0 {
+                log::error!("Device lost, no buffer markers were written");
+            } else {
+                log::error!("Device lost, last buffer markers written:");
+                log::error!("\treached: {}", describe(reached as usize));
+                if completed != 0 {
+                    log::error!("\tcompleted: {}", describe(completed as usize));
+                }
+            }
+        }
+    }
+
     pub fn make_render_pass(
         &self,
         key: super::RenderPassKey,
@@ -292,6 +435,100 @@ impl super::DeviceShared {
         }))
     }
 
+    unsafe fn buffer_device_address(&self, buffer: &super::Buffer) -> vk::DeviceAddress {
+        let info = vk::BufferDeviceAddressInfo::builder().buffer(buffer.raw);
+        unsafe { self.raw.get_buffer_device_address(&info) }
+    }
+
+    /// Maps the geometry of an acceleration structure to Vulkan, returning the
+    /// geometries with one build range for each.
+    ///
+    /// Missing buffers are given a null address, which is only valid for size queries.
+    pub(super) unsafe fn map_acceleration_structure_entries(
+        &self,
+        entries: &crate::AccelerationStructureEntries<super::Api>,
+    ) -> (
+        Vec<vk::AccelerationStructureGeometryKHR>,
+        Vec<vk::AccelerationStructureBuildRangeInfoKHR>,
+    ) {
+        let address = |buffer: Option<&super::Buffer>| vk::DeviceOrHostAddressConstKHR {
+            device_address: buffer
+                .map_or(0, |buffer| unsafe { self.buffer_device_address(buffer) }),
+        };
+
+        match *entries {
+            crate::AccelerationStructureEntries::Instances(ref instances) => {
+                let data = vk::AccelerationStructureGeometryInstancesDataKHR::builder()
+                    .array_of_pointers(false)
+                    .data(address(instances.buffer));
+                let geometry = vk::AccelerationStructureGeometryKHR::builder()
+                    .geometry_type(vk::GeometryTypeKHR::INSTANCES)
+                    .geometry(vk::AccelerationStructureGeometryDataKHR { instances: *data })
+                    .build();
+                let range = vk::AccelerationStructureBuildRangeInfoKHR::builder()
+                    .primitive_count(instances.count)
+                    .primitive_offset(instances.offset)
+                    .build();
+                (vec![geometry], vec![range])
+            }
+            crate::AccelerationStructureEntries::Triangles(triangles) => triangles
+                .iter()
+                .map(|triangles| {
+                    let mut data = vk::AccelerationStructureGeometryTrianglesDataKHR::builder()
+                        .vertex_format(conv::map_vertex_format(triangles.vertex_format))
+                        .vertex_data(address(triangles.vertex_buffer))
+                        .vertex_stride(triangles.vertex_stride)
+                        .max_vertex(
+                            triangles.first_vertex + triangles.vertex_count.saturating_sub(1),
+                        );
+                    let mut range = vk::AccelerationStructureBuildRangeInfoKHR::builder()
+                        .first_vertex(triangles.first_vertex);
+                    if let Some(ref indices) = triangles.indices {
+                        data = data
+                            .index_type(conv::map_index_format(indices.format))
+                            .index_data(address(indices.buffer));
+                        range = range
+                            .primitive_count(indices.count / 3)
+                            .primitive_offset(indices.offset);
+                    } else {
+                        data = data.index_type(vk::IndexType::NONE_KHR);
+                        range = range.primitive_count(triangles.vertex_count / 3);
+                    }
+                    if let Some(ref transform) = triangles.transform {
+                        data = data.transform_data(address(Some(transform.buffer)));
+                        range = range.transform_offset(transform.offset);
+                    }
+                    let geometry = vk::AccelerationStructureGeometryKHR::builder()
+                        .geometry_type(vk::GeometryTypeKHR::TRIANGLES)
+                        .geometry(vk::AccelerationStructureGeometryDataKHR { triangles: *data })
+                        .flags(conv::map_acceleration_structure_geometry_flags(
+                            triangles.flags,
+                        ))
+                        .build();
+                    (geometry, range.build())
+                })
+                .unzip(),
+            crate::AccelerationStructureEntries::AABBs(aabbs) => aabbs
+                .iter()
+                .map(|aabbs| {
+                    let data = vk::AccelerationStructureGeometryAabbsDataKHR::builder()
+                        .data(address(aabbs.buffer))
+                        .stride(aabbs.stride);
+                    let geometry = vk::AccelerationStructureGeometryKHR::builder()
+                        .geometry_type(vk::GeometryTypeKHR::AABBS)
+                        .geometry(vk::AccelerationStructureGeometryDataKHR { aabbs: *data })
+                        .flags(conv::map_acceleration_structure_geometry_flags(aabbs.flags))
+                        .build();
+                    let range = vk::AccelerationStructureBuildRangeInfoKHR::builder()
+                        .primitive_count(aabbs.count)
+                        .primitive_offset(aabbs.offset)
+                        .build();
+                    (geometry, range)
+                })
+                .unzip(),
+        }
+    }
+
     unsafe fn free_resources(&self) {
         for &raw in self.render_passes.lock().values() {
             unsafe { self.raw.destroy_render_pass(raw, None) };
@@ -299,12 +536,86 @@ impl super::DeviceShared {
         for &raw in self.framebuffers.lock().values() {
             unsafe { self.raw.destroy_framebuffer(raw, None) };
         }
+        unsafe { self.raw.destroy_pipeline_cache(self.pipeline_cache, None) };
+        if let Some(ref markers) = self.buffer_markers {
+            unsafe { markers.destroy(&self.raw) };
+        }
         if self.handle_is_owned {
             unsafe { self.raw.destroy_device(None) };
         }
     }
 }
 
+impl super::BufferMarkers {
+    /// Creates the marker buffer in host-visible, host-coherent memory.
+    pub(super) unsafe fn new(
+        device: &ash::Device,
+        memory_types: &[vk::MemoryType],
+    ) -> Result<Self, crate::DeviceError> {
+        let size = std::mem::size_of::<[u32; 2]>() as vk::DeviceSize;
+        let info = vk::BufferCreateInfo::builder()
+            .size(size)
+            .usage(vk::BufferUsageFlags::TRANSFER_DST)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+        let raw = unsafe { device.create_buffer(&info, None)? };
+
+        let req = unsafe { device.get_buffer_memory_requirements(raw) };
+        let flags = vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT;
+        let memory_type = memory_types.iter().enumerate().position(|(i, ty)| {
+            req.memory_type_bits & (1 << i) != 0 && ty.property_flags.contains(flags)
+        });
+        let memory_type = match memory_type {
+            Some(memory_type) => memory_type,
+            None => {
+                unsafe { device.destroy_buffer(raw, None) };
+                return Err(crate::DeviceError::ResourceCreationFailed);
+            }
+        };
+
+        let info = vk::MemoryAllocateInfo::builder()
+            .allocation_size(req.size)
+            .memory_type_index(memory_type as u32);
+        let memory = match unsafe { device.allocate_memory(&info, None) } {
+            Ok(memory) => memory,
+            Err(err) => {
+                unsafe { device.destroy_buffer(raw, None) };
+                return Err(err.into());
+            }
+        };
+        let mapped = unsafe {
+            device
+                .bind_buffer_memory(raw, memory, 0)
+                .and_then(|()| device.map_memory(memory, 0, size, vk::MemoryMapFlags::empty()))
+        };
+        match mapped {
+            Ok(mapped) => {
+                // A successful mapping is never null.
+                let mapped = ptr::NonNull::new(mapped.cast::<[u32; 2]>()).unwrap();
+                unsafe { ptr::write_volatile(mapped.as_ptr(), [0; 2]) };
+                Ok(Self {
+                    raw,
+                    memory,
+                    mapped,
+                })
+            }
+            Err(err) => {
+                unsafe {
+                    device.destroy_buffer(raw, None);
+                    device.free_memory(memory, None);
+                }
+                Err(err.into())
+            }
+        }
+    }
+
+    unsafe fn destroy(&self, device: &ash::Device) {
+        unsafe {
+            device.destroy_buffer(self.raw, None);
+            device.free_memory(self.memory, None);
+        }
+    }
+}
+
 impl gpu_alloc::MemoryDevice<vk::DeviceMemory> for super::DeviceShared {
     unsafe fn allocate_memory(
         &self,
@@ -422,6 +733,10 @@ impl
                 vk::DescriptorType::STORAGE_BUFFER_DYNAMIC,
                 descriptor_count.storage_buffer_dynamic,
             ),
+            (
+                vk::DescriptorType::ACCELERATION_STRUCTURE_KHR,
+                descriptor_count.acceleration_structure,
+            ),
         ];
 
         let filtered_counts = unfiltered_counts
@@ -537,6 +852,15 @@ struct CompiledStage {
 }
 
 impl super::Device {
+    /// Returns the memory types that resources with `memory_flags` can be allocated from.
+    fn allowed_memory_types(&self, memory_flags: crate::MemoryFlags) -> u32 {
+        if memory_flags.contains(crate::MemoryFlags::PROTECTED) {
+            self.protected_ash_memory_types
+        } else {
+            self.valid_ash_memory_types
+        }
+    }
+
     pub(super) unsafe fn create_swapchain(
         &self,
         surface: &mut super::Surface,
@@ -546,21 +870,25 @@ impl super::Device {
         profiling::scope!("Device::create_swapchain");
         let functor = khr::Swapchain::new(&surface.instance.raw, &self.shared.raw);
 
-        let old_swapchain = match provided_old_swapchain {
-            Some(osc) => osc.raw,
-            None => vk::SwapchainKHR::null(),
+        // The previous swapchain is retired rather than destroyed, so that the
+        // presentation engine can keep showing its images until ours are ready.
+        let (old_swapchain, mut retired) = match provided_old_swapchain {
+            Some(osc) => (osc.raw, osc.retired),
+            None => (vk::SwapchainKHR::null(), Vec::new()),
         };
 
-        let color_space = if config.format == wgt::TextureFormat::Rgba16Float {
-            // Enable wide color gamut mode
-            // Vulkan swapchain for Android only supports DISPLAY_P3_NONLINEAR_EXT and EXTENDED_SRGB_LINEAR_EXT
-            vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT
-        } else {
-            vk::ColorSpaceKHR::SRGB_NONLINEAR
-        };
+        let color_space = conv::map_color_space(config.color_space);
 
         let original_format = self.shared.private_caps.map_texture_format(config.format);
         let mut raw_flags = vk::SwapchainCreateFlagsKHR::empty();
+        if config.protected {
+            if !surface.supports_protected(self.shared.physical_device) {
+                return Err(crate::SurfaceError::Other(
+                    "surface does not support protected swapchains",
+                ));
+            }
+            raw_flags |= vk::SwapchainCreateFlagsKHR::PROTECTED;
+        }
         let mut raw_view_formats: Vec<vk::Format> = vec![];
         let mut wgt_view_formats = vec![];
         if !config.view_formats.is_empty() {
@@ -606,21 +934,25 @@ impl super::Device {
             unsafe { functor.create_swapchain(&info, None) }
         };
 
-        // doing this before bailing out with error
         if old_swapchain != vk::SwapchainKHR::null() {
-            unsafe { functor.destroy_swapchain(old_swapchain, None) }
+            retired.push(old_swapchain);
         }
 
         let raw = match result {
             Ok(swapchain) => swapchain,
             Err(error) => {
+                // The old swapchain is retired even if creation failed, and with
+                // no new swapchain there is nothing left to wait for.
+                for raw in retired {
+                    unsafe { functor.destroy_swapchain(raw, None) };
+                }
                 return Err(match error {
                     vk::Result::ERROR_SURFACE_LOST_KHR => crate::SurfaceError::Lost,
                     vk::Result::ERROR_NATIVE_WINDOW_IN_USE_KHR => {
                         crate::SurfaceError::Other("Native window is in use")
                     }
                     other => crate::DeviceError::from(other).into(),
-                })
+                });
             }
         };
 
@@ -640,9 +972,420 @@ impl super::Device {
             images,
             config: config.clone(),
             view_formats: wgt_view_formats,
+            retired,
+            first_presented_index: None,
+        })
+    }
+
+    /// Creates the image for `desc`, without any memory bound to it.
+    ///
+    /// Returns the image along with its creation flags and view formats.
+    unsafe fn create_image(
+        &self,
+        desc: &crate::TextureDescriptor,
+        external: Option<(
+            vk::ExternalMemoryHandleTypeFlags,
+            Option<&super::DrmFormatModifier>,
+        )>,
+    ) -> Result<(vk::Image, vk::ImageCreateFlags, Vec<wgt::TextureFormat>), crate::DeviceError>
+    {
+        let copy_size = desc.copy_extent();
+
+        let mut raw_flags = vk::ImageCreateFlags::empty();
+        if desc.is_cube_compatible() {
+            raw_flags |= vk::ImageCreateFlags::CUBE_COMPATIBLE;
+        }
+        if desc.memory_flags.contains(crate::MemoryFlags::PROTECTED) {
+            raw_flags |= vk::ImageCreateFlags::PROTECTED;
+        }
+        // Slices of 3D color targets are rendered to through 2D views.
+        if desc.dimension == wgt::TextureDimension::D3
+            && desc.usage.contains(crate::TextureUses::COLOR_TARGET)
+        {
+            raw_flags |= vk::ImageCreateFlags::TYPE_2D_ARRAY_COMPATIBLE;
+        }
+        // Planes are viewed with their own single-plane formats.
+        if desc.format.is_multi_planar_format() {
+            raw_flags |= vk::ImageCreateFlags::MUTABLE_FORMAT;
+        }
+
+        let original_format = self.shared.private_caps.map_texture_format(desc.format);
+        let mut vk_view_formats = vec![];
+        let mut wgt_view_formats = vec![];
+        if !desc.view_formats.is_empty() {
+            raw_flags |= vk::ImageCreateFlags::MUTABLE_FORMAT;
+            wgt_view_formats = desc.view_formats.clone();
+            wgt_view_formats.push(desc.format);
+
+            if self.shared_instance().driver_api_version >= vk::API_VERSION_1_2
+                || self
+                    .enabled_device_extensions()
+                    .contains(&vk::KhrImageFormatListFn::name())
+            {
+                vk_view_formats = desc
+                    .view_formats
+                    .iter()
+                    .map(|f| self.shared.private_caps.map_texture_format(*f))
+                    .collect();
+                vk_view_formats.push(original_format)
+            }
+        }
+
+        let mut vk_info = vk::ImageCreateInfo::builder()
+            .flags(raw_flags)
+            .image_type(conv::map_texture_dimension(desc.dimension))
+            .format(original_format)
+            .extent(conv::map_copy_extent(&copy_size))
+            .mip_levels(desc.mip_level_count)
+            .array_layers(desc.array_layer_count())
+            .samples(vk::SampleCountFlags::from_raw(desc.sample_count))
+            .tiling(match external {
+                Some((_, Some(_))) => vk::ImageTiling::DRM_FORMAT_MODIFIER_EXT,
+                _ => vk::ImageTiling::OPTIMAL,
+            })
+            .usage(conv::map_texture_usage(desc.usage))
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(vk::ImageLayout::UNDEFINED);
+        if !self.shared.concurrent_queue_families.is_empty() {
+            vk_info = vk_info
+                .sharing_mode(vk::SharingMode::CONCURRENT)
+                .queue_family_indices(&self.shared.concurrent_queue_families);
+        }
+
+        let mut format_list_info = vk::ImageFormatListCreateInfo::builder();
+        if !vk_view_formats.is_empty() {
+            format_list_info = format_list_info.view_formats(&vk_view_formats);
+            vk_info = vk_info.push_next(&mut format_list_info);
+        }
+
+        let mut external_info = vk::ExternalMemoryImageCreateInfo::builder();
+        let mut modifier_info = vk::ImageDrmFormatModifierExplicitCreateInfoEXT::builder();
+        if let Some((handle_type, modifier)) = external {
+            external_info = external_info.handle_types(handle_type);
+            vk_info = vk_info.push_next(&mut external_info);
+            if let Some(modifier) = modifier {
+                modifier_info = modifier_info
+                    .drm_format_modifier(modifier.modifier)
+                    .plane_layouts(&modifier.plane_layouts);
+                vk_info = vk_info.push_next(&mut modifier_info);
+            }
+        }
+
+        let raw = unsafe { self.shared.raw.create_image(&vk_info, None)? };
+        Ok((raw, raw_flags, wgt_view_formats))
+    }
+
+    fn acceleration_structure_fn(&self) -> &khr::AccelerationStructure {
+        self.shared
+            .extension_fns
+            .acceleration_structure
+            .as_ref()
+            .expect("Feature `RAY_TRACING_ACCELERATION_STRUCTURE` not enabled")
+    }
+
+    fn external_memory_fd(&self) -> Result<&khr::ExternalMemoryFd, crate::DeviceError> {
+        self.shared
+            .extension_fns
+            .external_memory_fd
+            .as_ref()
+            .ok_or(crate::DeviceError::ResourceCreationFailed)
+    }
+
+    fn external_semaphore_fd(&self) -> Result<&khr::ExternalSemaphoreFd, crate::DeviceError> {
+        if !self.shared.private_caps.external_timeline_semaphores {
+            return Err(crate::DeviceError::ResourceCreationFailed);
+        }
+        self.shared
+            .extension_fns
+            .external_semaphore_fd
+            .as_ref()
+            .ok_or(crate::DeviceError::ResourceCreationFailed)
+    }
+
+    /// Exports the timeline semaphore behind `fence` as a new opaque file descriptor,
+    /// owned by the caller.
+    ///
+    /// Other APIs, like CUDA, can import it to wait on the fence values passed to
+    /// [`crate::Queue::submit`], or to signal values that a [`super::Queue`] waits on.
+    ///
+    /// Fails with [`crate::DeviceError::ResourceCreationFailed`] if `VK_KHR_external_semaphore_fd`
+    /// isn't enabled or timeline semaphores can't be exported.
+    pub fn fence_fd(&self, fence: &super::Fence) -> Result<i32, crate::DeviceError> {
+        let functor = self.external_semaphore_fd()?;
+        let raw = fence
+            .raw_timeline_semaphore()
+            .ok_or(crate::DeviceError::ResourceCreationFailed)?;
+        let info = vk::SemaphoreGetFdInfoKHR::builder()
+            .semaphore(raw)
+            .handle_type(vk::ExternalSemaphoreHandleTypeFlags::OPAQUE_FD);
+        Ok(unsafe { functor.get_semaphore_fd(&info)? })
+    }
+
+    /// Creates a fence from a timeline semaphore exported by another API as an opaque
+    /// file descriptor.
+    ///
+    /// The fence can be passed to [`crate::Queue::wait_for_fence`] to make a submission
+    /// wait on work of the other API, and must be destroyed with
+    /// [`crate::Device::destroy_fence`].
+    ///
+    /// On success, ownership of `fd` is transferred to the driver. On failure, it stays
+    /// with the caller.
+    ///
+    /// # Safety
+    ///
+    /// - `fd` must refer to a timeline semaphore exported as `OPAQUE_FD` by the same
+    ///   driver and device.
+    pub unsafe fn import_fence_fd(&self, fd: i32) -> Result<super::Fence, crate::DeviceError> {
+        let functor = self.external_semaphore_fd()?;
+        let fence = unsafe { crate::Device::create_fence(self) }?;
+        let raw = fence.raw_timeline_semaphore().unwrap();
+        let info = vk::ImportSemaphoreFdInfoKHR::builder()
+            .semaphore(raw)
+            .handle_type(vk::ExternalSemaphoreHandleTypeFlags::OPAQUE_FD)
+            .fd(fd);
+        if let Err(err) = unsafe { functor.import_semaphore_fd(&info) } {
+            unsafe { crate::Device::destroy_fence(self, fence) };
+            return Err(err.into());
+        }
+        Ok(fence)
+    }
+
+    /// Allocates dedicated memory for the external image `raw` with `info`, and binds it.
+    ///
+    /// The memory is registered with the allocator, so it is freed when the
+    /// texture is destroyed. If `info` imports `import_fd`, the driver owns the
+    /// file descriptor once the memory is allocated, and releases it with the
+    /// memory if binding fails. If allocating fails, `import_fd` is closed.
+    unsafe fn bind_external_memory(
+        &self,
+        raw: vk::Image,
+        memory_type_bits: u32,
+        info: vk::MemoryAllocateInfoBuilder,
+        import_fd: Option<i32>,
+    ) -> Result<gpu_alloc::MemoryBlock<vk::DeviceMemory>, crate::DeviceError> {
+        let fail = |err| {
+            if let Some(fd) = import_fd {
+                close_fd(fd);
+            }
+            Err(err)
+        };
+
+        let req = unsafe { self.shared.raw.get_image_memory_requirements(raw) };
+        let memory_type_bits =
+            req.memory_type_bits & memory_type_bits & self.valid_ash_memory_types;
+        if memory_type_bits == 0 {
+            return fail(crate::DeviceError::ResourceCreationFailed);
+        }
+        let memory_type = memory_type_bits.trailing_zeros();
+
+        let mut allocator = self.mem_allocator.lock();
+        if allocator.remaining_allocations() == 0 {
+            return fail(crate::DeviceError::OutOfMemory);
+        }
+
+        let mut dedicated_info = vk::MemoryDedicatedAllocateInfo::builder().image(raw);
+        let info = info
+            .allocation_size(req.size)
+            .memory_type_index(memory_type)
+            .push_next(&mut dedicated_info);
+        let memory = match unsafe { self.shared.raw.allocate_memory(&info, None) } {
+            Ok(memory) => memory,
+            Err(err) => return fail(err.into()),
+        };
+        if let Err(err) = unsafe { self.shared.raw.bind_image_memory(raw, memory, 0) } {
+            unsafe { self.shared.raw.free_memory(memory, None) };
+            return Err(err.into());
+        }
+
+        let props = self.memory_types[memory_type as usize].property_flags;
+        Ok(unsafe {
+            allocator.import_memory(
+                memory,
+                memory_type,
+                gpu_alloc::MemoryPropertyFlags::from_bits_truncate(props.as_raw() as u8),
+                0,
+                req.size,
+            )
+        })
+    }
+
+    /// Creates a texture whose memory can be exported with [`Self::texture_memory_fd`].
+    ///
+    /// The texture uses optimal tiling, so DMA-BUFs exported from it are only
+    /// meaningful to the same driver.
+    ///
+    /// Fails with [`crate::DeviceError::ResourceCreationFailed`] if `VK_KHR_external_memory_fd`
+    /// (or `VK_EXT_external_memory_dma_buf`) isn't enabled.
+    pub unsafe fn create_exportable_texture(
+        &self,
+        desc: &crate::TextureDescriptor,
+        fd_type: super::ExternalMemoryFdType,
+    ) -> Result<super::Texture, crate::DeviceError> {
+        self.external_memory_fd()?;
+        let handle_type = self.external_handle_type(fd_type)?;
+        let (raw, raw_flags, view_formats) =
+            unsafe { self.create_image(desc, Some((handle_type, None)))? };
+
+        let mut export_info = vk::ExportMemoryAllocateInfo::builder().handle_types(handle_type);
+        let info = vk::MemoryAllocateInfo::builder().push_next(&mut export_info);
+        let device_local_types = self
+            .memory_types
+            .iter()
+            .enumerate()
+            .filter(|&(_, ty)| {
+                ty.property_flags
+                    .contains(vk::MemoryPropertyFlags::DEVICE_LOCAL)
+            })
+            .fold(0, |bits, (i, _)| bits | (1 << i));
+        let block = match unsafe { self.bind_external_memory(raw, device_local_types, info, None) }
+        {
+            Ok(block) => block,
+            Err(err) => {
+                unsafe { self.shared.raw.destroy_image(raw, None) };
+                return Err(err);
+            }
+        };
+
+        if let Some(label) = desc.label {
+            unsafe {
+                self.shared
+                    .set_object_name(vk::ObjectType::IMAGE, raw, label)
+            };
+        }
+
+        Ok(super::Texture {
+            raw,
+            drop_guard: None,
+            block: Some(block),
+            usage: desc.usage,
+            format: desc.format,
+            raw_flags,
+            copy_size: desc.copy_extent(),
+            view_formats,
+        })
+    }
+
+    /// Exports the memory of `texture` as a new file descriptor, owned by the caller.
+    ///
+    /// # Safety
+    ///
+    /// - `texture` must have been created by [`Self::create_exportable_texture`] with the same `fd_type`.
+    pub unsafe fn texture_memory_fd(
+        &self,
+        texture: &super::Texture,
+        fd_type: super::ExternalMemoryFdType,
+    ) -> Result<i32, crate::DeviceError> {
+        let functor = self.external_memory_fd()?;
+        let block = texture
+            .block
+            .as_ref()
+            .ok_or(crate::DeviceError::ResourceCreationFailed)?;
+        let info = vk::MemoryGetFdInfoKHR::builder()
+            .memory(*block.memory())
+            .handle_type(self.external_handle_type(fd_type)?);
+        Ok(unsafe { functor.get_memory_fd(&info)? })
+    }
+
+    /// Creates a texture from external memory referred to by `fd`.
+    ///
+    /// Ownership of `fd` is always transferred: to the driver on success, and it is
+    /// closed on failure.
+    ///
+    /// DMA-BUFs from other APIs, like video decoders, usually need their layout to be
+    /// described by a `modifier`, which requires `VK_EXT_image_drm_format_modifier`.
+    /// Without one, the image uses optimal tiling.
+    ///
+    /// # Safety
+    ///
+    /// - `fd` must refer to memory compatible with `desc` and `modifier`, of type `fd_type`.
+    /// - Synchronizing access to the memory with its other users is up to the caller.
+    pub unsafe fn import_texture_fd(
+        &self,
+        desc: &crate::TextureDescriptor,
+        fd: i32,
+        fd_type: super::ExternalMemoryFdType,
+        modifier: Option<&super::DrmFormatModifier>,
+    ) -> Result<super::Texture, crate::DeviceError> {
+        let prepared = (|| {
+            let functor = self.external_memory_fd()?;
+            let handle_type = self.external_handle_type(fd_type)?;
+            if modifier.is_some()
+                && !self
+                    .enabled_device_extensions()
+                    .contains(&vk::ExtImageDrmFormatModifierFn::name())
+            {
+                return Err(crate::DeviceError::ResourceCreationFailed);
+            }
+
+            let memory_type_bits = match fd_type {
+                super::ExternalMemoryFdType::Opaque => !0,
+                super::ExternalMemoryFdType::DmaBuf => {
+                    unsafe { functor.get_memory_fd_properties(handle_type, fd)? }.memory_type_bits
+                }
+            };
+
+            let image = unsafe { self.create_image(desc, Some((handle_type, modifier)))? };
+            Ok((handle_type, memory_type_bits, image))
+        })();
+        let (handle_type, memory_type_bits, (raw, raw_flags, view_formats)) = match prepared {
+            Ok(prepared) => prepared,
+            Err(err) => {
+                close_fd(fd);
+                return Err(err);
+            }
+        };
+
+        let mut import_info = vk::ImportMemoryFdInfoKHR::builder()
+            .handle_type(handle_type)
+            .fd(fd);
+        let info = vk::MemoryAllocateInfo::builder().push_next(&mut import_info);
+        let block =
+            match unsafe { self.bind_external_memory(raw, memory_type_bits, info, Some(fd)) } {
+                Ok(block) => block,
+                Err(err) => {
+                    unsafe { self.shared.raw.destroy_image(raw, None) };
+                    return Err(err);
+                }
+            };
+
+        if let Some(label) = desc.label {
+            unsafe {
+                self.shared
+                    .set_object_name(vk::ObjectType::IMAGE, raw, label)
+            };
+        }
+
+        Ok(super::Texture {
+            raw,
+            drop_guard: None,
+            block: Some(block),
+            usage: desc.usage,
+            format: desc.format,
+            raw_flags,
+            copy_size: desc.copy_extent(),
+            view_formats,
         })
     }
 
+    fn external_handle_type(
+        &self,
+        fd_type: super::ExternalMemoryFdType,
+    ) -> Result<vk::ExternalMemoryHandleTypeFlags, crate::DeviceError> {
+        match fd_type {
+            super::ExternalMemoryFdType::Opaque => Ok(vk::ExternalMemoryHandleTypeFlags::OPAQUE_FD),
+            super::ExternalMemoryFdType::DmaBuf => {
+                if self
+                    .enabled_device_extensions()
+                    .contains(&vk::ExtExternalMemoryDmaBufFn::name())
+                {
+                    Ok(vk::ExternalMemoryHandleTypeFlags::DMA_BUF_EXT)
+                } else {
+                    Err(crate::DeviceError::ResourceCreationFailed)
+                }
+            }
+        }
+    }
+
     /// # Safety
     ///
     /// - `vk_image` must be created respecting `desc`
@@ -714,16 +1457,24 @@ impl super::Device {
     ) -> Result<CompiledStage, crate::PipelineError> {
         let stage_flags = crate::auxil::map_naga_stage(naga_stage);
         let vk_module = match *stage.module {
+            super::ShaderModule::Raw(_) if !stage.constants.is_empty() => {
+                return Err(crate::PipelineError::Linkage(
+                    stage_flags,
+                    "Pipeline constants can't be applied to SPIR-V passthrough modules".to_string(),
+                ));
+            }
             super::ShaderModule::Raw(raw) => raw,
             super::ShaderModule::Intermediate {
                 ref naga_shader,
                 runtime_checks,
+                zero_initialize_workgroup_memory,
             } => {
                 let pipeline_options = naga::back::spv::PipelineOptions {
                     entry_point: stage.entry_point.to_string(),
                     shader_stage: naga_stage,
                 };
-                let needs_temp_options = !runtime_checks || !binding_map.is_empty();
+                let needs_temp_options =
+                    !runtime_checks || !zero_initialize_workgroup_memory || !binding_map.is_empty();
                 let mut temp_options;
                 let options = if needs_temp_options {
                     temp_options = self.naga_options.clone();
@@ -736,6 +1487,10 @@ impl super::Device {
                             binding_array: naga::proc::BoundsCheckPolicy::Unchecked,
                         };
                     }
+                    if !zero_initialize_workgroup_memory {
+                        temp_options.zero_initialize_workgroup_memory =
+                            naga::back::spv::ZeroInitializeWorkgroupMemoryMode::None;
+                    }
                     if !binding_map.is_empty() {
                         temp_options.binding_map = binding_map.clone();
                     }
@@ -743,10 +1498,12 @@ impl super::Device {
                 } else {
                     &self.naga_options
                 };
+                let module =
+                    crate::auxil::apply_pipeline_constants(&naga_shader.module, stage.constants);
                 let spv = {
                     profiling::scope!("naga::spv::write_vec");
                     naga::back::spv::write_vec(
-                        &naga_shader.module,
+                        &module,
                         &naga_shader.info,
                         options,
                         Some(&pipeline_options),
@@ -774,17 +1531,200 @@ impl super::Device {
         })
     }
 
-    /// Returns the queue family index of the device's internal queue.
-    ///
-    /// This is useful for constructing memory barriers needed for queue family ownership transfer when
-    /// external memory is involved (from/to `VK_QUEUE_FAMILY_EXTERNAL_KHR` and `VK_QUEUE_FAMILY_FOREIGN_EXT`
-    /// for example).
-    pub fn queue_family_index(&self) -> u32 {
-        self.shared.family_index
+    /// Returns the queue family index of the device's internal queue.
+    ///
+    /// This is useful for constructing memory barriers needed for queue family ownership transfer when
+    /// external memory is involved (from/to `VK_QUEUE_FAMILY_EXTERNAL_KHR` and `VK_QUEUE_FAMILY_FOREIGN_EXT`
+    /// for example).
+    pub fn queue_family_index(&self) -> u32 {
+        self.shared.family_index
+    }
+
+    pub fn queue_index(&self) -> u32 {
+        self.shared.queue_index
+    }
+
+    /// Returns the contents of the pipeline cache, to be saved and passed to
+    /// [`Self::seed_pipeline_cache`] on a later run so pipelines don't have to be
+    /// compiled from scratch again.
+    pub fn pipeline_cache_data(&self) -> Result<Vec<u8>, crate::DeviceError> {
+        let data = unsafe {
+            self.shared
+                .raw
+                .get_pipeline_cache_data(self.shared.pipeline_cache)?
+        };
+        Ok(data)
+    }
+
+    /// Merges data returned by [`Self::pipeline_cache_data`] into the pipeline cache.
+    ///
+    /// Returns `false`, leaving the cache untouched, if `data` was produced by a
+    /// different adapter or driver version.
+    ///
+    /// # Safety
+    ///
+    /// - Must not be called while pipelines are being created on this device.
+    pub unsafe fn seed_pipeline_cache(&self, data: &[u8]) -> Result<bool, crate::DeviceError> {
+        if !self.shared.is_pipeline_cache_compatible(data) {
+            log::info!("Ignoring pipeline cache data from a different adapter or driver");
+            return Ok(false);
+        }
+
+        let vk_info = vk::PipelineCacheCreateInfo::builder().initial_data(data);
+        let raw = unsafe { self.shared.raw.create_pipeline_cache(&vk_info, None)? };
+        let result = unsafe {
+            self.shared
+                .raw
+                .merge_pipeline_caches(self.shared.pipeline_cache, &[raw])
+        };
+        unsafe { self.shared.raw.destroy_pipeline_cache(raw, None) };
+        result?;
+        Ok(true)
+    }
+
+    /// Returns the compiled executables of `pipeline`, with compiler statistics such as
+    /// register counts and spills, and their disassembly when the driver provides it.
+    ///
+    /// Returns `None` unless `VK_KHR_pipeline_executable_properties` is enabled, which
+    /// requires `InstanceFlags::DEBUG`.
+    pub fn render_pipeline_executables(
+        &self,
+        pipeline: &super::RenderPipeline,
+    ) -> Option<Vec<super::PipelineExecutable>> {
+        unsafe { self.pipeline_executables(pipeline.raw) }
+    }
+
+    /// Same as [`Self::render_pipeline_executables`], for compute pipelines.
+    pub fn compute_pipeline_executables(
+        &self,
+        pipeline: &super::ComputePipeline,
+    ) -> Option<Vec<super::PipelineExecutable>> {
+        unsafe { self.pipeline_executables(pipeline.raw) }
     }
 
-    pub fn queue_index(&self) -> u32 {
-        self.shared.queue_index
+    unsafe fn pipeline_executables(
+        &self,
+        pipeline: vk::Pipeline,
+    ) -> Option<Vec<super::PipelineExecutable>> {
+        use crate::auxil::cstr_from_bytes_until_nul;
+
+        let extension = self
+            .shared
+            .extension_fns
+            .pipeline_executable_properties
+            .as_ref()?;
+        let to_string = |bytes: &[std::os::raw::c_char]| {
+            cstr_from_bytes_until_nul(bytes)
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+
+        let pipeline_info = vk::PipelineInfoKHR::builder().pipeline(pipeline);
+        let properties =
+            match unsafe { extension.get_pipeline_executable_properties(&pipeline_info) } {
+                Ok(properties) => properties,
+                Err(err) => {
+                    log::error!("get_pipeline_executable_properties: {}", err);
+                    return None;
+                }
+            };
+
+        let mut executables = Vec::with_capacity(properties.len());
+        for (index, props) in properties.iter().enumerate() {
+            let executable_info = vk::PipelineExecutableInfoKHR::builder()
+                .pipeline(pipeline)
+                .executable_index(index as u32);
+
+            let statistics =
+                unsafe { extension.get_pipeline_executable_statistics(&executable_info) }
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|statistic| super::PipelineExecutableStatistic {
+                        name: to_string(&statistic.name),
+                        description: to_string(&statistic.description),
+                        value: unsafe {
+                            match statistic.format {
+                                vk::PipelineExecutableStatisticFormatKHR::BOOL32 => {
+                                    super::PipelineExecutableStatisticValue::Bool(
+                                        statistic.value.b32 != vk::FALSE,
+                                    )
+                                }
+                                vk::PipelineExecutableStatisticFormatKHR::INT64 => {
+                                    super::PipelineExecutableStatisticValue::Int(
+                                        statistic.value.i64,
+                                    )
+                                }
+                                vk::PipelineExecutableStatisticFormatKHR::FLOAT64 => {
+                                    super::PipelineExecutableStatisticValue::Float(
+                                        statistic.value.f64,
+                                    )
+                                }
+                                _ => super::PipelineExecutableStatisticValue::Uint(
+                                    statistic.value.u64,
+                                ),
+                            }
+                        },
+                    })
+                    .collect();
+
+            // The first call only returns the sizes of the representations, the
+            // second one fills the buffers we allocated for them.
+            let mut representations = unsafe {
+                extension.get_pipeline_executable_internal_representations(&executable_info)
+            }
+            .unwrap_or_default();
+            let mut buffers = representations
+                .iter()
+                .map(|representation| vec![0u8; representation.data_size])
+                .collect::<Vec<_>>();
+            for (representation, buffer) in representations.iter_mut().zip(buffers.iter_mut()) {
+                representation.p_data = buffer.as_mut_ptr().cast();
+            }
+            let mut count = representations.len() as u32;
+            let result = unsafe {
+                (extension
+                    .fp()
+                    .get_pipeline_executable_internal_representations_khr)(
+                    self.shared.raw.handle(),
+                    &*executable_info,
+                    &mut count,
+                    representations.as_mut_ptr(),
+                )
+            };
+            let internal_representations = if result == vk::Result::SUCCESS {
+                representations
+                    .iter()
+                    .zip(buffers.iter())
+                    .filter(|&(representation, _)| representation.is_text == vk::TRUE)
+                    .map(|(representation, buffer)| {
+                        let text = match buffer.iter().position(|&byte| byte == 0) {
+                            Some(end) => &buffer[..end],
+                            None => &buffer[..],
+                        };
+                        (
+                            to_string(&representation.name),
+                            String::from_utf8_lossy(text).into_owned(),
+                        )
+                    })
+                    .collect()
+            } else {
+                log::warn!(
+                    "get_pipeline_executable_internal_representations: {:?}",
+                    result
+                );
+                Vec::new()
+            };
+
+            executables.push(super::PipelineExecutable {
+                name: to_string(&props.name),
+                description: to_string(&props.description),
+                stages: conv::map_vk_shader_stages(props.stages),
+                subgroup_size: props.subgroup_size,
+                statistics,
+                internal_representations,
+            });
+        }
+        Some(executables)
     }
 
     pub fn raw_device(&self) -> &ash::Device {
@@ -806,26 +1746,55 @@ impl super::Device {
     pub fn shared_instance(&self) -> &super::InstanceShared {
         &self.shared.instance
     }
+
+    /// Returns statistics about the `VkDeviceMemory` objects the memory allocator has made.
+    pub fn memory_stats(&self) -> super::MemoryStats {
+        let remaining_allocations = self.mem_allocator.lock().remaining_allocations();
+        super::MemoryStats {
+            allocation_count: self.max_memory_allocation_count - remaining_allocations,
+            max_allocation_count: self.max_memory_allocation_count,
+        }
+    }
 }
 
 impl crate::Device<super::Api> for super::Device {
     unsafe fn exit(self, queue: super::Queue) {
         unsafe { self.mem_allocator.into_inner().cleanup(&*self.shared) };
-        unsafe { self.desc_allocator.into_inner().cleanup(&*self.shared) };
+        let mut desc_allocator = self.desc_allocator.into_inner();
+        for (_, sets) in self.recycled_desc_sets.into_inner() {
+            unsafe { desc_allocator.free(&*self.shared, sets) };
+        }
+        unsafe { desc_allocator.cleanup(&*self.shared) };
         for &sem in queue.relay_semaphores.iter() {
             unsafe { self.shared.raw.destroy_semaphore(sem, None) };
         }
         unsafe { self.shared.free_resources() };
     }
 
+    unsafe fn destroy_queue(&self, queue: super::Queue) {
+        for &sem in queue.relay_semaphores.iter() {
+            unsafe { self.shared.raw.destroy_semaphore(sem, None) };
+        }
+    }
+
     unsafe fn create_buffer(
         &self,
         desc: &crate::BufferDescriptor,
     ) -> Result<super::Buffer, crate::DeviceError> {
-        let vk_info = vk::BufferCreateInfo::builder()
+        let mut raw_flags = vk::BufferCreateFlags::empty();
+        if desc.memory_flags.contains(crate::MemoryFlags::PROTECTED) {
+            raw_flags |= vk::BufferCreateFlags::PROTECTED;
+        }
+        let mut vk_info = vk::BufferCreateInfo::builder()
+            .flags(raw_flags)
             .size(desc.size)
             .usage(conv::map_buffer_usage(desc.usage))
             .sharing_mode(vk::SharingMode::EXCLUSIVE);
+        if !self.shared.concurrent_queue_families.is_empty() {
+            vk_info = vk_info
+                .sharing_mode(vk::SharingMode::CONCURRENT)
+                .queue_family_indices(&self.shared.concurrent_queue_families);
+        }
 
         let raw = unsafe { self.shared.raw.create_buffer(&vk_info, None)? };
         let req = unsafe { self.shared.raw.get_buffer_memory_requirements(raw) };
@@ -852,6 +1821,14 @@ impl crate::Device<super::Api> for super::Device {
             gpu_alloc::UsageFlags::TRANSIENT,
             desc.memory_flags.contains(crate::MemoryFlags::TRANSIENT),
         );
+        alloc_usage.set(
+            gpu_alloc::UsageFlags::DEVICE_ADDRESS,
+            desc.usage.intersects(
+                crate::BufferUses::ACCELERATION_STRUCTURE_SCRATCH
+                    | crate::BufferUses::BOTTOM_LEVEL_ACCELERATION_STRUCTURE_INPUT
+                    | crate::BufferUses::TOP_LEVEL_ACCELERATION_STRUCTURE_INPUT,
+            ),
+        );
 
         let block = unsafe {
             self.mem_allocator.lock().alloc(
@@ -860,7 +1837,8 @@ impl crate::Device<super::Api> for super::Device {
                     size: req.size,
                     align_mask: req.alignment - 1,
                     usage: alloc_usage,
-                    memory_types: req.memory_type_bits & self.valid_ash_memory_types,
+                    memory_types: req.memory_type_bits
+                        & self.allowed_memory_types(desc.memory_flags),
                 },
             )?
         };
@@ -956,66 +1934,31 @@ impl crate::Device<super::Api> for super::Device {
         desc: &crate::TextureDescriptor,
     ) -> Result<super::Texture, crate::DeviceError> {
         let copy_size = desc.copy_extent();
-
-        let mut raw_flags = vk::ImageCreateFlags::empty();
-        if desc.is_cube_compatible() {
-            raw_flags |= vk::ImageCreateFlags::CUBE_COMPATIBLE;
-        }
-
-        let original_format = self.shared.private_caps.map_texture_format(desc.format);
-        let mut vk_view_formats = vec![];
-        let mut wgt_view_formats = vec![];
-        if !desc.view_formats.is_empty() {
-            raw_flags |= vk::ImageCreateFlags::MUTABLE_FORMAT;
-            wgt_view_formats = desc.view_formats.clone();
-            wgt_view_formats.push(desc.format);
-
-            if self.shared_instance().driver_api_version >= vk::API_VERSION_1_2
-                || self
-                    .enabled_device_extensions()
-                    .contains(&vk::KhrImageFormatListFn::name())
-            {
-                vk_view_formats = desc
-                    .view_formats
-                    .iter()
-                    .map(|f| self.shared.private_caps.map_texture_format(*f))
-                    .collect();
-                vk_view_formats.push(original_format)
-            }
-        }
-
-        let mut vk_info = vk::ImageCreateInfo::builder()
-            .flags(raw_flags)
-            .image_type(conv::map_texture_dimension(desc.dimension))
-            .format(original_format)
-            .extent(conv::map_copy_extent(&copy_size))
-            .mip_levels(desc.mip_level_count)
-            .array_layers(desc.array_layer_count())
-            .samples(vk::SampleCountFlags::from_raw(desc.sample_count))
-            .tiling(vk::ImageTiling::OPTIMAL)
-            .usage(conv::map_texture_usage(desc.usage))
-            .sharing_mode(vk::SharingMode::EXCLUSIVE)
-            .initial_layout(vk::ImageLayout::UNDEFINED);
-
-        let mut format_list_info = vk::ImageFormatListCreateInfo::builder();
-        if !vk_view_formats.is_empty() {
-            format_list_info = format_list_info.view_formats(&vk_view_formats);
-            vk_info = vk_info.push_next(&mut format_list_info);
-        }
-
-        let raw = unsafe { self.shared.raw.create_image(&vk_info, None)? };
+        let (raw, raw_flags, wgt_view_formats) = unsafe { self.create_image(desc, None)? };
         let req = unsafe { self.shared.raw.get_image_memory_requirements(raw) };
 
-        let block = unsafe {
-            self.mem_allocator.lock().alloc(
-                &*self.shared,
-                gpu_alloc::Request {
-                    size: req.size,
-                    align_mask: req.alignment - 1,
-                    usage: gpu_alloc::UsageFlags::FAST_DEVICE_ACCESS,
-                    memory_types: req.memory_type_bits & self.valid_ash_memory_types,
-                },
-            )?
+        let request = gpu_alloc::Request {
+            size: req.size,
+            align_mask: req.alignment - 1,
+            usage: gpu_alloc::UsageFlags::FAST_DEVICE_ACCESS,
+            memory_types: req.memory_type_bits & self.allowed_memory_types(desc.memory_flags),
+        };
+        // Render targets are usually large and long-lived, so they get their own
+        // memory object when they are big enough, instead of fragmenting the blocks.
+        let block = if desc.usage.intersects(
+            crate::TextureUses::COLOR_TARGET
+                | crate::TextureUses::DEPTH_STENCIL_READ
+                | crate::TextureUses::DEPTH_STENCIL_WRITE,
+        ) {
+            unsafe {
+                self.mem_allocator.lock().alloc_with_dedicated(
+                    &*self.shared,
+                    request,
+                    gpu_alloc::Dedicated::Preferred,
+                )?
+            }
+        } else {
+            unsafe { self.mem_allocator.lock().alloc(&*self.shared, request)? }
         };
 
         unsafe {
@@ -1056,18 +1999,20 @@ impl crate::Device<super::Api> for super::Device {
         texture: &super::Texture,
         desc: &crate::TextureViewDescriptor,
     ) -> Result<super::TextureView, crate::DeviceError> {
-        let subresource_range = conv::map_subresource_range(&desc.range, desc.format);
+        let subresource_range = conv::map_subresource_range(&desc.range, texture.format);
         let mut vk_info = vk::ImageViewCreateInfo::builder()
             .flags(vk::ImageViewCreateFlags::empty())
             .image(texture.raw)
             .view_type(conv::map_view_dimension(desc.dimension))
             .format(self.shared.private_caps.map_texture_format(desc.format))
+            .components(conv::map_swizzle(desc.swizzle))
             .subresource_range(subresource_range);
         let layers =
             NonZeroU32::new(subresource_range.layer_count).expect("Unexpected zero layer count");
 
         let mut image_view_info;
-        let view_usage = if self.shared.private_caps.image_view_usage && !desc.usage.is_empty() {
+        let restricts_usage = self.shared.private_caps.image_view_usage && !desc.usage.is_empty();
+        let view_usage = if restricts_usage {
             image_view_info = vk::ImageViewUsageCreateInfo::builder()
                 .usage(conv::map_texture_usage(desc.usage))
                 .build();
@@ -1102,21 +2047,47 @@ impl crate::Device<super::Api> for super::Device {
                 .collect(),
         };
 
+        let depth_slices = if desc.dimension == wgt::TextureViewDimension::D3
+            && view_usage.contains(crate::TextureUses::COLOR_TARGET)
+        {
+            Some(super::DepthSliceViews {
+                image: texture.raw,
+                format: vk_info.format,
+                components: vk_info.components,
+                subresource_range,
+                usage: restricts_usage.then(|| conv::map_texture_usage(desc.usage)),
+                views: Mutex::default(),
+            })
+        } else {
+            None
+        };
+
         Ok(super::TextureView {
             raw,
             layers,
             attachment,
+            depth_slices,
         })
     }
     unsafe fn destroy_texture_view(&self, view: super::TextureView) {
+        let slice_views: Vec<vk::ImageView> = view
+            .depth_slices
+            .map(|depth_slices| depth_slices.views.into_inner().into_values().collect())
+            .unwrap_or_default();
         if !self.shared.private_caps.imageless_framebuffers {
+            let uses_view = |at: &super::FramebufferAttachment| {
+                at.raw == view.raw || slice_views.contains(&at.raw)
+            };
             let mut fbuf_lock = self.shared.framebuffers.lock();
             for (key, &raw_fbuf) in fbuf_lock.iter() {
-                if key.attachments.iter().any(|at| at.raw == view.raw) {
+                if key.attachments.iter().any(uses_view) {
                     unsafe { self.shared.raw.destroy_framebuffer(raw_fbuf, None) };
                 }
             }
-            fbuf_lock.retain(|key, _| !key.attachments.iter().any(|at| at.raw == view.raw));
+            fbuf_lock.retain(|key, _| !key.attachments.iter().any(uses_view));
+        }
+        for slice_raw in slice_views {
+            unsafe { self.shared.raw.destroy_image_view(slice_raw, None) };
         }
         unsafe { self.shared.raw.destroy_image_view(view.raw, None) };
     }
@@ -1125,6 +2096,7 @@ impl crate::Device<super::Api> for super::Device {
         &self,
         desc: &crate::SamplerDescriptor,
     ) -> Result<super::Sampler, crate::DeviceError> {
+        let mut custom_border_color_info = vk::SamplerCustomBorderColorCreateInfoEXT::default();
         let mut vk_info = vk::SamplerCreateInfo::builder()
             .flags(vk::SamplerCreateFlags::empty())
             .mag_filter(conv::map_filter_mode(desc.mag_filter))
@@ -1151,6 +2123,12 @@ impl crate::Device<super::Api> for super::Device {
         }
 
         if let Some(color) = desc.border_color {
+            if let wgt::SamplerBorderColor::Custom(rgba) = color {
+                custom_border_color_info.custom_border_color =
+                    vk::ClearColorValue { float32: rgba };
+                custom_border_color_info.format = vk::Format::UNDEFINED;
+                vk_info = vk_info.push_next(&mut custom_border_color_info);
+            }
             vk_info = vk_info.border_color(conv::map_border_color(color));
         }
 
@@ -1173,12 +2151,23 @@ impl crate::Device<super::Api> for super::Device {
         &self,
         desc: &crate::CommandEncoderDescriptor<super::Api>,
     ) -> Result<super::CommandEncoder, crate::DeviceError> {
+        let mut flags = vk::CommandPoolCreateFlags::TRANSIENT;
+        if desc.protected {
+            flags |= vk::CommandPoolCreateFlags::PROTECTED;
+        }
         let vk_info = vk::CommandPoolCreateInfo::builder()
             .queue_family_index(desc.queue.family_index)
-            .flags(vk::CommandPoolCreateFlags::TRANSIENT)
+            .flags(flags)
             .build();
         let raw = unsafe { self.shared.raw.create_command_pool(&vk_info, None)? };
 
+        if let Some(label) = desc.label {
+            unsafe {
+                self.shared
+                    .set_object_name(vk::ObjectType::COMMAND_POOL, raw, label)
+            };
+        }
+
         Ok(super::CommandEncoder {
             raw,
             device: Arc::clone(&self.shared),
@@ -1188,10 +2177,16 @@ impl crate::Device<super::Api> for super::Device {
             free: Vec::new(),
             discarded: Vec::new(),
             rpass_debug_marker_active: false,
+            checkpoint_groups: Vec::new(),
+            checkpoint_labels: Vec::new(),
             end_of_pass_timer_query: None,
+            protected: desc.protected,
+            supported_stages: desc.queue.supported_stages,
         })
     }
-    unsafe fn destroy_command_encoder(&self, cmd_encoder: super::CommandEncoder) {
+    unsafe fn destroy_command_encoder(&self, mut cmd_encoder: super::CommandEncoder) {
+        self.shared
+            .release_checkpoints(&mut cmd_encoder.checkpoint_labels);
         unsafe {
             // `vkDestroyCommandPool` also frees any command buffers allocated
             // from that pool, so there's no need to explicitly call
@@ -1250,6 +2245,9 @@ impl crate::Device<super::Api> for super::Device {
                 wgt::BindingType::StorageTexture { .. } => {
                     desc_count.storage_image += count;
                 }
+                wgt::BindingType::AccelerationStructure => {
+                    desc_count.acceleration_structure += count;
+                }
             }
         }
 
@@ -1282,6 +2280,20 @@ impl crate::Device<super::Api> for super::Device {
             .flags
             .contains(crate::BindGroupLayoutFlags::PARTIALLY_BOUND);
 
+        // Update-after-bind layouts can't contain dynamic buffers, and uniform
+        // buffers and acceleration structures need device features we don't enable.
+        let update_after_bind = partially_bound
+            && self.shared.private_caps.update_after_bind_descriptors
+            && desc.entries.iter().all(|entry| match entry.ty {
+                wgt::BindingType::Buffer {
+                    ty,
+                    has_dynamic_offset,
+                    ..
+                } => !has_dynamic_offset && ty != wgt::BufferBindingType::Uniform,
+                wgt::BindingType::AccelerationStructure => false,
+                _ => true,
+            });
+
         let vk_info = if partially_bound {
             binding_flag_vec = desc
                 .entries
@@ -1292,6 +2304,9 @@ impl crate::Device<super::Api> for super::Device {
                     if partially_bound && entry.count.is_some() {
                         flags |= vk::DescriptorBindingFlags::PARTIALLY_BOUND;
                     }
+                    if update_after_bind {
+                        flags |= vk::DescriptorBindingFlags::UPDATE_AFTER_BIND;
+                    }
 
                     flags
                 })
@@ -1304,6 +2319,11 @@ impl crate::Device<super::Api> for super::Device {
         } else {
             vk_info
         };
+        let vk_info = if update_after_bind {
+            vk_info.flags(vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL)
+        } else {
+            vk_info
+        };
 
         let raw = unsafe {
             self.shared
@@ -1323,9 +2343,14 @@ impl crate::Device<super::Api> for super::Device {
             desc_count,
             types: types.into_boxed_slice(),
             binding_arrays,
+            update_after_bind,
         })
     }
     unsafe fn destroy_bind_group_layout(&self, bg_layout: super::BindGroupLayout) {
+        // The layout handle may be reused by the driver, so the sets recycled for it must go.
+        if let Some(sets) = self.recycled_desc_sets.lock().remove(&bg_layout.raw) {
+            unsafe { self.desc_allocator.lock().free(&*self.shared, sets) };
+        }
         unsafe {
             self.shared
                 .raw
@@ -1402,17 +2427,30 @@ impl crate::Device<super::Api> for super::Device {
         &self,
         desc: &crate::BindGroupDescriptor<super::Api>,
     ) -> Result<super::BindGroup, crate::DeviceError> {
-        let mut vk_sets = unsafe {
-            self.desc_allocator.lock().allocate(
-                &*self.shared,
-                &desc.layout.raw,
-                gpu_descriptor::DescriptorSetLayoutCreateFlags::empty(),
-                &desc.layout.desc_count,
-                1,
-            )?
+        let recycled = self
+            .recycled_desc_sets
+            .lock()
+            .get_mut(&desc.layout.raw)
+            .and_then(Vec::pop);
+        let set = match recycled {
+            Some(set) => set,
+            None => {
+                let mut vk_sets = unsafe {
+                    self.desc_allocator.lock().allocate(
+                        &*self.shared,
+                        &desc.layout.raw,
+                        if desc.layout.update_after_bind {
+                            gpu_descriptor::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND
+                        } else {
+                            gpu_descriptor::DescriptorSetLayoutCreateFlags::empty()
+                        },
+                        &desc.layout.desc_count,
+                        1,
+                    )?
+                };
+                vk_sets.pop().unwrap()
+            }
         };
-
-        let set = vk_sets.pop().unwrap();
         if let Some(label) = desc.label {
             unsafe {
                 self.shared
@@ -1424,6 +2462,9 @@ impl crate::Device<super::Api> for super::Device {
         let mut buffer_infos = Vec::with_capacity(desc.buffers.len());
         let mut sampler_infos = Vec::with_capacity(desc.samplers.len());
         let mut image_infos = Vec::with_capacity(desc.textures.len());
+        let mut acceleration_structure_infos = Vec::with_capacity(desc.entries.len());
+        let mut raw_acceleration_structures =
+            Vec::with_capacity(desc.acceleration_structures.len());
         for entry in desc.entries {
             let (ty, size) = desc.layout.types[entry.binding as usize];
             if size == 0 {
@@ -1483,20 +2524,54 @@ impl crate::Device<super::Api> for super::Device {
                     ));
                     write.buffer_info(&buffer_infos[index..])
                 }
+                vk::DescriptorType::ACCELERATION_STRUCTURE_KHR => {
+                    let index = raw_acceleration_structures.len();
+                    let start = entry.resource_index;
+                    let end = start + entry.count;
+                    raw_acceleration_structures.extend(
+                        desc.acceleration_structures[start as usize..end as usize]
+                            .iter()
+                            .map(|acceleration_structure| acceleration_structure.raw),
+                    );
+                    // Both vectors have enough capacity up front, so the pointers
+                    // stay valid until the descriptor sets are updated.
+                    acceleration_structure_infos.push(
+                        vk::WriteDescriptorSetAccelerationStructureKHR::builder()
+                            .acceleration_structures(&raw_acceleration_structures[index..])
+                            .build(),
+                    );
+                    let mut write =
+                        write.push_next(acceleration_structure_infos.last_mut().unwrap());
+                    // The count is otherwise set from the length of the info slice.
+                    write.descriptor_count = entry.count;
+                    write
+                }
                 _ => unreachable!(),
             };
             writes.push(write.build());
         }
 
         unsafe { self.shared.raw.update_descriptor_sets(&writes, &[]) };
-        Ok(super::BindGroup { set })
+        Ok(super::BindGroup {
+            set,
+            layout: desc.layout.raw,
+        })
     }
     unsafe fn destroy_bind_group(&self, group: super::BindGroup) {
-        unsafe {
-            self.desc_allocator
-                .lock()
-                .free(&*self.shared, Some(group.set))
-        };
+        // Bind groups are only destroyed once the GPU is done with them, so their
+        // descriptor set can be rewritten right away.
+        let mut recycled_desc_sets = self.recycled_desc_sets.lock();
+        let sets = recycled_desc_sets.entry(group.layout).or_default();
+        if sets.len() < super::MAX_RECYCLED_DESC_SETS_PER_LAYOUT {
+            sets.push(group.set);
+        } else {
+            drop(recycled_desc_sets);
+            unsafe {
+                self.desc_allocator
+                    .lock()
+                    .free(&*self.shared, Some(group.set))
+            };
+        }
     }
 
     unsafe fn create_shader_module(
@@ -1506,14 +2581,22 @@ impl crate::Device<super::Api> for super::Device {
     ) -> Result<super::ShaderModule, crate::ShaderError> {
         let spv = match shader {
             crate::ShaderInput::Naga(naga_shader) => {
-                if self
-                    .shared
-                    .workarounds
-                    .contains(super::Workarounds::SEPARATE_ENTRY_POINTS)
+                // Modules with overridable constants are specialized at pipeline creation.
+                let has_overrides = naga_shader
+                    .module
+                    .constants
+                    .iter()
+                    .any(|(_, constant)| constant.r#override != naga::Override::None);
+                if has_overrides
+                    || self
+                        .shared
+                        .workarounds
+                        .contains(super::Workarounds::SEPARATE_ENTRY_POINTS)
                 {
                     return Ok(super::ShaderModule::Intermediate {
                         naga_shader,
                         runtime_checks: desc.runtime_checks,
+                        zero_initialize_workgroup_memory: desc.zero_initialize_workgroup_memory,
                     });
                 }
                 let mut naga_options = self.naga_options.clone();
@@ -1526,6 +2609,10 @@ impl crate::Device<super::Api> for super::Device {
                         binding_array: naga::proc::BoundsCheckPolicy::Unchecked,
                     };
                 }
+                if !desc.zero_initialize_workgroup_memory {
+                    naga_options.zero_initialize_workgroup_memory =
+                        naga::back::spv::ZeroInitializeWorkgroupMemoryMode::None;
+                }
                 Cow::Owned(
                     naga::back::spv::write_vec(
                         &naga_shader.module,
@@ -1563,12 +2650,18 @@ impl crate::Device<super::Api> for super::Device {
         &self,
         desc: &crate::RenderPipelineDescriptor<super::Api>,
     ) -> Result<super::RenderPipeline, crate::PipelineError> {
-        let dynamic_states = [
+        let mut dynamic_states = ArrayVec::<_, 6>::from_iter([
             vk::DynamicState::VIEWPORT,
             vk::DynamicState::SCISSOR,
             vk::DynamicState::BLEND_CONSTANTS,
             vk::DynamicState::STENCIL_REFERENCE,
-        ];
+        ]);
+        if self.shared.extension_fns.fragment_shading_rate.is_some() {
+            dynamic_states.push(vk::DynamicState::FRAGMENT_SHADING_RATE_KHR);
+        }
+        if self.shared.depth_bounds_test {
+            dynamic_states.push(vk::DynamicState::DEPTH_BOUNDS);
+        }
         let mut compatible_rp_key = super::RenderPassKey {
             sample_count: desc.multisample.count,
             multiview: desc.multiview,
@@ -1661,6 +2754,9 @@ impl crate::Device<super::Api> for super::Device {
                     .depth_write_enable(ds.depth_write_enabled)
                     .depth_compare_op(conv::map_comparison(ds.depth_compare));
             }
+            if ds.depth_bounds.is_some() {
+                vk_depth_stencil = vk_depth_stencil.depth_bounds_test_enable(true);
+            }
             if ds.stencil.is_enabled() {
                 let s = &ds.stencil;
                 let front = conv::map_stencil_face(&s.front, s.read_mask, s.write_mask);
@@ -1682,8 +2778,8 @@ impl crate::Device<super::Api> for super::Device {
 
         let vk_viewport = vk::PipelineViewportStateCreateInfo::builder()
             .flags(vk::PipelineViewportStateCreateFlags::empty())
-            .scissor_count(1)
-            .viewport_count(1)
+            .scissor_count(self.shared.viewport_count)
+            .viewport_count(self.shared.viewport_count)
             .build();
 
         let vk_sample_mask = [
@@ -1748,6 +2844,7 @@ impl crate::Device<super::Api> for super::Device {
 
         let vk_infos = [{
             vk::GraphicsPipelineCreateInfo::builder()
+                .flags(self.shared.pipeline_create_flags())
                 .layout(desc.layout.raw)
                 .stages(&stages)
                 .vertex_input_state(&vk_vertex_input)
@@ -1762,12 +2859,15 @@ impl crate::Device<super::Api> for super::Device {
                 .build()
         }];
 
+        let pipeline_cache = desc
+            .cache
+            .map_or(self.shared.pipeline_cache, |cache| cache.raw);
         let mut raw_vec = {
             profiling::scope!("vkCreateGraphicsPipelines");
             unsafe {
                 self.shared
                     .raw
-                    .create_graphics_pipelines(vk::PipelineCache::null(), &vk_infos, None)
+                    .create_graphics_pipelines(pipeline_cache, &vk_infos, None)
                     .map_err(|(_, e)| crate::DeviceError::from(e))
             }?
         };
@@ -1809,17 +2909,21 @@ impl crate::Device<super::Api> for super::Device {
 
         let vk_infos = [{
             vk::ComputePipelineCreateInfo::builder()
+                .flags(self.shared.pipeline_create_flags())
                 .layout(desc.layout.raw)
                 .stage(compiled.create_info)
                 .build()
         }];
 
+        let pipeline_cache = desc
+            .cache
+            .map_or(self.shared.pipeline_cache, |cache| cache.raw);
         let mut raw_vec = {
             profiling::scope!("vkCreateComputePipelines");
             unsafe {
                 self.shared
                     .raw
-                    .create_compute_pipelines(vk::PipelineCache::null(), &vk_infos, None)
+                    .create_compute_pipelines(pipeline_cache, &vk_infos, None)
                     .map_err(|(_, e)| crate::DeviceError::from(e))
             }?
         };
@@ -1842,6 +2946,38 @@ impl crate::Device<super::Api> for super::Device {
         unsafe { self.shared.raw.destroy_pipeline(pipeline.raw, None) };
     }
 
+    unsafe fn create_pipeline_cache(
+        &self,
+        desc: &crate::PipelineCacheDescriptor,
+    ) -> Result<super::PipelineCache, crate::DeviceError> {
+        let mut vk_info = vk::PipelineCacheCreateInfo::builder();
+        match desc.data {
+            Some(data) if self.shared.is_pipeline_cache_compatible(data) => {
+                vk_info = vk_info.initial_data(data);
+            }
+            Some(_) => {
+                log::info!("Ignoring pipeline cache data from a different adapter or driver");
+            }
+            None => {}
+        }
+
+        let raw = unsafe { self.shared.raw.create_pipeline_cache(&vk_info, None)? };
+        if let Some(label) = desc.label {
+            unsafe {
+                self.shared
+                    .set_object_name(vk::ObjectType::PIPELINE_CACHE, raw, label)
+            };
+        }
+
+        Ok(super::PipelineCache { raw })
+    }
+    unsafe fn destroy_pipeline_cache(&self, cache: super::PipelineCache) {
+        unsafe { self.shared.raw.destroy_pipeline_cache(cache.raw, None) };
+    }
+    unsafe fn pipeline_cache_get_data(&self, cache: &super::PipelineCache) -> Option<Vec<u8>> {
+        unsafe { self.shared.raw.get_pipeline_cache_data(cache.raw) }.ok()
+    }
+
     unsafe fn create_query_set(
         &self,
         desc: &wgt::QuerySetDescriptor<crate::Label>,
@@ -1885,7 +3021,13 @@ impl crate::Device<super::Api> for super::Device {
         Ok(if self.shared.private_caps.timeline_semaphores {
             let mut sem_type_info =
                 vk::SemaphoreTypeCreateInfo::builder().semaphore_type(vk::SemaphoreType::TIMELINE);
-            let vk_info = vk::SemaphoreCreateInfo::builder().push_next(&mut sem_type_info);
+            let mut vk_info = vk::SemaphoreCreateInfo::builder().push_next(&mut sem_type_info);
+            // Allow exporting every fence, see `Device::fence_fd`
+            let mut export_info = vk::ExportSemaphoreCreateInfo::builder()
+                .handle_types(vk::ExternalSemaphoreHandleTypeFlags::OPAQUE_FD);
+            if self.external_semaphore_fd().is_ok() {
+                vk_info = vk_info.push_next(&mut export_info);
+            }
             let raw = unsafe { self.shared.raw.create_semaphore(&vk_info, None) }?;
             super::Fence::TimelineSemaphore(raw)
         } else {
@@ -1950,6 +3092,10 @@ impl crate::Device<super::Api> for super::Device {
                 match result {
                     Ok(()) => Ok(true),
                     Err(vk::Result::TIMEOUT) => Ok(false),
+                    Err(vk::Result::ERROR_DEVICE_LOST) => {
+                        unsafe { self.shared.report_checkpoints() };
+                        Err(crate::DeviceError::Lost)
+                    }
                     Err(other) => Err(other.into()),
                 }
             }
@@ -1968,6 +3114,10 @@ impl crate::Device<super::Api> for super::Device {
                             } {
                                 Ok(()) => Ok(true),
                                 Err(vk::Result::TIMEOUT) => Ok(false),
+                                Err(vk::Result::ERROR_DEVICE_LOST) => {
+                                    unsafe { self.shared.report_checkpoints() };
+                                    Err(crate::DeviceError::Lost)
+                                }
                                 Err(other) => Err(other.into()),
                             }
                         }
@@ -2010,6 +3160,141 @@ impl crate::Device<super::Api> for super::Device {
             }
         }
     }
+
+    unsafe fn allocator_report(&self) -> Option<crate::AllocatorReport> {
+        Some(crate::AllocatorReport {
+            allocation_count: Some(self.memory_stats().allocation_count as u64),
+            allocated_bytes: None,
+        })
+    }
+
+    unsafe fn create_acceleration_structure(
+        &self,
+        desc: &crate::AccelerationStructureDescriptor,
+    ) -> Result<super::AccelerationStructure, crate::DeviceError> {
+        let ray_tracing_fn = self.acceleration_structure_fn();
+
+        let mut vk_buffer_info = vk::BufferCreateInfo::builder()
+            .size(desc.size)
+            .usage(
+                vk::BufferUsageFlags::ACCELERATION_STRUCTURE_STORAGE_KHR
+                    | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
+            )
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+        if !self.shared.concurrent_queue_families.is_empty() {
+            vk_buffer_info = vk_buffer_info
+                .sharing_mode(vk::SharingMode::CONCURRENT)
+                .queue_family_indices(&self.shared.concurrent_queue_families);
+        }
+
+        let buffer = unsafe { self.shared.raw.create_buffer(&vk_buffer_info, None)? };
+        let req = unsafe { self.shared.raw.get_buffer_memory_requirements(buffer) };
+
+        let block = unsafe {
+            self.mem_allocator.lock().alloc(
+                &*self.shared,
+                gpu_alloc::Request {
+                    size: req.size,
+                    align_mask: req.alignment - 1,
+                    usage: gpu_alloc::UsageFlags::FAST_DEVICE_ACCESS
+                        | gpu_alloc::UsageFlags::DEVICE_ADDRESS,
+                    memory_types: req.memory_type_bits & self.valid_ash_memory_types,
+                },
+            )?
+        };
+
+        unsafe {
+            self.shared
+                .raw
+                .bind_buffer_memory(buffer, *block.memory(), block.offset())?
+        };
+
+        let vk_info = vk::AccelerationStructureCreateInfoKHR::builder()
+            .buffer(buffer)
+            .offset(0)
+            .size(desc.size)
+            .ty(conv::map_acceleration_structure_format(desc.format));
+
+        let raw = unsafe { ray_tracing_fn.create_acceleration_structure(&vk_info, None)? };
+
+        if let Some(label) = desc.label {
+            unsafe {
+                self.shared
+                    .set_object_name(vk::ObjectType::ACCELERATION_STRUCTURE_KHR, raw, label)
+            };
+        }
+
+        Ok(super::AccelerationStructure {
+            raw,
+            buffer,
+            block: Mutex::new(block),
+        })
+    }
+
+    unsafe fn get_acceleration_structure_build_sizes(
+        &self,
+        desc: &crate::GetAccelerationStructureBuildSizesDescriptor<super::Api>,
+    ) -> crate::AccelerationStructureBuildSizes {
+        let ray_tracing_fn = self.acceleration_structure_fn();
+
+        let (geometries, ranges) =
+            unsafe { self.shared.map_acceleration_structure_entries(desc.entries) };
+        let primitive_counts = ranges
+            .iter()
+            .map(|range| range.primitive_count)
+            .collect::<Vec<_>>();
+        let ty = match *desc.entries {
+            crate::AccelerationStructureEntries::Instances(_) => {
+                vk::AccelerationStructureTypeKHR::TOP_LEVEL
+            }
+            _ => vk::AccelerationStructureTypeKHR::BOTTOM_LEVEL,
+        };
+
+        let vk_info = vk::AccelerationStructureBuildGeometryInfoKHR::builder()
+            .ty(ty)
+            .flags(conv::map_acceleration_structure_build_flags(desc.flags))
+            .geometries(&geometries);
+
+        let raw = unsafe {
+            ray_tracing_fn.get_acceleration_structure_build_sizes(
+                vk::AccelerationStructureBuildTypeKHR::DEVICE,
+                &vk_info,
+                &primitive_counts,
+            )
+        };
+
+        crate::AccelerationStructureBuildSizes {
+            acceleration_structure_size: raw.acceleration_structure_size,
+            update_scratch_size: raw.update_scratch_size,
+            build_scratch_size: raw.build_scratch_size,
+        }
+    }
+
+    unsafe fn get_acceleration_structure_device_address(
+        &self,
+        acceleration_structure: &super::AccelerationStructure,
+    ) -> wgt::BufferAddress {
+        let ray_tracing_fn = self.acceleration_structure_fn();
+        let vk_info = vk::AccelerationStructureDeviceAddressInfoKHR::builder()
+            .acceleration_structure(acceleration_structure.raw);
+        unsafe { ray_tracing_fn.get_acceleration_structure_device_address(&vk_info) }
+    }
+
+    unsafe fn destroy_acceleration_structure(
+        &self,
+        acceleration_structure: super::AccelerationStructure,
+    ) {
+        let ray_tracing_fn = self.acceleration_structure_fn();
+        unsafe {
+            ray_tracing_fn.destroy_acceleration_structure(acceleration_structure.raw, None);
+            self.shared
+                .raw
+                .destroy_buffer(acceleration_structure.buffer, None);
+            self.mem_allocator
+                .lock()
+                .dealloc(&*self.shared, acceleration_structure.block.into_inner());
+        }
+    }
 }
 
 impl From<gpu_alloc::AllocationError> for crate::DeviceError {
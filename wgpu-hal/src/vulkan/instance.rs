@@ -161,6 +161,13 @@ impl super::Swapchain {
         unsafe { device.destroy_fence(self.fence, None) };
         self
     }
+
+    /// Destroys the swapchains retired by reconfiguring into this one.
+    unsafe fn destroy_retired(&mut self) {
+        for raw in self.retired.drain(..) {
+            unsafe { self.functor.destroy_swapchain(raw, None) };
+        }
+    }
 }
 
 impl super::InstanceShared {
@@ -225,6 +232,11 @@ impl super::Instance {
             // VK_KHR_win32_surface
             extensions.push(khr::Win32Surface::name());
         }
+        if cfg!(not(any(target_os = "macos", target_os = "ios"))) {
+            // VK_KHR_display
+            // Allows presenting directly to a display, without a window system
+            extensions.push(khr::Display::name());
+        }
         if cfg!(target_os = "macos") {
             // VK_EXT_metal_surface
             extensions.push(ext::MetalSurface::name());
@@ -240,6 +252,11 @@ impl super::Instance {
         // Provid wide color gamut
         extensions.push(vk::ExtSwapchainColorspaceFn::name());
 
+        // VK_KHR_get_surface_capabilities2 and VK_KHR_surface_protected_capabilities
+        // Used to check whether protected swapchains can be created
+        extensions.push(khr::GetSurfaceCapabilities2::name());
+        extensions.push(vk::KhrSurfaceProtectedCapabilitiesFn::name());
+
         // VK_KHR_get_physical_device_properties2
         // Even though the extension was promoted to Vulkan 1.1, we still require the extension
         // so that we don't have to conditionally use the functions provided by the 1.1 instance
@@ -267,7 +284,8 @@ impl super::Instance {
     ///   same entry, driver_api_version and flags.
     /// - `android_sdk_version` is ignored and can be `0` for all platforms besides Android
     ///
-    /// If `debug_utils_user_data` is `Some`, then the validation layer is
+    /// If `extensions` contains `VK_EXT_debug_utils`, it is used to name objects.
+    /// If `debug_utils_user_data` is also `Some`, then the validation layer is
     /// available, so create a [`vk::DebugUtilsMessengerEXT`].
     #[allow(clippy::too_many_arguments)]
     pub unsafe fn from_raw(
@@ -283,14 +301,14 @@ impl super::Instance {
     ) -> Result<Self, crate::InstanceError> {
         log::info!("Instance version: 0x{:x}", driver_api_version);
 
-        let debug_utils = if let Some(debug_callback_user_data) = debug_utils_user_data {
-            if extensions.contains(&ext::DebugUtils::name()) {
-                log::info!("Enabling debug utils");
+        let debug_utils = if extensions.contains(&ext::DebugUtils::name()) {
+            log::info!("Enabling debug utils");
+            let extension = ext::DebugUtils::new(&entry, &raw_instance);
+            let messenger = if let Some(debug_callback_user_data) = debug_utils_user_data {
                 // Move the callback data to the heap, to ensure it will never be
                 // moved.
                 let callback_data = Box::new(debug_callback_user_data);
 
-                let extension = ext::DebugUtils::new(&entry, &raw_instance);
                 // having ERROR unconditionally because Vk doesn't like empty flags
                 let mut severity = vk::DebugUtilsMessageSeverityFlagsEXT::ERROR;
                 if log::max_level() >= log::LevelFilter::Debug {
@@ -313,22 +331,22 @@ impl super::Instance {
                     )
                     .pfn_user_callback(Some(debug_utils_messenger_callback))
                     .user_data(user_data_ptr as *mut _);
-                let messenger =
+                let raw =
                     unsafe { extension.create_debug_utils_messenger(&vk_info, None) }.unwrap();
-                Some(super::DebugUtils {
-                    extension,
-                    messenger,
-                    callback_data,
-                })
+                Some(super::DebugUtilsMessenger { raw, callback_data })
             } else {
-                log::info!("Debug utils not enabled: extension not listed");
+                log::info!(
+                    "Debug messenger not created: \
+                        debug_utils_user_data not passed to Instance::from_raw"
+                );
                 None
-            }
+            };
+            Some(super::DebugUtils {
+                extension,
+                messenger,
+            })
         } else {
-            log::info!(
-                "Debug utils not enabled: \
-                        debug_utils_user_data not passed to Instance::from_raw"
-            );
+            log::info!("Debug utils not enabled: extension not listed");
             None
         };
 
@@ -517,6 +535,146 @@ impl super::Instance {
         Ok(self.create_surface_from_vk_surface_khr(surface))
     }
 
+    /// Returns the physical device and properties of every display, in a stable order.
+    fn displays(
+        &self,
+        display_loader: &khr::Display,
+    ) -> Result<Vec<(vk::PhysicalDevice, vk::DisplayPropertiesKHR)>, crate::InstanceError> {
+        if !self.shared.extensions.contains(&khr::Display::name()) {
+            log::warn!("Vulkan driver does not support VK_KHR_display");
+            return Err(crate::InstanceError);
+        }
+
+        let raw_devices = unsafe { self.shared.raw.enumerate_physical_devices() }.map_err(|e| {
+            log::error!("enumerate_physical_devices: {}", e);
+            crate::InstanceError
+        })?;
+
+        let mut displays = Vec::new();
+        for phd in raw_devices {
+            match unsafe { display_loader.get_physical_device_display_properties(phd) } {
+                Ok(properties) => {
+                    displays.extend(properties.into_iter().map(|display| (phd, display)))
+                }
+                Err(e) => log::warn!("get_physical_device_display_properties: {}", e),
+            }
+        }
+        Ok(displays)
+    }
+
+    /// Lists the displays that [`Self::create_surface_from_display`] can present to.
+    pub fn enumerate_displays(&self) -> Vec<wgt::DisplayInfo> {
+        let display_loader = khr::Display::new(&self.shared.entry, &self.shared.raw);
+        self.displays(&display_loader)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, display)| wgt::DisplayInfo {
+                name: if display.display_name.is_null() {
+                    String::new()
+                } else {
+                    unsafe { CStr::from_ptr(display.display_name) }
+                        .to_string_lossy()
+                        .into_owned()
+                },
+                width: display.physical_resolution.width,
+                height: display.physical_resolution.height,
+            })
+            .collect()
+    }
+
+    /// Creates a surface that presents directly to a display, without a window system.
+    ///
+    /// `display` indexes the list returned by [`Self::enumerate_displays`]. The display is
+    /// driven in the mode with the highest refresh rate at its native resolution, on the first
+    /// plane able to show it.
+    pub fn create_surface_from_display(
+        &self,
+        display: usize,
+    ) -> Result<super::Surface, crate::InstanceError> {
+        let display_loader = khr::Display::new(&self.shared.entry, &self.shared.raw);
+        let (phd, display) = match self.displays(&display_loader)?.get(display) {
+            Some(&(phd, display)) => (phd, display),
+            None => {
+                log::warn!("Display {} does not exist", display);
+                return Err(crate::InstanceError);
+            }
+        };
+
+        let modes = unsafe { display_loader.get_display_mode_properties(phd, display.display) }
+            .unwrap_or_default();
+        let native = display.physical_resolution;
+        let mode = modes
+            .iter()
+            .filter(|mode| mode.parameters.visible_region == native)
+            .max_by_key(|mode| mode.parameters.refresh_rate)
+            .or_else(|| modes.first())
+            .ok_or_else(|| {
+                log::warn!("Display {:?} has no modes", display.display);
+                crate::InstanceError
+            })?;
+
+        let planes = unsafe { display_loader.get_physical_device_display_plane_properties(phd) }
+            .unwrap_or_default();
+        let (plane_index, plane) = planes
+            .iter()
+            .enumerate()
+            .find(|&(index, plane)| {
+                (plane.current_display == vk::DisplayKHR::null()
+                    || plane.current_display == display.display)
+                    && unsafe {
+                        display_loader.get_display_plane_supported_displays(phd, index as u32)
+                    }
+                    .map_or(false, |supported| supported.contains(&display.display))
+            })
+            .map(|(index, plane)| (index as u32, plane))
+            .ok_or_else(|| {
+                log::warn!("Display {:?} has no usable plane", display.display);
+                crate::InstanceError
+            })?;
+
+        let capabilities = unsafe {
+            display_loader.get_display_plane_capabilities(phd, mode.display_mode, plane_index)
+        }
+        .map_err(|e| {
+            log::error!("get_display_plane_capabilities: {}", e);
+            crate::InstanceError
+        })?;
+        let alpha_mode = [
+            vk::DisplayPlaneAlphaFlagsKHR::OPAQUE,
+            vk::DisplayPlaneAlphaFlagsKHR::GLOBAL,
+            vk::DisplayPlaneAlphaFlagsKHR::PER_PIXEL,
+            vk::DisplayPlaneAlphaFlagsKHR::PER_PIXEL_PREMULTIPLIED,
+        ]
+        .into_iter()
+        .find(|&flag| capabilities.supported_alpha.contains(flag))
+        .unwrap_or(vk::DisplayPlaneAlphaFlagsKHR::OPAQUE);
+
+        log::info!(
+            "Presenting to display {:?} at {}x{}@{}mHz on plane {}",
+            display.display,
+            mode.parameters.visible_region.width,
+            mode.parameters.visible_region.height,
+            mode.parameters.refresh_rate,
+            plane_index
+        );
+
+        let info = vk::DisplaySurfaceCreateInfoKHR::builder()
+            .display_mode(mode.display_mode)
+            .plane_index(plane_index)
+            .plane_stack_index(plane.current_stack_index)
+            .transform(vk::SurfaceTransformFlagsKHR::IDENTITY)
+            .global_alpha(1.0)
+            .alpha_mode(alpha_mode)
+            .image_extent(mode.parameters.visible_region);
+        let surface =
+            unsafe { display_loader.create_display_plane_surface(&info, None) }.map_err(|e| {
+                log::error!("create_display_plane_surface: {}", e);
+                crate::InstanceError
+            })?;
+
+        Ok(self.create_surface_from_vk_surface_khr(surface))
+    }
+
     fn create_surface_from_vk_surface_khr(&self, surface: vk::SurfaceKHR) -> super::Surface {
         let functor = khr::Surface::new(&self.shared.entry, &self.shared.raw);
         super::Surface {
@@ -528,12 +686,45 @@ impl super::Instance {
     }
 }
 
+impl super::Surface {
+    /// Returns whether protected swapchains can be created for this surface.
+    ///
+    /// Requires `VK_KHR_surface_protected_capabilities`.
+    pub(super) fn supports_protected(&self, phd: vk::PhysicalDevice) -> bool {
+        let extensions = &self.instance.extensions;
+        if !extensions.contains(&khr::GetSurfaceCapabilities2::name())
+            || !extensions.contains(&vk::KhrSurfaceProtectedCapabilitiesFn::name())
+        {
+            return false;
+        }
+
+        let loader = khr::GetSurfaceCapabilities2::new(&self.instance.entry, &self.instance.raw);
+        let surface_info = vk::PhysicalDeviceSurfaceInfo2KHR::builder().surface(self.raw);
+        let mut protected_caps = vk::SurfaceProtectedCapabilitiesKHR::default();
+        let mut caps = vk::SurfaceCapabilities2KHR::builder().push_next(&mut protected_caps);
+        let result = unsafe {
+            (loader.fp().get_physical_device_surface_capabilities2_khr)(
+                phd,
+                &*surface_info,
+                &mut *caps,
+            )
+        };
+        if result != vk::Result::SUCCESS {
+            log::warn!("get_physical_device_surface_capabilities2: {:?}", result);
+            return false;
+        }
+        protected_caps.supports_protected == vk::TRUE
+    }
+}
+
 impl Drop for super::InstanceShared {
     fn drop(&mut self) {
         unsafe {
             if let Some(du) = self.debug_utils.take() {
-                du.extension
-                    .destroy_debug_utils_messenger(du.messenger, None);
+                if let Some(messenger) = du.messenger {
+                    du.extension
+                        .destroy_debug_utils_messenger(messenger.raw, None);
+                }
             }
             if let Some(_drop_guard) = self.drop_guard.take() {
                 self.raw.destroy_instance(None);
@@ -807,7 +998,8 @@ impl crate::Surface<super::Api> for super::Surface {
 
     unsafe fn unconfigure(&mut self, device: &super::Device) {
         if let Some(sc) = self.swapchain.take() {
-            let swapchain = unsafe { sc.release_resources(&device.shared.raw) };
+            let mut swapchain = unsafe { sc.release_resources(&device.shared.raw) };
+            unsafe { swapchain.destroy_retired() };
             unsafe { swapchain.functor.destroy_swapchain(swapchain.raw, None) };
         }
     }
@@ -870,6 +1062,10 @@ impl crate::Surface<super::Api> for super::Surface {
             .map_err(crate::DeviceError::from)?;
         unsafe { sc.device.raw.reset_fences(fences) }.map_err(crate::DeviceError::from)?;
 
+        if sc.first_presented_index == Some(index) {
+            unsafe { sc.destroy_retired() };
+        }
+
         // https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkRenderPassBeginInfo.html#VUID-VkRenderPassBeginInfo-framebuffer-03209
         let raw_flags = if sc
             .raw_flags
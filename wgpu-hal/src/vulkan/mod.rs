@@ -31,16 +31,18 @@ mod conv;
 mod device;
 mod instance;
 
-use std::{borrow::Borrow, ffi::CStr, fmt, num::NonZeroU32, sync::Arc};
+use std::{borrow::Borrow, ffi::CStr, fmt, num::NonZeroU32, ops::Range, ptr, sync::Arc};
 
 use arrayvec::ArrayVec;
 use ash::{
-    extensions::{ext, khr},
+    extensions::{ext, khr, nv},
     vk,
 };
 use parking_lot::Mutex;
 
 const MILLIS_TO_NANOS: u64 = 1_000_000;
+/// Maximum number of descriptor sets kept for reuse per bind group layout.
+const MAX_RECYCLED_DESC_SETS_PER_LAYOUT: usize = 64;
 const MAX_TOTAL_ATTACHMENTS: usize = crate::MAX_COLOR_ATTACHMENTS * 2 + 1;
 
 #[derive(Clone)]
@@ -70,11 +72,22 @@ impl crate::Api for Api {
     type ShaderModule = ShaderModule;
     type RenderPipeline = RenderPipeline;
     type ComputePipeline = ComputePipeline;
+    type PipelineCache = PipelineCache;
+
+    type AccelerationStructure = AccelerationStructure;
 }
 
 struct DebugUtils {
     extension: ext::DebugUtils,
-    messenger: vk::DebugUtilsMessengerEXT,
+    /// The debug messenger, if the validation layer is available.
+    ///
+    /// Without it, the extension is still used to name objects and to
+    /// insert debug markers.
+    messenger: Option<DebugUtilsMessenger>,
+}
+
+struct DebugUtilsMessenger {
+    raw: vk::DebugUtilsMessengerEXT,
 
     /// Owning pointer to the debug messenger callback user data.
     ///
@@ -128,6 +141,16 @@ struct Swapchain {
     images: Vec<vk::Image>,
     config: crate::SurfaceConfiguration,
     view_formats: Vec<wgt::TextureFormat>,
+    /// Swapchains that were replaced by this one when reconfiguring.
+    ///
+    /// They are passed as `oldSwapchain` and kept alive until the presentation
+    /// engine is done with their images, see `first_presented_index`.
+    retired: Vec<vk::SwapchainKHR>,
+    /// Index of the first image presented from this swapchain.
+    ///
+    /// Once it is acquired again, the presentation engine has moved on to this
+    /// swapchain's images and the retired swapchains can be destroyed.
+    first_presented_index: Option<u32>,
 }
 
 pub struct Surface {
@@ -172,8 +195,106 @@ enum ExtensionFn<T> {
 struct DeviceExtensionFunctions {
     draw_indirect_count: Option<khr::DrawIndirectCount>,
     timeline_semaphore: Option<ExtensionFn<khr::TimelineSemaphore>>,
+    diagnostic_checkpoints: Option<nv::DeviceDiagnosticCheckpoints>,
+    buffer_marker: Option<vk::AmdBufferMarkerFn>,
+    external_memory_fd: Option<khr::ExternalMemoryFd>,
+    external_semaphore_fd: Option<khr::ExternalSemaphoreFd>,
+    fragment_shading_rate: Option<vk::KhrFragmentShadingRateFn>,
+    acceleration_structure: Option<khr::AccelerationStructure>,
+    pipeline_executable_properties: Option<khr::PipelineExecutableProperties>,
+}
+
+/// Debug labels recorded as `VK_NV_device_diagnostic_checkpoints` markers,
+/// or written to [`BufferMarkers`].
+///
+/// Checkpoint markers are opaque values that we get back when the device
+/// is lost. Each distinct label is stored here once, and the
+/// marker value encodes its index (see [`CheckpointLabels::marker`]), so that
+/// the reported markers can be mapped back to the labels of the last passes
+/// and debug groups that were executed.
+///
+/// Labels are counted for every checkpoint that command encoders record, and
+/// released when the encoders are reset, which happens once their submissions
+/// have retired. The slots of unused labels are reused.
+#[derive(Default)]
+struct CheckpointLabels {
+    /// Each label with the number of checkpoints that use it, which is zero
+    /// for free slots.
+    names: Vec<(String, usize)>,
+    free: Vec<usize>,
+    lookup: rustc_hash::FxHashMap<String, usize>,
+}
+
+impl CheckpointLabels {
+    fn intern(&mut self, label: &str) -> usize {
+        if let Some(&index) = self.lookup.get(label) {
+            self.names[index].1 += 1;
+            return index;
+        }
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.names[index] = (label.to_string(), 1);
+                index
+            }
+            None => {
+                self.names.push((label.to_string(), 1));
+                self.names.len() - 1
+            }
+        };
+        self.lookup.insert(label.to_string(), index);
+        index
+    }
+
+    /// Release one use of each label in `indices`.
+    fn release(&mut self, indices: impl Iterator<Item = usize>) {
+        for index in indices {
+            let (ref mut name, ref mut count) = self.names[index];
+            *count -= 1;
+            if *count == 0 {
+                self.lookup.remove(name.as_str());
+                name.clear();
+                self.free.push(index);
+            }
+        }
+    }
+
+    /// Encode the label `index` as a checkpoint marker.
+    ///
+    /// The marker is never zero, and its lowest bit tells whether it marks
+    /// the end of a debug group rather than its beginning.
+    fn marker(index: usize, is_end: bool) -> usize {
+        ((index << 1) | is_end as usize) + 1
+    }
+
+    fn describe(&self, marker: usize) -> Option<String> {
+        let value = marker.checked_sub(1)?;
+        let name = match self.names.get(value >> 1)? {
+            &(ref name, count) if count != 0 => name,
+            _ => return None,
+        };
+        Some(if value & 1 != 0 {
+            format!("end of {name:?}")
+        } else {
+            format!("{name:?}")
+        })
+    }
 }
 
+/// Host-visible buffer that `VK_AMD_buffer_marker` writes checkpoint markers to.
+///
+/// Each checkpoint writes its marker twice: to the first `u32` once the GPU
+/// reaches it, and to the second once all the work before it has completed.
+/// Both can still be read after the device is lost.
+struct BufferMarkers {
+    raw: vk::Buffer,
+    memory: vk::DeviceMemory,
+    /// Persistent mapping of the host-coherent `memory`.
+    mapped: ptr::NonNull<[u32; 2]>,
+}
+
+unsafe impl Send for BufferMarkers {}
+unsafe impl Sync for BufferMarkers {}
+
 /// Set of internal capabilities, which don't show up in the exposed
 /// device geometry, but affect the code paths taken internally.
 #[derive(Clone, Debug)]
@@ -191,11 +312,25 @@ struct PrivateCapabilities {
     /// Ability to present contents to any screen. Only needed to work around broken platform configurations.
     can_present: bool,
     non_coherent_map_mask: wgt::BufferAddress,
+    /// Whether `robustBufferAccess` is supported, in which case every device enables it.
+    ///
+    /// `DownlevelFlags::ROBUST_INDIRECT_EXECUTION` is reported based on this, so it must
+    /// not depend on the features requested at device creation.
     robust_buffer_access: bool,
     robust_image_access: bool,
     robust_buffer_access2: bool,
     robust_image_access2: bool,
+    /// Whether sampled images, storage images and storage buffers can be
+    /// updated after bind.
+    ///
+    /// Layouts with partially bound binding arrays of these are created with
+    /// `UPDATE_AFTER_BIND_POOL`, and their descriptors count against the much
+    /// larger `maxDescriptorSetUpdateAfterBind*` limits of the device.
+    update_after_bind_descriptors: bool,
     zero_initialize_workgroup_memory: bool,
+    /// Whether timeline semaphores can be exported and imported as opaque
+    /// file descriptors with `VK_KHR_external_semaphore_fd`.
+    external_timeline_semaphores: bool,
 }
 
 bitflags::bitflags!(
@@ -276,19 +411,95 @@ struct DeviceShared {
     enabled_extensions: Vec<&'static CStr>,
     extension_fns: DeviceExtensionFunctions,
     vendor_id: u32,
+    device_id: u32,
     timestamp_period: f32,
+    /// Cache used for every pipeline created on this device without a
+    /// [`PipelineCache`], see [`Device::pipeline_cache_data`].
+    pipeline_cache: vk::PipelineCache,
+    pipeline_cache_uuid: [u8; vk::UUID_SIZE],
+    /// Number of viewports and scissor rects of every render pipeline, which is
+    /// `wgt::MAX_VIEWPORTS` if `Features::MULTI_VIEWPORT` is enabled and 1 otherwise.
+    viewport_count: u32,
+    /// Whether `Features::DEPTH_BOUNDS_TEST` is enabled, making the depth bounds
+    /// dynamic state of every render pipeline.
+    depth_bounds_test: bool,
+    /// Queue families that buffers and images are shared between, when queues
+    /// from more than one family were opened.
+    concurrent_queue_families: Vec<u32>,
     private_caps: PrivateCapabilities,
     workarounds: Workarounds,
     render_passes: Mutex<rustc_hash::FxHashMap<RenderPassKey, vk::RenderPass>>,
     framebuffers: Mutex<rustc_hash::FxHashMap<FramebufferKey, vk::Framebuffer>>,
+    checkpoint_labels: Mutex<CheckpointLabels>,
+    buffer_markers: Option<BufferMarkers>,
+}
+
+/// Type of a file descriptor referring to external memory.
+///
+/// See [`Device::import_texture_fd`] and [`Device::create_exportable_texture`].
+///
+/// Only file descriptors are supported. Windows handles, from `VK_KHR_external_memory_win32`,
+/// aren't, so importing and exporting texture memory always fails on Windows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExternalMemoryFdType {
+    /// A descriptor only meaningful to the same driver and device, from `VK_KHR_external_memory_fd`.
+    Opaque,
+    /// A Linux DMA-BUF, from `VK_EXT_external_memory_dma_buf`.
+    DmaBuf,
+}
+
+/// The explicit layout of an imported DMA-BUF image.
+#[derive(Clone, Debug)]
+pub struct DrmFormatModifier {
+    /// The DRM format modifier, from `drm_fourcc.h`.
+    pub modifier: u64,
+    /// The layout of each memory plane of the image. The `size` of each is ignored,
+    /// and the `array_pitch` and `depth_pitch` must be zero.
+    pub plane_layouts: Vec<vk::SubresourceLayout>,
+}
+
+/// The budget of a memory heap, see [`Adapter::memory_budget`].
+#[derive(Clone, Copy, Debug)]
+pub struct MemoryHeapBudget {
+    /// Total size of the heap in bytes.
+    pub size: u64,
+    /// Whether the heap is local to the device, that is, VRAM on discrete GPUs.
+    pub device_local: bool,
+    /// How many bytes the process can allocate from the heap before allocations
+    /// may fail or degrade performance.
+    pub budget: u64,
+    /// How many bytes the process currently uses from the heap, if known.
+    pub usage: Option<u64>,
+}
+
+/// Statistics about the device memory allocations, see [`Device::memory_stats`].
+#[derive(Clone, Copy, Debug)]
+pub struct MemoryStats {
+    /// Number of `VkDeviceMemory` objects currently allocated, both the blocks
+    /// shared by many resources and the dedicated ones.
+    pub allocation_count: u32,
+    /// The device's `maxMemoryAllocationCount` limit.
+    pub max_allocation_count: u32,
 }
 
 pub struct Device {
     shared: Arc<DeviceShared>,
     mem_allocator: Mutex<gpu_alloc::GpuAllocator<vk::DeviceMemory>>,
+    max_memory_allocation_count: u32,
     desc_allocator:
         Mutex<gpu_descriptor::DescriptorAllocator<vk::DescriptorPool, vk::DescriptorSet>>,
+    /// Descriptor sets of destroyed bind groups, by layout. They are rewritten for
+    /// new bind groups instead of going back to their pool.
+    recycled_desc_sets: Mutex<
+        rustc_hash::FxHashMap<
+            vk::DescriptorSetLayout,
+            Vec<gpu_descriptor::DescriptorSet<vk::DescriptorSet>>,
+        >,
+    >,
     valid_ash_memory_types: u32,
+    /// Memory types for resources created with `MemoryFlags::PROTECTED`.
+    protected_ash_memory_types: u32,
+    memory_types: Vec<vk::MemoryType>,
     naga_options: naga::back::spv::Options<'static>,
     #[cfg(feature = "renderdoc")]
     render_doc: crate::auxil::renderdoc::RenderDoc,
@@ -299,6 +510,8 @@ pub struct Queue {
     swapchain_fn: khr::Swapchain,
     device: Arc<DeviceShared>,
     family_index: u32,
+    /// Pipeline stages that the queue family supports in barriers.
+    supported_stages: vk::PipelineStageFlags,
     /// We use a redundant chain of semaphores to pass on the signal
     /// from submissions to the last present, since it's required by the
     /// specification.
@@ -306,6 +519,8 @@ pub struct Queue {
     /// [Intel hangs in `anv_queue_finish`](https://gitlab.freedesktop.org/mesa/mesa/-/issues/5508).
     relay_semaphores: [vk::Semaphore; 2],
     relay_index: Option<usize>,
+    /// Timeline semaphore values the next submission waits on.
+    pending_waits: Vec<(vk::Semaphore, crate::FenceValue)>,
 }
 
 #[derive(Debug)]
@@ -340,6 +555,74 @@ pub struct TextureView {
     raw: vk::ImageView,
     layers: NonZeroU32,
     attachment: FramebufferAttachment,
+    /// 2D views of depth slices, for 3D views used as color targets.
+    depth_slices: Option<DepthSliceViews>,
+}
+
+/// 2D views of the depth slices of a 3D view, created when a slice is first
+/// rendered to.
+#[derive(Debug)]
+struct DepthSliceViews {
+    image: vk::Image,
+    format: vk::Format,
+    components: vk::ComponentMapping,
+    subresource_range: vk::ImageSubresourceRange,
+    /// Usage of the views, if it is restricted from the usage of the image.
+    usage: Option<vk::ImageUsageFlags>,
+    views: Mutex<rustc_hash::FxHashMap<u32, vk::ImageView>>,
+}
+
+impl DepthSliceViews {
+    fn get_or_create(
+        &self,
+        device: &DeviceShared,
+        slice: u32,
+    ) -> Result<vk::ImageView, vk::Result> {
+        let mut views = self.views.lock();
+        if let Some(&raw) = views.get(&slice) {
+            return Ok(raw);
+        }
+        let mut vk_info = vk::ImageViewCreateInfo::builder()
+            .image(self.image)
+            .view_type(vk::ImageViewType::TYPE_2D)
+            .format(self.format)
+            .components(self.components)
+            .subresource_range(vk::ImageSubresourceRange {
+                base_array_layer: slice,
+                layer_count: 1,
+                ..self.subresource_range
+            });
+        let mut usage_info;
+        if let Some(usage) = self.usage {
+            usage_info = vk::ImageViewUsageCreateInfo::builder().usage(usage).build();
+            vk_info = vk_info.push_next(&mut usage_info);
+        }
+        let raw = unsafe { device.raw.create_image_view(&vk_info, None) }?;
+        views.insert(slice, raw);
+        Ok(raw)
+    }
+}
+
+impl TextureView {
+    /// Returns the image view and framebuffer attachment to render to, which is
+    /// a single depth slice of a 3D view if `depth_slice` is given.
+    fn attachment_for(
+        &self,
+        device: &DeviceShared,
+        depth_slice: Option<u32>,
+    ) -> Result<(vk::ImageView, FramebufferAttachment), vk::Result> {
+        match (depth_slice, self.depth_slices.as_ref()) {
+            (Some(slice), Some(depth_slices)) => {
+                let raw = depth_slices.get_or_create(device, slice)?;
+                let mut attachment = self.attachment.clone();
+                if attachment.raw != vk::ImageView::null() {
+                    attachment.raw = raw;
+                }
+                Ok((raw, attachment))
+            }
+            _ => Ok((self.raw, self.attachment.clone())),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -354,6 +637,8 @@ pub struct BindGroupLayout {
     types: Box<[(vk::DescriptorType, u32)]>,
     /// Map of binding index to size,
     binding_arrays: Vec<(u32, NonZeroU32)>,
+    /// Whether the layout was created with `UPDATE_AFTER_BIND_POOL`.
+    update_after_bind: bool,
 }
 
 #[derive(Debug)]
@@ -365,12 +650,12 @@ pub struct PipelineLayout {
 #[derive(Debug)]
 pub struct BindGroup {
     set: gpu_descriptor::DescriptorSet<vk::DescriptorSet>,
+    layout: vk::DescriptorSetLayout,
 }
 
 #[derive(Default)]
 struct Temp {
     marker: Vec<u8>,
-    buffer_barriers: Vec<vk::BufferMemoryBarrier>,
     image_barriers: Vec<vk::ImageMemoryBarrier>,
 }
 
@@ -380,7 +665,6 @@ unsafe impl Sync for Temp {}
 impl Temp {
     fn clear(&mut self) {
         self.marker.clear();
-        self.buffer_barriers.clear();
         self.image_barriers.clear();
         //see also - https://github.com/NotIntMan/inplace_it/issues/8
     }
@@ -404,10 +688,21 @@ pub struct CommandEncoder {
     /// If this is true, the active renderpass enabled a debug span,
     /// and needs to be disabled on renderpass close.
     rpass_debug_marker_active: bool,
+    /// Label indices of the currently open debug groups, used to mark
+    /// their ends with diagnostic checkpoints.
+    checkpoint_groups: Vec<usize>,
+    /// Label indices of all the checkpoints recorded since the last reset,
+    /// released on the next one.
+    checkpoint_labels: Vec<usize>,
 
     /// If set, the end of the next render/compute pass will write a timestamp at
     /// the given pool & location.
     end_of_pass_timer_query: Option<(vk::QueryPool, u32)>,
+
+    /// The command pool is protected, see [`crate::CommandEncoderDescriptor::protected`].
+    protected: bool,
+    /// Pipeline stages that the queue family of the command pool supports in barriers.
+    supported_stages: vk::PipelineStageFlags,
 }
 
 impl fmt::Debug for CommandEncoder {
@@ -421,6 +716,7 @@ impl fmt::Debug for CommandEncoder {
 #[derive(Debug)]
 pub struct CommandBuffer {
     raw: vk::CommandBuffer,
+    protected: bool,
 }
 
 #[derive(Debug)]
@@ -430,6 +726,7 @@ pub enum ShaderModule {
     Intermediate {
         naga_shader: crate::NagaShader,
         runtime_checks: bool,
+        zero_initialize_workgroup_memory: bool,
     },
 }
 
@@ -438,11 +735,54 @@ pub struct RenderPipeline {
     raw: vk::Pipeline,
 }
 
+/// A compiled part of a pipeline, usually one shader stage, as reported by
+/// `VK_KHR_pipeline_executable_properties`.
+///
+/// See [`Device::render_pipeline_executables`].
+#[derive(Clone, Debug)]
+pub struct PipelineExecutable {
+    pub name: String,
+    pub description: String,
+    pub stages: wgt::ShaderStages,
+    pub subgroup_size: u32,
+    /// Compiler statistics, such as register counts or spills.
+    pub statistics: Vec<PipelineExecutableStatistic>,
+    /// Textual internal representations, such as the disassembly, as `(name, text)` pairs.
+    pub internal_representations: Vec<(String, String)>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PipelineExecutableStatistic {
+    pub name: String,
+    pub description: String,
+    pub value: PipelineExecutableStatisticValue,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PipelineExecutableStatisticValue {
+    Bool(bool),
+    Int(i64),
+    Uint(u64),
+    Float(f64),
+}
+
 #[derive(Debug)]
 pub struct ComputePipeline {
     raw: vk::Pipeline,
 }
 
+#[derive(Debug)]
+pub struct PipelineCache {
+    raw: vk::PipelineCache,
+}
+
+#[derive(Debug)]
+pub struct AccelerationStructure {
+    raw: vk::AccelerationStructureKHR,
+    buffer: vk::Buffer,
+    block: Mutex<gpu_alloc::MemoryBlock<vk::DeviceMemory>>,
+}
+
 #[derive(Debug)]
 pub struct QuerySet {
     raw: vk::QueryPool,
@@ -460,6 +800,19 @@ pub enum Fence {
 }
 
 impl Fence {
+    /// Returns the raw timeline semaphore backing this fence, if timeline
+    /// semaphores are supported.
+    ///
+    /// The semaphore's counter is signaled with the fence values passed to
+    /// [`crate::Queue::submit`], so it can be waited on or exported for interop
+    /// with other APIs. It must not be signaled or destroyed externally.
+    pub fn raw_timeline_semaphore(&self) -> Option<vk::Semaphore> {
+        match *self {
+            Self::TimelineSemaphore(raw) => Some(raw),
+            Self::FencePool { .. } => None,
+        }
+    }
+
     fn check_active(
         device: &ash::Device,
         mut max_value: crate::FenceValue,
@@ -523,33 +876,77 @@ impl Fence {
     }
 }
 
+impl Queue {
+    unsafe fn new(
+        device: Arc<DeviceShared>,
+        raw: vk::Queue,
+        family_index: u32,
+        family_flags: vk::QueueFlags,
+        swapchain_fn: khr::Swapchain,
+    ) -> Result<Self, crate::DeviceError> {
+        let mut relay_semaphores = [vk::Semaphore::null(); 2];
+        for sem in relay_semaphores.iter_mut() {
+            unsafe {
+                *sem = device
+                    .raw
+                    .create_semaphore(&vk::SemaphoreCreateInfo::builder(), None)?
+            };
+        }
+        Ok(Self {
+            raw,
+            swapchain_fn,
+            device,
+            family_index,
+            supported_stages: conv::map_queue_flags_to_stages(family_flags),
+            relay_semaphores,
+            relay_index: None,
+            pending_waits: Vec::new(),
+        })
+    }
+
+    /// Returns the index of the queue family this queue belongs to.
+    pub fn queue_family_index(&self) -> u32 {
+        self.family_index
+    }
+}
+
 impl crate::Queue<Api> for Queue {
     unsafe fn submit(
         &mut self,
         command_buffers: &[&CommandBuffer],
         signal_fence: Option<(&mut Fence, crate::FenceValue)>,
+        label: crate::Label,
     ) -> Result<(), crate::DeviceError> {
         let vk_cmd_buffers = command_buffers
             .iter()
             .map(|cmd| cmd.raw)
             .collect::<Vec<_>>();
 
-        let mut vk_info = vk::SubmitInfo::builder().command_buffers(&vk_cmd_buffers);
+        // Protected and unprotected command buffers have to be submitted in separate
+        // batches. Each batch is a range of `vk_cmd_buffers`.
+        let mut batches: Vec<(Range<usize>, bool)> = Vec::with_capacity(1);
+        for (index, cmd) in command_buffers.iter().enumerate() {
+            match batches.last_mut() {
+                Some(&mut (ref mut range, protected)) if protected == cmd.protected => {
+                    range.end = index + 1
+                }
+                _ => batches.push((index..index + 1, cmd.protected)),
+            }
+        }
+        if batches.is_empty() {
+            batches.push((0..0, false));
+        }
 
         let mut fence_raw = vk::Fence::null();
-        let mut vk_timeline_info;
         let mut signal_semaphores = [vk::Semaphore::null(), vk::Semaphore::null()];
-        let signal_values;
+        let mut signal_values = [!0, !0];
 
         if let Some((fence, value)) = signal_fence {
             fence.maintain(&self.device.raw)?;
             match *fence {
                 Fence::TimelineSemaphore(raw) => {
-                    signal_values = [!0, value];
+                    signal_values[1] = value;
                     signal_semaphores[1] = raw;
-                    vk_timeline_info = vk::TimelineSemaphoreSubmitInfo::builder()
-                        .signal_semaphore_values(&signal_values);
-                    vk_info = vk_info.push_next(&mut vk_timeline_info);
                 }
                 Fence::FencePool {
                     ref mut active,
@@ -569,12 +966,22 @@ impl crate::Queue<Api> for Queue {
             }
         }
 
-        let wait_stage_mask = [vk::PipelineStageFlags::TOP_OF_PIPE];
+        // Waits on other queues' timeline semaphores, see `Queue::wait_for_fence`
+        let has_timeline_waits = !self.pending_waits.is_empty();
+        let mut wait_semaphores = Vec::with_capacity(self.pending_waits.len() + 1);
+        let mut wait_values = Vec::with_capacity(self.pending_waits.len() + 1);
+        let mut wait_stage_masks = Vec::with_capacity(self.pending_waits.len() + 1);
+        for (raw, value) in self.pending_waits.drain(..) {
+            wait_semaphores.push(raw);
+            wait_values.push(value);
+            wait_stage_masks.push(vk::PipelineStageFlags::ALL_COMMANDS);
+        }
+
         let sem_index = match self.relay_index {
             Some(old_index) => {
-                vk_info = vk_info
-                    .wait_semaphores(&self.relay_semaphores[old_index..old_index + 1])
-                    .wait_dst_stage_mask(&wait_stage_mask);
+                wait_semaphores.push(self.relay_semaphores[old_index]);
+                wait_values.push(!0);
+                wait_stage_masks.push(vk::PipelineStageFlags::TOP_OF_PIPE);
                 (old_index + 1) % self.relay_semaphores.len()
             }
             None => 0,
@@ -587,23 +994,116 @@ impl crate::Queue<Api> for Queue {
         } else {
             2
         };
-        vk_info = vk_info.signal_semaphores(&signal_semaphores[..signal_count]);
+
+        // The first batch waits for the semaphores, and the last one signals them.
+        let last_batch = batches.len() - 1;
+        let mut vk_timeline_infos = vec![vk::TimelineSemaphoreSubmitInfo::default(); batches.len()];
+        let mut vk_protected_infos = vec![vk::ProtectedSubmitInfo::default(); batches.len()];
+        let vk_infos = batches
+            .iter()
+            .zip(
+                vk_timeline_infos
+                    .iter_mut()
+                    .zip(vk_protected_infos.iter_mut()),
+            )
+            .enumerate()
+            .map(
+                |(index, (&(ref range, protected), (timeline_info, protected_info)))| {
+                    let mut vk_info =
+                        vk::SubmitInfo::builder().command_buffers(&vk_cmd_buffers[range.clone()]);
+                    let mut timeline = vk::TimelineSemaphoreSubmitInfo::builder();
+                    let mut needs_timeline = false;
+                    if index == 0 {
+                        vk_info = vk_info
+                            .wait_semaphores(&wait_semaphores)
+                            .wait_dst_stage_mask(&wait_stage_masks);
+                        if has_timeline_waits {
+                            timeline = timeline.wait_semaphore_values(&wait_values);
+                            needs_timeline = true;
+                        }
+                    }
+                    if index == last_batch {
+                        vk_info = vk_info.signal_semaphores(&signal_semaphores[..signal_count]);
+                        if signal_count == 2 {
+                            timeline =
+                                timeline.signal_semaphore_values(&signal_values[..signal_count]);
+                            needs_timeline = true;
+                        }
+                    }
+                    if needs_timeline {
+                        *timeline_info = timeline.build();
+                        vk_info = vk_info.push_next(timeline_info);
+                    }
+                    if protected {
+                        protected_info.protected_submit = vk::TRUE;
+                        vk_info = vk_info.push_next(protected_info);
+                    }
+                    vk_info.build()
+                },
+            )
+            .collect::<Vec<_>>();
+
+        let debug_utils = label.and_then(|label| Some((self.device.debug_messenger()?, label)));
+        if let Some((ext, label)) = debug_utils {
+            let cstr = std::ffi::CString::new(label).unwrap_or_default();
+            let vk_label = vk::DebugUtilsLabelEXT::builder().label_name(&cstr).build();
+            unsafe { ext.queue_begin_debug_utils_label(self.raw, &vk_label) };
+        }
 
         profiling::scope!("vkQueueSubmit");
-        unsafe {
-            self.device
-                .raw
-                .queue_submit(self.raw, &[vk_info.build()], fence_raw)?
-        };
+        let result = unsafe { self.device.raw.queue_submit(self.raw, &vk_infos, fence_raw) };
+        if let Some((ext, _)) = debug_utils {
+            unsafe { ext.queue_end_debug_utils_label(self.raw) };
+        }
+        if result == Err(vk::Result::ERROR_DEVICE_LOST) {
+            unsafe { self.device.report_checkpoints() };
+        }
+        result?;
         Ok(())
     }
 
+    unsafe fn wait_for_fence(
+        &mut self,
+        fence: &Fence,
+        value: crate::FenceValue,
+    ) -> Result<(), crate::DeviceError> {
+        match *fence {
+            // Waited on by the next submission.
+            Fence::TimelineSemaphore(raw) => {
+                self.pending_waits.push((raw, value));
+                Ok(())
+            }
+            Fence::FencePool {
+                last_completed,
+                ref active,
+                free: _,
+            } => {
+                if value <= last_completed {
+                    return Ok(());
+                }
+                match active
+                    .iter()
+                    .find(|&&(active_value, _)| active_value >= value)
+                {
+                    Some(&(_, raw)) => {
+                        unsafe { self.device.raw.wait_for_fences(&[raw], true, !0) }?;
+                        Ok(())
+                    }
+                    None => {
+                        log::error!("No signals reached value {}", value);
+                        Err(crate::DeviceError::Lost)
+                    }
+                }
+            }
+        }
+    }
+
     unsafe fn present(
         &mut self,
         surface: &mut Surface,
         texture: SurfaceTexture,
     ) -> Result<(), crate::SurfaceError> {
-        let ssc = surface.swapchain.as_ref().unwrap();
+        let ssc = surface.swapchain.as_mut().unwrap();
 
         let swapchains = [ssc.raw];
         let image_indices = [texture.index];
@@ -633,6 +1133,7 @@ impl crate::Queue<Api> for Queue {
             #[cfg(not(target_os = "android"))]
             log::warn!("Suboptimal present of frame {}", texture.index);
         }
+        ssc.first_presented_index.get_or_insert(texture.index);
         Ok(())
     }
 
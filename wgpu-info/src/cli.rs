@@ -53,10 +53,10 @@ pub fn main() -> anyhow::Result<()> {
                 std::fs::read_to_string(path)
                     .with_context(|| format!("Could not read from file \"{path}\""))?
             };
-            crate::report::GpuReport::from_json(&json).context("Could not parse JSON")?
+            wgpu_info::GpuReport::from_json(&json).context("Could not parse JSON")?
         }
         // Generate the report natively
-        None => crate::report::GpuReport::generate(),
+        None => wgpu_info::GpuReport::generate(),
     };
 
     // Setup output writer
@@ -2,9 +2,9 @@ use std::io;
 
 use bitflags::Flags;
 
-use crate::{
-    report::{AdapterReport, GpuReport},
+use wgpu_info::{
     texture::{self, TEXTURE_FORMAT_LIST},
+    AdapterReport, GpuReport, SurfaceReport,
 };
 
 trait FlagsExt: Flags {
@@ -62,7 +62,8 @@ fn print_adapter(output: &mut impl io::Write, report: &AdapterReport, idx: usize
         limits,
         downlevel_caps:
         downlevel,
-        texture_format_features
+        texture_format_features,
+        surface,
     } = &report;
 
     //////////////////
@@ -234,6 +235,17 @@ fn print_adapter(output: &mut impl io::Write, report: &AdapterReport, idx: usize
     }
     write!(output, "\t\t {texture_format_whitespace}")?;
     wgpu::TextureFormatFeatureFlags::println_table_footer(output)?;
+
+    /////////////
+    // Surface //
+    /////////////
+
+    if let Some(SurfaceReport { formats, present_modes, alpha_modes }) = surface {
+        writeln!(output, "\n\t Surface:")?;
+        writeln!(output, "\t\t      Formats: {formats:?}")?;
+        writeln!(output, "\t\tPresent Modes: {present_modes:?}")?;
+        writeln!(output, "\t\t  Alpha Modes: {alpha_modes:?}")?;
+    }
     Ok(())
 }
 
@@ -0,0 +1,13 @@
+//! Structured reports of the adapters available to wgpu.
+//!
+//! [`GpuReport::generate`] collects the features, limits, downlevel capabilities and
+//! per-format texture capabilities of every adapter wgpu can enumerate. Reports can be
+//! serialized with serde, e.g. to attach them to diagnostics.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod report;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod texture;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use report::{AdapterReport, GpuReport, SurfaceReport};
@@ -2,19 +2,30 @@ use std::{collections::HashMap, io};
 
 use serde::{Deserialize, Serialize};
 use wgpu::{
-    AdapterInfo, DownlevelCapabilities, Features, Limits, TextureFormat, TextureFormatFeatures,
+    AdapterInfo, CompositeAlphaMode, DownlevelCapabilities, Features, Limits, PresentMode,
+    TextureFormat, TextureFormatFeatures,
 };
 
 use crate::texture;
 
+/// Report of every adapter available to wgpu.
 #[derive(Deserialize, Serialize)]
 pub struct GpuReport {
     pub devices: Vec<AdapterReport>,
 }
 
 impl GpuReport {
+    /// Generates a report for the adapters of all backends.
     pub fn generate() -> Self {
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        Self::generate_for(&instance, None)
+    }
+
+    /// Generates a report for the adapters of `instance`.
+    ///
+    /// If a `surface` created from `instance` is given, each adapter report also lists
+    /// how the adapter can present to it.
+    pub fn generate_for(instance: &wgpu::Instance, surface: Option<&wgpu::Surface>) -> Self {
         let adapters = instance.enumerate_adapters(wgpu::Backends::all());
 
         let mut devices = Vec::with_capacity(adapters.len());
@@ -26,6 +37,14 @@ impl GpuReport {
                 .into_iter()
                 .map(|format| (format, adapter.get_texture_format_features(format)))
                 .collect();
+            let surface = surface.map(|surface| {
+                let caps = surface.get_capabilities(&adapter);
+                SurfaceReport {
+                    formats: caps.formats,
+                    present_modes: caps.present_modes,
+                    alpha_modes: caps.alpha_modes,
+                }
+            });
 
             devices.push(AdapterReport {
                 info: adapter.get_info(),
@@ -33,6 +52,7 @@ impl GpuReport {
                 limits,
                 downlevel_caps,
                 texture_format_features,
+                surface,
             });
         }
 
@@ -48,6 +68,7 @@ impl GpuReport {
     }
 }
 
+/// Capabilities of a single adapter.
 #[derive(Deserialize, Serialize)]
 pub struct AdapterReport {
     pub info: AdapterInfo,
@@ -55,4 +76,15 @@ pub struct AdapterReport {
     pub limits: Limits,
     pub downlevel_caps: DownlevelCapabilities,
     pub texture_format_features: HashMap<TextureFormat, TextureFormatFeatures>,
+    /// Only present if the report was generated for a surface.
+    #[serde(default)]
+    pub surface: Option<SurfaceReport>,
+}
+
+/// How an adapter can present to a surface. Empty if it can't present to it at all.
+#[derive(Deserialize, Serialize)]
+pub struct SurfaceReport {
+    pub formats: Vec<TextureFormat>,
+    pub present_modes: Vec<PresentMode>,
+    pub alpha_modes: Vec<CompositeAlphaMode>,
 }
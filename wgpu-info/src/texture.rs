@@ -1,6 +1,6 @@
 // Lets keep these on one line
 #[rustfmt::skip]
-pub const TEXTURE_FORMAT_LIST: [wgpu::TextureFormat; 114] = [
+pub const TEXTURE_FORMAT_LIST: [wgpu::TextureFormat; 115] = [
     wgpu::TextureFormat::R8Unorm,
     wgpu::TextureFormat::R8Snorm,
     wgpu::TextureFormat::R8Uint,
@@ -48,6 +48,7 @@ pub const TEXTURE_FORMAT_LIST: [wgpu::TextureFormat; 114] = [
     wgpu::TextureFormat::Depth32FloatStencil8,
     wgpu::TextureFormat::Depth24Plus,
     wgpu::TextureFormat::Depth24PlusStencil8,
+    wgpu::TextureFormat::NV12,
     wgpu::TextureFormat::Rgb9e5Ufloat,
     wgpu::TextureFormat::Bc1RgbaUnorm,
     wgpu::TextureFormat::Bc1RgbaUnormSrgb,
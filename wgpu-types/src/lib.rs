@@ -17,6 +17,7 @@ use std::{num::NonZeroU32, ops::Range};
 
 pub mod assertions;
 pub mod math;
+pub mod webgpu_names;
 
 // Use this macro instead of the one provided by the bitflags_serde_shim crate
 // because the latter produces an error when deserializing bits that are not
@@ -86,6 +87,8 @@ pub const VERTEX_STRIDE_ALIGNMENT: BufferAddress = 4;
 pub const PUSH_CONSTANT_ALIGNMENT: u32 = 4;
 /// Maximum queries in a query set
 pub const QUERY_SET_MAX_QUERIES: u32 = 8192;
+/// Number of viewports and scissor rects of a render pass with [`Features::MULTI_VIEWPORT`].
+pub const MAX_VIEWPORTS: u32 = 16;
 /// Size of a single piece of query data.
 pub const QUERY_SIZE: u32 = 8;
 
@@ -179,9 +182,18 @@ bitflags::bitflags! {
 
 impl_bitflags!(Backends);
 
+impl Backends {
+    /// Backend that does no GPU work, for testing. Only available with the
+    /// `noop` feature enabled.
+    ///
+    /// It is not part of [`Backends::all()`], so that it is never picked unless
+    /// it is asked for explicitly.
+    pub const NOOP: Self = Self::from_bits_retain(1 << Backend::Empty as u32);
+}
+
 impl From<Backend> for Backends {
     fn from(backend: Backend) -> Self {
-        Self::from_bits(1 << backend as u32).unwrap()
+        Self::from_bits_retain(1 << backend as u32)
     }
 }
 
@@ -198,6 +210,9 @@ pub struct RequestAdapterOptions<S> {
     pub power_preference: PowerPreference,
     /// Indicates that only a fallback adapter can be returned. This is generally a "software"
     /// implementation on the system.
+    ///
+    /// On native, this restricts the selection to adapters with [`DeviceType::Cpu`], such as
+    /// lavapipe, SwiftShader, llvmpipe or WARP.
     pub force_fallback_adapter: bool,
     /// Surface that is required to be presentable with the requested adapter. This does not
     /// create the surface, only guarantees that the adapter can present to said surface.
@@ -276,15 +291,21 @@ bitflags::bitflags! {
         const TIMESTAMP_QUERY = 1 << 1;
         /// Allows non-zero value for the "first instance" in indirect draw calls.
         ///
+        /// Without this feature, indirect draws in render passes with a non-zero first instance
+        /// draw nothing, as long as the device validates indirect draws on the GPU. Indirect
+        /// draws in render bundles are not validated.
+        ///
         /// Supported Platforms:
         /// - Vulkan (mostly)
         /// - DX12
         /// - Metal
+        /// - OpenGL ES 3.1+ with `GL_EXT_base_instance`
         ///
         /// This is a web and native feature.
         const INDIRECT_FIRST_INSTANCE = 1 << 2;
 
-        // 3..8 available
+        // 3..8 should be available but are for now occupied by native only features,
+        // see the end of this list
 
         // Shader:
 
@@ -299,7 +320,8 @@ bitflags::bitflags! {
         /// This is a web and native feature.
         const SHADER_F16 = 1 << 8;
 
-        // 9..14 available
+        // 9..14 should be available but are for now occupied by native only features,
+        // see the end of this list
 
         // Texture Formats:
 
@@ -312,10 +334,25 @@ bitflags::bitflags! {
         // TODO const BGRA8UNORM_STORAGE = 1 << 16;
         // ? const NORM16_FILTERABLE = 1 << 17; (https://github.com/gpuweb/gpuweb/issues/3839)
         // ? const NORM16_RESOLVE = 1 << 18; (https://github.com/gpuweb/gpuweb/issues/3839)
-        // TODO const FLOAT32_FILTERABLE = 1 << 19;
+
+        /// Allows the [`TextureFormat::R32Float`], [`TextureFormat::Rg32Float`] and
+        /// [`TextureFormat::Rgba32Float`] formats to be filtered, so they can be bound as
+        /// [`TextureSampleType::Float { filterable: true }`] and sampled with a filtering sampler.
+        ///
+        /// Supported Platforms:
+        /// - Vulkan (mainly desktop)
+        /// - DX12
+        /// - Metal (macOS)
+        /// - OpenGL ES (with `OES_texture_float_linear`)
+        ///
+        /// This is a web and native feature.
+        const FLOAT32_FILTERABLE = 1 << 19;
+
         // ? const FLOAT32_BLENDABLE = 1 << 20; (https://github.com/gpuweb/gpuweb/issues/3556)
         // ? const 32BIT_FORMAT_MULTISAMPLE = 1 << 21; (https://github.com/gpuweb/gpuweb/issues/3844)
         // ? const 32BIT_FORMAT_RESOLVE = 1 << 22; (https://github.com/gpuweb/gpuweb/issues/3844)
+        //
+        // 20..23 are for now occupied by native only features, see the end of this list
 
         /// Allows for usage of textures of format [`TextureFormat::Rg11b10Float`] as a render target
         ///
@@ -738,7 +775,91 @@ bitflags::bitflags! {
         /// This is a native only feature.
         const VERTEX_ATTRIBUTE_64BIT = 1 << 53;
 
-        // 54..59 available
+        // Texture formats:
+
+        /// Enables the BC1, BC2 and BC3 (S3TC/DXTn) subset of the BCn family of compressed
+        /// textures, for adapters which can't support the full family.
+        ///
+        /// This is implied by [`Features::TEXTURE_COMPRESSION_BC`]: adapters supporting the full
+        /// family also report this feature, and devices created with the full family get it too.
+        ///
+        /// Support for this feature guarantees availability of [`TextureUsages::COPY_SRC | TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING`] for BC1-3 formats.
+        /// [`Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`] may enable additional usages.
+        ///
+        /// Supported Platforms:
+        /// - desktops
+        /// - Vulkan (some mobile)
+        /// - OpenGL (with `EXT_texture_compression_s3tc`)
+        ///
+        /// This is a native only feature.
+        const TEXTURE_COMPRESSION_S3TC = 1 << 54;
+
+        // Robustness:
+
+        /// Out-of-bounds buffer accesses in shaders are kept within the bound range by the
+        /// driver, rather than by bounds checks that wgpu inserts into shaders.
+        ///
+        /// When this feature is enabled on a device, the backend relies on the driver for
+        /// storage and uniform buffer robustness and never instruments buffer accesses.
+        /// This is unrelated to the index clamping controlled by [`ShaderBoundChecks`],
+        /// which still applies to arrays that aren't backed by buffers.
+        ///
+        /// Vulkan devices always enable `robustBufferAccess` when it's supported, and OpenGL ES
+        /// contexts are always created robust when possible. Requesting this feature only
+        /// drops the bounds checks that are otherwise added to buffer accesses in shaders.
+        ///
+        /// Supported platforms:
+        /// - Vulkan (with `robustBufferAccess`)
+        /// - OpenGL ES (robust contexts with `GL_KHR_robust_buffer_access_behavior`)
+        /// - WebGL2
+        ///
+        /// DX12 binds buffers with dynamic offsets as root descriptors, which have no
+        /// bounds checks, so it doesn't support this feature.
+        ///
+        /// This is a native only feature.
+        const ROBUST_BUFFER_ACCESS = 1 << 55;
+
+        // Rasterization:
+
+        /// Allows running fragment shaders at a coarser rate than once per pixel, for the
+        /// draws following [`RenderPass::set_shading_rate`].
+        ///
+        /// Only a single rate per draw is supported. Rates that vary across the target,
+        /// from a shading rate attachment or per primitive, are not.
+        ///
+        /// Supported platforms:
+        /// - Vulkan 1.2+ (with `VK_KHR_fragment_shading_rate` and `pipelineFragmentShadingRate`)
+        ///
+        /// Not yet implemented on DX12 (variable rate shading tier 1), Metal or GLES.
+        ///
+        /// This is a native only feature.
+        ///
+        /// [`RenderPass::set_shading_rate`]: ../wgpu/struct.RenderPass.html#method.set_shading_rate
+        const SHADING_RATE = 1 << 56;
+
+        // Ray tracing:
+
+        /// Allows creating and building ray tracing acceleration structures.
+        ///
+        /// Acceleration structures are only created and built through `wgpu-hal` for now, and
+        /// can then be bound with [`Device::create_acceleration_structure_from_hal`].
+        ///
+        /// Supported platforms:
+        /// - Vulkan 1.2+ (with `VK_KHR_acceleration_structure` and `bufferDeviceAddress`)
+        ///
+        /// This is a native only feature.
+        ///
+        /// [`Device::create_acceleration_structure_from_hal`]: ../wgpu/struct.Device.html#method.create_acceleration_structure_from_hal
+        const RAY_TRACING_ACCELERATION_STRUCTURE = 1 << 57;
+        /// Allows tracing rays against acceleration structures from any shader stage, using
+        /// ray queries, and binding acceleration structures with
+        /// [`BindingType::AccelerationStructure`].
+        ///
+        /// Supported platforms:
+        /// - Vulkan 1.2+ (with `VK_KHR_ray_query`)
+        ///
+        /// This is a native only feature.
+        const RAY_QUERY = 1 << 58;
 
         // Shader:
 
@@ -782,7 +903,192 @@ bitflags::bitflags! {
         /// This is a native only feature.
         const SHADER_EARLY_DEPTH_TEST = 1 << 62;
 
-        // 62..64 available
+        // Native only features placed in the bits left free above:
+
+        /// Allows the use of the [`BlendFactor::Src1`], [`BlendFactor::OneMinusSrc1`],
+        /// [`BlendFactor::Src1Alpha`] and [`BlendFactor::OneMinusSrc1Alpha`] blend factors,
+        /// which read from a second color output of the fragment shader.
+        ///
+        /// Only the first color target may use these factors.
+        ///
+        /// Note: `naga` can not declare a second blend source yet, so the fragment shader
+        /// has to be provided through `spirv-passthrough` right now.
+        ///
+        /// Supported Platforms:
+        /// - Vulkan (with `dualSrcBlend`)
+        /// - DX12
+        /// - Metal
+        /// - OpenGL ES (with `GL_EXT_blend_func_extended`)
+        ///
+        /// This is currently a native only feature.
+        const DUAL_SOURCE_BLENDING = 1 << 3;
+
+        /// Allows shaders to use i64 and u64.
+        ///
+        /// Note: this is not supported in `naga` yet, only through `spirv-passthrough` right now.
+        ///
+        /// Supported Platforms:
+        /// - Vulkan (with `shaderInt64`)
+        ///
+        /// This is a native only feature.
+        const SHADER_INT64 = 1 << 4;
+
+        /// Allows shaders to perform atomic operations on i64 and u64 values in storage buffers,
+        /// such as `atomicMax` on a packed depth and payload value.
+        ///
+        /// Only supported on adapters that also support [`Features::SHADER_INT64`].
+        ///
+        /// Note: this is not supported in `naga` yet, only through `spirv-passthrough` right now.
+        ///
+        /// Supported Platforms:
+        /// - Vulkan (with `VK_KHR_shader_atomic_int64`)
+        ///
+        /// This is a native only feature.
+        const SHADER_INT64_ATOMIC = 1 << 5;
+
+        /// Allows for usage of textures of format [`TextureFormat::Rg11b10Float`] as a storage texture
+        ///
+        /// Supported platforms:
+        /// - Vulkan (with `shaderStorageImageExtendedFormats`)
+        /// - DX12
+        /// - Metal (macOS and Apple3+)
+        ///
+        /// This is a native only feature.
+        const RG11B10UFLOAT_STORAGE = 1 << 6;
+
+        /// Allows the use of [`SamplerBorderColor::Custom`] as the border color of samplers
+        /// using [`AddressMode::ClampToBorder`].
+        ///
+        /// Requires [`Features::ADDRESS_MODE_CLAMP_TO_BORDER`] to use the border color.
+        ///
+        /// Supported platforms:
+        /// - Vulkan (with `VK_EXT_custom_border_color`)
+        /// - DX12
+        /// - OpenGL ES (with `GL_EXT_texture_border_clamp`)
+        ///
+        /// Metal only supports the fixed border colors, so it doesn't expose this feature.
+        ///
+        /// This is a native only feature.
+        const ADDRESS_MODE_CLAMP_TO_BORDER_CUSTOM = 1 << 7;
+
+        /// Allows setting [`MAX_VIEWPORTS`] viewports and scissor rects in a render pass, with
+        /// [`RenderPass::set_viewports`] and [`RenderPass::set_scissor_rects`]. The vertex
+        /// shader selects the viewport of each primitive by writing its index to the
+        /// `ViewportIndex` builtin, e.g. to render the six faces of a cube map in one pass.
+        ///
+        /// Note: `naga` can not write the viewport index yet, so the vertex shader has to be
+        /// provided through `spirv-passthrough` right now.
+        ///
+        /// Supported platforms:
+        /// - Vulkan (with `multiViewport`)
+        ///
+        /// This is a native only feature.
+        ///
+        /// [`RenderPass::set_viewports`]: ../wgpu/struct.RenderPass.html#method.set_viewports
+        /// [`RenderPass::set_scissor_rects`]: ../wgpu/struct.RenderPass.html#method.set_scissor_rects
+        const MULTI_VIEWPORT = 1 << 9;
+
+        /// Allows [`DepthStencilState::depth_bounds`] and [`RenderPass::set_depth_bounds`],
+        /// which discard fragments where the value already in the depth buffer is outside of
+        /// the given bounds.
+        ///
+        /// Supported platforms:
+        /// - Vulkan (with `depthBounds`)
+        ///
+        /// This is a native only feature.
+        ///
+        /// [`RenderPass::set_depth_bounds`]: ../wgpu/struct.RenderPass.html#method.set_depth_bounds
+        const DEPTH_BOUNDS_TEST = 1 << 10;
+
+        /// Allows 3D textures of BCn compressed formats, which are compressed slice by slice.
+        ///
+        /// The formats themselves still require [`Features::TEXTURE_COMPRESSION_BC`], or
+        /// [`Features::TEXTURE_COMPRESSION_S3TC`] for BC1 to BC3.
+        ///
+        /// Supported platforms:
+        /// - Vulkan (if the driver supports 3D images of all BCn formats)
+        /// - DX12
+        /// - Metal (macOS)
+        ///
+        /// This is a native only feature.
+        const TEXTURE_COMPRESSION_BC_SLICED_3D = 1 << 11;
+
+        /// Allows 3D textures of ASTC compressed formats, which are compressed slice by slice.
+        ///
+        /// The formats themselves still require [`Features::TEXTURE_COMPRESSION_ASTC`], or
+        /// [`Features::TEXTURE_COMPRESSION_ASTC_HDR`] for [`AstcChannel::Hdr`].
+        ///
+        /// Supported platforms:
+        /// - Vulkan (if the driver supports 3D images of all ASTC formats)
+        ///
+        /// This is a native only feature.
+        const TEXTURE_COMPRESSION_ASTC_SLICED_3D = 1 << 12;
+
+        /// Allows command buffers to be finished as reusable, so that they can be submitted
+        /// any number of times instead of being consumed by the first submission.
+        ///
+        /// Their encoder must be created with [`CommandEncoderDescriptor::reusable`]. A reusable
+        /// command buffer can't be submitted again until its previous submission is complete,
+        /// and it can't use surface textures.
+        ///
+        /// Supported Platforms:
+        /// - Vulkan
+        /// - DX12
+        /// - OpenGL
+        ///
+        /// This is a native only feature.
+        const REUSABLE_COMMAND_BUFFERS = 1 << 13;
+
+        /// Leaves `var<workgroup>` memory uninitialized at the start of each workgroup,
+        /// instead of zero-initializing it as WebGPU requires.
+        ///
+        /// This saves the cost of clearing workgroup memory in shaders that write it before
+        /// reading it. Shaders that read it first see whatever the previous workgroup left there,
+        /// which may come from another application.
+        ///
+        /// Supported Platforms:
+        /// - Vulkan
+        /// - DX12
+        /// - Metal
+        /// - OpenGL
+        ///
+        /// This is a native only feature.
+        const UNINITIALIZED_WORKGROUP_MEMORY = 1 << 63;
+
+        /// Allows creating pipeline caches with `Device::create_pipeline_cache`, to reuse
+        /// compiled pipelines between runs of the application.
+        ///
+        /// Supported platforms:
+        /// - Vulkan
+        /// - Metal (macOS 11+ and iOS 14+, with binary archives)
+        ///
+        /// This is a native only feature.
+        const PIPELINE_CACHE = 1 << 20;
+
+        /// Allows texture views with a [`TextureSwizzle`] other than the identity.
+        ///
+        /// Supported platforms:
+        /// - Vulkan
+        /// - DX12
+        /// - OpenGL ES (not WebGL)
+        ///
+        /// This is a native only feature.
+        const TEXTURE_COMPONENT_SWIZZLE = 1 << 21;
+
+        /// Allows creating protected buffers and textures with [`BufferUsages::PROTECTED`] and
+        /// [`TextureUsages::PROTECTED`], and configuring surfaces with the latter where the
+        /// surface supports protected swapchains.
+        ///
+        /// Protected resources can only be used by command encoders created with
+        /// [`CommandEncoderDescriptor::protected`], which in turn can only write to protected
+        /// resources. Queue writes to protected resources are rejected, since they are copied
+        /// from unprotected staging memory.
+        ///
+        /// Supported platforms:
+        /// - Vulkan 1.1+ (with `protectedMemory`)
+        ///
+        /// This is a native only feature.
+        const PROTECTED_CONTENT = 1 << 22;
     }
 }
 
@@ -791,15 +1097,34 @@ impl_bitflags!(Features);
 impl Features {
     /// Mask of all features which are part of the upstream WebGPU standard.
     pub const fn all_webgpu_mask() -> Self {
-        Self::from_bits_truncate(0x0000_0000_0000_FFFF)
+        Self::DEPTH_CLIP_CONTROL
+            .union(Self::TIMESTAMP_QUERY)
+            .union(Self::INDIRECT_FIRST_INSTANCE)
+            .union(Self::SHADER_F16)
+            .union(Self::FLOAT32_FILTERABLE)
+            .union(Self::RG11B10UFLOAT_RENDERABLE)
+            .union(Self::DEPTH32FLOAT_STENCIL8)
+            .union(Self::TEXTURE_COMPRESSION_BC)
+            .union(Self::TEXTURE_COMPRESSION_ETC2)
+            .union(Self::TEXTURE_COMPRESSION_ASTC)
     }
 
     /// Mask of all features that are only available when targeting native (not web).
     pub const fn all_native_mask() -> Self {
-        Self::from_bits_truncate(0xFFFF_FFFF_FFFF_0000)
+        Self::all().difference(Self::all_webgpu_mask())
     }
 }
 
+#[test]
+fn feature_masks() {
+    assert_eq!(
+        Features::all_webgpu_mask() | Features::all_native_mask(),
+        Features::all()
+    );
+    assert!(!Features::all_webgpu_mask().intersects(Features::all_native_mask()));
+    assert!(Features::all_native_mask().contains(Features::PROTECTED_CONTENT));
+}
+
 /// Represents the sets of limits an adapter/device supports.
 ///
 /// We provide three different defaults.
@@ -819,6 +1144,11 @@ impl Features {
 /// limits you need boosted. This will let you stay running on all hardware that supports the limits
 /// you need.
 ///
+/// Headless compute-only applications can request [`Limits::downlevel_defaults()`] with no
+/// features and no `compatible_surface`. Devices create their presentation objects only once a
+/// surface is configured, and no render-only helpers are set up front, so such a device is cheap to
+/// open on the Vulkan and GL backends.
+///
 /// Limits "better" than the default must be supported by the adapter and requested when requesting
 /// a device. If limits "better" than the adapter supports are requested, requesting a device will
 /// panic. Once a device is requested, you may only use resources up to the limits requested _even_
@@ -1360,6 +1690,15 @@ bitflags::bitflags! {
         ///
         /// The GLES/WebGL and Vulkan on Android doesn't support this.
         const SURFACE_VIEW_FORMATS = 1 << 21;
+
+        /// Indirect draws whose arguments reach past the end of the bound vertex, instance or
+        /// index buffers are executed safely, because out-of-bounds buffer reads are defined.
+        ///
+        /// Without this flag, wgpu validates the arguments of indirect draws in render passes on
+        /// the GPU, and skips the draws that are out of bounds.
+        ///
+        /// Metal, GLES and Vulkan without `robustBufferAccess` don't support this. DX12 and DX11 do.
+        const ROBUST_INDIRECT_EXECUTION = 1 << 22;
     }
 }
 
@@ -1370,8 +1709,13 @@ impl DownlevelFlags {
     pub const fn compliant() -> Self {
         // We use manual bit twiddling to make this a const fn as `Sub` and `.remove` aren't const
 
-        // WebGPU doesn't actually require aniso
-        Self::from_bits_truncate(Self::all().bits() & !Self::ANISOTROPIC_FILTERING.bits())
+        // WebGPU doesn't actually require aniso, and wgpu validates indirect draws
+        // when they aren't robust
+        Self::from_bits_truncate(
+            Self::all().bits()
+                & !Self::ANISOTROPIC_FILTERING.bits()
+                & !Self::ROBUST_INDIRECT_EXECUTION.bits(),
+        )
     }
 }
 
@@ -1446,6 +1790,18 @@ pub struct AdapterInfo {
     pub backend: Backend,
 }
 
+/// Information about a display that can be presented to without a window system.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayInfo {
+    /// Display name, as reported by the driver
+    pub name: String,
+    /// Width of the display's native resolution, in pixels
+    pub width: u32,
+    /// Height of the display's native resolution, in pixels
+    pub height: u32,
+}
+
 /// Describes a [`Device`](../wgpu/struct.Device.html).
 ///
 /// Corresponds to [WebGPU `GPUDeviceDescriptor`](
@@ -1463,6 +1819,14 @@ pub struct DeviceDescriptor<L> {
     /// Limits that the device should support. If any limit is "better" than the limit exposed by
     /// the adapter, creating a device will panic.
     pub limits: Limits,
+    /// Queues to create in addition to the main queue, e.g. to run compute work or uploads
+    /// asynchronously to rendering. Creating a device fails if the adapter can't provide them.
+    ///
+    /// Empty by default, so descriptors built with `..Default::default()` only get the
+    /// main queue.
+    ///
+    /// Supported on Vulkan and DX12. Not supported with [`Features::PROTECTED_CONTENT`].
+    pub additional_queues: Vec<QueueKind>,
 }
 
 impl<L> DeviceDescriptor<L> {
@@ -1472,10 +1836,41 @@ impl<L> DeviceDescriptor<L> {
             label: fun(&self.label),
             features: self.features,
             limits: self.limits.clone(),
+            additional_queues: self.additional_queues.clone(),
         }
     }
 }
 
+/// Kind of a queue requested in [`DeviceDescriptor::additional_queues`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(Serialize))]
+#[cfg_attr(feature = "replay", derive(Deserialize))]
+pub enum QueueKind {
+    /// A queue for compute and copy commands. It runs asynchronously to rendering when the
+    /// adapter has dedicated compute hardware.
+    Compute,
+    /// A queue for copy commands. It runs on dedicated copy hardware when the adapter has
+    /// some.
+    Transfer,
+}
+
+/// Why a device was lost, passed to the device lost callback.
+///
+/// Corresponds to [WebGPU `GPUDeviceLostReason`](
+/// https://gpuweb.github.io/gpuweb/#enumdef-gpudevicelostreason).
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(Serialize))]
+#[cfg_attr(feature = "replay", derive(Deserialize))]
+pub enum DeviceLostReason {
+    /// The backend reported the device as lost: a driver crash or reset, a
+    /// removed GPU, or a lost GL context.
+    Unknown = 0,
+    /// The device was dropped while a device lost callback was still set.
+    Dropped = 1,
+}
+
 bitflags::bitflags! {
     /// Describes the shader stages that a binding will be visible from.
     ///
@@ -1582,6 +1977,35 @@ pub enum BlendFactor {
     Constant = 11,
     /// 1.0 - Constant
     OneMinusConstant = 12,
+    /// S1.component
+    ///
+    /// Requires [`Features::DUAL_SOURCE_BLENDING`].
+    Src1 = 13,
+    /// 1.0 - S1.component
+    ///
+    /// Requires [`Features::DUAL_SOURCE_BLENDING`].
+    OneMinusSrc1 = 14,
+    /// S1.alpha
+    ///
+    /// Requires [`Features::DUAL_SOURCE_BLENDING`].
+    Src1Alpha = 15,
+    /// 1.0 - S1.alpha
+    ///
+    /// Requires [`Features::DUAL_SOURCE_BLENDING`].
+    OneMinusSrc1Alpha = 16,
+}
+
+impl BlendFactor {
+    /// Returns `true` if the blend factor references the second blend source.
+    pub fn ref_second_blend_source(&self) -> bool {
+        matches!(
+            self,
+            BlendFactor::Src1
+                | BlendFactor::OneMinusSrc1
+                | BlendFactor::Src1Alpha
+                | BlendFactor::OneMinusSrc1Alpha
+        )
+    }
 }
 
 /// Alpha blend operation.
@@ -1654,6 +2078,12 @@ impl BlendComponent {
             (_, _) => false,
         }
     }
+
+    /// Returns true if the component reads from the second blend source,
+    /// which requires [`Features::DUAL_SOURCE_BLENDING`].
+    pub fn uses_second_blend_source(&self) -> bool {
+        self.src_factor.ref_second_blend_source() || self.dst_factor.ref_second_blend_source()
+    }
 }
 
 impl Default for BlendComponent {
@@ -1833,6 +2263,85 @@ pub enum PolygonMode {
     Point = 2,
 }
 
+/// Size of the block of pixels that a single fragment shader invocation covers.
+///
+/// Variants are named `Rate{width}x{height}`, in the order returned by [`ShadingRate::size`].
+/// Render passes start at [`ShadingRate::Rate1x1`]. Changing the rate with
+/// `RenderPass::set_shading_rate` requires [`Features::SHADING_RATE`], even to set
+/// [`ShadingRate::Rate1x1`].
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(Serialize))]
+#[cfg_attr(feature = "replay", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum ShadingRate {
+    /// One invocation per pixel, the default.
+    #[default]
+    Rate1x1 = 0,
+    /// One invocation per block 1 pixel wide and 2 pixels high.
+    Rate1x2 = 1,
+    /// One invocation per block 2 pixels wide and 1 pixel high.
+    Rate2x1 = 2,
+    /// One invocation per block 2 pixels wide and 2 pixels high.
+    Rate2x2 = 3,
+}
+
+impl ShadingRate {
+    /// Width and height, in pixels, of the block covered by an invocation.
+    pub fn size(self) -> (u32, u32) {
+        match self {
+            Self::Rate1x1 => (1, 1),
+            Self::Rate1x2 => (1, 2),
+            Self::Rate2x1 => (2, 1),
+            Self::Rate2x2 => (2, 2),
+        }
+    }
+}
+
+/// Viewport of a render pass, mapping normalized device coordinates to framebuffer
+/// coordinates.
+///
+/// The rectangle is in pixels and must lie within the render target, and the depth
+/// range must lie within `0.0..=1.0`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "trace", derive(Serialize))]
+#[cfg_attr(feature = "replay", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct Viewport {
+    /// Left edge of the viewport.
+    pub x: f32,
+    /// Top edge of the viewport.
+    pub y: f32,
+    /// Width of the viewport, must be greater than zero.
+    pub width: f32,
+    /// Height of the viewport, must be greater than zero.
+    pub height: f32,
+    /// Depth that normalized device depth 0 maps to.
+    pub min_depth: f32,
+    /// Depth that normalized device depth 1 maps to.
+    pub max_depth: f32,
+}
+
+/// Scissor rect of a render pass, outside of which fragments are discarded.
+///
+/// The rectangle is in pixels and must lie within the render target.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(Serialize))]
+#[cfg_attr(feature = "replay", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ScissorRect {
+    /// Left edge of the rect.
+    pub x: u32,
+    /// Top edge of the rect.
+    pub y: u32,
+    /// Width of the rect.
+    pub width: u32,
+    /// Height of the rect.
+    pub height: u32,
+}
+
 /// Describes the state of primitive assembly and rasterization in a render pipeline.
 ///
 /// Corresponds to [WebGPU `GPUPrimitiveState`](
@@ -2156,48 +2665,64 @@ pub enum TextureFormat {
     /// [`Features::DEPTH32FLOAT_STENCIL8`] must be enabled to use this texture format.
     Depth32FloatStencil8,
 
+    /// YUV 4:2:0 chroma subsampled format.
+    ///
+    /// Contains two planes:
+    /// - 0: Single 8 bit channel luminance.
+    /// - 1: Dual 8 bit channel chrominance at half width and half height.
+    ///
+    /// Valid view formats for luminance are [`TextureFormat::R8Unorm`].
+    ///
+    /// Valid view formats for chrominance are [`TextureFormat::Rg8Unorm`].
+    ///
+    /// Width and height must be even.
+    ///
+    /// [`Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`] must be enabled to use this texture format,
+    /// and the adapter must report support for it through its format features.
+    NV12,
+
     // Compressed textures usable with `TEXTURE_COMPRESSION_BC` feature.
     /// 4x4 block compressed texture. 8 bytes per block (4 bit/px). 4 color + alpha pallet. 5 bit R + 6 bit G + 5 bit B + 1 bit alpha.
     /// [0, 63] ([0, 1] for alpha) converted to/from float [0, 1] in shader.
     ///
     /// Also known as DXT1.
     ///
-    /// [`Features::TEXTURE_COMPRESSION_BC`] must be enabled to use this texture format.
+    /// [`Features::TEXTURE_COMPRESSION_BC`] or [`Features::TEXTURE_COMPRESSION_S3TC`] must be enabled to use this texture format.
     Bc1RgbaUnorm,
     /// 4x4 block compressed texture. 8 bytes per block (4 bit/px). 4 color + alpha pallet. 5 bit R + 6 bit G + 5 bit B + 1 bit alpha.
     /// Srgb-color [0, 63] ([0, 1] for alpha) converted to/from linear-color float [0, 1] in shader.
     ///
     /// Also known as DXT1.
     ///
-    /// [`Features::TEXTURE_COMPRESSION_BC`] must be enabled to use this texture format.
+    /// [`Features::TEXTURE_COMPRESSION_BC`] or [`Features::TEXTURE_COMPRESSION_S3TC`] must be enabled to use this texture format.
     Bc1RgbaUnormSrgb,
     /// 4x4 block compressed texture. 16 bytes per block (8 bit/px). 4 color pallet. 5 bit R + 6 bit G + 5 bit B + 4 bit alpha.
     /// [0, 63] ([0, 15] for alpha) converted to/from float [0, 1] in shader.
     ///
     /// Also known as DXT3.
     ///
-    /// [`Features::TEXTURE_COMPRESSION_BC`] must be enabled to use this texture format.
+    /// [`Features::TEXTURE_COMPRESSION_BC`] or [`Features::TEXTURE_COMPRESSION_S3TC`] must be enabled to use this texture format.
     Bc2RgbaUnorm,
     /// 4x4 block compressed texture. 16 bytes per block (8 bit/px). 4 color pallet. 5 bit R + 6 bit G + 5 bit B + 4 bit alpha.
     /// Srgb-color [0, 63] ([0, 255] for alpha) converted to/from linear-color float [0, 1] in shader.
     ///
     /// Also known as DXT3.
     ///
-    /// [`Features::TEXTURE_COMPRESSION_BC`] must be enabled to use this texture format.
+    /// [`Features::TEXTURE_COMPRESSION_BC`] or [`Features::TEXTURE_COMPRESSION_S3TC`] must be enabled to use this texture format.
     Bc2RgbaUnormSrgb,
     /// 4x4 block compressed texture. 16 bytes per block (8 bit/px). 4 color pallet + 8 alpha pallet. 5 bit R + 6 bit G + 5 bit B + 8 bit alpha.
     /// [0, 63] ([0, 255] for alpha) converted to/from float [0, 1] in shader.
     ///
     /// Also known as DXT5.
     ///
-    /// [`Features::TEXTURE_COMPRESSION_BC`] must be enabled to use this texture format.
+    /// [`Features::TEXTURE_COMPRESSION_BC`] or [`Features::TEXTURE_COMPRESSION_S3TC`] must be enabled to use this texture format.
     Bc3RgbaUnorm,
     /// 4x4 block compressed texture. 16 bytes per block (8 bit/px). 4 color pallet + 8 alpha pallet. 5 bit R + 6 bit G + 5 bit B + 8 bit alpha.
     /// Srgb-color [0, 63] ([0, 255] for alpha) converted to/from linear-color float [0, 1] in shader.
     ///
     /// Also known as DXT5.
     ///
-    /// [`Features::TEXTURE_COMPRESSION_BC`] must be enabled to use this texture format.
+    /// [`Features::TEXTURE_COMPRESSION_BC`] or [`Features::TEXTURE_COMPRESSION_S3TC`] must be enabled to use this texture format.
     Bc3RgbaUnormSrgb,
     /// 4x4 block compressed texture. 8 bytes per block (4 bit/px). 8 color pallet. 8 bit R.
     /// [0, 255] converted to/from float [0, 1] in shader.
@@ -2381,6 +2906,7 @@ impl<'de> Deserialize<'de> for TextureFormat {
                     "stencil8" => TextureFormat::Stencil8,
                     "depth32float" => TextureFormat::Depth32Float,
                     "depth32float-stencil8" => TextureFormat::Depth32FloatStencil8,
+                    "nv12" => TextureFormat::NV12,
                     "depth16unorm" => TextureFormat::Depth16Unorm,
                     "depth24plus" => TextureFormat::Depth24Plus,
                     "depth24plus-stencil8" => TextureFormat::Depth24PlusStencil8,
@@ -2508,6 +3034,7 @@ impl Serialize for TextureFormat {
             TextureFormat::Depth32Float => "depth32float",
             TextureFormat::Depth16Unorm => "depth16unorm",
             TextureFormat::Depth32FloatStencil8 => "depth32float-stencil8",
+            TextureFormat::NV12 => "nv12",
             TextureFormat::Depth24Plus => "depth24plus",
             TextureFormat::Depth24PlusStencil8 => "depth24plus-stencil8",
             TextureFormat::Rgb9e5Ufloat => "rgb9e5ufloat",
@@ -2584,7 +3111,10 @@ impl TextureFormat {
             ) => Some(Self::Stencil8),
             (Self::Depth24PlusStencil8, TextureAspect::DepthOnly) => Some(Self::Depth24Plus),
             (Self::Depth32FloatStencil8, TextureAspect::DepthOnly) => Some(Self::Depth32Float),
-            (format, TextureAspect::All) => Some(format),
+            (Self::NV12, TextureAspect::Plane0) => Some(Self::R8Unorm),
+            (Self::NV12, TextureAspect::Plane1) => Some(Self::Rg8Unorm),
+            // views to multi-planar formats must specify the plane
+            (format, TextureAspect::All) if !format.is_multi_planar_format() => Some(format),
             _ => None,
         }
     }
@@ -2624,6 +3154,19 @@ impl TextureFormat {
         }
     }
 
+    /// Returns `true` if the format is a multi-planar format
+    pub fn is_multi_planar_format(&self) -> bool {
+        self.planes().is_some()
+    }
+
+    /// Returns the number of planes a multi-planar format has.
+    pub fn planes(&self) -> Option<u32> {
+        match *self {
+            Self::NV12 => Some(2),
+            _ => None,
+        }
+    }
+
     /// Returns `true` if the format has a color aspect
     pub fn has_color_aspect(&self) -> bool {
         !self.is_depth_stencil_format()
@@ -2701,6 +3244,8 @@ impl TextureFormat {
             | Self::Depth32Float
             | Self::Depth32FloatStencil8 => (1, 1),
 
+            Self::NV12 => (1, 1),
+
             Self::Bc1RgbaUnorm
             | Self::Bc1RgbaUnormSrgb
             | Self::Bc2RgbaUnorm
@@ -2751,6 +3296,25 @@ impl TextureFormat {
         self.block_dimensions() != (1, 1)
     }
 
+    /// Returns the size multiple requirement for a texture using this format.
+    pub fn size_multiple_requirement(&self) -> (u32, u32) {
+        match *self {
+            Self::NV12 => (2, 2),
+            _ => self.block_dimensions(),
+        }
+    }
+
+    /// Returns the horizontal and vertical subsampling factors of the given
+    /// plane of a multi-planar format.
+    ///
+    /// Returns `(1, 1)` for any other format or aspect.
+    pub fn subsampling_factors(&self, aspect: TextureAspect) -> (u32, u32) {
+        match (*self, aspect) {
+            (Self::NV12, TextureAspect::Plane1) => (2, 2),
+            _ => (1, 1),
+        }
+    }
+
     /// Returns the required features (if any) in order to use the texture.
     pub fn required_features(&self) -> Features {
         match *self {
@@ -2798,6 +3362,8 @@ impl TextureFormat {
 
             Self::Depth32FloatStencil8 => Features::DEPTH32FLOAT_STENCIL8,
 
+            Self::NV12 => Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
+
             Self::R16Unorm
             | Self::R16Snorm
             | Self::Rg16Unorm
@@ -2810,8 +3376,9 @@ impl TextureFormat {
             | Self::Bc2RgbaUnorm
             | Self::Bc2RgbaUnormSrgb
             | Self::Bc3RgbaUnorm
-            | Self::Bc3RgbaUnormSrgb
-            | Self::Bc4RUnorm
+            | Self::Bc3RgbaUnormSrgb => Features::TEXTURE_COMPRESSION_S3TC,
+
+            Self::Bc4RUnorm
             | Self::Bc4RSnorm
             | Self::Bc5RgUnorm
             | Self::Bc5RgSnorm
@@ -2852,12 +3419,15 @@ impl TextureFormat {
             TextureUsages::COPY_SRC | TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING;
         let attachment = basic | TextureUsages::RENDER_ATTACHMENT;
         let storage = basic | TextureUsages::STORAGE_BINDING;
-        let all_flags = TextureUsages::all();
-        let rg11b10f = if device_features.contains(Features::RG11B10UFLOAT_RENDERABLE) {
+        let all_flags = TextureUsages::all() - TextureUsages::PROTECTED - TextureUsages::TRANSIENT;
+        let mut rg11b10f = if device_features.contains(Features::RG11B10UFLOAT_RENDERABLE) {
             attachment
         } else {
             basic
         };
+        if device_features.contains(Features::RG11B10UFLOAT_STORAGE) {
+            rg11b10f |= TextureUsages::STORAGE_BINDING;
+        }
 
         #[rustfmt::skip] // lets make a nice table
         let (
@@ -2907,6 +3477,9 @@ impl TextureFormat {
             Self::Depth32Float =>         (        msaa, attachment),
             Self::Depth32FloatStencil8 => (        msaa, attachment),
 
+            // No guarantees, support is reported by the adapter.
+            Self::NV12 =>                 (        noaa, TextureUsages::empty()),
+
             Self::R16Unorm =>             (        msaa,    storage),
             Self::R16Snorm =>             (        msaa,    storage),
             Self::Rg16Unorm =>            (        msaa,    storage),
@@ -2947,7 +3520,11 @@ impl TextureFormat {
 
         let is_filterable =
             self.sample_type(None) == Some(TextureSampleType::Float { filterable: true });
-        flags.set(TextureFormatFeatureFlags::FILTERABLE, is_filterable);
+        let is_float32 = matches!(*self, Self::R32Float | Self::Rg32Float | Self::Rgba32Float);
+        flags.set(
+            TextureFormatFeatureFlags::FILTERABLE,
+            is_filterable || (is_float32 && device_features.contains(Features::FLOAT32_FILTERABLE)),
+        );
         flags.set(TextureFormatFeatureFlags::BLENDABLE, is_filterable);
 
         TextureFormatFeatures {
@@ -2958,7 +3535,7 @@ impl TextureFormat {
 
     /// Returns the sample type compatible with this format and aspect
     ///
-    /// Returns `None` only if the format is combined depth-stencil
+    /// Returns `None` only if the format is combined depth-stencil or multi-planar
     /// and `TextureAspect::All` or no `aspect` was provided
     pub fn sample_type(&self, aspect: Option<TextureAspect>) -> Option<TextureSampleType> {
         let float = TextureSampleType::Float { filterable: true };
@@ -3011,6 +3588,12 @@ impl TextureFormat {
                 None | Some(TextureAspect::All) => None,
                 Some(TextureAspect::DepthOnly) => Some(depth),
                 Some(TextureAspect::StencilOnly) => Some(uint),
+                _ => None,
+            },
+
+            Self::NV12 => match aspect {
+                Some(TextureAspect::Plane0) | Some(TextureAspect::Plane1) => Some(float),
+                _ => None,
             },
 
             Self::R16Unorm
@@ -3059,6 +3642,7 @@ impl TextureFormat {
     ///  - the format is combined depth-stencil and no `aspect` was provided
     ///  - the format is `Depth24Plus`
     ///  - the format is `Depth24PlusStencil8` and `aspect` is depth.
+    ///  - the format is multi-planar and no plane `aspect` was provided
     pub fn block_size(&self, aspect: Option<TextureAspect>) -> Option<u32> {
         match *self {
             Self::R8Unorm | Self::R8Snorm | Self::R8Uint | Self::R8Sint => Some(1),
@@ -3097,14 +3681,19 @@ impl TextureFormat {
             Self::Depth32Float => Some(4),
             Self::Depth24Plus => None,
             Self::Depth24PlusStencil8 => match aspect {
-                None | Some(TextureAspect::All) => None,
-                Some(TextureAspect::DepthOnly) => None,
                 Some(TextureAspect::StencilOnly) => Some(1),
+                _ => None,
             },
             Self::Depth32FloatStencil8 => match aspect {
-                None | Some(TextureAspect::All) => None,
                 Some(TextureAspect::DepthOnly) => Some(4),
                 Some(TextureAspect::StencilOnly) => Some(1),
+                _ => None,
+            },
+
+            Self::NV12 => match aspect {
+                Some(TextureAspect::Plane0) => Some(1),
+                Some(TextureAspect::Plane1) => Some(2),
+                _ => None,
             },
 
             Self::Bc1RgbaUnorm | Self::Bc1RgbaUnormSrgb | Self::Bc4RUnorm | Self::Bc4RSnorm => {
@@ -3143,7 +3732,7 @@ impl TextureFormat {
 
     /// Returns the number of components this format has taking into account the `aspect`.
     ///
-    /// The `aspect` is only relevant for combined depth-stencil formats.
+    /// The `aspect` is only relevant for combined depth-stencil and multi-planar formats.
     pub fn components_with_aspect(&self, aspect: TextureAspect) -> u8 {
         match *self {
             Self::R8Unorm
@@ -3194,8 +3783,14 @@ impl TextureFormat {
             Self::Stencil8 | Self::Depth16Unorm | Self::Depth24Plus | Self::Depth32Float => 1,
 
             Self::Depth24PlusStencil8 | Self::Depth32FloatStencil8 => match aspect {
-                TextureAspect::All => 2,
                 TextureAspect::DepthOnly | TextureAspect::StencilOnly => 1,
+                _ => 2,
+            },
+
+            Self::NV12 => match aspect {
+                TextureAspect::Plane0 => 1,
+                TextureAspect::Plane1 => 2,
+                _ => 3,
             },
 
             Self::Bc4RUnorm | Self::Bc4RSnorm => 1,
@@ -3456,6 +4051,10 @@ fn texture_format_serialize() {
         serde_json::to_string(&TextureFormat::Depth32FloatStencil8).unwrap(),
         "\"depth32float-stencil8\"".to_string()
     );
+    assert_eq!(
+        serde_json::to_string(&TextureFormat::NV12).unwrap(),
+        "\"nv12\"".to_string()
+    );
     assert_eq!(
         serde_json::to_string(&TextureFormat::Depth24Plus).unwrap(),
         "\"depth24plus\"".to_string()
@@ -3748,6 +4347,10 @@ fn texture_format_deserialize() {
         serde_json::from_str::<TextureFormat>("\"depth32float-stencil8\"").unwrap(),
         TextureFormat::Depth32FloatStencil8
     );
+    assert_eq!(
+        serde_json::from_str::<TextureFormat>("\"nv12\"").unwrap(),
+        TextureFormat::NV12
+    );
     assert_eq!(
         serde_json::from_str::<TextureFormat>("\"depth24plus\"").unwrap(),
         TextureFormat::Depth24Plus
@@ -3858,6 +4461,39 @@ fn texture_format_deserialize() {
     );
 }
 
+#[test]
+fn float32_filterable_format_features() {
+    for format in [
+        TextureFormat::R32Float,
+        TextureFormat::Rg32Float,
+        TextureFormat::Rgba32Float,
+    ] {
+        let flags = format.guaranteed_format_features(Features::empty()).flags;
+        assert!(!flags.contains(TextureFormatFeatureFlags::FILTERABLE));
+
+        let flags = format
+            .guaranteed_format_features(Features::FLOAT32_FILTERABLE)
+            .flags;
+        assert!(flags.contains(TextureFormatFeatureFlags::FILTERABLE));
+        assert!(!flags.contains(TextureFormatFeatureFlags::BLENDABLE));
+    }
+}
+
+#[test]
+fn rg11b10ufloat_storage_format_features() {
+    let format = TextureFormat::Rg11b10Float;
+    let usages = format
+        .guaranteed_format_features(Features::empty())
+        .allowed_usages;
+    assert!(!usages.contains(TextureUsages::STORAGE_BINDING));
+
+    let usages = format
+        .guaranteed_format_features(Features::RG11B10UFLOAT_STORAGE)
+        .allowed_usages;
+    assert!(usages.contains(TextureUsages::STORAGE_BINDING));
+    assert!(!usages.contains(TextureUsages::RENDER_ATTACHMENT));
+}
+
 bitflags::bitflags! {
     /// Color write mask. Disabled color channels will not be written to.
     ///
@@ -3898,6 +4534,11 @@ pub enum Maintain<T> {
     /// On the web, this has no effect. Callbacks are invoked from the
     /// window event loop.
     WaitForSubmissionIndex(T),
+    /// Same as WaitForSubmissionIndex but gives up once the timeout has elapsed.
+    ///
+    /// The timeout has millisecond granularity. If the submission didn't complete
+    /// in time, the queue isn't empty and `Device::poll` returns `false`.
+    WaitForSubmissionIndexWithTimeout(T, std::time::Duration),
     /// Same as WaitForSubmissionIndex but waits for the most recent submission.
     Wait,
     /// Check the device for a single time without blocking.
@@ -3908,7 +4549,9 @@ impl<T> Maintain<T> {
     /// This maintain represents a wait of some kind.
     pub fn is_wait(&self) -> bool {
         match *self {
-            Self::WaitForSubmissionIndex(..) | Self::Wait => true,
+            Self::WaitForSubmissionIndex(..)
+            | Self::WaitForSubmissionIndexWithTimeout(..)
+            | Self::Wait => true,
             Self::Poll => false,
         }
     }
@@ -3920,6 +4563,9 @@ impl<T> Maintain<T> {
     {
         match self {
             Self::WaitForSubmissionIndex(i) => Maintain::WaitForSubmissionIndex(func(i)),
+            Self::WaitForSubmissionIndexWithTimeout(i, timeout) => {
+                Maintain::WaitForSubmissionIndexWithTimeout(func(i), timeout)
+            }
             Self::Wait => Maintain::Wait,
             Self::Poll => Maintain::Poll,
         }
@@ -4018,6 +4664,44 @@ impl PartialEq for DepthBiasState {
 
 impl Eq for DepthBiasState {}
 
+/// Depth bounds test state.
+///
+/// Fragments where the value already in the depth buffer is outside of `min..=max`
+/// are discarded.
+///
+/// For use in [`DepthStencilState`]. Requires [`Features::DEPTH_BOUNDS_TEST`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "trace", derive(Serialize))]
+#[cfg_attr(feature = "replay", derive(Deserialize))]
+pub struct DepthBoundsState {
+    /// Minimum depth, in `0.0..=1.0`.
+    pub min: f32,
+    /// Maximum depth, in `min..=1.0`.
+    pub max: f32,
+}
+
+impl Default for DepthBoundsState {
+    fn default() -> Self {
+        Self { min: 0.0, max: 1.0 }
+    }
+}
+
+impl Hash for DepthBoundsState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.min.to_bits().hash(state);
+        self.max.to_bits().hash(state);
+    }
+}
+
+impl PartialEq for DepthBoundsState {
+    fn eq(&self, other: &Self) -> bool {
+        (self.min.to_bits() == other.min.to_bits()) && (self.max.to_bits() == other.max.to_bits())
+    }
+}
+
+impl Eq for DepthBoundsState {}
+
 /// Describes the depth/stencil state in a render pipeline.
 ///
 /// Corresponds to [WebGPU `GPUDepthStencilState`](
@@ -4042,6 +4726,15 @@ pub struct DepthStencilState {
     /// Depth bias state.
     #[cfg_attr(any(feature = "trace", feature = "replay"), serde(default))]
     pub bias: DepthBiasState,
+    /// Depth bounds test state, `None` disables the test. The bounds are applied whenever
+    /// the pipeline is set, and can be changed afterwards with
+    /// [`RenderPass::set_depth_bounds`].
+    ///
+    /// Requires [`Features::DEPTH_BOUNDS_TEST`] to be `Some`.
+    ///
+    /// [`RenderPass::set_depth_bounds`]: ../wgpu/struct.RenderPass.html#method.set_depth_bounds
+    #[cfg_attr(any(feature = "trace", feature = "replay"), serde(default))]
+    pub depth_bounds: Option<DepthBoundsState>,
 }
 
 impl DepthStencilState {
@@ -4457,6 +5150,11 @@ bitflags::bitflags! {
         const INDIRECT = 1 << 8;
         /// Allow a buffer to be the destination buffer for a [`CommandEncoder::resolve_query_set`] operation.
         const QUERY_RESOLVE = 1 << 9;
+        /// Allocate the buffer from protected memory, so that its contents can only be
+        /// accessed by protected command buffers. Protected buffers cannot be mapped.
+        ///
+        /// Requires [`Features::PROTECTED_CONTENT`].
+        const PROTECTED = 1 << 10;
     }
 }
 
@@ -4509,6 +5207,20 @@ impl<L> BufferDescriptor<L> {
 pub struct CommandEncoderDescriptor<L> {
     /// Debug label for the command encoder. This will show up in graphics debuggers for easy identification.
     pub label: L,
+    /// If true, the encoder can be finished into a command buffer that is submitted more than
+    /// once. Backends may record non-reusable command buffers more efficiently.
+    ///
+    /// [`Features::REUSABLE_COMMAND_BUFFERS`] must be enabled to finish it as reusable.
+    #[cfg_attr(any(feature = "trace", feature = "replay"), serde(default))]
+    pub reusable: bool,
+    /// If true, the encoder records a protected command buffer. It can read any resource, but
+    /// can only write to resources with the [`BufferUsages::PROTECTED`] or
+    /// [`TextureUsages::PROTECTED`] usage. Unprotected command buffers can't use protected
+    /// resources at all.
+    ///
+    /// [`Features::PROTECTED_CONTENT`] must be enabled to use this.
+    #[cfg_attr(any(feature = "trace", feature = "replay"), serde(default))]
+    pub protected: bool,
 }
 
 impl<L> CommandEncoderDescriptor<L> {
@@ -4516,13 +5228,29 @@ impl<L> CommandEncoderDescriptor<L> {
     pub fn map_label<K>(&self, fun: impl FnOnce(&L) -> K) -> CommandEncoderDescriptor<K> {
         CommandEncoderDescriptor {
             label: fun(&self.label),
+            reusable: self.reusable,
+            protected: self.protected,
         }
     }
+
+    /// Sets [`Self::reusable`].
+    pub fn with_reusable(self, reusable: bool) -> Self {
+        Self { reusable, ..self }
+    }
+
+    /// Sets [`Self::protected`].
+    pub fn with_protected(self, protected: bool) -> Self {
+        Self { protected, ..self }
+    }
 }
 
 impl<T> Default for CommandEncoderDescriptor<Option<T>> {
     fn default() -> Self {
-        Self { label: None }
+        Self {
+            label: None,
+            reusable: false,
+            protected: false,
+        }
     }
 }
 
@@ -4637,6 +5365,42 @@ impl Default for CompositeAlphaMode {
     }
 }
 
+/// Color space in which the presentation engine interprets the contents of
+/// surface textures.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(Serialize))]
+#[cfg_attr(feature = "replay", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum SurfaceColorSpace {
+    /// sRGB primaries with the sRGB transfer function.
+    #[default]
+    Srgb = 0,
+    /// Display P3 primaries with the sRGB transfer function.
+    DisplayP3 = 1,
+    /// sRGB primaries with a linear transfer function, where values outside
+    /// of `0.0..=1.0` extend beyond the sRGB gamut and brightness (scRGB).
+    ///
+    /// Requires [`TextureFormat::Rgba16Float`].
+    ExtendedSrgbLinear = 2,
+    /// BT.2020 primaries with the SMPTE ST 2084 (PQ) transfer function (HDR10).
+    ///
+    /// Requires [`TextureFormat::Rgb10a2Unorm`].
+    Hdr10 = 3,
+}
+
+impl SurfaceColorSpace {
+    /// Returns true if surface textures of the given format can be presented
+    /// in this color space.
+    pub fn is_compatible_with(&self, format: TextureFormat) -> bool {
+        match *self {
+            Self::Srgb | Self::DisplayP3 => true,
+            Self::ExtendedSrgbLinear => format == TextureFormat::Rgba16Float,
+            Self::Hdr10 => format == TextureFormat::Rgb10a2Unorm,
+        }
+    }
+}
+
 bitflags::bitflags! {
     /// Different ways that you can use a texture.
     ///
@@ -4660,6 +5424,18 @@ bitflags::bitflags! {
         const STORAGE_BINDING = 1 << 3;
         /// Allows a texture to be an output attachment of a render pass.
         const RENDER_ATTACHMENT = 1 << 4;
+        /// Allocate the texture from protected memory, so that its contents can only be
+        /// accessed by protected command buffers.
+        ///
+        /// Requires [`Features::PROTECTED_CONTENT`].
+        const PROTECTED = 1 << 5;
+        /// Hints that the texture only lives for the duration of the render passes it is
+        /// used in, such as multisampled attachments that are resolved, letting tile-based
+        /// GPUs avoid allocating memory for it (memoryless storage on Metal).
+        ///
+        /// Can only be combined with [`TextureUsages::RENDER_ATTACHMENT`], and render passes
+        /// must clear the texture on load and discard it on store.
+        const TRANSIENT = 1 << 6;
     }
 }
 
@@ -4680,6 +5456,11 @@ pub struct SurfaceCapabilities {
     ///
     /// Will return at least one element, CompositeAlphaMode::Opaque or CompositeAlphaMode::Inherit.
     pub alpha_modes: Vec<CompositeAlphaMode>,
+    /// List of supported pairs of format and color space to use with the given adapter.
+    ///
+    /// Every format in `formats` is supported with at least one color space, usually
+    /// [`SurfaceColorSpace::Srgb`].
+    pub color_spaces: Vec<(TextureFormat, SurfaceColorSpace)>,
     /// Bitflag of supported texture usages for the surface to use with the given adapter.
     ///
     /// The usage TextureUsages::RENDER_ATTACHMENT is guaranteed.
@@ -4692,6 +5473,7 @@ impl Default for SurfaceCapabilities {
             formats: Vec::new(),
             present_modes: Vec::new(),
             alpha_modes: vec![CompositeAlphaMode::Opaque],
+            color_spaces: Vec::new(),
             usages: TextureUsages::RENDER_ATTACHMENT,
         }
     }
@@ -4719,8 +5501,18 @@ pub struct SurfaceConfiguration<V> {
     /// AutoNoVsync will gracefully do a designed sets of fallbacks if their primary modes are
     /// unsupported.
     pub present_mode: PresentMode,
+    /// Desired maximum number of frames that the presentation engine should queue in advance.
+    ///
+    /// Lower values reduce the latency between input and display at the cost of throughput,
+    /// as the CPU has to wait for the GPU more often. Typical values range from 1 to 3;
+    /// a value of 2 is a good balance. This is a hint, and the value is clamped
+    /// to what the backend supports.
+    pub desired_maximum_frame_latency: u32,
     /// Specifies how the alpha channel of the textures should be handled during compositing.
     pub alpha_mode: CompositeAlphaMode,
+    /// Color space in which the textures are presented. Must be paired with `format`
+    /// in the color spaces returned by `Surface::get_capabilities`.
+    pub color_space: SurfaceColorSpace,
     /// Specifies what view formats will be allowed when calling create_view() on texture returned by get_current_texture().
     ///
     /// View formats of the same format as the texture are always allowed.
@@ -4738,7 +5530,9 @@ impl<V: Clone> SurfaceConfiguration<V> {
             width: self.width,
             height: self.height,
             present_mode: self.present_mode,
+            desired_maximum_frame_latency: self.desired_maximum_frame_latency,
             alpha_mode: self.alpha_mode,
+            color_space: self.color_space,
             view_formats: fun(self.view_formats.clone()),
         }
     }
@@ -5291,6 +6085,78 @@ pub enum TextureAspect {
     StencilOnly,
     /// Depth.
     DepthOnly,
+    /// Plane 0 of a multi-planar format.
+    Plane0,
+    /// Plane 1 of a multi-planar format.
+    Plane1,
+}
+
+/// Source of one component of a swizzled texture view.
+///
+/// See [`TextureSwizzle`].
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "trace", derive(Serialize))]
+#[cfg_attr(feature = "replay", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum SwizzleComponent {
+    /// Always 0.
+    Zero,
+    /// Always 1.
+    One,
+    /// The red component of the texture.
+    R,
+    /// The green component of the texture.
+    G,
+    /// The blue component of the texture.
+    B,
+    /// The alpha component of the texture.
+    A,
+}
+
+/// Remapping of the components read through a texture view.
+///
+/// Each field selects where the corresponding component comes from. For example,
+/// a view of an `R8Unorm` texture with `r`, `g` and `b` set to [`SwizzleComponent::R`]
+/// and `a` set to [`SwizzleComponent::One`] reads as opaque grayscale.
+///
+/// Anything other than [`TextureSwizzle::IDENTITY`] requires
+/// [`Features::TEXTURE_COMPONENT_SWIZZLE`], and the view can only be used
+/// as a sampled texture.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "trace", derive(Serialize))]
+#[cfg_attr(feature = "replay", derive(Deserialize))]
+pub struct TextureSwizzle {
+    /// Source of the red component.
+    pub r: SwizzleComponent,
+    /// Source of the green component.
+    pub g: SwizzleComponent,
+    /// Source of the blue component.
+    pub b: SwizzleComponent,
+    /// Source of the alpha component.
+    pub a: SwizzleComponent,
+}
+
+impl TextureSwizzle {
+    /// Every component is read from itself.
+    pub const IDENTITY: Self = Self {
+        r: SwizzleComponent::R,
+        g: SwizzleComponent::G,
+        b: SwizzleComponent::B,
+        a: SwizzleComponent::A,
+    };
+
+    /// Returns true if this swizzle leaves every component in place.
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+}
+
+impl Default for TextureSwizzle {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
 }
 
 /// How edges should be handled in texture addressing.
@@ -5372,6 +6238,11 @@ pub struct PushConstantRange {
 pub struct CommandBufferDescriptor<L> {
     /// Debug label of this command buffer.
     pub label: L,
+    /// If true, the command buffer can be submitted more than once.
+    ///
+    /// [`Features::REUSABLE_COMMAND_BUFFERS`] must be enabled to use this.
+    #[cfg_attr(any(feature = "trace", feature = "replay"), serde(default))]
+    pub reusable: bool,
 }
 
 impl<L> CommandBufferDescriptor<L> {
@@ -5379,6 +6250,7 @@ impl<L> CommandBufferDescriptor<L> {
     pub fn map_label<K>(&self, fun: impl FnOnce(&L) -> K) -> CommandBufferDescriptor<K> {
         CommandBufferDescriptor {
             label: fun(&self.label),
+            reusable: self.reusable,
         }
     }
 }
@@ -5844,6 +6716,21 @@ pub enum BindingType {
         /// Dimension of the texture view that is going to be sampled.
         view_dimension: TextureViewDimension,
     },
+    /// A ray tracing acceleration structure.
+    ///
+    /// Example WGSL syntax:
+    /// ```rust,ignore
+    /// @group(0) @binding(0)
+    /// var my_acceleration_structure: acceleration_structure;
+    /// ```
+    ///
+    /// Example GLSL syntax:
+    /// ```cpp,ignore
+    /// layout(set=0, binding=0) uniform accelerationStructureEXT myAccelerationStructure;
+    /// ```
+    ///
+    /// Requires [`Features::RAY_QUERY`].
+    AccelerationStructure,
 }
 
 impl BindingType {
@@ -5943,7 +6830,6 @@ impl<T> ImageCopyTexture<T> {
 ///
 /// Corresponds to [WebGPU `GPUImageCopyExternalImage`](
 /// https://gpuweb.github.io/gpuweb/#dictdef-gpuimagecopyexternalimage).
-#[cfg(target_arch = "wasm32")]
 #[derive(Clone, Debug)]
 pub struct ImageCopyExternalImage {
     /// The texture to be copied from. The copy source data is captured at the moment
@@ -5966,44 +6852,79 @@ pub struct ImageCopyExternalImage {
 ///
 /// Corresponds to the [implicit union type on WebGPU `GPUImageCopyExternalImage.source`](
 /// https://gpuweb.github.io/gpuweb/#dom-gpuimagecopyexternalimage-source).
-#[cfg(target_arch = "wasm32")]
 #[derive(Clone, Debug)]
 pub enum ExternalImageSource {
     /// Copy from a previously-decoded image bitmap.
+    #[cfg(target_arch = "wasm32")]
     ImageBitmap(web_sys::ImageBitmap),
     /// Copy from a current frame of a video element.
+    #[cfg(target_arch = "wasm32")]
     HTMLVideoElement(web_sys::HtmlVideoElement),
     /// Copy from a on-screen canvas.
+    #[cfg(target_arch = "wasm32")]
     HTMLCanvasElement(web_sys::HtmlCanvasElement),
     /// Copy from a off-screen canvas.
     ///
     /// Requies [`DownlevelFlags::UNRESTRICTED_EXTERNAL_TEXTURE_COPIES`]
+    #[cfg(target_arch = "wasm32")]
     OffscreenCanvas(web_sys::OffscreenCanvas),
+    /// Copy from an image decoded into CPU memory.
+    #[cfg(not(target_arch = "wasm32"))]
+    CpuImage(CpuImage),
 }
 
-#[cfg(target_arch = "wasm32")]
 impl ExternalImageSource {
     /// Gets the pixel, not css, width of the source.
     pub fn width(&self) -> u32 {
         match self {
+            #[cfg(target_arch = "wasm32")]
             ExternalImageSource::ImageBitmap(b) => b.width(),
+            #[cfg(target_arch = "wasm32")]
             ExternalImageSource::HTMLVideoElement(v) => v.video_width(),
+            #[cfg(target_arch = "wasm32")]
             ExternalImageSource::HTMLCanvasElement(c) => c.width(),
+            #[cfg(target_arch = "wasm32")]
             ExternalImageSource::OffscreenCanvas(c) => c.width(),
+            #[cfg(not(target_arch = "wasm32"))]
+            ExternalImageSource::CpuImage(i) => i.width,
         }
     }
 
     /// Gets the pixel, not css, height of the source.
     pub fn height(&self) -> u32 {
         match self {
+            #[cfg(target_arch = "wasm32")]
             ExternalImageSource::ImageBitmap(b) => b.height(),
+            #[cfg(target_arch = "wasm32")]
             ExternalImageSource::HTMLVideoElement(v) => v.video_height(),
+            #[cfg(target_arch = "wasm32")]
             ExternalImageSource::HTMLCanvasElement(c) => c.height(),
+            #[cfg(target_arch = "wasm32")]
             ExternalImageSource::OffscreenCanvas(c) => c.height(),
+            #[cfg(not(target_arch = "wasm32"))]
+            ExternalImageSource::CpuImage(i) => i.height,
         }
     }
 }
 
+/// An sRGB image with 8-bit RGBA texels, decoded into CPU memory.
+///
+/// Native counterpart of the browser image sources of [`ExternalImageSource`].
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Debug)]
+pub struct CpuImage {
+    /// Tightly packed rows of RGBA texels, starting at the top left.
+    ///
+    /// Must hold at least `width * height * 4` bytes.
+    pub data: std::sync::Arc<[u8]>,
+    /// Width of the image in texels.
+    pub width: u32,
+    /// Height of the image in texels.
+    pub height: u32,
+    /// If the color channels of `data` are already multiplied by alpha.
+    pub premultiplied_alpha: bool,
+}
+
 #[cfg(target_arch = "wasm32")]
 impl std::ops::Deref for ExternalImageSource {
     type Target = js_sys::Object;
@@ -6184,7 +7105,7 @@ impl ImageSubresourceRange {
 
 /// Color variation to use when sampler addressing mode is [`AddressMode::ClampToBorder`]
 #[repr(C)]
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "trace", derive(serde::Serialize))]
 #[cfg_attr(feature = "replay", derive(serde::Deserialize))]
 pub enum SamplerBorderColor {
@@ -6201,8 +7122,32 @@ pub enum SamplerBorderColor {
     /// this is equivalent to `TransparentBlack`. Requires
     /// [`Features::ADDRESS_MODE_CLAMP_TO_ZERO`]. Not supported on the web.
     Zero,
+
+    /// An arbitrary RGBA color. Requires
+    /// [`Features::ADDRESS_MODE_CLAMP_TO_BORDER_CUSTOM`]. Not supported on the web.
+    Custom([f32; 4]),
+}
+
+impl Hash for SamplerBorderColor {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        if let Self::Custom(color) = *self {
+            color.map(f32::to_bits).hash(state);
+        }
+    }
 }
 
+impl PartialEq for SamplerBorderColor {
+    fn eq(&self, other: &Self) -> bool {
+        match (*self, *other) {
+            (Self::Custom(a), Self::Custom(b)) => a.map(f32::to_bits) == b.map(f32::to_bits),
+            (a, b) => std::mem::discriminant(&a) == std::mem::discriminant(&b),
+        }
+    }
+}
+
+impl Eq for SamplerBorderColor {}
+
 /// Describes how to create a QuerySet.
 ///
 /// Corresponds to [WebGPU `GPUQuerySetDescriptor`](
@@ -6310,6 +7255,8 @@ pub struct DrawIndirectArgs {
     /// Offset into the vertex buffers, in vertices, to begin drawing from.
     pub first_vertex: u32,
     /// First instance to draw.
+    ///
+    /// Must be 0 unless [`Features::INDIRECT_FIRST_INSTANCE`] is enabled.
     pub first_instance: u32,
 }
 
@@ -6326,6 +7273,8 @@ pub struct DrawIndexedIndirectArgs {
     /// Added to each index value before indexing into the vertex buffers.
     pub base_vertex: i32,
     /// First instance to draw.
+    ///
+    /// Must be 0 unless [`Features::INDIRECT_FIRST_INSTANCE`] is enabled.
     pub first_instance: u32,
 }
 
@@ -6429,10 +7378,92 @@ pub enum Gles3MinorVersion {
     Version2,
 }
 
+bitflags::bitflags! {
+    /// Instance debugging flags.
+    ///
+    /// These are not part of the WebGPU standard.
+    ///
+    /// Defaults to enabling both flags if the build configuration has `debug_assertions`.
+    #[repr(transparent)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    pub struct InstanceFlags: u32 {
+        /// Generate debug information in shaders and objects, and enable debug
+        /// contexts where the backend has them (e.g. GL debug contexts).
+        ///
+        /// When the device is lost, Vulkan logs the last debug markers the GPU reached
+        /// (with `VK_NV_device_diagnostic_checkpoints` or `VK_AMD_buffer_marker`), and
+        /// DX12 logs its DRED breadcrumbs and page fault. Other backends don't.
+        const DEBUG = 1 << 0;
+        /// Enable validation, if possible: Vulkan validation layers, the D3D12 debug
+        /// layer and GL debug output.
+        const VALIDATION = 1 << 1;
+        /// Don't pass labels to the backend, so its objects are left unnamed in
+        /// debuggers like RenderDoc. This avoids the cost of naming every object,
+        /// for example in release builds.
+        const DISCARD_HAL_LABELS = 1 << 2;
+    }
+}
+
+impl_bitflags!(InstanceFlags);
+
+impl Default for InstanceFlags {
+    fn default() -> Self {
+        Self::from_build_config()
+    }
+}
+
+impl InstanceFlags {
+    /// Enable debugging and validation flags.
+    pub fn debugging() -> Self {
+        InstanceFlags::DEBUG | InstanceFlags::VALIDATION
+    }
+
+    /// Infer good defaults from the build type.
+    ///
+    /// Returns the default flags, plus [`debugging()`](Self::debugging) if the build
+    /// configuration has `debug_assertions`.
+    pub fn from_build_config() -> Self {
+        if cfg!(debug_assertions) {
+            return InstanceFlags::debugging();
+        }
+
+        InstanceFlags::empty()
+    }
+
+    /// Returns this set of flags, affected by environment variables.
+    ///
+    /// The presence of an environment variable implies that the corresponding flag should be
+    /// set unless the value is "0", in which case the flag is unset. If the environment
+    /// variable is not present, then the flag is unaffected.
+    ///
+    /// - `WGPU_DEBUG`: [`DEBUG`](Self::DEBUG)
+    /// - `WGPU_VALIDATION`: [`VALIDATION`](Self::VALIDATION)
+    /// - `WGPU_DISCARD_HAL_LABELS`: [`DISCARD_HAL_LABELS`](Self::DISCARD_HAL_LABELS)
+    pub fn with_env(mut self) -> Self {
+        fn env(key: &str) -> Option<bool> {
+            std::env::var(key).ok().map(|s| s != "0")
+        }
+
+        if let Some(bit) = env("WGPU_DEBUG") {
+            self.set(Self::DEBUG, bit);
+        }
+        if let Some(bit) = env("WGPU_VALIDATION") {
+            self.set(Self::VALIDATION, bit);
+        }
+        if let Some(bit) = env("WGPU_DISCARD_HAL_LABELS") {
+            self.set(Self::DISCARD_HAL_LABELS, bit);
+        }
+
+        self
+    }
+}
+
 /// Options for creating an instance.
 pub struct InstanceDescriptor {
     /// Which `Backends` to enable.
     pub backends: Backends,
+    /// Flags to tune the behavior of the instance.
+    pub flags: InstanceFlags,
     /// Which DX12 shader compiler to use.
     pub dx12_shader_compiler: Dx12Compiler,
     /// Which OpenGL ES 3 minor version to request.
@@ -6443,6 +7474,7 @@ impl Default for InstanceDescriptor {
     fn default() -> Self {
         Self {
             backends: Backends::all(),
+            flags: InstanceFlags::default(),
             dx12_shader_compiler: Dx12Compiler::default(),
             gles_minor_version: Gles3MinorVersion::default(),
         }
@@ -0,0 +1,268 @@
+//! Conversions between [`Features`]/[`Limits`] and the names the WebGPU spec uses for them.
+//!
+//! Feature names follow [`GPUFeatureName`](https://gpuweb.github.io/gpuweb/#enumdef-gpufeaturename):
+//! the flag name in kebab-case, e.g. [`Features::TEXTURE_COMPRESSION_BC`] is
+//! `"texture-compression-bc"`. Native-only features use the same convention, e.g.
+//! [`Features::PUSH_CONSTANTS`] is `"push-constants"`.
+//!
+//! Limit names follow [`GPUSupportedLimits`](https://gpuweb.github.io/gpuweb/#gpusupportedlimits),
+//! e.g. [`Limits::max_storage_buffer_binding_size`] is `"maxStorageBufferBindingSize"`. These are
+//! also the names `Limits` is serialized with. Native-only limits are named the same way.
+//!
+//! To serialize [`Features`] as a list of names rather than as bits, use the `features` module
+//! with `#[serde(with = "wgpu_types::webgpu_names::features")]`.
+
+use crate::{Features, Limits};
+
+impl Features {
+    /// Returns the feature named `name`, like `"texture-compression-bc"`.
+    ///
+    /// Returns `None` if `name` is not the name of a single feature.
+    pub fn from_webgpu_name(name: &str) -> Option<Self> {
+        if !name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+        {
+            return None;
+        }
+        Self::from_name(&name.to_ascii_uppercase().replace('-', "_"))
+    }
+
+    /// Iterates over the names of the features in `self`, like `"texture-compression-bc"`.
+    pub fn webgpu_names(&self) -> impl Iterator<Item = String> + '_ {
+        self.iter_names()
+            .map(|(name, _)| name.to_ascii_lowercase().replace('_', "-"))
+    }
+}
+
+macro_rules! webgpu_limits {
+    ($(($name:literal, $field:ident)),* $(,)?) => {
+        /// The names of all [`Limits`], in declaration order.
+        pub const LIMIT_NAMES: &[&str] = &[$($name),*];
+
+        impl Limits {
+            /// Returns the value of the limit named `name`, like `"maxStorageBufferBindingSize"`.
+            ///
+            /// Returns `None` if there is no limit with that name.
+            pub fn webgpu_limit(&self, name: &str) -> Option<u64> {
+                match name {
+                    $($name => Some(self.$field.into()),)*
+                    _ => None,
+                }
+            }
+
+            /// Sets the limit named `name`, like `"maxStorageBufferBindingSize"`, to `value`.
+            ///
+            /// Returns `false` and leaves `self` unchanged if there is no limit with that name,
+            /// or if `value` doesn't fit in the limit's type.
+            pub fn set_webgpu_limit(&mut self, name: &str, value: u64) -> bool {
+                match name {
+                    $($name => match value.try_into() {
+                        Ok(value) => {
+                            self.$field = value;
+                            true
+                        }
+                        Err(_) => false,
+                    },)*
+                    _ => false,
+                }
+            }
+        }
+    };
+}
+
+webgpu_limits![
+    ("maxTextureDimension1D", max_texture_dimension_1d),
+    ("maxTextureDimension2D", max_texture_dimension_2d),
+    ("maxTextureDimension3D", max_texture_dimension_3d),
+    ("maxTextureArrayLayers", max_texture_array_layers),
+    ("maxBindGroups", max_bind_groups),
+    ("maxBindingsPerBindGroup", max_bindings_per_bind_group),
+    (
+        "maxDynamicUniformBuffersPerPipelineLayout",
+        max_dynamic_uniform_buffers_per_pipeline_layout
+    ),
+    (
+        "maxDynamicStorageBuffersPerPipelineLayout",
+        max_dynamic_storage_buffers_per_pipeline_layout
+    ),
+    (
+        "maxSampledTexturesPerShaderStage",
+        max_sampled_textures_per_shader_stage
+    ),
+    ("maxSamplersPerShaderStage", max_samplers_per_shader_stage),
+    (
+        "maxStorageBuffersPerShaderStage",
+        max_storage_buffers_per_shader_stage
+    ),
+    (
+        "maxStorageTexturesPerShaderStage",
+        max_storage_textures_per_shader_stage
+    ),
+    (
+        "maxUniformBuffersPerShaderStage",
+        max_uniform_buffers_per_shader_stage
+    ),
+    (
+        "maxUniformBufferBindingSize",
+        max_uniform_buffer_binding_size
+    ),
+    (
+        "maxStorageBufferBindingSize",
+        max_storage_buffer_binding_size
+    ),
+    ("maxVertexBuffers", max_vertex_buffers),
+    ("maxBufferSize", max_buffer_size),
+    ("maxVertexAttributes", max_vertex_attributes),
+    ("maxVertexBufferArrayStride", max_vertex_buffer_array_stride),
+    (
+        "minUniformBufferOffsetAlignment",
+        min_uniform_buffer_offset_alignment
+    ),
+    (
+        "minStorageBufferOffsetAlignment",
+        min_storage_buffer_offset_alignment
+    ),
+    (
+        "maxInterStageShaderComponents",
+        max_inter_stage_shader_components
+    ),
+    (
+        "maxComputeWorkgroupStorageSize",
+        max_compute_workgroup_storage_size
+    ),
+    (
+        "maxComputeInvocationsPerWorkgroup",
+        max_compute_invocations_per_workgroup
+    ),
+    ("maxComputeWorkgroupSizeX", max_compute_workgroup_size_x),
+    ("maxComputeWorkgroupSizeY", max_compute_workgroup_size_y),
+    ("maxComputeWorkgroupSizeZ", max_compute_workgroup_size_z),
+    (
+        "maxComputeWorkgroupsPerDimension",
+        max_compute_workgroups_per_dimension
+    ),
+    ("maxPushConstantSize", max_push_constant_size),
+    ("maxNonSamplerBindings", max_non_sampler_bindings),
+];
+
+/// Serializes [`Features`] as a list of feature names, for use with `#[serde(with = "...")]`.
+///
+/// Deserializing fails on unknown names.
+#[cfg(feature = "serde")]
+pub mod features {
+    use crate::Features;
+    use serde::{de, ser::SerializeSeq, Deserializer, Serializer};
+
+    /// Serializes `features` as a list of feature names.
+    pub fn serialize<S: Serializer>(features: &Features, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(None)?;
+        for name in features.webgpu_names() {
+            seq.serialize_element(&name)?;
+        }
+        seq.end()
+    }
+
+    /// Deserializes a list of feature names into [`Features`].
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Features, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Features;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a list of feature names")
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Features, A::Error> {
+                let mut features = Features::empty();
+                while let Some(name) = seq.next_element::<std::borrow::Cow<'de, str>>()? {
+                    features |= Features::from_webgpu_name(&name).ok_or_else(|| {
+                        de::Error::custom(format_args!("unknown feature name `{name}`"))
+                    })?;
+                }
+                Ok(features)
+            }
+        }
+
+        deserializer.deserialize_seq(Visitor)
+    }
+}
+
+#[test]
+fn feature_names() {
+    assert_eq!(
+        Features::from_webgpu_name("texture-compression-bc"),
+        Some(Features::TEXTURE_COMPRESSION_BC)
+    );
+    assert_eq!(
+        Features::from_webgpu_name("depth32float-stencil8"),
+        Some(Features::DEPTH32FLOAT_STENCIL8)
+    );
+    assert_eq!(Features::from_webgpu_name("TEXTURE_COMPRESSION_BC"), None);
+    assert_eq!(Features::from_webgpu_name("not-a-feature"), None);
+
+    let features = Features::SHADER_F16 | Features::PUSH_CONSTANTS;
+    let names: Vec<_> = features.webgpu_names().collect();
+    assert_eq!(names, ["shader-f16", "push-constants"]);
+    for name in Features::all().webgpu_names() {
+        assert!(Features::from_webgpu_name(&name).is_some(), "{name}");
+    }
+}
+
+#[test]
+fn limit_names() {
+    let mut limits = Limits::default();
+    for &name in LIMIT_NAMES {
+        assert!(limits.webgpu_limit(name).is_some(), "{name}");
+    }
+    assert_eq!(
+        limits.webgpu_limit("maxStorageBufferBindingSize"),
+        Some(limits.max_storage_buffer_binding_size.into())
+    );
+    assert!(limits.set_webgpu_limit("maxBindGroups", 8));
+    assert_eq!(limits.max_bind_groups, 8);
+    assert!(limits.set_webgpu_limit("maxBufferSize", 1 << 40));
+    assert_eq!(limits.max_buffer_size, 1 << 40);
+    assert!(!limits.set_webgpu_limit("maxBindGroups", 1 << 40));
+    assert!(!limits.set_webgpu_limit("maxBindGroupz", 8));
+    assert_eq!(limits.max_bind_groups, 8);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_names() {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Config {
+        #[serde(with = "features")]
+        features: Features,
+        limits: Limits,
+    }
+
+    let config = Config {
+        features: Features::TIMESTAMP_QUERY | Features::INDIRECT_FIRST_INSTANCE,
+        limits: Limits::default(),
+    };
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(
+        json["features"],
+        serde_json::json!(["timestamp-query", "indirect-first-instance"])
+    );
+    for &name in LIMIT_NAMES {
+        assert_eq!(
+            json["limits"][name].as_u64(),
+            config.limits.webgpu_limit(name),
+            "{name}"
+        );
+    }
+
+    let config: Config = serde_json::from_value(json).unwrap();
+    assert_eq!(
+        config.features,
+        Features::TIMESTAMP_QUERY | Features::INDIRECT_FIRST_INSTANCE
+    );
+    assert!(serde_json::from_value::<Config>(
+        serde_json::json!({ "features": ["bogus"], "limits": {} })
+    )
+    .is_err());
+}
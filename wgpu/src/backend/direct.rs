@@ -1,7 +1,8 @@
 use crate::{
     context::{ObjectId, Unused},
     AdapterInfo, BindGroupDescriptor, BindGroupLayoutDescriptor, BindingResource, BufferBinding,
-    BufferDescriptor, CommandEncoderDescriptor, ComputePassDescriptor, ComputePipelineDescriptor,
+    BufferDescriptor, CommandEncoderDescriptor, CompilationInfo, CompilationMessage,
+    CompilationMessageType, ComputePassDescriptor, ComputePipelineDescriptor,
     DownlevelCapabilities, Features, Label, Limits, LoadOp, MapMode, Operations,
     PipelineLayoutDescriptor, RenderBundleEncoderDescriptor, RenderPipelineDescriptor,
     SamplerDescriptor, ShaderModuleDescriptor, ShaderModuleDescriptorSpirV, ShaderSource,
@@ -121,10 +122,12 @@ impl Context {
             id: device_id,
             error_sink: error_sink.clone(),
             features: desc.features,
+            additional_queues: desc.additional_queues.len(),
         };
         let queue = Queue {
             id: device_id,
             error_sink,
+            additional_index: None,
         };
         Ok((device, queue))
     }
@@ -186,6 +189,43 @@ impl Context {
         )
     }
 
+    pub unsafe fn create_acceleration_structure_from_hal<A: wgc::hal_api::HalApi>(
+        &self,
+        hal_acceleration_structure: A::AccelerationStructure,
+        device: &Device,
+        desc: &crate::AccelerationStructureDescriptor,
+    ) -> wgc::id::AccelerationStructureId {
+        let global = &self.0;
+        let (id, error) = unsafe {
+            global.create_acceleration_structure_from_hal::<A>(
+                hal_acceleration_structure,
+                device.id,
+                &wgc::resource::AccelerationStructureDescriptor {
+                    label: desc.label.map(Borrowed),
+                },
+                (),
+            )
+        };
+        if let Some(cause) = error {
+            self.handle_error(
+                &device.error_sink,
+                cause,
+                LABEL,
+                desc.label,
+                "Device::create_acceleration_structure_from_hal",
+            );
+        }
+        id
+    }
+
+    pub fn acceleration_structure_drop(
+        &self,
+        acceleration_structure: &wgc::id::AccelerationStructureId,
+    ) {
+        let global = &self.0;
+        wgc::gfx_select!(*acceleration_structure => global.acceleration_structure_drop(*acceleration_structure))
+    }
+
     pub unsafe fn device_as_hal<A: wgc::hal_api::HalApi, F: FnOnce(Option<&A::Device>) -> R, R>(
         &self,
         device: &Device,
@@ -197,6 +237,57 @@ impl Context {
         }
     }
 
+    pub fn device_generate_report(&self, device: &Device) -> wgc::device::DeviceReport {
+        let global = &self.0;
+        match wgc::gfx_select!(device.id => global.device_generate_report(device.id)) {
+            Ok(report) => report,
+            Err(err) => self.handle_error_fatal(err, "Device::generate_report"),
+        }
+    }
+
+    pub fn device_snapshot(
+        &self,
+        device: &Device,
+    ) -> Result<wgc::device::snapshot::DeviceSnapshot, wgc::device::snapshot::DeviceSnapshotError>
+    {
+        let global = &self.0;
+        wgc::gfx_select!(device.id => global.device_snapshot(device.id))
+    }
+
+    pub fn device_restore(
+        &self,
+        device: &Device,
+        snapshot: &wgc::device::snapshot::DeviceSnapshot,
+    ) -> Result<(), wgc::device::snapshot::DeviceSnapshotError> {
+        let global = &self.0;
+        wgc::gfx_select!(device.id => global.device_restore(device.id, snapshot))
+    }
+
+    pub unsafe fn device_set_zero_initialization(&self, device: &Device, enabled: bool) {
+        let global = &self.0;
+        let id = device.id;
+        let res =
+            unsafe { wgc::gfx_select!(id => global.device_set_zero_initialization(id, enabled)) };
+        if let Err(cause) = res {
+            self.handle_error_fatal(cause, "Device::set_zero_initialization");
+        }
+    }
+
+    pub unsafe fn device_fence_as_hal<
+        A: wgc::hal_api::HalApi,
+        F: FnOnce(Option<&A::Fence>) -> R,
+        R,
+    >(
+        &self,
+        device: &Device,
+        hal_fence_callback: F,
+    ) -> R {
+        unsafe {
+            self.0
+                .device_fence_as_hal::<A, F, R>(device.id, hal_fence_callback)
+        }
+    }
+
     pub unsafe fn surface_as_hal_mut<
         A: wgc::hal_api::HalApi,
         F: FnOnce(Option<&mut A::Surface>) -> R,
@@ -212,6 +303,32 @@ impl Context {
         }
     }
 
+    pub fn surface_suspend(&self, surface: &Surface) {
+        if let Err(e) = self.0.surface_suspend(surface.id) {
+            self.handle_error_fatal(e, "Surface::suspend");
+        }
+        *surface.configured_device.lock() = None;
+    }
+
+    pub unsafe fn surface_resume(
+        &self,
+        surface: &Surface,
+        display_handle: raw_window_handle::RawDisplayHandle,
+        window_handle: raw_window_handle::RawWindowHandle,
+    ) -> Result<(), crate::CreateSurfaceError> {
+        let result = self
+            .0
+            .surface_resume(surface.id, display_handle, window_handle);
+        *surface.configured_device.lock() = None;
+        match result {
+            Ok(()) => Ok(()),
+            Err(wgc::present::SurfaceError::Creation(hal::InstanceError)) => {
+                Err(crate::CreateSurfaceError {})
+            }
+            Err(e) => self.handle_error_fatal(e, "Surface::resume"),
+        }
+    }
+
     pub unsafe fn texture_as_hal<A: wgc::hal_api::HalApi, F: FnOnce(Option<&A::Texture>)>(
         &self,
         texture: &Texture,
@@ -223,6 +340,44 @@ impl Context {
         }
     }
 
+    pub unsafe fn queue_as_hal_mut<
+        A: wgc::hal_api::HalApi,
+        F: FnOnce(Option<&mut A::Queue>) -> R,
+        R,
+    >(
+        &self,
+        queue: &Queue,
+        hal_queue_callback: F,
+    ) -> R {
+        unsafe {
+            self.0
+                .queue_as_hal_mut::<A, F, R>(queue.id, hal_queue_callback)
+        }
+    }
+
+    pub unsafe fn buffer_as_hal<A: wgc::hal_api::HalApi, F: FnOnce(Option<&A::Buffer>) -> R, R>(
+        &self,
+        buffer: wgc::id::BufferId,
+        hal_buffer_callback: F,
+    ) -> R {
+        unsafe { self.0.buffer_as_hal::<A, F, R>(buffer, hal_buffer_callback) }
+    }
+
+    pub unsafe fn texture_view_as_hal<
+        A: wgc::hal_api::HalApi,
+        F: FnOnce(Option<&A::TextureView>) -> R,
+        R,
+    >(
+        &self,
+        texture_view: wgc::id::TextureViewId,
+        hal_texture_view_callback: F,
+    ) -> R {
+        unsafe {
+            self.0
+                .texture_view_as_hal::<A, F, R>(texture_view, hal_texture_view_callback)
+        }
+    }
+
     pub fn generate_report(&self) -> wgc::global::GlobalReport {
         self.0.generate_report()
     }
@@ -239,6 +394,36 @@ impl Context {
         }
     }
 
+    #[cfg(all(
+        unix,
+        not(target_os = "emscripten"),
+        not(target_os = "ios"),
+        not(target_os = "macos")
+    ))]
+    pub fn enumerate_displays(&self) -> Vec<wgt::DisplayInfo> {
+        self.0.instance_enumerate_displays()
+    }
+
+    #[cfg(all(
+        unix,
+        not(target_os = "emscripten"),
+        not(target_os = "ios"),
+        not(target_os = "macos")
+    ))]
+    pub fn create_surface_from_display(
+        &self,
+        display: usize,
+    ) -> Result<Surface, crate::CreateSurfaceError> {
+        let id = self
+            .0
+            .instance_create_surface_from_display(display, ())
+            .map_err(|hal::InstanceError| crate::CreateSurfaceError {})?;
+        Ok(Surface {
+            id,
+            configured_device: Mutex::default(),
+        })
+    }
+
     #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
     pub fn instance_create_surface_from_canvas(
         &self,
@@ -308,6 +493,7 @@ impl Context {
             label_key,
         };
         let mut sink = sink_mutex.lock();
+        let mut is_internal = false;
         let mut source_opt: Option<&(dyn Error + 'static)> = Some(&error);
         while let Some(source) = source_opt {
             if let Some(wgc::device::DeviceError::OutOfMemory) =
@@ -317,12 +503,20 @@ impl Context {
                     source: Box::new(error),
                 });
             }
+            is_internal |= is_internal_error(source);
             source_opt = source.source();
         }
 
+        if is_internal {
+            return sink.handle_error(crate::Error::Internal {
+                description: self.format_error("Internal", &error),
+                source: Box::new(error),
+            });
+        }
+
         // Otherwise, it is a validation error
         sink.handle_error(crate::Error::Validation {
-            description: self.format_error(&error),
+            description: self.format_error("Validation", &error),
             source: Box::new(error),
         });
     }
@@ -342,10 +536,13 @@ impl Context {
         cause: impl Error + WasmNotSend + WasmNotSync + 'static,
         operation: &'static str,
     ) -> ! {
-        panic!("Error in {operation}: {f}", f = self.format_error(&cause));
+        panic!(
+            "Error in {operation}: {f}",
+            f = self.format_error("Validation", &cause)
+        );
     }
 
-    fn format_error(&self, err: &(impl Error + 'static)) -> String {
+    fn format_error(&self, kind: &str, err: &(impl Error + 'static)) -> String {
         let global = self.global();
         let mut err_descs = vec![];
 
@@ -361,10 +558,55 @@ impl Context {
             source_opt = source.source();
         }
 
-        format!("Validation Error\n\nCaused by:\n{}", err_descs.join(""))
+        format!("{kind} Error\n\nCaused by:\n{}", err_descs.join(""))
+    }
+
+    fn queue_submit_ids(
+        &self,
+        queue: &wgc::id::QueueId,
+        queue_data: &Queue,
+        command_buffers: &[wgc::id::CommandBufferId],
+        label: Option<&str>,
+    ) -> (Unused, wgc::device::queue::WrappedSubmissionIndex) {
+        let global = &self.0;
+        let result = match queue_data.additional_index {
+            Some(index) => {
+                wgc::gfx_select!(*queue => global.queue_submit_additional(*queue, index, command_buffers, label))
+            }
+            None => wgc::gfx_select!(*queue => global.queue_submit(*queue, command_buffers, label)),
+        };
+        let index = match result {
+            Ok(index) => index,
+            Err(err) => {
+                self.handle_error_nolabel(&queue_data.error_sink, err, "Queue::submit");
+                // Nothing was submitted, so there is nothing to wait for.
+                wgc::device::queue::WrappedSubmissionIndex {
+                    queue_id: *queue,
+                    index: 0,
+                }
+            }
+        };
+        (Unused, index)
     }
 }
 
+/// Returns `true` if `error` is a failure of the implementation or driver rather than of the
+/// API usage, which is reported as [`crate::Error::Internal`].
+fn is_internal_error(error: &(dyn Error + 'static)) -> bool {
+    use wgc::pipeline::{CreateComputePipelineError, CreateRenderPipelineError};
+
+    matches!(
+        error.downcast_ref::<wgc::device::DeviceError>(),
+        Some(wgc::device::DeviceError::ResourceCreationFailed)
+    ) || matches!(
+        error.downcast_ref::<CreateRenderPipelineError>(),
+        Some(CreateRenderPipelineError::Internal { .. })
+    ) || matches!(
+        error.downcast_ref::<CreateComputePipelineError>(),
+        Some(CreateComputePipelineError::Internal(_))
+    )
+}
+
 fn map_buffer_copy_view(view: crate::ImageCopyBuffer) -> wgc::command::ImageCopyBuffer {
     wgc::command::ImageCopyBuffer {
         buffer: view.buffer.id.into(),
@@ -458,6 +700,8 @@ pub struct Device {
     id: wgc::id::DeviceId,
     error_sink: ErrorSink,
     features: Features,
+    /// Number of queues created in addition to the main queue.
+    additional_queues: usize,
 }
 
 impl Device {
@@ -491,6 +735,15 @@ impl Texture {
 pub struct Queue {
     id: wgc::id::QueueId,
     error_sink: ErrorSink,
+    /// Index of the queue in `DeviceDescriptor::additional_queues`, or
+    /// `None` for the main queue.
+    additional_index: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct RenderBundleEncoder {
+    encoder: wgc::command::RenderBundleEncoder,
+    error_sink: ErrorSink,
 }
 
 impl Queue {
@@ -507,6 +760,68 @@ pub struct CommandEncoder {
     open: bool,
 }
 
+#[derive(Debug)]
+pub struct ShaderModule {
+    compilation_info: CompilationInfo,
+}
+
+impl From<&wgc::pipeline::CreateShaderModuleError> for CompilationInfo {
+    fn from(error: &wgc::pipeline::CreateShaderModuleError) -> Self {
+        use wgc::pipeline::CreateShaderModuleError as Csme;
+
+        fn message(
+            message: String,
+            location: Option<wgc::naga::SourceLocation>,
+        ) -> CompilationMessage {
+            CompilationMessage {
+                message,
+                message_type: CompilationMessageType::Error,
+                location: location.map(|location| crate::SourceLocation {
+                    line_number: location.line_number,
+                    line_position: location.line_position,
+                    offset: location.offset,
+                    length: location.length,
+                }),
+            }
+        }
+
+        let messages = match *error {
+            #[cfg(feature = "wgsl")]
+            Csme::Parsing(ref err) => vec![message(
+                err.inner.message().to_string(),
+                err.inner.location(&err.source),
+            )],
+            #[cfg(feature = "glsl")]
+            Csme::ParsingGlsl(ref err) => err
+                .inner
+                .errors
+                .iter()
+                .map(|inner| {
+                    let location = inner
+                        .meta
+                        .is_defined()
+                        .then(|| inner.meta.location(&err.source));
+                    message(inner.kind.to_string(), location)
+                })
+                .collect(),
+            Csme::Validation(ref err) => {
+                // Naga puts the specifics of validation errors in their sources.
+                let mut text = err.inner.to_string();
+                let mut source = err.inner.source();
+                while let Some(inner) = source {
+                    text = format!("{text}: {inner}");
+                    source = inner.source();
+                }
+                vec![message(text, err.inner.location(&err.source))]
+            }
+            // These aren't problems with the shader itself.
+            Csme::Device(_) | Csme::Generation => Vec::new(),
+            _ => vec![message(error.to_string(), None)],
+        };
+        CompilationInfo { messages }
+    }
+}
+
 impl crate::Context for Context {
     type AdapterId = wgc::id::AdapterId;
     type AdapterData = ();
@@ -515,7 +830,7 @@ impl crate::Context for Context {
     type QueueId = wgc::id::QueueId;
     type QueueData = Queue;
     type ShaderModuleId = wgc::id::ShaderModuleId;
-    type ShaderModuleData = ();
+    type ShaderModuleData = ShaderModule;
     type BindGroupLayoutId = wgc::id::BindGroupLayoutId;
     type BindGroupLayoutData = ();
     type BindGroupId = wgc::id::BindGroupId;
@@ -536,6 +851,8 @@ impl crate::Context for Context {
     type RenderPipelineData = ();
     type ComputePipelineId = wgc::id::ComputePipelineId;
     type ComputePipelineData = ();
+    type PipelineCacheId = wgc::id::PipelineCacheId;
+    type PipelineCacheData = ();
     type CommandEncoderId = wgc::id::CommandEncoderId;
     type CommandEncoderData = CommandEncoder;
     type ComputePassId = Unused;
@@ -545,7 +862,7 @@ impl crate::Context for Context {
     type CommandBufferId = wgc::id::CommandBufferId;
     type CommandBufferData = ();
     type RenderBundleEncoderId = Unused;
-    type RenderBundleEncoderData = wgc::command::RenderBundleEncoder;
+    type RenderBundleEncoderData = RenderBundleEncoder;
     type RenderBundleId = wgc::id::RenderBundleId;
     type RenderBundleData = ();
 
@@ -571,6 +888,7 @@ impl crate::Context for Context {
     >;
 
     type PopErrorScopeFuture = Ready<Option<crate::Error>>;
+    type CompilationInfoFuture = Ready<CompilationInfo>;
 
     fn init(instance_desc: wgt::InstanceDescriptor) -> Self {
         Self(wgc::global::Global::new(
@@ -608,7 +926,9 @@ impl crate::Context for Context {
                 force_fallback_adapter: options.force_fallback_adapter,
                 compatible_surface: options.compatible_surface.map(|surface| surface.id.into()),
             },
-            wgc::instance::AdapterInputs::Mask(wgt::Backends::all(), |_| ()),
+            // The instance only has the backends it was created with, which may
+            // include the noop backend that isn't part of `Backends::all()`.
+            wgc::instance::AdapterInputs::Mask(wgt::Backends::all() | wgt::Backends::NOOP, |_| ()),
         );
         ready(id.ok().map(|id| (id, ())))
     }
@@ -636,10 +956,12 @@ impl crate::Context for Context {
             id: device_id,
             error_sink: error_sink.clone(),
             features: desc.features,
+            additional_queues: desc.additional_queues.len(),
         };
         let queue = Queue {
             id: device_id,
             error_sink,
+            additional_index: None,
         };
         ready(Ok((device_id, device, device_id, queue)))
     }
@@ -908,15 +1230,11 @@ impl crate::Context for Context {
                 stage,
                 ref defines,
             } => {
-                // Parse the given shader code and store its representation.
                 let options = naga::front::glsl::Options {
                     stage,
                     defines: defines.clone(),
                 };
-                let mut parser = naga::front::glsl::Frontend::default();
-                let module = parser.parse(&options, shader).unwrap();
-
-                wgc::pipeline::ShaderModuleSource::Naga(Owned(module))
+                wgc::pipeline::ShaderModuleSource::Glsl(Borrowed(shader), options)
             }
             #[cfg(feature = "wgsl")]
             ShaderSource::Wgsl(ref code) => wgc::pipeline::ShaderModuleSource::Wgsl(Borrowed(code)),
@@ -927,16 +1245,21 @@ impl crate::Context for Context {
         let (id, error) = wgc::gfx_select!(
             device => global.device_create_shader_module(*device, &descriptor, source, ())
         );
-        if let Some(cause) = error {
-            self.handle_error(
-                &device_data.error_sink,
-                cause,
-                LABEL,
-                desc.label,
-                "Device::create_shader_module",
-            );
-        }
-        (id, ())
+        let compilation_info = match error {
+            Some(cause) => {
+                let compilation_info = CompilationInfo::from(&cause);
+                self.handle_error(
+                    &device_data.error_sink,
+                    cause,
+                    LABEL,
+                    desc.label,
+                    "Device::create_shader_module",
+                );
+                compilation_info
+            }
+            None => CompilationInfo::default(),
+        };
+        (id, ShaderModule { compilation_info })
     }
 
     unsafe fn device_create_shader_module_spirv(
@@ -955,16 +1278,29 @@ impl crate::Context for Context {
         let (id, error) = wgc::gfx_select!(
             device => global.device_create_shader_module_spirv(*device, &descriptor, Borrowed(&desc.source), ())
         );
-        if let Some(cause) = error {
-            self.handle_error(
-                &device_data.error_sink,
-                cause,
-                LABEL,
-                desc.label,
-                "Device::create_shader_module_spirv",
-            );
-        }
-        (id, ())
+        let compilation_info = match error {
+            Some(cause) => {
+                let compilation_info = CompilationInfo::from(&cause);
+                self.handle_error(
+                    &device_data.error_sink,
+                    cause,
+                    LABEL,
+                    desc.label,
+                    "Device::create_shader_module_spirv",
+                );
+                compilation_info
+            }
+            None => CompilationInfo::default(),
+        };
+        (id, ShaderModule { compilation_info })
+    }
+
+    fn shader_get_compilation_info(
+        &self,
+        _shader: &Self::ShaderModuleId,
+        shader_data: &Self::ShaderModuleData,
+    ) -> Self::CompilationInfoFuture {
+        ready(shader_data.compilation_info.clone())
     }
 
     fn device_create_bind_group_layout(
@@ -1081,6 +1417,9 @@ impl crate::Context for Context {
                             &remaining_arrayed_texture_views[array.len()..];
                         bm::BindingResource::TextureViewArray(Owned(views))
                     }
+                    BindingResource::AccelerationStructure(acceleration_structure) => {
+                        bm::BindingResource::AccelerationStructure(acceleration_structure.id.into())
+                    }
                 },
             })
             .collect::<Vec<_>>();
@@ -1183,6 +1522,7 @@ impl crate::Context for Context {
                 stage: pipe::ProgrammableStageDescriptor {
                     module: desc.vertex.module.id.into(),
                     entry_point: Borrowed(desc.vertex.entry_point),
+                    constants: Borrowed(desc.vertex.constants),
                 },
                 buffers: Borrowed(&vertex_buffers),
             },
@@ -1193,10 +1533,12 @@ impl crate::Context for Context {
                 stage: pipe::ProgrammableStageDescriptor {
                     module: frag.module.id.into(),
                     entry_point: Borrowed(frag.entry_point),
+                    constants: Borrowed(frag.constants),
                 },
                 targets: Borrowed(frag.targets),
             }),
             multiview: desc.multiview,
+            cache: desc.cache.map(|c| c.id.into()),
         };
 
         let global = &self.0;
@@ -1242,7 +1584,9 @@ impl crate::Context for Context {
             stage: pipe::ProgrammableStageDescriptor {
                 module: desc.module.id.into(),
                 entry_point: Borrowed(desc.entry_point),
+                constants: Borrowed(desc.constants),
             },
+            cache: desc.cache.map(|c| c.id.into()),
         };
 
         let global = &self.0;
@@ -1271,6 +1615,33 @@ impl crate::Context for Context {
         }
         (id, ())
     }
+    unsafe fn device_create_pipeline_cache(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &crate::PipelineCacheDescriptor<'_>,
+    ) -> (Self::PipelineCacheId, Self::PipelineCacheData) {
+        let descriptor = wgc::pipeline::PipelineCacheDescriptor {
+            label: desc.label.map(Borrowed),
+            data: desc.data.map(Borrowed),
+        };
+        let global = &self.0;
+        let (id, error) = wgc::gfx_select!(device => global.device_create_pipeline_cache(
+            *device,
+            &descriptor,
+            ()
+        ));
+        if let Some(cause) = error {
+            self.handle_error(
+                &device_data.error_sink,
+                cause,
+                LABEL,
+                desc.label,
+                "Device::create_pipeline_cache",
+            );
+        }
+        (id, ())
+    }
     fn device_create_buffer(
         &self,
         device: &Self::DeviceId,
@@ -1415,10 +1786,71 @@ impl crate::Context for Context {
             },
         )
     }
+
+    fn device_create_command_encoder_for_queue(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        _queue: &Self::QueueId,
+        queue_data: &Self::QueueData,
+        desc: &CommandEncoderDescriptor,
+    ) -> (Self::CommandEncoderId, Self::CommandEncoderData) {
+        let index = match queue_data.additional_index {
+            Some(index) => index,
+            None => {
+                return crate::Context::device_create_command_encoder(
+                    self,
+                    device,
+                    device_data,
+                    desc,
+                )
+            }
+        };
+        let global = &self.0;
+        let (id, error) = wgc::gfx_select!(device => global.device_create_command_encoder_for_queue(
+            *device,
+            index,
+            &desc.map_label(|l| l.map(Borrowed)),
+            ()
+        ));
+        if let Some(cause) = error {
+            self.handle_error(
+                &device_data.error_sink,
+                cause,
+                LABEL,
+                desc.label,
+                "Device::create_command_encoder_for_queue",
+            );
+        }
+        (
+            id,
+            CommandEncoder {
+                error_sink: Arc::clone(&device_data.error_sink),
+                open: true,
+            },
+        )
+    }
+
+    fn device_additional_queue(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        index: usize,
+    ) -> Option<(Self::QueueId, Self::QueueData)> {
+        (index < device_data.additional_queues).then(|| {
+            let queue = Queue {
+                id: *device,
+                error_sink: Arc::clone(&device_data.error_sink),
+                additional_index: Some(index),
+            };
+            (*device, queue)
+        })
+    }
+
     fn device_create_render_bundle_encoder(
         &self,
         device: &Self::DeviceId,
-        _device_data: &Self::DeviceData,
+        device_data: &Self::DeviceData,
         desc: &RenderBundleEncoderDescriptor,
     ) -> (Self::RenderBundleEncoderId, Self::RenderBundleEncoderData) {
         let descriptor = wgc::command::RenderBundleEncoderDescriptor {
@@ -1429,7 +1861,13 @@ impl crate::Context for Context {
             multiview: desc.multiview,
         };
         match wgc::command::RenderBundleEncoder::new(&descriptor, *device, None) {
-            Ok(encoder) => (Unused, encoder),
+            Ok(encoder) => (
+                Unused,
+                RenderBundleEncoder {
+                    encoder,
+                    error_sink: Arc::clone(&device_data.error_sink),
+                },
+            ),
             Err(e) => panic!("Error in Device::create_render_bundle_encoder: {e}"),
         }
     }
@@ -1441,6 +1879,8 @@ impl crate::Context for Context {
         {
             match wgc::gfx_select!(device => global.device_poll(*device, wgt::Maintain::Wait)) {
                 Ok(_) => (),
+                // Nothing is left to wait for on a lost device.
+                Err(wgc::device::WaitIdleError::Device(wgc::device::DeviceError::Lost)) => (),
                 Err(err) => self.handle_error_fatal(err, "Device::drop"),
             }
         }
@@ -1460,6 +1900,8 @@ impl crate::Context for Context {
             maintain_inner
         )) {
             Ok(queue_empty) => queue_empty,
+            // The device lost callback reports the loss, and no work remains.
+            Err(wgc::device::WaitIdleError::Device(wgc::device::DeviceError::Lost)) => true,
             Err(err) => self.handle_error_fatal(err, "Device::poll"),
         }
     }
@@ -1472,6 +1914,20 @@ impl crate::Context for Context {
         let mut error_sink = device_data.error_sink.lock();
         error_sink.uncaptured_handler = handler;
     }
+    fn device_set_device_lost_callback(
+        &self,
+        device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        device_lost_callback: crate::context::DeviceLostCallback,
+    ) {
+        let closure = wgc::device::DeviceLostClosure::from_rust(device_lost_callback);
+        let global = &self.0;
+        let res =
+            wgc::gfx_select!(device => global.device_set_device_lost_closure(*device, closure));
+        if let Err(cause) = res {
+            self.handle_error_fatal(cause, "Device::set_device_lost_callback");
+        }
+    }
     fn device_push_error_scope(
         &self,
         _device: &Self::DeviceId,
@@ -1490,7 +1946,10 @@ impl crate::Context for Context {
         device_data: &Self::DeviceData,
     ) -> Self::PopErrorScopeFuture {
         let mut error_sink = device_data.error_sink.lock();
-        let scope = error_sink.scopes.pop().unwrap();
+        let scope = error_sink
+            .scopes
+            .pop()
+            .expect("Mismatched pop_error_scope call: no error scope is active");
         ready(scope.error)
     }
 
@@ -1508,7 +1967,7 @@ impl crate::Context for Context {
                 MapMode::Write => wgc::device::HostMap::Write,
             },
             callback: wgc::resource::BufferMapCallback::from_rust(Box::new(|status| {
-                let res = status.map_err(|_| crate::BufferAsyncError);
+                let res = status.map_err(map_buffer_async_error);
                 callback(res);
             })),
         };
@@ -1569,6 +2028,7 @@ impl crate::Context for Context {
                 base_array_layer: desc.base_array_layer,
                 array_layer_count: desc.array_layer_count,
             },
+            swizzle: desc.swizzle,
         };
         let global = &self.0;
         let (id, error) = wgc::gfx_select!(
@@ -1601,6 +2061,22 @@ impl crate::Context for Context {
         let _ = wgc::gfx_select!(buffer => global.buffer_destroy(*buffer));
     }
 
+    fn buffer_resize(
+        &self,
+        buffer: &Self::BufferId,
+        buffer_data: &Self::BufferData,
+        size: wgt::BufferAddress,
+    ) -> bool {
+        let global = &self.0;
+        match wgc::gfx_select!(buffer => global.buffer_resize(*buffer, size)) {
+            Ok(()) => true,
+            Err(cause) => {
+                self.handle_error_nolabel(&buffer_data.error_sink, cause, "Buffer::resize");
+                false
+            }
+        }
+    }
+
     fn buffer_drop(&self, buffer: &Self::BufferId, _buffer_data: &Self::BufferData) {
         let global = &self.0;
         wgc::gfx_select!(buffer => global.buffer_drop(*buffer, false))
@@ -1717,6 +2193,15 @@ impl crate::Context for Context {
         wgc::gfx_select!(*pipeline => global.render_pipeline_drop(*pipeline))
     }
 
+    fn pipeline_cache_drop(
+        &self,
+        cache: &Self::PipelineCacheId,
+        _cache_data: &Self::PipelineCacheData,
+    ) {
+        let global = &self.0;
+        wgc::gfx_select!(*cache => global.pipeline_cache_drop(*cache))
+    }
+
     fn compute_pipeline_get_bind_group_layout(
         &self,
         pipeline: &Self::ComputePipelineId,
@@ -1745,6 +2230,15 @@ impl crate::Context for Context {
         (id, ())
     }
 
+    fn pipeline_cache_get_data(
+        &self,
+        cache: &Self::PipelineCacheId,
+        _cache_data: &Self::PipelineCacheData,
+    ) -> Option<Vec<u8>> {
+        let global = &self.0;
+        wgc::gfx_select!(*cache => global.pipeline_cache_get_data(*cache))
+    }
+
     fn command_encoder_copy_buffer_to_buffer(
         &self,
         encoder: &Self::CommandEncoderId,
@@ -1913,6 +2407,7 @@ impl crate::Context for Context {
                 ca.as_ref()
                     .map(|at| wgc::command::RenderPassColorAttachment {
                         view: at.view.id.into(),
+                        depth_slice: at.depth_slice,
                         resolve_target: at.resolve_target.map(|rt| rt.id.into()),
                         channel: map_pass_channel(Some(&at.ops)),
                     })
@@ -1979,8 +2474,12 @@ impl crate::Context for Context {
         &self,
         encoder: Self::CommandEncoderId,
         encoder_data: &mut Self::CommandEncoderData,
+        reusable: bool,
     ) -> (Self::CommandBufferId, Self::CommandBufferData) {
-        let descriptor = wgt::CommandBufferDescriptor::default();
+        let descriptor = wgt::CommandBufferDescriptor {
+            label: None,
+            reusable,
+        };
         encoder_data.open = false; // prevent the drop
         let global = &self.0;
         let (id, error) =
@@ -2145,13 +2644,23 @@ impl crate::Context for Context {
         desc: &crate::RenderBundleDescriptor,
     ) -> (Self::RenderBundleId, Self::RenderBundleData) {
         let global = &self.0;
-        let (id, error) = wgc::gfx_select!(encoder_data.parent() => global.render_bundle_encoder_finish(
-            encoder_data,
+        let RenderBundleEncoder {
+            encoder,
+            error_sink,
+        } = encoder_data;
+        let (id, error) = wgc::gfx_select!(encoder.parent() => global.render_bundle_encoder_finish(
+            encoder,
             &desc.map_label(|l| l.map(Borrowed)),
             ()
         ));
         if let Some(err) = error {
-            self.handle_error_fatal(err, "RenderBundleEncoder::finish");
+            self.handle_error(
+                &error_sink,
+                err,
+                LABEL,
+                desc.label,
+                "RenderBundleEncoder::finish",
+            );
         }
         (id, ())
     }
@@ -2269,7 +2778,7 @@ impl crate::Context for Context {
         }
     }
 
-    #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+    #[cfg(not(target_os = "emscripten"))]
     fn queue_copy_external_image_to_texture(
         &self,
         queue: &Self::QueueId,
@@ -2297,20 +2806,25 @@ impl crate::Context for Context {
     fn queue_submit<I: Iterator<Item = (Self::CommandBufferId, Self::CommandBufferData)>>(
         &self,
         queue: &Self::QueueId,
-        _queue_data: &Self::QueueData,
+        queue_data: &Self::QueueData,
         command_buffers: I,
+        label: Option<&str>,
     ) -> (Self::SubmissionIndex, Self::SubmissionIndexData) {
         let temp_command_buffers = command_buffers
             .map(|(i, _)| i)
             .collect::<SmallVec<[_; 4]>>();
+        self.queue_submit_ids(queue, queue_data, &temp_command_buffers, label)
+    }
 
-        let global = &self.0;
-        let index = match wgc::gfx_select!(*queue => global.queue_submit(*queue, &temp_command_buffers))
-        {
-            Ok(index) => index,
-            Err(err) => self.handle_error_fatal(err, "Queue::submit"),
-        };
-        (Unused, index)
+    fn queue_submit_reusable<I: Iterator<Item = Self::CommandBufferId>>(
+        &self,
+        queue: &Self::QueueId,
+        queue_data: &Self::QueueData,
+        command_buffers: I,
+        label: Option<&str>,
+    ) -> (Self::SubmissionIndex, Self::SubmissionIndexData) {
+        let temp_command_buffers = command_buffers.collect::<SmallVec<[_; 4]>>();
+        self.queue_submit_ids(queue, queue_data, &temp_command_buffers, label)
     }
 
     fn queue_get_timestamp_period(
@@ -2493,7 +3007,7 @@ impl crate::Context for Context {
         pipeline: &Self::RenderPipelineId,
         _pipeline_data: &Self::RenderPipelineData,
     ) {
-        wgpu_render_bundle_set_pipeline(encoder_data, *pipeline)
+        wgpu_render_bundle_set_pipeline(&mut encoder_data.encoder, *pipeline)
     }
 
     fn render_bundle_encoder_set_bind_group(
@@ -2507,7 +3021,7 @@ impl crate::Context for Context {
     ) {
         unsafe {
             wgpu_render_bundle_set_bind_group(
-                encoder_data,
+                &mut encoder_data.encoder,
                 index,
                 *bind_group,
                 offsets.as_ptr(),
@@ -2526,7 +3040,9 @@ impl crate::Context for Context {
         offset: wgt::BufferAddress,
         size: Option<wgt::BufferSize>,
     ) {
-        encoder_data.set_index_buffer(*buffer, index_format, offset, size)
+        encoder_data
+            .encoder
+            .set_index_buffer(*buffer, index_format, offset, size)
     }
 
     fn render_bundle_encoder_set_vertex_buffer(
@@ -2539,7 +3055,7 @@ impl crate::Context for Context {
         offset: wgt::BufferAddress,
         size: Option<wgt::BufferSize>,
     ) {
-        wgpu_render_bundle_set_vertex_buffer(encoder_data, slot, *buffer, offset, size)
+        wgpu_render_bundle_set_vertex_buffer(&mut encoder_data.encoder, slot, *buffer, offset, size)
     }
 
     fn render_bundle_encoder_set_push_constants(
@@ -2552,7 +3068,7 @@ impl crate::Context for Context {
     ) {
         unsafe {
             wgpu_render_bundle_set_push_constants(
-                encoder_data,
+                &mut encoder_data.encoder,
                 stages,
                 offset,
                 data.len().try_into().unwrap(),
@@ -2561,6 +3077,38 @@ impl crate::Context for Context {
         }
     }
 
+    fn render_bundle_encoder_insert_debug_marker(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        label: &str,
+    ) {
+        unsafe {
+            let label = std::ffi::CString::new(label).unwrap();
+            wgpu_render_bundle_insert_debug_marker(&mut encoder_data.encoder, label.as_ptr());
+        }
+    }
+
+    fn render_bundle_encoder_push_debug_group(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        group_label: &str,
+    ) {
+        unsafe {
+            let label = std::ffi::CString::new(group_label).unwrap();
+            wgpu_render_bundle_push_debug_group(&mut encoder_data.encoder, label.as_ptr());
+        }
+    }
+
+    fn render_bundle_encoder_pop_debug_group(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+    ) {
+        wgpu_render_bundle_pop_debug_group(&mut encoder_data.encoder);
+    }
+
     fn render_bundle_encoder_draw(
         &self,
         _encoder: &mut Self::RenderBundleEncoderId,
@@ -2569,7 +3117,7 @@ impl crate::Context for Context {
         instances: Range<u32>,
     ) {
         wgpu_render_bundle_draw(
-            encoder_data,
+            &mut encoder_data.encoder,
             vertices.end - vertices.start,
             instances.end - instances.start,
             vertices.start,
@@ -2586,7 +3134,7 @@ impl crate::Context for Context {
         instances: Range<u32>,
     ) {
         wgpu_render_bundle_draw_indexed(
-            encoder_data,
+            &mut encoder_data.encoder,
             indices.end - indices.start,
             instances.end - instances.start,
             indices.start,
@@ -2603,7 +3151,11 @@ impl crate::Context for Context {
         _indirect_buffer_data: &Self::BufferData,
         indirect_offset: wgt::BufferAddress,
     ) {
-        wgpu_render_bundle_draw_indirect(encoder_data, *indirect_buffer, indirect_offset)
+        wgpu_render_bundle_draw_indirect(
+            &mut encoder_data.encoder,
+            *indirect_buffer,
+            indirect_offset,
+        )
     }
 
     fn render_bundle_encoder_draw_indexed_indirect(
@@ -2614,7 +3166,11 @@ impl crate::Context for Context {
         _indirect_buffer_data: &Self::BufferData,
         indirect_offset: wgt::BufferAddress,
     ) {
-        wgpu_render_bundle_draw_indexed_indirect(encoder_data, *indirect_buffer, indirect_offset)
+        wgpu_render_bundle_draw_indexed_indirect(
+            &mut encoder_data.encoder,
+            *indirect_buffer,
+            indirect_offset,
+        )
     }
 
     fn render_bundle_encoder_multi_draw_indirect(
@@ -2919,6 +3475,63 @@ impl crate::Context for Context {
         wgpu_render_pass_set_stencil_reference(pass_data, reference)
     }
 
+    fn render_pass_set_shading_rate(
+        &self,
+        _pass: &mut Self::RenderPassId,
+        pass_data: &mut Self::RenderPassData,
+        rate: wgt::ShadingRate,
+    ) {
+        wgpu_render_pass_set_shading_rate(pass_data, rate)
+    }
+
+    fn render_pass_set_depth_bounds(
+        &self,
+        _pass: &mut Self::RenderPassId,
+        pass_data: &mut Self::RenderPassData,
+        min: f32,
+        max: f32,
+    ) {
+        wgpu_render_pass_set_depth_bounds(pass_data, min, max)
+    }
+
+    fn render_pass_set_viewports(
+        &self,
+        _pass: &mut Self::RenderPassId,
+        pass_data: &mut Self::RenderPassData,
+        viewports: &[wgt::Viewport],
+    ) {
+        for (index, viewport) in viewports.iter().enumerate() {
+            wgpu_render_pass_set_viewport_indexed(
+                pass_data,
+                index as u32,
+                viewport.x,
+                viewport.y,
+                viewport.width,
+                viewport.height,
+                viewport.min_depth,
+                viewport.max_depth,
+            )
+        }
+    }
+
+    fn render_pass_set_scissor_rects(
+        &self,
+        _pass: &mut Self::RenderPassId,
+        pass_data: &mut Self::RenderPassData,
+        rects: &[wgt::ScissorRect],
+    ) {
+        for (index, rect) in rects.iter().enumerate() {
+            wgpu_render_pass_set_scissor_rect_indexed(
+                pass_data,
+                index as u32,
+                rect.x,
+                rect.y,
+                rect.width,
+                rect.height,
+            )
+        }
+    }
+
     fn render_pass_insert_debug_marker(
         &self,
         _pass: &mut Self::RenderPassId,
@@ -3065,6 +3678,7 @@ impl ErrorSinkRaw {
         let filter = match err {
             crate::Error::OutOfMemory { .. } => crate::ErrorFilter::OutOfMemory,
             crate::Error::Validation { .. } => crate::ErrorFilter::Validation,
+            crate::Error::Internal { .. } => crate::ErrorFilter::Internal,
         };
         match self
             .scopes
@@ -3090,6 +3704,18 @@ impl fmt::Debug for ErrorSinkRaw {
     }
 }
 
+fn map_buffer_async_error(error: wgc::resource::BufferAccessError) -> crate::BufferAsyncError {
+    use wgc::{device::DeviceError, resource::BufferAccessError};
+    match error {
+        BufferAccessError::Device(DeviceError::Lost) => crate::BufferAsyncError::DeviceLost,
+        BufferAccessError::Device(DeviceError::OutOfMemory) => crate::BufferAsyncError::OutOfMemory,
+        BufferAccessError::AlreadyMapped => crate::BufferAsyncError::AlreadyMapped,
+        BufferAccessError::MapAlreadyPending => crate::BufferAsyncError::MapAlreadyPending,
+        BufferAccessError::MapAborted => crate::BufferAsyncError::Aborted,
+        other => crate::BufferAsyncError::Validation(other.to_string()),
+    }
+}
+
 fn default_error_handler(err: crate::Error) {
     log::error!("Handling wgpu errors as fatal by default");
     panic!("wgpu error: {err}\n");
@@ -120,6 +120,11 @@ impl crate::Error {
             }
         } else if js_error.has_type::<web_sys::GpuOutOfMemoryError>() {
             crate::Error::OutOfMemory { source }
+        } else if let Some(js_error) = js_error.dyn_ref::<web_sys::GpuInternalError>() {
+            crate::Error::Internal {
+                source,
+                description: js_error.message(),
+            }
         } else {
             panic!("Unexpected error");
         }
@@ -396,18 +401,19 @@ fn map_depth_stencil_state(desc: &wgt::DepthStencilState) -> web_sys::GpuDepthSt
     mapped
 }
 
-fn map_blend_component(desc: &wgt::BlendComponent) -> web_sys::GpuBlendComponent {
+/// Returns `None` if the component uses a blend factor that WebGPU has no equivalent for.
+fn map_blend_component(desc: &wgt::BlendComponent) -> Option<web_sys::GpuBlendComponent> {
     let mut mapped = web_sys::GpuBlendComponent::new();
-    mapped.dst_factor(map_blend_factor(desc.dst_factor));
+    mapped.dst_factor(map_blend_factor(desc.dst_factor)?);
     mapped.operation(map_blend_operation(desc.operation));
-    mapped.src_factor(map_blend_factor(desc.src_factor));
-    mapped
+    mapped.src_factor(map_blend_factor(desc.src_factor)?);
+    Some(mapped)
 }
 
-fn map_blend_factor(factor: wgt::BlendFactor) -> web_sys::GpuBlendFactor {
+fn map_blend_factor(factor: wgt::BlendFactor) -> Option<web_sys::GpuBlendFactor> {
     use web_sys::GpuBlendFactor as bf;
     use wgt::BlendFactor;
-    match factor {
+    Some(match factor {
         BlendFactor::Zero => bf::Zero,
         BlendFactor::One => bf::One,
         BlendFactor::Src => bf::Src,
@@ -421,7 +427,11 @@ fn map_blend_factor(factor: wgt::BlendFactor) -> web_sys::GpuBlendFactor {
         BlendFactor::SrcAlphaSaturated => bf::SrcAlphaSaturated,
         BlendFactor::Constant => bf::Constant,
         BlendFactor::OneMinusConstant => bf::OneMinusConstant,
-    }
+        BlendFactor::Src1
+        | BlendFactor::OneMinusSrc1
+        | BlendFactor::Src1Alpha
+        | BlendFactor::OneMinusSrc1Alpha => return None,
+    })
 }
 
 fn map_blend_operation(op: wgt::BlendOperation) -> web_sys::GpuBlendOperation {
@@ -571,7 +581,14 @@ fn map_tagged_texture_copy_view(
     let mut mapped = web_sys::GpuImageCopyTextureTagged::new(&texture.0);
     mapped.mip_level(view.mip_level);
     mapped.origin(&map_origin_3d(view.origin));
-    mapped.aspect(map_texture_aspect(view.aspect));
+    match map_texture_aspect(view.aspect) {
+        Some(aspect) => mapped.aspect(aspect),
+        None => {
+            log::error!("Aspect {:?} has no WebGPU equivalent", view.aspect);
+            // No texture has this many mip levels, so the browser rejects the copy.
+            mapped.mip_level(u32::MAX)
+        }
+    };
     // mapped.color_space(map_color_space(view.color_space));
     mapped.premultiplied_alpha(view.premultiplied_alpha);
     mapped
@@ -586,12 +603,14 @@ fn map_external_texture_copy_view(
     mapped
 }
 
-fn map_texture_aspect(aspect: wgt::TextureAspect) -> web_sys::GpuTextureAspect {
-    match aspect {
+/// Returns `None` for the aspects of multi-planar formats, which WebGPU doesn't have.
+fn map_texture_aspect(aspect: wgt::TextureAspect) -> Option<web_sys::GpuTextureAspect> {
+    Some(match aspect {
         wgt::TextureAspect::All => web_sys::GpuTextureAspect::All,
         wgt::TextureAspect::StencilOnly => web_sys::GpuTextureAspect::StencilOnly,
         wgt::TextureAspect::DepthOnly => web_sys::GpuTextureAspect::DepthOnly,
-    }
+        wgt::TextureAspect::Plane0 | wgt::TextureAspect::Plane1 => return None,
+    })
 }
 
 fn map_filter_mode(mode: wgt::FilterMode) -> web_sys::GpuFilterMode {
@@ -684,6 +703,10 @@ fn map_wgt_features(supported_features: web_sys::GpuSupportedFeatures) -> wgt::F
             _ => {}
         }
     }
+    // The full BCn family implies its BC1-3 subset.
+    if features.contains(wgt::Features::TEXTURE_COMPRESSION_BC) {
+        features |= wgt::Features::TEXTURE_COMPRESSION_S3TC;
+    }
     features
 }
 
@@ -725,6 +748,21 @@ fn map_wgt_limits(limits: web_sys::GpuSupportedLimits) -> wgt::Limits {
     }
 }
 
+/// Sets the `constants` member of a programmable stage.
+// TODO: Migrate to a web_sys api once it exposes `constants`.
+fn set_pipeline_constants(stage: &JsValue, constants: &std::collections::HashMap<String, f64>) {
+    if constants.is_empty() {
+        return;
+    }
+    let object = js_sys::Object::new();
+    for (key, &value) in constants.iter() {
+        js_sys::Reflect::set(&object, &JsValue::from(key.as_str()), &JsValue::from(value))
+            .expect("Setting Object properties should never fail.");
+    }
+    js_sys::Reflect::set(stage, &JsValue::from("constants"), &object)
+        .expect("Setting Object properties should never fail.");
+}
+
 fn map_js_sys_limits(limits: &wgt::Limits) -> js_sys::Object {
     let object = js_sys::Object::new();
 
@@ -825,6 +863,41 @@ fn future_pop_error_scope(result: JsFutureResult) -> Option<crate::Error> {
     }
 }
 
+fn future_compilation_info(result: JsFutureResult) -> crate::CompilationInfo {
+    let js_info: web_sys::GpuCompilationInfo = match result {
+        Ok(js_value) => js_value.unchecked_into(),
+        Err(_) => return crate::CompilationInfo::default(),
+    };
+    let messages = js_info
+        .messages()
+        .iter()
+        .map(|js_message| {
+            let js_message: web_sys::GpuCompilationMessage = js_message.unchecked_into();
+            // A line number of 0 means the message isn't tied to a location.
+            let location = (js_message.line_num() != 0.0).then(|| crate::SourceLocation {
+                line_number: js_message.line_num() as u32,
+                line_position: js_message.line_pos() as u32,
+                offset: js_message.offset() as u32,
+                length: js_message.length() as u32,
+            });
+            crate::CompilationMessage {
+                message: js_message.message(),
+                message_type: match js_message.type_() {
+                    web_sys::GpuCompilationMessageType::Error => {
+                        crate::CompilationMessageType::Error
+                    }
+                    web_sys::GpuCompilationMessageType::Warning => {
+                        crate::CompilationMessageType::Warning
+                    }
+                    _ => crate::CompilationMessageType::Info,
+                },
+                location,
+            }
+        })
+        .collect();
+    crate::CompilationInfo { messages }
+}
+
 /// Calls `callback(success_value)` when the promise completes successfully, calls `callback(failure_value)`
 /// when the promise completes unsuccessfully.
 fn register_then_closures<F, T>(promise: &Promise, callback: F, success_value: T, failure_value: T)
@@ -990,6 +1063,8 @@ impl crate::context::Context for Context {
     type RenderPipelineData = Sendable<web_sys::GpuRenderPipeline>;
     type ComputePipelineId = Identified<web_sys::GpuComputePipeline>;
     type ComputePipelineData = Sendable<web_sys::GpuComputePipeline>;
+    type PipelineCacheId = Identified<()>;
+    type PipelineCacheData = Sendable<()>;
     type CommandEncoderId = Identified<web_sys::GpuCommandEncoder>;
     type CommandEncoderData = Sendable<web_sys::GpuCommandEncoder>;
     type ComputePassId = Identified<web_sys::GpuComputePassEncoder>;
@@ -1029,6 +1104,10 @@ impl crate::context::Context for Context {
     >;
     type PopErrorScopeFuture =
         MakeSendFuture<wasm_bindgen_futures::JsFuture, fn(JsFutureResult) -> Option<crate::Error>>;
+    type CompilationInfoFuture = MakeSendFuture<
+        wasm_bindgen_futures::JsFuture,
+        fn(JsFutureResult) -> crate::CompilationInfo,
+    >;
 
     fn init(_instance_desc: wgt::InstanceDescriptor) -> Self {
         let global: Global = js_sys::global().unchecked_into();
@@ -1056,12 +1135,13 @@ impl crate::context::Context for Context {
             raw_window_handle::RawWindowHandle::Web(web_handle) => web_handle.id,
             _ => panic!("expected valid handle for canvas"),
         };
-        let canvas_node: wasm_bindgen::JsValue = web_sys::window()
+        // Workers have no document, their surfaces are created from an `OffscreenCanvas`.
+        let document = web_sys::window()
             .and_then(|win| win.document())
-            .and_then(|doc| {
-                doc.query_selector_all(&format!("[data-raw-handle=\"{canvas_attribute}\"]"))
-                    .ok()
-            })
+            .ok_or(crate::CreateSurfaceError {})?;
+        let canvas_node: wasm_bindgen::JsValue = document
+            .query_selector_all(&format!("[data-raw-handle=\"{canvas_attribute}\"]"))
+            .ok()
             .and_then(|nodes| nodes.get(0))
             .expect("expected to find single canvas")
             .into();
@@ -1120,11 +1200,16 @@ impl crate::context::Context for Context {
         )
         .expect("Setting Object properties should never fail.");
 
+        // The BC1-3 subset is only available on the web through the full BCn family.
+        let mut features = desc.features;
+        if features.contains(wgt::Features::TEXTURE_COMPRESSION_S3TC) {
+            features |= wgt::Features::TEXTURE_COMPRESSION_BC;
+        }
         let required_features = FEATURES_MAPPING
             .iter()
             .copied()
             .flat_map(|(flag, value)| {
-                if desc.features.contains(flag) {
+                if features.contains(flag) {
                     Some(JsValue::from(value))
                 } else {
                     None
@@ -1137,7 +1222,13 @@ impl crate::context::Context for Context {
             mapped_desc.label(label);
         }
 
-        let device_promise = adapter_data.0.request_device_with_descriptor(&mapped_desc);
+        let device_promise = if desc.additional_queues.is_empty() {
+            adapter_data.0.request_device_with_descriptor(&mapped_desc)
+        } else {
+            js_sys::Promise::reject(&JsValue::from_str(
+                "Additional queues are not supported on the web",
+            ))
+        };
 
         MakeSendFuture::new(
             wasm_bindgen_futures::JsFuture::from(device_promise),
@@ -1239,12 +1330,17 @@ impl crate::context::Context for Context {
             formats.swap(0, index);
         }
 
+        let color_spaces = formats
+            .iter()
+            .map(|&format| (format, wgt::SurfaceColorSpace::Srgb))
+            .collect();
         wgt::SurfaceCapabilities {
             // https://gpuweb.github.io/gpuweb/#supported-context-formats
             formats,
             // Doesn't really have meaning on the web.
             present_modes: vec![wgt::PresentMode::Fifo],
             alpha_modes: vec![wgt::CompositeAlphaMode::Opaque],
+            color_spaces,
             // Statically set to RENDER_ATTACHMENT for now. See https://gpuweb.github.io/gpuweb/#dom-gpucanvasconfiguration-usage
             usages: wgt::TextureUsages::RENDER_ATTACHMENT,
         }
@@ -1277,12 +1373,18 @@ impl crate::context::Context for Context {
         {
             panic!("Only Opaque/Auto or PreMultiplied alpha mode are supported on web");
         }
+        if config.color_space != wgt::SurfaceColorSpace::Srgb {
+            panic!("Only the Srgb color space is supported on web");
+        }
         let alpha_mode = match config.alpha_mode {
             wgt::CompositeAlphaMode::PreMultiplied => web_sys::GpuCanvasAlphaMode::Premultiplied,
             _ => web_sys::GpuCanvasAlphaMode::Opaque,
         };
         let mut mapped =
             web_sys::GpuCanvasConfiguration::new(&device_data.0, map_texture_format(config.format));
+        if config.usage.contains(wgt::TextureUsages::PROTECTED) {
+            panic!("PROTECTED_CONTENT feature must be enabled to configure protected surfaces")
+        }
         mapped.usage(config.usage.bits());
         mapped.alpha_mode(alpha_mode);
         let mapped_view_formats = config
@@ -1454,6 +1556,18 @@ impl crate::context::Context for Context {
         unreachable!("SPIRV_SHADER_PASSTHROUGH is not enabled for this backend")
     }
 
+    fn shader_get_compilation_info(
+        &self,
+        _shader: &Self::ShaderModuleId,
+        shader_data: &Self::ShaderModuleData,
+    ) -> Self::CompilationInfoFuture {
+        let compilation_info_promise = shader_data.0.compilation_info();
+        MakeSendFuture::new(
+            wasm_bindgen_futures::JsFuture::from(compilation_info_promise),
+            future_compilation_info,
+        )
+    }
+
     fn device_create_bind_group_layout(
         &self,
         _device: &Self::DeviceId,
@@ -1540,6 +1654,9 @@ impl crate::context::Context for Context {
                         storage_texture.view_dimension(map_texture_view_dimension(view_dimension));
                         mapped_entry.storage_texture(&storage_texture);
                     }
+                    wgt::BindingType::AccelerationStructure => {
+                        panic!("AccelerationStructure is not available")
+                    }
                 }
 
                 mapped_entry
@@ -1644,6 +1761,7 @@ impl crate::context::Context for Context {
             downcast_ref(desc.vertex.module.data.as_ref());
         let mut mapped_vertex_state =
             web_sys::GpuVertexState::new(desc.vertex.entry_point, &module.0);
+        set_pipeline_constants(&mapped_vertex_state, desc.vertex.constants);
 
         let buffers = desc
             .vertex
@@ -1703,13 +1821,28 @@ impl crate::context::Context for Context {
                         let mapped_format = map_texture_format(target.format);
                         let mut mapped_color_state =
                             web_sys::GpuColorTargetState::new(mapped_format);
+                        let mut write_mask = target.write_mask.bits();
                         if let Some(ref bs) = target.blend {
-                            let alpha = map_blend_component(&bs.alpha);
-                            let color = map_blend_component(&bs.color);
-                            let mapped_blend_state = web_sys::GpuBlendState::new(&alpha, &color);
-                            mapped_color_state.blend(&mapped_blend_state);
+                            match (
+                                map_blend_component(&bs.alpha),
+                                map_blend_component(&bs.color),
+                            ) {
+                                (Some(alpha), Some(color)) => {
+                                    let mapped_blend_state =
+                                        web_sys::GpuBlendState::new(&alpha, &color);
+                                    mapped_color_state.blend(&mapped_blend_state);
+                                }
+                                _ => {
+                                    log::error!(
+                                        "Dual-source blend factors require `Features::DUAL_SOURCE_BLENDING`, which has no WebGPU equivalent"
+                                    );
+                                    // A write mask with bits past `GPUColorWrite.ALL` makes the
+                                    // browser reject the pipeline.
+                                    write_mask = !wgt::ColorWrites::ALL.bits();
+                                }
+                            }
                         }
-                        mapped_color_state.write_mask(target.write_mask.bits());
+                        mapped_color_state.write_mask(write_mask);
                         wasm_bindgen::JsValue::from(mapped_color_state)
                     }
                     None => wasm_bindgen::JsValue::null(),
@@ -1719,6 +1852,7 @@ impl crate::context::Context for Context {
                 downcast_ref(frag.module.data.as_ref());
             let mapped_fragment_desc =
                 web_sys::GpuFragmentState::new(frag.entry_point, &module.0, &targets);
+            set_pipeline_constants(&mapped_fragment_desc, frag.constants);
             mapped_desc.fragment(&mapped_fragment_desc);
         }
 
@@ -1744,6 +1878,7 @@ impl crate::context::Context for Context {
             downcast_ref(desc.module.data.as_ref());
         let mapped_compute_stage =
             web_sys::GpuProgrammableStage::new(desc.entry_point, &shader_module.0);
+        set_pipeline_constants(&mapped_compute_stage, desc.constants);
         let auto_layout = wasm_bindgen::JsValue::from(web_sys::GpuAutoLayoutMode::Auto);
         let mut mapped_desc = web_sys::GpuComputePipelineDescriptor::new(
             &match desc.layout {
@@ -1762,12 +1897,29 @@ impl crate::context::Context for Context {
         create_identified(device_data.0.create_compute_pipeline(&mapped_desc))
     }
 
+    unsafe fn device_create_pipeline_cache(
+        &self,
+        _device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        _desc: &crate::PipelineCacheDescriptor<'_>,
+    ) -> (Self::PipelineCacheId, Self::PipelineCacheData) {
+        // WebGPU has no pipeline caches; pipelines are cached by the browser. The cache is only
+        // a hint, so pipelines created with it are still valid.
+        log::error!(
+            "Pipeline caches require `Features::PIPELINE_CACHE`, which has no WebGPU equivalent"
+        );
+        create_identified(())
+    }
+
     fn device_create_buffer(
         &self,
         _device: &Self::DeviceId,
         device_data: &Self::DeviceData,
         desc: &crate::BufferDescriptor,
     ) -> (Self::BufferId, Self::BufferData) {
+        if desc.usage.contains(wgt::BufferUsages::PROTECTED) {
+            panic!("PROTECTED_CONTENT feature must be enabled to create protected buffers")
+        }
         let mut mapped_desc =
             web_sys::GpuBufferDescriptor::new(desc.size as f64, desc.usage.bits());
         mapped_desc.mapped_at_creation(desc.mapped_at_creation);
@@ -1783,10 +1935,14 @@ impl crate::context::Context for Context {
         device_data: &Self::DeviceData,
         desc: &crate::TextureDescriptor,
     ) -> (Self::TextureId, Self::TextureData) {
+        if desc.usage.contains(wgt::TextureUsages::PROTECTED) {
+            panic!("PROTECTED_CONTENT feature must be enabled to create protected textures")
+        }
         let mut mapped_desc = web_sys::GpuTextureDescriptor::new(
             map_texture_format(desc.format),
             &map_extent_3d(desc.size),
-            desc.usage.bits(),
+            // `TRANSIENT` is a hint that WebGPU doesn't have
+            (desc.usage - wgt::TextureUsages::TRANSIENT).bits(),
         );
         if let Some(label) = desc.label {
             mapped_desc.label(label);
@@ -1853,6 +2009,9 @@ impl crate::context::Context for Context {
         device_data: &Self::DeviceData,
         desc: &crate::CommandEncoderDescriptor,
     ) -> (Self::CommandEncoderId, Self::CommandEncoderData) {
+        if desc.protected {
+            panic!("PROTECTED_CONTENT feature must be enabled to create protected command encoders")
+        }
         let mut mapped_desc = web_sys::GpuCommandEncoderDescriptor::new();
         if let Some(label) = desc.label {
             mapped_desc.label(label);
@@ -1864,6 +2023,27 @@ impl crate::context::Context for Context {
         )
     }
 
+    fn device_create_command_encoder_for_queue(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        _queue: &Self::QueueId,
+        _queue_data: &Self::QueueData,
+        desc: &crate::CommandEncoderDescriptor,
+    ) -> (Self::CommandEncoderId, Self::CommandEncoderData) {
+        // There are no additional queues, so this is the main queue.
+        crate::context::Context::device_create_command_encoder(self, device, device_data, desc)
+    }
+
+    fn device_additional_queue(
+        &self,
+        _device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        _index: usize,
+    ) -> Option<(Self::QueueId, Self::QueueData)> {
+        None
+    }
+
     fn device_create_render_bundle_encoder(
         &self,
         _device: &Self::DeviceId,
@@ -1922,6 +2102,21 @@ impl crate::context::Context for Context {
         f.forget();
     }
 
+    fn device_set_device_lost_callback(
+        &self,
+        _device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        device_lost_callback: crate::context::DeviceLostCallback,
+    ) {
+        let closure = Closure::once(move |info: JsValue| {
+            let info = info.unchecked_into::<web_sys::GpuDeviceLostInfo>();
+            device_lost_callback(wgt::DeviceLostReason::Unknown, info.message());
+        });
+        let _ = device_data.0.lost().then(&closure);
+        // The promise resolves at most once, the closure lives until then.
+        closure.forget();
+    }
+
     fn device_push_error_scope(
         &self,
         _device: &Self::DeviceId,
@@ -1931,6 +2126,7 @@ impl crate::context::Context for Context {
         device_data.0.push_error_scope(match filter {
             crate::ErrorFilter::OutOfMemory => web_sys::GpuErrorFilter::OutOfMemory,
             crate::ErrorFilter::Validation => web_sys::GpuErrorFilter::Validation,
+            crate::ErrorFilter::Internal => web_sys::GpuErrorFilter::Internal,
         });
     }
 
@@ -1960,7 +2156,14 @@ impl crate::context::Context for Context {
             (range.end - range.start) as f64,
         );
 
-        register_then_closures(&map_promise, callback, Ok(()), Err(crate::BufferAsyncError));
+        register_then_closures(
+            &map_promise,
+            callback,
+            Ok(()),
+            Err(crate::BufferAsyncError::Validation(
+                "mapAsync promise was rejected".to_string(),
+            )),
+        );
     }
 
     fn buffer_get_mapped_range(
@@ -2008,7 +2211,6 @@ impl crate::context::Context for Context {
         if let Some(format) = desc.format {
             mapped.format(map_texture_format(format));
         }
-        mapped.aspect(map_texture_aspect(desc.aspect));
         mapped.base_array_layer(desc.base_array_layer);
         if let Some(count) = desc.array_layer_count {
             mapped.array_layer_count(count);
@@ -2017,6 +2219,22 @@ impl crate::context::Context for Context {
         if let Some(count) = desc.mip_level_count {
             mapped.mip_level_count(count);
         }
+        match map_texture_aspect(desc.aspect) {
+            Some(aspect) => {
+                mapped.aspect(aspect);
+            }
+            None => {
+                log::error!("Aspect {:?} has no WebGPU equivalent", desc.aspect);
+                // An empty mip range makes the browser reject the view.
+                mapped.mip_level_count(0);
+            }
+        }
+        if desc.swizzle.map_or(false, |swizzle| !swizzle.is_identity()) {
+            log::error!(
+                "Texture view swizzles require `Features::TEXTURE_COMPONENT_SWIZZLE`, which has no WebGPU equivalent"
+            );
+            mapped.mip_level_count(0);
+        }
         if let Some(label) = desc.label {
             mapped.label(label);
         }
@@ -2135,6 +2353,13 @@ impl crate::context::Context for Context {
         // Dropped automatically
     }
 
+    fn pipeline_cache_drop(
+        &self,
+        _cache: &Self::PipelineCacheId,
+        _cache_data: &Self::PipelineCacheData,
+    ) {
+    }
+
     fn compute_pipeline_get_bind_group_layout(
         &self,
         _pipeline: &Self::ComputePipelineId,
@@ -2153,6 +2378,14 @@ impl crate::context::Context for Context {
         create_identified(pipeline_data.0.get_bind_group_layout(index))
     }
 
+    fn pipeline_cache_get_data(
+        &self,
+        _cache: &Self::PipelineCacheId,
+        _cache_data: &Self::PipelineCacheData,
+    ) -> Option<Vec<u8>> {
+        None
+    }
+
     fn command_encoder_copy_buffer_to_buffer(
         &self,
         _encoder: &Self::CommandEncoderId,
@@ -2291,6 +2524,15 @@ impl crate::context::Context for Context {
                         mapped_color_attachment.resolve_target(&resolve_target_view.0);
                     }
                     mapped_color_attachment.store_op(map_store_op(ca.ops.store));
+                    // TODO: Migrate to a web_sys api once it exposes `depthSlice`.
+                    if let Some(depth_slice) = ca.depth_slice {
+                        js_sys::Reflect::set(
+                            &mapped_color_attachment,
+                            &JsValue::from("depthSlice"),
+                            &JsValue::from(depth_slice),
+                        )
+                        .expect("Setting Object properties should never fail.");
+                    }
 
                     wasm_bindgen::JsValue::from(mapped_color_attachment)
                 }
@@ -2353,7 +2595,11 @@ impl crate::context::Context for Context {
         &self,
         _encoder: Self::CommandEncoderId,
         encoder_data: &mut Self::CommandEncoderData,
+        reusable: bool,
     ) -> (Self::CommandBufferId, Self::CommandBufferData) {
+        if reusable {
+            panic!("REUSABLE_COMMAND_BUFFERS feature must be enabled to finish a reusable command buffer")
+        }
         let label = encoder_data.0.label();
         create_identified(if label.is_empty() {
             encoder_data.0.finish()
@@ -2371,7 +2617,7 @@ impl crate::context::Context for Context {
         _texture: &crate::Texture,
         _subresource_range: &wgt::ImageSubresourceRange,
     ) {
-        //TODO
+        panic!("CLEAR_TEXTURE feature must be enabled to call clear_texture")
     }
 
     fn command_encoder_clear_buffer(
@@ -2633,6 +2879,7 @@ impl crate::context::Context for Context {
         _queue: &Self::QueueId,
         queue_data: &Self::QueueData,
         command_buffers: I,
+        _label: Option<&str>,
     ) -> (Self::SubmissionIndex, Self::SubmissionIndexData) {
         let temp_command_buffers = command_buffers
             .map(|(_, data)| data.0)
@@ -2643,6 +2890,18 @@ impl crate::context::Context for Context {
         (Unused, ())
     }
 
+    fn queue_submit_reusable<I: Iterator<Item = Self::CommandBufferId>>(
+        &self,
+        _queue: &Self::QueueId,
+        _queue_data: &Self::QueueData,
+        _command_buffers: I,
+        _label: Option<&str>,
+    ) -> (Self::SubmissionIndex, Self::SubmissionIndexData) {
+        panic!(
+            "REUSABLE_COMMAND_BUFFERS feature must be enabled to submit a reusable command buffer"
+        )
+    }
+
     fn queue_get_timestamp_period(
         &self,
         _queue: &Self::QueueId,
@@ -2894,6 +3153,35 @@ impl crate::context::Context for Context {
         panic!("PUSH_CONSTANTS feature must be enabled to call multi_draw_indexed_indirect")
     }
 
+    fn render_bundle_encoder_insert_debug_marker(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        _encoder_data: &mut Self::RenderBundleEncoderData,
+        _label: &str,
+    ) {
+        // Not available in gecko yet
+        // encoder_data.0.insert_debug_marker(label);
+    }
+
+    fn render_bundle_encoder_push_debug_group(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        _encoder_data: &mut Self::RenderBundleEncoderData,
+        _group_label: &str,
+    ) {
+        // Not available in gecko yet
+        // encoder_data.0.push_debug_group(group_label);
+    }
+
+    fn render_bundle_encoder_pop_debug_group(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        _encoder_data: &mut Self::RenderBundleEncoderData,
+    ) {
+        // Not available in gecko yet
+        // encoder_data.0.pop_debug_group();
+    }
+
     fn render_bundle_encoder_draw(
         &self,
         _encoder: &mut Self::RenderBundleEncoderId,
@@ -3279,6 +3567,43 @@ impl crate::context::Context for Context {
         pass_data.0.set_stencil_reference(reference);
     }
 
+    fn render_pass_set_shading_rate(
+        &self,
+        _pass: &mut Self::RenderPassId,
+        _pass_data: &mut Self::RenderPassData,
+        _rate: wgt::ShadingRate,
+    ) {
+        unreachable!("SHADING_RATE is not supported on WebGPU")
+    }
+
+    fn render_pass_set_depth_bounds(
+        &self,
+        _pass: &mut Self::RenderPassId,
+        _pass_data: &mut Self::RenderPassData,
+        _min: f32,
+        _max: f32,
+    ) {
+        unreachable!("DEPTH_BOUNDS_TEST is not supported on WebGPU")
+    }
+
+    fn render_pass_set_viewports(
+        &self,
+        _pass: &mut Self::RenderPassId,
+        _pass_data: &mut Self::RenderPassData,
+        _viewports: &[wgt::Viewport],
+    ) {
+        unreachable!("MULTI_VIEWPORT is not supported on WebGPU")
+    }
+
+    fn render_pass_set_scissor_rects(
+        &self,
+        _pass: &mut Self::RenderPassId,
+        _pass_data: &mut Self::RenderPassData,
+        _rects: &[wgt::ScissorRect],
+    ) {
+        unreachable!("MULTI_VIEWPORT is not supported on WebGPU")
+    }
+
     fn render_pass_insert_debug_marker(
         &self,
         _pass: &mut Self::RenderPassId,
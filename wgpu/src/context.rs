@@ -9,8 +9,9 @@ use wgt::{
 
 use crate::{
     AnyWasmNotSendSync, BindGroupDescriptor, BindGroupLayoutDescriptor, Buffer, BufferAsyncError,
-    BufferDescriptor, CommandEncoderDescriptor, ComputePassDescriptor, ComputePipelineDescriptor,
-    DeviceDescriptor, Error, ErrorFilter, ImageCopyBuffer, ImageCopyTexture, Maintain, MapMode,
+    BufferDescriptor, CommandEncoderDescriptor, CompilationInfo, ComputePassDescriptor,
+    ComputePipelineDescriptor, DeviceDescriptor, DeviceLostReason, Error, ErrorFilter,
+    ImageCopyBuffer, ImageCopyTexture, Maintain, MapMode, PipelineCacheDescriptor,
     PipelineLayoutDescriptor, QuerySetDescriptor, RenderBundleDescriptor,
     RenderBundleEncoderDescriptor, RenderPassDescriptor, RenderPipelineDescriptor,
     RequestAdapterOptions, RequestDeviceError, SamplerDescriptor, ShaderModuleDescriptor,
@@ -59,6 +60,8 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
     type RenderPipelineData: ContextData;
     type ComputePipelineId: ContextId + WasmNotSend + WasmNotSync;
     type ComputePipelineData: ContextData;
+    type PipelineCacheId: ContextId + WasmNotSend + WasmNotSync;
+    type PipelineCacheData: ContextData;
     type CommandEncoderId: ContextId + WasmNotSend + WasmNotSync;
     type CommandEncoderData: ContextData;
     type ComputePassId: ContextId;
@@ -94,6 +97,7 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         > + WasmNotSend
         + 'static;
     type PopErrorScopeFuture: Future<Output = Option<Error>> + WasmNotSend + 'static;
+    type CompilationInfoFuture: Future<Output = CompilationInfo> + WasmNotSend + 'static;
 
     fn init(instance_desc: wgt::InstanceDescriptor) -> Self;
     fn instance_create_surface(
@@ -202,6 +206,11 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         device_data: &Self::DeviceData,
         desc: &ShaderModuleDescriptorSpirV,
     ) -> (Self::ShaderModuleId, Self::ShaderModuleData);
+    fn shader_get_compilation_info(
+        &self,
+        shader: &Self::ShaderModuleId,
+        shader_data: &Self::ShaderModuleData,
+    ) -> Self::CompilationInfoFuture;
     fn device_create_bind_group_layout(
         &self,
         device: &Self::DeviceId,
@@ -232,6 +241,12 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         device_data: &Self::DeviceData,
         desc: &ComputePipelineDescriptor,
     ) -> (Self::ComputePipelineId, Self::ComputePipelineData);
+    unsafe fn device_create_pipeline_cache(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &PipelineCacheDescriptor,
+    ) -> (Self::PipelineCacheId, Self::PipelineCacheData);
     fn device_create_buffer(
         &self,
         device: &Self::DeviceId,
@@ -262,6 +277,20 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         device_data: &Self::DeviceData,
         desc: &CommandEncoderDescriptor,
     ) -> (Self::CommandEncoderId, Self::CommandEncoderData);
+    fn device_create_command_encoder_for_queue(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        queue: &Self::QueueId,
+        queue_data: &Self::QueueData,
+        desc: &CommandEncoderDescriptor,
+    ) -> (Self::CommandEncoderId, Self::CommandEncoderData);
+    fn device_additional_queue(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        index: usize,
+    ) -> Option<(Self::QueueId, Self::QueueData)>;
     fn device_create_render_bundle_encoder(
         &self,
         device: &Self::DeviceId,
@@ -281,6 +310,12 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         device_data: &Self::DeviceData,
         handler: Box<dyn UncapturedErrorHandler>,
     );
+    fn device_set_device_lost_callback(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        device_lost_callback: DeviceLostCallback,
+    );
     fn device_push_error_scope(
         &self,
         device: &Self::DeviceId,
@@ -328,6 +363,18 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
     fn surface_drop(&self, surface: &Self::SurfaceId, surface_data: &Self::SurfaceData);
     fn adapter_drop(&self, adapter: &Self::AdapterId, adapter_data: &Self::AdapterData);
     fn buffer_destroy(&self, buffer: &Self::BufferId, buffer_data: &Self::BufferData);
+    /// Returns `true` if the buffer was resized.
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    fn buffer_resize(
+        &self,
+        buffer: &Self::BufferId,
+        buffer_data: &Self::BufferData,
+        size: wgt::BufferAddress,
+    ) -> bool;
     fn buffer_drop(&self, buffer: &Self::BufferId, buffer_data: &Self::BufferData);
     fn texture_destroy(&self, texture: &Self::TextureId, texture_data: &Self::TextureData);
     fn texture_drop(&self, texture: &Self::TextureId, texture_data: &Self::TextureData);
@@ -383,6 +430,11 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         pipeline: &Self::RenderPipelineId,
         pipeline_data: &Self::RenderPipelineData,
     );
+    fn pipeline_cache_drop(
+        &self,
+        cache: &Self::PipelineCacheId,
+        cache_data: &Self::PipelineCacheData,
+    );
 
     fn compute_pipeline_get_bind_group_layout(
         &self,
@@ -396,6 +448,11 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         pipeline_data: &Self::RenderPipelineData,
         index: u32,
     ) -> (Self::BindGroupLayoutId, Self::BindGroupLayoutData);
+    fn pipeline_cache_get_data(
+        &self,
+        cache: &Self::PipelineCacheId,
+        cache_data: &Self::PipelineCacheData,
+    ) -> Option<Vec<u8>>;
 
     #[allow(clippy::too_many_arguments)]
     fn command_encoder_copy_buffer_to_buffer(
@@ -465,6 +522,7 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         &self,
         encoder: Self::CommandEncoderId,
         encoder_data: &mut Self::CommandEncoderData,
+        reusable: bool,
     ) -> (Self::CommandBufferId, Self::CommandBufferData);
 
     fn command_encoder_clear_texture(
@@ -571,7 +629,7 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         data_layout: ImageDataLayout,
         size: Extent3d,
     );
-    #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+    #[cfg(not(target_os = "emscripten"))]
     fn queue_copy_external_image_to_texture(
         &self,
         queue: &Self::QueueId,
@@ -585,6 +643,14 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         queue: &Self::QueueId,
         queue_data: &Self::QueueData,
         command_buffers: I,
+        label: Option<&str>,
+    ) -> (Self::SubmissionIndex, Self::SubmissionIndexData);
+    fn queue_submit_reusable<I: Iterator<Item = Self::CommandBufferId>>(
+        &self,
+        queue: &Self::QueueId,
+        queue_data: &Self::QueueData,
+        command_buffers: I,
+        label: Option<&str>,
     ) -> (Self::SubmissionIndex, Self::SubmissionIndexData);
     fn queue_get_timestamp_period(
         &self,
@@ -725,6 +791,23 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         offset: u32,
         data: &[u8],
     );
+    fn render_bundle_encoder_insert_debug_marker(
+        &self,
+        encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        label: &str,
+    );
+    fn render_bundle_encoder_push_debug_group(
+        &self,
+        encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        group_label: &str,
+    );
+    fn render_bundle_encoder_pop_debug_group(
+        &self,
+        encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+    );
     fn render_bundle_encoder_draw(
         &self,
         encoder: &mut Self::RenderBundleEncoderId,
@@ -955,6 +1038,31 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         pass_data: &mut Self::RenderPassData,
         reference: u32,
     );
+    fn render_pass_set_shading_rate(
+        &self,
+        pass: &mut Self::RenderPassId,
+        pass_data: &mut Self::RenderPassData,
+        rate: wgt::ShadingRate,
+    );
+    fn render_pass_set_depth_bounds(
+        &self,
+        pass: &mut Self::RenderPassId,
+        pass_data: &mut Self::RenderPassData,
+        min: f32,
+        max: f32,
+    );
+    fn render_pass_set_viewports(
+        &self,
+        pass: &mut Self::RenderPassId,
+        pass_data: &mut Self::RenderPassData,
+        viewports: &[wgt::Viewport],
+    );
+    fn render_pass_set_scissor_rects(
+        &self,
+        pass: &mut Self::RenderPassId,
+        pass_data: &mut Self::RenderPassData,
+        rects: &[wgt::ScissorRect],
+    );
     fn render_pass_insert_debug_marker(
         &self,
         pass: &mut Self::RenderPassId,
@@ -1181,6 +1289,23 @@ pub type DevicePopErrorFuture = Box<dyn Future<Output = Option<Error>> + Send>;
 )))]
 pub type DevicePopErrorFuture = Box<dyn Future<Output = Option<Error>>>;
 
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+pub type ShaderCompilationInfoFuture = Box<dyn Future<Output = CompilationInfo> + Send>;
+#[cfg(not(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+)))]
+pub type ShaderCompilationInfoFuture = Box<dyn Future<Output = CompilationInfo>>;
+
 #[cfg(any(
     not(target_arch = "wasm32"),
     all(
@@ -1197,6 +1322,22 @@ pub type SubmittedWorkDoneCallback = Box<dyn FnOnce() + Send + 'static>;
     )
 )))]
 pub type SubmittedWorkDoneCallback = Box<dyn FnOnce() + 'static>;
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+pub type DeviceLostCallback = Box<dyn FnOnce(DeviceLostReason, String) + Send + 'static>;
+#[cfg(not(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+)))]
+pub type DeviceLostCallback = Box<dyn FnOnce(DeviceLostReason, String) + 'static>;
 
 /// An object safe variant of [`Context`] implemented by all types that implement [`Context`].
 pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
@@ -1296,6 +1437,11 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         device_data: &crate::Data,
         desc: &ShaderModuleDescriptorSpirV,
     ) -> (ObjectId, Box<crate::Data>);
+    fn shader_get_compilation_info(
+        &self,
+        shader: &ObjectId,
+        shader_data: &crate::Data,
+    ) -> Pin<ShaderCompilationInfoFuture>;
     fn device_create_bind_group_layout(
         &self,
         device: &ObjectId,
@@ -1326,6 +1472,12 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         device_data: &crate::Data,
         desc: &ComputePipelineDescriptor,
     ) -> (ObjectId, Box<crate::Data>);
+    unsafe fn device_create_pipeline_cache(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &PipelineCacheDescriptor,
+    ) -> (ObjectId, Box<crate::Data>);
     fn device_create_buffer(
         &self,
         device: &ObjectId,
@@ -1356,6 +1508,20 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         device_data: &crate::Data,
         desc: &CommandEncoderDescriptor,
     ) -> (ObjectId, Box<crate::Data>);
+    fn device_create_command_encoder_for_queue(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        queue: &ObjectId,
+        queue_data: &crate::Data,
+        desc: &CommandEncoderDescriptor,
+    ) -> (ObjectId, Box<crate::Data>);
+    fn device_additional_queue(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        index: usize,
+    ) -> Option<(ObjectId, Box<crate::Data>)>;
     fn device_create_render_bundle_encoder(
         &self,
         device: &ObjectId,
@@ -1371,6 +1537,12 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         device_data: &crate::Data,
         handler: Box<dyn UncapturedErrorHandler>,
     );
+    fn device_set_device_lost_callback(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        device_lost_callback: DeviceLostCallback,
+    );
     fn device_push_error_scope(
         &self,
         device: &ObjectId,
@@ -1417,6 +1589,17 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
     fn surface_drop(&self, surface: &ObjectId, surface_data: &crate::Data);
     fn adapter_drop(&self, adapter: &ObjectId, adapter_data: &crate::Data);
     fn buffer_destroy(&self, buffer: &ObjectId, buffer_data: &crate::Data);
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    fn buffer_resize(
+        &self,
+        buffer: &ObjectId,
+        buffer_data: &crate::Data,
+        size: wgt::BufferAddress,
+    ) -> bool;
     fn buffer_drop(&self, buffer: &ObjectId, buffer_data: &crate::Data);
     fn texture_destroy(&self, buffer: &ObjectId, buffer_data: &crate::Data);
     fn texture_drop(&self, texture: &ObjectId, texture_data: &crate::Data);
@@ -1436,6 +1619,7 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
     fn render_bundle_drop(&self, render_bundle: &ObjectId, render_bundle_data: &crate::Data);
     fn compute_pipeline_drop(&self, pipeline: &ObjectId, pipeline_data: &crate::Data);
     fn render_pipeline_drop(&self, pipeline: &ObjectId, pipeline_data: &crate::Data);
+    fn pipeline_cache_drop(&self, cache: &ObjectId, cache_data: &crate::Data);
 
     fn compute_pipeline_get_bind_group_layout(
         &self,
@@ -1449,6 +1633,11 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         pipeline_data: &crate::Data,
         index: u32,
     ) -> (ObjectId, Box<crate::Data>);
+    fn pipeline_cache_get_data(
+        &self,
+        cache: &ObjectId,
+        cache_data: &crate::Data,
+    ) -> Option<Vec<u8>>;
 
     #[allow(clippy::too_many_arguments)]
     fn command_encoder_copy_buffer_to_buffer(
@@ -1518,6 +1707,7 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         &self,
         encoder: ObjectId,
         encoder_data: &mut crate::Data,
+        reusable: bool,
     ) -> (ObjectId, Box<crate::Data>);
 
     fn command_encoder_clear_texture(
@@ -1620,7 +1810,7 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         data_layout: ImageDataLayout,
         size: Extent3d,
     );
-    #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+    #[cfg(not(target_os = "emscripten"))]
     fn queue_copy_external_image_to_texture(
         &self,
         queue: &ObjectId,
@@ -1634,6 +1824,14 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         queue: &ObjectId,
         queue_data: &crate::Data,
         command_buffers: Box<dyn Iterator<Item = (ObjectId, Box<crate::Data>)> + 'a>,
+        label: Option<&str>,
+    ) -> (ObjectId, Arc<crate::Data>);
+    fn queue_submit_reusable<'a>(
+        &self,
+        queue: &ObjectId,
+        queue_data: &crate::Data,
+        command_buffers: Box<dyn Iterator<Item = ObjectId> + 'a>,
+        label: Option<&str>,
     ) -> (ObjectId, Arc<crate::Data>);
     fn queue_get_timestamp_period(&self, queue: &ObjectId, queue_data: &crate::Data) -> f32;
     fn queue_on_submitted_work_done(
@@ -1766,6 +1964,23 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         offset: u32,
         data: &[u8],
     );
+    fn render_bundle_encoder_insert_debug_marker(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        label: &str,
+    );
+    fn render_bundle_encoder_push_debug_group(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        group_label: &str,
+    );
+    fn render_bundle_encoder_pop_debug_group(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+    );
     fn render_bundle_encoder_draw(
         &self,
         encoder: &mut ObjectId,
@@ -1996,6 +2211,31 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         pass_data: &mut crate::Data,
         reference: u32,
     );
+    fn render_pass_set_shading_rate(
+        &self,
+        pass: &mut ObjectId,
+        pass_data: &mut crate::Data,
+        rate: wgt::ShadingRate,
+    );
+    fn render_pass_set_depth_bounds(
+        &self,
+        pass: &mut ObjectId,
+        pass_data: &mut crate::Data,
+        min: f32,
+        max: f32,
+    );
+    fn render_pass_set_viewports(
+        &self,
+        pass: &mut ObjectId,
+        pass_data: &mut crate::Data,
+        viewports: &[wgt::Viewport],
+    );
+    fn render_pass_set_scissor_rects(
+        &self,
+        pass: &mut ObjectId,
+        pass_data: &mut crate::Data,
+        rects: &[wgt::ScissorRect],
+    );
     fn render_pass_insert_debug_marker(
         &self,
         pass: &mut ObjectId,
@@ -2279,6 +2519,20 @@ where
         (shader_module.into(), Box::new(data) as _)
     }
 
+    fn shader_get_compilation_info(
+        &self,
+        shader: &ObjectId,
+        shader_data: &crate::Data,
+    ) -> Pin<ShaderCompilationInfoFuture> {
+        let shader = <T::ShaderModuleId>::from(*shader);
+        let shader_data = downcast_ref(shader_data);
+        Box::pin(Context::shader_get_compilation_info(
+            self,
+            &shader,
+            shader_data,
+        ))
+    }
+
     fn device_create_bind_group_layout(
         &self,
         device: &ObjectId,
@@ -2344,6 +2598,19 @@ where
         (compute_pipeline.into(), Box::new(data) as _)
     }
 
+    unsafe fn device_create_pipeline_cache(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &PipelineCacheDescriptor,
+    ) -> (ObjectId, Box<crate::Data>) {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        let (pipeline_cache, data) =
+            unsafe { Context::device_create_pipeline_cache(self, &device, device_data, desc) };
+        (pipeline_cache.into(), Box::new(data) as _)
+    }
+
     fn device_create_buffer(
         &self,
         device: &ObjectId,
@@ -2405,6 +2672,41 @@ where
         (command_encoder.into(), Box::new(data) as _)
     }
 
+    fn device_create_command_encoder_for_queue(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        queue: &ObjectId,
+        queue_data: &crate::Data,
+        desc: &CommandEncoderDescriptor,
+    ) -> (ObjectId, Box<crate::Data>) {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        let queue = <T::QueueId>::from(*queue);
+        let queue_data = downcast_ref(queue_data);
+        let (command_encoder, data) = Context::device_create_command_encoder_for_queue(
+            self,
+            &device,
+            device_data,
+            &queue,
+            queue_data,
+            desc,
+        );
+        (command_encoder.into(), Box::new(data) as _)
+    }
+
+    fn device_additional_queue(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        index: usize,
+    ) -> Option<(ObjectId, Box<crate::Data>)> {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        Context::device_additional_queue(self, &device, device_data, index)
+            .map(|(queue, data)| (queue.into(), Box::new(data) as _))
+    }
+
     fn device_create_render_bundle_encoder(
         &self,
         device: &ObjectId,
@@ -2446,6 +2748,17 @@ where
         Context::device_on_uncaptured_error(self, &device, device_data, handler)
     }
 
+    fn device_set_device_lost_callback(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        device_lost_callback: DeviceLostCallback,
+    ) {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        Context::device_set_device_lost_callback(self, &device, device_data, device_lost_callback)
+    }
+
     fn device_push_error_scope(
         &self,
         device: &ObjectId,
@@ -2542,6 +2855,22 @@ where
         Context::buffer_destroy(self, &buffer, buffer_data)
     }
 
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    fn buffer_resize(
+        &self,
+        buffer: &ObjectId,
+        buffer_data: &crate::Data,
+        size: wgt::BufferAddress,
+    ) -> bool {
+        let buffer = <T::BufferId>::from(*buffer);
+        let buffer_data = downcast_ref(buffer_data);
+        Context::buffer_resize(self, &buffer, buffer_data, size)
+    }
+
     fn buffer_drop(&self, buffer: &ObjectId, buffer_data: &crate::Data) {
         let buffer = <T::BufferId>::from(*buffer);
         let buffer_data = downcast_ref(buffer_data);
@@ -2636,6 +2965,12 @@ where
         Context::render_pipeline_drop(self, &pipeline, pipeline_data)
     }
 
+    fn pipeline_cache_drop(&self, cache: &ObjectId, cache_data: &crate::Data) {
+        let cache = <T::PipelineCacheId>::from(*cache);
+        let cache_data = downcast_ref(cache_data);
+        Context::pipeline_cache_drop(self, &cache, cache_data)
+    }
+
     fn compute_pipeline_get_bind_group_layout(
         &self,
         pipeline: &ObjectId,
@@ -2662,6 +2997,16 @@ where
         (bind_group_layout.into(), Box::new(data) as _)
     }
 
+    fn pipeline_cache_get_data(
+        &self,
+        cache: &ObjectId,
+        cache_data: &crate::Data,
+    ) -> Option<Vec<u8>> {
+        let cache = <T::PipelineCacheId>::from(*cache);
+        let cache_data = downcast_ref(cache_data);
+        Context::pipeline_cache_get_data(self, &cache, cache_data)
+    }
+
     fn command_encoder_copy_buffer_to_buffer(
         &self,
         encoder: &ObjectId,
@@ -2818,9 +3163,14 @@ where
         &self,
         encoder: ObjectId,
         encoder_data: &mut crate::Data,
+        reusable: bool,
     ) -> (ObjectId, Box<crate::Data>) {
-        let (command_buffer, data) =
-            Context::command_encoder_finish(self, encoder.into(), downcast_mut(encoder_data));
+        let (command_buffer, data) = Context::command_encoder_finish(
+            self,
+            encoder.into(),
+            downcast_mut(encoder_data),
+            reusable,
+        );
         (command_buffer.into(), Box::new(data) as _)
     }
 
@@ -3038,7 +3388,7 @@ where
         Context::queue_write_texture(self, &queue, queue_data, texture, data, data_layout, size)
     }
 
-    #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+    #[cfg(not(target_os = "emscripten"))]
     fn queue_copy_external_image_to_texture(
         &self,
         queue: &ObjectId,
@@ -3057,6 +3407,7 @@ where
         queue: &ObjectId,
         queue_data: &crate::Data,
         command_buffers: Box<dyn Iterator<Item = (ObjectId, Box<crate::Data>)> + 'a>,
+        label: Option<&str>,
     ) -> (ObjectId, Arc<crate::Data>) {
         let queue = <T::QueueId>::from(*queue);
         let queue_data = downcast_ref(queue_data);
@@ -3065,7 +3416,22 @@ where
             (<T::CommandBufferId>::from(id), command_buffer_data)
         });
         let (submission_index, data) =
-            Context::queue_submit(self, &queue, queue_data, command_buffers);
+            Context::queue_submit(self, &queue, queue_data, command_buffers, label);
+        (submission_index.into(), Arc::new(data) as _)
+    }
+
+    fn queue_submit_reusable<'a>(
+        &self,
+        queue: &ObjectId,
+        queue_data: &crate::Data,
+        command_buffers: Box<dyn Iterator<Item = ObjectId> + 'a>,
+        label: Option<&str>,
+    ) -> (ObjectId, Arc<crate::Data>) {
+        let queue = <T::QueueId>::from(*queue);
+        let queue_data = downcast_ref(queue_data);
+        let command_buffers = command_buffers.map(<T::CommandBufferId>::from);
+        let (submission_index, data) =
+            Context::queue_submit_reusable(self, &queue, queue_data, command_buffers, label);
         (submission_index.into(), Arc::new(data) as _)
     }
 
@@ -3381,6 +3747,43 @@ where
         )
     }
 
+    fn render_bundle_encoder_insert_debug_marker(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        label: &str,
+    ) {
+        let mut encoder = <T::RenderBundleEncoderId>::from(*encoder);
+        let encoder_data = downcast_mut::<T::RenderBundleEncoderData>(encoder_data);
+        Context::render_bundle_encoder_insert_debug_marker(self, &mut encoder, encoder_data, label)
+    }
+
+    fn render_bundle_encoder_push_debug_group(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        group_label: &str,
+    ) {
+        let mut encoder = <T::RenderBundleEncoderId>::from(*encoder);
+        let encoder_data = downcast_mut::<T::RenderBundleEncoderData>(encoder_data);
+        Context::render_bundle_encoder_push_debug_group(
+            self,
+            &mut encoder,
+            encoder_data,
+            group_label,
+        )
+    }
+
+    fn render_bundle_encoder_pop_debug_group(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+    ) {
+        let mut encoder = <T::RenderBundleEncoderId>::from(*encoder);
+        let encoder_data = downcast_mut::<T::RenderBundleEncoderData>(encoder_data);
+        Context::render_bundle_encoder_pop_debug_group(self, &mut encoder, encoder_data)
+    }
+
     fn render_bundle_encoder_draw(
         &self,
         encoder: &mut ObjectId,
@@ -3914,6 +4317,51 @@ where
         Context::render_pass_set_stencil_reference(self, &mut pass, pass_data, reference)
     }
 
+    fn render_pass_set_shading_rate(
+        &self,
+        pass: &mut ObjectId,
+        pass_data: &mut crate::Data,
+        rate: wgt::ShadingRate,
+    ) {
+        let mut pass = <T::RenderPassId>::from(*pass);
+        let pass_data = downcast_mut::<T::RenderPassData>(pass_data);
+        Context::render_pass_set_shading_rate(self, &mut pass, pass_data, rate)
+    }
+
+    fn render_pass_set_depth_bounds(
+        &self,
+        pass: &mut ObjectId,
+        pass_data: &mut crate::Data,
+        min: f32,
+        max: f32,
+    ) {
+        let mut pass = <T::RenderPassId>::from(*pass);
+        let pass_data = downcast_mut::<T::RenderPassData>(pass_data);
+        Context::render_pass_set_depth_bounds(self, &mut pass, pass_data, min, max)
+    }
+
+    fn render_pass_set_viewports(
+        &self,
+        pass: &mut ObjectId,
+        pass_data: &mut crate::Data,
+        viewports: &[wgt::Viewport],
+    ) {
+        let mut pass = <T::RenderPassId>::from(*pass);
+        let pass_data = downcast_mut::<T::RenderPassData>(pass_data);
+        Context::render_pass_set_viewports(self, &mut pass, pass_data, viewports)
+    }
+
+    fn render_pass_set_scissor_rects(
+        &self,
+        pass: &mut ObjectId,
+        pass_data: &mut crate::Data,
+        rects: &[wgt::ScissorRect],
+    ) {
+        let mut pass = <T::RenderPassId>::from(*pass);
+        let pass_data = downcast_mut::<T::RenderPassData>(pass_data);
+        Context::render_pass_set_scissor_rects(self, &mut pass, pass_data, rects)
+    }
+
     fn render_pass_insert_debug_marker(
         &self,
         pass: &mut ObjectId,
@@ -15,6 +15,7 @@ mod macros;
 use std::{
     any::Any,
     borrow::Cow,
+    collections::HashMap,
     error,
     fmt::{Debug, Display},
     future::Future,
@@ -32,18 +33,20 @@ pub use wgt::{
     AdapterInfo, AddressMode, AstcBlock, AstcChannel, Backend, Backends, BindGroupLayoutEntry,
     BindingType, BlendComponent, BlendFactor, BlendOperation, BlendState, BufferAddress,
     BufferBindingType, BufferSize, BufferUsages, Color, ColorTargetState, ColorWrites,
-    CommandBufferDescriptor, CompareFunction, CompositeAlphaMode, DepthBiasState,
-    DepthStencilState, DeviceType, DownlevelCapabilities, DownlevelFlags, Dx12Compiler,
-    DynamicOffset, Extent3d, Face, Features, FilterMode, FrontFace, Gles3MinorVersion,
-    ImageDataLayout, ImageSubresourceRange, IndexFormat, InstanceDescriptor, Limits,
-    MultisampleState, Origin2d, Origin3d, PipelineStatisticsTypes, PolygonMode, PowerPreference,
-    PredefinedColorSpace, PresentMode, PresentationTimestamp, PrimitiveState, PrimitiveTopology,
-    PushConstantRange, QueryType, RenderBundleDepthStencil, SamplerBindingType, SamplerBorderColor,
-    ShaderLocation, ShaderModel, ShaderStages, StencilFaceState, StencilOperation, StencilState,
-    StorageTextureAccess, SurfaceCapabilities, SurfaceStatus, TextureAspect, TextureDimension,
-    TextureFormat, TextureFormatFeatureFlags, TextureFormatFeatures, TextureSampleType,
-    TextureUsages, TextureViewDimension, VertexAttribute, VertexFormat, VertexStepMode,
-    WasmNotSend, WasmNotSync, COPY_BUFFER_ALIGNMENT, COPY_BYTES_PER_ROW_ALIGNMENT, MAP_ALIGNMENT,
+    CommandBufferDescriptor, CompareFunction, CompositeAlphaMode, DepthBiasState, DepthBoundsState,
+    DepthStencilState, DeviceLostReason, DeviceType, DisplayInfo, DownlevelCapabilities,
+    DownlevelFlags, Dx12Compiler, DynamicOffset, Extent3d, Face, Features, FilterMode, FrontFace,
+    Gles3MinorVersion, ImageDataLayout, ImageSubresourceRange, IndexFormat, InstanceDescriptor,
+    InstanceFlags, Limits, MultisampleState, Origin2d, Origin3d, PipelineStatisticsTypes,
+    PolygonMode, PowerPreference, PredefinedColorSpace, PresentMode, PresentationTimestamp,
+    PrimitiveState, PrimitiveTopology, PushConstantRange, QueryType, QueueKind,
+    RenderBundleDepthStencil, SamplerBindingType, SamplerBorderColor, ScissorRect, ShaderLocation,
+    ShaderModel, ShaderStages, ShadingRate, StencilFaceState, StencilOperation, StencilState,
+    StorageTextureAccess, SurfaceCapabilities, SurfaceColorSpace, SurfaceStatus, SwizzleComponent,
+    TextureAspect, TextureDimension, TextureFormat, TextureFormatFeatureFlags,
+    TextureFormatFeatures, TextureSampleType, TextureSwizzle, TextureUsages, TextureViewDimension,
+    VertexAttribute, VertexFormat, VertexStepMode, Viewport, WasmNotSend, WasmNotSync,
+    COPY_BUFFER_ALIGNMENT, COPY_BYTES_PER_ROW_ALIGNMENT, MAP_ALIGNMENT, MAX_VIEWPORTS,
     PUSH_CONSTANT_ALIGNMENT, QUERY_RESOLVE_BUFFER_ALIGNMENT, QUERY_SET_MAX_QUERIES, QUERY_SIZE,
     VERTEX_STRIDE_ALIGNMENT,
 };
@@ -63,9 +66,10 @@ pub use ::hal;
 #[doc(hidden)]
 pub use ::wgc as core;
 
-// wasm-only types, we try to keep as many types non-platform
-// specific, but these need to depend on web-sys.
-#[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+// The web image sources of these types depend on web-sys.
+#[cfg(not(target_arch = "wasm32"))]
+pub use wgt::CpuImage;
+#[cfg(not(target_os = "emscripten"))]
 pub use wgt::{ExternalImageSource, ImageCopyExternalImage};
 
 /// Filter for error scopes.
@@ -75,6 +79,8 @@ pub enum ErrorFilter {
     OutOfMemory,
     /// Catch only validation errors.
     Validation,
+    /// Catch only internal errors.
+    Internal,
 }
 static_assertions::assert_impl_all!(ErrorFilter: Send, Sync);
 
@@ -193,7 +199,8 @@ static_assertions::assert_impl_all!(SubmissionIndex: Send, Sync);
 #[derive(Debug)]
 struct MapContext {
     total_size: BufferAddress,
-    initial_range: Range<BufferAddress>,
+    /// Disjoint ranges requested through `map_async` (or `mapped_at_creation`).
+    mapped_ranges: Vec<Range<BufferAddress>>,
     sub_ranges: Vec<Range<BufferAddress>>,
 }
 
@@ -201,13 +208,13 @@ impl MapContext {
     fn new(total_size: BufferAddress) -> Self {
         Self {
             total_size,
-            initial_range: 0..0,
+            mapped_ranges: Vec::new(),
             sub_ranges: Vec::new(),
         }
     }
 
     fn reset(&mut self) {
-        self.initial_range = 0..0;
+        self.mapped_ranges.clear();
 
         assert!(
             self.sub_ranges.is_empty(),
@@ -215,12 +222,40 @@ impl MapContext {
         );
     }
 
-    fn add(&mut self, offset: BufferAddress, size: Option<BufferSize>) -> BufferAddress {
+    /// Records a newly requested mapping, returning `false` if it overlaps
+    /// a range that was already requested.
+    fn map(&mut self, range: Range<BufferAddress>) -> bool {
+        if self
+            .mapped_ranges
+            .iter()
+            .any(|r| range.start == r.start || (range.start < r.end && r.start < range.end))
+        {
+            return false;
+        }
+        self.mapped_ranges.push(range);
+        true
+    }
+
+    /// Returns the end of the sub-range, which must lie within a single mapped range.
+    fn resolve_end(&self, offset: BufferAddress, size: Option<BufferSize>) -> BufferAddress {
+        let mapped = self
+            .mapped_ranges
+            .iter()
+            .find(|r| r.start <= offset && offset < r.end.max(r.start + 1))
+            .unwrap_or_else(|| panic!("Offset {offset} is not in a mapped range"));
         let end = match size {
             Some(s) => offset + s.get(),
-            None => self.initial_range.end,
+            None => mapped.end,
         };
-        assert!(self.initial_range.start <= offset && end <= self.initial_range.end);
+        assert!(
+            end <= mapped.end,
+            "Range {offset}..{end} exceeds mapped range {mapped:?}"
+        );
+        end
+    }
+
+    fn add(&mut self, offset: BufferAddress, size: Option<BufferSize>) -> BufferAddress {
+        let end = self.resolve_end(offset, size);
         for sub in self.sub_ranges.iter() {
             assert!(
                 end <= sub.start || offset >= sub.end,
@@ -232,10 +267,7 @@ impl MapContext {
     }
 
     fn remove(&mut self, offset: BufferAddress, size: Option<BufferSize>) {
-        let end = match size {
-            Some(s) => offset + s.get(),
-            None => self.initial_range.end,
-        };
+        let end = self.resolve_end(offset, size);
 
         let index = self
             .sub_ranges
@@ -369,6 +401,53 @@ impl Drop for Sampler {
     }
 }
 
+/// Handle to a ray tracing acceleration structure.
+///
+/// Acceleration structures are only created and built with `wgpu-hal` for now, see
+/// [`Device::create_acceleration_structure_from_hal`]. They can be bound with
+/// [`BindingResource::AccelerationStructure`].
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    target_os = "emscripten",
+    feature = "webgl"
+))]
+#[derive(Debug)]
+pub struct AccelerationStructure {
+    context: Arc<C>,
+    id: ObjectId,
+}
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    target_os = "emscripten",
+    feature = "webgl"
+))]
+impl Drop for AccelerationStructure {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            self.context
+                .as_any()
+                .downcast_ref::<crate::backend::Context>()
+                .unwrap()
+                .acceleration_structure_drop(&self.id.into());
+        }
+    }
+}
+
+/// Describes an [`AccelerationStructure`].
+///
+/// For use with [`Device::create_acceleration_structure_from_hal`].
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    target_os = "emscripten",
+    feature = "webgl"
+))]
+#[derive(Clone, Debug, Default)]
+pub struct AccelerationStructureDescriptor<'a> {
+    /// Debug label of the acceleration structure. This will show up in graphics debuggers for
+    /// easy identification.
+    pub label: Label<'a>,
+}
+
 /// Describes a [`Surface`].
 ///
 /// For use with [`Surface::configure`].
@@ -514,6 +593,18 @@ impl Drop for ShaderModule {
     }
 }
 
+impl ShaderModule {
+    /// Returns the errors and warnings produced while compiling this shader module.
+    ///
+    /// Errors are still reported to the device's error handler when the module is created;
+    /// this gives access to them as structured messages with source locations, for display in
+    /// a shader editor for example.
+    pub fn get_compilation_info(&self) -> impl Future<Output = CompilationInfo> + WasmNotSend {
+        self.context
+            .shader_get_compilation_info(&self.id, self.data.as_ref())
+    }
+}
+
 /// Source of a shader module.
 ///
 /// The source will be parsed and validated.
@@ -534,6 +625,8 @@ pub enum ShaderSource<'a> {
     SpirV(Cow<'a, [u32]>),
     /// GLSL module as a string slice.
     ///
+    /// Parse and validation errors are reported like those of WGSL modules.
+    ///
     /// Note: GLSL is not yet fully supported and must be a specific ShaderStage.
     #[cfg(feature = "glsl")]
     Glsl {
@@ -583,6 +676,59 @@ pub struct ShaderModuleDescriptorSpirV<'a> {
 }
 static_assertions::assert_impl_all!(ShaderModuleDescriptorSpirV: Send, Sync);
 
+/// Compilation information for a shader module.
+///
+/// Returned by [`ShaderModule::get_compilation_info`].
+///
+/// Corresponds to [WebGPU `GPUCompilationInfo`](https://gpuweb.github.io/gpuweb/#gpucompilationinfo).
+#[derive(Clone, Debug, Default)]
+pub struct CompilationInfo {
+    /// The messages produced while compiling the shader module.
+    pub messages: Vec<CompilationMessage>,
+}
+
+/// A single message produced while compiling a shader module.
+///
+/// Corresponds to [WebGPU `GPUCompilationMessage`](https://gpuweb.github.io/gpuweb/#gpucompilationmessage).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompilationMessage {
+    /// The human-readable text of the message.
+    pub message: String,
+    /// The severity of the message.
+    pub message_type: CompilationMessageType,
+    /// The part of the shader source the message refers to, if known.
+    pub location: Option<SourceLocation>,
+}
+
+/// Severity of a [`CompilationMessage`].
+///
+/// Corresponds to [WebGPU `GPUCompilationMessageType`](https://gpuweb.github.io/gpuweb/#enumdef-gpucompilationmessagetype).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompilationMessageType {
+    /// An error that makes the shader module invalid.
+    Error,
+    /// A warning about valid but suspicious code.
+    Warning,
+    /// Purely informational.
+    Info,
+}
+
+/// Location of a [`CompilationMessage`] in the shader source.
+///
+/// On native, `line_position`, `offset` and `length` are counted in bytes
+/// (UTF-8 code units). On the web, they are counted in UTF-16 code units.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+    /// 1-based line number.
+    pub line_number: u32,
+    /// 1-based position of the start of the span within its line.
+    pub line_position: u32,
+    /// 0-based offset of the start of the span in the source.
+    pub offset: u32,
+    /// Length of the span.
+    pub length: u32,
+}
+
 /// Handle to a pipeline layout.
 ///
 /// A `PipelineLayout` object describes the available binding groups of a pipeline.
@@ -697,6 +843,49 @@ impl ComputePipeline {
     }
 }
 
+/// Handle to a pipeline cache.
+///
+/// A `PipelineCache` holds the compiled forms of the pipelines created with it, so they can be
+/// saved with [`PipelineCache::get_data`] and passed to [`Device::create_pipeline_cache`] on a
+/// later run to skip most of the shader compilation.
+///
+/// It is backed by a `VkPipelineCache` on Vulkan and by an `MTLBinaryArchive` on Metal, the
+/// backends exposing [`Features::PIPELINE_CACHE`].
+#[derive(Debug)]
+pub struct PipelineCache {
+    context: Arc<C>,
+    id: ObjectId,
+    data: Box<Data>,
+}
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(PipelineCache: Send, Sync);
+
+impl Drop for PipelineCache {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            self.context
+                .pipeline_cache_drop(&self.id, self.data.as_ref());
+        }
+    }
+}
+
+impl PipelineCache {
+    /// Returns the contents of the cache, to be passed to [`Device::create_pipeline_cache`] on a
+    /// later run.
+    ///
+    /// Returns `None` if the backend can't serialize pipeline caches.
+    pub fn get_data(&self) -> Option<Vec<u8>> {
+        self.context
+            .pipeline_cache_get_data(&self.id, self.data.as_ref())
+    }
+}
+
 /// Handle to a command buffer on the GPU.
 ///
 /// A `CommandBuffer` represents a complete sequence of commands that may be submitted to a command
@@ -730,6 +919,41 @@ impl Drop for CommandBuffer {
     }
 }
 
+/// Handle to a command buffer on the GPU that can be submitted more than once.
+///
+/// A `ReusableCommandBuffer` is obtained by recording a series of commands to a
+/// [`CommandEncoder`] and then calling [`CommandEncoder::finish_reusable`]. It is submitted with
+/// [`Queue::submit_reusable`], which doesn't consume it, so that commands that don't change
+/// between frames only need to be recorded once.
+///
+/// It can't be submitted again until its previous submission is complete, which can be
+/// waited on with [`Device::poll`].
+///
+/// This type is unique to the Rust API of `wgpu`.
+#[derive(Debug)]
+pub struct ReusableCommandBuffer {
+    context: Arc<C>,
+    id: ObjectId,
+    data: Box<Data>,
+}
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(ReusableCommandBuffer: Send, Sync);
+
+impl Drop for ReusableCommandBuffer {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            self.context
+                .command_buffer_drop(&self.id, self.data.as_ref());
+        }
+    }
+}
+
 /// Encodes a series of GPU operations.
 ///
 /// A command encoder can record [`RenderPass`]es, [`ComputePass`]es,
@@ -738,6 +962,12 @@ impl Drop for CommandBuffer {
 /// When finished recording, call [`CommandEncoder::finish`] to obtain a [`CommandBuffer`] which may
 /// be submitted for execution.
 ///
+/// On native, separate encoders created from the same [`Device`] may be recorded concurrently from
+/// different threads, and the resulting command buffers submitted together with a single
+/// [`Queue::submit`]. Commands recorded in a [`RenderPass`] or [`ComputePass`] are buffered in the
+/// pass itself and only validated once the pass is dropped, so recording them doesn't take any
+/// device-wide locks.
+///
 /// Corresponds to [WebGPU `GPUCommandEncoder`](https://gpuweb.github.io/gpuweb/#command-encoder).
 #[derive(Debug)]
 pub struct CommandEncoder {
@@ -957,6 +1187,16 @@ pub enum BindingResource<'a> {
     /// Corresponds to [`wgt::BindingType::Texture`] and [`wgt::BindingType::StorageTexture`] with
     /// [`BindGroupLayoutEntry::count`] set to Some.
     TextureViewArray(&'a [&'a TextureView]),
+    /// Binding is a ray tracing acceleration structure.
+    ///
+    /// Corresponds to [`wgt::BindingType::AccelerationStructure`] with
+    /// [`BindGroupLayoutEntry::count`] set to None.
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    AccelerationStructure(&'a AccelerationStructure),
 }
 #[cfg(any(
     not(target_arch = "wasm32"),
@@ -1084,6 +1324,10 @@ static_assertions::assert_impl_all!(RenderPassTimestampWrites: Send, Sync);
 pub struct RenderPassColorAttachment<'tex> {
     /// The view to use as an attachment.
     pub view: &'tex TextureView,
+    /// The depth slice of a 3D texture `view` to render to.
+    ///
+    /// Must be `Some` if `view` is a 3D texture view, and `None` otherwise.
+    pub depth_slice: Option<u32>,
     /// The view that will receive the resolved output if multisampling is used.
     pub resolve_target: Option<&'tex TextureView>,
     /// What operations will be performed on this color attachment.
@@ -1109,8 +1353,15 @@ pub struct RenderPassDepthStencilAttachment<'tex> {
     /// The view to use as an attachment.
     pub view: &'tex TextureView,
     /// What operations will be performed on the depth part of the attachment.
+    ///
+    /// If `None`, the depth aspect is read-only: it is loaded, never written
+    /// to, and the texture may also be bound for sampling within the pass.
+    /// Binding it requires the stencil aspect, if any, to be read-only too,
+    /// and [`DownlevelFlags::READ_ONLY_DEPTH_STENCIL`].
     pub depth_ops: Option<Operations<f32>>,
     /// What operations will be performed on the stencil part of the attachment.
+    ///
+    /// If `None`, the stencil aspect is read-only, as with `depth_ops`.
     pub stencil_ops: Option<Operations<u32>>,
 }
 #[cfg(any(
@@ -1232,6 +1483,13 @@ pub struct TextureViewDescriptor<'a> {
     /// If `Some(count)`, `base_array_layer + count` must be less or equal to the underlying array count.
     /// If `None`, considered to include the rest of the array layers, but at least 1 in total.
     pub array_layer_count: Option<u32>,
+    /// Remapping of the components read through the view. If `None`, the components are
+    /// read unchanged.
+    ///
+    /// Anything other than [`TextureSwizzle::IDENTITY`] requires
+    /// [`Features::TEXTURE_COMPONENT_SWIZZLE`], and the view can then only be bound
+    /// as a sampled texture.
+    pub swizzle: Option<TextureSwizzle>,
 }
 static_assertions::assert_impl_all!(TextureViewDescriptor: Send, Sync);
 
@@ -1427,6 +1685,14 @@ pub struct VertexState<'a> {
     /// The name of the entry point in the compiled shader. There must be a function with this name
     /// in the shader.
     pub entry_point: &'a str,
+    /// Values for the pipeline-overridable constants of the shader, keyed by
+    /// the constant's name or numeric id.
+    ///
+    /// For now these are the specialization constants of SPIR-V shaders, whose ids are their
+    /// `SpecId`s. WGSL `override` declarations aren't supported by the shader translator yet.
+    ///
+    /// The values are converted to the types of the constants they override.
+    pub constants: &'a HashMap<String, f64>,
     /// The format of any vertex buffers used with this pipeline.
     pub buffers: &'a [VertexBufferLayout<'a>],
 }
@@ -1452,6 +1718,14 @@ pub struct FragmentState<'a> {
     /// The name of the entry point in the compiled shader. There must be a function with this name
     /// in the shader.
     pub entry_point: &'a str,
+    /// Values for the pipeline-overridable constants of the shader, keyed by
+    /// the constant's name or numeric id.
+    ///
+    /// For now these are the specialization constants of SPIR-V shaders, whose ids are their
+    /// `SpecId`s. WGSL `override` declarations aren't supported by the shader translator yet.
+    ///
+    /// The values are converted to the types of the constants they override.
+    pub constants: &'a HashMap<String, f64>,
     /// The color state of the render targets.
     pub targets: &'a [Option<ColorTargetState>],
 }
@@ -1489,6 +1763,8 @@ pub struct RenderPipelineDescriptor<'a> {
     /// If the pipeline will be used with a multiview render pass, this indicates how many array
     /// layers the attachments will have.
     pub multiview: Option<NonZeroU32>,
+    /// The cache to look the pipeline up in and record it into, if any.
+    pub cache: Option<&'a PipelineCache>,
 }
 #[cfg(any(
     not(target_arch = "wasm32"),
@@ -1565,6 +1841,16 @@ pub struct ComputePipelineDescriptor<'a> {
     /// The name of the entry point in the compiled shader. There must be a function with this name
     /// and no return value in the shader.
     pub entry_point: &'a str,
+    /// Values for the pipeline-overridable constants of the shader, keyed by
+    /// the constant's name or numeric id.
+    ///
+    /// For now these are the specialization constants of SPIR-V shaders, whose ids are their
+    /// `SpecId`s. WGSL `override` declarations aren't supported by the shader translator yet.
+    ///
+    /// The values are converted to the types of the constants they override.
+    pub constants: &'a HashMap<String, f64>,
+    /// The cache to look the pipeline up in and record it into, if any.
+    pub cache: Option<&'a PipelineCache>,
 }
 #[cfg(any(
     not(target_arch = "wasm32"),
@@ -1575,6 +1861,28 @@ pub struct ComputePipelineDescriptor<'a> {
 ))]
 static_assertions::assert_impl_all!(ComputePipelineDescriptor: Send, Sync);
 
+/// Describes a pipeline cache.
+///
+/// For use with [`Device::create_pipeline_cache`].
+#[derive(Clone, Debug, Default)]
+pub struct PipelineCacheDescriptor<'a> {
+    /// Debug label of the pipeline cache. This will show up in graphics debuggers for easy
+    /// identification.
+    pub label: Label<'a>,
+    /// Data returned by [`PipelineCache::get_data`] on a previous run, to seed the cache with.
+    ///
+    /// Data produced by a different adapter or driver version is ignored, leaving the cache empty.
+    pub data: Option<&'a [u8]>,
+}
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(PipelineCacheDescriptor: Send, Sync);
+
 pub use wgt::ImageCopyBuffer as ImageCopyBufferBase;
 /// View of a buffer which can be used to copy to/from a texture.
 ///
@@ -1829,11 +2137,57 @@ impl Instance {
             })
     }
 
+    /// Retrieves the [`Adapter`] that `score` rates highest, among those matching the given [`Backends`].
+    ///
+    /// This is an alternative to [`Instance::request_adapter`] for applications with their own
+    /// selection policy. `score` is called once per adapter, and can inspect it through
+    /// [`Adapter::get_info`], [`Adapter::features`], [`Adapter::limits`] and
+    /// [`Adapter::is_surface_supported`]. Returning `None` rejects the adapter. Ties go to the
+    /// adapter enumerated first.
+    ///
+    /// If every adapter is rejected, `None` is returned.
+    ///
+    /// # Arguments
+    ///
+    /// - `backends` - Backends from which to enumerate adapters.
+    /// - `score` - Rates an adapter, higher is better.
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub fn select_adapter(
+        &self,
+        backends: Backends,
+        mut score: impl FnMut(&Adapter) -> Option<u64>,
+    ) -> Option<Adapter> {
+        let mut best: Option<(u64, Adapter)> = None;
+        for adapter in self.enumerate_adapters(backends) {
+            match (score(&adapter), &best) {
+                (Some(s), Some((best_score, _))) if s <= *best_score => {}
+                (Some(s), _) => best = Some((s, adapter)),
+                (None, _) => {}
+            }
+        }
+        best.map(|(_, adapter)| adapter)
+    }
+
     /// Retrieves an [`Adapter`] which matches the given [`RequestAdapterOptions`].
     ///
     /// Some options are "soft", so treated as non-mandatory. Others are "hard".
     ///
     /// If no adapters are found that suffice all the "hard" options, `None` is returned.
+    ///
+    /// On native, adapters are first filtered by the "hard" options: `compatible_surface`,
+    /// and `force_fallback_adapter`, which keeps only CPU adapters. Among the rest, the
+    /// `power_preference` picks a device type, in this order of preference:
+    ///
+    /// - [`PowerPreference::LowPower`]: integrated, discrete, other, virtual, CPU.
+    /// - [`PowerPreference::HighPerformance`]: discrete, integrated, other, virtual, CPU.
+    /// - [`PowerPreference::None`]: the first discrete, integrated or other adapter.
+    ///
+    /// Within a type, Vulkan adapters come first, followed by Metal, DX12, DX11 and GL.
+    /// Use [`Instance::select_adapter`] to apply a different policy.
     pub fn request_adapter(
         &self,
         options: &RequestAdapterOptions,
@@ -1892,6 +2246,9 @@ impl Instance {
     ///
     /// - On WebGL2: Will return an error if the browser does not support WebGL2,
     ///   or declines to provide GPU access (such as due to a resource shortage).
+    /// - On web: Will return an error if there is no document to look the canvas up in,
+    ///   such as on a web worker. Use `Instance::create_surface_from_offscreen_canvas`
+    ///   there instead.
     ///
     /// # Panics
     ///
@@ -1904,11 +2261,24 @@ impl Instance {
         &self,
         window: &W,
     ) -> Result<Surface, CreateSurfaceError> {
-        let (id, data) = DynContext::instance_create_surface(
-            &*self.context,
-            raw_window_handle::HasRawDisplayHandle::raw_display_handle(window),
-            raw_window_handle::HasRawWindowHandle::raw_window_handle(window),
-        )?;
+        unsafe {
+            self.create_surface_from_raw(
+                raw_window_handle::HasRawDisplayHandle::raw_display_handle(window),
+                raw_window_handle::HasRawWindowHandle::raw_window_handle(window),
+            )
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The handles must be valid, see [`Instance::create_surface`].
+    unsafe fn create_surface_from_raw(
+        &self,
+        display_handle: raw_window_handle::RawDisplayHandle,
+        window_handle: raw_window_handle::RawWindowHandle,
+    ) -> Result<Surface, CreateSurfaceError> {
+        let (id, data) =
+            DynContext::instance_create_surface(&*self.context, display_handle, window_handle)?;
         Ok(Surface {
             context: Arc::clone(&self.context),
             id,
@@ -1917,6 +2287,106 @@ impl Instance {
         })
     }
 
+    /// Creates a surface from a Win32 `HWND`, for windowing systems that don't
+    /// implement [`raw_window_handle::HasRawWindowHandle`].
+    ///
+    /// # Safety
+    ///
+    /// - `hwnd` must be a valid window handle, and `hinstance` the handle of the module
+    ///   that created it, or null.
+    /// - The window must remain valid until after the returned [`Surface`] is dropped.
+    #[cfg(target_os = "windows")]
+    pub unsafe fn create_surface_from_hwnd(
+        &self,
+        hinstance: *mut std::ffi::c_void,
+        hwnd: *mut std::ffi::c_void,
+    ) -> Result<Surface, CreateSurfaceError> {
+        let mut window_handle = raw_window_handle::Win32WindowHandle::empty();
+        window_handle.hinstance = hinstance;
+        window_handle.hwnd = hwnd;
+        unsafe {
+            self.create_surface_from_raw(
+                raw_window_handle::WindowsDisplayHandle::empty().into(),
+                window_handle.into(),
+            )
+        }
+    }
+
+    /// Creates a surface from a Wayland `wl_display` and `wl_surface`, for windowing
+    /// systems that don't implement [`raw_window_handle::HasRawWindowHandle`].
+    ///
+    /// # Safety
+    ///
+    /// - `display` must be a valid `wl_display` pointer, and `surface` a valid
+    ///   `wl_surface` pointer created on it.
+    /// - Both must remain valid until after the returned [`Surface`] is dropped.
+    #[cfg(all(
+        unix,
+        not(target_os = "emscripten"),
+        not(target_os = "ios"),
+        not(target_os = "macos"),
+        not(target_os = "android")
+    ))]
+    pub unsafe fn create_surface_from_wayland(
+        &self,
+        display: *mut std::ffi::c_void,
+        surface: *mut std::ffi::c_void,
+    ) -> Result<Surface, CreateSurfaceError> {
+        let mut display_handle = raw_window_handle::WaylandDisplayHandle::empty();
+        display_handle.display = display;
+        let mut window_handle = raw_window_handle::WaylandWindowHandle::empty();
+        window_handle.surface = surface;
+        unsafe { self.create_surface_from_raw(display_handle.into(), window_handle.into()) }
+    }
+
+    /// Creates a surface from an Xlib `Display` and `Window`, for windowing systems
+    /// that don't implement [`raw_window_handle::HasRawWindowHandle`].
+    ///
+    /// # Safety
+    ///
+    /// - `display` must be a valid `Display` pointer, and `window` a window created on it.
+    /// - Both must remain valid until after the returned [`Surface`] is dropped.
+    #[cfg(all(
+        unix,
+        not(target_os = "emscripten"),
+        not(target_os = "ios"),
+        not(target_os = "macos"),
+        not(target_os = "android")
+    ))]
+    pub unsafe fn create_surface_from_xlib(
+        &self,
+        display: *mut std::ffi::c_void,
+        window: std::os::raw::c_ulong,
+    ) -> Result<Surface, CreateSurfaceError> {
+        let mut display_handle = raw_window_handle::XlibDisplayHandle::empty();
+        display_handle.display = display;
+        let mut window_handle = raw_window_handle::XlibWindowHandle::empty();
+        window_handle.window = window;
+        unsafe { self.create_surface_from_raw(display_handle.into(), window_handle.into()) }
+    }
+
+    /// Creates a surface from an Android `ANativeWindow`, for windowing systems that
+    /// don't implement [`raw_window_handle::HasRawWindowHandle`].
+    ///
+    /// # Safety
+    ///
+    /// - `window` must be a valid `ANativeWindow` pointer.
+    /// - It must remain valid until after the returned [`Surface`] is dropped.
+    #[cfg(target_os = "android")]
+    pub unsafe fn create_surface_from_android_native_window(
+        &self,
+        window: *mut std::ffi::c_void,
+    ) -> Result<Surface, CreateSurfaceError> {
+        let mut window_handle = raw_window_handle::AndroidNdkWindowHandle::empty();
+        window_handle.a_native_window = window;
+        unsafe {
+            self.create_surface_from_raw(
+                raw_window_handle::AndroidDisplayHandle::empty().into(),
+                window_handle.into(),
+            )
+        }
+    }
+
     /// Creates a surface from `CoreAnimationLayer`.
     ///
     /// # Safety
@@ -1942,6 +2412,59 @@ impl Instance {
         }
     }
 
+    /// Lists the displays that [`Instance::create_surface_from_display`] can present to.
+    ///
+    /// This uses `VK_KHR_display` on the Vulkan backend, and returns an empty list if the
+    /// driver doesn't support it.
+    #[cfg(all(
+        unix,
+        not(target_os = "emscripten"),
+        not(target_os = "ios"),
+        not(target_os = "macos")
+    ))]
+    pub fn enumerate_displays(&self) -> Vec<DisplayInfo> {
+        self.context
+            .as_any()
+            .downcast_ref::<crate::backend::Context>()
+            .unwrap()
+            .enumerate_displays()
+    }
+
+    /// Creates a surface that presents directly to a display, without a window system.
+    ///
+    /// `display` is an index into the list returned by [`Instance::enumerate_displays`]. The
+    /// display is driven at its native resolution, through `VK_KHR_display` on the Vulkan
+    /// backend. It is intended for kiosk and embedded systems where no X11 or Wayland server
+    /// is running.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Vulkan driver does not support `VK_KHR_display`, if `display`
+    /// is out of range, or if the display has no usable plane.
+    #[cfg(all(
+        unix,
+        not(target_os = "emscripten"),
+        not(target_os = "ios"),
+        not(target_os = "macos")
+    ))]
+    pub fn create_surface_from_display(
+        &self,
+        display: usize,
+    ) -> Result<Surface, CreateSurfaceError> {
+        let surface = self
+            .context
+            .as_any()
+            .downcast_ref::<crate::backend::Context>()
+            .unwrap()
+            .create_surface_from_display(display)?;
+        Ok(Surface {
+            context: Arc::clone(&self.context),
+            id: ObjectId::from(surface.id()),
+            data: Box::new(surface),
+            config: Mutex::new(None),
+        })
+    }
+
     /// Creates a surface from `IDCompositionVisual`.
     ///
     /// # Safety
@@ -2028,7 +2551,9 @@ impl Instance {
     /// Creates a surface from a `web_sys::OffscreenCanvas`.
     ///
     /// The `canvas` argument must be a valid `OffscreenCanvas` object
-    /// to create a surface upon.
+    /// to create a surface upon. Unlike the other surface constructors, this
+    /// works on web workers, for example with a canvas passed from the main
+    /// thread with `transferControlToOffscreen`.
     ///
     /// # Errors
     ///
@@ -2157,6 +2682,7 @@ impl Adapter {
     ///
     /// - `hal_device` must be created from this adapter internal handle.
     /// - `desc.features` must be a subset of `hal_device` features.
+    /// - `hal_device` must have been opened with `desc.additional_queues`.
     #[cfg(any(
         not(target_arch = "wasm32"),
         target_os = "emscripten",
@@ -2419,6 +2945,45 @@ impl Device {
         }
     }
 
+    /// Creates an empty [`CommandEncoder`] whose command buffer is submitted to `queue`.
+    ///
+    /// Command buffers of an additional queue can only be submitted to that queue. They
+    /// can't record the commands the queue doesn't support, e.g. render passes on a
+    /// [`QueueKind::Compute`] queue.
+    pub fn create_command_encoder_for_queue(
+        &self,
+        queue: &Queue,
+        desc: &CommandEncoderDescriptor,
+    ) -> CommandEncoder {
+        let (id, data) = DynContext::device_create_command_encoder_for_queue(
+            &*self.context,
+            &self.id,
+            self.data.as_ref(),
+            &queue.id,
+            queue.data.as_ref(),
+            desc,
+        );
+        CommandEncoder {
+            context: Arc::clone(&self.context),
+            id: Some(id),
+            data,
+        }
+    }
+
+    /// Returns the queue `index` of [`DeviceDescriptor::additional_queues`], or `None`
+    /// if the device has fewer additional queues.
+    ///
+    /// Work submitted to it waits for the buffers and textures it uses to be done with on
+    /// the other queues.
+    pub fn additional_queue(&self, index: usize) -> Option<Queue> {
+        DynContext::device_additional_queue(&*self.context, &self.id, self.data.as_ref(), index)
+            .map(|(id, data)| Queue {
+                context: Arc::clone(&self.context),
+                id,
+                data,
+            })
+    }
+
     /// Creates an empty [`RenderBundleEncoder`].
     pub fn create_render_bundle_encoder(
         &self,
@@ -2518,7 +3083,7 @@ impl Device {
     pub fn create_buffer(&self, desc: &BufferDescriptor) -> Buffer {
         let mut map_context = MapContext::new(desc.size);
         if desc.mapped_at_creation {
-            map_context.initial_range = 0..desc.size;
+            map_context.mapped_ranges.push(0..desc.size);
         }
 
         let (id, data) =
@@ -2613,7 +3178,7 @@ impl Device {
     ) -> Buffer {
         let mut map_context = MapContext::new(desc.size);
         if desc.mapped_at_creation {
-            map_context.initial_range = 0..desc.size;
+            map_context.mapped_ranges.push(0..desc.size);
         }
 
         let (id, buffer) = unsafe {
@@ -2638,6 +3203,41 @@ impl Device {
         }
     }
 
+    /// Creates an [`AccelerationStructure`] from a wgpu-hal acceleration structure.
+    ///
+    /// Requires [`Features::RAY_TRACING_ACCELERATION_STRUCTURE`].
+    ///
+    /// # Safety
+    ///
+    /// - `hal_acceleration_structure` must be created from this device internal handle
+    /// - `hal_acceleration_structure` must be built before the bind groups using it are used
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub unsafe fn create_acceleration_structure_from_hal<A: wgc::hal_api::HalApi>(
+        &self,
+        hal_acceleration_structure: A::AccelerationStructure,
+        desc: &AccelerationStructureDescriptor,
+    ) -> AccelerationStructure {
+        let id = unsafe {
+            self.context
+                .as_any()
+                .downcast_ref::<crate::backend::Context>()
+                .unwrap()
+                .create_acceleration_structure_from_hal::<A>(
+                    hal_acceleration_structure,
+                    self.data.as_ref().downcast_ref().unwrap(),
+                    desc,
+                )
+        };
+        AccelerationStructure {
+            context: Arc::clone(&self.context),
+            id: ObjectId::from(id),
+        }
+    }
+
     /// Creates a new [`Sampler`].
     ///
     /// `desc` specifies the behavior of the sampler.
@@ -2662,7 +3262,53 @@ impl Device {
         }
     }
 
+    /// Creates a new [`PipelineCache`].
+    ///
+    /// Requires [`Features::PIPELINE_CACHE`].
+    ///
+    /// # Safety
+    ///
+    /// `desc.data` must have been returned by [`PipelineCache::get_data`]. Backends only
+    /// check that it comes from the same adapter and driver, and pass it on to the driver,
+    /// which can crash or misbehave on corrupted data.
+    pub unsafe fn create_pipeline_cache(&self, desc: &PipelineCacheDescriptor) -> PipelineCache {
+        let (id, data) = unsafe {
+            DynContext::device_create_pipeline_cache(
+                &*self.context,
+                &self.id,
+                self.data.as_ref(),
+                desc,
+            )
+        };
+        PipelineCache {
+            context: Arc::clone(&self.context),
+            id,
+            data,
+        }
+    }
+
+    /// Set a callback called when the device is lost, replacing the previous one.
+    ///
+    /// The callback is called at most once, when the backend reports the device
+    /// as lost or removed, or when the device is dropped. Once lost, the device
+    /// reports an error for every call instead of doing work, and should be
+    /// recreated from its adapter. On native, loss is noticed while polling the
+    /// device, see [`Device::poll`].
+    pub fn set_device_lost_callback(
+        &self,
+        callback: impl FnOnce(DeviceLostReason, String) + Send + 'static,
+    ) {
+        DynContext::device_set_device_lost_callback(
+            &*self.context,
+            &self.id,
+            self.data.as_ref(),
+            Box::new(callback),
+        )
+    }
+
     /// Set a callback for errors that are not handled in error scopes.
+    ///
+    /// The default handler panics, so set one to log or recover from errors instead.
     pub fn on_uncaptured_error(&self, handler: Box<dyn UncapturedErrorHandler>) {
         self.context
             .device_on_uncaptured_error(&self.id, self.data.as_ref(), handler);
@@ -2699,33 +3345,158 @@ impl Device {
     /// If this `Device` uses a different backend, apply `hal_device_callback`
     /// to `None`.
     ///
-    /// The device is locked for reading while `hal_device_callback` runs. If
-    /// the callback attempts to perform any `wgpu` operations that require
-    /// write access to the device (destroying a buffer, say), deadlock will
-    /// occur. The locks are automatically released when the callback returns.
+    /// The device is locked for reading while `hal_device_callback` runs. If
+    /// the callback attempts to perform any `wgpu` operations that require
+    /// write access to the device (destroying a buffer, say), deadlock will
+    /// occur. The locks are automatically released when the callback returns.
+    ///
+    /// # Safety
+    ///
+    /// - The raw handle passed to the callback must not be manually destroyed.
+    ///
+    /// [`A::Device`]: hal::Api::Device
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub unsafe fn as_hal<A: wgc::hal_api::HalApi, F: FnOnce(Option<&A::Device>) -> R, R>(
+        &self,
+        hal_device_callback: F,
+    ) -> R {
+        unsafe {
+            self.context
+                .as_any()
+                .downcast_ref::<crate::backend::Context>()
+                .unwrap()
+                .device_as_hal::<A, F, R>(
+                    self.data.as_ref().downcast_ref().unwrap(),
+                    hal_device_callback,
+                )
+        }
+    }
+
+    /// Enable or disable the zero initialization of the buffers and textures
+    /// created from now on. It is enabled by default.
+    ///
+    /// WebGPU requires resources to read as zero until written, so `wgpu` tracks
+    /// which parts of each resource were written and clears the others before
+    /// they are read. Applications that always write their resources before
+    /// reading them can disable this to skip its tracking and clearing costs.
+    ///
+    /// # Safety
+    ///
+    /// - With zero initialization disabled, the contents of new resources are
+    ///   undefined and may hold data left by other applications. The caller is
+    ///   responsible for never exposing them.
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub unsafe fn set_zero_initialization(&self, enabled: bool) {
+        unsafe {
+            self.context
+                .as_any()
+                .downcast_ref::<crate::backend::Context>()
+                .unwrap()
+                .device_set_zero_initialization(self.data.as_ref().downcast_ref().unwrap(), enabled)
+        }
+    }
+
+    /// Generates a report of the resources this device keeps alive, with the
+    /// statistics of the backend's memory allocator where available.
+    ///
+    /// Comparing reports taken over time helps finding resource leaks.
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub fn generate_report(&self) -> wgc::device::DeviceReport {
+        self.context
+            .as_any()
+            .downcast_ref::<crate::backend::Context>()
+            .unwrap()
+            .device_generate_report(self.data.as_ref().downcast_ref().unwrap())
+    }
+
+    /// Reads back the contents of this device's buffers and textures, for example to implement
+    /// save states.
+    ///
+    /// Only resources created with [`BufferUsages::COPY_SRC`] or [`TextureUsages::COPY_SRC`] are
+    /// captured. The snapshot also holds the descriptors of the device's pipelines, which
+    /// [`Device::restore`] doesn't recreate. This submits the copies and waits for them to
+    /// complete. The snapshot doesn't depend on the backend, and can be serialized with the
+    /// `trace` and `replay` features.
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub fn snapshot(
+        &self,
+    ) -> Result<wgc::device::snapshot::DeviceSnapshot, wgc::device::snapshot::DeviceSnapshotError>
+    {
+        self.context
+            .as_any()
+            .downcast_ref::<crate::backend::Context>()
+            .unwrap()
+            .device_snapshot(self.data.as_ref().downcast_ref().unwrap())
+    }
+
+    /// Writes the contents captured by [`Device::snapshot`] back to their resources.
+    ///
+    /// The resources must still be alive and have been created with [`BufferUsages::COPY_DST`]
+    /// or [`TextureUsages::COPY_DST`]. They are all validated before any of them is written. The
+    /// writes are submitted with the next queue submission, like [`Queue::write_buffer`].
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub fn restore(
+        &self,
+        snapshot: &wgc::device::snapshot::DeviceSnapshot,
+    ) -> Result<(), wgc::device::snapshot::DeviceSnapshotError> {
+        self.context
+            .as_any()
+            .downcast_ref::<crate::backend::Context>()
+            .unwrap()
+            .device_restore(self.data.as_ref().downcast_ref().unwrap(), snapshot)
+    }
+
+    /// Apply a callback to this `Device`'s underlying backend submission fence.
+    ///
+    /// The fence is signaled with an increasing value on every submission, as
+    /// represented by [`SubmissionIndex`]. On Vulkan it is a timeline semaphore when
+    /// those are supported, which can be exported for interop with other APIs.
+    ///
+    /// If this `Device` uses a different backend than `A`, the callback is applied to `None`.
     ///
-    /// # Safety
+    /// The device is locked for reading while `hal_fence_callback` runs, like with
+    /// [`Device::as_hal`].
     ///
-    /// - The raw handle passed to the callback must not be manually destroyed.
+    /// # Safety
     ///
-    /// [`A::Device`]: hal::Api::Device
+    /// - The raw handle passed to the callback must not be manually destroyed or signaled.
     #[cfg(any(
         not(target_arch = "wasm32"),
         target_os = "emscripten",
         feature = "webgl"
     ))]
-    pub unsafe fn as_hal<A: wgc::hal_api::HalApi, F: FnOnce(Option<&A::Device>) -> R, R>(
+    pub unsafe fn fence_as_hal<A: wgc::hal_api::HalApi, F: FnOnce(Option<&A::Fence>) -> R, R>(
         &self,
-        hal_device_callback: F,
+        hal_fence_callback: F,
     ) -> R {
         unsafe {
             self.context
                 .as_any()
                 .downcast_ref::<crate::backend::Context>()
                 .unwrap()
-                .device_as_hal::<A, F, R>(
+                .device_fence_as_hal::<A, F, R>(
                     self.data.as_ref().downcast_ref().unwrap(),
-                    hal_device_callback,
+                    hal_fence_callback,
                 )
         }
     }
@@ -2770,12 +3541,39 @@ impl error::Error for CreateSurfaceError {}
 
 /// Error occurred when trying to async map a buffer.
 #[derive(Clone, PartialEq, Eq, Debug)]
-pub struct BufferAsyncError;
+#[non_exhaustive]
+pub enum BufferAsyncError {
+    /// The device was lost before the mapping could complete.
+    DeviceLost,
+    /// Not enough memory was left to map the buffer.
+    OutOfMemory,
+    /// The requested range overlaps a range that is already mapped, or was
+    /// requested with a different [`MapMode`].
+    AlreadyMapped,
+    /// The requested range overlaps a range whose mapping is still pending.
+    MapAlreadyPending,
+    /// The buffer was unmapped or destroyed before the mapping completed.
+    Aborted,
+    /// The mapping request was invalid. Contains a description of the error.
+    Validation(String),
+}
 static_assertions::assert_impl_all!(BufferAsyncError: Send, Sync);
 
 impl Display for BufferAsyncError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Error occurred when trying to async map a buffer")
+        match self {
+            Self::DeviceLost => write!(f, "Device was lost while mapping a buffer"),
+            Self::OutOfMemory => write!(f, "Not enough memory left to map a buffer"),
+            Self::AlreadyMapped => write!(f, "Buffer range is already mapped"),
+            Self::MapAlreadyPending => write!(f, "Buffer range is already pending a map"),
+            Self::Aborted => write!(f, "Buffer map was aborted"),
+            Self::Validation(message) => {
+                write!(
+                    f,
+                    "Error occurred when trying to async map a buffer: {message}"
+                )
+            }
+        }
     }
 }
 
@@ -2920,14 +3718,61 @@ impl Buffer {
         DynContext::buffer_unmap(&*self.context, &self.id, self.data.as_ref());
     }
 
+    /// Returns the inner hal Buffer using a callback. The hal buffer will be `None` if the
+    /// backend type argument does not match with this wgpu Buffer
+    ///
+    /// # Safety
+    ///
+    /// - The raw handle obtained from the hal Buffer must not be manually destroyed
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub unsafe fn as_hal<A: wgc::hal_api::HalApi, F: FnOnce(Option<&A::Buffer>) -> R, R>(
+        &self,
+        hal_buffer_callback: F,
+    ) -> R {
+        unsafe {
+            self.context
+                .as_any()
+                .downcast_ref::<crate::backend::Context>()
+                .unwrap()
+                .buffer_as_hal::<A, F, R>(self.id.into(), hal_buffer_callback)
+        }
+    }
+
     /// Destroy the associated native resources as soon as possible.
     pub fn destroy(&self) {
         DynContext::buffer_destroy(&*self.context, &self.id, self.data.as_ref());
     }
 
+    /// Resizes the buffer to `size` bytes, keeping its contents up to the smaller of the old and
+    /// new sizes. The bytes past the old size are zeroed.
+    ///
+    /// The buffer is reallocated and its contents are copied on the queue, ahead of the next
+    /// submission. It must have the [`BufferUsages::COPY_SRC`] usage and must not be mapped.
+    /// The bind groups and render bundles that use the buffer must be dropped before resizing it.
+    /// So must the command buffers that use it and aren't submitted yet, and the
+    /// [`ReusableCommandBuffer`]s that use it, whether they have been submitted or not.
+    ///
+    /// This is not available on WebGPU.
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub fn resize(&mut self, size: BufferAddress) {
+        if DynContext::buffer_resize(&*self.context, &self.id, self.data.as_ref(), size) {
+            self.size = size;
+            self.map_context.get_mut().total_size = size;
+        }
+    }
+
     /// Returns the length of the buffer allocation in bytes.
     ///
-    /// This is always equal to the `size` that was specified when creating the buffer.
+    /// This is always equal to the `size` that was specified when creating the buffer, or last
+    /// given to [`Buffer::resize`].
     pub fn size(&self) -> BufferAddress {
         self.size
     }
@@ -2943,6 +3788,10 @@ impl Buffer {
 impl<'a> BufferSlice<'a> {
     /// Map the buffer. Buffer is ready to map once the callback is called.
     ///
+    /// Several disjoint slices of a buffer may be mapped at the same time, as long as they all use
+    /// the same [`MapMode`]. Requesting a slice that overlaps one that is already mapped or pending
+    /// calls the callback with an error.
+    ///
     /// For the callback to complete, either `queue.submit(..)`, `instance.poll_all(..)`, or `device.poll(..)`
     /// must be called elsewhere in the runtime, possibly integrated into an event loop or run on a separate thread.
     ///
@@ -2955,18 +3804,13 @@ impl<'a> BufferSlice<'a> {
         mode: MapMode,
         callback: impl FnOnce(Result<(), BufferAsyncError>) + WasmNotSend + 'static,
     ) {
-        let mut mc = self.buffer.map_context.lock();
-        assert_eq!(
-            mc.initial_range,
-            0..0,
-            "Buffer {:?} is already mapped",
-            self.buffer.id
-        );
         let end = match self.size {
             Some(s) => self.offset + s.get(),
-            None => mc.total_size,
+            None => self.buffer.map_context.lock().total_size,
         };
-        mc.initial_range = self.offset..end;
+        // An overlapping request is still forwarded, so that the backend reports
+        // the error through the callback.
+        self.buffer.map_context.lock().map(self.offset..end);
 
         DynContext::buffer_map_async(
             &*self.buffer.context,
@@ -3153,6 +3997,32 @@ impl Texture {
     }
 }
 
+impl TextureView {
+    /// Returns the inner hal TextureView using a callback. The hal texture view will be `None` if the
+    /// backend type argument does not match with this wgpu TextureView
+    ///
+    /// # Safety
+    ///
+    /// - The raw handle obtained from the hal TextureView must not be manually destroyed
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub unsafe fn as_hal<A: wgc::hal_api::HalApi, F: FnOnce(Option<&A::TextureView>) -> R, R>(
+        &self,
+        hal_texture_view_callback: F,
+    ) -> R {
+        unsafe {
+            self.context
+                .as_any()
+                .downcast_ref::<crate::backend::Context>()
+                .unwrap()
+                .texture_view_as_hal::<A, F, R>(self.id.into(), hal_texture_view_callback)
+        }
+    }
+}
+
 impl Drop for Texture {
     fn drop(&mut self) {
         if self.owned && !thread::panicking() {
@@ -3176,6 +4046,7 @@ impl CommandEncoder {
             &*self.context,
             self.id.take().unwrap(),
             self.data.as_mut(),
+            false,
         );
         CommandBuffer {
             context: Arc::clone(&self.context),
@@ -3184,6 +4055,26 @@ impl CommandEncoder {
         }
     }
 
+    /// Finishes recording and returns a [`ReusableCommandBuffer`] that can be submitted for
+    /// execution any number of times.
+    ///
+    /// Requires [`Features::REUSABLE_COMMAND_BUFFERS`], and the encoder must have been created
+    /// with [`CommandEncoderDescriptor::reusable`]. The recorded commands must not use surface
+    /// textures.
+    pub fn finish_reusable(mut self) -> ReusableCommandBuffer {
+        let (id, data) = DynContext::command_encoder_finish(
+            &*self.context,
+            self.id.take().unwrap(),
+            self.data.as_mut(),
+            true,
+        );
+        ReusableCommandBuffer {
+            context: Arc::clone(&self.context),
+            id,
+            data,
+        }
+    }
+
     /// Begins recording of a render pass.
     ///
     /// This function returns a [`RenderPass`] object which records a single render pass.
@@ -3574,6 +4465,74 @@ impl<'a> RenderPass<'a> {
         );
     }
 
+    /// Sets the rate at which the fragment shader is invoked.
+    ///
+    /// Each invocation covers a block of pixels of the given size, trading shading detail
+    /// for performance. The rate applies to the whole target, and is reset to
+    /// [`ShadingRate::Rate1x1`] at the start of every render pass.
+    ///
+    /// Requires [`Features::SHADING_RATE`].
+    pub fn set_shading_rate(&mut self, rate: ShadingRate) {
+        DynContext::render_pass_set_shading_rate(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            rate,
+        );
+    }
+
+    /// Sets the bounds of the depth bounds test, which discards fragments where the value
+    /// already in the depth buffer is outside of `min..=max`.
+    ///
+    /// Both bounds must be in `0.0..=1.0`, and `min` must not be greater than `max`. The
+    /// bounds only take effect for pipelines with [`DepthStencilState::depth_bounds`] set,
+    /// and are replaced by those whenever such a pipeline is set.
+    ///
+    /// Requires [`Features::DEPTH_BOUNDS_TEST`].
+    pub fn set_depth_bounds(&mut self, min: f32, max: f32) {
+        DynContext::render_pass_set_depth_bounds(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            min,
+            max,
+        );
+    }
+
+    /// Sets the viewports used during the rasterization stage, starting from the viewport
+    /// at index 0. The vertex shader selects the viewport of each primitive.
+    ///
+    /// Viewports that are not set cover the whole render target. At most [`MAX_VIEWPORTS`]
+    /// viewports can be set, and they are reset at the start of every render pass.
+    ///
+    /// Requires [`Features::MULTI_VIEWPORT`].
+    pub fn set_viewports(&mut self, viewports: &[Viewport]) {
+        DynContext::render_pass_set_viewports(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            viewports,
+        );
+    }
+
+    /// Sets the scissor rects used during the rasterization stage, starting from the
+    /// scissor rect at index 0. Each primitive uses the scissor rect of its viewport, see
+    /// [`RenderPass::set_viewports`].
+    ///
+    /// Scissor rects that are not set cover the whole render target. At most
+    /// [`MAX_VIEWPORTS`] scissor rects can be set, and they are reset at the start of every
+    /// render pass.
+    ///
+    /// Requires [`Features::MULTI_VIEWPORT`].
+    pub fn set_scissor_rects(&mut self, rects: &[ScissorRect]) {
+        DynContext::render_pass_set_scissor_rects(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            rects,
+        );
+    }
+
     /// Draws primitives from the active vertex buffer(s).
     ///
     /// The active vertex buffer(s) can be set with [`RenderPass::set_vertex_buffer`].
@@ -4428,6 +5387,37 @@ impl<'a> RenderBundleEncoder<'a> {
             data,
         );
     }
+
+    /// Inserts debug marker.
+    pub fn insert_debug_marker(&mut self, label: &str) {
+        DynContext::render_bundle_encoder_insert_debug_marker(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            label,
+        );
+    }
+
+    /// Start record commands and group it into debug marker group.
+    ///
+    /// Groups pushed in a bundle must be popped before the bundle is finished.
+    pub fn push_debug_group(&mut self, label: &str) {
+        DynContext::render_bundle_encoder_push_debug_group(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            label,
+        );
+    }
+
+    /// Stops command recording and creates debug group.
+    pub fn pop_debug_group(&mut self) {
+        DynContext::render_bundle_encoder_pop_debug_group(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+        );
+    }
 }
 
 /// A read-only view into a staging buffer.
@@ -4582,7 +5572,11 @@ impl Queue {
     }
 
     /// Schedule a copy of data from `image` into `texture`.
-    #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+    ///
+    /// On native, the source must be an [`ExternalImageSource::CpuImage`] and `dest`
+    /// must have one of the `Rgba8Unorm(Srgb)` or `Bgra8Unorm(Srgb)` formats. Flipping
+    /// and alpha premultiplication are then applied on the CPU before the upload.
+    #[cfg(not(target_os = "emscripten"))]
     pub fn copy_external_image_to_texture(
         &self,
         source: &wgt::ImageCopyExternalImage,
@@ -4603,6 +5597,25 @@ impl Queue {
     pub fn submit<I: IntoIterator<Item = CommandBuffer>>(
         &self,
         command_buffers: I,
+    ) -> SubmissionIndex {
+        self.submit_with_label(None, command_buffers)
+    }
+
+    /// Submits a series of finished command buffers for execution, tagging the submission
+    /// with `label`.
+    ///
+    /// The label is forwarded to the backend's profiling APIs, so that tools like PIX, Instruments
+    /// or RenderDoc can attribute the GPU time of the submission to a frame or job of the application:
+    /// - Vulkan brackets the submission in a queue debug label, if debug utils are enabled.
+    /// - DX12 brackets the submission in a PIX event on the queue.
+    /// - Metal prefixes the labels of the submitted command buffers.
+    /// - GL wraps the submission in a debug group, if debug output is available.
+    ///
+    /// It is ignored on WebGPU.
+    pub fn submit_with_label<I: IntoIterator<Item = CommandBuffer>>(
+        &self,
+        label: Option<&str>,
+        command_buffers: I,
     ) -> SubmissionIndex {
         let (raw, data) = DynContext::queue_submit(
             &*self.context,
@@ -4613,6 +5626,26 @@ impl Queue {
                     .into_iter()
                     .map(|mut comb| (comb.id.take().unwrap(), comb.data.take().unwrap())),
             ),
+            label,
+        );
+
+        SubmissionIndex(raw, data)
+    }
+
+    /// Submits a series of reusable command buffers for execution, without consuming them.
+    ///
+    /// The previous submission of each command buffer must be complete, and a command buffer
+    /// can't be submitted more than once per call.
+    pub fn submit_reusable<'a, I: IntoIterator<Item = &'a ReusableCommandBuffer>>(
+        &self,
+        command_buffers: I,
+    ) -> SubmissionIndex {
+        let (raw, data) = DynContext::queue_submit_reusable(
+            &*self.context,
+            &self.id,
+            self.data.as_ref(),
+            Box::new(command_buffers.into_iter().map(|comb| comb.id)),
+            None,
         );
 
         SubmissionIndex(raw, data)
@@ -4647,6 +5680,39 @@ impl Queue {
             Box::new(callback),
         )
     }
+
+    /// Returns the inner hal Queue using a callback. The hal queue will be `None` if the
+    /// backend type argument does not match with this wgpu Queue
+    ///
+    /// This allows backend specific synchronization with other APIs, like making the next
+    /// submission wait on a fence imported from them on Vulkan.
+    ///
+    /// The device is locked for writing while `hal_queue_callback` runs, so the callback
+    /// must not perform any `wgpu` operations on it.
+    ///
+    /// # Safety
+    ///
+    /// - The raw handle obtained from the hal Queue must not be manually destroyed
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub unsafe fn as_hal_mut<A: wgc::hal_api::HalApi, F: FnOnce(Option<&mut A::Queue>) -> R, R>(
+        &self,
+        hal_queue_callback: F,
+    ) -> R {
+        unsafe {
+            self.context
+                .as_any()
+                .downcast_ref::<crate::backend::Context>()
+                .unwrap()
+                .queue_as_hal_mut::<A, F, R>(
+                    self.data.as_ref().downcast_ref().unwrap(),
+                    hal_queue_callback,
+                )
+        }
+    }
 }
 
 impl SurfaceTexture {
@@ -4693,6 +5759,14 @@ impl Surface {
         )
     }
 
+    /// Returns the texture formats the surface can be configured with when used with the given adapter.
+    ///
+    /// The first format is the preferred one. Returns an empty list if the surface is incompatible
+    /// with the adapter, see [`Adapter::is_surface_supported`].
+    pub fn get_supported_formats(&self, adapter: &Adapter) -> Vec<TextureFormat> {
+        self.get_capabilities(adapter).formats
+    }
+
     /// Return a default `SurfaceConfiguration` from width and height to use for the [`Surface`] with this adapter.
     ///
     /// Returns None if the surface isn't supported by this adapter
@@ -4703,13 +5777,24 @@ impl Surface {
         height: u32,
     ) -> Option<SurfaceConfiguration> {
         let caps = self.get_capabilities(adapter);
+        let format = *caps.formats.get(0)?;
+        let color_space = if caps
+            .color_spaces
+            .contains(&(format, wgt::SurfaceColorSpace::Srgb))
+        {
+            wgt::SurfaceColorSpace::Srgb
+        } else {
+            caps.color_spaces.iter().find(|&&(f, _)| f == format)?.1
+        };
         Some(SurfaceConfiguration {
             usage: wgt::TextureUsages::RENDER_ATTACHMENT,
-            format: *caps.formats.get(0)?,
+            format,
             width,
             height,
             present_mode: *caps.present_modes.get(0)?,
+            desired_maximum_frame_latency: 2,
             alpha_mode: wgt::CompositeAlphaMode::Auto,
+            color_space,
             view_formats: vec![],
         })
     }
@@ -4791,6 +5876,62 @@ impl Surface {
             .ok_or(SurfaceError::Lost)
     }
 
+    /// Releases the window this surface presents to, keeping the devices using it alive.
+    ///
+    /// Call this when the platform destroys the window, e.g. when an Android application
+    /// goes to the background, then [`Surface::resume`] once a new window is available.
+    ///
+    /// # Panics
+    ///
+    /// - A [`SurfaceTexture`] of this surface is still alive.
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub fn suspend(&self) {
+        self.context
+            .as_any()
+            .downcast_ref::<crate::backend::Context>()
+            .unwrap()
+            .surface_suspend(self.data.downcast_ref().unwrap());
+        *self.config.lock() = None;
+    }
+
+    /// Makes this surface present to `window`, after [`Surface::suspend`].
+    ///
+    /// The surface must be configured again with [`Surface::configure`] before use.
+    /// Fails if no backend could create a surface for `window`, leaving this surface
+    /// suspended.
+    ///
+    /// # Safety
+    ///
+    /// - Same as [`Instance::create_surface`].
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub unsafe fn resume<
+        W: raw_window_handle::HasRawWindowHandle + raw_window_handle::HasRawDisplayHandle,
+    >(
+        &self,
+        window: &W,
+    ) -> Result<(), CreateSurfaceError> {
+        *self.config.lock() = None;
+        unsafe {
+            self.context
+                .as_any()
+                .downcast_ref::<crate::backend::Context>()
+                .unwrap()
+                .surface_resume(
+                    self.data.downcast_ref().unwrap(),
+                    raw_window_handle::HasRawDisplayHandle::raw_display_handle(window),
+                    raw_window_handle::HasRawWindowHandle::raw_window_handle(window),
+                )
+        }
+    }
+
     /// Returns the inner hal Surface using a callback. The hal surface will be `None` if the
     /// backend type argument does not match with this wgpu Surface
     ///
@@ -5054,6 +6195,19 @@ impl ComputePipeline {
     }
 }
 
+#[cfg(feature = "expose-ids")]
+impl PipelineCache {
+    /// Returns a globally-unique identifier for this `PipelineCache`.
+    ///
+    /// Calling this method multiple times on the same object will always return the same value.
+    /// The returned value is guaranteed to be unique among all `PipelineCache`s created from the same
+    /// `Instance`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "expose-ids")))]
+    pub fn global_id(&self) -> Id<PipelineCache> {
+        Id(self.id.global_id(), std::marker::PhantomData)
+    }
+}
+
 #[cfg(feature = "expose-ids")]
 impl RenderBundle {
     /// Returns a globally-unique identifier for this `RenderBundle`.
@@ -5131,6 +6285,30 @@ pub enum Error {
         /// Description of the validation error.
         description: String,
     },
+    /// Internal error, signifying a failure of the implementation or driver rather than of the
+    /// API usage, such as a backend shader compiler rejecting a validated shader.
+    Internal {
+        /// Lower level source of the error.
+        #[cfg(any(
+            not(target_arch = "wasm32"),
+            all(
+                feature = "fragile-send-sync-non-atomic-wasm",
+                not(target_feature = "atomics")
+            )
+        ))]
+        source: Box<dyn error::Error + Send + 'static>,
+        /// Lower level source of the error.
+        #[cfg(not(any(
+            not(target_arch = "wasm32"),
+            all(
+                feature = "fragile-send-sync-non-atomic-wasm",
+                not(target_feature = "atomics")
+            )
+        )))]
+        source: Box<dyn error::Error + 'static>,
+        /// Description of the internal error.
+        description: String,
+    },
 }
 #[cfg(any(
     not(target_arch = "wasm32"),
@@ -5146,6 +6324,7 @@ impl error::Error for Error {
         match self {
             Error::OutOfMemory { source } => Some(source.as_ref()),
             Error::Validation { source, .. } => Some(source.as_ref()),
+            Error::Internal { source, .. } => Some(source.as_ref()),
         }
     }
 }
@@ -5155,6 +6334,7 @@ impl Display for Error {
         match self {
             Error::OutOfMemory { .. } => f.write_str("Out of Memory"),
             Error::Validation { description, .. } => f.write_str(description),
+            Error::Internal { description, .. } => f.write_str(description),
         }
     }
 }
@@ -0,0 +1,169 @@
+use crate::TextureFormat;
+
+/// Returns the uncompressed format that BC compressed textures of `format` are
+/// decompressed to by [`decompress_bc`], or `None` if it can't decompress them.
+///
+/// BC1 to BC3 are decompressed to RGBA8, BC4 to R8 and BC5 to RG8, keeping the
+/// srgb-ness and signedness of the format. BC6H and BC7 are not supported.
+pub fn bc_decompressed_format(format: TextureFormat) -> Option<TextureFormat> {
+    use TextureFormat as Tf;
+    Some(match format {
+        Tf::Bc1RgbaUnorm | Tf::Bc2RgbaUnorm | Tf::Bc3RgbaUnorm => Tf::Rgba8Unorm,
+        Tf::Bc1RgbaUnormSrgb | Tf::Bc2RgbaUnormSrgb | Tf::Bc3RgbaUnormSrgb => Tf::Rgba8UnormSrgb,
+        Tf::Bc4RUnorm => Tf::R8Unorm,
+        Tf::Bc4RSnorm => Tf::R8Snorm,
+        Tf::Bc5RgUnorm => Tf::Rg8Unorm,
+        Tf::Bc5RgSnorm => Tf::Rg8Snorm,
+        _ => return None,
+    })
+}
+
+/// Decompresses a `width` by `height` image of BC compressed `format`.
+///
+/// `data` holds the blocks of the image, tightly packed in rows. The texels are
+/// returned tightly packed in rows, in the format given by [`bc_decompressed_format`].
+///
+/// Returns `None` if `format` can't be decompressed or `data` is too short.
+pub fn decompress_bc(
+    format: TextureFormat,
+    width: u32,
+    height: u32,
+    data: &[u8],
+) -> Option<Vec<u8>> {
+    use TextureFormat as Tf;
+
+    let texel_size = bc_decompressed_format(format)?.block_size(None).unwrap() as usize;
+    let block_size = format.block_size(None).unwrap() as usize;
+    let (width, height) = (width as usize, height as usize);
+    let (blocks_wide, blocks_high) = ((width + 3) / 4, (height + 3) / 4);
+    if data.len() < blocks_wide * blocks_high * block_size {
+        return None;
+    }
+
+    let mut texels = vec![0; width * height * texel_size];
+    for (index, block) in data
+        .chunks_exact(block_size)
+        .take(blocks_wide * blocks_high)
+        .enumerate()
+    {
+        let block_texels = match format {
+            Tf::Bc1RgbaUnorm | Tf::Bc1RgbaUnormSrgb => decode_color(block, true),
+            Tf::Bc2RgbaUnorm | Tf::Bc2RgbaUnormSrgb => {
+                let mut texels = decode_color(&block[8..], false);
+                let alpha = u64::from_le_bytes(block[..8].try_into().unwrap());
+                for (i, texel) in texels.iter_mut().enumerate() {
+                    texel[3] = ((alpha >> (4 * i)) & 0xF) as u8 * 17;
+                }
+                texels
+            }
+            Tf::Bc3RgbaUnorm | Tf::Bc3RgbaUnormSrgb => {
+                let mut texels = decode_color(&block[8..], false);
+                let alpha = decode_channel(&block[..8], false);
+                for (texel, alpha) in texels.iter_mut().zip(alpha) {
+                    texel[3] = alpha;
+                }
+                texels
+            }
+            Tf::Bc4RUnorm | Tf::Bc4RSnorm => {
+                let red = decode_channel(block, format == Tf::Bc4RSnorm);
+                red.map(|r| [r, 0, 0, 0])
+            }
+            Tf::Bc5RgUnorm | Tf::Bc5RgSnorm => {
+                let signed = format == Tf::Bc5RgSnorm;
+                let red = decode_channel(&block[..8], signed);
+                let green = decode_channel(&block[8..], signed);
+                let mut texels = [[0; 4]; 16];
+                for (i, texel) in texels.iter_mut().enumerate() {
+                    *texel = [red[i], green[i], 0, 0];
+                }
+                texels
+            }
+            _ => unreachable!(),
+        };
+
+        let (block_x, block_y) = (index % blocks_wide * 4, index / blocks_wide * 4);
+        for (i, texel) in block_texels.iter().enumerate() {
+            let (x, y) = (block_x + i % 4, block_y + i / 4);
+            if x < width && y < height {
+                let offset = (y * width + x) * texel_size;
+                texels[offset..offset + texel_size].copy_from_slice(&texel[..texel_size]);
+            }
+        }
+    }
+    Some(texels)
+}
+
+/// Decodes the 8 byte color block shared by BC1 to BC3.
+///
+/// Only BC1 uses the three color mode, where the fourth color is transparent black.
+fn decode_color(block: &[u8], allow_three_colors: bool) -> [[u8; 4]; 16] {
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let expand = |c: u16| {
+        let (r, g, b) = ((c >> 11) & 0x1F, (c >> 5) & 0x3F, c & 0x1F);
+        [
+            (r << 3 | r >> 2) as u32,
+            (g << 2 | g >> 4) as u32,
+            (b << 3 | b >> 2) as u32,
+        ]
+    };
+    let (e0, e1) = (expand(c0), expand(c1));
+    let mix = |w0: u32, w1: u32| {
+        let sum = w0 + w1;
+        let mut color = [255; 4];
+        for (c, (a, b)) in color.iter_mut().zip(e0.iter().zip(e1.iter())) {
+            *c = ((a * w0 + b * w1 + sum / 2) / sum) as u8;
+        }
+        color
+    };
+
+    let palette = if c0 > c1 || !allow_three_colors {
+        [mix(1, 0), mix(0, 1), mix(2, 1), mix(1, 2)]
+    } else {
+        [mix(1, 0), mix(0, 1), mix(1, 1), [0; 4]]
+    };
+    let indices = u32::from_le_bytes(block[4..8].try_into().unwrap());
+    let mut texels = [[0; 4]; 16];
+    for (i, texel) in texels.iter_mut().enumerate() {
+        *texel = palette[(indices >> (2 * i)) as usize & 3];
+    }
+    texels
+}
+
+/// Decodes the 8 byte single channel block of BC4, also used by BC3 and BC5.
+///
+/// Signed values are returned as the bytes of `i8`s.
+fn decode_channel(block: &[u8], signed: bool) -> [u8; 16] {
+    let endpoint = |byte: u8| {
+        if signed {
+            (byte as i8).max(-127) as i32
+        } else {
+            byte as i32
+        }
+    };
+    let (e0, e1) = (endpoint(block[0]), endpoint(block[1]));
+    let lerp = |i: i32, n: i32| ((e0 * (n - i) + e1 * i) as f32 / n as f32).round() as i32;
+
+    let mut palette = [e0, e1, 0, 0, 0, 0, 0, 0];
+    if e0 > e1 {
+        for i in 1..7 {
+            palette[i as usize + 1] = lerp(i, 7);
+        }
+    } else {
+        for i in 1..5 {
+            palette[i as usize + 1] = lerp(i, 5);
+        }
+        palette[6] = if signed { -127 } else { 0 };
+        palette[7] = if signed { 127 } else { 255 };
+    }
+
+    let indices = block[2..8]
+        .iter()
+        .rev()
+        .fold(0u64, |bits, &byte| bits << 8 | byte as u64);
+    let mut values = [0; 16];
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = palette[(indices >> (3 * i)) as usize & 7] as u8;
+    }
+    values
+}
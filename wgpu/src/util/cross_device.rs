@@ -0,0 +1,82 @@
+use std::sync::mpsc;
+
+use crate::{
+    Buffer, BufferAddress, BufferAsyncError, BufferSlice, Device, Extent3d, ImageCopyTexture,
+    ImageDataLayout, Maintain, Queue,
+};
+
+use super::{DownloadBuffer, DownloadTexture};
+
+/// Copies the contents of `src` to `dst` at `dst_offset`, where both buffers may belong to
+/// devices of different adapters.
+///
+/// The data goes through host memory: it's read back from `src_device`, then written with
+/// [`Queue::write_buffer`] ahead of the next submission on `dst_queue`. This blocks until the
+/// read back is done.
+///
+/// `src` must have the [`COPY_SRC`](crate::BufferUsages::COPY_SRC) usage and `dst` the
+/// [`COPY_DST`](crate::BufferUsages::COPY_DST) usage.
+pub fn copy_buffer_to_device(
+    src_device: &Device,
+    src_queue: &Queue,
+    src: &BufferSlice,
+    dst_queue: &Queue,
+    dst: &Buffer,
+    dst_offset: BufferAddress,
+) -> Result<(), BufferAsyncError> {
+    let (sender, receiver) = mpsc::channel();
+    DownloadBuffer::read_buffer(src_device, src_queue, src, move |result| {
+        let _ = sender.send(result.map(|download| download.to_vec()));
+    });
+    src_device.poll(Maintain::Wait);
+    let data = receiver
+        .recv()
+        .map_err(|_| BufferAsyncError::DeviceLost)??;
+    dst_queue.write_buffer(dst, dst_offset, &data);
+    Ok(())
+}
+
+/// Copies a region of `src` to `dst`, where both textures may belong to devices of different
+/// adapters.
+///
+/// The data goes through host memory: it's read back from `src_device`, then written with
+/// [`Queue::write_texture`] ahead of the next submission on `dst_queue`. This blocks until the
+/// read back is done.
+///
+/// `src` must have the [`COPY_SRC`](crate::TextureUsages::COPY_SRC) usage and `dst` the
+/// [`COPY_DST`](crate::TextureUsages::COPY_DST) usage. Both must have the same format.
+///
+/// # Panics
+///
+/// - If the aspect of `src` has no defined texel block size, like the depth aspect of
+///   `Depth24Plus`.
+pub fn copy_texture_to_device(
+    src_device: &Device,
+    src_queue: &Queue,
+    src: ImageCopyTexture,
+    dst_queue: &Queue,
+    dst: ImageCopyTexture,
+    size: Extent3d,
+) -> Result<(), BufferAsyncError> {
+    let (sender, receiver) = mpsc::channel();
+    DownloadTexture::read_texture(src_device, src_queue, src, size, move |result| {
+        let _ = sender.send(result.map(|download| (download.bytes_per_row(), download.to_vec())));
+    });
+    src_device.poll(Maintain::Wait);
+    let (bytes_per_row, data) = receiver
+        .recv()
+        .map_err(|_| BufferAsyncError::DeviceLost)??;
+
+    let block_height = dst.texture.format().block_dimensions().1;
+    dst_queue.write_texture(
+        dst,
+        &data,
+        ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(bytes_per_row),
+            rows_per_image: Some((size.height + block_height - 1) / block_height),
+        },
+        size,
+    );
+    Ok(())
+}
@@ -27,6 +27,13 @@ pub trait DeviceExt {
     ///
     /// Implicitly adds the `COPY_DST` usage if it is not present in the descriptor,
     /// as it is required to be able to upload the data to the gpu.
+    ///
+    /// With the `bc-decompression` feature, BC compressed textures are decompressed
+    /// on the CPU if the device doesn't support [`Features::TEXTURE_COMPRESSION_BC`].
+    /// The texture is then created with the format given by
+    /// [`bc_decompressed_format`](crate::util::bc_decompressed_format).
+    ///
+    /// [`Features::TEXTURE_COMPRESSION_BC`]: crate::Features::TEXTURE_COMPRESSION_BC
     fn create_texture_with_data(
         &self,
         queue: &crate::Queue,
@@ -80,6 +87,16 @@ impl DeviceExt for crate::Device {
         desc: &crate::TextureDescriptor,
         data: &[u8],
     ) -> crate::Texture {
+        #[cfg(feature = "bc-decompression")]
+        if !self
+            .features()
+            .contains(crate::Features::TEXTURE_COMPRESSION_BC)
+        {
+            if let Some(format) = super::bc_decompressed_format(desc.format) {
+                return create_texture_with_decompressed_data(self, queue, desc, format, data);
+            }
+        }
+
         // Implicitly add the COPY_DST usage
         let mut desc = desc.to_owned();
         desc.usage |= crate::TextureUsages::COPY_DST;
@@ -143,3 +160,55 @@ impl DeviceExt for crate::Device {
         texture
     }
 }
+
+/// Decompresses the BC compressed `data` of the texture described by `desc`,
+/// and creates the texture with the uncompressed `format` instead.
+#[cfg(feature = "bc-decompression")]
+fn create_texture_with_decompressed_data(
+    device: &crate::Device,
+    queue: &crate::Queue,
+    desc: &crate::TextureDescriptor,
+    format: crate::TextureFormat,
+    data: &[u8],
+) -> crate::Texture {
+    let block_size = desc.format.block_size(None).unwrap();
+    let mut decompressed = Vec::new();
+    let mut binary_offset = 0;
+    for _layer in 0..desc.array_layer_count() {
+        for mip in 0..desc.mip_level_count {
+            let mip_size = desc.mip_level_size(mip).unwrap();
+            let mip_physical = mip_size.physical_size(desc.format);
+            let slice_size =
+                (mip_physical.width / 4 * mip_physical.height / 4 * block_size) as usize;
+            let depth = match desc.dimension {
+                wgt::TextureDimension::D3 => mip_size.depth_or_array_layers,
+                _ => 1,
+            };
+            for _slice in 0..depth {
+                let end_offset = binary_offset + slice_size;
+                decompressed.extend(
+                    super::decompress_bc(
+                        desc.format,
+                        mip_size.width,
+                        mip_size.height,
+                        &data[binary_offset..end_offset],
+                    )
+                    .unwrap(),
+                );
+                binary_offset = end_offset;
+            }
+        }
+    }
+
+    let view_formats: Vec<_> = desc
+        .view_formats
+        .iter()
+        .filter_map(|&view_format| super::bc_decompressed_format(view_format))
+        .collect();
+    let decompressed_desc = crate::TextureDescriptor {
+        format,
+        view_formats: &view_formats,
+        ..desc.clone()
+    };
+    device.create_texture_with_data(queue, &decompressed_desc, &decompressed)
+}
@@ -0,0 +1,65 @@
+use crate::{
+    ComputePipeline, ComputePipelineDescriptor, Device, Error, ErrorFilter, RenderPipeline,
+    RenderPipelineDescriptor, ShaderModule, ShaderModuleDescriptor,
+};
+
+/// Runs `create` inside validation and out-of-memory error scopes, returning the first error
+/// it raised instead of passing it to the uncaptured error handler.
+async fn capture_errors<T>(device: &Device, create: impl FnOnce() -> T) -> Result<T, Error> {
+    device.push_error_scope(ErrorFilter::OutOfMemory);
+    device.push_error_scope(ErrorFilter::Validation);
+    let value = create();
+    let validation = device.pop_error_scope().await;
+    let out_of_memory = device.pop_error_scope().await;
+    match validation.or(out_of_memory) {
+        Some(error) => Err(error),
+        None => Ok(value),
+    }
+}
+
+/// Creates a [`ShaderModule`], returning compilation errors instead of raising them.
+///
+/// Meant for reloading shaders while the application runs: a typo in the new source leaves
+/// the old module and its pipelines usable.
+pub async fn try_create_shader_module(
+    device: &Device,
+    desc: ShaderModuleDescriptor<'_>,
+) -> Result<ShaderModule, Error> {
+    capture_errors(device, || device.create_shader_module(desc)).await
+}
+
+/// Creates a copy of the pipeline described by `desc` that uses `module` for all of its stages,
+/// returning errors instead of raising them.
+///
+/// `desc.layout` should be set: bind groups created against an explicit layout stay compatible
+/// with the new pipeline, while a derived layout is derived again from `module`. Unchanged
+/// stages are fetched from `desc.cache` if it's set.
+pub async fn recreate_render_pipeline_with_shader(
+    device: &Device,
+    desc: &RenderPipelineDescriptor<'_>,
+    module: &ShaderModule,
+) -> Result<RenderPipeline, Error> {
+    let mut desc = desc.clone();
+    desc.vertex.module = module;
+    if let Some(ref mut fragment) = desc.fragment {
+        fragment.module = module;
+    }
+    capture_errors(device, || device.create_render_pipeline(&desc)).await
+}
+
+/// Creates a copy of the pipeline described by `desc` that uses `module`, returning errors
+/// instead of raising them.
+///
+/// `desc.layout` should be set: bind groups created against an explicit layout stay compatible
+/// with the new pipeline, while a derived layout is derived again from `module`.
+pub async fn recreate_compute_pipeline_with_shader(
+    device: &Device,
+    desc: &ComputePipelineDescriptor<'_>,
+    module: &ShaderModule,
+) -> Result<ComputePipeline, Error> {
+    let desc = ComputePipelineDescriptor {
+        module,
+        ..desc.clone()
+    };
+    capture_errors(device, || device.create_compute_pipeline(&desc)).await
+}
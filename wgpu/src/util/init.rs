@@ -36,7 +36,12 @@ pub fn power_preference_from_env() -> Option<PowerPreference> {
     )
 }
 
-/// Initialize the adapter obeying the WGPU_ADAPTER_NAME environment variable.
+/// Initialize the adapter obeying the WGPU_ADAPTER_NAME, WGPU_ADAPTER_DEVICE_ID and
+/// WGPU_ADAPTER_INDEX environment variables.
+///
+/// Adapters are filtered by name substring and PCI device id, then WGPU_ADAPTER_INDEX picks
+/// among the remaining ones, in the order of [`Instance::enumerate_adapters`]. This tells apart
+/// identical GPUs on multi-GPU machines. Returns `None` if none of the variables are set.
 #[cfg(not(target_arch = "wasm32"))]
 pub fn initialize_adapter_from_env(
     instance: &Instance,
@@ -45,30 +50,41 @@ pub fn initialize_adapter_from_env(
     let desired_adapter_name = std::env::var("WGPU_ADAPTER_NAME")
         .as_deref()
         .map(str::to_lowercase)
-        .ok()?;
+        .ok();
+    let desired_device_id = std::env::var("WGPU_ADAPTER_DEVICE_ID").ok().map(|id| {
+        match id.strip_prefix("0x") {
+            Some(hex) => u32::from_str_radix(hex, 16),
+            None => id.parse(),
+        }
+        .expect("WGPU_ADAPTER_DEVICE_ID is not a valid device id!")
+    });
+    let desired_index = std::env::var("WGPU_ADAPTER_INDEX").ok().map(|index| {
+        index
+            .parse::<usize>()
+            .expect("WGPU_ADAPTER_INDEX is not a valid index!")
+    });
+    if desired_adapter_name.is_none() && desired_device_id.is_none() && desired_index.is_none() {
+        return None;
+    }
 
     let adapters = instance.enumerate_adapters(Backends::all());
 
-    let mut chosen_adapter = None;
-    for adapter in adapters {
-        let info = adapter.get_info();
-
-        if let Some(surface) = compatible_surface {
-            if !adapter.is_surface_supported(surface) {
-                continue;
-            }
-        }
-
-        if info.name.to_lowercase().contains(&desired_adapter_name) {
-            chosen_adapter = Some(adapter);
-            break;
-        }
-    }
+    let chosen_adapter = adapters
+        .filter(|adapter| {
+            let info = adapter.get_info();
+            compatible_surface.map_or(true, |surface| adapter.is_surface_supported(surface))
+                && desired_adapter_name
+                    .as_ref()
+                    .map_or(true, |name| info.name.to_lowercase().contains(name))
+                && desired_device_id.map_or(true, |id| info.device == id)
+        })
+        .nth(desired_index.unwrap_or(0));
 
-    Some(chosen_adapter.expect("WGPU_ADAPTER_NAME set but no matching adapter found!"))
+    Some(chosen_adapter.expect("WGPU_ADAPTER_* set but no matching adapter found!"))
 }
 
-/// Initialize the adapter obeying the WGPU_ADAPTER_NAME environment variable.
+/// Initialize the adapter obeying the WGPU_ADAPTER_NAME, WGPU_ADAPTER_DEVICE_ID and
+/// WGPU_ADAPTER_INDEX environment variables.
 #[cfg(target_arch = "wasm32")]
 pub fn initialize_adapter_from_env(
     _instance: &Instance,
@@ -77,7 +93,7 @@ pub fn initialize_adapter_from_env(
     None
 }
 
-/// Initialize the adapter obeying the WGPU_ADAPTER_NAME environment variable and if it doesn't exist fall back on a default adapter.
+/// Initialize the adapter obeying the WGPU_ADAPTER_* environment variables and if none are set fall back on a default adapter.
 pub async fn initialize_adapter_from_env_or_default(
     instance: &Instance,
     compatible_surface: Option<&Surface>,
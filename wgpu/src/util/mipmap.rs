@@ -0,0 +1,305 @@
+use crate::{
+    BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
+    BindingResource, BindingType, CommandEncoder, ComputePassDescriptor, ComputePipelineDescriptor,
+    Device, FilterMode, FragmentState, LoadOp, Operations, PipelineLayoutDescriptor,
+    RenderPassColorAttachment, RenderPassDescriptor, RenderPipelineDescriptor, SamplerDescriptor,
+    ShaderModuleDescriptor, ShaderSource, ShaderStages, StorageTextureAccess, Texture,
+    TextureDimension, TextureFormat, TextureFormatFeatureFlags, TextureSampleType, TextureUsages,
+    TextureView, TextureViewDescriptor, TextureViewDimension, VertexState,
+};
+use std::borrow::Cow;
+
+const BLIT_SHADER: &str = "
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+// Meant to be called with 3 vertex indices, draws one triangle covering the viewport.
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let tc = vec2<f32>(f32(vertex_index / 2u) * 2.0, f32(vertex_index & 1u) * 2.0);
+    var result: VertexOutput;
+    result.position = vec4<f32>(tc.x * 2.0 - 1.0, 1.0 - tc.y * 2.0, 0.0, 1.0);
+    result.tex_coords = tc;
+    return result;
+}
+
+@group(0) @binding(0)
+var src: texture_2d<f32>;
+@group(0) @binding(1)
+var src_sampler: sampler;
+
+@fragment
+fn fs_main(vertex: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(src, src_sampler, vertex.tex_coords);
+}
+";
+
+const DOWNSAMPLE_SHADER: &str = "
+@group(0) @binding(0)
+var src: texture_2d<f32>;
+@group(0) @binding(1)
+var dst: texture_storage_2d<FORMAT, write>;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = textureDimensions(dst);
+    if id.x >= size.x || id.y >= size.y {
+        return;
+    }
+    // Odd source sizes are clamped to the last texel.
+    let last = vec2<i32>(textureDimensions(src)) - 1;
+    let base = vec2<i32>(id.xy) * 2;
+    let color = textureLoad(src, min(base, last), 0)
+        + textureLoad(src, min(base + vec2<i32>(1, 0), last), 0)
+        + textureLoad(src, min(base + vec2<i32>(0, 1), last), 0)
+        + textureLoad(src, min(base + vec2<i32>(1, 1), last), 0);
+    textureStore(dst, vec2<i32>(id.xy), color * 0.25);
+}
+";
+
+/// Name of the WGSL storage texture format that the compute downsampler can write to.
+fn storage_format_name(format: TextureFormat) -> Option<&'static str> {
+    Some(match format {
+        TextureFormat::Rgba8Unorm => "rgba8unorm",
+        TextureFormat::Rgba8Snorm => "rgba8snorm",
+        TextureFormat::Rgba16Float => "rgba16float",
+        TextureFormat::R32Float => "r32float",
+        TextureFormat::Rg32Float => "rg32float",
+        TextureFormat::Rgba32Float => "rgba32float",
+        _ => return None,
+    })
+}
+
+/// Fill mip levels `1..` of every layer of `texture` by repeatedly downsampling mip level 0.
+///
+/// Formats that are renderable and filterable are downsampled with a render pass
+/// drawing the previous level through a linear sampler. Views of sRGB formats are
+/// decoded when sampled and encoded again when rendered to, so filtering happens in
+/// linear space. This requires the [`TextureUsages::RENDER_ATTACHMENT`] and
+/// [`TextureUsages::TEXTURE_BINDING`] usages.
+///
+/// Other float formats usable as storage textures (e.g. `Rgba32Float`, which isn't
+/// filterable) are instead downsampled with a 2x2 box filter in a compute shader.
+/// This requires the [`TextureUsages::STORAGE_BINDING`] and
+/// [`TextureUsages::TEXTURE_BINDING`] usages.
+///
+/// The GL backend can't yet sample from views of mip levels other than the first, so
+/// only mip level 1 is generated correctly there.
+///
+/// # Panics
+///
+/// - If the texture is not 2D.
+/// - If the format of the texture can be downsampled with neither method.
+pub fn generate_mipmaps(encoder: &mut CommandEncoder, device: &Device, texture: &Texture) {
+    assert_eq!(
+        texture.dimension(),
+        TextureDimension::D2,
+        "Mipmaps can only be generated for 2D textures"
+    );
+
+    let format = texture.format();
+    let format_features = format.guaranteed_format_features(device.features());
+    let is_float = matches!(
+        format.sample_type(None),
+        Some(TextureSampleType::Float { .. })
+    );
+
+    if is_float
+        && format_features
+            .flags
+            .contains(TextureFormatFeatureFlags::FILTERABLE)
+        && format_features
+            .allowed_usages
+            .contains(TextureUsages::RENDER_ATTACHMENT)
+    {
+        generate_mipmaps_with_render_passes(encoder, device, texture);
+    } else if let Some(name) = storage_format_name(format).filter(|_| {
+        format_features
+            .allowed_usages
+            .contains(TextureUsages::STORAGE_BINDING)
+    }) {
+        generate_mipmaps_with_compute(encoder, device, texture, name);
+    } else {
+        panic!("Mipmaps can't be generated for textures of format {format:?}");
+    }
+}
+
+fn mip_view(texture: &Texture, mip_level: u32, layer: u32) -> TextureView {
+    texture.create_view(&TextureViewDescriptor {
+        label: Some("(wgpu internal) mipmap view"),
+        dimension: Some(TextureViewDimension::D2),
+        base_mip_level: mip_level,
+        mip_level_count: Some(1),
+        base_array_layer: layer,
+        array_layer_count: Some(1),
+        ..Default::default()
+    })
+}
+
+fn generate_mipmaps_with_render_passes(
+    encoder: &mut CommandEncoder,
+    device: &Device,
+    texture: &Texture,
+) {
+    let shader = device.create_shader_module(ShaderModuleDescriptor {
+        label: Some("(wgpu internal) mipmap blit"),
+        source: ShaderSource::Wgsl(Cow::Borrowed(BLIT_SHADER)),
+    });
+    let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+        label: Some("(wgpu internal) mipmap blit"),
+        layout: None,
+        vertex: VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            constants: &Default::default(),
+            buffers: &[],
+        },
+        fragment: Some(FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            constants: &Default::default(),
+            targets: &[Some(texture.format().into())],
+        }),
+        primitive: Default::default(),
+        depth_stencil: None,
+        multisample: Default::default(),
+        multiview: None,
+        cache: None,
+    });
+    let bind_group_layout = pipeline.get_bind_group_layout(0);
+    let sampler = device.create_sampler(&SamplerDescriptor {
+        label: Some("(wgpu internal) mipmap blit"),
+        mag_filter: FilterMode::Linear,
+        min_filter: FilterMode::Linear,
+        ..Default::default()
+    });
+
+    for layer in 0..texture.depth_or_array_layers() {
+        for mip_level in 1..texture.mip_level_count() {
+            let src_view = mip_view(texture, mip_level - 1, layer);
+            let dst_view = mip_view(texture, mip_level, layer);
+            let bind_group = device.create_bind_group(&BindGroupDescriptor {
+                label: None,
+                layout: &bind_group_layout,
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(&src_view),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(&sampler),
+                    },
+                ],
+            });
+
+            let mut pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some("(wgpu internal) mipmap blit"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &dst_view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(Default::default()),
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+    }
+}
+
+fn generate_mipmaps_with_compute(
+    encoder: &mut CommandEncoder,
+    device: &Device,
+    texture: &Texture,
+    storage_format_name: &str,
+) {
+    let shader = device.create_shader_module(ShaderModuleDescriptor {
+        label: Some("(wgpu internal) mipmap downsample"),
+        source: ShaderSource::Wgsl(Cow::Owned(
+            DOWNSAMPLE_SHADER.replace("FORMAT", storage_format_name),
+        )),
+    });
+    let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+        label: Some("(wgpu internal) mipmap downsample"),
+        entries: &[
+            BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::Texture {
+                    sample_type: TextureSampleType::Float { filterable: false },
+                    view_dimension: TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            BindGroupLayoutEntry {
+                binding: 1,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::StorageTexture {
+                    access: StorageTextureAccess::WriteOnly,
+                    format: texture.format(),
+                    view_dimension: TextureViewDimension::D2,
+                },
+                count: None,
+            },
+        ],
+    });
+    let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+        label: Some("(wgpu internal) mipmap downsample"),
+        bind_group_layouts: &[&bind_group_layout],
+        push_constant_ranges: &[],
+    });
+    let pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
+        label: Some("(wgpu internal) mipmap downsample"),
+        layout: Some(&pipeline_layout),
+        module: &shader,
+        entry_point: "main",
+        constants: &Default::default(),
+        cache: None,
+    });
+
+    let mut dispatches = Vec::new();
+    for layer in 0..texture.depth_or_array_layers() {
+        for mip_level in 1..texture.mip_level_count() {
+            let src_view = mip_view(texture, mip_level - 1, layer);
+            let dst_view = mip_view(texture, mip_level, layer);
+            let bind_group = device.create_bind_group(&BindGroupDescriptor {
+                label: None,
+                layout: &bind_group_layout,
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(&src_view),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::TextureView(&dst_view),
+                    },
+                ],
+            });
+            let width = (texture.width() >> mip_level).max(1);
+            let height = (texture.height() >> mip_level).max(1);
+            dispatches.push((bind_group, (width + 7) / 8, (height + 7) / 8));
+        }
+    }
+
+    let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+        label: Some("(wgpu internal) mipmap downsample"),
+        timestamp_writes: None,
+    });
+    pass.set_pipeline(&pipeline);
+    for (bind_group, x, y) in &dispatches {
+        pass.set_bind_group(0, bind_group, &[]);
+        pass.dispatch_workgroups(*x, *y, 1);
+    }
+}
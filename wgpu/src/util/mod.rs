@@ -3,11 +3,22 @@
 //! Nothing in this module is a part of the WebGPU API specification;
 //! they are unique to the `wgpu` library.
 
+#[cfg(feature = "bc-decompression")]
+mod bc;
 mod belt;
+#[cfg(not(target_arch = "wasm32"))]
+mod cross_device;
 mod device;
 mod encoder;
+mod hot_reload;
 mod indirect;
 mod init;
+#[cfg(feature = "wgsl")]
+mod mipmap;
+mod profiler;
+#[cfg(feature = "wgsl")]
+mod tonemap;
+mod uniform;
 
 use std::sync::Arc;
 use std::{
@@ -16,11 +27,27 @@ use std::{
     ptr::copy_nonoverlapping,
 };
 
+#[cfg(feature = "bc-decompression")]
+pub use bc::{bc_decompressed_format, decompress_bc};
 pub use belt::StagingBelt;
+#[cfg(not(target_arch = "wasm32"))]
+pub use cross_device::{copy_buffer_to_device, copy_texture_to_device};
 pub use device::{BufferInitDescriptor, DeviceExt};
 pub use encoder::RenderEncoder;
+pub use hot_reload::{
+    recreate_compute_pipeline_with_shader, recreate_render_pipeline_with_shader,
+    try_create_shader_module,
+};
 pub use indirect::*;
 pub use init::*;
+#[cfg(feature = "wgsl")]
+pub use mipmap::generate_mipmaps;
+pub use profiler::{
+    chrome_trace_json, GpuProfiler, GpuTimerScopeResult, ProfileScopeExt, ProfilerScope,
+};
+#[cfg(feature = "wgsl")]
+pub use tonemap::{OutputTransfer, TonemapOperator, TonemapParams, Tonemapper};
+pub use uniform::DynamicUniformBuffer;
 pub use wgt::math::*;
 
 /// Treat the given byte slice as a SPIR-V module.
@@ -109,8 +136,11 @@ impl DownloadBuffer {
             label: None,
         }));
 
-        let mut encoder =
-            device.create_command_encoder(&super::CommandEncoderDescriptor { label: None });
+        let mut encoder = device.create_command_encoder(&super::CommandEncoderDescriptor {
+            label: None,
+            reusable: false,
+            protected: false,
+        });
         encoder.copy_buffer_to_buffer(buffer.buffer, buffer.offset, &download, 0, size);
         let command_buffer: super::CommandBuffer = encoder.finish();
         queue.submit(Some(command_buffer));
@@ -141,3 +171,110 @@ impl std::ops::Deref for DownloadBuffer {
         self.1.slice()
     }
 }
+
+/// Tightly packed copy of a texture region downloaded back from the GPU.
+///
+/// Rows of texel blocks follow each other without the
+/// [`COPY_BYTES_PER_ROW_ALIGNMENT`](crate::COPY_BYTES_PER_ROW_ALIGNMENT) padding
+/// that copies to buffers require, followed by the next array layer.
+pub struct DownloadTexture {
+    data: Vec<u8>,
+    bytes_per_row: u32,
+}
+
+impl DownloadTexture {
+    /// Asynchronously read the contents of a region of a texture.
+    ///
+    /// The texture must have the [`TextureUsages::COPY_SRC`](crate::TextureUsages::COPY_SRC)
+    /// usage. This includes surface textures if the surface was configured with it.
+    ///
+    /// # Panics
+    ///
+    /// - If the aspect of `texture` has no defined texel block size,
+    ///   like the depth aspect of `Depth24Plus`.
+    pub fn read_texture(
+        device: &super::Device,
+        queue: &super::Queue,
+        texture: super::ImageCopyTexture,
+        size: super::Extent3d,
+        callback: impl FnOnce(Result<Self, super::BufferAsyncError>) + Send + 'static,
+    ) {
+        let format = texture.texture.format();
+        let block_size = format.block_size(Some(texture.aspect)).unwrap_or_else(|| {
+            panic!(
+                "Can't read back {format:?} with aspect {:?}",
+                texture.aspect
+            )
+        });
+        let (block_width, block_height) = format.block_dimensions();
+        let bytes_per_row = (size.width + block_width - 1) / block_width * block_size;
+        let padded_bytes_per_row = align_to(bytes_per_row, super::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let rows_per_image = (size.height + block_height - 1) / block_height;
+        let rows = rows_per_image * size.depth_or_array_layers;
+
+        let download = Arc::new(device.create_buffer(&super::BufferDescriptor {
+            size: padded_bytes_per_row as super::BufferAddress * rows as super::BufferAddress,
+            usage: super::BufferUsages::COPY_DST | super::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+            label: None,
+        }));
+
+        let mut encoder = device.create_command_encoder(&super::CommandEncoderDescriptor {
+            label: None,
+            reusable: false,
+            protected: false,
+        });
+        encoder.copy_texture_to_buffer(
+            texture,
+            super::ImageCopyBuffer {
+                buffer: &download,
+                layout: super::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(rows_per_image),
+                },
+            },
+            size,
+        );
+        queue.submit(Some(encoder.finish()));
+
+        download
+            .clone()
+            .slice(..)
+            .map_async(super::MapMode::Read, move |result| {
+                if let Err(e) = result {
+                    callback(Err(e));
+                    return;
+                }
+
+                let padded = download.slice(..).get_mapped_range();
+                let data = padded
+                    .chunks(padded_bytes_per_row as usize)
+                    .flat_map(|row| &row[..bytes_per_row as usize])
+                    .copied()
+                    .collect();
+                drop(padded);
+                callback(Ok(Self {
+                    data,
+                    bytes_per_row,
+                }));
+            });
+    }
+
+    /// Number of bytes in each tightly packed row of texel blocks.
+    pub fn bytes_per_row(&self) -> u32 {
+        self.bytes_per_row
+    }
+
+    /// Take the tightly packed texel data.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+impl std::ops::Deref for DownloadTexture {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        &self.data
+    }
+}
@@ -0,0 +1,415 @@
+use crate::{
+    Buffer, BufferAddress, BufferDescriptor, BufferUsages, CommandEncoder, Device, Features,
+    MapMode, QuerySet, QuerySetDescriptor, QueryType, Queue, QUERY_SIZE,
+};
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::ops::{Deref, DerefMut, Range};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Timing of a scope recorded by a [`GpuProfiler`], along with the scopes nested in it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GpuTimerScopeResult {
+    /// Label passed when the scope was opened.
+    pub label: String,
+    /// Start and end of the scope in seconds, relative to an arbitrary origin
+    /// of the GPU.
+    ///
+    /// This is `None` if the scope wasn't timed, because the device doesn't support
+    /// [`Features::TIMESTAMP_QUERY`] or because the frame had more scopes than the
+    /// profiler was created for.
+    pub time: Option<Range<f64>>,
+    /// Scopes opened inside this one, in recording order.
+    pub nested_scopes: Vec<GpuTimerScopeResult>,
+}
+
+/// A scope that was closed, whose timestamps haven't been read back yet.
+struct RecordedScope {
+    label: String,
+    /// Index of the start timestamp query, followed by the end one.
+    query: Option<u32>,
+    nested: Vec<RecordedScope>,
+}
+
+/// Query set and buffers holding the timestamps of one frame.
+struct QueryPool {
+    query_set: QuerySet,
+    /// Destination of `resolve_query_set`, which can't be mapped.
+    resolve_buffer: Buffer,
+    /// Copy of `resolve_buffer` which is mapped to read the timestamps.
+    read_buffer: Buffer,
+}
+
+struct Frame {
+    /// `None` if timestamps are not supported.
+    pool: Option<QueryPool>,
+    used_queries: u32,
+    resolved_queries: u32,
+    scopes: Vec<RecordedScope>,
+    /// Set once the mapping of `read_buffer` is done, or right away if there is
+    /// nothing to read.
+    ready: Arc<AtomicBool>,
+    /// Whether `read_buffer` was mapped successfully.
+    mapped: Arc<AtomicBool>,
+}
+
+/// Measures the GPU time spent in nested scopes of command encoders.
+///
+/// Each scope writes a timestamp to a query set when it is opened and when it is
+/// closed. The query sets are owned by the profiler and are only reused once their
+/// timestamps were read back, so they may be shared by many frames in flight.
+///
+/// Profiling a frame generally goes as follows:
+/// 1. Record commands into scopes using [`ProfileScopeExt::profile_scope()`] or
+///    [`GpuProfiler::scope()`]. Scopes are nested with [`ProfilerScope::scope()`].
+/// 2. Call [`GpuProfiler::resolve_queries()`] on the encoder submitted last.
+/// 3. Submit all the command encoders that were used in steps 1 and 2.
+/// 4. Call [`GpuProfiler::end_frame()`].
+/// 5. Poll the device and call [`GpuProfiler::process_finished_frame()`] until it
+///    returns the report of the frame, usually a few frames later.
+///
+/// If the device doesn't support [`Features::TIMESTAMP_QUERY`], scopes still make up
+/// reports, but none of them are timed.
+pub struct GpuProfiler {
+    max_queries: u32,
+    /// Nanoseconds per timestamp tick.
+    timestamp_period: f32,
+    /// Frame whose scopes are being recorded.
+    current_frame: Frame,
+    /// Frames that ended, oldest first, whose timestamps are being read back.
+    pending_frames: VecDeque<Frame>,
+    /// Query pools of processed frames, ready to be reused.
+    free_pools: Vec<QueryPool>,
+}
+
+impl GpuProfiler {
+    /// Creates a profiler that times up to `max_scopes_per_frame` scopes per frame.
+    ///
+    /// Scopes beyond this count are still reported, without timing.
+    pub fn new(device: &Device, queue: &Queue, max_scopes_per_frame: u32) -> Self {
+        let max_queries = max_scopes_per_frame
+            .saturating_mul(2)
+            .min(crate::QUERY_SET_MAX_QUERIES);
+        let enabled = device.features().contains(Features::TIMESTAMP_QUERY) && max_queries > 0;
+        let pool = if enabled {
+            Some(Self::create_pool(device, max_queries))
+        } else {
+            None
+        };
+        GpuProfiler {
+            max_queries,
+            timestamp_period: queue.get_timestamp_period(),
+            current_frame: Frame::new(pool),
+            pending_frames: VecDeque::new(),
+            free_pools: Vec::new(),
+        }
+    }
+
+    fn create_pool(device: &Device, max_queries: u32) -> QueryPool {
+        let size = max_queries as BufferAddress * QUERY_SIZE as BufferAddress;
+        QueryPool {
+            query_set: device.create_query_set(&QuerySetDescriptor {
+                label: Some("(wgpu internal) GpuProfiler query set"),
+                ty: QueryType::Timestamp,
+                count: max_queries,
+            }),
+            resolve_buffer: device.create_buffer(&BufferDescriptor {
+                label: Some("(wgpu internal) GpuProfiler resolve buffer"),
+                size,
+                usage: BufferUsages::QUERY_RESOLVE | BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            }),
+            read_buffer: device.create_buffer(&BufferDescriptor {
+                label: Some("(wgpu internal) GpuProfiler read buffer"),
+                size,
+                usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            }),
+        }
+    }
+
+    /// Records the commands of `f` into a scope named `label`.
+    ///
+    /// The scope is timed from the commands recorded into `encoder` before `f`
+    /// is called to the ones recorded after it returns.
+    pub fn scope<R>(
+        &mut self,
+        label: &str,
+        encoder: &mut CommandEncoder,
+        f: impl FnOnce(&mut ProfilerScope<'_>) -> R,
+    ) -> R {
+        let (result, scope) = self.record_scope(label, encoder, f);
+        self.current_frame.scopes.push(scope);
+        result
+    }
+
+    fn record_scope<R>(
+        &mut self,
+        label: &str,
+        encoder: &mut CommandEncoder,
+        f: impl FnOnce(&mut ProfilerScope<'_>) -> R,
+    ) -> (R, RecordedScope) {
+        let query = self.current_frame.allocate_query_pair(self.max_queries);
+        if let (Some(pool), Some(index)) = (&self.current_frame.pool, query) {
+            encoder.write_timestamp(&pool.query_set, index);
+        }
+
+        let mut scope = ProfilerScope {
+            profiler: self,
+            encoder,
+            nested: Vec::new(),
+        };
+        let result = f(&mut scope);
+        let nested = scope.nested;
+
+        if let (Some(pool), Some(index)) = (&self.current_frame.pool, query) {
+            encoder.write_timestamp(&pool.query_set, index + 1);
+        }
+        let scope = RecordedScope {
+            label: label.to_string(),
+            query,
+            nested,
+        };
+        (result, scope)
+    }
+
+    /// Resolves the timestamps of all the scopes recorded so far in this frame.
+    ///
+    /// This must be recorded into an encoder that is submitted after all the
+    /// encoders in which the scopes were recorded, and after the last scope of
+    /// the frame. Additional calls are harmless.
+    pub fn resolve_queries(&mut self, encoder: &mut CommandEncoder) {
+        let frame = &mut self.current_frame;
+        let pool = match frame.pool {
+            Some(ref pool) if frame.resolved_queries < frame.used_queries => pool,
+            _ => return,
+        };
+        let size = frame.used_queries as BufferAddress * QUERY_SIZE as BufferAddress;
+        encoder.resolve_query_set(
+            &pool.query_set,
+            0..frame.used_queries,
+            &pool.resolve_buffer,
+            0,
+        );
+        encoder.copy_buffer_to_buffer(&pool.resolve_buffer, 0, &pool.read_buffer, 0, size);
+        frame.resolved_queries = frame.used_queries;
+    }
+
+    /// Ends the current frame and starts reading its timestamps back.
+    ///
+    /// This must be called after the encoder passed to
+    /// [`GpuProfiler::resolve_queries()`] was submitted.
+    ///
+    /// # Panics
+    ///
+    /// - If scopes were recorded since the last call to [`GpuProfiler::resolve_queries()`].
+    pub fn end_frame(&mut self, device: &Device) {
+        assert_eq!(
+            self.current_frame.resolved_queries, self.current_frame.used_queries,
+            "GpuProfiler::resolve_queries must be called after the last scope of the frame"
+        );
+
+        let pool = self.current_frame.pool.is_some().then(|| {
+            self.free_pools
+                .pop()
+                .unwrap_or_else(|| Self::create_pool(device, self.max_queries))
+        });
+        let frame = std::mem::replace(&mut self.current_frame, Frame::new(pool));
+
+        match frame.pool {
+            Some(ref pool) if frame.used_queries > 0 => {
+                let size = frame.used_queries as BufferAddress * QUERY_SIZE as BufferAddress;
+                let ready = Arc::clone(&frame.ready);
+                let mapped = Arc::clone(&frame.mapped);
+                pool.read_buffer
+                    .slice(..size)
+                    .map_async(MapMode::Read, move |result| {
+                        // On failure, the frame is reported without timings.
+                        mapped.store(result.is_ok(), Ordering::Release);
+                        ready.store(true, Ordering::Release);
+                    });
+            }
+            _ => frame.ready.store(true, Ordering::Release),
+        }
+        self.pending_frames.push_back(frame);
+    }
+
+    /// Returns the report of the oldest ended frame, if its timestamps were read back.
+    ///
+    /// The device must be polled for the timestamps to become available, so this
+    /// returns `None` until the frame is done executing on the GPU. Frames are
+    /// returned in the order they ended.
+    pub fn process_finished_frame(&mut self) -> Option<Vec<GpuTimerScopeResult>> {
+        if !self.pending_frames.front()?.ready.load(Ordering::Acquire) {
+            return None;
+        }
+        let frame = self.pending_frames.pop_front().unwrap();
+
+        let mut timestamps = Vec::new();
+        match frame.pool {
+            Some(ref pool) if frame.mapped.load(Ordering::Acquire) => {
+                let size = frame.used_queries as BufferAddress * QUERY_SIZE as BufferAddress;
+                timestamps.extend(
+                    pool.read_buffer
+                        .slice(..size)
+                        .get_mapped_range()
+                        .chunks_exact(QUERY_SIZE as usize)
+                        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap())),
+                );
+                pool.read_buffer.unmap();
+            }
+            _ => {}
+        }
+
+        let period = self.timestamp_period as f64 / 1_000_000_000.0;
+        let results = frame
+            .scopes
+            .into_iter()
+            .map(|scope| scope.into_result(&timestamps, period))
+            .collect();
+        if let Some(pool) = frame.pool {
+            self.free_pools.push(pool);
+        }
+        Some(results)
+    }
+}
+
+impl Frame {
+    fn new(pool: Option<QueryPool>) -> Self {
+        Frame {
+            pool,
+            used_queries: 0,
+            resolved_queries: 0,
+            scopes: Vec::new(),
+            ready: Arc::new(AtomicBool::new(false)),
+            mapped: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Reserves the queries for the start and end timestamps of a scope.
+    fn allocate_query_pair(&mut self, max_queries: u32) -> Option<u32> {
+        if self.pool.is_none() || self.used_queries + 2 > max_queries {
+            return None;
+        }
+        let index = self.used_queries;
+        self.used_queries += 2;
+        Some(index)
+    }
+}
+
+impl RecordedScope {
+    fn into_result(self, timestamps: &[u64], period: f64) -> GpuTimerScopeResult {
+        let time = self.query.and_then(|index| {
+            let start = *timestamps.get(index as usize)?;
+            let end = *timestamps.get(index as usize + 1)?;
+            Some(start as f64 * period..end as f64 * period)
+        });
+        GpuTimerScopeResult {
+            label: self.label,
+            time,
+            nested_scopes: self
+                .nested
+                .into_iter()
+                .map(|scope| scope.into_result(timestamps, period))
+                .collect(),
+        }
+    }
+}
+
+/// A scope being recorded by a [`GpuProfiler`].
+///
+/// Commands are recorded through the [`CommandEncoder`] it dereferences to.
+pub struct ProfilerScope<'a> {
+    profiler: &'a mut GpuProfiler,
+    encoder: &'a mut CommandEncoder,
+    nested: Vec<RecordedScope>,
+}
+
+impl ProfilerScope<'_> {
+    /// Records the commands of `f` into a scope named `label`, nested in this one.
+    pub fn scope<R>(&mut self, label: &str, f: impl FnOnce(&mut ProfilerScope<'_>) -> R) -> R {
+        let (result, scope) = self.profiler.record_scope(label, self.encoder, f);
+        self.nested.push(scope);
+        result
+    }
+}
+
+impl Deref for ProfilerScope<'_> {
+    type Target = CommandEncoder;
+
+    fn deref(&self) -> &CommandEncoder {
+        self.encoder
+    }
+}
+
+impl DerefMut for ProfilerScope<'_> {
+    fn deref_mut(&mut self) -> &mut CommandEncoder {
+        self.encoder
+    }
+}
+
+/// Extension trait to record profiler scopes directly on a [`CommandEncoder`].
+pub trait ProfileScopeExt {
+    /// Records the commands of `f` into a scope of `profiler` named `label`.
+    ///
+    /// See [`GpuProfiler::scope()`].
+    fn profile_scope<R>(
+        &mut self,
+        profiler: &mut GpuProfiler,
+        label: &str,
+        f: impl FnOnce(&mut ProfilerScope<'_>) -> R,
+    ) -> R;
+}
+
+impl ProfileScopeExt for CommandEncoder {
+    fn profile_scope<R>(
+        &mut self,
+        profiler: &mut GpuProfiler,
+        label: &str,
+        f: impl FnOnce(&mut ProfilerScope<'_>) -> R,
+    ) -> R {
+        profiler.scope(label, self, f)
+    }
+}
+
+/// Formats the timed scopes of a report as JSON, in the trace event format
+/// understood by `chrome://tracing` and Perfetto.
+///
+/// Scopes without timing are skipped.
+pub fn chrome_trace_json(scopes: &[GpuTimerScopeResult]) -> String {
+    fn write_events(json: &mut String, scopes: &[GpuTimerScopeResult]) {
+        for scope in scopes {
+            if let Some(ref time) = scope.time {
+                if !json.ends_with('[') {
+                    json.push(',');
+                }
+                json.push_str("{\"name\":\"");
+                for c in scope.label.chars() {
+                    match c {
+                        '"' => json.push_str("\\\""),
+                        '\\' => json.push_str("\\\\"),
+                        c if c.is_control() => {
+                            let _ = write!(json, "\\u{:04x}", c as u32);
+                        }
+                        c => json.push(c),
+                    }
+                }
+                let _ = write!(
+                    json,
+                    "\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":0,\"tid\":0}}",
+                    time.start * 1_000_000.0,
+                    (time.end - time.start) * 1_000_000.0,
+                );
+            }
+            write_events(json, &scope.nested_scopes);
+        }
+    }
+
+    let mut json = String::from("{\"traceEvents\":[");
+    write_events(&mut json, scopes);
+    json.push_str("]}");
+    json
+}
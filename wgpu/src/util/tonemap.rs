@@ -0,0 +1,288 @@
+use crate::{
+    util::DeviceExt, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindingResource, Buffer,
+    BufferUsages, CommandEncoder, Device, FilterMode, FragmentState, LoadOp, Operations, Queue,
+    RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline, RenderPipelineDescriptor,
+    Sampler, SamplerDescriptor, ShaderModuleDescriptor, ShaderSource, TextureFormat, TextureView,
+    VertexState,
+};
+use std::borrow::Cow;
+
+const TONEMAP_SHADER: &str = "
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+// Meant to be called with 3 vertex indices, draws one triangle covering the viewport.
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let tc = vec2<f32>(f32(vertex_index / 2u) * 2.0, f32(vertex_index & 1u) * 2.0);
+    var result: VertexOutput;
+    result.position = vec4<f32>(tc.x * 2.0 - 1.0, 1.0 - tc.y * 2.0, 0.0, 1.0);
+    result.tex_coords = tc;
+    return result;
+}
+
+struct Params {
+    exposure: f32,
+    curve: u32,
+    transfer: u32,
+    white_nits: f32,
+};
+
+@group(0) @binding(0)
+var src: texture_2d<f32>;
+@group(0) @binding(1)
+var src_sampler: sampler;
+@group(0) @binding(2)
+var<uniform> params: Params;
+
+fn srgb_oetf(linear: vec3<f32>) -> vec3<f32> {
+    let c = clamp(linear, vec3<f32>(0.0), vec3<f32>(1.0));
+    return select(1.055 * pow(c, vec3<f32>(1.0 / 2.4)) - 0.055, c * 12.92, c <= vec3<f32>(0.0031308));
+}
+
+fn pq_oetf(linear: vec3<f32>) -> vec3<f32> {
+    // Rec. 709 to Rec. 2020 primaries, then absolute luminance relative to 10000 nits.
+    let to_rec2020 = mat3x3<f32>(
+        vec3<f32>(0.6274, 0.0691, 0.0164),
+        vec3<f32>(0.3293, 0.9195, 0.0880),
+        vec3<f32>(0.0433, 0.0114, 0.8956),
+    );
+    let y = clamp(to_rec2020 * linear * params.white_nits / 10000.0, vec3<f32>(0.0), vec3<f32>(1.0));
+    let m1 = 0.1593017578125;
+    let m2 = 78.84375;
+    let c1 = 0.8359375;
+    let c2 = 18.8515625;
+    let c3 = 18.6875;
+    let ym = pow(y, vec3<f32>(m1));
+    return pow((c1 + c2 * ym) / (1.0 + c3 * ym), vec3<f32>(m2));
+}
+
+@fragment
+fn fs_main(vertex: VertexOutput) -> @location(0) vec4<f32> {
+    let hdr = textureSample(src, src_sampler, vertex.tex_coords);
+    var color = max(hdr.rgb * params.exposure, vec3<f32>(0.0));
+    switch params.curve {
+        case 1u: {
+            color = color / (1.0 + color);
+        }
+        case 2u: {
+            // Narkowicz's fit of the ACES filmic curve.
+            color = clamp(
+                (color * (2.51 * color + 0.03)) / (color * (2.43 * color + 0.59) + 0.14),
+                vec3<f32>(0.0),
+                vec3<f32>(1.0),
+            );
+        }
+        default: {}
+    }
+    switch params.transfer {
+        case 1u: {
+            color = srgb_oetf(color);
+        }
+        case 2u: {
+            color = pq_oetf(color);
+        }
+        default: {}
+    }
+    return vec4<f32>(color, hdr.a);
+}
+";
+
+/// Curve compressing HDR colors into the displayable range, see [`Tonemapper`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TonemapOperator {
+    /// Leave colors unchanged, they are clipped by the output format if needed.
+    ///
+    /// This is the right choice for HDR outputs that can display the full range.
+    #[default]
+    None,
+    /// `c / (1 + c)`, a simple curve that never clips.
+    Reinhard,
+    /// An approximation of the ACES filmic curve.
+    Aces,
+}
+
+/// Transfer function encoding the linear colors for the output, see [`Tonemapper`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OutputTransfer {
+    /// Write linear colors, e.g. for `Rgba16Float` outputs.
+    Linear,
+    /// Encode colors with the sRGB transfer function.
+    ///
+    /// Outputs with an sRGB format are encoded by the hardware instead.
+    #[default]
+    Srgb,
+    /// Convert colors to BT.2020 primaries and encode them with the SMPTE ST 2084 (PQ)
+    /// transfer function, for HDR10 outputs in `Rgb10a2Unorm`.
+    Pq,
+}
+
+/// Parameters of a [`Tonemapper`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TonemapParams {
+    /// Multiplies colors before they are tonemapped.
+    pub exposure: f32,
+    /// Curve applied after the exposure.
+    pub operator: TonemapOperator,
+    /// Encoding of the tonemapped colors.
+    pub transfer: OutputTransfer,
+    /// Luminance in nits of a linear value of 1.0, for [`OutputTransfer::Pq`].
+    pub white_nits: f32,
+}
+
+impl Default for TonemapParams {
+    fn default() -> Self {
+        Self {
+            exposure: 1.0,
+            operator: TonemapOperator::default(),
+            transfer: OutputTransfer::default(),
+            // The reference white of BT.2408.
+            white_nits: 203.0,
+        }
+    }
+}
+
+/// Converts an HDR texture, typically `Rgba16Float`, to an output format such as that of
+/// a surface, with a tonemapping curve and transfer function.
+///
+/// This is the final pass of most HDR renderers: render the scene to an intermediate
+/// texture, then draw it to the surface texture with [`Tonemapper::render()`]. The source
+/// view must be a 2D float texture with [`TextureUsages::TEXTURE_BINDING`] and is sampled
+/// linearly, so it may have a different size than the output.
+///
+/// [`TextureUsages::TEXTURE_BINDING`]: crate::TextureUsages::TEXTURE_BINDING
+pub struct Tonemapper {
+    pipeline: RenderPipeline,
+    bind_group_layout: BindGroupLayout,
+    sampler: Sampler,
+    params: Buffer,
+    output_format: TextureFormat,
+}
+
+impl Tonemapper {
+    /// Creates a tonemapper writing to views of `output_format`, which must be renderable.
+    pub fn new(device: &Device, output_format: TextureFormat, params: TonemapParams) -> Self {
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("(wgpu internal) tonemap"),
+            source: ShaderSource::Wgsl(Cow::Borrowed(TONEMAP_SHADER)),
+        });
+        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("(wgpu internal) tonemap"),
+            layout: None,
+            vertex: VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                constants: &Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                constants: &Default::default(),
+                targets: &[Some(output_format.into())],
+            }),
+            primitive: Default::default(),
+            depth_stencil: None,
+            multisample: Default::default(),
+            multiview: None,
+            cache: None,
+        });
+        let bind_group_layout = pipeline.get_bind_group_layout(0);
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("(wgpu internal) tonemap"),
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+        let params = device.create_buffer_init(&crate::util::BufferInitDescriptor {
+            label: Some("(wgpu internal) tonemap params"),
+            contents: &Self::params_bytes(output_format, &params),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            params,
+            output_format,
+        }
+    }
+
+    fn params_bytes(output_format: TextureFormat, params: &TonemapParams) -> [u8; 16] {
+        let operator: u32 = match params.operator {
+            TonemapOperator::None => 0,
+            TonemapOperator::Reinhard => 1,
+            TonemapOperator::Aces => 2,
+        };
+        let transfer: u32 = match params.transfer {
+            OutputTransfer::Srgb if output_format.is_srgb() => 0,
+            OutputTransfer::Linear => 0,
+            OutputTransfer::Srgb => 1,
+            OutputTransfer::Pq => 2,
+        };
+        let mut bytes = [0; 16];
+        bytes[0..4].copy_from_slice(&params.exposure.to_ne_bytes());
+        bytes[4..8].copy_from_slice(&operator.to_ne_bytes());
+        bytes[8..12].copy_from_slice(&transfer.to_ne_bytes());
+        bytes[12..16].copy_from_slice(&params.white_nits.to_ne_bytes());
+        bytes
+    }
+
+    /// Changes the parameters used by the next submitted [`Tonemapper::render()`] passes.
+    pub fn set_params(&self, queue: &Queue, params: &TonemapParams) {
+        queue.write_buffer(
+            &self.params,
+            0,
+            &Self::params_bytes(self.output_format, params),
+        );
+    }
+
+    /// Records a render pass drawing `src` to `dst`, replacing its contents.
+    pub fn render(
+        &self,
+        device: &Device,
+        encoder: &mut CommandEncoder,
+        src: &TextureView,
+        dst: &TextureView,
+    ) {
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("(wgpu internal) tonemap"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(src),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&self.sampler),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: self.params.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut pass = encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("(wgpu internal) tonemap"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: dst,
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(Default::default()),
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
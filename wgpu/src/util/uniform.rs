@@ -0,0 +1,126 @@
+use crate::{
+    util::align_to, BindingResource, Buffer, BufferAddress, BufferBinding, BufferDescriptor,
+    BufferSize, BufferUsages, Device, DynamicOffset, Queue,
+};
+use std::fmt;
+
+/// Sub-allocates per-draw uniform data out of a single buffer, to be bound with dynamic offsets.
+///
+/// Each call to [`DynamicUniformBuffer::push()`] appends data at the next offset aligned to
+/// [`Limits::min_uniform_buffer_offset_alignment`], and returns that offset for use with
+/// [`RenderPass::set_bind_group()`]. The data is kept on the CPU until
+/// [`DynamicUniformBuffer::write()`] uploads it all with a single [`Queue::write_buffer()`],
+/// which is ordered before any later submission, so the buffer can be reused every frame.
+///
+/// Using it generally goes as follows:
+/// 1. Call [`DynamicUniformBuffer::clear()`] at the start of the frame.
+/// 2. Call [`DynamicUniformBuffer::push()`] for each draw, remembering the returned offsets.
+/// 3. Call [`DynamicUniformBuffer::write()`]. If it returns `true`, the buffer was too small
+///    and had to be reallocated, so bind groups referencing it must be recreated.
+/// 4. Record and submit the draws, binding with the offsets from step 2.
+///
+/// The bind group layout entry for the buffer must have `has_dynamic_offset: true`, and the
+/// bind group should use [`DynamicUniformBuffer::binding()`].
+///
+/// [`Limits::min_uniform_buffer_offset_alignment`]: crate::Limits::min_uniform_buffer_offset_alignment
+/// [`RenderPass::set_bind_group()`]: crate::RenderPass::set_bind_group
+/// [`Queue::write_buffer()`]: crate::Queue::write_buffer
+pub struct DynamicUniformBuffer {
+    label: Option<String>,
+    buffer: Buffer,
+    capacity: BufferAddress,
+    alignment: BufferAddress,
+    /// Data pushed since the last clear, waiting to be written.
+    data: Vec<u8>,
+}
+
+impl DynamicUniformBuffer {
+    /// Create a new dynamic uniform buffer able to hold `capacity` bytes before it has to grow.
+    ///
+    /// The capacity should be about the amount of uniform data pushed per frame, including the
+    /// padding between entries.
+    pub fn new(device: &Device, label: Option<&str>, capacity: BufferAddress) -> Self {
+        let alignment = device.limits().min_uniform_buffer_offset_alignment as BufferAddress;
+        let capacity = align_to(capacity.max(alignment), alignment);
+        let label = label.map(String::from);
+        let buffer = Self::create_buffer(device, label.as_deref(), capacity);
+        Self {
+            label,
+            buffer,
+            capacity,
+            alignment,
+            data: Vec::new(),
+        }
+    }
+
+    fn create_buffer(device: &Device, label: Option<&str>, size: BufferAddress) -> Buffer {
+        device.create_buffer(&BufferDescriptor {
+            label,
+            size,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Append `data` to the buffer, returning the dynamic offset it will be found at.
+    pub fn push(&mut self, data: &[u8]) -> DynamicOffset {
+        let offset = align_to(self.data.len() as BufferAddress, self.alignment);
+        self.data.resize(offset as usize, 0);
+        self.data.extend_from_slice(data);
+        offset as DynamicOffset
+    }
+
+    /// Forget all data pushed so far, so the buffer can be refilled from the start.
+    ///
+    /// Offsets returned by earlier calls to [`DynamicUniformBuffer::push()`] remain valid for
+    /// work already submitted.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Upload all data pushed since the last clear.
+    ///
+    /// Returns `true` if the buffer had to be reallocated to fit the data. In that case,
+    /// [`DynamicUniformBuffer::buffer()`] is a new buffer and bind groups created with the
+    /// old one must be recreated.
+    pub fn write(&mut self, device: &Device, queue: &Queue) -> bool {
+        let size = align_to(self.data.len() as BufferAddress, self.alignment);
+        let grown = size > self.capacity;
+        if grown {
+            self.capacity = size.next_power_of_two();
+            self.buffer = Self::create_buffer(device, self.label.as_deref(), self.capacity);
+        }
+        if !self.data.is_empty() {
+            queue.write_buffer(&self.buffer, 0, &self.data);
+        }
+        grown
+    }
+
+    /// The underlying buffer.
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// A binding of `size` bytes at offset zero, to be moved by the dynamic offsets.
+    ///
+    /// Every entry pushed should be at least `size` bytes, so that the binding stays within
+    /// the buffer at all of the returned offsets.
+    pub fn binding(&self, size: BufferSize) -> BindingResource {
+        BindingResource::Buffer(BufferBinding {
+            buffer: &self.buffer,
+            offset: 0,
+            size: Some(size),
+        })
+    }
+}
+
+impl fmt::Debug for DynamicUniformBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynamicUniformBuffer")
+            .field("label", &self.label)
+            .field("capacity", &self.capacity)
+            .field("alignment", &self.alignment)
+            .field("len", &self.data.len())
+            .finish_non_exhaustive()
+    }
+}